@@ -0,0 +1,195 @@
+/// How allocations are placed inside a memory block.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AllocationStrategy {
+    /// First fit from a list of free ranges, which are merged again when freed. Suits
+    /// long-lived resources of mixed sizes.
+    #[default]
+    FreeList,
+
+    /// Allocations are appended one after another, and space is only reclaimed once every
+    /// allocation of the block is freed. Suits short-lived, per-frame resources.
+    Linear,
+}
+
+/// Bookkeeping of the ranges handed out from one block.
+#[derive(Debug)]
+pub(super) enum Ranges {
+    FreeList {
+        /// Free `(offset, size)` ranges, sorted by offset and never adjacent.
+        free: Vec<(u64, u64)>,
+        live: usize,
+    },
+    Linear {
+        end: u64,
+        live: usize,
+    },
+}
+
+impl Ranges {
+    pub(super) fn new(strategy: AllocationStrategy, size: u64) -> Self {
+        match strategy {
+            AllocationStrategy::FreeList => Self::FreeList {
+                free: vec![(0, size)],
+                live: 0,
+            },
+            AllocationStrategy::Linear => Self::Linear { end: 0, live: 0 },
+        }
+    }
+
+    /// Number of allocations that were not freed yet.
+    #[inline]
+    pub(super) fn live(&self) -> usize {
+        match *self {
+            Self::FreeList { live, .. } | Self::Linear { live, .. } => live,
+        }
+    }
+
+    /// Reserves `size` bytes aligned to `alignment` in a block of `capacity` bytes, returning
+    /// their offset.
+    pub(super) fn allocate(&mut self, capacity: u64, size: u64, alignment: u64) -> Option<u64> {
+        match self {
+            Self::FreeList { free, live } => {
+                let (index, offset) =
+                    free.iter().enumerate().find_map(|(index, &(start, len))| {
+                        let offset = start.checked_next_multiple_of(alignment)?;
+
+                        (offset.checked_add(size)? <= start + len).then_some((index, offset))
+                    })?;
+
+                let (start, len) = free[index];
+                let before = (start, offset - start);
+                let after = (offset + size, start + len - offset - size);
+
+                match (before.1 > 0, after.1 > 0) {
+                    (true, true) => {
+                        free[index] = before;
+                        free.insert(index + 1, after);
+                    }
+                    (true, false) => free[index] = before,
+                    (false, true) => free[index] = after,
+                    (false, false) => {
+                        free.remove(index);
+                    }
+                }
+
+                *live += 1;
+
+                Some(offset)
+            }
+            Self::Linear { end, live } => {
+                let offset = end.checked_next_multiple_of(alignment)?;
+
+                if offset.checked_add(size)? > capacity {
+                    return None;
+                }
+
+                *end = offset + size;
+                *live += 1;
+
+                Some(offset)
+            }
+        }
+    }
+
+    /// Releases a range returned by [`allocate`](Self::allocate).
+    pub(super) fn free(&mut self, offset: u64, size: u64) {
+        match self {
+            Self::FreeList { free, live } => {
+                let index = free.partition_point(|&(start, _)| start < offset);
+
+                let merges_before = index > 0 && {
+                    let (start, len) = free[index - 1];
+                    start + len == offset
+                };
+                let merges_after = free
+                    .get(index)
+                    .is_some_and(|&(start, _)| offset + size == start);
+
+                match (merges_before, merges_after) {
+                    (true, true) => {
+                        let (_, after_len) = free.remove(index);
+                        free[index - 1].1 += size + after_len;
+                    }
+                    (true, false) => free[index - 1].1 += size,
+                    (false, true) => {
+                        free[index].0 = offset;
+                        free[index].1 += size;
+                    }
+                    (false, false) => free.insert(index, (offset, size)),
+                }
+
+                *live -= 1;
+            }
+            Self::Linear { end, live } => {
+                *live -= 1;
+
+                if *live == 0 {
+                    *end = 0;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_list() {
+        let mut ranges = Ranges::new(AllocationStrategy::FreeList, 1024);
+
+        let a = ranges.allocate(1024, 100, 64).unwrap();
+        let b = ranges.allocate(1024, 100, 256).unwrap();
+        let c = ranges.allocate(1024, 100, 1).unwrap();
+
+        assert_eq!((a, b, c), (0, 256, 100), "The gap before `b` is reused");
+        assert_eq!(ranges.allocate(1024, 1024, 1), None);
+
+        ranges.free(c, 100);
+        ranges.free(a, 100);
+
+        let Ranges::FreeList { free, live } = &ranges else {
+            unreachable!()
+        };
+
+        assert_eq!(*live, 1);
+        assert_eq!(
+            free,
+            &[(0, 256), (356, 668)],
+            "Neighbouring ranges are merged"
+        );
+
+        ranges.free(b, 100);
+
+        let Ranges::FreeList { free, .. } = &ranges else {
+            unreachable!()
+        };
+
+        assert_eq!(free, &[(0, 1024)]);
+        assert_eq!(ranges.live(), 0);
+    }
+
+    #[test]
+    fn test_linear() {
+        let mut ranges = Ranges::new(AllocationStrategy::Linear, 1024);
+
+        let a = ranges.allocate(1024, 100, 1).unwrap();
+        let b = ranges.allocate(1024, 100, 256).unwrap();
+
+        assert_eq!((a, b), (0, 256));
+
+        ranges.free(a, 100);
+
+        assert_eq!(
+            ranges.allocate(1024, 700, 1),
+            None,
+            "Space is not reclaimed while the block has live allocations"
+        );
+
+        ranges.free(b, 100);
+
+        assert_eq!(ranges.allocate(1024, 1024, 1), Some(0));
+    }
+}
@@ -0,0 +1,185 @@
+use core::ops::BitOr;
+
+/// Properties of a memory type, with the same bit values as `VkMemoryPropertyFlags`.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemoryFlags(pub u32);
+
+impl MemoryFlags {
+    pub const EMPTY: Self = Self(0);
+    pub const DEVICE_LOCAL: Self = Self(0x1);
+    pub const HOST_VISIBLE: Self = Self(0x2);
+    pub const HOST_COHERENT: Self = Self(0x4);
+    pub const HOST_CACHED: Self = Self(0x8);
+    pub const LAZILY_ALLOCATED: Self = Self(0x10);
+
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline]
+    const fn count(self, other: Self) -> u32 {
+        (self.0 & other.0).count_ones()
+    }
+}
+
+impl BitOr for MemoryFlags {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryType {
+    pub flags: MemoryFlags,
+    pub heap_index: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MemoryHeap {
+    pub size: u64,
+    /// Whether the heap is local to the device, as opposed to host memory visible to it.
+    pub device_local: bool,
+}
+
+/// The memory types and heaps of a device.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryProperties {
+    pub types: Vec<MemoryType>,
+    pub heaps: Vec<MemoryHeap>,
+}
+
+/// What an allocation is going to be used for, which decides the memory type it is placed in.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MemoryUsage {
+    /// Only accessed by the device. Placed in device-local memory when possible.
+    #[default]
+    GpuOnly,
+
+    /// Written by the host and read by the device, such as staging and uniform buffers.
+    /// Placed in host-coherent memory, so writes need no flushing.
+    Upload,
+
+    /// Written by the device and read back by the host.
+    Readback,
+}
+
+impl MemoryUsage {
+    /// Flags a memory type must have for this usage.
+    const fn required(self) -> MemoryFlags {
+        match self {
+            Self::GpuOnly => MemoryFlags::EMPTY,
+            // Every device has a host-visible, host-coherent memory type.
+            Self::Upload | Self::Readback => {
+                MemoryFlags(MemoryFlags::HOST_VISIBLE.0 | MemoryFlags::HOST_COHERENT.0)
+            }
+        }
+    }
+
+    /// Flags that make a memory type a better fit for this usage.
+    const fn preferred(self) -> MemoryFlags {
+        match self {
+            Self::GpuOnly => MemoryFlags::DEVICE_LOCAL,
+            Self::Upload => MemoryFlags::EMPTY,
+            Self::Readback => MemoryFlags::HOST_CACHED,
+        }
+    }
+
+    /// Flags that make a memory type a worse fit for this usage.
+    const fn avoided(self) -> MemoryFlags {
+        match self {
+            Self::GpuOnly => {
+                MemoryFlags(MemoryFlags::HOST_VISIBLE.0 | MemoryFlags::LAZILY_ALLOCATED.0)
+            }
+            Self::Upload | Self::Readback => MemoryFlags::LAZILY_ALLOCATED,
+        }
+    }
+}
+
+impl MemoryProperties {
+    /// Memory types allowed by `type_bits` that can serve `usage`, best fit first.
+    ///
+    /// Types are ranked by how many preferred flags they have, then by how few avoided flags
+    /// they have. Ties keep the driver's order, which lists faster types first.
+    pub fn candidates(&self, type_bits: u32, usage: MemoryUsage) -> Vec<u32> {
+        let mut candidates = self
+            .types
+            .iter()
+            .enumerate()
+            .take(u32::BITS as usize)
+            .filter(|&(index, memory_type)| {
+                type_bits & (1 << index) != 0 && memory_type.flags.contains(usage.required())
+            })
+            .map(|(index, memory_type)| {
+                let score = 2 * memory_type.flags.count(usage.preferred()) as i32
+                    - memory_type.flags.count(usage.avoided()) as i32;
+
+                (index as u32, score)
+            })
+            .collect::<Vec<_>>();
+
+        // Stable, so equally scored types stay in driver order.
+        candidates.sort_by_key(|&(_, score)| -score);
+
+        candidates.into_iter().map(|(index, _)| index).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties(flags: &[MemoryFlags]) -> MemoryProperties {
+        MemoryProperties {
+            types: flags
+                .iter()
+                .map(|&flags| MemoryType {
+                    flags,
+                    heap_index: 0,
+                })
+                .collect(),
+            heaps: vec![MemoryHeap {
+                size: 1 << 30,
+                device_local: true,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_candidates() {
+        let host = MemoryFlags::HOST_VISIBLE | MemoryFlags::HOST_COHERENT;
+        let properties = properties(&[
+            host,
+            MemoryFlags::DEVICE_LOCAL,
+            MemoryFlags::DEVICE_LOCAL | host,
+            host | MemoryFlags::HOST_CACHED,
+        ]);
+
+        assert_eq!(
+            properties.candidates(0b1111, MemoryUsage::GpuOnly),
+            [1, 2, 0, 3]
+        );
+        assert_eq!(
+            properties.candidates(0b1111, MemoryUsage::Upload),
+            [0, 2, 3],
+            "Upload memory must be host visible and coherent"
+        );
+        assert_eq!(properties.candidates(0b1111, MemoryUsage::Readback)[0], 3);
+        assert_eq!(
+            properties.candidates(0b0101, MemoryUsage::GpuOnly),
+            [2, 0],
+            "Types outside the bit mask are skipped"
+        );
+        assert!(
+            properties
+                .candidates(1 << 4, MemoryUsage::GpuOnly)
+                .is_empty(),
+            "Types beyond the reported count are ignored"
+        );
+    }
+}
@@ -0,0 +1,850 @@
+mod block;
+mod memory_type;
+
+use core::ptr::NonNull;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+pub use block::AllocationStrategy;
+pub use memory_type::{MemoryFlags, MemoryHeap, MemoryProperties, MemoryType, MemoryUsage};
+
+use block::Ranges;
+
+/// Size of the memory blocks allocations are carved from, unless configured otherwise.
+pub const DEFAULT_BLOCK_SIZE: u64 = 64 << 20;
+
+/// A source of device memory objects, such as a Vulkan device.
+///
+/// # Safety
+///
+/// `allocate_memory` must return memory of at least `size` bytes from the given memory type,
+/// which stays valid until passed to `free_memory`. `map_memory` must return a pointer to the
+/// first `size` bytes of the memory, valid until it is freed.
+#[allow(clippy::missing_safety_doc)]
+pub unsafe trait MemoryDevice {
+    type Memory: Copy + Eq;
+    type Error;
+
+    unsafe fn allocate_memory(
+        &self,
+        size: u64,
+        memory_type_index: u32,
+    ) -> Result<Self::Memory, Self::Error>;
+
+    /// Maps the first `size` bytes of host-visible `memory` into the address space of the
+    /// host. Freeing the memory unmaps it.
+    unsafe fn map_memory(
+        &self,
+        memory: Self::Memory,
+        size: u64,
+    ) -> Result<NonNull<u8>, Self::Error>;
+
+    unsafe fn free_memory(&self, memory: Self::Memory);
+}
+
+#[derive(Clone, Copy, Debug, thiserror::Error)]
+pub enum DeviceAllocError<E> {
+    #[error("No memory type satisfies the allocation request")]
+    NoSuitableMemoryType,
+
+    #[error(transparent)]
+    Device(E),
+}
+
+/// The device memory counterpart of [`Allocator`](crate::Allocator): hands out ranges of
+/// device memory objects provided by a [`MemoryDevice`].
+///
+/// # Safety
+///
+/// Allocations must not overlap while they are alive, and must satisfy the size, alignment
+/// and memory type bits of their request.
+#[allow(clippy::missing_safety_doc)]
+pub unsafe trait DeviceAllocator<D: MemoryDevice> {
+    unsafe fn allocate(
+        &self,
+        device: &D,
+        request: &AllocationRequest,
+    ) -> Result<DeviceAllocation<D::Memory>, DeviceAllocError<D::Error>>;
+
+    /// Frees `allocation`, which must come from this allocator and `device`.
+    unsafe fn deallocate(&self, device: &D, allocation: &DeviceAllocation<D::Memory>);
+}
+
+/// Memory layout of the resource an allocation is bound to.
+///
+/// Linear and optimal resources in the same memory object must be `bufferImageGranularity`
+/// bytes apart, so the [`BlockAllocator`] keeps them in separate blocks when the device
+/// reports a granularity above one byte.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Tiling {
+    /// Buffers and linearly tiled images.
+    #[default]
+    Linear,
+
+    /// Optimally tiled images.
+    Optimal,
+}
+
+/// Size, alignment and placement of a device memory allocation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllocationRequest {
+    pub size: u64,
+    /// A power of two.
+    pub alignment: u64,
+    /// Bit `i` is set if memory type `i` may be used.
+    pub memory_type_bits: u32,
+    pub usage: MemoryUsage,
+    pub strategy: AllocationStrategy,
+    pub tiling: Tiling,
+    /// Gives the allocation a memory object of its own instead of a range of a shared block.
+    pub dedicated: bool,
+}
+
+impl AllocationRequest {
+    /// A free-list, GPU-only request for a linear resource, as returned by its memory
+    /// requirements.
+    pub const fn new(size: u64, alignment: u64, memory_type_bits: u32) -> Self {
+        Self {
+            size,
+            alignment,
+            memory_type_bits,
+            usage: MemoryUsage::GpuOnly,
+            strategy: AllocationStrategy::FreeList,
+            tiling: Tiling::Linear,
+            dedicated: false,
+        }
+    }
+
+    #[inline]
+    pub const fn with_usage(mut self, usage: MemoryUsage) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    #[inline]
+    pub const fn with_strategy(mut self, strategy: AllocationStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    #[inline]
+    pub const fn with_tiling(mut self, tiling: Tiling) -> Self {
+        self.tiling = tiling;
+        self
+    }
+
+    #[inline]
+    pub const fn with_dedicated(mut self, dedicated: bool) -> Self {
+        self.dedicated = dedicated;
+        self
+    }
+}
+
+/// A range of a device memory object.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DeviceAllocation<M> {
+    memory: M,
+    offset: u64,
+    size: u64,
+    memory_type_index: u32,
+    /// Id and pool index of the block the range belongs to, or `None` for a dedicated
+    /// allocation.
+    block: Option<(u64, usize)>,
+    /// Host address of the range, if its memory is host visible.
+    mapped: Option<NonNull<u8>>,
+}
+
+// The mapped pointer is an address of device memory, only accessed through the allocation's
+// owner.
+unsafe impl<M: Send> Send for DeviceAllocation<M> {}
+unsafe impl<M: Sync> Sync for DeviceAllocation<M> {}
+
+impl<M: Copy> DeviceAllocation<M> {
+    /// The memory object the allocation lives in.
+    #[inline]
+    pub fn memory(&self) -> M {
+        self.memory
+    }
+
+    /// Offset of the allocation in its memory object.
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    #[inline]
+    pub fn memory_type_index(&self) -> u32 {
+        self.memory_type_index
+    }
+
+    #[inline]
+    pub fn is_dedicated(&self) -> bool {
+        self.block.is_none()
+    }
+
+    /// Host address of the allocation's first byte, if it lives in host-visible memory.
+    ///
+    /// Host-visible memory is mapped for as long as it is allocated.
+    #[inline]
+    pub fn mapped_ptr(&self) -> Option<NonNull<u8>> {
+        self.mapped
+    }
+}
+
+/// Memory use of one heap, as tracked by a [`BlockAllocator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapStatistics {
+    /// Size of the heap as reported by the device.
+    pub heap_size: u64,
+    /// Memory objects allocated from the heap, dedicated allocations included.
+    pub memory_objects: usize,
+    /// Bytes of all memory objects allocated from the heap.
+    pub reserved_bytes: u64,
+    pub allocations: usize,
+    /// Bytes handed out to allocations, without alignment padding.
+    pub used_bytes: u64,
+}
+
+struct Block<M> {
+    id: u64,
+    memory: M,
+    size: u64,
+    ranges: Ranges,
+    mapped: Option<NonNull<u8>>,
+}
+
+#[derive(Clone, Copy)]
+struct Dedicated<M> {
+    memory: M,
+    size: u64,
+    memory_type_index: u32,
+}
+
+// The mapped pointer is only handed out, never dereferenced by the allocator.
+unsafe impl<M: Send> Send for Block<M> {}
+
+struct State<M> {
+    /// Blocks of each memory type, indexed by
+    /// `(memory_type_index * 2 + strategy) * 2 + tiling`.
+    pools: Vec<Vec<Block<M>>>,
+    dedicated: Vec<Dedicated<M>>,
+    /// Bytes handed out per heap.
+    used_bytes: Vec<u64>,
+    allocations: Vec<usize>,
+    next_block_id: u64,
+}
+
+/// A [`DeviceAllocator`] that sub-allocates from large memory blocks.
+///
+/// Every memory type has one pool of blocks per [`AllocationStrategy`], split by [`Tiling`]
+/// when the device's buffer-image granularity requires it. Requests that are
+/// large compared to the block size, or that ask for it, get a dedicated memory object. At
+/// most one empty block is kept per pool, to avoid allocating memory objects back and forth.
+pub struct BlockAllocator<M> {
+    properties: MemoryProperties,
+    block_size: u64,
+    buffer_image_granularity: u64,
+    state: Mutex<State<M>>,
+}
+
+/// Pools of one memory type: one per strategy and tiling.
+const POOLS_PER_TYPE: usize = 4;
+
+impl<M: Copy + Eq> BlockAllocator<M> {
+    /// Creates an allocator for a device with the given memory `properties`, using blocks of
+    /// [`DEFAULT_BLOCK_SIZE`] bytes.
+    pub fn new(properties: MemoryProperties) -> Self {
+        Self::with_block_size(properties, DEFAULT_BLOCK_SIZE)
+    }
+
+    /// Creates an allocator with blocks of `block_size` bytes. Blocks of small heaps are kept
+    /// to an eighth of the heap.
+    pub fn with_block_size(properties: MemoryProperties, block_size: u64) -> Self {
+        let heap_count = properties.heaps.len();
+        let pool_count = properties.types.len() * POOLS_PER_TYPE;
+
+        Self {
+            properties,
+            block_size,
+            buffer_image_granularity: 1,
+            state: Mutex::new(State {
+                pools: (0..pool_count).map(|_| Vec::new()).collect(),
+                dedicated: Vec::new(),
+                used_bytes: vec![0; heap_count],
+                allocations: vec![0; heap_count],
+                next_block_id: 0,
+            }),
+        }
+    }
+
+    /// Sets the device's `bufferImageGranularity`. Above one byte, linear and optimal
+    /// resources are sub-allocated from separate blocks, so they never share a granularity
+    /// page.
+    #[inline]
+    pub fn with_buffer_image_granularity(mut self, granularity: u64) -> Self {
+        self.buffer_image_granularity = granularity;
+        self
+    }
+
+    #[inline]
+    pub fn properties(&self) -> &MemoryProperties {
+        &self.properties
+    }
+
+    /// Memory use of every heap, in heap order.
+    pub fn statistics(&self) -> Vec<HeapStatistics> {
+        let state = self.lock();
+
+        let mut statistics = self
+            .properties
+            .heaps
+            .iter()
+            .zip(&state.used_bytes)
+            .zip(&state.allocations)
+            .map(|((heap, &used_bytes), &allocations)| HeapStatistics {
+                heap_size: heap.size,
+                used_bytes,
+                allocations,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        let blocks = state.pools.iter().enumerate().flat_map(|(pool, blocks)| {
+            blocks
+                .iter()
+                .map(move |block| ((pool / POOLS_PER_TYPE) as u32, block.size))
+        });
+        let dedicated = state
+            .dedicated
+            .iter()
+            .map(|dedicated| (dedicated.memory_type_index, dedicated.size));
+
+        for (memory_type_index, size) in blocks.chain(dedicated) {
+            if let Some(heap) = statistics.get_mut(self.heap_index(memory_type_index)) {
+                heap.memory_objects += 1;
+                heap.reserved_bytes += size;
+            }
+        }
+
+        statistics
+    }
+
+    /// Frees every memory object, including those of allocations still alive.
+    ///
+    /// # Safety
+    ///
+    /// Memory from this allocator must no longer be used, and `device` must be the device
+    /// it was allocated from.
+    pub unsafe fn release<D: MemoryDevice<Memory = M>>(&self, device: &D) {
+        let mut state = self.lock();
+
+        for block in state.pools.iter_mut().flat_map(|blocks| blocks.drain(..)) {
+            unsafe { device.free_memory(block.memory) };
+        }
+
+        for dedicated in state.dedicated.drain(..) {
+            unsafe { device.free_memory(dedicated.memory) };
+        }
+
+        state.used_bytes.fill(0);
+        state.allocations.fill(0);
+    }
+
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, State<M>> {
+        // Every update leaves the state consistent before anything that can panic.
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[inline]
+    fn heap_index(&self, memory_type_index: u32) -> usize {
+        self.properties.types[memory_type_index as usize].heap_index as usize
+    }
+
+    /// Pool serving `request` in memory type `memory_type_index`.
+    #[inline]
+    fn pool_index(&self, memory_type_index: u32, request: &AllocationRequest) -> usize {
+        let tiling = if self.buffer_image_granularity > 1 {
+            request.tiling
+        } else {
+            Tiling::Linear
+        };
+
+        (memory_type_index as usize * 2 + request.strategy as usize) * 2 + tiling as usize
+    }
+
+    /// Size of new blocks for `memory_type_index`.
+    fn block_size(&self, memory_type_index: u32) -> u64 {
+        let heap_size = self
+            .properties
+            .heaps
+            .get(self.heap_index(memory_type_index))
+            .map_or(0, |heap| heap.size);
+
+        self.block_size.min(heap_size / 8).max(1)
+    }
+
+    /// Allocates a memory object, mapping it if its type is host visible.
+    unsafe fn allocate_object<D: MemoryDevice<Memory = M>>(
+        &self,
+        device: &D,
+        size: u64,
+        memory_type_index: u32,
+    ) -> Result<(M, Option<NonNull<u8>>), D::Error> {
+        let memory = unsafe { device.allocate_memory(size, memory_type_index) }?;
+
+        let flags = self.properties.types[memory_type_index as usize].flags;
+
+        if !flags.contains(MemoryFlags::HOST_VISIBLE) {
+            return Ok((memory, None));
+        }
+
+        match unsafe { device.map_memory(memory, size) } {
+            Ok(mapped) => Ok((memory, Some(mapped))),
+            Err(err) => {
+                unsafe { device.free_memory(memory) };
+
+                Err(err)
+            }
+        }
+    }
+
+    /// Places a request in memory type `memory_type_index`.
+    unsafe fn allocate_in<D: MemoryDevice<Memory = M>>(
+        &self,
+        device: &D,
+        state: &mut State<M>,
+        request: &AllocationRequest,
+        memory_type_index: u32,
+    ) -> Result<DeviceAllocation<M>, D::Error> {
+        let block_size = self.block_size(memory_type_index);
+
+        if request.dedicated || request.size > block_size / 2 {
+            let (memory, mapped) =
+                unsafe { self.allocate_object(device, request.size, memory_type_index) }?;
+
+            state.dedicated.push(Dedicated {
+                memory,
+                size: request.size,
+                memory_type_index,
+            });
+
+            return Ok(DeviceAllocation {
+                memory,
+                offset: 0,
+                size: request.size,
+                memory_type_index,
+                block: None,
+                mapped,
+            });
+        }
+
+        let pool_index = self.pool_index(memory_type_index, request);
+        let pool = &mut state.pools[pool_index];
+
+        for block in pool.iter_mut() {
+            if let Some(offset) = block
+                .ranges
+                .allocate(block.size, request.size, request.alignment)
+            {
+                return Ok(DeviceAllocation {
+                    memory: block.memory,
+                    offset,
+                    size: request.size,
+                    memory_type_index,
+                    block: Some((block.id, pool_index)),
+                    mapped: block
+                        .mapped
+                        .map(|mapped| unsafe { mapped.add(offset as usize) }),
+                });
+            }
+        }
+
+        let (memory, mapped) =
+            unsafe { self.allocate_object(device, block_size, memory_type_index) }?;
+
+        let mut block = Block {
+            id: state.next_block_id,
+            memory,
+            size: block_size,
+            ranges: Ranges::new(request.strategy, block_size),
+            mapped,
+        };
+
+        state.next_block_id += 1;
+
+        // A fresh block fits any request of at most half its size.
+        let offset = block
+            .ranges
+            .allocate(block_size, request.size, request.alignment)
+            .unwrap_or_default();
+
+        let allocation = DeviceAllocation {
+            memory,
+            offset,
+            size: request.size,
+            memory_type_index,
+            block: Some((block.id, pool_index)),
+            mapped: mapped.map(|mapped| unsafe { mapped.add(offset as usize) }),
+        };
+
+        pool.push(block);
+
+        Ok(allocation)
+    }
+}
+
+unsafe impl<D> DeviceAllocator<D> for BlockAllocator<D::Memory>
+where
+    D: MemoryDevice,
+{
+    /// Allocates from the best fitting memory type, falling back to the next one if the
+    /// device fails to provide memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoSuitableMemoryType` if no memory type allowed by the request can serve its
+    /// usage, or the device's error if no candidate type could provide memory.
+    unsafe fn allocate(
+        &self,
+        device: &D,
+        request: &AllocationRequest,
+    ) -> Result<DeviceAllocation<D::Memory>, DeviceAllocError<D::Error>> {
+        debug_assert!(request.alignment.is_power_of_two());
+
+        let mut state = self.lock();
+        let mut result = Err(DeviceAllocError::NoSuitableMemoryType);
+
+        for memory_type_index in self
+            .properties
+            .candidates(request.memory_type_bits, request.usage)
+        {
+            result = unsafe { self.allocate_in(device, &mut state, request, memory_type_index) }
+                .map_err(DeviceAllocError::Device);
+
+            if let Ok(allocation) = &result {
+                let heap_index = self.heap_index(allocation.memory_type_index);
+
+                state.used_bytes[heap_index] += allocation.size;
+                state.allocations[heap_index] += 1;
+
+                break;
+            }
+        }
+
+        result
+    }
+
+    unsafe fn deallocate(&self, device: &D, allocation: &DeviceAllocation<D::Memory>) {
+        let mut state = self.lock();
+        let heap_index = self.heap_index(allocation.memory_type_index);
+
+        state.used_bytes[heap_index] -= allocation.size;
+        state.allocations[heap_index] -= 1;
+
+        let Some((block_id, pool_index)) = allocation.block else {
+            if let Some(index) = state
+                .dedicated
+                .iter()
+                .position(|dedicated| dedicated.memory == allocation.memory)
+            {
+                state.dedicated.swap_remove(index);
+                unsafe { device.free_memory(allocation.memory) };
+            }
+
+            return;
+        };
+
+        let pool = &mut state.pools[pool_index];
+
+        let Some(index) = pool.iter().position(|block| block.id == block_id) else {
+            return;
+        };
+
+        pool[index].ranges.free(allocation.offset, allocation.size);
+
+        if pool[index].ranges.live() == 0
+            && pool.iter().filter(|block| block.ranges.live() == 0).count() > 1
+        {
+            let block = pool.swap_remove(index);
+
+            unsafe { device.free_memory(block.memory) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::RefCell;
+
+    use super::*;
+
+    /// Hands out increasing memory handles, failing for memory types in `failing_types`.
+    #[derive(Default)]
+    struct FakeDevice {
+        live: RefCell<Vec<(u32, u64)>>,
+        next: RefCell<u32>,
+        failing_types: Vec<u32>,
+        /// Host storage of mapped memory, kept until the device is dropped.
+        mapped: RefCell<Vec<Box<[u8]>>>,
+    }
+
+    unsafe impl MemoryDevice for FakeDevice {
+        type Memory = u32;
+        type Error = ();
+
+        unsafe fn allocate_memory(&self, size: u64, memory_type_index: u32) -> Result<u32, ()> {
+            if self.failing_types.contains(&memory_type_index) {
+                return Err(());
+            }
+
+            let mut next = self.next.borrow_mut();
+
+            *next += 1;
+            self.live.borrow_mut().push((*next, size));
+
+            Ok(*next)
+        }
+
+        unsafe fn map_memory(&self, _memory: u32, size: u64) -> Result<NonNull<u8>, ()> {
+            let mut storage = vec![0; size as usize].into_boxed_slice();
+            let mapped = NonNull::from(&mut storage[0]);
+
+            self.mapped.borrow_mut().push(storage);
+
+            Ok(mapped)
+        }
+
+        unsafe fn free_memory(&self, memory: u32) {
+            self.live.borrow_mut().retain(|&(live, _)| live != memory);
+        }
+    }
+
+    fn properties() -> MemoryProperties {
+        MemoryProperties {
+            types: vec![
+                MemoryType {
+                    flags: MemoryFlags::DEVICE_LOCAL,
+                    heap_index: 0,
+                },
+                MemoryType {
+                    flags: MemoryFlags::HOST_VISIBLE | MemoryFlags::HOST_COHERENT,
+                    heap_index: 1,
+                },
+            ],
+            heaps: vec![
+                MemoryHeap {
+                    size: 1 << 30,
+                    device_local: true,
+                },
+                MemoryHeap {
+                    size: 1 << 20,
+                    device_local: false,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_sub_allocation() {
+        let device = FakeDevice::default();
+        let allocator = BlockAllocator::with_block_size(properties(), 1 << 16);
+        let request = AllocationRequest::new(1000, 256, 0b11);
+
+        unsafe {
+            let a = allocator.allocate(&device, &request).unwrap();
+            let b = allocator.allocate(&device, &request).unwrap();
+
+            assert_eq!(a.memory(), b.memory(), "Both fit in the same block");
+            assert_eq!((a.offset(), b.offset()), (0, 1024));
+            assert_eq!(a.memory_type_index(), 0);
+
+            let statistics = allocator.statistics();
+
+            assert_eq!(
+                statistics[0],
+                HeapStatistics {
+                    heap_size: 1 << 30,
+                    memory_objects: 1,
+                    reserved_bytes: 1 << 16,
+                    allocations: 2,
+                    used_bytes: 2000,
+                }
+            );
+            assert_eq!(
+                statistics[1],
+                HeapStatistics {
+                    heap_size: 1 << 20,
+                    ..Default::default()
+                }
+            );
+
+            allocator.deallocate(&device, &a);
+            allocator.deallocate(&device, &b);
+
+            assert_eq!(
+                device.live.borrow().len(),
+                1,
+                "One empty block is kept for reuse"
+            );
+
+            allocator.release(&device);
+
+            assert!(device.live.borrow().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_dedicated() {
+        let device = FakeDevice::default();
+        let allocator = BlockAllocator::with_block_size(properties(), 1 << 16);
+
+        unsafe {
+            let large = allocator
+                .allocate(&device, &AllocationRequest::new(1 << 15 | 1, 256, 0b11))
+                .unwrap();
+            let requested = allocator
+                .allocate(
+                    &device,
+                    &AllocationRequest::new(64, 64, 0b11).with_dedicated(true),
+                )
+                .unwrap();
+
+            assert!(
+                large.is_dedicated(),
+                "Over half a block gets its own memory"
+            );
+            assert!(requested.is_dedicated());
+            assert_ne!(large.memory(), requested.memory());
+            assert_eq!(allocator.statistics()[0].memory_objects, 2);
+
+            allocator.deallocate(&device, &large);
+            allocator.deallocate(&device, &requested);
+
+            assert!(device.live.borrow().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_buffer_image_granularity() {
+        let device = FakeDevice::default();
+        let buffer = AllocationRequest::new(100, 4, 0b01);
+        let image = buffer.with_tiling(Tiling::Optimal);
+
+        unsafe {
+            let allocator = BlockAllocator::with_block_size(properties(), 1 << 16)
+                .with_buffer_image_granularity(1024);
+
+            let a = allocator.allocate(&device, &buffer).unwrap();
+            let b = allocator.allocate(&device, &image).unwrap();
+            let c = allocator.allocate(&device, &image).unwrap();
+
+            assert_ne!(
+                a.memory(),
+                b.memory(),
+                "Linear and optimal resources use separate blocks"
+            );
+            assert_eq!(b.memory(), c.memory());
+
+            for allocation in [a, b, c] {
+                allocator.deallocate(&device, &allocation);
+            }
+
+            allocator.release(&device);
+
+            let allocator = BlockAllocator::with_block_size(properties(), 1 << 16);
+
+            let a = allocator.allocate(&device, &buffer).unwrap();
+            let b = allocator.allocate(&device, &image).unwrap();
+
+            assert_eq!(
+                a.memory(),
+                b.memory(),
+                "Without a granularity, every resource shares the blocks"
+            );
+
+            allocator.deallocate(&device, &a);
+            allocator.deallocate(&device, &b);
+            allocator.release(&device);
+        }
+
+        assert!(device.live.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_memory_type_fallback() {
+        let device = FakeDevice {
+            failing_types: vec![0],
+            ..Default::default()
+        };
+        let allocator = BlockAllocator::new(properties());
+
+        unsafe {
+            let allocation = allocator
+                .allocate(&device, &AllocationRequest::new(64, 64, 0b11))
+                .unwrap();
+
+            assert_eq!(
+                allocation.memory_type_index(),
+                1,
+                "Falls back to the next candidate type"
+            );
+            assert_eq!(
+                allocator.statistics()[1].reserved_bytes,
+                (1 << 20) / 8,
+                "Blocks of small heaps are an eighth of the heap"
+            );
+
+            assert!(matches!(
+                allocator.allocate(&device, &AllocationRequest::new(64, 64, 0b01)),
+                Err(DeviceAllocError::Device(()))
+            ));
+            assert!(matches!(
+                allocator.allocate(
+                    &device,
+                    &AllocationRequest::new(64, 64, 0b01).with_usage(MemoryUsage::Upload)
+                ),
+                Err(DeviceAllocError::NoSuitableMemoryType)
+            ));
+
+            allocator.deallocate(&device, &allocation);
+            allocator.release(&device);
+        }
+    }
+
+    #[test]
+    fn test_host_visible_memory_is_mapped() {
+        let device = FakeDevice::default();
+        let allocator = BlockAllocator::new(properties());
+        let request = AllocationRequest::new(100, 64, 0b11).with_usage(MemoryUsage::Upload);
+
+        unsafe {
+            let gpu_only = allocator
+                .allocate(&device, &AllocationRequest::new(100, 64, 0b11))
+                .unwrap();
+            let a = allocator.allocate(&device, &request).unwrap();
+            let b = allocator.allocate(&device, &request).unwrap();
+
+            assert_eq!(gpu_only.mapped_ptr(), None);
+            assert_eq!(a.memory(), b.memory());
+            assert_eq!(
+                b.mapped_ptr().unwrap().as_ptr() as usize
+                    - a.mapped_ptr().unwrap().as_ptr() as usize,
+                b.offset() as usize,
+                "Allocations point into the block's mapping"
+            );
+            assert_eq!(device.mapped.borrow().len(), 1, "Blocks are mapped once");
+
+            for allocation in [gpu_only, a, b] {
+                allocator.deallocate(&device, &allocation);
+            }
+
+            allocator.release(&device);
+        }
+    }
+}
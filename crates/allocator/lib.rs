@@ -1,7 +1,13 @@
 mod allocator;
+mod device;
 mod system;
 
 pub use allocator::{AllocError, Allocator};
+pub use device::{
+    AllocationRequest, AllocationStrategy, BlockAllocator, DEFAULT_BLOCK_SIZE, DeviceAllocError,
+    DeviceAllocation, DeviceAllocator, HeapStatistics, MemoryDevice, MemoryFlags, MemoryHeap,
+    MemoryProperties, MemoryType, MemoryUsage, Tiling,
+};
 pub use system::System;
 
 #[cfg(feature = "sys_raw")]
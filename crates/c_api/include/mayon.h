@@ -39,6 +39,10 @@ enum MynFallibleResult
    * @brief Vulkan could not be loaded or initialized.
    */
   MAYON_RESULT_VULKAN_LOAD_ERROR = 45057,
+  /**
+   * @brief Vulkan was loaded, but the backend failed for another reason.
+   */
+  MAYON_RESULT_VULKAN_INTERNAL_ERROR = 45058,
   /**
    * @brief An unspecified internal error occurred.
    */
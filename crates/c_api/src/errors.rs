@@ -29,6 +29,9 @@ pub(crate) enum Error {
         function_name: &'static str,
         return_code: i32,
     },
+    Vulkan {
+        kind: VulkanErrorKind,
+    },
 }
 
 #[inline]
@@ -60,6 +63,7 @@ pub(crate) fn set_null_pointer_arg(name: &'static CStr) -> MynFallibleResult {
 /// - Records `UnsupportedTargetPlatform` and returns `MAYON_RESULT_UNSUPPORTED_PLATFORM_ERROR`.
 /// - Records `FailedBackendLoad { name: "Vulkan" }` and returns `MAYON_RESULT_BACKEND_LOAD_ERROR`.
 /// - Records `VulkanFunction { function_name, return_code }` and returns `MAYON_RESULT_VULKAN_LOAD_ERROR`.
+/// - Records any other Vulkan error as `Vulkan { kind }` and returns `MAYON_RESULT_VULKAN_INTERNAL_ERROR`.
 ///
 /// # Returns
 ///
@@ -93,6 +97,11 @@ pub(crate) fn set_vulkan_error(error: CreateBackendError<VulkanErrorKind>) -> My
 
             MynFallibleResult::MAYON_RESULT_VULKAN_LOAD_ERROR
         }
+        CreateBackendErrorKind::BackendInternal(kind) => {
+            LAST_ERROR.set(Some(Error::Vulkan { kind }));
+
+            MynFallibleResult::MAYON_RESULT_VULKAN_INTERNAL_ERROR
+        }
         CreateBackendErrorKind::AllocationFailed => {
             LAST_ERROR.set(Some(Error::InstanceAllocation));
 
@@ -133,6 +142,7 @@ pub(crate) fn get_message() -> *const c_char {
             } => store_message(format!(
                 "Vulkan Error: {function_name} return {return_code}"
             )),
+            Error::Vulkan { kind } => store_message(format!("Vulkan Error: {kind}")),
         }
     })
 }
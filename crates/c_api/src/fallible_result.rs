@@ -39,6 +39,9 @@ pub enum MynFallibleResult {
     /// @brief Vulkan could not be loaded or initialized.
     MAYON_RESULT_VULKAN_LOAD_ERROR = 0o1_3_00_01, // subsystem=00 (Vulkan), detail=1
 
+    /// @brief Vulkan was loaded, but the backend failed for another reason.
+    MAYON_RESULT_VULKAN_INTERNAL_ERROR = 0o1_3_00_02, // subsystem=00 (Vulkan), detail=2
+
     /// @brief An unspecified internal error occurred.
     MAYON_RESULT_UNKNOWN_ERROR = 0o1_7_77_77, // catch-all
 }
@@ -91,6 +91,7 @@ pub unsafe extern "C" fn mayon_new_instance_on_vulkan(
         engine_name: conversions::ptr_to_op_cstr(params.engine_name),
         engine_version: params.engine_version.into(),
         target_platform: None, // TODO: add c-api for target platforms
+        ..VulkanBackendParams::default()
     };
 
     match Instance::<
@@ -0,0 +1,40 @@
+/// The kind of hardware (or software) an adapter represents.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum::Display)]
+pub enum AdapterType {
+    #[strum(to_string = "Discrete GPU")]
+    DiscreteGpu,
+
+    #[strum(to_string = "Integrated GPU")]
+    IntegratedGpu,
+
+    #[strum(to_string = "Virtual GPU")]
+    VirtualGpu,
+
+    #[strum(to_string = "CPU")]
+    Cpu,
+
+    #[strum(to_string = "Other")]
+    Other,
+}
+
+/// A physical device reported by a backend, such as a GPU or a software rasterizer.
+///
+/// Adapters are obtained through [`Instance::enumerate_adapters`](crate::Instance::enumerate_adapters)
+/// and describe the device without opening it.
+pub trait Adapter {
+    /// Human readable device name reported by the driver.
+    fn name(&self) -> &str;
+
+    /// The kind of device this adapter represents.
+    fn adapter_type(&self) -> AdapterType;
+
+    /// PCI vendor identifier (or a Khronos-assigned identifier for non-PCI vendors).
+    fn vendor_id(&self) -> u32;
+
+    /// Vendor specific device identifier.
+    fn device_id(&self) -> u32;
+
+    /// Vendor specific driver version, encoded as reported by the driver.
+    fn driver_version(&self) -> u32;
+}
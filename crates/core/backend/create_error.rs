@@ -1,7 +1,6 @@
-use core::{
-    fmt::{Debug, Display},
-    panic::Location,
-};
+use core::fmt::{Debug, Display};
+#[cfg(feature = "error_location")]
+use core::panic::Location;
 
 #[derive(Copy, Clone, Debug, thiserror::Error)]
 #[error("{kind}")]
@@ -5,4 +5,10 @@ mod traits;
 pub use create_error::{CreateBackendError, CreateBackendErrorKind};
 
 pub use target_platform::{TargetPlatform, UnsupportedPlatformError};
-pub use traits::{Backend, CreateBackend, CreateContextFromRwh};
+pub use traits::{
+    Backend, CreateBackend, CreateCommandPool, CreateDevice, CreateHeadlessContext,
+    EnumerateAdapters,
+};
+
+#[cfg(feature = "windowing")]
+pub use traits::CreateContextFromRwh;
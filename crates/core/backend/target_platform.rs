@@ -1,4 +1,5 @@
 use bitflags::bitflags;
+#[cfg(feature = "windowing")]
 use raw_window_handle::RawDisplayHandle;
 
 bitflags! {
@@ -18,6 +19,7 @@ bitflags! {
 #[error("Unsupported target windowing platform")]
 pub struct UnsupportedPlatformError;
 
+#[cfg(feature = "windowing")]
 impl TargetPlatform {
     /// Maps a `RawDisplayHandle` to the corresponding `TargetPlatform`, optionally adding the `HEADLESS` flag.
     ///
@@ -1,13 +1,21 @@
+#[cfg(feature = "windowing")]
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 
 use allocator::Allocator;
 
-use crate::{BaseError, CreateBackendError, InstanceRef, context::ContextHandler, logger::Logger};
+use crate::{
+    Adapter, BaseError, CommandPool, CreateBackendError, Device, Format, InstanceRef, QueueKind,
+    context::ContextHandler,
+    device::{DestroyDevice, DeviceHandler},
+    logger::Logger,
+};
 
 pub trait Backend {
     type Context: ContextHandler;
+    type Device: DeviceHandler;
 }
 
+#[cfg(feature = "windowing")]
 pub trait CreateContextFromRwh<L, A>: Backend
 where
     A: Allocator,
@@ -24,6 +32,70 @@ where
         H: HasDisplayHandle + HasWindowHandle;
 }
 
+pub trait CreateHeadlessContext<L, A>: Backend
+where
+    A: Allocator,
+    L: Logger,
+    Self: Sized,
+{
+    type Error: BaseError;
+
+    fn create_headless_context(
+        instance: &InstanceRef<Self, L, A>,
+        width: u32,
+        height: u32,
+        format: Format,
+    ) -> Result<Self::Context, crate::CreateContextError<<Self::Error as BaseError>::ErrorKind>>;
+}
+
+pub trait EnumerateAdapters<L, A>: Backend
+where
+    A: Allocator,
+    L: Logger,
+    Self: Sized,
+{
+    type Error: BaseError;
+    type Adapter: Adapter;
+
+    fn enumerate_adapters(
+        instance: &InstanceRef<Self, L, A>,
+    ) -> Result<Vec<Self::Adapter>, Self::Error>;
+}
+
+pub trait CreateDevice<L, A>: EnumerateAdapters<L, A>
+where
+    A: Allocator,
+    L: Logger,
+    Self: Sized,
+{
+    type Error: BaseError;
+
+    #[allow(clippy::type_complexity)]
+    fn create_device(
+        instance: &InstanceRef<Self, L, A>,
+        adapter: &<Self as EnumerateAdapters<L, A>>::Adapter,
+    ) -> Result<
+        Self::Device,
+        crate::CreateDeviceError<<<Self as CreateDevice<L, A>>::Error as BaseError>::ErrorKind>,
+    >;
+}
+
+pub trait CreateCommandPool<L, A>: Backend
+where
+    A: Allocator,
+    L: Logger,
+    Self: Sized,
+    Self::Device: DestroyDevice<Self, L, A>,
+{
+    type Error: BaseError;
+    type CommandPool: CommandPool;
+
+    fn create_command_pool(
+        device: &Device<Self, L, A>,
+        queue: QueueKind,
+    ) -> Result<Self::CommandPool, Self::Error>;
+}
+
 pub trait CreateBackend<'s, A, L>
 where
     A: Allocator,
@@ -0,0 +1,98 @@
+use crate::BaseError;
+
+/// The kind of queue a command pool records for and submits to.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum::Display)]
+pub enum QueueKind {
+    #[strum(to_string = "graphics")]
+    Graphics,
+
+    #[strum(to_string = "compute")]
+    Compute,
+
+    #[strum(to_string = "transfer")]
+    Transfer,
+}
+
+/// How a command buffer is going to be submitted once recorded.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum CommandBufferUsage {
+    /// The buffer is submitted once, then recorded again before its next submission.
+    #[default]
+    OneTimeSubmit,
+
+    /// The buffer can be submitted any number of times without being recorded again.
+    Reusable,
+}
+
+/// Lifecycle state of a [`CommandBuffer`].
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum::Display)]
+pub enum CommandBufferState {
+    /// Freshly allocated or reset; ready to begin recording.
+    #[strum(to_string = "initial")]
+    Initial,
+
+    /// Between [`CommandBuffer::begin`] and [`CommandBuffer::end`].
+    #[strum(to_string = "recording")]
+    Recording,
+
+    /// Recording ended; the buffer can be submitted.
+    #[strum(to_string = "executable")]
+    Executable,
+
+    /// A one-time-submit buffer that was already submitted; it must be recorded again.
+    #[strum(to_string = "invalid")]
+    Invalid,
+}
+
+/// A pool that command buffers for one queue are allocated from.
+///
+/// Pools are meant to be used from a single thread: create one pool per recording thread.
+/// Command buffers borrow their pool, so [`reset`](Self::reset) cannot be called while any
+/// of them is alive.
+pub trait CommandPool {
+    type Error: BaseError;
+
+    type CommandBuffer<'p>: CommandBuffer<Error = Self::Error>
+    where
+        Self: 'p;
+
+    /// The queue this pool's command buffers are submitted to.
+    fn queue(&self) -> QueueKind;
+
+    /// Allocates a command buffer in the [`Initial`](CommandBufferState::Initial) state.
+    fn allocate(&self) -> Result<Self::CommandBuffer<'_>, Self::Error>;
+
+    /// Waits for every submission from this pool to complete, then resets all of its
+    /// command buffers at once.
+    fn reset(&mut self) -> Result<(), Self::Error>;
+
+    /// Submits executable command buffers of this pool to its queue, in order.
+    fn submit<'p>(
+        &'p self,
+        command_buffers: &[&Self::CommandBuffer<'p>],
+    ) -> Result<(), Self::Error>;
+}
+
+/// A buffer of recorded commands.
+pub trait CommandBuffer {
+    type Error: BaseError;
+
+    /// Current lifecycle state.
+    fn state(&self) -> CommandBufferState;
+
+    /// Starts recording, discarding previously recorded commands.
+    ///
+    /// If the buffer is still executing, this waits for the execution to complete.
+    fn begin(&mut self, usage: CommandBufferUsage) -> Result<(), Self::Error>;
+
+    /// Ends recording, making the buffer executable.
+    fn end(&mut self) -> Result<(), Self::Error>;
+
+    /// Discards recorded commands, returning the buffer to the initial state.
+    ///
+    /// If the buffer is still executing, this waits for the execution to complete.
+    fn reset(&mut self) -> Result<(), Self::Error>;
+}
@@ -75,6 +75,19 @@ where
     }
 }
 
+impl<B, L, A> ops::DerefMut for Context<B, L, A>
+where
+    B: Backend,
+    B::Context: DestroyContext<B, L, A>,
+    L: Logger,
+    A: Allocator,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut self.0.as_mut().context }
+    }
+}
+
 impl<B, L, A> Drop for Context<B, L, A>
 where
     B: Backend,
@@ -0,0 +1,102 @@
+use core::fmt::{Debug, Display};
+#[cfg(feature = "error_location")]
+use core::panic::Location;
+
+#[derive(Copy, Clone, Debug, thiserror::Error)]
+#[error("{kind}")]
+pub struct CreateDeviceError<B>
+where
+    B: Copy + Clone + Debug + Display,
+{
+    pub(crate) kind: CreateDeviceErrorKind<B>,
+    #[cfg(feature = "error_location")]
+    pub(crate) location: &'static Location<'static>,
+}
+
+impl<B> CreateDeviceError<B>
+where
+    B: Copy + Clone + Debug + Display,
+{
+    /// Creates a new `CreateDeviceError` with the given error kind.
+    ///
+    /// The `location` argument is included only when the `error_location` feature is enabled
+    /// and captures the caller location for diagnostic purposes.
+    pub const fn new(
+        kind: CreateDeviceErrorKind<B>,
+        #[cfg(feature = "error_location")] location: &'static Location<'static>,
+    ) -> Self {
+        Self {
+            kind,
+            #[cfg(feature = "error_location")]
+            location,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, thiserror::Error)]
+pub enum CreateDeviceErrorKind<B>
+where
+    B: Copy + Clone + Debug + Display,
+{
+    #[error("Allocating memory for device failed")]
+    AllocationFailed,
+
+    #[error("Adapter does not provide the required queues")]
+    UnsupportedAdapter,
+
+    #[error("{0}")]
+    BackendInternal(B),
+}
+
+impl<B> crate::BaseError for CreateDeviceError<B>
+where
+    B: Copy + Clone + Debug + Display,
+{
+    type ErrorKind = CreateDeviceErrorKind<B>;
+
+    /// Returns the error's kind.
+    fn kind(&self) -> Self::ErrorKind {
+        self.kind
+    }
+
+    /// Get the stored caller location associated with this error.
+    #[cfg(feature = "error_location")]
+    #[inline]
+    fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+
+impl<B> CreateDeviceErrorKind<B>
+where
+    B: Copy + Clone + Debug + Display,
+{
+    /// Convert this `CreateDeviceErrorKind` into an error `Result`, producing a
+    /// `CreateDeviceError` that captures the caller's source location.
+    ///
+    /// # Returns
+    ///
+    /// `Err(CreateDeviceError)` containing this kind and the call-site `Location`.
+    #[cfg(feature = "error_location")]
+    #[inline]
+    #[track_caller]
+    pub const fn into_result<T>(self) -> Result<T, self::CreateDeviceError<B>> {
+        Err(CreateDeviceError {
+            kind: self,
+            location: Location::caller(),
+        })
+    }
+
+    /// Converts this `CreateDeviceErrorKind` into an `Err` value containing a `CreateDeviceError`.
+    ///
+    /// This version is used when the `error_location` feature is disabled and therefore does not attach a call-site location to the error.
+    ///
+    /// # Returns
+    ///
+    /// `Err(CreateDeviceError)` with `kind` set to `self`.
+    #[cfg(not(feature = "error_location"))]
+    #[inline]
+    pub const fn into_result<T>(self) -> Result<T, self::CreateDeviceError<B>> {
+        Err(CreateDeviceError { kind: self })
+    }
+}
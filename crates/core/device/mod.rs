@@ -0,0 +1,192 @@
+mod create_error;
+
+use core::{
+    ops,
+    ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering, fence},
+};
+
+use crate::{Backend, CreateCommandPool, InstanceRef, QueueKind, logger::Logger};
+use allocator::Allocator;
+
+pub use create_error::{CreateDeviceError, CreateDeviceErrorKind};
+
+pub trait DeviceHandler {}
+
+pub trait DestroyDevice<B, L, A>: DeviceHandler
+where
+    B: Backend,
+    L: Logger,
+    A: Allocator,
+{
+    fn destroy(&self, instance: &InstanceRef<B, L, A>);
+}
+
+/// A logical device opened on an [`Adapter`](crate::Adapter).
+///
+/// The device owns the backend's queues and is the parent of every GPU resource.
+/// It is internally reference-counted, so resources can keep it alive by holding a clone;
+/// the backend device is destroyed when the last clone is dropped.
+pub struct Device<B, L, A>(NonNull<Inner<B, L, A>>)
+where
+    B: Backend,
+    B::Device: DestroyDevice<B, L, A>,
+    L: Logger,
+    A: Allocator;
+
+struct Inner<B, L, A>
+where
+    B: Backend,
+    B::Device: DestroyDevice<B, L, A>,
+    L: Logger,
+    A: Allocator,
+{
+    instance: InstanceRef<B, L, A>,
+    device: B::Device,
+
+    ref_count: AtomicUsize,
+}
+
+impl<B, L, A> Device<B, L, A>
+where
+    B: Backend,
+    B::Device: DestroyDevice<B, L, A>,
+    L: Logger,
+    A: Allocator,
+{
+    /// Moves `device` into a reference-counted allocation owned by the instance allocator.
+    ///
+    /// On allocation failure the backend device is handed back so the caller can destroy it.
+    #[inline]
+    pub(crate) fn create(
+        instance: &InstanceRef<B, L, A>,
+        device: B::Device,
+    ) -> Result<Self, B::Device> {
+        let Ok(buffer) = (unsafe { instance.allocator().allocate_uninit::<Inner<B, L, A>>() })
+        else {
+            return Err(device);
+        };
+
+        let ptr = buffer.cast::<Inner<B, L, A>>();
+
+        unsafe {
+            ptr.write(Inner {
+                instance: instance.clone(),
+                device,
+                ref_count: AtomicUsize::new(1),
+            });
+        }
+
+        Ok(Self(ptr))
+    }
+
+    /// Returns the instance this device was created from.
+    #[inline(always)]
+    pub fn instance(&self) -> &InstanceRef<B, L, A> {
+        unsafe { &self.0.as_ref().instance }
+    }
+
+    /// Creates a command pool recording for the device's `queue`.
+    ///
+    /// Command pools are not shared between threads; create one per recording thread. The
+    /// pool keeps this device alive.
+    ///
+    /// # Errors
+    ///
+    /// Returns the backend error if the device has no such queue or the pool could not be
+    /// created.
+    #[inline]
+    pub fn create_command_pool(
+        &self,
+        queue: QueueKind,
+    ) -> Result<B::CommandPool, <B as CreateCommandPool<L, A>>::Error>
+    where
+        B: CreateCommandPool<L, A>,
+    {
+        B::create_command_pool(self, queue)
+    }
+}
+
+impl<B, L, A> Clone for Device<B, L, A>
+where
+    B: Backend,
+    B::Device: DestroyDevice<B, L, A>,
+    L: Logger,
+    A: Allocator,
+{
+    fn clone(&self) -> Self {
+        const MAX_REFCOUNT: usize = (isize::MAX) as _;
+
+        let old_count = unsafe { self.0.as_ref() }
+            .ref_count
+            .fetch_add(1, Ordering::Relaxed);
+
+        if old_count >= MAX_REFCOUNT {
+            std::process::abort();
+        }
+
+        Self(self.0)
+    }
+}
+
+impl<B, L, A> ops::Deref for Device<B, L, A>
+where
+    B: Backend,
+    B::Device: DestroyDevice<B, L, A>,
+    L: Logger,
+    A: Allocator,
+{
+    type Target = B::Device;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &self.0.as_ref().device }
+    }
+}
+
+impl<B, L, A> Drop for Device<B, L, A>
+where
+    B: Backend,
+    B::Device: DestroyDevice<B, L, A>,
+    L: Logger,
+    A: Allocator,
+{
+    fn drop(&mut self) {
+        unsafe {
+            if self.0.as_ref().ref_count.fetch_sub(1, Ordering::Release) != 1 {
+                return;
+            }
+
+            fence(Ordering::Acquire);
+
+            let inner = self.0.as_ref();
+
+            inner.device.destroy(&inner.instance);
+
+            // `Inner` owns an instance reference; hold another one so the allocator outlives
+            // the deallocation.
+            let instance = inner.instance.clone();
+
+            instance
+                .allocator()
+                .deallocate_init::<Inner<B, L, A>>(self.0);
+        }
+    }
+}
+
+unsafe impl<B, L, A> Send for Device<B, L, A>
+where
+    B: Backend + Send + Sync,
+    B::Device: DestroyDevice<B, L, A> + Send + Sync,
+    L: Logger + Send + Sync,
+    A: Allocator + Send + Sync,
+{
+}
+unsafe impl<B, L, A> Sync for Device<B, L, A>
+where
+    B: Backend + Send + Sync,
+    B::Device: DestroyDevice<B, L, A> + Send + Sync,
+    L: Logger + Send + Sync,
+    A: Allocator + Send + Sync,
+{
+}
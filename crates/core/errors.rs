@@ -1,7 +1,6 @@
-use core::{
-    fmt::{Debug, Display},
-    panic::Location,
-};
+use core::fmt::{Debug, Display};
+#[cfg(feature = "error_location")]
+use core::panic::Location;
 
 pub trait BaseError
 where
@@ -0,0 +1,42 @@
+/// Pixel format of an image or render target.
+///
+/// The names follow the channel order and numeric type of each texel, e.g. `Rgba8Srgb` is
+/// four 8-bit channels stored as sRGB-encoded unsigned normalized values.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum::Display)]
+pub enum Format {
+    R8Unorm,
+    Rg8Unorm,
+    Rgba8Unorm,
+    Rgba8Srgb,
+    Bgra8Unorm,
+    Bgra8Srgb,
+    R16Float,
+    Rgba16Float,
+    R32Float,
+    Rgba32Float,
+}
+
+impl Format {
+    /// Size of a single texel in bytes.
+    #[inline]
+    pub const fn texel_size(self) -> usize {
+        match self {
+            Self::R8Unorm => 1,
+            Self::Rg8Unorm | Self::R16Float => 2,
+            Self::Rgba8Unorm
+            | Self::Rgba8Srgb
+            | Self::Bgra8Unorm
+            | Self::Bgra8Srgb
+            | Self::R32Float => 4,
+            Self::Rgba16Float => 8,
+            Self::Rgba32Float => 16,
+        }
+    }
+
+    /// Whether texel values are stored sRGB-encoded.
+    #[inline]
+    pub const fn is_srgb(self) -> bool {
+        matches!(self, Self::Rgba8Srgb | Self::Bgra8Srgb)
+    }
+}
@@ -16,9 +16,11 @@ where
     L: Logger,
     B: Backend,
 {
+    // Declared first so the backend is dropped before the allocator and logger it may
+    // still reference.
+    backend: B,
     allocator: A,
     logger: L,
-    backend: B,
 
     ref_count: AtomicUsize,
 }
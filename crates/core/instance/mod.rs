@@ -1,12 +1,14 @@
 mod inner;
 
+#[cfg(feature = "windowing")]
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 
 use allocator::{Allocator, System};
 
 use crate::{
-    Backend, BaseError, CreateBackend, CreateBackendError, CreateContextErrorKind,
-    CreateContextFromRwh,
+    Adapter, AdapterType, Backend, BaseError, CreateBackend, CreateBackendError,
+    CreateContextErrorKind, CreateDevice, CreateDeviceErrorKind, CreateHeadlessContext,
+    DestroyDevice, EnumerateAdapters, Format,
     logger::{DefaultLogger, Logger},
 };
 
@@ -104,6 +106,12 @@ where
     L: Logger,
     A: Allocator,
 {
+    /// Returns a reference to the graphics backend, for backend-specific queries.
+    #[inline(always)]
+    pub fn backend(&self) -> &B {
+        unsafe { self.create_ref() }.backend()
+    }
+
     /// Creates a new [`Context`](crate::Context) from raw window handles.
     ///
     /// This method allows Mayon to interface with externally created windows (e.g., from `winit`).
@@ -116,6 +124,7 @@ where
     ///
     /// Returns a [`CreateContextError`](crate::CreateContextError) if the context could not be created.
     /// This can happen due to incompatible window handles, device loss, or allocation failures.
+    #[cfg(feature = "windowing")]
     #[allow(clippy::type_complexity)]
     pub fn create_context_from_rwh<H>(
         &self,
@@ -125,7 +134,7 @@ where
         crate::CreateContextError<<B::Error as BaseError>::ErrorKind>,
     >
     where
-        B: CreateContextFromRwh<L, A>,
+        B: crate::CreateContextFromRwh<L, A>,
         B::Context: crate::context::DestroyContext<B, L, A>,
         H: HasDisplayHandle + HasWindowHandle,
     {
@@ -139,4 +148,143 @@ where
 
         Ok(context)
     }
+
+    /// Creates a new [`Context`](crate::Context) that renders to offscreen images instead of a window.
+    ///
+    /// This allows rendering on machines without a display, such as CI runners using a
+    /// software rasterizer.
+    ///
+    /// # Parameters
+    ///
+    /// * `width`: Width of the offscreen images in pixels.
+    /// * `height`: Height of the offscreen images in pixels.
+    /// * `format`: Pixel format of the offscreen images.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CreateContextError`](crate::CreateContextError) if the context could not be created.
+    #[allow(clippy::type_complexity)]
+    pub fn create_headless_context(
+        &self,
+        width: u32,
+        height: u32,
+        format: Format,
+    ) -> Result<
+        crate::Context<B, L, A>,
+        crate::CreateContextError<<B::Error as BaseError>::ErrorKind>,
+    >
+    where
+        B: CreateHeadlessContext<L, A>,
+        B::Context: crate::context::DestroyContext<B, L, A>,
+    {
+        let instance = unsafe { self.create_ref() };
+
+        let Ok(context) = crate::Context::create(
+            instance,
+            B::create_headless_context(instance, width, height, format)?,
+        ) else {
+            return CreateContextErrorKind::AllocationFailed.into_result();
+        };
+
+        Ok(context)
+    }
+
+    /// Lists the adapters (physical devices) available to this instance.
+    ///
+    /// The adapters are returned in the order reported by the backend.
+    ///
+    /// # Errors
+    ///
+    /// Returns the backend error if the devices could not be queried.
+    pub fn enumerate_adapters(&self) -> Result<Vec<B::Adapter>, B::Error>
+    where
+        B: EnumerateAdapters<L, A>,
+    {
+        B::enumerate_adapters(unsafe { self.create_ref() })
+    }
+
+    /// Picks an adapter according to a list of preferred adapter types.
+    ///
+    /// `preference` is ordered from most to least preferred. The first adapter whose type
+    /// matches the earliest entry in `preference` is returned; ties keep the backend's
+    /// enumeration order. An empty `preference` selects the first adapter reported.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(Some(adapter))` when a matching adapter exists, `Ok(None)` otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns the backend error if the devices could not be queried.
+    pub fn select_adapter(&self, preference: &[AdapterType]) -> Result<Option<B::Adapter>, B::Error>
+    where
+        B: EnumerateAdapters<L, A>,
+    {
+        let adapters = self.enumerate_adapters()?;
+
+        if preference.is_empty() {
+            return Ok(adapters.into_iter().next());
+        }
+
+        let rank = |adapter: &B::Adapter| {
+            preference
+                .iter()
+                .position(|&adapter_type| adapter_type == adapter.adapter_type())
+        };
+
+        let mut selected: Option<(usize, B::Adapter)> = None;
+
+        for adapter in adapters {
+            let Some(adapter_rank) = rank(&adapter) else {
+                continue;
+            };
+
+            if selected
+                .as_ref()
+                .is_none_or(|(selected_rank, _)| adapter_rank < *selected_rank)
+            {
+                selected = Some((adapter_rank, adapter));
+            }
+        }
+
+        Ok(selected.map(|(_, adapter)| adapter))
+    }
+
+    /// Opens a logical [`Device`](crate::Device) on `adapter`.
+    ///
+    /// The backend selects the queues it needs from the adapter; the returned device keeps
+    /// the instance alive for as long as it (or any of its clones) exists.
+    ///
+    /// # Parameters
+    ///
+    /// * `adapter`: An adapter obtained from [`enumerate_adapters`](Self::enumerate_adapters)
+    ///   or [`select_adapter`](Self::select_adapter) on this instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`CreateDeviceError`](crate::CreateDeviceError) if the adapter lacks the
+    /// required queues, the backend fails to create the device, or allocation fails.
+    #[allow(clippy::type_complexity)]
+    pub fn create_device(
+        &self,
+        adapter: &<B as EnumerateAdapters<L, A>>::Adapter,
+    ) -> Result<
+        crate::Device<B, L, A>,
+        crate::CreateDeviceError<<<B as CreateDevice<L, A>>::Error as BaseError>::ErrorKind>,
+    >
+    where
+        B: CreateDevice<L, A>,
+        B::Device: DestroyDevice<B, L, A>,
+    {
+        let instance = unsafe { self.create_ref() };
+
+        match crate::Device::create(instance, B::create_device(instance, adapter)?) {
+            Ok(device) => Ok(device),
+            Err(device) => {
+                device.destroy(instance);
+
+                CreateDeviceErrorKind::AllocationFailed.into_result()
+            }
+        }
+    }
 }
@@ -1,11 +1,19 @@
+mod adapter;
 mod backend;
+mod command;
 mod context;
+mod device;
 mod errors;
+mod format;
 mod instance;
 
 pub mod logger;
 
+pub use adapter::{Adapter, AdapterType};
 pub use backend::*;
+pub use command::*;
 pub use context::*;
+pub use device::*;
 pub use errors::BaseError;
+pub use format::Format;
 pub use instance::{Instance, InstanceRef};
@@ -3,4 +3,8 @@
 pub enum Target {
     #[strum(serialize = "mayon::backend")]
     Backend,
+
+    /// Messages reported by the graphics API's validation layers.
+    #[strum(serialize = "mayon::validation")]
+    Validation,
 }
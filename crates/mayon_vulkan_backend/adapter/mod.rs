@@ -0,0 +1,77 @@
+use mayon_core::{Adapter, AdapterType};
+
+use crate::{
+    VulkanVersion,
+    types::{PhysicalDevice, PhysicalDeviceProperties, PhysicalDeviceType},
+};
+
+/// A Vulkan physical device as reported by `vkEnumeratePhysicalDevices`.
+#[derive(Clone, Debug)]
+pub struct VulkanAdapter {
+    pub(crate) physical_device: PhysicalDevice,
+    name: String,
+    adapter_type: AdapterType,
+    vendor_id: u32,
+    device_id: u32,
+    driver_version: u32,
+    api_version: VulkanVersion,
+}
+
+impl VulkanAdapter {
+    /// Builds an adapter description from the properties reported for `physical_device`.
+    pub(crate) fn new(
+        physical_device: PhysicalDevice,
+        properties: &PhysicalDeviceProperties,
+    ) -> Self {
+        let adapter_type = match properties.device_type {
+            PhysicalDeviceType::DISCRETE_GPU => AdapterType::DiscreteGpu,
+            PhysicalDeviceType::INTEGRATED_GPU => AdapterType::IntegratedGpu,
+            PhysicalDeviceType::VIRTUAL_GPU => AdapterType::VirtualGpu,
+            PhysicalDeviceType::CPU => AdapterType::Cpu,
+            _ => AdapterType::Other,
+        };
+
+        Self {
+            physical_device,
+            name: properties.device_name().to_string_lossy().into_owned(),
+            adapter_type,
+            vendor_id: properties.vendor_id,
+            device_id: properties.device_id,
+            driver_version: properties.driver_version,
+            api_version: VulkanVersion::from_raw(properties.api_version),
+        }
+    }
+
+    /// Highest Vulkan version supported by the device.
+    #[inline]
+    pub const fn api_version(&self) -> VulkanVersion {
+        self.api_version
+    }
+}
+
+impl Adapter for VulkanAdapter {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    fn adapter_type(&self) -> AdapterType {
+        self.adapter_type
+    }
+
+    #[inline]
+    fn vendor_id(&self) -> u32 {
+        self.vendor_id
+    }
+
+    #[inline]
+    fn device_id(&self) -> u32 {
+        self.device_id
+    }
+
+    #[inline]
+    fn driver_version(&self) -> u32 {
+        self.driver_version
+    }
+}
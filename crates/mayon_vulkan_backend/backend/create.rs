@@ -1,20 +1,24 @@
-use core::{ffi::CStr, marker::PhantomData, ptr::NonNull};
+use core::{ffi::CStr, fmt, marker::PhantomData, ptr::NonNull};
 
+#[cfg(feature = "windowing")]
 use raw_window_handle::HasDisplayHandle;
 
 use allocator::Allocator;
+#[cfg(feature = "windowing")]
+use mayon_core::UnsupportedPlatformError;
 use mayon_core::{
-    BaseError, CreateBackend, CreateBackendError, TargetPlatform, UnsupportedPlatformError, info,
+    BaseError, CreateBackend, CreateBackendError, CreateBackendErrorKind, TargetPlatform, debug,
+    error, info,
     logger::{Logger, Target as LogTarget},
 };
-use utils::{BufferOverflowError, InlineVec};
 
+use super::debug_messenger::messenger_create_info;
 use crate::{
-    VulkanBackend, VulkanError,
-    backend::FnTable,
+    VulkanBackend, VulkanError, VulkanErrorKind,
+    fn_table::{Entry, FnTable, VulkanLoader},
     types::{
-        AllocationCallbacks, ApplicationInfo, ExtensionName, InstanceCreateInfo, LayerName,
-        LayerProperties,
+        AllocationCallbacks, ApplicationInfo, ExtensionName, ExtensionProperties,
+        InstanceCreateInfo, LayerName, LayerProperties,
     },
 };
 
@@ -33,7 +37,16 @@ where
     /// installs allocation callbacks that use `allocator`, and logs successful creation
     /// to `logger`. It returns the constructed `VulkanBackend` on success.
     ///
-    /// Panics if the internal extension-name buffer capacity is exceeded.
+    /// When the validation layer is enabled, a `VK_EXT_debug_utils` messenger forwards its
+    /// messages to `logger` under `Target::Validation`.
+    ///
+    /// With `params.compute_only`, no surface extension is requested; setting a target
+    /// platform as well fails with `UnsupportedTargetPlatform`.
+    ///
+    /// The API version is negotiated between `params.min_api_version`,
+    /// `params.preferred_api_version` and what the loader supports. Layers and instance
+    /// extensions are checked against what the loader reports before
+    /// the instance is created.
     ///
     /// # Returns
     ///
@@ -59,37 +72,127 @@ where
     where
         Self: Sized,
     {
-        let fns = FnTable::global()?;
+        if params.compute_only
+            && params
+                .target_platform
+                .is_some_and(|target_platform| !target_platform.is_empty())
+        {
+            return CreateBackendErrorKind::UnsupportedTargetPlatform.into_result();
+        }
 
-        let application_info = ApplicationInfo::new(params);
+        let entry = Entry::load(params.loader)?;
 
-        #[cfg(debug_assertions)]
-        let layers = {
-            let layers = select_available_layers(fns, [LayerName::VALIDATION])?;
+        let instance_version = entry.enumerate_instance_version()?;
+        let api_version = negotiate_api_version(
+            params.min_api_version,
+            params.preferred_api_version,
+            instance_version,
+        )?;
 
-            if layers.is_empty() {
-                info!(logger, LogTarget::Backend, "Vulkan layers not found");
-            } else {
-                info!(
-                    logger,
-                    LogTarget::Backend,
-                    "Vulkan layers found: {:?}",
-                    layers
-                );
-            }
+        info!(
+            logger,
+            LogTarget::Backend,
+            "Vulkan API version {} (loader supports {})",
+            api_version,
+            instance_version
+        );
 
-            layers
-        };
-        #[cfg(not(debug_assertions))]
-        let layers = [];
+        let application_info = ApplicationInfo::new(&params, api_version);
+
+        let available_layers = entry.enumerate_instance_layer_properties()?;
+        let available_layers = available_layers
+            .iter()
+            .map(LayerProperties::name)
+            .collect::<Vec<_>>();
+
+        let validation_layer = LayerName::VALIDATION;
+        let validation_layer = params.validation.then_some(validation_layer.as_c_str());
+
+        let layers = select_names(
+            logger,
+            "layer",
+            &available_layers,
+            params.required_layers.iter().copied(),
+            params
+                .optional_layers
+                .iter()
+                .copied()
+                .chain(validation_layer),
+        )
+        .or_else(|index| VulkanErrorKind::MissingLayer { index }.into_result())?;
+
+        let validation = validation_layer.is_some_and(|layer| layers.contains(&layer));
+
+        let mut available_extensions = entry.enumerate_instance_extension_properties(None)?;
+
+        for &layer in &layers {
+            available_extensions
+                .extend(entry.enumerate_instance_extension_properties(Some(layer))?);
+        }
 
-        let mut extensions = InlineVec::<ExtensionName, 12>::new();
+        let available_extensions = available_extensions
+            .iter()
+            .map(ExtensionProperties::name)
+            .collect::<Vec<_>>();
+
+        let presentation = params.target_platform.is_some_and(|target_platform| {
+            !target_platform
+                .difference(TargetPlatform::HEADLESS)
+                .is_empty()
+        });
+
+        let mut platform_extensions = Vec::new();
 
         if let Some(target_platform) = params.target_platform {
-            append_extension_names(&target_platform, &mut extensions)
-                .expect("Vulkan extension name buffer overflow");
+            append_extension_names(&target_platform, &mut platform_extensions);
         }
 
+        let debug_utils = ExtensionName::DEBUG_UTILS;
+        let debug_utils = validation.then_some(debug_utils.as_c_str());
+
+        let extensions = select_names(
+            logger,
+            "instance extension",
+            &available_extensions,
+            platform_extensions
+                .iter()
+                .map(ExtensionName::as_c_str)
+                .chain(params.required_extensions.iter().copied()),
+            params
+                .optional_extensions
+                .iter()
+                .copied()
+                .chain(debug_utils),
+        )
+        .or_else(|index| match index.checked_sub(platform_extensions.len()) {
+            Some(index) => VulkanErrorKind::MissingExtension { index }.into_result(),
+            None => VulkanErrorKind::PresentationUnsupported.into_result(),
+        })?;
+
+        let messenger = debug_utils.is_some_and(|debug_utils| extensions.contains(&debug_utils));
+
+        info!(
+            logger,
+            LogTarget::Backend,
+            "Vulkan layers enabled: {:?}",
+            layers
+        );
+        debug!(
+            logger,
+            LogTarget::Backend,
+            "Vulkan instance extensions enabled: {:?}",
+            extensions
+        );
+
+        let layers = layers
+            .into_iter()
+            .map(LayerName::from_c_str)
+            .collect::<Vec<_>>();
+        let extensions = extensions
+            .into_iter()
+            .map(ExtensionName::from_c_str)
+            .collect::<Vec<_>>();
+
         let info =
             InstanceCreateInfo::new(&application_info, layers.as_slice(), extensions.as_slice());
 
@@ -97,20 +200,53 @@ where
             NonNull::new_unchecked((allocator as *const A).cast_mut())
         });
 
-        let instance = unsafe { fns.create_instance(&info, allocation_callbacks.alloc_ref()) }?;
+        let instance = unsafe { entry.create_instance(&info, allocation_callbacks.alloc_ref()) }?;
+        let fns = unsafe {
+            FnTable::load(
+                &entry,
+                instance,
+                allocation_callbacks.alloc_ref(),
+                &extensions,
+            )
+        }?;
 
         info!(
             logger,
             LogTarget::Backend,
-            "Vulkan instance created: {:?}",
-            instance
+            "Vulkan instance created: {:?}{}",
+            instance,
+            if params.compute_only {
+                " (compute only)"
+            } else {
+                ""
+            }
         );
 
-        Ok(Self {
+        let mut backend = Self {
             instance,
+            fns,
+            debug_messenger: None,
+            api_version,
             alloc: allocation_callbacks,
+            presentation,
+            compute_only: params.compute_only,
+            _entry: entry,
             _marker: PhantomData,
-        })
+        };
+
+        if messenger {
+            let info = messenger_create_info::<L>(logger);
+
+            // The messenger keeps a pointer to `logger`, which lives next to the backend in
+            // the instance and is dropped after it.
+            backend.debug_messenger = Some(unsafe {
+                backend
+                    .fns
+                    .create_debug_utils_messenger(instance, &info, backend.allocator())
+            }?);
+        }
+
+        Ok(backend)
     }
 }
 
@@ -121,10 +257,33 @@ pub struct VulkanBackendParams<'s> {
     pub engine_name: Option<&'s CStr>,
     pub engine_version: VulkanVersion,
     pub target_platform: Option<TargetPlatform>,
+    pub loader: VulkanLoader<'s>,
+    /// Lowest Vulkan API version the application can run on. Instance creation fails with
+    /// `UnsupportedApiVersion` if the loader offers less.
+    pub min_api_version: VulkanVersion,
+    /// Vulkan API version to request when the loader supports it. Older loaders get the
+    /// highest version they support, down to `min_api_version`.
+    pub preferred_api_version: VulkanVersion,
+    /// Enables `VK_LAYER_KHRONOS_validation` and routes its messages to the logger, if the
+    /// layer is installed. Defaults to on in debug builds.
+    pub validation: bool,
+    /// Layers that must be enabled; instance creation fails with `MissingLayer` otherwise.
+    pub required_layers: &'s [&'s CStr],
+    /// Layers enabled only when available.
+    pub optional_layers: &'s [&'s CStr],
+    /// Instance extensions that must be enabled, in addition to the surface extensions of
+    /// `target_platform`; instance creation fails with `MissingExtension` otherwise.
+    pub required_extensions: &'s [&'s CStr],
+    /// Instance extensions enabled only when available.
+    pub optional_extensions: &'s [&'s CStr],
+    /// Creates an instance for compute work only: no surface extension is requested, only
+    /// adapters with a compute queue are enumerated, devices open no graphics queue, and
+    /// contexts cannot be created. `target_platform` must be left unset.
+    pub compute_only: bool,
 }
 
 impl Default for VulkanBackendParams<'_> {
-    /// Creates a default `VulkanBackendParams` with application and engine versions set to 0.1.0 and no names or target platform.
+    /// Creates a default `VulkanBackendParams` with application and engine versions set to 0.1.0, no names or target platform, and the system loader.
     ///
     /// # Examples
     ///
@@ -145,6 +304,15 @@ impl Default for VulkanBackendParams<'_> {
             engine_name: None,
             engine_version: v0_1,
             target_platform: None,
+            loader: VulkanLoader::System,
+            min_api_version: VulkanVersion::new(1, 0, 0),
+            preferred_api_version: VulkanVersion::new(1, 3, 0),
+            validation: cfg!(debug_assertions),
+            required_layers: &[],
+            optional_layers: &[],
+            required_extensions: &[],
+            optional_extensions: &[],
+            compute_only: false,
         }
     }
 }
@@ -194,6 +362,63 @@ impl<'s> VulkanBackendParams<'s> {
         self
     }
 
+    /// Sets where the Vulkan loader is found, replacing the platform's default search.
+    #[inline]
+    pub fn with_loader(mut self, loader: VulkanLoader<'s>) -> Self {
+        self.loader = loader;
+        self
+    }
+
+    /// Sets the range of Vulkan API versions the application accepts.
+    #[inline]
+    pub fn with_api_version(
+        mut self,
+        min: impl Into<VulkanVersion>,
+        preferred: impl Into<VulkanVersion>,
+    ) -> Self {
+        self.min_api_version = min.into();
+        self.preferred_api_version = preferred.into();
+        self
+    }
+
+    /// Turns the validation layer on or off, regardless of the build profile.
+    #[inline]
+    pub fn with_validation(mut self, validation: bool) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    #[inline]
+    pub fn with_required_layers(mut self, layers: &'s [&'s CStr]) -> Self {
+        self.required_layers = layers;
+        self
+    }
+
+    #[inline]
+    pub fn with_optional_layers(mut self, layers: &'s [&'s CStr]) -> Self {
+        self.optional_layers = layers;
+        self
+    }
+
+    #[inline]
+    pub fn with_required_extensions(mut self, extensions: &'s [&'s CStr]) -> Self {
+        self.required_extensions = extensions;
+        self
+    }
+
+    #[inline]
+    pub fn with_optional_extensions(mut self, extensions: &'s [&'s CStr]) -> Self {
+        self.optional_extensions = extensions;
+        self
+    }
+
+    /// Turns compute-only mode on or off; see [`compute_only`](Self::compute_only).
+    #[inline]
+    pub fn with_compute_only(mut self, compute_only: bool) -> Self {
+        self.compute_only = compute_only;
+        self
+    }
+
     /// Sets the backend's target platform from a raw-window-handle display and returns the updated params.
     ///
     /// If `display` is `Some`, converts it to a `TargetPlatform` using `TargetPlatform::from_raw_display_handle`
@@ -210,6 +435,7 @@ impl<'s> VulkanBackendParams<'s> {
     /// let params = params.with_target_from_rwh::<raw_window_handle::RawDisplayHandle>(None, false).unwrap();
     /// assert!(params.target_platform.is_none());
     /// ```
+    #[cfg(feature = "windowing")]
     pub fn with_target_from_rwh(
         mut self,
         display: Option<impl HasDisplayHandle>,
@@ -231,8 +457,11 @@ impl<'s> VulkanBackendParams<'s> {
     }
 }
 
+/// A Vulkan version number.
+///
+/// Versions order by major, then minor, then patch.
 #[repr(C)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct VulkanVersion {
     pub major: u32,
     pub minor: u32,
@@ -271,6 +500,24 @@ impl VulkanVersion {
     pub(crate) const fn raw(&self) -> u32 {
         (self.major << 22) | (self.minor << 12) | self.patch
     }
+
+    /// Decodes a packed Vulkan version value, ignoring the variant bits.
+    ///
+    /// This is the inverse of [`raw`](Self::raw) for versions reported by the driver.
+    pub(crate) const fn from_raw(raw: u32) -> Self {
+        Self {
+            major: (raw >> 22) & 0x7f,
+            minor: (raw >> 12) & 0x3ff,
+            patch: raw & 0xfff,
+        }
+    }
+}
+
+impl fmt::Display for VulkanVersion {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
 }
 
 impl From<(u32, u32, u32)> for VulkanVersion {
@@ -296,88 +543,100 @@ impl From<(u32, u32)> for VulkanVersion {
 
 /// Appends the Vulkan surface extension names required for the specified target platforms.
 ///
-/// Given a set of target platforms, pushes the corresponding `ExtensionName` entries into
-/// `buffer` for each matching platform (or the generic `SURFACE` entry for `HEADLESS`).
-///
-/// # Parameters
-///
-/// - `targets`: Target platforms to inspect for required surface extensions.
-/// - `buffer`: Inline vector to receive the extension names; must have sufficient capacity.
-///
-/// # Returns
-///
-/// `Ok(())` on success, `Err(BufferOverflowError)` if `buffer` does not have enough capacity.
-///
-/// # Examples
-///
-/// ```
-/// use inline_vec::InlineVec;
-/// // types `TargetPlatform` and `ExtensionName` are assumed to be in scope for the example
-/// let targets = TargetPlatform::WIN32;
-/// let mut buf = InlineVec::<ExtensionName, 4>::new();
-/// let res = append_extension_names(&targets, &mut buf);
-/// assert!(res.is_ok());
-/// ```
+/// Pushes the corresponding `ExtensionName` entries into `buffer` for each matching platform,
+/// preceded by the generic `SURFACE` entry when any platform is set.
 #[inline(always)]
-fn append_extension_names<const CAPACITY: usize>(
-    targets: &TargetPlatform,
-    buffer: &mut InlineVec<ExtensionName, CAPACITY>,
-) -> Result<(), BufferOverflowError> {
+fn append_extension_names(targets: &TargetPlatform, buffer: &mut Vec<ExtensionName>) {
     if !targets.is_empty() {
-        buffer.push(ExtensionName::SURFACE)?;
+        buffer.push(ExtensionName::SURFACE);
     }
 
-    if targets.contains(TargetPlatform::WAYLAND) {
-        buffer.push(ExtensionName::WAYLAND_SURFACE)?;
-    }
-
-    if targets.contains(TargetPlatform::XCB) {
-        buffer.push(ExtensionName::XCB_SURFACE)?;
-    }
-
-    if targets.contains(TargetPlatform::XLIB) {
-        buffer.push(ExtensionName::XLIB_SURFACE)?;
-    }
-
-    if targets.contains(TargetPlatform::WIN32) {
-        buffer.push(ExtensionName::WIN32_SURFACE)?;
-    }
-
-    if targets.contains(TargetPlatform::ANDROID) {
-        buffer.push(ExtensionName::ANDROID_SURFACE)?;
+    for (target, extension) in [
+        (TargetPlatform::WAYLAND, ExtensionName::WAYLAND_SURFACE),
+        (TargetPlatform::XCB, ExtensionName::XCB_SURFACE),
+        (TargetPlatform::XLIB, ExtensionName::XLIB_SURFACE),
+        (TargetPlatform::WIN32, ExtensionName::WIN32_SURFACE),
+        (TargetPlatform::ANDROID, ExtensionName::ANDROID_SURFACE),
+        (TargetPlatform::METAL, ExtensionName::MACOS_SURFACE),
+    ] {
+        if targets.contains(target) {
+            buffer.push(extension);
+        }
     }
+}
 
-    if targets.contains(TargetPlatform::METAL) {
-        buffer.push(ExtensionName::MACOS_SURFACE)?;
+/// Picks the API version to request from the loader: `preferred`, capped by what the loader
+/// supports.
+///
+/// # Errors
+///
+/// Returns `UnsupportedApiVersion` if the loader supports less than `min`.
+fn negotiate_api_version(
+    min: VulkanVersion,
+    preferred: VulkanVersion,
+    available: VulkanVersion,
+) -> crate::Result<VulkanVersion> {
+    // Patch levels never affect compatibility.
+    let available = VulkanVersion::new(available.major, available.minor, 0);
+    let version = preferred.max(min).min(available);
+
+    if version < min {
+        return VulkanErrorKind::UnsupportedApiVersion {
+            required: min,
+            available,
+        }
+        .into_result();
     }
 
-    Ok(())
+    Ok(version)
 }
 
-fn select_available_layers<const N: usize>(
-    fns: &FnTable,
-    requested_layers: [LayerName; N],
-) -> Result<InlineVec<LayerName, N>, VulkanError> {
-    const CHUNK_SIZE: usize = 64;
-    let mut available_layers = InlineVec::<LayerName, N>::new();
-    let mut buf_props = LayerProperties::zeroized::<CHUNK_SIZE>();
-
-    let mut count = CHUNK_SIZE as u32;
+/// Picks the names to enable: every `required` name, then the `optional` names found in
+/// `available`, skipping duplicates. Missing optional names are logged and skipped.
+///
+/// # Errors
+///
+/// Returns the position in `required` of the first name missing from `available`.
+fn select_names<'n, L: Logger>(
+    logger: &L,
+    kind: &str,
+    available: &[&CStr],
+    required: impl IntoIterator<Item = &'n CStr>,
+    optional: impl IntoIterator<Item = &'n CStr>,
+) -> Result<Vec<&'n CStr>, usize> {
+    let mut selected = Vec::new();
+
+    for (index, name) in required.into_iter().enumerate() {
+        if !available.contains(&name) {
+            error!(
+                logger,
+                LogTarget::Backend,
+                "Required Vulkan {} not available: {:?}",
+                kind,
+                name
+            );
+
+            return Err(index);
+        }
 
-    unsafe {
-        fns.enumerate_instance_layer_properties(&mut count, buf_props.as_mut_ptr())?;
+        if !selected.contains(&name) {
+            selected.push(name);
+        }
     }
 
-    let actual_count = (count as usize).min(CHUNK_SIZE);
-
-    for property in &buf_props[..actual_count] {
-        for &requested in &requested_layers {
-            if requested == property.layer_name {
-                let _ = available_layers.push(requested);
-                break;
-            }
+    for name in optional {
+        if !available.contains(&name) {
+            info!(
+                logger,
+                LogTarget::Backend,
+                "Optional Vulkan {} not available: {:?}",
+                kind,
+                name
+            );
+        } else if !selected.contains(&name) {
+            selected.push(name);
         }
     }
 
-    Ok(available_layers)
+    Ok(selected)
 }
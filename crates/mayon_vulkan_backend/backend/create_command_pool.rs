@@ -0,0 +1,27 @@
+use allocator::Allocator;
+use mayon_core::{CreateCommandPool, QueueKind, logger::Logger};
+
+use crate::{Device, VulkanBackend, VulkanCommandPool, VulkanError};
+
+impl<'a, L, A> CreateCommandPool<L, A> for VulkanBackend<'a, L, A>
+where
+    L: Logger,
+    A: Allocator,
+{
+    type Error = VulkanError;
+    type CommandPool = VulkanCommandPool<'a, L, A>;
+
+    /// Creates a command pool for the device's `queue`, whose command buffers can be reset
+    /// individually.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingQueue` if the device has no graphics queue and `queue` is
+    /// [`QueueKind::Graphics`], or a `VulkanError` if `vkCreateCommandPool` fails.
+    fn create_command_pool(
+        device: &Device<'a, L, A>,
+        queue: QueueKind,
+    ) -> crate::Result<Self::CommandPool> {
+        VulkanCommandPool::new(device.clone(), queue)
+    }
+}
@@ -5,8 +5,7 @@ use mayon_core::{
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle, RawDisplayHandle, RawWindowHandle};
 
 use crate::{
-    VulkanContext, VulkanError,
-    fn_table::FnTable,
+    VulkanContext, VulkanError, VulkanErrorKind,
     types::{
         WaylandSurfaceCreateInfo, Win32SurfaceCreateInfo, XcbSurfaceCreateInfo,
         XlibSurfaceCreateInfo,
@@ -27,7 +26,12 @@ where
     where
         H: HasDisplayHandle + HasWindowHandle,
     {
-        let fns = FnTable::global()?;
+        if instance.backend().is_compute_only() {
+            return CreateContextErrorKind::BackendInternal(VulkanErrorKind::ComputeOnlyInstance)
+                .into_result();
+        }
+
+        let fns = instance.backend().fns();
 
         let (vk_instance, alloc_callbacks) = unsafe {
             let backend = instance.backend();
@@ -89,6 +93,6 @@ where
             surface
         );
 
-        Ok(VulkanContext { surface })
+        Ok(VulkanContext::new(surface))
     }
 }
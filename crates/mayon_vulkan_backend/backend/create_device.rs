@@ -0,0 +1,205 @@
+use std::{ptr, sync::Mutex};
+
+use allocator::{Allocator, BlockAllocator};
+use mayon_core::{
+    Adapter, CreateDevice, CreateDeviceError, CreateDeviceErrorKind, InstanceRef, debug, info,
+    logger::{Logger, Target as LogTarget},
+};
+
+use crate::{
+    VulkanAdapter, VulkanBackend, VulkanDevice, VulkanError, VulkanErrorKind,
+    device::{
+        DESCRIPTOR_INDEXING_VERSION, DYNAMIC_RENDERING_VERSION, QueueFamilies, RenderPassCache,
+        TIMELINE_SEMAPHORE_VERSION, VulkanQueue,
+    },
+    fn_table::DeviceFnTable,
+    types::{
+        DeviceCreateInfo, DeviceQueueCreateInfo, ExtensionName,
+        PhysicalDeviceDescriptorIndexingFeatures, PhysicalDeviceDynamicRenderingFeatures,
+        PhysicalDeviceFeatures2, PhysicalDeviceTimelineSemaphoreFeatures,
+    },
+};
+
+impl<L, A> CreateDevice<L, A> for VulkanBackend<'_, L, A>
+where
+    L: Logger,
+    A: Allocator,
+{
+    type Error = VulkanError;
+
+    /// Creates a logical device on `adapter` with one queue per selected queue family.
+    ///
+    /// Graphics, compute and transfer families are picked from the adapter's queue families,
+    /// preferring dedicated compute and transfer families; devices of compute-only instances
+    /// open no graphics queue. `VK_KHR_swapchain` is enabled when
+    /// the instance was created with surface extensions. Timeline semaphores are enabled when
+    /// both the instance and the adapter support Vulkan 1.2. Dynamic rendering is enabled from
+    /// Vulkan 1.3, or through `VK_KHR_dynamic_rendering` on Vulkan 1.2 adapters exposing it;
+    /// other devices render with cached render pass objects. The descriptor indexing features
+    /// of bindless tables are enabled on Vulkan 1.2 adapters supporting all of them. The
+    /// device is created with the instance's allocation callbacks, and its functions are
+    /// resolved through `vkGetDeviceProcAddr`. Resource memory is sub-allocated by a
+    /// `BlockAllocator` built from the adapter's memory types, heaps and buffer-image
+    /// granularity.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedAdapter` if the adapter has no compute-capable queue family, or a
+    /// `VulkanError` if the adapter's extensions cannot be enumerated, `vkCreateDevice` fails
+    /// or a device function cannot be resolved.
+    fn create_device(
+        instance: &InstanceRef<Self, L, A>,
+        adapter: &VulkanAdapter,
+    ) -> Result<Self::Device, CreateDeviceError<VulkanErrorKind>> {
+        let fns = instance.backend().fns();
+        let physical_device = adapter.physical_device;
+
+        let family_properties =
+            unsafe { fns.get_physical_device_queue_family_properties(physical_device) };
+
+        let Some(mut families) = QueueFamilies::select(&family_properties) else {
+            return CreateDeviceErrorKind::UnsupportedAdapter.into_result();
+        };
+
+        if instance.backend().is_compute_only() {
+            families.graphics = None;
+        }
+
+        let priorities = [1.0f32];
+
+        let queue_create_infos = families
+            .unique()
+            .into_iter()
+            .map(|family_index| DeviceQueueCreateInfo::new(family_index, &priorities))
+            .collect::<Vec<_>>();
+
+        let mut extensions = Vec::new();
+
+        if instance.backend().presentation() {
+            extensions.push(ExtensionName::SWAPCHAIN);
+        }
+
+        // Device-level functionality is capped by the version the instance was created with.
+        let api_version = adapter.api_version().min(instance.backend().api_version());
+
+        // The extension depends on functionality that is core in Vulkan 1.2, so older devices
+        // always fall back to render passes.
+        let dynamic_rendering = if api_version >= DYNAMIC_RENDERING_VERSION {
+            true
+        } else if api_version >= TIMELINE_SEMAPHORE_VERSION {
+            let available = unsafe { fns.enumerate_device_extension_properties(physical_device) }?;
+            let supported = available
+                .iter()
+                .any(|properties| properties.name() == ExtensionName::DYNAMIC_RENDERING.as_c_str());
+
+            if supported {
+                extensions.push(ExtensionName::DYNAMIC_RENDERING);
+            }
+
+            supported
+        } else {
+            false
+        };
+
+        let descriptor_indexing = api_version >= DESCRIPTOR_INDEXING_VERSION && {
+            let mut supported = PhysicalDeviceDescriptorIndexingFeatures::none();
+
+            unsafe {
+                fns.get_physical_device_features2(
+                    physical_device,
+                    &mut PhysicalDeviceFeatures2::new(&mut supported),
+                )
+            }?;
+
+            supported.supports_bindless()
+        };
+
+        let dynamic_rendering_features = PhysicalDeviceDynamicRenderingFeatures::enabled();
+        let mut descriptor_indexing_features = PhysicalDeviceDescriptorIndexingFeatures::bindless();
+        let mut timeline_semaphore_features = PhysicalDeviceTimelineSemaphoreFeatures::enabled();
+
+        // Optional features are chained behind the timeline semaphore features.
+        if dynamic_rendering {
+            timeline_semaphore_features.next =
+                ptr::from_ref(&dynamic_rendering_features).cast_mut().cast();
+        }
+
+        if descriptor_indexing {
+            descriptor_indexing_features.next = timeline_semaphore_features.next;
+            timeline_semaphore_features.next =
+                ptr::from_mut(&mut descriptor_indexing_features).cast();
+        }
+
+        let mut info = DeviceCreateInfo::new(&queue_create_infos, &extensions);
+
+        if api_version >= TIMELINE_SEMAPHORE_VERSION {
+            info = info.with_features(&timeline_semaphore_features);
+        }
+
+        let allocator = unsafe { instance.backend().allocator() };
+
+        let handle = unsafe { fns.create_device(physical_device, &info, allocator) }?;
+
+        let device_fns = unsafe {
+            DeviceFnTable::load(fns.get_device_proc_addr(), handle, allocator, &extensions)
+        }?;
+
+        let memory_properties =
+            unsafe { fns.get_physical_device_memory_properties(physical_device) };
+        let limits = unsafe { fns.get_physical_device_properties(physical_device) }.limits;
+
+        let get_queue = |family_index| VulkanQueue {
+            handle: unsafe { device_fns.get_device_queue(handle, family_index, 0) },
+            family_index,
+        };
+
+        let device = VulkanDevice {
+            handle,
+            physical_device,
+            api_version,
+            memory_allocator: BlockAllocator::new((&memory_properties).into())
+                .with_buffer_image_granularity(limits.buffer_image_granularity),
+            graphics_queue: families.graphics.map(get_queue),
+            compute_queue: get_queue(families.compute),
+            transfer_queue: get_queue(families.transfer),
+            fns: device_fns,
+            dynamic_rendering,
+            render_passes: RenderPassCache::new(),
+            descriptor_indexing,
+            queue_lock: Mutex::new(()),
+        };
+
+        info!(
+            instance.logger(),
+            LogTarget::Backend,
+            "Vulkan device created: {:?} on {} (Vulkan {}, {})",
+            device.handle,
+            adapter.name(),
+            api_version,
+            if dynamic_rendering {
+                "dynamic rendering"
+            } else {
+                "render passes"
+            }
+        );
+
+        for (usage, queue) in [
+            ("graphics", device.graphics_queue),
+            ("compute", Some(device.compute_queue)),
+            ("transfer", Some(device.transfer_queue)),
+        ] {
+            if let Some(queue) = queue {
+                debug!(
+                    instance.logger(),
+                    LogTarget::Backend,
+                    "Vulkan {} queue: {:?} (family {})",
+                    usage,
+                    queue.handle,
+                    queue.family_index
+                );
+            }
+        }
+
+        Ok(device)
+    }
+}
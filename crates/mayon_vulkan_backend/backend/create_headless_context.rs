@@ -0,0 +1,46 @@
+use allocator::Allocator;
+use mayon_core::{
+    CreateContextError, CreateContextErrorKind, CreateHeadlessContext, Format, InstanceRef, debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+use crate::{VulkanBackend, VulkanContext, VulkanError, VulkanErrorKind};
+
+impl<L, A> CreateHeadlessContext<L, A> for VulkanBackend<'_, L, A>
+where
+    L: Logger,
+    A: Allocator,
+{
+    type Error = VulkanError;
+
+    /// Creates a context that renders into offscreen images instead of a surface.
+    ///
+    /// No surface extension is needed, so this works on instances created without a target
+    /// platform. The images are allocated once the context is configured with a device.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ComputeOnlyInstance` if the instance was created for compute only.
+    fn create_headless_context(
+        instance: &InstanceRef<Self, L, A>,
+        width: u32,
+        height: u32,
+        format: Format,
+    ) -> Result<Self::Context, CreateContextError<VulkanErrorKind>> {
+        if instance.backend().is_compute_only() {
+            return CreateContextErrorKind::BackendInternal(VulkanErrorKind::ComputeOnlyInstance)
+                .into_result();
+        }
+
+        debug!(
+            instance.logger(),
+            LogTarget::Backend,
+            "Created headless context: {}x{}, {}",
+            width,
+            height,
+            format
+        );
+
+        Ok(VulkanContext::new_headless(width, height, format))
+    }
+}
@@ -0,0 +1,177 @@
+use core::ffi::{CStr, c_void};
+
+use mayon_core::logger::{Level, Logger, Target as LogTarget};
+
+use crate::types::{
+    DebugUtilsMessageSeverityFlags, DebugUtilsMessageTypeFlags, DebugUtilsMessengerCallbackData,
+    DebugUtilsMessengerCreateInfo,
+};
+
+/// Describes a messenger that forwards every debug-utils message to `logger`.
+///
+/// `logger` must stay at the same address for as long as the messenger exists.
+#[inline]
+pub(super) fn messenger_create_info<L: Logger>(logger: &L) -> DebugUtilsMessengerCreateInfo {
+    DebugUtilsMessengerCreateInfo::new(
+        debug_utils_messenger_callback::<L>,
+        (logger as *const L).cast_mut().cast(),
+    )
+}
+
+/// Logs a debug-utils message under [`LogTarget::Validation`].
+///
+/// Errors and warnings keep their level. Info messages, mostly loader chatter, are logged as
+/// `Debug`, and verbose ones as `Trace`.
+///
+/// Always returns `VK_FALSE`, so the call that triggered the message is not aborted.
+unsafe extern "system" fn debug_utils_messenger_callback<L: Logger>(
+    message_severity: DebugUtilsMessageSeverityFlags,
+    message_types: DebugUtilsMessageTypeFlags,
+    callback_data: *const DebugUtilsMessengerCallbackData,
+    user_data: *mut c_void,
+) -> u32 {
+    let logger = unsafe { &*user_data.cast_const().cast::<L>() };
+
+    let level = if message_severity.contains(DebugUtilsMessageSeverityFlags::ERROR) {
+        Level::Error
+    } else if message_severity.contains(DebugUtilsMessageSeverityFlags::WARNING) {
+        Level::Warn
+    } else if message_severity.contains(DebugUtilsMessageSeverityFlags::INFO) {
+        Level::Debug
+    } else {
+        Level::Trace
+    };
+
+    let kind = if message_types.contains(DebugUtilsMessageTypeFlags::VALIDATION) {
+        "validation"
+    } else if message_types.contains(DebugUtilsMessageTypeFlags::PERFORMANCE) {
+        "performance"
+    } else {
+        "general"
+    };
+
+    let message = unsafe { callback_data.as_ref() }
+        .filter(|data| !data.message.is_null())
+        .map(|data| unsafe { CStr::from_ptr(data.message) }.to_string_lossy());
+
+    logger.log(
+        level,
+        LogTarget::Validation,
+        format_args!("[{}] {}", kind, message.as_deref().unwrap_or_default()),
+    );
+
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{cell::RefCell, ffi::c_char, fmt::Arguments, ptr::null};
+
+    use super::*;
+    use crate::types::StructureType;
+
+    #[derive(Default)]
+    struct RecordingLogger(RefCell<Vec<(Level, LogTarget, String)>>);
+
+    impl Logger for RecordingLogger {
+        fn log(&self, level: Level, target: LogTarget, args: Arguments) {
+            self.0.borrow_mut().push((level, target, args.to_string()));
+        }
+    }
+
+    fn callback_data(message: *const c_char) -> DebugUtilsMessengerCallbackData {
+        DebugUtilsMessengerCallbackData {
+            struct_type: StructureType::DebugUtilsMessengerCallbackDataExt,
+            next: null(),
+            flags: 0,
+            message_id_name: null(),
+            message_id_number: 0,
+            message,
+            queue_label_count: 0,
+            queue_labels: null(),
+            cmd_buf_label_count: 0,
+            cmd_buf_labels: null(),
+            object_count: 0,
+            objects: null(),
+        }
+    }
+
+    #[test]
+    fn test_callback_logs_to_validation_target() {
+        let logger = RecordingLogger::default();
+        let info = messenger_create_info(&logger);
+
+        let messages = [
+            (
+                DebugUtilsMessageSeverityFlags::ERROR,
+                DebugUtilsMessageTypeFlags::VALIDATION,
+                c"Validation Error: [ VUID-vkDestroyDevice-device-05137 ]".as_ptr(),
+            ),
+            (
+                DebugUtilsMessageSeverityFlags::WARNING,
+                DebugUtilsMessageTypeFlags::PERFORMANCE,
+                c"Slow path".as_ptr(),
+            ),
+            (
+                DebugUtilsMessageSeverityFlags::INFO,
+                DebugUtilsMessageTypeFlags::GENERAL,
+                c"Loaded layer".as_ptr(),
+            ),
+            (
+                DebugUtilsMessageSeverityFlags::VERBOSE,
+                DebugUtilsMessageTypeFlags::GENERAL,
+                null(),
+            ),
+        ];
+
+        for (severity, types, message) in messages {
+            let data = callback_data(message);
+
+            let abort = unsafe { (info.user_callback)(severity, types, &data, info.user_data) };
+
+            assert_eq!(abort, 0);
+        }
+
+        assert_eq!(
+            logger.0.into_inner(),
+            [
+                (
+                    Level::Error,
+                    LogTarget::Validation,
+                    "[validation] Validation Error: [ VUID-vkDestroyDevice-device-05137 ]"
+                        .to_owned()
+                ),
+                (
+                    Level::Warn,
+                    LogTarget::Validation,
+                    "[performance] Slow path".to_owned()
+                ),
+                (
+                    Level::Debug,
+                    LogTarget::Validation,
+                    "[general] Loaded layer".to_owned()
+                ),
+                (Level::Trace, LogTarget::Validation, "[general] ".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_callback_without_data() {
+        let logger = RecordingLogger::default();
+
+        unsafe {
+            debug_utils_messenger_callback::<RecordingLogger>(
+                DebugUtilsMessageSeverityFlags::ERROR,
+                DebugUtilsMessageTypeFlags::GENERAL,
+                null(),
+                (&logger as *const RecordingLogger).cast_mut().cast(),
+            )
+        };
+
+        assert_eq!(
+            logger.0.into_inner(),
+            [(Level::Error, LogTarget::Validation, "[general] ".to_owned())]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use allocator::Allocator;
+use mayon_core::{
+    Adapter, EnumerateAdapters, InstanceRef, debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+use crate::{VulkanAdapter, VulkanBackend, VulkanError, device::QueueFamilies};
+
+impl<L, A> EnumerateAdapters<L, A> for VulkanBackend<'_, L, A>
+where
+    L: Logger,
+    A: Allocator,
+{
+    type Error = VulkanError;
+    type Adapter = VulkanAdapter;
+
+    /// Queries every physical device exposed by the Vulkan instance.
+    ///
+    /// Each device is described with its name, type, PCI identifiers, driver version and
+    /// supported API version. Compute-only instances skip devices without a compute-capable
+    /// queue family, as no device could be opened on them.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if the function table is unavailable or
+    /// `vkEnumeratePhysicalDevices` fails.
+    fn enumerate_adapters(
+        instance: &InstanceRef<Self, L, A>,
+    ) -> Result<Vec<Self::Adapter>, Self::Error> {
+        let fns = instance.backend().fns();
+        let vk_instance = instance.backend().instance();
+
+        let physical_devices = unsafe { fns.enumerate_physical_devices(vk_instance) }?;
+
+        let adapters = physical_devices
+            .into_iter()
+            .filter(|&physical_device| {
+                !instance.backend().is_compute_only() || {
+                    let families =
+                        unsafe { fns.get_physical_device_queue_family_properties(physical_device) };
+
+                    QueueFamilies::select(&families).is_some()
+                }
+            })
+            .map(|physical_device| {
+                let properties = unsafe { fns.get_physical_device_properties(physical_device) };
+
+                VulkanAdapter::new(physical_device, &properties)
+            })
+            .collect::<Vec<_>>();
+
+        for adapter in &adapters {
+            debug!(
+                instance.logger(),
+                LogTarget::Backend,
+                "Vulkan adapter found: {:?} {} ({}, Vulkan {}.{}.{})",
+                adapter.physical_device,
+                adapter.name(),
+                adapter.adapter_type(),
+                adapter.api_version().major,
+                adapter.api_version().minor,
+                adapter.api_version().patch
+            );
+        }
+
+        Ok(adapters)
+    }
+}
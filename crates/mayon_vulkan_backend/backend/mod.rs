@@ -1,12 +1,21 @@
 mod create;
+mod create_command_pool;
+#[cfg(feature = "windowing")]
 mod create_context;
+mod create_device;
+mod create_headless_context;
+mod debug_messenger;
+mod enumerate_adapters;
 
 use allocator::{Allocator, System};
 use mayon_core::logger::Logger;
 
 pub use create::{VulkanBackendParams, VulkanVersion};
 
-use crate::{fn_table::FnTable, types};
+use crate::{
+    fn_table::{Entry, FnTable},
+    types,
+};
 
 pub struct VulkanBackend<'a, L, A = System>
 where
@@ -14,7 +23,14 @@ where
     A: Allocator + 'static,
 {
     instance: types::Instance,
+    fns: FnTable,
+    debug_messenger: Option<types::DebugUtilsMessenger>,
+    api_version: VulkanVersion,
     alloc: types::AllocationCallbacks<'a, A>,
+    presentation: bool,
+    compute_only: bool,
+    // Declared last so the loader library is closed after the instance is destroyed.
+    _entry: Entry,
     _marker: std::marker::PhantomData<L>,
 }
 
@@ -28,6 +44,33 @@ where
         self.instance
     }
 
+    /// Instance-level functions of this backend's instance.
+    #[inline(always)]
+    pub(crate) fn fns(&self) -> &FnTable {
+        &self.fns
+    }
+
+    /// Vulkan API version negotiated for the instance.
+    ///
+    /// Devices may support less; see [`VulkanAdapter::api_version`](crate::VulkanAdapter::api_version).
+    #[inline(always)]
+    pub fn api_version(&self) -> VulkanVersion {
+        self.api_version
+    }
+
+    /// Whether the instance was created with surface extensions, allowing presentation.
+    #[inline(always)]
+    pub(crate) fn presentation(&self) -> bool {
+        self.presentation
+    }
+
+    /// Whether the instance was created for compute work only; see
+    /// [`VulkanBackendParams::compute_only`].
+    #[inline(always)]
+    pub fn is_compute_only(&self) -> bool {
+        self.compute_only
+    }
+
     #[inline(always)]
     pub(crate) unsafe fn allocator(&self) -> types::AllocationCallbacksRef<'a> {
         unsafe { self.alloc.alloc_ref() }
@@ -39,7 +82,8 @@ where
     L: Logger,
     A: Allocator + 'static,
 {
-    type Context = crate::VulkanContext;
+    type Context = crate::VulkanContext<'a, L, A>;
+    type Device = crate::VulkanDevice;
 }
 
 impl<'a, L, A> Drop for VulkanBackend<'a, L, A>
@@ -48,10 +92,446 @@ where
     A: Allocator + 'static,
 {
     fn drop(&mut self) {
-        let fns = FnTable::global().expect("Vulkan library is not loaded");
-
         unsafe {
-            fns.destroy_instance(self.instance, self.alloc.alloc_ref());
+            if let Some(messenger) = self.debug_messenger {
+                self.fns.destroy_debug_utils_messenger(
+                    self.instance,
+                    messenger,
+                    self.alloc.alloc_ref(),
+                );
+            }
+
+            self.fns
+                .destroy_instance(self.instance, self.alloc.alloc_ref());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{
+        ffi::{CStr, c_char, c_void},
+        mem::transmute,
+    };
+    use std::path::Path;
+
+    use mayon_core::{
+        BaseError, CreateBackendErrorKind, CreateContextErrorKind, Format, QueueKind,
+        TargetPlatform, logger::DefaultLogger,
+    };
+
+    use super::*;
+    use crate::{
+        Instance, VulkanErrorKind, VulkanFunctionName, VulkanLoader,
+        fn_table::{MockDriver, MockHandleKind, mock},
+    };
+
+    /// Resolves like the mock driver, except that `vkCreateDevice` is missing.
+    unsafe extern "system" fn without_create_device(
+        instance: *mut c_void,
+        name: *const c_char,
+    ) -> Option<unsafe extern "system" fn()> {
+        if unsafe { CStr::from_ptr(name) } == c"vkCreateDevice" {
+            return None;
+        }
+
+        unsafe { MockDriver::get_instance_proc_addr()(instance, name) }
+    }
+
+    /// Resolves like the mock driver, except that no function of a `KHR` extension exists, as
+    /// with a driver offering neither surfaces nor swapchains.
+    unsafe extern "system" fn without_khr_extensions(
+        instance: *mut c_void,
+        name: *const c_char,
+    ) -> Option<unsafe extern "system" fn()> {
+        let name = unsafe { CStr::from_ptr(name) };
+
+        if name == c"vkGetDeviceProcAddr" {
+            let get_device_proc_addr = device_without_khr_extensions as *const ();
+
+            return Some(unsafe {
+                transmute::<*const (), unsafe extern "system" fn()>(get_device_proc_addr)
+            });
+        }
+
+        if name.to_bytes().ends_with(b"KHR") {
+            return None;
+        }
+
+        unsafe { MockDriver::get_instance_proc_addr()(instance, name.as_ptr()) }
+    }
+
+    unsafe extern "system" fn device_without_khr_extensions(
+        device: types::Device,
+        name: *const c_char,
+    ) -> Option<unsafe extern "system" fn()> {
+        if unsafe { CStr::from_ptr(name) }.to_bytes().ends_with(b"KHR") {
+            return None;
         }
+
+        unsafe { mock::get_device_proc_addr(device, name) }
+    }
+
+    #[test]
+    fn test_missing_function_destroys_instance() {
+        MockDriver::reset();
+
+        let params = VulkanBackendParams::default()
+            .with_loader(VulkanLoader::GetInstanceProcAddr(without_create_device));
+
+        let Err(err) = Instance::new(params, DefaultLogger) else {
+            panic!("Instance creation should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            CreateBackendErrorKind::BackendInternal(VulkanErrorKind::FunctionLoadFailed {
+                name: VulkanFunctionName::CreateDevice
+            })
+        ));
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateInstance),
+            1
+        );
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_surfaceless_instance() {
+        let destroy_surface = |instance: &Instance| unsafe {
+            MockDriver::get_instance_proc_addr()(
+                instance.backend().instance.as_raw() as *mut c_void,
+                c"vkDestroySurfaceKHR".as_ptr(),
+            )
+        };
+
+        // Without `VK_KHR_surface` the loader resolves none of its functions.
+        let instance = mock::instance();
+
+        assert!(!instance.backend().presentation());
+        assert!(destroy_surface(&instance).is_none());
+
+        drop(instance);
+
+        let params = VulkanBackendParams {
+            target_platform: Some(TargetPlatform::XLIB),
+            ..mock::params()
+        };
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+
+        assert!(destroy_surface(&instance).is_some());
+
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_validation_messenger() {
+        MockDriver::reset();
+
+        let params = mock::params().with_validation(true);
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+
+        assert_eq!(
+            MockDriver::live_handles()
+                .iter()
+                .filter(|handle| handle.kind == MockHandleKind::DebugUtilsMessenger)
+                .count(),
+            1
+        );
+
+        drop(instance);
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyDebugUtilsMessenger),
+            1
+        );
+
+        MockDriver::reset();
+
+        let params = mock::params().with_validation(false);
+        drop(Instance::new(params, DefaultLogger).unwrap());
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateDebugUtilsMessenger),
+            0
+        );
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_required_layers_and_extensions() {
+        MockDriver::reset();
+
+        let layers = [c"VK_LAYER_KHRONOS_validation", c"VK_LAYER_MAYON_missing"];
+        let params = mock::params().with_required_layers(&layers);
+
+        let Err(err) = Instance::new(params, DefaultLogger) else {
+            panic!("Instance creation should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            CreateBackendErrorKind::BackendInternal(VulkanErrorKind::MissingLayer { index: 1 })
+        ));
+
+        let extensions = [c"VK_KHR_missing"];
+        let params = mock::params()
+            .with_validation(false)
+            .with_optional_layers(&layers)
+            .with_required_extensions(&extensions);
+
+        let Err(err) = Instance::new(params, DefaultLogger) else {
+            panic!("Instance creation should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            CreateBackendErrorKind::BackendInternal(VulkanErrorKind::MissingExtension { index: 0 })
+        ));
+
+        // Extensions provided by an enabled layer count as available.
+        let extensions = [c"VK_EXT_debug_utils", c"VK_KHR_surface"];
+        let params = mock::params()
+            .with_validation(false)
+            .with_optional_layers(&layers)
+            .with_required_extensions(&extensions)
+            .with_optional_extensions(&[c"VK_KHR_missing"]);
+
+        drop(Instance::new(params, DefaultLogger).unwrap());
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateDebugUtilsMessenger),
+            0
+        );
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_api_version_negotiation() {
+        let instance = mock::instance();
+
+        assert_eq!(
+            instance.backend().api_version(),
+            VulkanVersion::new(1, 3, 0)
+        );
+
+        let params = mock::params().with_api_version((1, 0), (1, 2));
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+
+        assert_eq!(
+            instance.backend().api_version(),
+            VulkanVersion::new(1, 2, 0)
+        );
+
+        let params = mock::params().with_api_version((1, 1), (1, 5));
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+
+        assert_eq!(
+            instance.backend().api_version(),
+            VulkanVersion::new(1, 3, 0)
+        );
+
+        let params = mock::params().with_api_version((1, 4), (1, 4));
+
+        let Err(err) = Instance::new(params, DefaultLogger) else {
+            panic!("Instance creation should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            CreateBackendErrorKind::BackendInternal(VulkanErrorKind::UnsupportedApiVersion {
+                required: VulkanVersion {
+                    major: 1,
+                    minor: 4,
+                    ..
+                },
+                available: VulkanVersion {
+                    major: 1,
+                    minor: 3,
+                    ..
+                },
+            })
+        ));
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateInstance),
+            3
+        );
+    }
+
+    #[test]
+    fn test_missing_library() {
+        let params = VulkanBackendParams::default()
+            .with_loader(VulkanLoader::Path(Path::new("/nonexistent/libvulkan.so")));
+
+        let Err(err) = Instance::new(params, DefaultLogger) else {
+            panic!("Instance creation should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            CreateBackendErrorKind::BackendInternal(VulkanErrorKind::LibraryLoad)
+        ));
+    }
+
+    #[test]
+    fn test_drop_destroys_instance() {
+        MockDriver::reset();
+
+        let params = mock::params().with_validation(false);
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+
+        assert_eq!(
+            MockDriver::live_handles()
+                .iter()
+                .map(|handle| handle.kind)
+                .collect::<Vec<_>>(),
+            [MockHandleKind::Instance]
+        );
+
+        drop(instance);
+
+        assert_eq!(
+            MockDriver::calls().last(),
+            Some(&VulkanFunctionName::DestroyInstance)
+        );
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_compute_only_instance() {
+        MockDriver::reset();
+
+        let params = mock::params().with_compute_only(true);
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+
+        assert!(instance.backend().is_compute_only());
+        assert!(!instance.backend().presentation());
+
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+
+        assert_eq!(device.graphics_queue_family(), None);
+        assert!(matches!(
+            device
+                .create_command_pool(QueueKind::Graphics)
+                .err()
+                .unwrap()
+                .kind(),
+            VulkanErrorKind::MissingQueue {
+                kind: QueueKind::Graphics
+            }
+        ));
+        drop(device.create_command_pool(QueueKind::Compute).unwrap());
+
+        assert!(matches!(
+            instance
+                .create_headless_context(64, 64, Format::Rgba8Unorm)
+                .err()
+                .unwrap()
+                .kind(),
+            CreateContextErrorKind::BackendInternal(VulkanErrorKind::ComputeOnlyInstance)
+        ));
+
+        drop(device);
+        drop(instance);
+
+        let params = VulkanBackendParams {
+            target_platform: Some(TargetPlatform::XLIB),
+            ..mock::params().with_compute_only(true)
+        };
+
+        let Err(err) = Instance::new(params, DefaultLogger) else {
+            panic!("Instance creation should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            CreateBackendErrorKind::UnsupportedTargetPlatform
+        ));
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateInstance),
+            1
+        );
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_compute_only_skips_adapters_without_compute() {
+        MockDriver::reset();
+        MockDriver::transfer_queue_only();
+
+        let instance = Instance::new(mock::params(), DefaultLogger).unwrap();
+
+        assert_eq!(instance.enumerate_adapters().unwrap().len(), 1);
+
+        drop(instance);
+
+        let params = mock::params().with_compute_only(true);
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+
+        assert!(instance.enumerate_adapters().unwrap().is_empty());
+        assert!(instance.select_adapter(&[]).unwrap().is_none());
+
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_compute_only_without_khr_extensions() {
+        MockDriver::reset();
+
+        let params = VulkanBackendParams::default()
+            .with_compute_only(true)
+            .with_loader(VulkanLoader::GetInstanceProcAddr(without_khr_extensions));
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+
+        drop(device.create_command_pool(QueueKind::Compute).unwrap());
+        drop(device);
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_drop_outlived_by_children() {
+        let instance = mock::instance();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+        let mut context = instance
+            .create_headless_context(64, 64, Format::Rgba8Unorm)
+            .unwrap();
+
+        context.configure(&device, 64, 64).unwrap();
+
+        assert_eq!(context.acquire_next_image().unwrap(), Some(0));
+
+        drop(instance);
+        drop(device);
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyInstance),
+            0,
+            "The context keeps the instance alive"
+        );
+
+        drop(context);
+
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::DestroyDevice), 1);
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyInstance),
+            1
+        );
+
+        MockDriver::assert_no_leaks();
     }
 }
@@ -0,0 +1,365 @@
+mod staging;
+
+use core::{
+    marker::PhantomData,
+    mem::size_of,
+    ops::BitOr,
+    ptr::{NonNull, copy_nonoverlapping},
+};
+
+use allocator::{AllocationRequest, Allocator, DeviceAllocation, MemoryUsage};
+use mayon_core::{
+    debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+pub use staging::StagingRing;
+
+use crate::{
+    Device, VulkanErrorKind,
+    types::{self, BufferCreateInfo, BufferUsageFlags, DeviceMemory},
+};
+
+/// What a [`Buffer`] can be used for. Usages combine with `|`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BufferUsage(u32);
+
+impl BufferUsage {
+    pub const VERTEX: Self = Self(BufferUsageFlags::VERTEX_BUFFER.0);
+    pub const INDEX: Self = Self(BufferUsageFlags::INDEX_BUFFER.0);
+    pub const UNIFORM: Self = Self(BufferUsageFlags::UNIFORM_BUFFER.0);
+    pub const STORAGE: Self = Self(BufferUsageFlags::STORAGE_BUFFER.0);
+    /// Source of the parameters of indirect commands, such as
+    /// [`dispatch_indirect`](crate::VulkanCommandBuffer::dispatch_indirect).
+    pub const INDIRECT: Self = Self(BufferUsageFlags::INDIRECT_BUFFER.0);
+    /// Source of transfer commands, such as the staging buffer of a [`StagingRing`].
+    pub const TRANSFER_SRC: Self = Self(BufferUsageFlags::TRANSFER_SRC.0);
+    /// Destination of transfer commands, such as uploads through a [`StagingRing`].
+    pub const TRANSFER_DST: Self = Self(BufferUsageFlags::TRANSFER_DST.0);
+
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline]
+    const fn flags(self) -> BufferUsageFlags {
+        BufferUsageFlags(self.0)
+    }
+}
+
+impl BitOr for BufferUsage {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// A buffer of `len` elements of type `T` in device memory.
+///
+/// The buffer is created with the device's queue families sharing it, so it can be used on
+/// any of the device's queues without ownership transfers. Memory is sub-allocated from the
+/// device's memory allocator according to the buffer's [`MemoryUsage`]. Host-visible memory
+/// stays mapped, and is accessed with [`write`](Self::write) and [`read`](Self::read);
+/// device-local memory is filled through a [`StagingRing`].
+///
+/// The buffer must not be dropped while a pending submission still uses it.
+pub struct Buffer<'a, T, L, A>
+where
+    T: Copy,
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    handle: types::Buffer,
+    memory: DeviceAllocation<DeviceMemory>,
+    len: usize,
+    usage: BufferUsage,
+    _marker: PhantomData<[T]>,
+}
+
+impl<'a, T, L, A> Buffer<'a, T, L, A>
+where
+    T: Copy,
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates a buffer for `len` elements of `T` on `device`, placed according to
+    /// `memory_usage`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EmptyBuffer` if the buffer would hold no byte, `NoSuitableMemoryType` if no
+    /// memory type fits `memory_usage`, or a `VulkanError` if creating the buffer, allocating
+    /// or binding its memory fails.
+    pub fn new(
+        device: &Device<'a, L, A>,
+        len: usize,
+        usage: BufferUsage,
+        memory_usage: MemoryUsage,
+    ) -> crate::Result<Self> {
+        let size = (size_of::<T>() as u64).saturating_mul(len as u64);
+
+        if size == 0 {
+            return VulkanErrorKind::EmptyBuffer.into_result();
+        }
+
+        let fns = &device.fns;
+        let queue_families = device.queue_families();
+        let info = BufferCreateInfo::new(size, usage.flags(), &queue_families);
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            let handle = fns.create_buffer(device.handle, &info, allocator)?;
+
+            let requirements = fns.get_buffer_memory_requirements(device.handle, handle);
+            let request = AllocationRequest::from(requirements).with_usage(memory_usage);
+
+            let memory = match device.allocate_memory(&request, allocator) {
+                Ok(memory) => memory,
+                Err(err) => {
+                    fns.destroy_buffer(device.handle, handle, allocator);
+
+                    return Err(err);
+                }
+            };
+
+            if let Err(err) =
+                fns.bind_buffer_memory(device.handle, handle, memory.memory(), memory.offset())
+            {
+                fns.destroy_buffer(device.handle, handle, allocator);
+                device.free_memory(&memory, allocator);
+
+                return Err(err);
+            }
+
+            debug!(
+                device.instance().logger(),
+                LogTarget::Backend,
+                "Vulkan buffer created: {:?}, {} bytes in memory type {}",
+                handle,
+                size,
+                memory.memory_type_index()
+            );
+
+            Ok(Self {
+                device: device.clone(),
+                handle,
+                memory,
+                len,
+                usage,
+                _marker: PhantomData,
+            })
+        }
+    }
+
+    /// Creates a host-visible buffer holding a copy of `data`, such as a uniform buffer
+    /// updated every frame.
+    ///
+    /// # Errors
+    ///
+    /// See [`new`](Self::new).
+    pub fn from_slice(
+        device: &Device<'a, L, A>,
+        usage: BufferUsage,
+        data: &[T],
+    ) -> crate::Result<Self> {
+        let mut buffer = Self::new(device, data.len(), usage, MemoryUsage::Upload)?;
+
+        buffer.write(0, data)?;
+
+        Ok(buffer)
+    }
+
+    /// The device the buffer was created on.
+    #[inline]
+    pub fn device(&self) -> &Device<'a, L, A> {
+        &self.device
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> types::Buffer {
+        self.handle
+    }
+
+    /// Number of elements in the buffer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always `false`: buffers hold at least one element.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Size of the buffer in bytes.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        (size_of::<T>() * self.len) as u64
+    }
+
+    #[inline]
+    pub fn usage(&self) -> BufferUsage {
+        self.usage
+    }
+
+    /// Whether the buffer's memory is mapped for [`write`](Self::write) and
+    /// [`read`](Self::read).
+    #[inline]
+    pub fn is_host_visible(&self) -> bool {
+        self.memory.mapped_ptr().is_some()
+    }
+
+    /// Host address of element `offset`, after checking that `count` elements starting there
+    /// are in bounds.
+    fn mapped_range(&self, offset: usize, count: usize) -> crate::Result<NonNull<u8>> {
+        let end = offset.saturating_add(count);
+
+        if end > self.len {
+            return VulkanErrorKind::BufferOutOfBounds { end, len: self.len }.into_result();
+        }
+
+        let Some(mapped) = self.memory.mapped_ptr() else {
+            return VulkanErrorKind::BufferNotHostVisible.into_result();
+        };
+
+        Ok(unsafe { mapped.add(offset * size_of::<T>()) })
+    }
+
+    /// Copies `data` into the buffer, starting at element `offset`.
+    ///
+    /// Memory is host coherent, so the write is visible to the device without flushing. It
+    /// must not overlap data a pending submission still reads.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BufferOutOfBounds` if `data` does not fit at `offset`, or
+    /// `BufferNotHostVisible` if the buffer's memory is not mapped.
+    pub fn write(&mut self, offset: usize, data: &[T]) -> crate::Result<()> {
+        let mapped = self.mapped_range(offset, data.len())?;
+
+        unsafe {
+            copy_nonoverlapping(
+                data.as_ptr().cast::<u8>(),
+                mapped.as_ptr(),
+                size_of_val(data),
+            )
+        };
+
+        Ok(())
+    }
+
+    /// Copies elements starting at element `offset` into `out`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BufferOutOfBounds` if `out` reaches past the end of the buffer, or
+    /// `BufferNotHostVisible` if the buffer's memory is not mapped.
+    ///
+    /// # Safety
+    ///
+    /// The bytes read must be valid values of `T`. Memory that was never written holds
+    /// unspecified bytes.
+    pub unsafe fn read(&self, offset: usize, out: &mut [T]) -> crate::Result<()> {
+        let mapped = self.mapped_range(offset, out.len())?;
+
+        unsafe {
+            copy_nonoverlapping(
+                mapped.as_ptr(),
+                out.as_mut_ptr().cast::<u8>(),
+                size_of_val(out),
+            )
+        };
+
+        Ok(())
+    }
+}
+
+impl<T, L, A> Drop for Buffer<'_, T, L, A>
+where
+    T: Copy,
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        unsafe {
+            let allocator = self.device.instance().backend().allocator();
+
+            self.device
+                .fns
+                .destroy_buffer(self.device.handle, self.handle, allocator);
+            self.device.free_memory(&self.memory, allocator);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::BaseError;
+
+    use super::*;
+    use crate::fn_table::{MockDriver, mock};
+
+    #[test]
+    fn test_host_visible_buffer() {
+        let device = mock::device();
+
+        let mut buffer =
+            Buffer::<u32, _, _>::from_slice(&device, BufferUsage::UNIFORM, &[1, 2, 3, 4]).unwrap();
+
+        assert!(buffer.is_host_visible());
+        assert_eq!(buffer.size(), 16);
+
+        buffer.write(2, &[7]).unwrap();
+
+        let mut out = [0; 3];
+
+        unsafe { buffer.read(1, &mut out) }.unwrap();
+
+        assert_eq!(out, [2, 7, 4]);
+
+        let Err(err) = buffer.write(3, &[0, 0]) else {
+            panic!("Writing past the end should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            VulkanErrorKind::BufferOutOfBounds { end: 5, len: 4 }
+        ));
+
+        let device_local = Buffer::<u32, _, _>::new(
+            &device,
+            4,
+            BufferUsage::VERTEX | BufferUsage::TRANSFER_DST,
+            MemoryUsage::GpuOnly,
+        )
+        .unwrap();
+
+        assert!(!device_local.is_host_visible());
+        assert!(matches!(
+            unsafe { device_local.read(0, &mut out) }
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::BufferNotHostVisible
+        ));
+
+        assert!(matches!(
+            Buffer::<u32, _, _>::new(&device, 0, BufferUsage::VERTEX, MemoryUsage::GpuOnly)
+                .err()
+                .unwrap()
+                .kind(),
+            VulkanErrorKind::EmptyBuffer
+        ));
+
+        drop(buffer);
+        drop(device_local);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
@@ -0,0 +1,759 @@
+use core::{mem::size_of, ptr::copy_nonoverlapping};
+use std::collections::VecDeque;
+
+use allocator::{Allocator, MemoryUsage};
+use mayon_core::{CommandBuffer, CommandBufferUsage, CommandPool, QueueKind, logger::Logger};
+
+use super::{Buffer, BufferUsage};
+use crate::{
+    Device, Texture, TextureDesc, TextureDimension, TextureUsage, TimelineSemaphore,
+    VulkanCommandPool, VulkanErrorKind,
+    texture::LayoutTransition,
+    types::{
+        self, BufferCopy, BufferImageCopy, Extent3D, ImageLayout, ImageSubresourceLayers, Offset3D,
+    },
+};
+
+/// Alignment of every staged region, so copies start at offsets drivers handle best.
+const STAGING_ALIGNMENT: usize = 16;
+
+/// A command queued by a [`StagingRing`] until its next flush.
+enum PendingCommand {
+    CopyBuffer {
+        dst: types::Buffer,
+        region: BufferCopy,
+    },
+    CopyImage {
+        dst: types::Image,
+        region: BufferImageCopy,
+    },
+    Transition(LayoutTransition),
+}
+
+impl PendingCommand {
+    /// Whether `self` and `other` can be recorded as a single command.
+    #[inline]
+    fn batches_with(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::CopyBuffer { dst: a, .. }, Self::CopyBuffer { dst: b, .. }) => a == b,
+            (Self::CopyImage { dst: a, .. }, Self::CopyImage { dst: b, .. }) => a == b,
+            (Self::Transition(_), Self::Transition(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+/// A host-visible ring buffer staging uploads into device-local [`Buffer`]s and
+/// [`Texture`]s.
+///
+/// Uploads are written into the ring and queued as copies. [`flush`](Self::flush) records
+/// every queued copy into one command buffer and submits it on the transfer queue. When the
+/// ring is full, queued copies are flushed and the oldest batch is waited for, so the ring
+/// reuses its memory without ever overwriting data still being copied.
+///
+/// Destinations must outlive the copies targeting them: until the batch is flushed and
+/// completed, see [`finish`](Self::finish). Uploads not flushed when the ring is dropped
+/// are discarded.
+pub struct StagingRing<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    // Dropped before the staging buffer: the pool waits for its pending submissions.
+    pool: VulkanCommandPool<'a, L, A>,
+    staging: Buffer<'a, u8, L, A>,
+    /// Offset of the next staged region.
+    head: usize,
+    /// Bytes of the ring in use by queued or in-flight copies, including the padding skipped
+    /// at alignment and wraparound.
+    used: usize,
+    /// Commands queued since the last flush, and the ring bytes they use.
+    pending: Vec<PendingCommand>,
+    pending_bytes: usize,
+    /// Submitted batches, oldest first, with the ring bytes they use.
+    in_flight: VecDeque<(u64, usize)>,
+}
+
+impl<'a, L, A> StagingRing<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates a staging ring of `capacity` bytes on `device`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EmptyBuffer` if `capacity` is `0`, or a `VulkanError` if creating the
+    /// command pool or the staging buffer fails.
+    pub fn new(device: &Device<'a, L, A>, capacity: usize) -> crate::Result<Self> {
+        let staging = Buffer::new(
+            device,
+            capacity,
+            BufferUsage::TRANSFER_SRC,
+            MemoryUsage::Upload,
+        )?;
+
+        Ok(Self {
+            pool: VulkanCommandPool::new(device.clone(), QueueKind::Transfer)?,
+            staging,
+            head: 0,
+            used: 0,
+            pending: Vec::new(),
+            pending_bytes: 0,
+            in_flight: VecDeque::new(),
+        })
+    }
+
+    /// Size of the ring in bytes.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.staging.len()
+    }
+
+    /// Creates a device-local buffer filled with `data`.
+    ///
+    /// The upload is only queued: the buffer holds `data` once the ring is flushed and the
+    /// transfer completed.
+    ///
+    /// # Errors
+    ///
+    /// See [`Buffer::new`] and [`upload`](Self::upload).
+    pub fn create_buffer<T: Copy>(
+        &mut self,
+        usage: BufferUsage,
+        data: &[T],
+    ) -> crate::Result<Buffer<'a, T, L, A>> {
+        let buffer = Buffer::new(
+            self.staging.device(),
+            data.len(),
+            usage | BufferUsage::TRANSFER_DST,
+            MemoryUsage::GpuOnly,
+        )?;
+
+        self.upload(&buffer, 0, data)?;
+
+        Ok(buffer)
+    }
+
+    /// Queues a copy of `data` into `dst`, starting at element `offset`.
+    ///
+    /// Data larger than the ring is split into several copies, flushing in between.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingBufferUsage` if `dst` was not created with
+    /// [`TRANSFER_DST`](BufferUsage::TRANSFER_DST), `BufferOutOfBounds` if `data` does not
+    /// fit at `offset`, or a `VulkanError` if flushing or waiting for the ring fails.
+    pub fn upload<T: Copy>(
+        &mut self,
+        dst: &Buffer<'a, T, L, A>,
+        offset: usize,
+        data: &[T],
+    ) -> crate::Result<()> {
+        if !dst.usage().contains(BufferUsage::TRANSFER_DST) {
+            return VulkanErrorKind::MissingBufferUsage {
+                usage: BufferUsage::TRANSFER_DST,
+            }
+            .into_result();
+        }
+
+        let end = offset.saturating_add(data.len());
+
+        if end > dst.len() {
+            return VulkanErrorKind::BufferOutOfBounds {
+                end,
+                len: dst.len(),
+            }
+            .into_result();
+        }
+
+        let bytes = data.as_ptr().cast::<u8>();
+        let size = size_of_val(data);
+        let dst_offset = offset * size_of::<T>();
+        let mut copied = 0;
+
+        while copied < size {
+            let chunk = (size - copied).min(self.capacity());
+            let start = self.reserve(chunk)?;
+
+            let Some(mapped) = self.staging.memory.mapped_ptr() else {
+                unreachable!("Staging memory is host visible");
+            };
+
+            unsafe { copy_nonoverlapping(bytes.add(copied), mapped.add(start).as_ptr(), chunk) };
+
+            self.pending.push(PendingCommand::CopyBuffer {
+                dst: dst.handle(),
+                region: BufferCopy {
+                    src_offset: start as u64,
+                    dst_offset: (dst_offset + copied) as u64,
+                    size: chunk as u64,
+                },
+            });
+
+            copied += chunk;
+        }
+
+        Ok(())
+    }
+
+    /// Creates a device-local texture and fills its first mip level with `data`.
+    ///
+    /// The texture can also be written by transfers, and read by them when it has several
+    /// mip levels so they can be filled with [`Texture::generate_mips`] once the upload
+    /// completed. The upload is only queued, as with [`upload_texture`](Self::upload_texture).
+    ///
+    /// # Errors
+    ///
+    /// See [`Texture::new`] and [`upload_texture`](Self::upload_texture).
+    pub fn create_texture(
+        &mut self,
+        desc: &TextureDesc,
+        data: &[u8],
+    ) -> crate::Result<Texture<'a, L, A>> {
+        let mut usage = desc.usage | TextureUsage::TRANSFER_DST;
+
+        if desc.mip_levels > 1 {
+            usage = usage | TextureUsage::TRANSFER_SRC;
+        }
+
+        let texture = Texture::new(self.staging.device(), &desc.with_usage(usage))?;
+
+        self.upload_texture(&texture, 0, data)?;
+
+        Ok(texture)
+    }
+
+    /// Queues a copy of `data` into mip level `mip_level` of `texture`, which is then left in
+    /// its resting layout.
+    ///
+    /// `data` holds tightly packed texels, row by row, for each depth slice of 3D textures or
+    /// each layer of array and cube textures in turn. Levels larger than the ring are split
+    /// into several copies, flushing in between.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingTextureUsage` if `texture` was not created with
+    /// [`TRANSFER_DST`](TextureUsage::TRANSFER_DST), `MipLevelOutOfRange` if the texture has
+    /// no level `mip_level`, `TextureDataSize` if `data` is not the size of the level,
+    /// `StagingRingTooSmall` if a single row of texels does not fit in the ring, or a
+    /// `VulkanError` if flushing or waiting for the ring fails.
+    pub fn upload_texture(
+        &mut self,
+        texture: &Texture<'a, L, A>,
+        mip_level: u32,
+        data: &[u8],
+    ) -> crate::Result<()> {
+        if !texture.usage().contains(TextureUsage::TRANSFER_DST) {
+            return VulkanErrorKind::MissingTextureUsage {
+                usage: TextureUsage::TRANSFER_DST,
+            }
+            .into_result();
+        }
+
+        if mip_level >= texture.mip_levels() {
+            return VulkanErrorKind::MipLevelOutOfRange {
+                level: mip_level,
+                count: texture.mip_levels(),
+            }
+            .into_result();
+        }
+
+        let expected = texture.level_size(mip_level);
+
+        if data.len() != expected {
+            return VulkanErrorKind::TextureDataSize {
+                expected,
+                actual: data.len(),
+            }
+            .into_result();
+        }
+
+        let extent = texture.level_extent(mip_level);
+        let row_size = extent.width as usize * texture.format().texel_size();
+
+        if row_size > self.capacity() {
+            return VulkanErrorKind::StagingRingTooSmall {
+                required: row_size,
+                capacity: self.capacity(),
+            }
+            .into_result();
+        }
+
+        let rows = extent.height as usize;
+        let slice_size = row_size * rows;
+        let is_volume = matches!(texture.dimension(), TextureDimension::D3 { .. });
+        let slice_count = if is_volume {
+            extent.depth as usize
+        } else {
+            texture.dimension().layers() as usize
+        };
+
+        let levels = mip_level..mip_level + 1;
+
+        self.pending.extend(
+            texture
+                .update_layouts(levels.clone(), ImageLayout::TRANSFER_DST_OPTIMAL)
+                .into_iter()
+                .map(PendingCommand::Transition),
+        );
+
+        // Whole slices are copied together while they fit in the ring, and rows of a single
+        // slice otherwise.
+        let (mut slice, mut row) = (0, 0);
+
+        while slice < slice_count {
+            let (slices, row_count) = if row == 0 && slice_size <= self.capacity() {
+                (
+                    (slice_count - slice).min(self.capacity() / slice_size),
+                    rows,
+                )
+            } else {
+                (1, (rows - row).min(self.capacity() / row_size))
+            };
+
+            let size = slices * row_count * row_size;
+            let start = self.reserve(size)?;
+            let copied = slice * slice_size + row * row_size;
+
+            let Some(mapped) = self.staging.memory.mapped_ptr() else {
+                unreachable!("Staging memory is host visible");
+            };
+
+            unsafe {
+                copy_nonoverlapping(data.as_ptr().add(copied), mapped.add(start).as_ptr(), size)
+            };
+
+            let (image_subresource, depth) = if is_volume {
+                (ImageSubresourceLayers::color(mip_level, 1), slices)
+            } else {
+                (
+                    ImageSubresourceLayers {
+                        base_array_layer: slice as u32,
+                        ..ImageSubresourceLayers::color(mip_level, slices as u32)
+                    },
+                    1,
+                )
+            };
+
+            self.pending.push(PendingCommand::CopyImage {
+                dst: texture.handle(),
+                region: BufferImageCopy {
+                    buffer_offset: start as u64,
+                    buffer_row_length: 0,
+                    buffer_image_height: 0,
+                    image_subresource,
+                    image_offset: Offset3D {
+                        x: 0,
+                        y: row as i32,
+                        z: if is_volume { slice as i32 } else { 0 },
+                    },
+                    image_extent: Extent3D {
+                        width: extent.width,
+                        height: row_count as u32,
+                        depth: depth as u32,
+                    },
+                },
+            });
+
+            row += row_count;
+
+            if row == rows {
+                slice += slices;
+                row = 0;
+            }
+        }
+
+        if let Some(layout) = texture.resting_layout() {
+            self.pending.extend(
+                texture
+                    .update_layouts(levels, layout)
+                    .into_iter()
+                    .map(PendingCommand::Transition),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Reserves `size` bytes of the ring, no more than its capacity, and returns their
+    /// offset.
+    fn reserve(&mut self, size: usize) -> crate::Result<usize> {
+        loop {
+            if self.used == 0 {
+                self.head = 0;
+            }
+
+            let aligned = self.head.next_multiple_of(STAGING_ALIGNMENT);
+            let start = if aligned + size <= self.capacity() {
+                aligned
+            } else {
+                0
+            };
+            let needed = if start == 0 && self.head != 0 {
+                self.capacity() - self.head + size
+            } else {
+                start - self.head + size
+            };
+
+            if self.used + needed <= self.capacity() {
+                self.head = start + size;
+                self.used += needed;
+                self.pending_bytes += needed;
+
+                return Ok(start);
+            }
+
+            if !self.pending.is_empty() {
+                self.flush()?;
+            }
+
+            let Some(&(serial, bytes)) = self.in_flight.front() else {
+                unreachable!("An idle ring fits any reservation");
+            };
+
+            self.pool.wait(serial)?;
+            self.in_flight.pop_front();
+            self.used -= bytes;
+        }
+    }
+
+    /// Submits every queued copy on the transfer queue.
+    ///
+    /// # Errors
+    ///
+    /// See [`flush_with`](Self::flush_with).
+    #[inline]
+    pub fn flush(&mut self) -> crate::Result<()> {
+        self.flush_with(&[])
+    }
+
+    /// Submits every queued copy on the transfer queue, signaling `signals` once the copies
+    /// completed, so queues using the destination buffers can wait for them.
+    ///
+    /// Does nothing if no copy is queued, apart from signaling.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if recording or submitting the copies fails. They stay queued
+    /// in that case.
+    pub fn flush_with(
+        &mut self,
+        signals: &[(&TimelineSemaphore<'a, L, A>, u64)],
+    ) -> crate::Result<()> {
+        if self.pending.is_empty() && signals.is_empty() {
+            return Ok(());
+        }
+
+        {
+            let mut command_buffer = self.pool.allocate()?;
+
+            command_buffer.begin(CommandBufferUsage::OneTimeSubmit)?;
+
+            let src = self.staging.handle();
+
+            for commands in self.pending.chunk_by(PendingCommand::batches_with) {
+                match commands[0] {
+                    PendingCommand::CopyBuffer { dst, .. } => {
+                        let regions = commands
+                            .iter()
+                            .filter_map(|command| match command {
+                                PendingCommand::CopyBuffer { region, .. } => Some(*region),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>();
+
+                        command_buffer.copy_buffer(src, dst, &regions)?;
+                    }
+                    PendingCommand::CopyImage { dst, .. } => {
+                        let regions = commands
+                            .iter()
+                            .filter_map(|command| match command {
+                                PendingCommand::CopyImage { region, .. } => Some(*region),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>();
+
+                        command_buffer.copy_buffer_to_image(
+                            src,
+                            (dst, ImageLayout::TRANSFER_DST_OPTIMAL),
+                            &regions,
+                        )?;
+                    }
+                    PendingCommand::Transition(_) => {
+                        let transitions = commands
+                            .iter()
+                            .filter_map(|command| match command {
+                                PendingCommand::Transition(transition) => Some(transition.clone()),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>();
+
+                        LayoutTransition::record(&mut command_buffer, &transitions)?;
+                    }
+                }
+            }
+
+            command_buffer.end()?;
+
+            self.pool.submit_with(&[&command_buffer], &[], signals)?;
+        }
+
+        self.in_flight
+            .push_back((self.pool.last_submission(), self.pending_bytes));
+        self.pending.clear();
+        self.pending_bytes = 0;
+
+        Ok(())
+    }
+
+    /// Submits every queued copy and waits until all uploads completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if flushing or waiting fails.
+    pub fn finish(&mut self) -> crate::Result<()> {
+        self.flush()?;
+
+        while let Some(&(serial, bytes)) = self.in_flight.front() {
+            self.pool.wait(serial)?;
+            self.in_flight.pop_front();
+            self.used -= bytes;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::{BaseError, Format};
+
+    use super::*;
+    use crate::{
+        VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+    };
+
+    /// Copies `src` into a readback buffer on the transfer queue, and reads it back.
+    fn read_back<L, A>(ring: &StagingRing<'_, L, A>, src: &Buffer<'_, u32, L, A>) -> Vec<u32>
+    where
+        L: Logger,
+        A: Allocator + 'static,
+    {
+        let readback = Buffer::<u32, _, _>::new(
+            src.device(),
+            src.len(),
+            BufferUsage::TRANSFER_DST,
+            MemoryUsage::Readback,
+        )
+        .unwrap();
+
+        let mut command_buffer = ring.pool.allocate().unwrap();
+
+        command_buffer
+            .begin(CommandBufferUsage::OneTimeSubmit)
+            .unwrap();
+        command_buffer
+            .copy_buffer(
+                src.handle(),
+                readback.handle(),
+                &[BufferCopy {
+                    src_offset: 0,
+                    dst_offset: 0,
+                    size: src.size(),
+                }],
+            )
+            .unwrap();
+        command_buffer.end().unwrap();
+        ring.pool.submit(&[&command_buffer]).unwrap();
+        ring.pool.wait(ring.pool.last_submission()).unwrap();
+
+        let mut out = vec![0; src.len()];
+
+        unsafe { readback.read(0, &mut out) }.unwrap();
+
+        out
+    }
+
+    #[test]
+    fn test_staged_upload() {
+        let device = mock::device();
+
+        let mut ring = StagingRing::new(&device, 1024).unwrap();
+
+        let vertices = ring
+            .create_buffer(BufferUsage::VERTEX, &[1u32, 2, 3, 4])
+            .unwrap();
+        ring.upload(&vertices, 2, &[8, 9]).unwrap();
+
+        let indices = ring
+            .create_buffer(BufferUsage::INDEX, &[5u32, 6, 7])
+            .unwrap();
+
+        assert!(!vertices.is_host_visible());
+
+        ring.finish().unwrap();
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::QueueSubmit),
+            1,
+            "Queued copies are batched into one submission"
+        );
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdCopyBuffer),
+            2,
+            "Consecutive copies into the same buffer share a command"
+        );
+
+        assert_eq!(read_back(&ring, &vertices), [1, 2, 8, 9]);
+        assert_eq!(read_back(&ring, &indices), [5, 6, 7]);
+
+        let Err(err) = ring.upload(&vertices, 3, &[0, 0]) else {
+            panic!("Uploading past the end should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            VulkanErrorKind::BufferOutOfBounds { end: 5, len: 4 }
+        ));
+
+        let uniforms =
+            Buffer::<u32, _, _>::from_slice(&device, BufferUsage::UNIFORM, &[0]).unwrap();
+
+        assert!(matches!(
+            ring.upload(&uniforms, 0, &[1]).unwrap_err().kind(),
+            VulkanErrorKind::MissingBufferUsage {
+                usage: BufferUsage::TRANSFER_DST
+            }
+        ));
+
+        drop(uniforms);
+        drop(vertices);
+        drop(indices);
+        drop(ring);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_ring_wraparound() {
+        let device = mock::device();
+
+        let mut ring = StagingRing::new(&device, 64).unwrap();
+
+        // Larger than the ring, so split into chunks flushed in between.
+        let data = (0..40).collect::<Vec<u32>>();
+        let large = ring.create_buffer(BufferUsage::STORAGE, &data).unwrap();
+
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::QueueSubmit), 2);
+
+        // Wraps around the end of the ring, waiting for earlier batches.
+        let small = ring
+            .create_buffer(BufferUsage::STORAGE, &[1u32, 2, 3, 4, 5, 6, 7, 8, 9, 10])
+            .unwrap();
+
+        ring.upload(&small, 0, &[11, 12, 13, 14, 15, 16, 17, 18])
+            .unwrap();
+        ring.finish().unwrap();
+
+        assert_eq!(read_back(&ring, &large), data);
+        assert_eq!(
+            read_back(&ring, &small),
+            [11, 12, 13, 14, 15, 16, 17, 18, 9, 10]
+        );
+
+        drop(large);
+        drop(small);
+        drop(ring);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_staged_texture_upload() {
+        let device = mock::device();
+
+        let mut ring = StagingRing::new(&device, 256).unwrap();
+
+        // 64 bytes per face: four faces fit in the ring, then the last two.
+        let cube_desc = TextureDesc::new(TextureDimension::Cube, Format::Rgba8Unorm, 4, 4)
+            .with_full_mip_chain();
+        let cube = ring.create_texture(&cube_desc, &[0; 6 * 64]).unwrap();
+
+        assert!(
+            cube.usage()
+                .contains(TextureUsage::TRANSFER_SRC | TextureUsage::TRANSFER_DST)
+        );
+        assert_eq!(cube.layout(0), ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+        assert_eq!(cube.layout(1), ImageLayout::UNDEFINED);
+
+        // 2 KiB with 128-byte rows: copied two rows at a time.
+        let tall = ring
+            .create_texture(
+                &TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 32, 16),
+                &[0; 2048],
+            )
+            .unwrap();
+        let volume = ring
+            .create_texture(
+                &TextureDesc::new(TextureDimension::D3 { depth: 4 }, Format::R8Unorm, 8, 8),
+                &[0; 256],
+            )
+            .unwrap();
+
+        ring.finish().unwrap();
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdCopyBufferToImage),
+            2 + 8 + 1
+        );
+
+        let wide = TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 128, 1);
+
+        assert!(matches!(
+            ring.create_texture(&wide, &[0; 512]).err().unwrap().kind(),
+            VulkanErrorKind::StagingRingTooSmall {
+                required: 512,
+                capacity: 256
+            }
+        ));
+        assert!(matches!(
+            ring.upload_texture(&cube, 0, &[0; 64]).unwrap_err().kind(),
+            VulkanErrorKind::TextureDataSize {
+                expected: 384,
+                actual: 64
+            }
+        ));
+        assert!(matches!(
+            ring.upload_texture(&cube, 3, &[]).unwrap_err().kind(),
+            VulkanErrorKind::MipLevelOutOfRange { level: 3, count: 3 }
+        ));
+
+        // Mips are generated on the graphics queue once the upload completed.
+        let graphics = device.create_command_pool(QueueKind::Graphics).unwrap();
+
+        {
+            let mut command_buffer = graphics.allocate().unwrap();
+
+            command_buffer
+                .begin(CommandBufferUsage::OneTimeSubmit)
+                .unwrap();
+            cube.generate_mips(&mut command_buffer).unwrap();
+            command_buffer.end().unwrap();
+        }
+
+        MockDriver::assert_valid_layouts();
+
+        drop(graphics);
+        drop(cube);
+        drop(tall);
+        drop(volume);
+        drop(ring);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
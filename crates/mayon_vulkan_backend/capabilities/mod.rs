@@ -0,0 +1,216 @@
+use std::ffi::{CStr, CString};
+
+use crate::{
+    VulkanLoader, VulkanVersion,
+    fn_table::Entry,
+    types::{ExtensionProperties, LayerProperties},
+};
+
+/// What the Vulkan implementation offers before any instance is created.
+///
+/// Use it to pick [`VulkanBackendParams`](crate::VulkanBackendParams) that the system can
+/// satisfy, for example to request an optional layer only when it is installed.
+#[derive(Clone, Debug)]
+pub struct VulkanCapabilities {
+    instance_version: VulkanVersion,
+    layers: Vec<VulkanLayer>,
+    extensions: Vec<VulkanExtension>,
+}
+
+/// An instance layer and the instance extensions it provides.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VulkanLayer {
+    name: CString,
+    spec_version: VulkanVersion,
+    implementation_version: u32,
+    description: String,
+    extensions: Vec<VulkanExtension>,
+}
+
+/// An instance extension.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VulkanExtension {
+    name: CString,
+    spec_version: u32,
+}
+
+impl VulkanCapabilities {
+    /// Loads Vulkan through `loader` and lists its instance version, layers and extensions.
+    ///
+    /// The library is unloaded again before returning.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LibraryLoad` or `FunctionLoadFailed` if Vulkan cannot be loaded, or a
+    /// `VulkanError` if an enumeration call fails.
+    pub fn query(loader: VulkanLoader) -> crate::Result<Self> {
+        let entry = Entry::load(loader)?;
+
+        let layers = entry
+            .enumerate_instance_layer_properties()?
+            .iter()
+            .map(|properties| {
+                let extensions =
+                    entry.enumerate_instance_extension_properties(Some(properties.name()))?;
+
+                Ok(VulkanLayer::new(properties, &extensions))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let extensions = entry
+            .enumerate_instance_extension_properties(None)?
+            .iter()
+            .map(VulkanExtension::new)
+            .collect();
+
+        Ok(Self {
+            instance_version: entry.enumerate_instance_version()?,
+            layers,
+            extensions,
+        })
+    }
+
+    /// Highest Vulkan version the loader supports for instances.
+    #[inline]
+    pub fn instance_version(&self) -> VulkanVersion {
+        self.instance_version
+    }
+
+    /// Installed instance layers.
+    #[inline]
+    pub fn layers(&self) -> &[VulkanLayer] {
+        &self.layers
+    }
+
+    /// Instance extensions provided by the implementation and implicitly enabled layers.
+    ///
+    /// Extensions that only come with an explicit layer are listed by
+    /// [`VulkanLayer::extensions`].
+    #[inline]
+    pub fn extensions(&self) -> &[VulkanExtension] {
+        &self.extensions
+    }
+
+    /// Whether the layer called `name` is installed.
+    #[inline]
+    pub fn has_layer(&self, name: &CStr) -> bool {
+        self.layers.iter().any(|layer| layer.name() == name)
+    }
+
+    /// Whether the instance extension called `name` is available without enabling a layer.
+    #[inline]
+    pub fn has_extension(&self, name: &CStr) -> bool {
+        self.extensions
+            .iter()
+            .any(|extension| extension.name() == name)
+    }
+}
+
+impl VulkanLayer {
+    fn new(properties: &LayerProperties, extensions: &[ExtensionProperties]) -> Self {
+        Self {
+            name: properties.name().to_owned(),
+            spec_version: VulkanVersion::from_raw(properties.spec_version),
+            implementation_version: properties.implementation_version,
+            description: unsafe { CStr::from_ptr(properties.description.as_ptr()) }
+                .to_string_lossy()
+                .into_owned(),
+            extensions: extensions.iter().map(VulkanExtension::new).collect(),
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    /// Vulkan version the layer was written against.
+    #[inline]
+    pub fn spec_version(&self) -> VulkanVersion {
+        self.spec_version
+    }
+
+    #[inline]
+    pub fn implementation_version(&self) -> u32 {
+        self.implementation_version
+    }
+
+    #[inline]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Instance extensions that become available when this layer is enabled.
+    #[inline]
+    pub fn extensions(&self) -> &[VulkanExtension] {
+        &self.extensions
+    }
+}
+
+impl VulkanExtension {
+    fn new(properties: &ExtensionProperties) -> Self {
+        Self {
+            name: properties.name().to_owned(),
+            spec_version: properties.spec_version,
+        }
+    }
+
+    #[inline]
+    pub fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    #[inline]
+    pub fn spec_version(&self) -> u32 {
+        self.spec_version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReturnCode, VulkanErrorKind, VulkanFunctionName, fn_table::MockDriver};
+
+    #[test]
+    fn test_query() {
+        MockDriver::reset();
+
+        let capabilities = VulkanCapabilities::query(VulkanLoader::Mock).unwrap();
+
+        assert_eq!(capabilities.instance_version(), VulkanVersion::new(1, 3, 0));
+        assert!(capabilities.has_layer(c"VK_LAYER_KHRONOS_validation"));
+        assert!(capabilities.has_extension(c"VK_KHR_surface"));
+        assert!(!capabilities.has_extension(c"VK_EXT_debug_utils"));
+        assert_eq!(
+            capabilities.layers()[0]
+                .extensions()
+                .iter()
+                .map(VulkanExtension::name)
+                .collect::<Vec<_>>(),
+            [c"VK_EXT_debug_utils"]
+        );
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateInstance),
+            0
+        );
+    }
+
+    #[test]
+    fn test_query_error() {
+        MockDriver::reset();
+        MockDriver::fail_next(
+            VulkanFunctionName::EnumerateInstanceExtensionProperties,
+            ReturnCode::HostMemory,
+        );
+
+        let err = VulkanCapabilities::query(VulkanLoader::Mock).unwrap_err();
+
+        assert!(matches!(
+            err.kind,
+            VulkanErrorKind::FunctionReturn {
+                name: VulkanFunctionName::EnumerateInstanceExtensionProperties,
+                code: ReturnCode::HostMemory,
+            }
+        ));
+    }
+}
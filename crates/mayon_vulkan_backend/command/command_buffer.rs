@@ -0,0 +1,486 @@
+use core::cell::Cell;
+
+use allocator::Allocator;
+use mayon_core::{CommandBuffer, CommandBufferState, CommandBufferUsage, logger::Logger};
+
+use super::VulkanCommandPool;
+use crate::{
+    VulkanError, VulkanErrorKind,
+    types::{
+        self, BufferCopy, BufferImageCopy, CommandBufferBeginInfo, CommandBufferResetFlags,
+        CommandBufferUsageFlags, Filter, ImageBlit, ImageLayout, ImageMemoryBarrier, MemoryBarrier,
+        PipelineBindPoint, PipelineStageFlags, RenderPassBeginInfo, RenderingInfo,
+        ShaderStageFlags,
+    },
+};
+
+/// A primary command buffer allocated from a [`VulkanCommandPool`].
+///
+/// Dropping the command buffer hands it back to its pool for reuse.
+pub struct VulkanCommandBuffer<'p, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    pool: &'p VulkanCommandPool<'a, L, A>,
+    pub(super) handle: types::CommandBuffer,
+    pub(super) state: Cell<CommandBufferState>,
+    pub(super) usage: Cell<CommandBufferUsage>,
+    /// Serial of the last submission this buffer was part of.
+    pub(super) submission: Cell<u64>,
+    /// Whether a dynamic rendering instance or render pass was begun and not yet ended.
+    rendering: Cell<bool>,
+    /// Whether a compute pipeline was bound since recording began.
+    compute_pipeline: Cell<bool>,
+}
+
+impl<'p, 'a, L, A> VulkanCommandBuffer<'p, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    #[inline]
+    pub(super) fn new(
+        pool: &'p VulkanCommandPool<'a, L, A>,
+        handle: types::CommandBuffer,
+        submission: u64,
+    ) -> Self {
+        Self {
+            pool,
+            handle,
+            state: Cell::new(CommandBufferState::Initial),
+            usage: Cell::new(CommandBufferUsage::OneTimeSubmit),
+            submission: Cell::new(submission),
+            rendering: Cell::new(false),
+            compute_pipeline: Cell::new(false),
+        }
+    }
+
+    /// The pool this command buffer was allocated from.
+    #[inline]
+    pub fn pool(&self) -> &'p VulkanCommandPool<'a, L, A> {
+        self.pool
+    }
+
+    /// Fails with `UnexpectedCommandBufferState` unless the buffer is recording.
+    #[inline]
+    pub(crate) fn ensure_recording(&self) -> crate::Result<()> {
+        let state = self.state.get();
+
+        if state != CommandBufferState::Recording {
+            return VulkanErrorKind::UnexpectedCommandBufferState { state }.into_result();
+        }
+
+        Ok(())
+    }
+
+    /// Whether rendering was begun and not yet ended.
+    #[inline]
+    pub(crate) fn is_rendering(&self) -> bool {
+        self.rendering.get()
+    }
+
+    /// Whether a compute pipeline was bound since recording began.
+    #[inline]
+    pub(crate) fn has_compute_pipeline(&self) -> bool {
+        self.compute_pipeline.get()
+    }
+
+    /// Begins dynamic rendering into the attachments of `info`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording, or
+    /// `FunctionLoadFailed` if the device does not support dynamic rendering.
+    pub(crate) fn begin_dynamic_rendering(&mut self, info: &RenderingInfo) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe { self.pool.device.fns.cmd_begin_rendering(self.handle, info) }?;
+
+        self.rendering.set(true);
+
+        Ok(())
+    }
+
+    /// Ends the dynamic rendering begun by [`Self::begin_dynamic_rendering`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording, or
+    /// `FunctionLoadFailed` if the device does not support dynamic rendering.
+    pub(crate) fn end_dynamic_rendering(&mut self) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe { self.pool.device.fns.cmd_end_rendering(self.handle) }?;
+
+        self.rendering.set(false);
+
+        Ok(())
+    }
+
+    /// Begins the render pass of `info`, recording its single subpass inline.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn begin_render_pass(&mut self, info: &RenderPassBeginInfo) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe {
+            self.pool
+                .device
+                .fns
+                .cmd_begin_render_pass(self.handle, info)
+        };
+
+        self.rendering.set(true);
+
+        Ok(())
+    }
+
+    /// Ends the render pass begun by [`Self::begin_render_pass`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn end_render_pass(&mut self) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe { self.pool.device.fns.cmd_end_render_pass(self.handle) };
+
+        self.rendering.set(false);
+
+        Ok(())
+    }
+
+    /// Records copies of byte ranges from `src` to `dst`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn copy_buffer(
+        &mut self,
+        src: types::Buffer,
+        dst: types::Buffer,
+        regions: &[BufferCopy],
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe {
+            self.pool
+                .device
+                .fns
+                .cmd_copy_buffer(self.handle, src, dst, regions)
+        };
+
+        Ok(())
+    }
+
+    /// Records copies of texels from `src` into `dst`, which is in `dst_layout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn copy_buffer_to_image(
+        &mut self,
+        src: types::Buffer,
+        (dst, dst_layout): (types::Image, ImageLayout),
+        regions: &[BufferImageCopy],
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe {
+            self.pool.device.fns.cmd_copy_buffer_to_image(
+                self.handle,
+                src,
+                dst,
+                dst_layout,
+                regions,
+            )
+        };
+
+        Ok(())
+    }
+
+    /// Records scaled copies between image regions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn blit_image(
+        &mut self,
+        src: (types::Image, ImageLayout),
+        dst: (types::Image, ImageLayout),
+        regions: &[ImageBlit],
+        filter: Filter,
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe {
+            self.pool
+                .device
+                .fns
+                .cmd_blit_image(self.handle, src, dst, regions, filter)
+        };
+
+        Ok(())
+    }
+
+    /// Records a pipeline barrier made of global memory barriers and image layout
+    /// transitions. Does nothing if both are empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn pipeline_barrier(
+        &mut self,
+        (src_stage, dst_stage): (PipelineStageFlags, PipelineStageFlags),
+        memory_barriers: &[MemoryBarrier],
+        image_barriers: &[ImageMemoryBarrier],
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        if !memory_barriers.is_empty() || !image_barriers.is_empty() {
+            unsafe {
+                self.pool.device.fns.cmd_pipeline_barrier(
+                    self.handle,
+                    src_stage,
+                    dst_stage,
+                    memory_barriers,
+                    image_barriers,
+                )
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Records the binding of `pipeline` to `bind_point`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn bind_pipeline(
+        &mut self,
+        bind_point: PipelineBindPoint,
+        pipeline: types::Pipeline,
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe {
+            self.pool
+                .device
+                .fns
+                .cmd_bind_pipeline(self.handle, bind_point, pipeline)
+        };
+
+        if bind_point == PipelineBindPoint::COMPUTE {
+            self.compute_pipeline.set(true);
+        }
+
+        Ok(())
+    }
+
+    /// Records the binding of `sets` to consecutive sets of `layout` from `first_set`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn bind_descriptor_sets(
+        &mut self,
+        bind_point: PipelineBindPoint,
+        (layout, first_set): (types::PipelineLayout, u32),
+        sets: &[types::DescriptorSet],
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe {
+            self.pool.device.fns.cmd_bind_descriptor_sets(
+                self.handle,
+                bind_point,
+                layout,
+                first_set,
+                sets,
+            )
+        };
+
+        Ok(())
+    }
+
+    /// Records an update of the push constants of `layout` at `offset` with `values`, for
+    /// `stages`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn push_constant_values<T: Copy>(
+        &mut self,
+        (layout, stages): (types::PipelineLayout, ShaderStageFlags),
+        offset: u32,
+        values: &T,
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe {
+            self.pool
+                .device
+                .fns
+                .cmd_push_constants(self.handle, layout, stages, offset, values)
+        };
+
+        Ok(())
+    }
+
+    /// Records a dispatch of `workgroups` workgroups of the bound compute pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn dispatch_workgroups(&mut self, workgroups: [u32; 3]) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe { self.pool.device.fns.cmd_dispatch(self.handle, workgroups) };
+
+        Ok(())
+    }
+
+    /// Records a dispatch of the bound compute pipeline, reading the workgroup counts from
+    /// `buffer` at `offset` bytes when executed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording.
+    pub(crate) fn dispatch_workgroups_indirect(
+        &mut self,
+        buffer: types::Buffer,
+        offset: u64,
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        unsafe {
+            self.pool
+                .device
+                .fns
+                .cmd_dispatch_indirect(self.handle, buffer, offset)
+        };
+
+        Ok(())
+    }
+}
+
+impl<L, A> CommandBuffer for VulkanCommandBuffer<'_, '_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    type Error = VulkanError;
+
+    #[inline]
+    fn state(&self) -> CommandBufferState {
+        self.state.get()
+    }
+
+    /// Starts recording. The pool allows implicit resets, so any previous recording is
+    /// discarded.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is already recording, or a
+    /// `VulkanError` if waiting for its last submission or `vkBeginCommandBuffer` fails.
+    fn begin(&mut self, usage: CommandBufferUsage) -> crate::Result<()> {
+        let state = self.state.get();
+
+        if state == CommandBufferState::Recording {
+            return VulkanErrorKind::UnexpectedCommandBufferState { state }.into_result();
+        }
+
+        self.pool.wait(self.submission.get())?;
+
+        let flags = match usage {
+            CommandBufferUsage::OneTimeSubmit => CommandBufferUsageFlags::ONE_TIME_SUBMIT,
+            CommandBufferUsage::Reusable => CommandBufferUsageFlags::EMPTY,
+        };
+
+        unsafe {
+            self.pool
+                .device
+                .fns
+                .begin_command_buffer(self.handle, &CommandBufferBeginInfo::new(flags))
+        }?;
+
+        self.state.set(CommandBufferState::Recording);
+        self.usage.set(usage);
+        self.rendering.set(false);
+        self.compute_pipeline.set(false);
+
+        Ok(())
+    }
+
+    /// Ends recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording,
+    /// `RenderingInProgress` if rendering was begun and not ended, or a `VulkanError` if
+    /// `vkEndCommandBuffer` fails.
+    fn end(&mut self) -> crate::Result<()> {
+        let state = self.state.get();
+
+        if state != CommandBufferState::Recording {
+            return VulkanErrorKind::UnexpectedCommandBufferState { state }.into_result();
+        }
+
+        if self.rendering.get() {
+            return VulkanErrorKind::RenderingInProgress.into_result();
+        }
+
+        unsafe { self.pool.device.fns.end_command_buffer(self.handle) }?;
+
+        self.state.set(CommandBufferState::Executable);
+
+        Ok(())
+    }
+
+    /// Discards recorded commands.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if waiting for the last submission or `vkResetCommandBuffer`
+    /// fails.
+    fn reset(&mut self) -> crate::Result<()> {
+        self.pool.wait(self.submission.get())?;
+
+        unsafe {
+            self.pool
+                .device
+                .fns
+                .reset_command_buffer(self.handle, CommandBufferResetFlags::EMPTY)
+        }?;
+
+        self.state.set(CommandBufferState::Initial);
+        self.rendering.set(false);
+        self.compute_pipeline.set(false);
+
+        Ok(())
+    }
+}
+
+impl<L, A> Drop for VulkanCommandBuffer<'_, '_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        // A buffer cannot be begun while recording, so end the recording before recycling it.
+        if self.state.get() == CommandBufferState::Recording {
+            let _ = unsafe {
+                self.pool
+                    .device
+                    .fns
+                    .reset_command_buffer(self.handle, CommandBufferResetFlags::EMPTY)
+            };
+        }
+
+        self.pool.recycle(self.handle, self.submission.get());
+    }
+}
@@ -0,0 +1,600 @@
+mod command_buffer;
+
+use core::{
+    cell::{Cell, RefCell},
+    ptr,
+};
+
+use allocator::Allocator;
+use mayon_core::{
+    CommandBufferState, CommandBufferUsage, CommandPool, QueueKind, debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+pub use command_buffer::VulkanCommandBuffer;
+
+use crate::{
+    Device, TimelineSemaphore, VulkanError, VulkanErrorKind,
+    device::VulkanQueue,
+    types::{
+        self, CommandBufferAllocateInfo, CommandBufferLevel, CommandPoolCreateFlags,
+        CommandPoolCreateInfo, CommandPoolResetFlags, Fence, FenceCreateFlags, FenceCreateInfo,
+        PipelineStageFlags, SubmitInfo, TimelineSemaphoreSubmitInfo,
+    },
+};
+
+/// A command pool recording for one of the device's queues.
+///
+/// The pool is `Send` but not `Sync` when the logger and allocator are `Send + Sync`:
+/// recording threads each create their own pool. Every submission is tracked with a fence, so
+/// command buffers are never begun, reset or recycled while the device may still be executing
+/// them.
+pub struct VulkanCommandPool<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    handle: types::CommandPool,
+    queue: VulkanQueue,
+    kind: QueueKind,
+    /// Command buffers whose owners were dropped, with the submission they may still be part of.
+    free_buffers: RefCell<Vec<(types::CommandBuffer, u64)>>,
+    /// Submissions not yet observed to be complete.
+    submissions: RefCell<Vec<Submission>>,
+    /// Unsignaled fences ready for the next submission.
+    free_fences: RefCell<Vec<Fence>>,
+    /// Serial of the previous submission. Serial `0` stands for "never submitted".
+    last_serial: Cell<u64>,
+}
+
+#[derive(Clone, Copy)]
+struct Submission {
+    serial: u64,
+    fence: Fence,
+}
+
+impl<'a, L, A> VulkanCommandPool<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    pub(crate) fn new(device: Device<'a, L, A>, kind: QueueKind) -> crate::Result<Self> {
+        let Some(queue) = device.queue(kind) else {
+            return VulkanErrorKind::MissingQueue { kind }.into_result();
+        };
+
+        let info = CommandPoolCreateInfo::new(
+            CommandPoolCreateFlags::RESET_COMMAND_BUFFER,
+            queue.family_index,
+        );
+
+        let handle = unsafe {
+            device.fns.create_command_pool(
+                device.handle,
+                &info,
+                device.instance().backend().allocator(),
+            )
+        }?;
+
+        debug!(
+            device.instance().logger(),
+            LogTarget::Backend,
+            "Vulkan command pool created: {:?} for the {} queue (family {})",
+            handle,
+            kind,
+            queue.family_index
+        );
+
+        Ok(Self {
+            device,
+            handle,
+            queue,
+            kind,
+            free_buffers: RefCell::new(Vec::new()),
+            submissions: RefCell::new(Vec::new()),
+            free_fences: RefCell::new(Vec::new()),
+            last_serial: Cell::new(0),
+        })
+    }
+
+    #[inline]
+    pub(crate) fn device(&self) -> &Device<'a, L, A> {
+        &self.device
+    }
+
+    /// Blocks until submission `serial` completed. Returns immediately if it already did.
+    pub(crate) fn wait(&self, serial: u64) -> crate::Result<()> {
+        let mut submissions = self.submissions.borrow_mut();
+
+        let Some(index) = submissions
+            .iter()
+            .position(|submission| submission.serial == serial)
+        else {
+            return Ok(());
+        };
+
+        unsafe {
+            self.device.fns.wait_for_fences(
+                self.device.handle,
+                &[submissions[index].fence],
+                true,
+                u64::MAX,
+            )
+        }?;
+
+        self.retire(&mut submissions, index)
+    }
+
+    /// Forgets a completed submission, recycling its fence.
+    ///
+    /// If the fence cannot be reset, the submission is kept so the fence is destroyed with
+    /// the pool.
+    fn retire(&self, submissions: &mut Vec<Submission>, index: usize) -> crate::Result<()> {
+        let fence = submissions[index].fence;
+
+        unsafe { self.device.fns.reset_fences(self.device.handle, &[fence]) }?;
+
+        submissions.swap_remove(index);
+        self.free_fences.borrow_mut().push(fence);
+
+        Ok(())
+    }
+
+    /// Returns an unsignaled fence, recycling the fences of completed submissions first.
+    fn acquire_fence(&self) -> crate::Result<Fence> {
+        {
+            let mut submissions = self.submissions.borrow_mut();
+            let mut index = 0;
+
+            while index < submissions.len() {
+                let completed = unsafe {
+                    self.device
+                        .fns
+                        .get_fence_status(self.device.handle, submissions[index].fence)
+                }?;
+
+                if completed {
+                    self.retire(&mut submissions, index)?;
+                } else {
+                    index += 1;
+                }
+            }
+        }
+
+        if let Some(fence) = self.free_fences.borrow_mut().pop() {
+            return Ok(fence);
+        }
+
+        unsafe {
+            self.device.fns.create_fence(
+                self.device.handle,
+                &FenceCreateInfo::new(FenceCreateFlags::EMPTY),
+                self.device.instance().backend().allocator(),
+            )
+        }
+    }
+
+    /// Submits `command_buffers` to the pool's queue in a single batch, synchronized with
+    /// timeline semaphores.
+    ///
+    /// The batch starts executing once every semaphore in `waits` reached its value, and each
+    /// semaphore in `signals` is set to its value once the batch completed. Values signaled
+    /// must be greater than the semaphore's current and pending values.
+    ///
+    /// A command buffer that is still executing from an earlier submission is waited for
+    /// first, as Vulkan does not allow it to be pending twice. One-time-submit buffers become
+    /// [`Invalid`](CommandBufferState::Invalid) until recorded again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ForeignCommandBuffer` if a buffer was allocated from another pool,
+    /// `UnexpectedCommandBufferState` if a buffer is not executable, or a `VulkanError` if
+    /// `vkQueueSubmit` fails.
+    pub fn submit_with<'p>(
+        &'p self,
+        command_buffers: &[&VulkanCommandBuffer<'p, 'a, L, A>],
+        waits: &[(&TimelineSemaphore<'a, L, A>, u64)],
+        signals: &[(&TimelineSemaphore<'a, L, A>, u64)],
+    ) -> crate::Result<()> {
+        let handles = self.prepare_submission(command_buffers)?;
+
+        let (wait_semaphores, wait_values) = split_timeline_points(waits);
+        let (signal_semaphores, signal_values) = split_timeline_points(signals);
+        let wait_stages = vec![PipelineStageFlags::ALL_COMMANDS; waits.len()];
+        let timeline_values = TimelineSemaphoreSubmitInfo::new(&wait_values, &signal_values);
+
+        let mut submit_info = SubmitInfo::new(&handles);
+
+        if !waits.is_empty() || !signals.is_empty() {
+            submit_info = submit_info
+                .with_wait_semaphores(&wait_semaphores, &wait_stages)
+                .with_signal_semaphores(&signal_semaphores)
+                .with_timeline_values(&timeline_values);
+        }
+
+        self.finish_submission(command_buffers, submit_info)
+    }
+
+    /// Submits the command buffer of a frame, waiting on and signaling binary semaphores.
+    ///
+    /// `wait` delays the given stages until its semaphore is signaled, as image acquisition
+    /// does; `signal` is signaled once the command buffer completed, for presentation.
+    ///
+    /// # Errors
+    ///
+    /// See [`submit_with`](Self::submit_with).
+    pub(crate) fn submit_frame<'p>(
+        &'p self,
+        command_buffer: &VulkanCommandBuffer<'p, 'a, L, A>,
+        wait: Option<(types::Semaphore, PipelineStageFlags)>,
+        signal: Option<types::Semaphore>,
+    ) -> crate::Result<()> {
+        let command_buffers = [command_buffer];
+        let handles = self.prepare_submission(&command_buffers)?;
+
+        let (wait_semaphores, wait_stages): (Vec<_>, Vec<_>) = wait.into_iter().unzip();
+        let signal_semaphores = signal.into_iter().collect::<Vec<_>>();
+
+        let submit_info = SubmitInfo::new(&handles)
+            .with_wait_semaphores(&wait_semaphores, &wait_stages)
+            .with_signal_semaphores(&signal_semaphores);
+
+        self.finish_submission(&command_buffers, submit_info)
+    }
+
+    /// Checks `command_buffers` can be submitted from this pool and waits for those still
+    /// executing, returning their handles.
+    fn prepare_submission(
+        &self,
+        command_buffers: &[&VulkanCommandBuffer<'_, 'a, L, A>],
+    ) -> crate::Result<Vec<types::CommandBuffer>> {
+        for command_buffer in command_buffers {
+            if !ptr::eq(command_buffer.pool(), self) {
+                return VulkanErrorKind::ForeignCommandBuffer.into_result();
+            }
+
+            let state = command_buffer.state.get();
+
+            if state != CommandBufferState::Executable {
+                return VulkanErrorKind::UnexpectedCommandBufferState { state }.into_result();
+            }
+        }
+
+        for command_buffer in command_buffers {
+            self.wait(command_buffer.submission.get())?;
+        }
+
+        Ok(command_buffers
+            .iter()
+            .map(|command_buffer| command_buffer.handle)
+            .collect())
+    }
+
+    /// Submits `submit_info`, the batch of `command_buffers`, and tracks it with a fence.
+    fn finish_submission(
+        &self,
+        command_buffers: &[&VulkanCommandBuffer<'_, 'a, L, A>],
+        submit_info: SubmitInfo<'_>,
+    ) -> crate::Result<()> {
+        let fence = self.acquire_fence()?;
+
+        let result = {
+            let _queues = self.device.lock_queues();
+
+            unsafe {
+                self.device
+                    .fns
+                    .queue_submit(self.queue.handle, &[submit_info], Some(fence))
+            }
+        };
+
+        if let Err(err) = result {
+            self.free_fences.borrow_mut().push(fence);
+
+            return Err(err);
+        }
+
+        let serial = self.last_serial.get() + 1;
+
+        self.last_serial.set(serial);
+        self.submissions
+            .borrow_mut()
+            .push(Submission { serial, fence });
+
+        for command_buffer in command_buffers {
+            command_buffer.submission.set(serial);
+
+            if command_buffer.usage.get() == CommandBufferUsage::OneTimeSubmit {
+                command_buffer.state.set(CommandBufferState::Invalid);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serial of the latest submission, or `0` if nothing was submitted yet.
+    #[inline]
+    pub(crate) fn last_submission(&self) -> u64 {
+        self.last_serial.get()
+    }
+
+    /// Takes back a command buffer whose owner was dropped.
+    #[inline]
+    fn recycle(&self, handle: types::CommandBuffer, submission: u64) {
+        self.free_buffers.borrow_mut().push((handle, submission));
+    }
+}
+
+impl<'a, L, A> CommandPool for VulkanCommandPool<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    type Error = VulkanError;
+
+    type CommandBuffer<'p>
+        = VulkanCommandBuffer<'p, 'a, L, A>
+    where
+        Self: 'p;
+
+    #[inline]
+    fn queue(&self) -> QueueKind {
+        self.kind
+    }
+
+    /// Allocates a primary command buffer, reusing one whose owner was dropped if possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkAllocateCommandBuffers` fails.
+    fn allocate(&self) -> crate::Result<Self::CommandBuffer<'_>> {
+        let recycled = self.free_buffers.borrow_mut().pop();
+
+        let (handle, submission) = match recycled {
+            Some(recycled) => recycled,
+            None => {
+                let info =
+                    CommandBufferAllocateInfo::new(self.handle, CommandBufferLevel::PRIMARY, 1);
+
+                let handle = unsafe {
+                    self.device
+                        .fns
+                        .allocate_command_buffer(self.device.handle, &info)
+                }?;
+
+                (handle, 0)
+            }
+        };
+
+        Ok(VulkanCommandBuffer::new(self, handle, submission))
+    }
+
+    /// Waits for every submission from this pool to complete, then resets the pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if waiting for the submissions or `vkResetCommandPool` fails.
+    fn reset(&mut self) -> crate::Result<()> {
+        let fns = &self.device.fns;
+        let submissions = self.submissions.get_mut();
+
+        if !submissions.is_empty() {
+            let fences = submissions
+                .iter()
+                .map(|submission| submission.fence)
+                .collect::<Vec<_>>();
+
+            unsafe {
+                fns.wait_for_fences(self.device.handle, &fences, true, u64::MAX)?;
+                fns.reset_fences(self.device.handle, &fences)?;
+            }
+
+            submissions.clear();
+            self.free_fences.get_mut().extend(fences);
+        }
+
+        unsafe {
+            fns.reset_command_pool(
+                self.device.handle,
+                self.handle,
+                CommandPoolResetFlags::EMPTY,
+            )
+        }
+    }
+
+    /// Submits `command_buffers` to the pool's queue in a single batch.
+    ///
+    /// Equivalent to [`submit_with`](Self::submit_with) without semaphores.
+    ///
+    /// # Errors
+    ///
+    /// See [`submit_with`](Self::submit_with).
+    #[inline]
+    fn submit<'p>(&'p self, command_buffers: &[&Self::CommandBuffer<'p>]) -> crate::Result<()> {
+        self.submit_with(command_buffers, &[], &[])
+    }
+}
+
+/// Splits `(semaphore, value)` pairs into the parallel arrays Vulkan takes.
+fn split_timeline_points<L, A>(
+    points: &[(&TimelineSemaphore<'_, L, A>, u64)],
+) -> (Vec<types::Semaphore>, Vec<u64>)
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    points
+        .iter()
+        .map(|&(semaphore, value)| (semaphore.handle(), value))
+        .unzip()
+}
+
+impl<L, A> Drop for VulkanCommandPool<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        let fns = &self.device.fns;
+        let device = &self.device;
+
+        let pending = self
+            .submissions
+            .get_mut()
+            .iter()
+            .map(|submission| submission.fence)
+            .collect::<Vec<_>>();
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            if !pending.is_empty() {
+                // Nothing useful can be done if the device is lost at this point; destroy anyway.
+                let _ = fns.wait_for_fences(device.handle, &pending, true, u64::MAX);
+            }
+
+            fns.destroy_command_pool(device.handle, self.handle, allocator);
+
+            for fence in pending
+                .into_iter()
+                .chain(self.free_fences.get_mut().drain(..))
+            {
+                fns.destroy_fence(device.handle, fence, allocator);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::{BaseError, CommandBuffer, logger::DefaultLogger};
+
+    use super::*;
+    use crate::{
+        ReturnCode, VulkanFunctionName,
+        fn_table::{MockDriver, MockHandleKind, mock},
+    };
+
+    #[test]
+    fn test_pool_is_send() {
+        fn assert_send<T: Send>() {}
+
+        assert_send::<VulkanCommandPool<'static, DefaultLogger, allocator::System>>();
+        assert_send::<crate::DescriptorAllocator<'static, DefaultLogger, allocator::System>>();
+    }
+
+    #[test]
+    fn test_record_and_submit() {
+        let device = mock::device();
+
+        let mut pool = device.create_command_pool(QueueKind::Graphics).unwrap();
+
+        assert_eq!(pool.queue(), QueueKind::Graphics);
+
+        {
+            let mut command_buffer = pool.allocate().unwrap();
+
+            assert_eq!(command_buffer.state(), CommandBufferState::Initial);
+
+            let Err(err) = pool.submit(&[&command_buffer]) else {
+                panic!("Submitting an unrecorded command buffer should fail");
+            };
+
+            assert!(matches!(
+                err.kind(),
+                VulkanErrorKind::UnexpectedCommandBufferState {
+                    state: CommandBufferState::Initial
+                }
+            ));
+
+            command_buffer.begin(CommandBufferUsage::Reusable).unwrap();
+            command_buffer.end().unwrap();
+            pool.submit(&[&command_buffer]).unwrap();
+
+            assert_eq!(command_buffer.state(), CommandBufferState::Executable);
+
+            // Still pending, so it is waited for before the second submission.
+            pool.submit(&[&command_buffer]).unwrap();
+
+            assert_eq!(MockDriver::call_count(VulkanFunctionName::QueueSubmit), 2);
+            assert_eq!(MockDriver::call_count(VulkanFunctionName::WaitForFences), 1);
+
+            command_buffer
+                .begin(CommandBufferUsage::OneTimeSubmit)
+                .unwrap();
+            command_buffer.end().unwrap();
+            pool.submit(&[&command_buffer]).unwrap();
+
+            assert_eq!(command_buffer.state(), CommandBufferState::Invalid);
+        }
+
+        // The dropped command buffer is handed out again.
+        drop(pool.allocate().unwrap());
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::AllocateCommandBuffers),
+            1
+        );
+
+        pool.reset().unwrap();
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::ResetCommandPool),
+            1
+        );
+
+        drop(pool);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_foreign_command_buffer() {
+        let device = mock::device();
+
+        let graphics = device.create_command_pool(QueueKind::Graphics).unwrap();
+        let transfer = device.create_command_pool(QueueKind::Transfer).unwrap();
+
+        let mut command_buffer = transfer.allocate().unwrap();
+
+        command_buffer.begin(CommandBufferUsage::default()).unwrap();
+        command_buffer.end().unwrap();
+
+        let Err(err) = graphics.submit(&[&command_buffer]) else {
+            panic!("Submitting to another pool's queue should fail");
+        };
+
+        assert!(matches!(err.kind(), VulkanErrorKind::ForeignCommandBuffer));
+
+        MockDriver::fail_next(VulkanFunctionName::QueueSubmit, ReturnCode::DeviceLost);
+
+        let Err(err) = transfer.submit(&[&command_buffer]) else {
+            panic!("Submission should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            VulkanErrorKind::FunctionReturn {
+                name: VulkanFunctionName::QueueSubmit,
+                code: ReturnCode::DeviceLost,
+            }
+        ));
+        assert_eq!(command_buffer.state(), CommandBufferState::Executable);
+
+        drop(command_buffer);
+
+        assert_eq!(
+            MockDriver::live_handles()
+                .iter()
+                .filter(|handle| handle.kind == MockHandleKind::CommandPool)
+                .count(),
+            2
+        );
+
+        drop((graphics, transfer, device));
+
+        MockDriver::assert_no_leaks();
+    }
+}
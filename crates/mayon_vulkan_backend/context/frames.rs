@@ -0,0 +1,437 @@
+use core::cell::{Cell, RefCell};
+
+use allocator::Allocator;
+use mayon_core::{
+    CommandBuffer, CommandBufferUsage, CommandPool, QueueKind, debug, error,
+    logger::{Logger, Target as LogTarget},
+};
+
+use super::{image::TargetImage, swapchain::Swapchain};
+use crate::{
+    Device, RenderGraph, Texture, TextureView, TransientResources, VulkanCommandBuffer,
+    VulkanCommandPool,
+    types::{
+        AccessFlags, ImageLayout, ImageMemoryBarrier, ImageSubresourceRange, PipelineStageFlags,
+        Semaphore, SemaphoreCreateInfo,
+    },
+};
+
+/// Number of frames in flight used unless configured otherwise.
+pub(super) const DEFAULT_FRAMES_IN_FLIGHT: usize = 2;
+
+/// Resources of one frame in flight.
+struct FrameSlot<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Signaled when the acquired swapchain image can be rendered to. Replaced when a frame
+    /// fails to submit, as nothing waits on the signal then.
+    acquire_semaphore: Cell<Semaphore>,
+    /// Records the frame's commands. Its last submission is the one beginning the slot again
+    /// waits for.
+    command_pool: VulkanCommandPool<'a, L, A>,
+    /// Transient textures of the render graphs recorded into the last submission.
+    transients: RefCell<Vec<TransientResources<'a, L, A>>>,
+}
+
+/// How the image of a frame is handed over once rendering finished.
+pub(super) enum FrameTarget<'c, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// The image is presented by `swapchain`, after `present_semaphore` is signaled.
+    Present {
+        swapchain: &'c Swapchain<'a, L, A>,
+        present_semaphore: Semaphore,
+    },
+    /// The image stays on the device for reading back.
+    Offscreen,
+}
+
+/// Paces rendering so the CPU records at most a fixed number of frames ahead of the GPU.
+///
+/// Each frame owns a semaphore for image acquisition and a graphics command pool. Frames are
+/// used round-robin; beginning a frame waits for its previous submission to complete.
+pub(super) struct Frames<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    slots: Vec<FrameSlot<'a, L, A>>,
+    current: Cell<usize>,
+}
+
+impl<'a, L, A> Frames<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates `count` frames submitting to the graphics queue of `device`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingQueue` if the device has no graphics queue, or a `VulkanError` if
+    /// creating a semaphore or command pool fails.
+    pub(super) fn new(device: Device<'a, L, A>, count: usize) -> crate::Result<Self> {
+        let mut frames = Self {
+            device,
+            slots: Vec::with_capacity(count),
+            current: Cell::new(0),
+        };
+
+        for _ in 0..count {
+            match frames.create_slot() {
+                Ok(slot) => frames.slots.push(slot),
+                Err(err) => {
+                    frames.destroy();
+
+                    return Err(err);
+                }
+            }
+        }
+
+        debug!(
+            frames.device.instance().logger(),
+            LogTarget::Backend,
+            "Vulkan frames in flight: {}",
+            count
+        );
+
+        Ok(frames)
+    }
+
+    fn create_slot(&self) -> crate::Result<FrameSlot<'a, L, A>> {
+        let fns = &self.device.fns;
+        let device = self.device.handle;
+
+        unsafe {
+            let allocator = self.device.instance().backend().allocator();
+
+            let acquire_semaphore =
+                fns.create_semaphore(device, &SemaphoreCreateInfo::new(), allocator)?;
+
+            match VulkanCommandPool::new(self.device.clone(), QueueKind::Graphics) {
+                Ok(command_pool) => Ok(FrameSlot {
+                    acquire_semaphore: Cell::new(acquire_semaphore),
+                    command_pool,
+                    transients: RefCell::new(Vec::new()),
+                }),
+                Err(err) => {
+                    fns.destroy_semaphore(device, acquire_semaphore, allocator);
+
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn slot(&self) -> &FrameSlot<'a, L, A> {
+        &self.slots[self.current.get()]
+    }
+
+    /// Waits until the current frame's previous submission completed.
+    ///
+    /// Returns the semaphore to signal when acquiring the frame's image.
+    pub(super) fn wait(&self) -> crate::Result<Semaphore> {
+        let slot = self.slot();
+
+        slot.command_pool
+            .wait(slot.command_pool.last_submission())?;
+        slot.transients.borrow_mut().clear();
+
+        Ok(slot.acquire_semaphore.get())
+    }
+
+    /// Index of the frame the next [`wait`](Self::wait) is for.
+    #[cfg(test)]
+    pub(super) fn current(&self) -> usize {
+        self.current.get()
+    }
+
+    /// Starts recording the current frame into `image`, which is moved to the color
+    /// attachment layout.
+    ///
+    /// Must follow a [`wait`](Self::wait) for the same frame.
+    pub(super) fn begin<'c>(
+        &'c self,
+        image: &'c TargetImage<'a, L, A>,
+        image_index: u32,
+        target: FrameTarget<'c, 'a, L, A>,
+    ) -> crate::Result<VulkanFrame<'c, 'a, L, A>> {
+        Ok(VulkanFrame {
+            frames: self,
+            command_buffer: Some(self.record_begin(image)?),
+            transients: Vec::new(),
+            image,
+            image_index,
+            target,
+        })
+    }
+
+    /// Allocates the current frame's command buffer and records the transition of `image` to
+    /// the color attachment layout.
+    fn record_begin<'c>(
+        &'c self,
+        image: &TargetImage<'a, L, A>,
+    ) -> crate::Result<VulkanCommandBuffer<'c, 'a, L, A>> {
+        let mut command_buffer = self.slot().command_pool.allocate()?;
+
+        command_buffer.begin(CommandBufferUsage::OneTimeSubmit)?;
+
+        image
+            .texture()
+            .update_layouts(0..1, ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+
+        // The previous contents are not needed; the acquire semaphore is waited on at the
+        // color attachment output stage, so the transition happens after it.
+        command_buffer.pipeline_barrier(
+            (
+                PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+            ),
+            &[],
+            &[ImageMemoryBarrier::new(
+                image.texture().handle(),
+                ImageSubresourceRange::COLOR,
+                (AccessFlags::EMPTY, ImageLayout::UNDEFINED),
+                (
+                    AccessFlags::COLOR_ATTACHMENT_WRITE,
+                    ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                ),
+            )],
+        )?;
+
+        Ok(command_buffer)
+    }
+
+    /// Finishes recording `command_buffer` and submits it, then moves on to the next frame.
+    ///
+    /// `image` is transitioned for presentation or, for offscreen targets, for transfer reads.
+    /// Presented frames wait on their acquire semaphore and signal the present semaphore.
+    /// `transients` are kept until the frame is waited for again.
+    ///
+    /// If the frame cannot be submitted, the next frame reuses the current one's resources;
+    /// a presented frame's acquire semaphore is replaced first, as it may be left signaled.
+    fn end(
+        &self,
+        mut command_buffer: VulkanCommandBuffer<'_, 'a, L, A>,
+        transients: Vec<TransientResources<'a, L, A>>,
+        image: &TargetImage<'a, L, A>,
+        target: &FrameTarget<'_, 'a, L, A>,
+    ) -> crate::Result<()> {
+        let slot = self.slot();
+
+        slot.transients.borrow_mut().extend(transients);
+
+        let (new_layout, wait, signal) = match target {
+            FrameTarget::Present {
+                present_semaphore, ..
+            } => (
+                ImageLayout::PRESENT_SRC,
+                Some((
+                    slot.acquire_semaphore.get(),
+                    PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                )),
+                Some(*present_semaphore),
+            ),
+            FrameTarget::Offscreen => (ImageLayout::TRANSFER_SRC_OPTIMAL, None, None),
+        };
+
+        let submitted = image
+            .texture()
+            .transition(&mut command_buffer, 0..1, new_layout)
+            .and_then(|()| command_buffer.end())
+            .and_then(|()| {
+                slot.command_pool
+                    .submit_frame(&command_buffer, wait, signal)
+            });
+
+        match submitted {
+            Ok(()) => {
+                self.current
+                    .set((self.current.get() + 1) % self.slots.len());
+            }
+            Err(_) if wait.is_some() => self.replace_acquire_semaphore(slot),
+            Err(_) => {}
+        }
+
+        submitted
+    }
+
+    /// Replaces the acquire semaphore of `slot`, whose signal no submission waits on.
+    ///
+    /// The old semaphore is kept if creating its replacement fails.
+    fn replace_acquire_semaphore(&self, slot: &FrameSlot<'a, L, A>) {
+        let fns = &self.device.fns;
+        let device = &self.device;
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            // The presentation engine may still be signaling the semaphore.
+            let _ = fns.device_wait_idle(device.handle);
+
+            match fns.create_semaphore(device.handle, &SemaphoreCreateInfo::new(), allocator) {
+                Ok(semaphore) => {
+                    fns.destroy_semaphore(device.handle, slot.acquire_semaphore.get(), allocator);
+                    slot.acquire_semaphore.set(semaphore);
+                }
+                Err(err) => error!(
+                    device.instance().logger(),
+                    LogTarget::Backend,
+                    "Failed to replace a frame's acquire semaphore: {}",
+                    err
+                ),
+            }
+        }
+    }
+
+    pub(super) fn destroy(&self) {
+        let fns = &self.device.fns;
+        let device = &self.device;
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            let _ = fns.device_wait_idle(device.handle);
+
+            // The command pools are destroyed once the frames are dropped.
+            for slot in &self.slots {
+                fns.destroy_semaphore(device.handle, slot.acquire_semaphore.get(), allocator);
+            }
+        }
+    }
+}
+
+/// A frame being recorded, started by [`VulkanContext::begin_frame`].
+///
+/// The frame's command buffer is recording and its image is in the color attachment layout.
+/// [`end`](Self::end) submits the commands and presents the image. Dropping an unfinished
+/// frame does the same, but can only log errors; call `end` to handle them.
+///
+/// [`VulkanContext::begin_frame`]: crate::VulkanContext::begin_frame
+pub struct VulkanFrame<'c, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    frames: &'c Frames<'a, L, A>,
+    command_buffer: Option<VulkanCommandBuffer<'c, 'a, L, A>>,
+    transients: Vec<TransientResources<'a, L, A>>,
+    image: &'c TargetImage<'a, L, A>,
+    image_index: u32,
+    target: FrameTarget<'c, 'a, L, A>,
+}
+
+impl<'c, 'a, L, A> VulkanFrame<'c, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// The command buffer the frame's commands are recorded into.
+    #[inline]
+    pub fn command_buffer(&mut self) -> &mut VulkanCommandBuffer<'c, 'a, L, A> {
+        self.command_buffer
+            .as_mut()
+            .expect("the command buffer is only taken when the frame ends")
+    }
+
+    /// The image the frame renders into.
+    #[inline]
+    pub fn texture(&self) -> &'c Texture<'a, L, A> {
+        self.image.texture()
+    }
+
+    /// A view of the frame's image, for use as a color attachment.
+    #[inline]
+    pub fn view(&self) -> TextureView<'c, 'a, L, A> {
+        self.image.view()
+    }
+
+    /// Records `graph` into the frame's command buffer.
+    ///
+    /// The graph may import the frame's [texture](Self::texture) to render into it. Its
+    /// transient textures are kept alive until the GPU finished the frame.
+    ///
+    /// # Errors
+    ///
+    /// See [`RenderGraph::execute`]. As the command buffer may then reference destroyed
+    /// textures, the commands recorded into the frame so far are discarded, and the frame
+    /// starts over with a new command buffer.
+    pub fn execute_graph(&mut self, graph: RenderGraph<'_, 'a, L, A>) -> crate::Result<()> {
+        match graph.execute(self.command_buffer()) {
+            Ok(transients) => {
+                self.transients.push(transients);
+
+                Ok(())
+            }
+            Err(err) => {
+                // Dropping the command buffer resets it and hands it back to the pool.
+                self.command_buffer = None;
+                self.command_buffer = Some(self.frames.record_begin(self.image)?);
+
+                Err(err)
+            }
+        }
+    }
+
+    /// Index of the frame's image within the render target.
+    #[inline]
+    pub fn image_index(&self) -> u32 {
+        self.image_index
+    }
+
+    /// Submits the frame's commands and presents its image.
+    ///
+    /// Headless contexts leave the image in the transfer source layout instead of presenting
+    /// it. An out-of-date swapchain is not an error; it is recreated when the next frame
+    /// begins.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if rendering is still in progress, or a
+    /// `VulkanError` if submission or presentation fails.
+    pub fn end(mut self) -> crate::Result<()> {
+        self.finish()
+    }
+
+    fn finish(&mut self) -> crate::Result<()> {
+        let Some(command_buffer) = self.command_buffer.take() else {
+            return Ok(());
+        };
+
+        let transients = core::mem::take(&mut self.transients);
+
+        self.frames
+            .end(command_buffer, transients, self.image, &self.target)?;
+
+        match &self.target {
+            FrameTarget::Present {
+                swapchain,
+                present_semaphore,
+            } => swapchain.present(self.image_index, &[*present_semaphore]),
+            FrameTarget::Offscreen => Ok(()),
+        }
+    }
+}
+
+impl<L, A> Drop for VulkanFrame<'_, '_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        if let Err(err) = self.finish() {
+            error!(
+                self.frames.device.instance().logger(),
+                LogTarget::Backend,
+                "Failed to end a dropped frame: {}",
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use allocator::Allocator;
+use mayon_core::logger::Logger;
+
+use crate::{Texture, TextureView, types::ImageView};
+
+/// An image of a render target, with the view frames render into.
+///
+/// The view is kept for the image's lifetime, so framebuffers cached for it survive from one
+/// frame to the next.
+pub(super) struct TargetImage<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    texture: Texture<'a, L, A>,
+    view: ImageView,
+}
+
+impl<'a, L, A> TargetImage<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates the view of `texture`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkCreateImageView` fails.
+    pub(super) fn new(texture: Texture<'a, L, A>) -> crate::Result<Self> {
+        let view = TextureView::new(&texture)?.into_handle();
+
+        Ok(Self { texture, view })
+    }
+
+    #[inline]
+    pub(super) fn texture(&self) -> &Texture<'a, L, A> {
+        &self.texture
+    }
+
+    #[inline]
+    pub(super) fn view(&self) -> TextureView<'_, 'a, L, A> {
+        TextureView::borrowed(&self.texture, self.view)
+    }
+
+    /// Destroys the view. The texture goes away once the image is dropped.
+    pub(super) fn destroy(&self) {
+        unsafe { TextureView::destroy_handle(self.texture.device(), self.view) };
+    }
+}
@@ -1,27 +1,566 @@
+mod frames;
+mod image;
+mod offscreen;
+mod swapchain;
+
 use allocator::Allocator;
-use mayon_core::{ContextHandler, DestroyContext, InstanceRef, logger::Logger};
+use mayon_core::{ContextHandler, DestroyContext, Format, InstanceRef, QueueKind, logger::Logger};
+
+use crate::{
+    Device, VulkanBackend, VulkanErrorKind,
+    types::{Extent2D, Surface},
+};
 
-use crate::{VulkanBackend, fn_table::FnTable, types::Surface};
+pub use frames::VulkanFrame;
 
-pub struct VulkanContext {
-    pub(crate) surface: Surface,
+use frames::{DEFAULT_FRAMES_IN_FLIGHT, FrameTarget, Frames};
+use offscreen::Offscreen;
+use swapchain::Swapchain;
+
+pub struct VulkanContext<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    target: RenderTarget<'a, L, A>,
+    frames: Option<Frames<'a, L, A>>,
+    frames_in_flight: usize,
 }
 
-impl ContextHandler for VulkanContext {}
+/// What a context renders into.
+///
+/// The images behind either variant only exist once the context is configured with a device.
+enum RenderTarget<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    // Only created from window handles, which need the `windowing` feature.
+    #[cfg_attr(not(feature = "windowing"), allow(dead_code))]
+    Surface {
+        surface: Surface,
+        swapchain: Option<Swapchain<'a, L, A>>,
+    },
+    Offscreen {
+        format: Format,
+        extent: Extent2D,
+        images: Option<Offscreen<'a, L, A>>,
+    },
+}
 
-impl<'a, L, A> DestroyContext<VulkanBackend<'a, L, A>, L, A> for VulkanContext
+impl<'a, L, A> VulkanContext<'a, L, A>
 where
     L: Logger,
-    A: Allocator,
+    A: Allocator + 'static,
+{
+    #[cfg_attr(not(feature = "windowing"), allow(dead_code))]
+    #[inline]
+    pub(crate) fn new(surface: Surface) -> Self {
+        Self {
+            target: RenderTarget::Surface {
+                surface,
+                swapchain: None,
+            },
+            frames: None,
+            frames_in_flight: DEFAULT_FRAMES_IN_FLIGHT,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn new_headless(width: u32, height: u32, format: Format) -> Self {
+        Self {
+            target: RenderTarget::Offscreen {
+                format,
+                extent: Extent2D { width, height },
+                images: None,
+            },
+            frames: None,
+            frames_in_flight: DEFAULT_FRAMES_IN_FLIGHT,
+        }
+    }
+
+    /// Whether this context renders to offscreen images rather than a window surface.
+    #[inline]
+    pub fn is_headless(&self) -> bool {
+        matches!(self.target, RenderTarget::Offscreen { .. })
+    }
+
+    /// Creates the images this context renders into on `device`, replacing any existing ones.
+    ///
+    /// For a window context this creates the swapchain. The surface format and present mode
+    /// are picked from what the surface supports, preferring an sRGB format and mailbox
+    /// presentation, falling back to FIFO. `width` and `height` are only used when the
+    /// surface lets the swapchain decide its size.
+    ///
+    /// For a headless context this allocates the offscreen images with the format given at
+    /// creation and a size of `width` by `height`.
+    ///
+    /// If the device has a graphics queue, the frames in flight used by
+    /// [`begin_frame`](Self::begin_frame) are created as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PresentationUnsupported` if the device has no graphics queue able to present
+    /// to this surface, or if the instance was created without surface extensions, and
+    /// `UnsupportedSurfaceFormat` if no format of the surface can be described as a texture.
+    /// Returns a `VulkanError` if a Vulkan call fails, including `VK_ERROR_FORMAT_NOT_SUPPORTED`
+    /// when the headless format cannot be rendered to.
+    pub fn configure(
+        &mut self,
+        device: &Device<'a, L, A>,
+        width: u32,
+        height: u32,
+    ) -> crate::Result<()> {
+        let requested_extent = Extent2D { width, height };
+
+        if let Some(frames) = self.frames.take() {
+            frames.destroy();
+        }
+
+        match &mut self.target {
+            RenderTarget::Surface { surface, swapchain } => {
+                let fns = device.instance().backend().fns();
+
+                let Some(graphics_queue) = device.graphics_queue else {
+                    return VulkanErrorKind::PresentationUnsupported.into_result();
+                };
+
+                let supported = device.instance().backend().presentation()
+                    && unsafe {
+                        fns.get_physical_device_surface_support(
+                            device.physical_device,
+                            graphics_queue.family_index,
+                            *surface,
+                        )
+                    }?;
+
+                if !supported {
+                    return VulkanErrorKind::PresentationUnsupported.into_result();
+                }
+
+                if let Some(swapchain) = swapchain.take() {
+                    swapchain.destroy();
+                }
+
+                *swapchain = Some(Swapchain::new(device.clone(), *surface, requested_extent)?);
+            }
+            RenderTarget::Offscreen {
+                format,
+                extent,
+                images,
+            } => {
+                if let Some(images) = images.take() {
+                    images.destroy();
+                }
+
+                *extent = requested_extent;
+                *images = Some(Offscreen::new(device.clone(), *format, requested_extent)?);
+            }
+        }
+
+        if device.queue(QueueKind::Graphics).is_some() {
+            self.frames = Some(Frames::new(device.clone(), self.frames_in_flight)?);
+        }
+
+        Ok(())
+    }
+
+    /// Number of frames the CPU may record ahead of the GPU.
+    #[inline]
+    pub fn frames_in_flight(&self) -> usize {
+        self.frames_in_flight
+    }
+
+    /// Sets how many frames the CPU may record ahead of the GPU. A count of zero is treated
+    /// as one.
+    ///
+    /// More frames in flight hide more CPU/GPU latency, at the cost of input lag and memory.
+    /// The new count takes effect on the next [`configure`](Self::configure).
+    #[inline]
+    pub fn set_frames_in_flight(&mut self, count: usize) {
+        self.frames_in_flight = count.max(1);
+    }
+
+    /// Starts a frame rendering into the next image of the render target.
+    ///
+    /// Waits until the GPU finished the frame that last used the same frame-in-flight slot,
+    /// then acquires the next image like [`acquire_next_image`](Self::acquire_next_image),
+    /// without blocking on the acquisition itself. The returned frame exposes the command
+    /// buffer to record into and the image, which stays in the color attachment layout until
+    /// the frame is [ended](VulkanFrame::end).
+    ///
+    /// Returns `Ok(None)` when there is nothing to render to, in which case no frame is
+    /// started.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingQueue` if the device has no graphics queue, or a `VulkanError` if a
+    /// Vulkan call fails.
+    pub fn begin_frame(&mut self) -> crate::Result<Option<VulkanFrame<'_, 'a, L, A>>> {
+        let configured = match &self.target {
+            RenderTarget::Surface { swapchain, .. } => swapchain.is_some(),
+            RenderTarget::Offscreen { images, .. } => images.is_some(),
+        };
+
+        if !configured {
+            return Ok(None);
+        }
+
+        let Some(frames) = self.frames.as_ref() else {
+            return VulkanErrorKind::MissingQueue {
+                kind: QueueKind::Graphics,
+            }
+            .into_result();
+        };
+
+        let acquire_semaphore = frames.wait()?;
+
+        let image_index = match &mut self.target {
+            RenderTarget::Surface {
+                surface,
+                swapchain: Some(swapchain),
+            } => swapchain.acquire_next_image(*surface, Some(acquire_semaphore))?,
+            RenderTarget::Offscreen {
+                images: Some(images),
+                ..
+            } => images.acquire_next_image()?,
+            _ => None,
+        };
+
+        let Some(image_index) = image_index else {
+            return Ok(None);
+        };
+
+        let (image, target) = match &self.target {
+            RenderTarget::Surface {
+                swapchain: Some(swapchain),
+                ..
+            } => (
+                swapchain.image(image_index),
+                FrameTarget::Present {
+                    swapchain,
+                    present_semaphore: swapchain.present_semaphore(image_index),
+                },
+            ),
+            RenderTarget::Offscreen {
+                images: Some(images),
+                ..
+            } => (images.image(image_index), FrameTarget::Offscreen),
+            _ => return Ok(None),
+        };
+
+        frames.begin(image, image_index, target).map(Some)
+    }
+
+    /// Records a new size for the render target.
+    ///
+    /// The images are recreated lazily by the next [`acquire_next_image`](Self::acquire_next_image).
+    #[inline]
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let requested_extent = Extent2D { width, height };
+
+        match &mut self.target {
+            RenderTarget::Surface { swapchain, .. } => {
+                if let Some(swapchain) = swapchain.as_mut() {
+                    swapchain.resize(requested_extent);
+                }
+            }
+            RenderTarget::Offscreen { extent, images, .. } => {
+                *extent = requested_extent;
+
+                if let Some(images) = images.as_mut() {
+                    images.resize(requested_extent);
+                }
+            }
+        }
+    }
+
+    /// Acquires the next image to render into and returns its index.
+    ///
+    /// If the swapchain is out of date it is recreated first. Headless contexts cycle through
+    /// their offscreen images. Returns `Ok(None)` when there is nothing to render to: the
+    /// context has not been configured, or the target has a zero-sized extent (e.g. a
+    /// minimized window).
+    ///
+    /// The image's contents and layout are undefined. [`begin_frame`](Self::begin_frame)
+    /// acquires the image and records its transitions for rendering and presentation.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if acquisition or image recreation fails, including
+    /// `UnsupportedSurfaceFormat` if the recreated surface has no format textures can describe.
+    #[inline]
+    pub fn acquire_next_image(&mut self) -> crate::Result<Option<u32>> {
+        match &mut self.target {
+            RenderTarget::Surface {
+                surface,
+                swapchain: Some(swapchain),
+            } => swapchain.acquire_next_image(*surface, None),
+            RenderTarget::Offscreen {
+                images: Some(images),
+                ..
+            } => images.acquire_next_image(),
+            _ => Ok(None),
+        }
+    }
+
+    /// Queues the image at `image_index` for presentation.
+    ///
+    /// An out-of-date or suboptimal swapchain is not an error; it is recreated on the next
+    /// [`acquire_next_image`](Self::acquire_next_image). For headless contexts this does nothing.
+    ///
+    /// The image must have been moved to the present layout by commands whose submission
+    /// completed; frames [ended](VulkanFrame::end) are presented without calling this.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkQueuePresentKHR` fails.
+    #[inline]
+    pub fn present(&mut self, image_index: u32) -> crate::Result<()> {
+        match &mut self.target {
+            RenderTarget::Surface {
+                swapchain: Some(swapchain),
+                ..
+            } => swapchain.present(image_index, &[]),
+            _ => Ok(()),
+        }
+    }
+
+    /// Current extent of the render target as `(width, height)`.
+    ///
+    /// Window contexts report `None` until a swapchain exists. Headless contexts report the
+    /// requested size, even before they are configured.
+    #[inline]
+    pub fn extent(&self) -> Option<(u32, u32)> {
+        let extent = match &self.target {
+            RenderTarget::Surface { swapchain, .. } => swapchain.as_ref()?.extent(),
+            RenderTarget::Offscreen { extent, images, .. } => Some(
+                images
+                    .as_ref()
+                    .and_then(Offscreen::extent)
+                    .unwrap_or(*extent),
+            ),
+        };
+
+        extent.map(|extent| (extent.width, extent.height))
+    }
+
+    /// Number of images in the render target.
+    #[inline]
+    pub fn image_count(&self) -> usize {
+        match &self.target {
+            RenderTarget::Surface { swapchain, .. } => {
+                swapchain.as_ref().map_or(0, Swapchain::image_count)
+            }
+            RenderTarget::Offscreen { images, .. } => {
+                images.as_ref().map_or(0, Offscreen::image_count)
+            }
+        }
+    }
+}
+
+impl<L, A> ContextHandler for VulkanContext<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+}
+
+impl<'a, L, A> DestroyContext<VulkanBackend<'a, L, A>, L, A> for VulkanContext<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
 {
     fn destroy(&self, instance: &InstanceRef<VulkanBackend<'a, L, A>, L, A>) {
-        unsafe {
-            let fns = FnTable::global().expect("Vulkan library is not loaded");
+        if let Some(frames) = self.frames.as_ref() {
+            frames.destroy();
+        }
+
+        match &self.target {
+            RenderTarget::Surface { surface, swapchain } => {
+                if let Some(swapchain) = swapchain.as_ref() {
+                    swapchain.destroy();
+                }
+
+                unsafe {
+                    let fns = instance.backend().fns();
+
+                    let vk_instance = instance.backend().instance();
+                    let vk_allocator = instance.backend().allocator();
+
+                    fns.destroy_surface(vk_instance, *surface, vk_allocator);
+                }
+            }
+            RenderTarget::Offscreen { images, .. } => {
+                if let Some(images) = images.as_ref() {
+                    images.destroy();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        LoadOp, RenderGraph, ReturnCode, StoreOp, TextureAccess, VulkanFunctionName,
+        fn_table::{MockDriver, MockHandleKind, mock},
+        types::ImageLayout,
+    };
+
+    fn live_semaphores() -> usize {
+        MockDriver::live_handles()
+            .iter()
+            .filter(|handle| handle.kind == MockHandleKind::Semaphore)
+            .count()
+    }
+
+    #[test]
+    fn test_frames_in_flight() {
+        let instance = mock::instance();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+        let mut context = instance
+            .create_headless_context(64, 64, mayon_core::Format::Rgba8Unorm)
+            .unwrap();
+
+        assert!(
+            context.begin_frame().unwrap().is_none(),
+            "Not configured yet"
+        );
+
+        context.configure(&device, 64, 64).unwrap();
+
+        assert_eq!(context.frames_in_flight(), DEFAULT_FRAMES_IN_FLIGHT);
+        assert_eq!(live_semaphores(), DEFAULT_FRAMES_IN_FLIGHT);
+
+        let mut frame = context.begin_frame().unwrap().unwrap();
+
+        assert_eq!(frame.image_index(), 0);
+        assert_eq!(frame.texture().format(), mayon_core::Format::Rgba8Unorm);
+        assert_eq!(
+            frame.texture().layout(0),
+            ImageLayout::COLOR_ATTACHMENT_OPTIMAL
+        );
+        assert_eq!(frame.view().texture().desc().width, 64);
+
+        frame.command_buffer().ensure_recording().unwrap();
+        frame.end().unwrap();
+
+        for image_index in [1, 0] {
+            let frame = context.begin_frame().unwrap().unwrap();
+
+            assert_eq!(frame.image_index(), image_index);
+
+            frame.end().unwrap();
+        }
+
+        // Dropping an unfinished frame still submits it.
+        drop(context.begin_frame().unwrap());
+
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::QueueSubmit), 4);
+
+        context.set_frames_in_flight(3);
+        context.configure(&device, 64, 64).unwrap();
+
+        assert_eq!(context.frames_in_flight(), 3);
+        assert_eq!(live_semaphores(), 3);
+
+        context.set_frames_in_flight(0);
+
+        assert_eq!(context.frames_in_flight(), 1);
+
+        drop(context);
+        drop(device);
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_failed_frame_submission() {
+        let instance = mock::instance();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+        let mut context = instance
+            .create_headless_context(64, 64, mayon_core::Format::Rgba8Unorm)
+            .unwrap();
+
+        context.configure(&device, 64, 64).unwrap();
+
+        MockDriver::fail_next(VulkanFunctionName::QueueSubmit, ReturnCode::DeviceLost);
+
+        assert!(context.begin_frame().unwrap().unwrap().end().is_err());
+
+        // The next frame reuses the resources of the one that was never submitted.
+        assert_eq!(context.frames.as_ref().unwrap().current(), 0);
+
+        context.begin_frame().unwrap().unwrap().end().unwrap();
 
-            let vk_instance = instance.backend().instance();
-            let vk_allocator = instance.backend().allocator();
+        assert_eq!(context.frames.as_ref().unwrap().current(), 1);
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::QueueSubmit), 2);
+        assert_eq!(live_semaphores(), DEFAULT_FRAMES_IN_FLIGHT);
 
-            fns.destroy_surface(vk_instance, self.surface, vk_allocator);
+        drop(context);
+        drop(device);
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_frame_graph() {
+        let instance = mock::instance();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+        let mut context = instance
+            .create_headless_context(64, 64, mayon_core::Format::Rgba8Unorm)
+            .unwrap();
+
+        context.configure(&device, 64, 64).unwrap();
+
+        let mut frame = context.begin_frame().unwrap().unwrap();
+        let texture = frame.texture();
+        let desc = *texture.desc();
+
+        let mut graph = RenderGraph::new(&device);
+        let target = graph.import_texture(texture);
+        let scratch = graph.create_texture(&desc);
+
+        graph
+            .add_pass("scratch")
+            .color_attachment(scratch, LoadOp::Clear([0.0; 4]), StoreOp::Store)
+            .record(|_| Ok(()))
+            .unwrap();
+        graph
+            .add_pass("target")
+            .texture(scratch, TextureAccess::TransferSrc)
+            .texture(target, TextureAccess::TransferDst)
+            .record(|_| Ok(()))
+            .unwrap();
+
+        frame.execute_graph(graph).unwrap();
+
+        assert_eq!(texture.layout(0), ImageLayout::TRANSFER_DST_OPTIMAL);
+
+        // The transient texture lives until the frame's slot is waited for again.
+        frame.end().unwrap();
+
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::CreateImage), 3);
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::DestroyImage), 0);
+        assert_eq!(texture.layout(0), ImageLayout::TRANSFER_SRC_OPTIMAL);
+
+        for _ in 0..DEFAULT_FRAMES_IN_FLIGHT {
+            context.begin_frame().unwrap().unwrap().end().unwrap();
         }
+
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::DestroyImage), 1);
+
+        drop(context);
+        drop(device);
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
     }
 }
@@ -0,0 +1,189 @@
+use allocator::Allocator;
+use mayon_core::{
+    Format, info,
+    logger::{Logger, Target as LogTarget},
+};
+
+use super::image::TargetImage;
+use crate::{
+    Device, Texture, TextureDesc, TextureDimension, TextureUsage,
+    types::{Extent2D, ImageCreateFlags, ImageTiling, ImageType},
+};
+
+/// Number of images cycled through by an offscreen target.
+const IMAGE_COUNT: usize = 2;
+
+const IMAGE_USAGE: TextureUsage = TextureUsage::COLOR_ATTACHMENT
+    .union(TextureUsage::TRANSFER_SRC)
+    .union(TextureUsage::TRANSFER_DST);
+
+/// A ring of device-local images standing in for a swapchain when there is no window.
+pub(super) struct Offscreen<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    format: Format,
+    images: Vec<TargetImage<'a, L, A>>,
+    extent: Extent2D,
+    requested_extent: Extent2D,
+    next_image: usize,
+    out_of_date: bool,
+}
+
+impl<'a, L, A> Offscreen<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates the offscreen images on `device`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `VK_ERROR_FORMAT_NOT_SUPPORTED` if `format` cannot be used as a color
+    /// attachment on the device.
+    pub(super) fn new(
+        device: Device<'a, L, A>,
+        format: Format,
+        requested_extent: Extent2D,
+    ) -> crate::Result<Self> {
+        let fns = device.instance().backend().fns();
+
+        unsafe {
+            fns.get_physical_device_image_format_properties(
+                device.physical_device,
+                format.into(),
+                ImageType::TYPE_2D,
+                ImageTiling::OPTIMAL,
+                IMAGE_USAGE.flags(),
+                ImageCreateFlags::EMPTY,
+            )
+        }?;
+
+        let mut offscreen = Self {
+            device,
+            format,
+            images: Vec::with_capacity(IMAGE_COUNT),
+            extent: Extent2D::default(),
+            requested_extent,
+            next_image: 0,
+            out_of_date: true,
+        };
+
+        if let Err(err) = offscreen.recreate() {
+            offscreen.destroy();
+
+            return Err(err);
+        }
+
+        Ok(offscreen)
+    }
+
+    #[inline]
+    pub(super) fn resize(&mut self, extent: Extent2D) {
+        if self.requested_extent != extent {
+            self.requested_extent = extent;
+            self.out_of_date = true;
+        }
+    }
+
+    #[inline]
+    pub(super) fn extent(&self) -> Option<Extent2D> {
+        (!self.images.is_empty()).then_some(self.extent)
+    }
+
+    #[inline]
+    pub(super) fn image_count(&self) -> usize {
+        self.images.len()
+    }
+
+    #[inline]
+    pub(super) fn image(&self, image_index: u32) -> &TargetImage<'a, L, A> {
+        &self.images[image_index as usize]
+    }
+
+    fn recreate(&mut self) -> crate::Result<()> {
+        let fns = &self.device.fns;
+
+        unsafe {
+            // A lost device surfaces again in the creation calls below.
+            let _ = fns.device_wait_idle(self.device.handle);
+        }
+
+        self.destroy_images();
+        self.images.clear();
+
+        let extent = self.requested_extent;
+
+        if extent.width == 0 || extent.height == 0 {
+            return Ok(());
+        }
+
+        let desc = TextureDesc::new(
+            TextureDimension::D2,
+            self.format,
+            extent.width,
+            extent.height,
+        )
+        .with_usage(IMAGE_USAGE);
+
+        for _ in 0..IMAGE_COUNT {
+            let image = TargetImage::new(Texture::new(&self.device, &desc)?)?;
+
+            self.images.push(image);
+        }
+
+        self.extent = extent;
+        self.next_image = 0;
+        self.out_of_date = false;
+
+        info!(
+            self.device.instance().logger(),
+            LogTarget::Backend,
+            "Vulkan offscreen target created: {}x{}, {} images, {:?}",
+            extent.width,
+            extent.height,
+            self.images.len(),
+            self.format
+        );
+
+        Ok(())
+    }
+
+    /// Hands out the next image of the ring.
+    ///
+    /// Returns `Ok(None)` while the requested extent is zero-sized.
+    pub(super) fn acquire_next_image(&mut self) -> crate::Result<Option<u32>> {
+        if self.out_of_date {
+            self.recreate()?;
+        }
+
+        if self.images.is_empty() {
+            return Ok(None);
+        }
+
+        let image_index = self.next_image;
+
+        self.next_image = (self.next_image + 1) % self.images.len();
+
+        Ok(Some(image_index as u32))
+    }
+
+    /// Destroys the views of the images, which are freed once dropped.
+    fn destroy_images(&self) {
+        for image in &self.images {
+            image.destroy();
+        }
+    }
+
+    pub(super) fn destroy(&self) {
+        let fns = &self.device.fns;
+
+        unsafe {
+            let _ = fns.device_wait_idle(self.device.handle);
+        }
+
+        self.destroy_images();
+    }
+}
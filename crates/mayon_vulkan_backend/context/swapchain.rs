@@ -0,0 +1,525 @@
+use core::cell::Cell;
+
+use allocator::Allocator;
+use mayon_core::{
+    debug, info,
+    logger::{Logger, Target as LogTarget},
+};
+
+use super::image::TargetImage;
+use crate::{
+    Device, Texture, TextureDesc, TextureDimension, TextureUsage, VulkanErrorKind,
+    VulkanFunctionName,
+    types::{
+        ColorSpace, CompositeAlphaFlags, Extent2D, Fence, FenceCreateFlags, FenceCreateInfo,
+        Format, ImageUsageFlags, PresentInfo, PresentMode, Semaphore, SemaphoreCreateInfo, Surface,
+        SurfaceCapabilities, SurfaceFormat, SwapchainCreateInfo, VkResult,
+    },
+};
+
+/// A swapchain together with the state needed to recreate it.
+///
+/// `handle` is `None` while the surface has a zero-sized extent, since Vulkan does not allow
+/// creating a swapchain for it.
+pub(super) struct Swapchain<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    handle: Option<crate::types::Swapchain>,
+    images: Vec<TargetImage<'a, L, A>>,
+    surface_format: SurfaceFormat,
+    present_mode: PresentMode,
+    extent: Extent2D,
+    requested_extent: Extent2D,
+    acquire_fence: Fence,
+    /// One per image: signaled by the frame rendering to the image, waited on by its
+    /// presentation. Presentation does not signal anything on completion, so these cannot be
+    /// reused per frame in flight.
+    present_semaphores: Vec<Semaphore>,
+    /// Set by presentation, which only borrows the swapchain while a frame holds its image.
+    out_of_date: Cell<bool>,
+}
+
+impl<'a, L, A> Swapchain<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    pub(super) fn new(
+        device: Device<'a, L, A>,
+        surface: Surface,
+        requested_extent: Extent2D,
+    ) -> crate::Result<Self> {
+        let fns = &device.fns;
+
+        let acquire_fence = unsafe {
+            fns.create_fence(
+                device.handle,
+                &FenceCreateInfo::new(FenceCreateFlags::EMPTY),
+                device.instance().backend().allocator(),
+            )
+        }?;
+
+        let mut swapchain = Self {
+            device,
+            handle: None,
+            images: Vec::new(),
+            surface_format: SurfaceFormat::default(),
+            present_mode: PresentMode::FIFO,
+            extent: Extent2D::default(),
+            requested_extent,
+            acquire_fence,
+            present_semaphores: Vec::new(),
+            out_of_date: Cell::new(true),
+        };
+
+        if let Err(err) = swapchain.recreate(surface) {
+            swapchain.destroy();
+
+            return Err(err);
+        }
+
+        Ok(swapchain)
+    }
+
+    #[inline]
+    pub(super) fn resize(&mut self, extent: Extent2D) {
+        if self.requested_extent != extent {
+            self.requested_extent = extent;
+            self.out_of_date.set(true);
+        }
+    }
+
+    #[inline]
+    pub(super) fn extent(&self) -> Option<Extent2D> {
+        self.handle.map(|_| self.extent)
+    }
+
+    #[inline]
+    pub(super) fn image_count(&self) -> usize {
+        self.images.len()
+    }
+
+    #[inline]
+    pub(super) fn image(&self, image_index: u32) -> &TargetImage<'a, L, A> {
+        &self.images[image_index as usize]
+    }
+
+    #[inline]
+    pub(super) fn present_semaphore(&self, image_index: u32) -> Semaphore {
+        self.present_semaphores[image_index as usize]
+    }
+
+    /// Rebuilds the swapchain from the current surface capabilities.
+    ///
+    /// The previous swapchain is passed as `oldSwapchain` and destroyed once the new one
+    /// exists. The device is idled first so none of the old images are still in use.
+    ///
+    /// Fails with `UnsupportedSurfaceFormat` if no surface format maps to a texture format.
+    fn recreate(&mut self, surface: Surface) -> crate::Result<()> {
+        let device = &self.device;
+        let backend = device.instance().backend();
+        let instance_fns = backend.fns();
+        let fns = &device.fns;
+
+        let capabilities = unsafe {
+            instance_fns.get_physical_device_surface_capabilities(device.physical_device, surface)
+        }?;
+
+        let extent = choose_extent(&capabilities, self.requested_extent);
+
+        if extent.width == 0 || extent.height == 0 {
+            // Keep the old swapchain around until the surface becomes visible again.
+            return Ok(());
+        }
+
+        let formats = unsafe {
+            instance_fns.get_physical_device_surface_formats(device.physical_device, surface)
+        }?;
+        let present_modes = unsafe {
+            instance_fns.get_physical_device_surface_present_modes(device.physical_device, surface)
+        }?;
+
+        let Some((surface_format, format)) = choose_surface_format(&formats) else {
+            return VulkanErrorKind::UnsupportedSurfaceFormat.into_result();
+        };
+        let present_mode = choose_present_mode(&present_modes);
+
+        let image_count = if capabilities.max_image_count == 0 {
+            capabilities.min_image_count + 1
+        } else {
+            (capabilities.min_image_count + 1).min(capabilities.max_image_count)
+        };
+
+        let texture_usage = if capabilities
+            .supported_usage_flags
+            .intersection(ImageUsageFlags::TRANSFER_DST)
+            == ImageUsageFlags::TRANSFER_DST
+        {
+            TextureUsage::COLOR_ATTACHMENT.union(TextureUsage::TRANSFER_DST)
+        } else {
+            TextureUsage::COLOR_ATTACHMENT
+        };
+        let image_usage = texture_usage.flags();
+
+        let composite_alpha = [
+            CompositeAlphaFlags::OPAQUE,
+            CompositeAlphaFlags::PRE_MULTIPLIED,
+            CompositeAlphaFlags::POST_MULTIPLIED,
+            CompositeAlphaFlags::INHERIT,
+        ]
+        .into_iter()
+        .find(|&flag| capabilities.supported_composite_alpha.contains(flag))
+        .unwrap_or(CompositeAlphaFlags::OPAQUE);
+
+        let info = SwapchainCreateInfo::new(
+            surface,
+            image_count,
+            surface_format,
+            extent,
+            image_usage,
+            capabilities.current_transform,
+            composite_alpha,
+            present_mode,
+            self.handle,
+        );
+
+        unsafe {
+            // A lost device surfaces again in the creation call below.
+            let _ = fns.device_wait_idle(device.handle);
+
+            let handle = fns.create_swapchain(device.handle, &info, backend.allocator())?;
+
+            // Views of the old images go before the images themselves.
+            for image in self.images.drain(..) {
+                image.destroy();
+            }
+
+            if let Some(old_handle) = self.handle.replace(handle) {
+                fns.destroy_swapchain(device.handle, old_handle, backend.allocator());
+            }
+
+            let desc = TextureDesc::new(TextureDimension::D2, format, extent.width, extent.height)
+                .with_usage(texture_usage);
+
+            for image in fns.get_swapchain_images(device.handle, handle)? {
+                let texture = Texture::from_swapchain_image(device, image, desc);
+
+                self.images.push(TargetImage::new(texture)?);
+            }
+
+            let allocator = backend.allocator();
+
+            while self.present_semaphores.len() > self.images.len() {
+                if let Some(semaphore) = self.present_semaphores.pop() {
+                    fns.destroy_semaphore(device.handle, semaphore, allocator);
+                }
+            }
+
+            while self.present_semaphores.len() < self.images.len() {
+                let semaphore =
+                    fns.create_semaphore(device.handle, &SemaphoreCreateInfo::new(), allocator)?;
+
+                self.present_semaphores.push(semaphore);
+            }
+        }
+
+        self.surface_format = surface_format;
+        self.present_mode = present_mode;
+        self.extent = extent;
+        self.out_of_date.set(false);
+
+        info!(
+            device.instance().logger(),
+            LogTarget::Backend,
+            "Vulkan swapchain created: {:?} {}x{}, {} images, {:?}, {:?}",
+            self.handle,
+            extent.width,
+            extent.height,
+            self.images.len(),
+            self.surface_format.format,
+            self.present_mode
+        );
+
+        Ok(())
+    }
+
+    /// Acquires the next image, recreating the swapchain first if it is out of date.
+    ///
+    /// With a `semaphore`, it is signaled once the image is ready to be rendered to. Without
+    /// one, this blocks until the image is ready.
+    pub(super) fn acquire_next_image(
+        &mut self,
+        surface: Surface,
+        semaphore: Option<Semaphore>,
+    ) -> crate::Result<Option<u32>> {
+        loop {
+            if self.out_of_date.get() {
+                self.recreate(surface)?;
+            }
+
+            if self.out_of_date.get() {
+                return Ok(None);
+            }
+
+            let fns = &self.device.fns;
+
+            let Some(handle) = self.handle else {
+                return Ok(None);
+            };
+
+            let (result, image_index) = unsafe {
+                fns.acquire_next_image(
+                    self.device.handle,
+                    handle,
+                    u64::MAX,
+                    semaphore,
+                    semaphore.is_none().then_some(self.acquire_fence),
+                )
+            }?;
+
+            match result {
+                VkResult::Success => {}
+                VkResult::SuboptimalKhr => {
+                    // The image is still acquired and usable; recreate after presenting it.
+                    self.out_of_date.set(true);
+                }
+                VkResult::OutOfDateKhr => {
+                    debug!(
+                        self.device.instance().logger(),
+                        LogTarget::Backend,
+                        "Vulkan swapchain out of date, recreating"
+                    );
+
+                    self.out_of_date.set(true);
+
+                    continue;
+                }
+                _ => return result.into_result(VulkanFunctionName::AcquireNextImage, || None),
+            }
+
+            if semaphore.is_some() {
+                return Ok(Some(image_index));
+            }
+
+            unsafe {
+                fns.wait_for_fences(self.device.handle, &[self.acquire_fence], true, u64::MAX)?;
+                fns.reset_fences(self.device.handle, &[self.acquire_fence])?;
+            }
+
+            return Ok(Some(image_index));
+        }
+    }
+
+    /// Presents `image_index` once every semaphore in `wait_semaphores` is signaled.
+    pub(super) fn present(
+        &self,
+        image_index: u32,
+        wait_semaphores: &[Semaphore],
+    ) -> crate::Result<()> {
+        let fns = &self.device.fns;
+
+        let (Some(handle), Some(queue)) = (self.handle, self.device.graphics_queue) else {
+            return Ok(());
+        };
+
+        let result = {
+            let _queues = self.device.lock_queues();
+
+            unsafe {
+                fns.queue_present(
+                    queue.handle,
+                    &PresentInfo::new(wait_semaphores, &handle, &image_index),
+                )
+            }
+        }?;
+
+        match result {
+            VkResult::Success => Ok(()),
+            VkResult::SuboptimalKhr | VkResult::OutOfDateKhr => {
+                self.out_of_date.set(true);
+
+                Ok(())
+            }
+            _ => result.into_result(VulkanFunctionName::QueuePresent, || ()),
+        }
+    }
+
+    pub(super) fn destroy(&self) {
+        let fns = &self.device.fns;
+        let device = &self.device;
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            let _ = fns.device_wait_idle(device.handle);
+
+            for image in &self.images {
+                image.destroy();
+            }
+
+            if let Some(handle) = self.handle {
+                fns.destroy_swapchain(device.handle, handle, allocator);
+            }
+
+            fns.destroy_fence(device.handle, self.acquire_fence, allocator);
+
+            for &semaphore in &self.present_semaphores {
+                fns.destroy_semaphore(device.handle, semaphore, allocator);
+            }
+        }
+    }
+}
+
+/// Picks the swapchain extent, honoring the surface size when the platform dictates it.
+fn choose_extent(capabilities: &SurfaceCapabilities, requested: Extent2D) -> Extent2D {
+    if capabilities.current_extent.width != SurfaceCapabilities::UNDEFINED_EXTENT {
+        return capabilities.current_extent;
+    }
+
+    Extent2D {
+        width: requested.width.clamp(
+            capabilities.min_image_extent.width,
+            capabilities.max_image_extent.width,
+        ),
+        height: requested.height.clamp(
+            capabilities.min_image_extent.height,
+            capabilities.max_image_extent.height,
+        ),
+    }
+}
+
+/// Prefers an 8-bit sRGB format in the sRGB color space, then the first reported format that
+/// frames can describe as a texture, which is returned along with the surface format.
+///
+/// Returns `None` if no reported format can be described as a texture.
+fn choose_surface_format(formats: &[SurfaceFormat]) -> Option<(SurfaceFormat, mayon_core::Format)> {
+    const PREFERRED: [Format; 2] = [Format::B8G8R8A8_SRGB, Format::R8G8B8A8_SRGB];
+
+    // A single `UNDEFINED` entry means the surface has no preference.
+    let formats = match formats {
+        [only] if only.format == Format::UNDEFINED => &[SurfaceFormat {
+            format: Format::B8G8R8A8_SRGB,
+            color_space: ColorSpace::SRGB_NONLINEAR,
+        }],
+        formats => formats,
+    };
+
+    PREFERRED
+        .iter()
+        .find_map(|&format| {
+            formats.iter().find(|candidate| {
+                candidate.format == format && candidate.color_space == ColorSpace::SRGB_NONLINEAR
+            })
+        })
+        .into_iter()
+        .chain(formats)
+        .find_map(|&candidate| Some((candidate, candidate.format.core_format()?)))
+}
+
+/// Prefers mailbox presentation; FIFO is the fallback every implementation must support.
+fn choose_present_mode(present_modes: &[PresentMode]) -> PresentMode {
+    if present_modes.contains(&PresentMode::MAILBOX) {
+        PresentMode::MAILBOX
+    } else {
+        PresentMode::FIFO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(format: Format) -> SurfaceFormat {
+        SurfaceFormat {
+            format,
+            color_space: ColorSpace::SRGB_NONLINEAR,
+        }
+    }
+
+    #[test]
+    fn test_choose_surface_format() {
+        assert_eq!(
+            choose_surface_format(&[
+                format(Format::B8G8R8A8_UNORM),
+                format(Format::R8G8B8A8_SRGB),
+                format(Format::B8G8R8A8_SRGB),
+            ]),
+            Some((format(Format::B8G8R8A8_SRGB), mayon_core::Format::Bgra8Srgb))
+        );
+
+        assert_eq!(
+            choose_surface_format(&[format(Format::UNDEFINED), format(Format::B8G8R8A8_UNORM)]),
+            Some((
+                format(Format::B8G8R8A8_UNORM),
+                mayon_core::Format::Bgra8Unorm
+            )),
+            "Falls back to the first format textures can describe"
+        );
+
+        assert_eq!(
+            choose_surface_format(&[format(Format::UNDEFINED)]),
+            Some((format(Format::B8G8R8A8_SRGB), mayon_core::Format::Bgra8Srgb)),
+            "No preference picks sRGB"
+        );
+
+        assert_eq!(
+            choose_surface_format(&[format(Format::R32_UINT)]),
+            None,
+            "Formats textures cannot describe are never picked"
+        );
+    }
+
+    #[test]
+    fn test_choose_present_mode() {
+        assert_eq!(
+            choose_present_mode(&[PresentMode::FIFO, PresentMode::MAILBOX]),
+            PresentMode::MAILBOX
+        );
+        assert_eq!(
+            choose_present_mode(&[PresentMode::IMMEDIATE, PresentMode::FIFO]),
+            PresentMode::FIFO
+        );
+    }
+
+    #[test]
+    fn test_choose_extent() {
+        let mut capabilities = SurfaceCapabilities {
+            current_extent: Extent2D {
+                width: 800,
+                height: 600,
+            },
+            min_image_extent: Extent2D {
+                width: 1,
+                height: 1,
+            },
+            max_image_extent: Extent2D {
+                width: 4096,
+                height: 4096,
+            },
+            ..Default::default()
+        };
+
+        let requested = Extent2D {
+            width: 1024,
+            height: 8192,
+        };
+
+        assert_eq!(
+            choose_extent(&capabilities, requested),
+            capabilities.current_extent
+        );
+
+        capabilities.current_extent.width = SurfaceCapabilities::UNDEFINED_EXTENT;
+
+        assert_eq!(
+            choose_extent(&capabilities, requested),
+            Extent2D {
+                width: 1024,
+                height: 4096,
+            }
+        );
+    }
+}
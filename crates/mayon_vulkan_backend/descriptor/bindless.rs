@@ -0,0 +1,659 @@
+use std::sync::{Mutex, PoisonError};
+
+use allocator::Allocator;
+use mayon_core::{
+    debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+use crate::{
+    Buffer, BufferUsage, DescriptorBinding, DescriptorKind, Device, Sampler, ShaderStages,
+    TextureUsage, TextureView, TimelineSemaphore, VulkanErrorKind,
+    types::{
+        self, DescriptorBindingFlags, DescriptorBufferInfo, DescriptorImageInfo,
+        DescriptorPoolCreateInfo, DescriptorPoolSize, DescriptorSetAllocateInfo,
+        DescriptorSetLayoutBinding, DescriptorSetLayoutBindingFlagsCreateInfo,
+        DescriptorSetLayoutCreateInfo, ImageLayout, WriteDescriptorSet,
+    },
+};
+
+/// Kind of resource held by one of the arrays of a [`BindlessTable`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BindlessKind {
+    /// Sampled images, at binding 0.
+    Texture,
+    /// Samplers, at binding 1.
+    Sampler,
+    /// Storage buffers, at binding 2.
+    Buffer,
+}
+
+impl BindlessKind {
+    const ALL: [Self; 3] = [Self::Texture, Self::Sampler, Self::Buffer];
+
+    /// Binding of the array in the table's set.
+    #[inline]
+    pub const fn binding(self) -> u32 {
+        self as u32
+    }
+
+    #[inline]
+    const fn descriptor_kind(self) -> DescriptorKind {
+        match self {
+            Self::Texture => DescriptorKind::SampledImage,
+            Self::Sampler => DescriptorKind::Sampler,
+            Self::Buffer => DescriptorKind::StorageBuffer,
+        }
+    }
+}
+
+/// Number of resources of each kind a [`BindlessTable`] holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BindlessDesc {
+    pub textures: u32,
+    pub samplers: u32,
+    pub buffers: u32,
+}
+
+impl Default for BindlessDesc {
+    /// Well within the update-after-bind limits every device supporting descriptor indexing
+    /// guarantees.
+    #[inline]
+    fn default() -> Self {
+        Self {
+            textures: 16384,
+            samplers: 1024,
+            buffers: 16384,
+        }
+    }
+}
+
+impl BindlessDesc {
+    #[inline]
+    const fn capacity(&self, kind: BindlessKind) -> u32 {
+        match kind {
+            BindlessKind::Texture => self.textures,
+            BindlessKind::Sampler => self.samplers,
+            BindlessKind::Buffer => self.buffers,
+        }
+    }
+
+    /// Bindings of the table's arrays, as seen by pipelines binding the table at `set`.
+    pub(crate) fn bindings(&self, set: u32) -> Vec<DescriptorBinding> {
+        BindlessKind::ALL
+            .iter()
+            .map(|&kind| DescriptorBinding {
+                set,
+                binding: kind.binding(),
+                kind: kind.descriptor_kind(),
+                count: self.capacity(kind),
+                stages: ShaderStages::ALL,
+            })
+            .collect()
+    }
+
+    /// Creates the layout of the table's set. Layouts created from equal descriptions are
+    /// compatible, so pipeline layouts create their own rather than sharing the table's.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkCreateDescriptorSetLayout` fails.
+    pub(crate) fn create_set_layout<L, A>(
+        &self,
+        device: &Device<'_, L, A>,
+    ) -> crate::Result<types::DescriptorSetLayout>
+    where
+        L: Logger,
+        A: Allocator + 'static,
+    {
+        let bindings = self
+            .bindings(0)
+            .iter()
+            .map(|binding| {
+                DescriptorSetLayoutBinding::new(
+                    binding.binding,
+                    binding.kind.into(),
+                    binding.count,
+                    binding.stages.flags(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Slots are written while the set is bound, and left unwritten until used.
+        let binding_flags = [DescriptorBindingFlags::UPDATE_AFTER_BIND
+            .union(DescriptorBindingFlags::UPDATE_UNUSED_WHILE_PENDING)
+            .union(DescriptorBindingFlags::PARTIALLY_BOUND);
+            BindlessKind::ALL.len()];
+        let binding_flags_info = DescriptorSetLayoutBindingFlagsCreateInfo::new(&binding_flags);
+
+        unsafe {
+            device.fns.create_descriptor_set_layout(
+                device.handle,
+                &DescriptorSetLayoutCreateInfo::new(&bindings)
+                    .update_after_bind(&binding_flags_info),
+                device.instance().backend().allocator(),
+            )
+        }
+    }
+}
+
+/// Stable index of a resource in a [`BindlessTable`], which shaders use to index the array
+/// of its [`kind`](Self::kind).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BindlessIndex {
+    kind: BindlessKind,
+    index: u32,
+}
+
+impl BindlessIndex {
+    #[inline]
+    pub fn kind(&self) -> BindlessKind {
+        self.kind
+    }
+
+    /// Index in the array of the resource's kind.
+    #[inline]
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+}
+
+/// Slots of one array of a table.
+#[derive(Default)]
+struct Slots {
+    /// Whether each slot used so far holds a resource.
+    live: Vec<bool>,
+    /// Slots that can be handed out again.
+    free: Vec<u32>,
+    /// Removed slots, with the timeline value their last use completes at.
+    retired: Vec<(u64, u32)>,
+}
+
+/// Large arrays of textures, samplers and storage buffers in a single descriptor set, which
+/// shaders index with the stable [`BindlessIndex`] of each resource.
+///
+/// The set is updated after being bound, so resources can be added while previous frames
+/// are still in flight. A removed resource's slot is only handed out again once the
+/// submissions that may still use it completed, as reported by a [`TimelineSemaphore`]
+/// passed to [`reclaim`](Self::reclaim).
+///
+/// Pipelines bind the table's set with a layout made by
+/// [`PipelineLayout::with_bindless`](crate::PipelineLayout::with_bindless). Resources must
+/// stay alive until their slot is reclaimed.
+pub struct BindlessTable<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    desc: BindlessDesc,
+    set_layout: types::DescriptorSetLayout,
+    pool: types::DescriptorPool,
+    set: types::DescriptorSet,
+    /// Slots of each kind, in the order of [`BindlessKind::ALL`].
+    slots: Mutex<[Slots; 3]>,
+}
+
+impl<'a, L, A> BindlessTable<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates a table holding up to the number of resources of each kind of `desc`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DescriptorIndexingUnsupported` if the device was created without the
+    /// descriptor indexing features, or a `VulkanError` if creating the set layout, the pool
+    /// or allocating the set fails.
+    pub fn new(device: &Device<'a, L, A>, desc: &BindlessDesc) -> crate::Result<Self> {
+        if !device.descriptor_indexing() {
+            return VulkanErrorKind::DescriptorIndexingUnsupported.into_result();
+        }
+
+        let set_layout = desc.create_set_layout(device)?;
+
+        let fns = &device.fns;
+        let pool_sizes = desc
+            .bindings(0)
+            .iter()
+            .map(|binding| DescriptorPoolSize {
+                descriptor_type: binding.kind.into(),
+                descriptor_count: binding.count,
+            })
+            .collect::<Vec<_>>();
+        let set_layouts = [set_layout];
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            let pool = match fns.create_descriptor_pool(
+                device.handle,
+                &DescriptorPoolCreateInfo::new(1, &pool_sizes).update_after_bind(),
+                allocator,
+            ) {
+                Ok(pool) => pool,
+                Err(err) => {
+                    fns.destroy_descriptor_set_layout(device.handle, set_layout, allocator);
+
+                    return Err(err);
+                }
+            };
+
+            let set = match fns.allocate_descriptor_set(
+                device.handle,
+                &DescriptorSetAllocateInfo::new(pool, &set_layouts),
+            ) {
+                Ok(set) => set,
+                Err(err) => {
+                    fns.destroy_descriptor_pool(device.handle, pool, allocator);
+                    fns.destroy_descriptor_set_layout(device.handle, set_layout, allocator);
+
+                    return Err(err);
+                }
+            };
+
+            debug!(
+                device.instance().logger(),
+                LogTarget::Backend,
+                "Vulkan bindless table created: {:?}, {:?}",
+                set,
+                desc
+            );
+
+            Ok(Self {
+                device: device.clone(),
+                desc: *desc,
+                set_layout,
+                pool,
+                set,
+                slots: Mutex::new(Default::default()),
+            })
+        }
+    }
+
+    #[inline]
+    pub fn device(&self) -> &Device<'a, L, A> {
+        &self.device
+    }
+
+    #[inline]
+    pub fn desc(&self) -> &BindlessDesc {
+        &self.desc
+    }
+
+    /// Number of resources of `kind` in the table.
+    pub fn len(&self, kind: BindlessKind) -> usize {
+        self.lock()[kind as usize]
+            .live
+            .iter()
+            .filter(|&&live| live)
+            .count()
+    }
+
+    /// Adds `view` to the textures, to be sampled in its shader read-only layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingTextureUsage` if the texture was not created with
+    /// [`TextureUsage::SAMPLED`], or `BindlessTableFull` if every texture slot is taken.
+    pub fn add_texture(&self, view: &TextureView<'_, 'a, L, A>) -> crate::Result<BindlessIndex> {
+        if !view.texture().usage().contains(TextureUsage::SAMPLED) {
+            return VulkanErrorKind::MissingTextureUsage {
+                usage: TextureUsage::SAMPLED,
+            }
+            .into_result();
+        }
+
+        self.add(
+            BindlessKind::Texture,
+            Descriptor::Image(DescriptorImageInfo {
+                sampler: None,
+                image_view: Some(view.handle()),
+                image_layout: ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            }),
+        )
+    }
+
+    /// Adds `sampler` to the samplers.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BindlessTableFull` if every sampler slot is taken.
+    pub fn add_sampler(&self, sampler: Sampler<'_>) -> crate::Result<BindlessIndex> {
+        self.add(
+            BindlessKind::Sampler,
+            Descriptor::Image(DescriptorImageInfo {
+                sampler: Some(sampler.handle()),
+                image_view: None,
+                image_layout: ImageLayout::UNDEFINED,
+            }),
+        )
+    }
+
+    /// Adds the whole of `buffer` to the storage buffers.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingBufferUsage` if the buffer was not created with
+    /// [`BufferUsage::STORAGE`], or `BindlessTableFull` if every buffer slot is taken.
+    pub fn add_buffer<T: Copy>(
+        &self,
+        buffer: &Buffer<'a, T, L, A>,
+    ) -> crate::Result<BindlessIndex> {
+        if !buffer.usage().contains(BufferUsage::STORAGE) {
+            return VulkanErrorKind::MissingBufferUsage {
+                usage: BufferUsage::STORAGE,
+            }
+            .into_result();
+        }
+
+        self.add(
+            BindlessKind::Buffer,
+            Descriptor::Buffer(DescriptorBufferInfo {
+                buffer: buffer.handle(),
+                offset: 0,
+                range: DescriptorBufferInfo::WHOLE_SIZE,
+            }),
+        )
+    }
+
+    /// Removes the resource at `index`. Its slot is handed out again once a
+    /// [`reclaim`](Self::reclaim) observes `last_use`, the timeline value signaled when the
+    /// last submission that may use the resource completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidBindlessIndex` if `index` holds no resource, such as after it was
+    /// already removed.
+    pub fn remove(&self, index: BindlessIndex, last_use: u64) -> crate::Result<()> {
+        let mut slots = self.lock();
+        let slots = &mut slots[index.kind as usize];
+
+        match slots.live.get_mut(index.index as usize) {
+            Some(live) if *live => *live = false,
+            _ => return VulkanErrorKind::InvalidBindlessIndex.into_result(),
+        }
+
+        slots.retired.push((last_use, index.index));
+
+        Ok(())
+    }
+
+    /// Makes the slots of removed resources available again once `timeline` reached the
+    /// value their last use completes at.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if reading the counter of `timeline` fails.
+    pub fn reclaim(&self, timeline: &TimelineSemaphore<'a, L, A>) -> crate::Result<()> {
+        let completed = timeline.value()?;
+
+        for slots in self.lock().iter_mut() {
+            let Slots { free, retired, .. } = slots;
+
+            retired.retain(|&(last_use, index)| {
+                if last_use > completed {
+                    return true;
+                }
+
+                free.push(index);
+
+                false
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Writes `descriptor` to a free slot of `kind`, preferring reclaimed slots.
+    fn add(&self, kind: BindlessKind, descriptor: Descriptor) -> crate::Result<BindlessIndex> {
+        let mut slots = self.lock();
+        let slots = &mut slots[kind as usize];
+
+        let index = match slots.free.pop() {
+            Some(index) => index,
+            None if (slots.live.len() as u32) < self.desc.capacity(kind) => {
+                slots.live.push(false);
+
+                slots.live.len() as u32 - 1
+            }
+            None => return VulkanErrorKind::BindlessTableFull { kind }.into_result(),
+        };
+
+        let dst = (self.set, kind.binding(), index);
+        let descriptor_type = kind.descriptor_kind().into();
+        let write = match &descriptor {
+            Descriptor::Buffer(info) => {
+                WriteDescriptorSet::buffers(dst, descriptor_type, core::slice::from_ref(info))
+            }
+            Descriptor::Image(info) => {
+                WriteDescriptorSet::images(dst, descriptor_type, core::slice::from_ref(info))
+            }
+        };
+
+        // The slot is neither live nor retired, so no pending submission uses it.
+        unsafe {
+            self.device
+                .fns
+                .update_descriptor_sets(self.device.handle, &[write])
+        };
+
+        slots.live[index as usize] = true;
+
+        Ok(BindlessIndex { kind, index })
+    }
+
+    /// Locks the slots. They are only changed once descriptors are written, so a panic
+    /// while holding the lock leaves them consistent.
+    #[inline]
+    fn lock(&self) -> std::sync::MutexGuard<'_, [Slots; 3]> {
+        self.slots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+enum Descriptor {
+    Buffer(DescriptorBufferInfo),
+    Image(DescriptorImageInfo),
+}
+
+impl<L, A> Drop for BindlessTable<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        let device = &self.device;
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            // Destroying the pool frees the set.
+            device
+                .fns
+                .destroy_descriptor_pool(device.handle, self.pool, allocator);
+            device
+                .fns
+                .destroy_descriptor_set_layout(device.handle, self.set_layout, allocator);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::{BaseError, Format, logger::DefaultLogger};
+
+    use super::*;
+    use crate::{
+        ComputePipeline, DescriptorAllocator, Instance, MemoryUsage, PipelineCache, PipelineLayout,
+        SamplerCache, SamplerDesc, ShaderModule, Texture, TextureDesc, TextureDimension,
+        VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+        shader::test_shaders,
+    };
+
+    #[test]
+    fn test_descriptor_indexing_unsupported() {
+        MockDriver::reset();
+
+        let params = mock::params().with_api_version((1, 0), (1, 1));
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+
+        assert!(!device.descriptor_indexing());
+        assert!(matches!(
+            BindlessTable::new(&device, &BindlessDesc::default())
+                .err()
+                .unwrap()
+                .kind(),
+            VulkanErrorKind::DescriptorIndexingUnsupported
+        ));
+
+        drop(device);
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_bindless_slots() {
+        let device = mock::device();
+
+        assert!(device.descriptor_indexing());
+
+        let desc = BindlessDesc {
+            textures: 2,
+            samplers: 1,
+            buffers: 1,
+        };
+        let table = BindlessTable::new(&device, &desc).unwrap();
+
+        let texture = Texture::new(
+            &device,
+            &TextureDesc::new(TextureDimension::D2, Format::Rgba8Srgb, 4, 4),
+        )
+        .unwrap();
+        let view = TextureView::new(&texture).unwrap();
+        let samplers = SamplerCache::new(&device);
+        let sampler = samplers.get(&SamplerDesc::default()).unwrap();
+        let storage =
+            Buffer::<u32, _, _>::new(&device, 4, BufferUsage::STORAGE, MemoryUsage::Upload)
+                .unwrap();
+        let uniform =
+            Buffer::<u32, _, _>::new(&device, 4, BufferUsage::UNIFORM, MemoryUsage::Upload)
+                .unwrap();
+
+        let first = table.add_texture(&view).unwrap();
+        let second = table.add_texture(&view).unwrap();
+
+        assert_eq!((first.index(), second.index()), (0, 1));
+        assert_eq!(table.add_sampler(sampler).unwrap().index(), 0);
+        assert_eq!(
+            table.add_buffer(&storage).unwrap().kind(),
+            BindlessKind::Buffer
+        );
+        assert!(matches!(
+            table.add_buffer(&uniform).unwrap_err().kind(),
+            VulkanErrorKind::MissingBufferUsage {
+                usage: BufferUsage::STORAGE
+            }
+        ));
+        assert!(matches!(
+            table.add_texture(&view).unwrap_err().kind(),
+            VulkanErrorKind::BindlessTableFull {
+                kind: BindlessKind::Texture
+            }
+        ));
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::UpdateDescriptorSets),
+            4
+        );
+
+        let timeline = TimelineSemaphore::new(&device, 0).unwrap();
+
+        table.remove(first, 5).unwrap();
+
+        assert_eq!(table.len(BindlessKind::Texture), 1);
+        assert!(matches!(
+            table.remove(first, 5).unwrap_err().kind(),
+            VulkanErrorKind::InvalidBindlessIndex
+        ));
+
+        timeline.signal(4).unwrap();
+        table.reclaim(&timeline).unwrap();
+
+        assert!(
+            table.add_texture(&view).is_err(),
+            "Slots are not reused before their last use completed"
+        );
+
+        timeline.signal(5).unwrap();
+        table.reclaim(&timeline).unwrap();
+
+        assert_eq!(table.add_texture(&view).unwrap(), first);
+        assert_eq!(table.len(BindlessKind::Texture), 2);
+
+        drop(table);
+        drop(timeline);
+        drop(samplers);
+        drop(view);
+        drop(texture);
+        drop(storage);
+        drop(uniform);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_bindless_pipeline_layout() {
+        let device = mock::device();
+
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let fragment = ShaderModule::from_words(&device, &test_shaders::fragment()).unwrap();
+        let table = BindlessTable::new(&device, &BindlessDesc::default()).unwrap();
+
+        let layout = PipelineLayout::with_bindless(&device, &[&vertex], 1, &table).unwrap();
+
+        assert_eq!(layout.set_count(), 2);
+        assert_eq!(layout.bindless_set(), Some(1));
+        assert_eq!(layout.set(1), BindlessDesc::default().bindings(1));
+
+        let allocator = DescriptorAllocator::new(&device);
+
+        assert!(allocator.allocate(&layout, 0).is_ok());
+        assert!(matches!(
+            allocator.allocate(&layout, 1).err().unwrap().kind(),
+            VulkanErrorKind::BindlessDescriptorSet { set: 1 }
+        ));
+
+        // The fragment shader's set 1 holds combined image samplers, which tables do not.
+        assert!(matches!(
+            PipelineLayout::with_bindless(&device, &[&vertex, &fragment], 1, &table)
+                .err()
+                .unwrap()
+                .kind(),
+            VulkanErrorKind::ShaderBindingMismatch { set: 1, binding: 0 }
+        ));
+
+        // Shaders indexing the table may leave its arrays unsized, or size them smaller.
+        let shader = ShaderModule::from_words(&device, &test_shaders::bindless()).unwrap();
+        let compute_layout = PipelineLayout::with_bindless(&device, &[&shader], 1, &table).unwrap();
+        let cache = PipelineCache::new(&device).unwrap();
+
+        assert!(ComputePipeline::new(&cache, &compute_layout, &shader, "main").is_ok());
+
+        drop(cache);
+        drop(compute_layout);
+        drop(shader);
+        drop(allocator);
+        drop(layout);
+        drop(table);
+        drop(vertex);
+        drop(fragment);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
@@ -0,0 +1,621 @@
+mod bindless;
+mod writer;
+
+use core::cell::{Cell, RefCell};
+
+use allocator::Allocator;
+use mayon_core::{
+    BaseError, debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+pub use bindless::{BindlessDesc, BindlessIndex, BindlessKind, BindlessTable};
+pub use writer::DescriptorWriter;
+
+use crate::{
+    DescriptorBinding, Device, PipelineLayout, ReturnCode, VulkanError, VulkanErrorKind,
+    types::{
+        self, DescriptorPoolCreateInfo, DescriptorPoolSize, DescriptorSetAllocateInfo,
+        DescriptorType,
+    },
+};
+
+/// Descriptors of each type reserved per set in new pools.
+const POOL_RATIOS: [(DescriptorType, u32); 6] = [
+    (DescriptorType::UNIFORM_BUFFER, 2),
+    (DescriptorType::STORAGE_BUFFER, 2),
+    (DescriptorType::COMBINED_IMAGE_SAMPLER, 4),
+    (DescriptorType::SAMPLED_IMAGE, 2),
+    (DescriptorType::STORAGE_IMAGE, 1),
+    (DescriptorType::SAMPLER, 1),
+];
+
+/// Sets held by the first pool. Every new pool holds twice as many as the one before, up to
+/// [`MAX_SETS_PER_POOL`].
+const INITIAL_SETS_PER_POOL: u32 = 16;
+const MAX_SETS_PER_POOL: u32 = 4096;
+
+/// Allocates descriptor sets from pools it adds as they run out.
+///
+/// Sets are never freed one by one: [`reset`](Self::reset) frees all of them at once. Keep
+/// one allocator per frame in flight, and reset it once the frame's submission completed
+/// to reuse its pools for the next frame. Like [`VulkanCommandPool`](crate::VulkanCommandPool),
+/// the allocator is `Send` but not `Sync` when the logger and allocator are `Send + Sync`:
+/// recording threads each use their own.
+pub struct DescriptorAllocator<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    /// Pools sets may still be allocated from, the last one being tried first.
+    ready: RefCell<Vec<types::DescriptorPool>>,
+    /// Pools that ran out of room since the last reset.
+    full: RefCell<Vec<types::DescriptorPool>>,
+    /// Number of sets the next pool holds.
+    sets_per_pool: Cell<u32>,
+}
+
+/// A descriptor set allocated from a [`DescriptorAllocator`], valid until the allocator is
+/// reset. Its bindings are written with a [`DescriptorWriter`].
+pub struct DescriptorSet<'d, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: &'d Device<'a, L, A>,
+    handle: types::DescriptorSet,
+    bindings: &'d [DescriptorBinding],
+}
+
+impl<'a, L, A> DescriptorAllocator<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates an allocator on `device`. Pools are only created as sets are allocated.
+    #[inline]
+    pub fn new(device: &Device<'a, L, A>) -> Self {
+        Self {
+            device: device.clone(),
+            ready: RefCell::new(Vec::new()),
+            full: RefCell::new(Vec::new()),
+            sets_per_pool: Cell::new(INITIAL_SETS_PER_POOL),
+        }
+    }
+
+    #[inline]
+    pub fn device(&self) -> &Device<'a, L, A> {
+        &self.device
+    }
+
+    /// Number of pools created so far.
+    #[inline]
+    pub fn pool_count(&self) -> usize {
+        self.ready.borrow().len() + self.full.borrow().len()
+    }
+
+    /// Allocates a set with the layout of set `set` of `layout`.
+    ///
+    /// When the current pool is out of memory or too fragmented, it is set aside until the
+    /// next reset, and the set is allocated from the next pool with room left, or from a new,
+    /// larger pool once every pool is full. Should even a new pool have no room for the set,
+    /// it is allocated from a pool holding exactly one set of the layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DescriptorSetOutOfRange` if `layout` has no set `set`,
+    /// `BindlessDescriptorSet` if `set` is bound with a bindless table, or a `VulkanError` if
+    /// creating a pool or `vkAllocateDescriptorSets` fails.
+    pub fn allocate<'d>(
+        &'d self,
+        layout: &'d PipelineLayout<'a, L, A>,
+        set: u32,
+    ) -> crate::Result<DescriptorSet<'d, 'a, L, A>> {
+        if layout.bindless_set() == Some(set) {
+            return VulkanErrorKind::BindlessDescriptorSet { set }.into_result();
+        }
+
+        let Some(set_layout) = layout.set_layout(set) else {
+            return VulkanErrorKind::DescriptorSetOutOfRange {
+                set,
+                count: layout.set_count() as u32,
+            }
+            .into_result();
+        };
+        let bindings = layout.set(set);
+
+        let handle = loop {
+            let last = self.ready.borrow().last().copied();
+            let Some(pool) = last else {
+                break self.allocate_from_new_pool(bindings, set_layout)?;
+            };
+
+            match self.allocate_from(pool, set_layout) {
+                Err(err) if is_pool_exhausted(&err) => {
+                    self.ready.borrow_mut().pop();
+                    self.full.borrow_mut().push(pool);
+                }
+                result => break result?,
+            }
+        };
+
+        Ok(DescriptorSet {
+            device: &self.device,
+            handle,
+            bindings,
+        })
+    }
+
+    /// Frees every set allocated so far, making the room of every pool available again.
+    ///
+    /// No pending submission may still use the sets.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkResetDescriptorPool` fails.
+    pub fn reset(&mut self) -> crate::Result<()> {
+        let ready = self.ready.get_mut();
+        let full = self.full.get_mut();
+
+        for &pool in ready.iter().chain(full.iter()) {
+            unsafe {
+                self.device
+                    .fns
+                    .reset_descriptor_pool(self.device.handle, pool)
+            }?;
+        }
+
+        ready.append(full);
+
+        Ok(())
+    }
+
+    fn allocate_from(
+        &self,
+        pool: types::DescriptorPool,
+        set_layout: types::DescriptorSetLayout,
+    ) -> crate::Result<types::DescriptorSet> {
+        let set_layouts = [set_layout];
+
+        unsafe {
+            self.device.fns.allocate_descriptor_set(
+                self.device.handle,
+                &DescriptorSetAllocateInfo::new(pool, &set_layouts),
+            )
+        }
+    }
+
+    /// Allocates a set from a new pool, falling back to a pool sized to the set alone if the
+    /// new pool has no room for it.
+    fn allocate_from_new_pool(
+        &self,
+        bindings: &[DescriptorBinding],
+        set_layout: types::DescriptorSetLayout,
+    ) -> crate::Result<types::DescriptorSet> {
+        let max_sets = self.sets_per_pool.get();
+        let pool = self.create_pool(max_sets, &pool_sizes(bindings, max_sets, &POOL_RATIOS))?;
+
+        self.ready.borrow_mut().push(pool);
+        self.sets_per_pool
+            .set((max_sets * 2).min(MAX_SETS_PER_POOL));
+
+        match self.allocate_from(pool, set_layout) {
+            Err(err) if is_pool_exhausted(&err) => {
+                self.ready.borrow_mut().pop();
+                self.full.borrow_mut().push(pool);
+            }
+            result => return result,
+        }
+
+        let pool = self.create_pool(1, &pool_sizes(bindings, 1, &[]))?;
+
+        // The pool is full once it holds its set.
+        self.full.borrow_mut().push(pool);
+
+        self.allocate_from(pool, set_layout)
+    }
+
+    fn create_pool(
+        &self,
+        max_sets: u32,
+        pool_sizes: &[DescriptorPoolSize],
+    ) -> crate::Result<types::DescriptorPool> {
+        let pool = unsafe {
+            self.device.fns.create_descriptor_pool(
+                self.device.handle,
+                &DescriptorPoolCreateInfo::new(max_sets, pool_sizes),
+                self.device.instance().backend().allocator(),
+            )
+        }?;
+
+        debug!(
+            self.device.instance().logger(),
+            LogTarget::Backend,
+            "Vulkan descriptor pool created: {:?} for {} sets",
+            pool,
+            max_sets
+        );
+
+        Ok(pool)
+    }
+}
+
+/// Descriptor counts of a pool holding `max_sets` sets: per set, `ratios` descriptors of
+/// each type, raised to the total count of the type in `bindings` so the pool fits
+/// `max_sets` sets of them.
+fn pool_sizes(
+    bindings: &[DescriptorBinding],
+    max_sets: u32,
+    ratios: &[(DescriptorType, u32)],
+) -> Vec<DescriptorPoolSize> {
+    let mut per_set = Vec::<(DescriptorType, u32)>::new();
+
+    for binding in bindings {
+        let descriptor_type = binding.kind.into();
+
+        match per_set.iter_mut().find(|(ty, _)| *ty == descriptor_type) {
+            Some((_, count)) => *count = count.saturating_add(binding.count),
+            None => per_set.push((descriptor_type, binding.count)),
+        }
+    }
+
+    for &(descriptor_type, ratio) in ratios {
+        match per_set.iter_mut().find(|(ty, _)| *ty == descriptor_type) {
+            Some((_, count)) => *count = (*count).max(ratio),
+            None => per_set.push((descriptor_type, ratio)),
+        }
+    }
+
+    per_set
+        .into_iter()
+        .filter(|&(_, count)| count > 0)
+        .map(|(descriptor_type, count)| DescriptorPoolSize {
+            descriptor_type,
+            descriptor_count: count.saturating_mul(max_sets),
+        })
+        .collect()
+}
+
+/// Whether `err` reports a pool without room for another set.
+#[inline]
+fn is_pool_exhausted(err: &VulkanError) -> bool {
+    matches!(
+        err.kind(),
+        VulkanErrorKind::FunctionReturn {
+            code: ReturnCode::OutOfPoolMemory | ReturnCode::FragmentedPool,
+            ..
+        }
+    )
+}
+
+impl<L, A> Drop for DescriptorAllocator<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        let device = &self.device;
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            for pool in self
+                .ready
+                .get_mut()
+                .drain(..)
+                .chain(self.full.get_mut().drain(..))
+            {
+                device
+                    .fns
+                    .destroy_descriptor_pool(device.handle, pool, allocator);
+            }
+        }
+    }
+}
+
+impl<L, A> DescriptorSet<'_, '_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    #[inline]
+    pub(crate) fn handle(&self) -> types::DescriptorSet {
+        self.handle
+    }
+
+    /// Bindings of the set, ordered by binding.
+    #[inline]
+    pub fn bindings(&self) -> &[DescriptorBinding] {
+        self.bindings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::Format;
+
+    use super::*;
+    use crate::{
+        Buffer, BufferUsage, DescriptorKind, MemoryUsage, SamplerCache, SamplerDesc, ShaderModule,
+        ShaderStages, Texture, TextureDesc, TextureDimension, TextureUsage, TextureView,
+        VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+        shader::test_shaders,
+    };
+
+    #[test]
+    fn test_allocate_descriptor_sets() {
+        let device = mock::device();
+
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let fragment = ShaderModule::from_words(&device, &test_shaders::fragment()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&vertex, &fragment]).unwrap();
+
+        let mut allocator = DescriptorAllocator::new(&device);
+
+        assert_eq!(allocator.pool_count(), 0);
+        assert!(matches!(
+            allocator.allocate(&layout, 2).err().unwrap().kind(),
+            VulkanErrorKind::DescriptorSetOutOfRange { set: 2, count: 2 }
+        ));
+
+        let sets = (0..INITIAL_SETS_PER_POOL)
+            .map(|_| allocator.allocate(&layout, 0).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(allocator.pool_count(), 1);
+
+        let grown = allocator.allocate(&layout, 1).unwrap();
+
+        assert_eq!(allocator.pool_count(), 2, "A full pool adds a new one");
+        assert_eq!(grown.bindings(), layout.set(1));
+
+        MockDriver::fail_next(
+            VulkanFunctionName::AllocateDescriptorSets,
+            ReturnCode::FragmentedPool,
+        );
+        allocator.allocate(&layout, 0).unwrap();
+
+        assert_eq!(
+            allocator.pool_count(),
+            3,
+            "A fragmented pool adds a new one"
+        );
+
+        drop(sets);
+        allocator.reset().unwrap();
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::ResetDescriptorPool),
+            3
+        );
+
+        for _ in 0..INITIAL_SETS_PER_POOL * 4 {
+            allocator.allocate(&layout, 0).unwrap();
+        }
+
+        assert_eq!(allocator.pool_count(), 3, "Reset pools are reused");
+
+        drop(allocator);
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyDescriptorPool),
+            3
+        );
+
+        drop(layout);
+        drop(vertex);
+        drop(fragment);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_pool_sizes() {
+        let binding = |binding, kind, count| DescriptorBinding {
+            set: 0,
+            binding,
+            kind,
+            count,
+            stages: ShaderStages::FRAGMENT,
+        };
+        let bindings = [
+            binding(0, DescriptorKind::CombinedImageSampler, 3),
+            binding(1, DescriptorKind::CombinedImageSampler, 4),
+            binding(2, DescriptorKind::UniformBuffer, 1),
+        ];
+
+        let size = |descriptor_type, descriptor_count| DescriptorPoolSize {
+            descriptor_type,
+            descriptor_count,
+        };
+
+        assert_eq!(
+            pool_sizes(&bindings, 8, &[]),
+            [
+                size(DescriptorType::COMBINED_IMAGE_SAMPLER, 56),
+                size(DescriptorType::UNIFORM_BUFFER, 8),
+            ],
+            "Counts of a type are summed over the bindings"
+        );
+        assert_eq!(
+            pool_sizes(&bindings, 8, &POOL_RATIOS),
+            [
+                size(DescriptorType::COMBINED_IMAGE_SAMPLER, 56),
+                size(DescriptorType::UNIFORM_BUFFER, 16),
+                size(DescriptorType::STORAGE_BUFFER, 16),
+                size(DescriptorType::SAMPLED_IMAGE, 16),
+                size(DescriptorType::STORAGE_IMAGE, 8),
+                size(DescriptorType::SAMPLER, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_new_pool_without_room() {
+        let device = mock::device();
+
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let fragment = ShaderModule::from_words(&device, &test_shaders::fragment()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&vertex, &fragment]).unwrap();
+
+        let allocator = DescriptorAllocator::new(&device);
+
+        MockDriver::fail_next(
+            VulkanFunctionName::AllocateDescriptorSets,
+            ReturnCode::OutOfPoolMemory,
+        );
+        allocator.allocate(&layout, 0).unwrap();
+
+        assert_eq!(
+            allocator.pool_count(),
+            2,
+            "A pool sized to the set is added"
+        );
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::AllocateDescriptorSets),
+            2
+        );
+
+        drop(allocator);
+        drop(layout);
+        drop(vertex);
+        drop(fragment);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_write_descriptor_set() {
+        let device = mock::device();
+
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let fragment = ShaderModule::from_words(&device, &test_shaders::fragment()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&vertex, &fragment]).unwrap();
+
+        let uniforms =
+            Buffer::<[f32; 16], _, _>::new(&device, 1, BufferUsage::UNIFORM, MemoryUsage::Upload)
+                .unwrap();
+        let vertices =
+            Buffer::<[f32; 16], _, _>::new(&device, 1, BufferUsage::VERTEX, MemoryUsage::Upload)
+                .unwrap();
+
+        let desc = TextureDesc::new(TextureDimension::D2, Format::Rgba8Srgb, 4, 4);
+        let sampled = Texture::new(&device, &desc).unwrap();
+        let storage = Texture::new(
+            &device,
+            &TextureDesc {
+                usage: TextureUsage::STORAGE,
+                ..desc
+            },
+        )
+        .unwrap();
+        let sampled_view = TextureView::new(&sampled).unwrap();
+        let storage_view = TextureView::new(&storage).unwrap();
+        let samplers = SamplerCache::new(&device);
+        let sampler = samplers.get(&SamplerDesc::default()).unwrap();
+
+        let allocator = DescriptorAllocator::new(&device);
+        let camera = allocator.allocate(&layout, 0).unwrap();
+        let material = allocator.allocate(&layout, 1).unwrap();
+
+        DescriptorWriter::new(&camera)
+            .uniform_buffer(0, &uniforms)
+            .update()
+            .unwrap();
+        DescriptorWriter::new(&material)
+            .combined_texture_sampler(0, &sampled_view, sampler)
+            .update()
+            .unwrap();
+
+        // Set 1 binding 0 is an array of four combined image samplers.
+        DescriptorWriter::new(&material)
+            .combined_texture_sampler_array(0, 2, &[(&sampled_view, sampler); 2])
+            .update()
+            .unwrap();
+
+        assert!(matches!(
+            DescriptorWriter::new(&material)
+                .combined_texture_sampler_array(0, 3, &[(&sampled_view, sampler); 2])
+                .update()
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::DescriptorArrayOutOfRange {
+                binding: 0,
+                element: 3,
+                len: 2,
+                count: 4
+            }
+        ));
+        assert!(matches!(
+            DescriptorWriter::new(&camera)
+                .uniform_buffer_array(0, 1, &[&uniforms])
+                .update()
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::DescriptorArrayOutOfRange { binding: 0, .. }
+        ));
+        assert!(matches!(
+            DescriptorWriter::new(&camera)
+                .uniform_buffer(3, &uniforms)
+                .update()
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::UnknownDescriptorBinding { binding: 3 }
+        ));
+        assert!(matches!(
+            DescriptorWriter::new(&camera)
+                .storage_buffer(0, &uniforms)
+                .update()
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::DescriptorKindMismatch {
+                binding: 0,
+                expected: crate::DescriptorKind::UniformBuffer
+            }
+        ));
+        assert!(matches!(
+            DescriptorWriter::new(&camera)
+                .uniform_buffer(0, &vertices)
+                .update()
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::MissingBufferUsage {
+                usage: BufferUsage::UNIFORM
+            }
+        ));
+        assert!(matches!(
+            DescriptorWriter::new(&material)
+                .combined_texture_sampler(0, &storage_view, sampler)
+                .update()
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::MissingTextureUsage {
+                usage: TextureUsage::SAMPLED
+            }
+        ));
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::UpdateDescriptorSets),
+            3,
+            "Invalid writes are rejected before reaching the driver"
+        );
+
+        drop(allocator);
+        drop(samplers);
+        drop(sampled_view);
+        drop(storage_view);
+        drop(sampled);
+        drop(storage);
+        drop(uniforms);
+        drop(vertices);
+        drop(layout);
+        drop(vertex);
+        drop(fragment);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
@@ -0,0 +1,363 @@
+use allocator::Allocator;
+use mayon_core::logger::Logger;
+
+use super::DescriptorSet;
+use crate::{
+    Buffer, BufferUsage, DescriptorKind, Sampler, TextureUsage, TextureView, VulkanErrorKind,
+    types::{DescriptorBufferInfo, DescriptorImageInfo, ImageLayout, WriteDescriptorSet},
+};
+
+/// Writes resources to the bindings of a [`DescriptorSet`].
+///
+/// Each method writes the first element of a binding; its `_array` variant writes
+/// consecutive elements from `first_element`. Nothing reaches the set until
+/// [`update`](Self::update), which checks every write against the set's layout first.
+///
+/// The resources must stay alive as long as the set is used by pending submissions.
+#[must_use]
+pub struct DescriptorWriter<'w, 'd, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    set: &'w DescriptorSet<'d, 'a, L, A>,
+    writes: Vec<Write>,
+}
+
+struct Write {
+    binding: u32,
+    first_element: u32,
+    kind: DescriptorKind,
+    resources: Resources,
+    /// The usage the resource lacks for `kind`, if any.
+    missing: Option<VulkanErrorKind>,
+}
+
+/// The sampler and view written to an image descriptor, either of which may be unused.
+type ImageResource<'r, 'a, L, A> = (Option<Sampler<'r>>, Option<&'r TextureView<'r, 'a, L, A>>);
+
+enum Resources {
+    Buffers(Vec<DescriptorBufferInfo>),
+    Images(Vec<DescriptorImageInfo>),
+}
+
+impl Resources {
+    #[inline]
+    fn len(&self) -> usize {
+        match self {
+            Self::Buffers(buffers) => buffers.len(),
+            Self::Images(images) => images.len(),
+        }
+    }
+}
+
+impl<'w, 'd, 'a, L, A> DescriptorWriter<'w, 'd, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    #[inline]
+    pub fn new(set: &'w DescriptorSet<'d, 'a, L, A>) -> Self {
+        Self {
+            set,
+            writes: Vec::new(),
+        }
+    }
+
+    /// Binds the whole of `buffer`, which needs [`BufferUsage::UNIFORM`], to a uniform
+    /// buffer binding.
+    #[inline]
+    pub fn uniform_buffer<T: Copy>(self, binding: u32, buffer: &Buffer<'a, T, L, A>) -> Self {
+        self.uniform_buffer_array(binding, 0, &[buffer])
+    }
+
+    /// Binds the whole of each of `buffers` to consecutive elements of a uniform buffer
+    /// binding.
+    #[inline]
+    pub fn uniform_buffer_array<T: Copy>(
+        self,
+        binding: u32,
+        first_element: u32,
+        buffers: &[&Buffer<'a, T, L, A>],
+    ) -> Self {
+        self.buffers(
+            (binding, first_element),
+            DescriptorKind::UniformBuffer,
+            buffers,
+            BufferUsage::UNIFORM,
+        )
+    }
+
+    /// Binds the whole of `buffer`, which needs [`BufferUsage::STORAGE`], to a storage
+    /// buffer binding.
+    #[inline]
+    pub fn storage_buffer<T: Copy>(self, binding: u32, buffer: &Buffer<'a, T, L, A>) -> Self {
+        self.storage_buffer_array(binding, 0, &[buffer])
+    }
+
+    /// Binds the whole of each of `buffers` to consecutive elements of a storage buffer
+    /// binding.
+    #[inline]
+    pub fn storage_buffer_array<T: Copy>(
+        self,
+        binding: u32,
+        first_element: u32,
+        buffers: &[&Buffer<'a, T, L, A>],
+    ) -> Self {
+        self.buffers(
+            (binding, first_element),
+            DescriptorKind::StorageBuffer,
+            buffers,
+            BufferUsage::STORAGE,
+        )
+    }
+
+    /// Binds `view` to a sampled image binding. The texture, which needs
+    /// [`TextureUsage::SAMPLED`], must be in its shader read-only layout when used.
+    #[inline]
+    pub fn sampled_texture(self, binding: u32, view: &TextureView<'_, 'a, L, A>) -> Self {
+        self.sampled_texture_array(binding, 0, &[view])
+    }
+
+    /// Binds `views` to consecutive elements of a sampled image binding.
+    #[inline]
+    pub fn sampled_texture_array(
+        self,
+        binding: u32,
+        first_element: u32,
+        views: &[&TextureView<'_, 'a, L, A>],
+    ) -> Self {
+        self.images(
+            (binding, first_element),
+            DescriptorKind::SampledImage,
+            &views
+                .iter()
+                .map(|&view| (None, Some(view)))
+                .collect::<Vec<_>>(),
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        )
+    }
+
+    /// Binds `view` to a storage image binding. The texture, which needs
+    /// [`TextureUsage::STORAGE`], must be in the general layout when used.
+    #[inline]
+    pub fn storage_texture(self, binding: u32, view: &TextureView<'_, 'a, L, A>) -> Self {
+        self.storage_texture_array(binding, 0, &[view])
+    }
+
+    /// Binds `views` to consecutive elements of a storage image binding.
+    #[inline]
+    pub fn storage_texture_array(
+        self,
+        binding: u32,
+        first_element: u32,
+        views: &[&TextureView<'_, 'a, L, A>],
+    ) -> Self {
+        self.images(
+            (binding, first_element),
+            DescriptorKind::StorageImage,
+            &views
+                .iter()
+                .map(|&view| (None, Some(view)))
+                .collect::<Vec<_>>(),
+            ImageLayout::GENERAL,
+        )
+    }
+
+    /// Binds `view` and `sampler` to a combined image sampler binding. The texture, which
+    /// needs [`TextureUsage::SAMPLED`], must be in its shader read-only layout when used.
+    #[inline]
+    pub fn combined_texture_sampler(
+        self,
+        binding: u32,
+        view: &TextureView<'_, 'a, L, A>,
+        sampler: Sampler<'_>,
+    ) -> Self {
+        self.combined_texture_sampler_array(binding, 0, &[(view, sampler)])
+    }
+
+    /// Binds each view and sampler of `pairs` to consecutive elements of a combined image
+    /// sampler binding.
+    #[inline]
+    pub fn combined_texture_sampler_array(
+        self,
+        binding: u32,
+        first_element: u32,
+        pairs: &[(&TextureView<'_, 'a, L, A>, Sampler<'_>)],
+    ) -> Self {
+        self.images(
+            (binding, first_element),
+            DescriptorKind::CombinedImageSampler,
+            &pairs
+                .iter()
+                .map(|&(view, sampler)| (Some(sampler), Some(view)))
+                .collect::<Vec<_>>(),
+            ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+        )
+    }
+
+    /// Binds `sampler` to a sampler binding.
+    #[inline]
+    pub fn sampler(self, binding: u32, sampler: Sampler<'_>) -> Self {
+        self.sampler_array(binding, 0, &[sampler])
+    }
+
+    /// Binds `samplers` to consecutive elements of a sampler binding.
+    #[inline]
+    pub fn sampler_array(self, binding: u32, first_element: u32, samplers: &[Sampler<'_>]) -> Self {
+        self.images(
+            (binding, first_element),
+            DescriptorKind::Sampler,
+            &samplers
+                .iter()
+                .map(|&sampler| (Some(sampler), None))
+                .collect::<Vec<_>>(),
+            ImageLayout::UNDEFINED,
+        )
+    }
+
+    /// Writes every binding to the set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnknownDescriptorBinding` if the set has no such binding,
+    /// `DescriptorKindMismatch` if a binding holds another kind of descriptor,
+    /// `DescriptorArrayOutOfRange` if a write goes past the end of its binding's array, or
+    /// `MissingBufferUsage` or `MissingTextureUsage` if a resource was not created with the
+    /// usage its binding needs. The set is left untouched on error.
+    pub fn update(self) -> crate::Result<()> {
+        for write in &self.writes {
+            let Some(binding) = self
+                .set
+                .bindings()
+                .iter()
+                .find(|binding| binding.binding == write.binding)
+            else {
+                return VulkanErrorKind::UnknownDescriptorBinding {
+                    binding: write.binding,
+                }
+                .into_result();
+            };
+
+            if binding.kind != write.kind {
+                return VulkanErrorKind::DescriptorKindMismatch {
+                    binding: write.binding,
+                    expected: binding.kind,
+                }
+                .into_result();
+            }
+
+            let len = write.resources.len();
+
+            if u64::from(write.first_element) + len as u64 > u64::from(binding.count) {
+                return VulkanErrorKind::DescriptorArrayOutOfRange {
+                    binding: write.binding,
+                    element: write.first_element,
+                    len,
+                    count: binding.count,
+                }
+                .into_result();
+            }
+
+            if let Some(missing) = write.missing {
+                return missing.into_result();
+            }
+        }
+
+        let handle = self.set.handle();
+        let descriptor_writes = self
+            .writes
+            .iter()
+            .filter(|write| write.resources.len() > 0)
+            .map(|write| {
+                let dst = (handle, write.binding, write.first_element);
+                let descriptor_type = write.kind.into();
+
+                match &write.resources {
+                    Resources::Buffers(infos) => {
+                        WriteDescriptorSet::buffers(dst, descriptor_type, infos)
+                    }
+                    Resources::Images(infos) => {
+                        WriteDescriptorSet::images(dst, descriptor_type, infos)
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let device = self.set.device;
+
+        unsafe {
+            device
+                .fns
+                .update_descriptor_sets(device.handle, &descriptor_writes)
+        };
+
+        Ok(())
+    }
+
+    fn buffers<T: Copy>(
+        mut self,
+        (binding, first_element): (u32, u32),
+        kind: DescriptorKind,
+        buffers: &[&Buffer<'a, T, L, A>],
+        usage: BufferUsage,
+    ) -> Self {
+        self.writes.push(Write {
+            binding,
+            first_element,
+            kind,
+            resources: Resources::Buffers(
+                buffers
+                    .iter()
+                    .map(|buffer| DescriptorBufferInfo {
+                        buffer: buffer.handle(),
+                        offset: 0,
+                        range: DescriptorBufferInfo::WHOLE_SIZE,
+                    })
+                    .collect(),
+            ),
+            missing: buffers
+                .iter()
+                .any(|buffer| !buffer.usage().contains(usage))
+                .then_some(VulkanErrorKind::MissingBufferUsage { usage }),
+        });
+
+        self
+    }
+
+    fn images(
+        mut self,
+        (binding, first_element): (u32, u32),
+        kind: DescriptorKind,
+        resources: &[ImageResource<'_, 'a, L, A>],
+        image_layout: ImageLayout,
+    ) -> Self {
+        let usage = match kind {
+            DescriptorKind::StorageImage => TextureUsage::STORAGE,
+            _ => TextureUsage::SAMPLED,
+        };
+
+        self.writes.push(Write {
+            binding,
+            first_element,
+            kind,
+            resources: Resources::Images(
+                resources
+                    .iter()
+                    .map(|(sampler, view)| DescriptorImageInfo {
+                        sampler: sampler.map(|sampler| sampler.handle()),
+                        image_view: view.map(TextureView::handle),
+                        image_layout,
+                    })
+                    .collect(),
+            ),
+            missing: resources
+                .iter()
+                .filter_map(|(_, view)| *view)
+                .any(|view| !view.texture().usage().contains(usage))
+                .then_some(VulkanErrorKind::MissingTextureUsage { usage }),
+        });
+
+        self
+    }
+}
@@ -0,0 +1,210 @@
+use core::ptr::NonNull;
+
+use allocator::{
+    AllocationRequest, DeviceAllocError, DeviceAllocation, DeviceAllocator, HeapStatistics,
+    MemoryDevice,
+};
+
+use crate::{
+    VulkanDevice, VulkanError, VulkanErrorKind,
+    fn_table::DeviceFnTable,
+    types::{AllocationCallbacksRef, Device, DeviceMemory, MemoryAllocateInfo},
+};
+
+/// Hands out memory objects of a device to its block allocator.
+struct MemorySource<'d, 'a> {
+    fns: &'d DeviceFnTable,
+    device: Device,
+    allocator: AllocationCallbacksRef<'a>,
+}
+
+unsafe impl MemoryDevice for MemorySource<'_, '_> {
+    type Memory = DeviceMemory;
+    type Error = VulkanError;
+
+    #[inline]
+    unsafe fn allocate_memory(
+        &self,
+        size: u64,
+        memory_type_index: u32,
+    ) -> crate::Result<DeviceMemory> {
+        unsafe {
+            self.fns.allocate_memory(
+                self.device,
+                &MemoryAllocateInfo::new(size, memory_type_index),
+                self.allocator,
+            )
+        }
+    }
+
+    #[inline]
+    unsafe fn map_memory(&self, memory: DeviceMemory, size: u64) -> crate::Result<NonNull<u8>> {
+        unsafe { self.fns.map_memory(self.device, memory, size) }
+    }
+
+    #[inline]
+    unsafe fn free_memory(&self, memory: DeviceMemory) {
+        unsafe { self.fns.free_memory(self.device, memory, self.allocator) }
+    }
+}
+
+impl VulkanDevice {
+    #[inline]
+    fn memory_source<'a>(&self, allocator: AllocationCallbacksRef<'a>) -> MemorySource<'_, 'a> {
+        MemorySource {
+            fns: &self.fns,
+            device: self.handle,
+            allocator,
+        }
+    }
+
+    /// Allocates device memory for `request` from the device's block allocator.
+    ///
+    /// `allocator` must be the instance's allocation callbacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoSuitableMemoryType` if no memory type allowed by the request fits its usage,
+    /// or a `VulkanError` if `vkAllocateMemory` fails for every candidate type.
+    pub(crate) unsafe fn allocate_memory(
+        &self,
+        request: &AllocationRequest,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<DeviceAllocation<DeviceMemory>> {
+        let source = self.memory_source(allocator);
+
+        match unsafe { self.memory_allocator.allocate(&source, request) } {
+            Ok(allocation) => Ok(allocation),
+            Err(DeviceAllocError::NoSuitableMemoryType) => {
+                VulkanErrorKind::NoSuitableMemoryType.into_result()
+            }
+            Err(DeviceAllocError::Device(err)) => Err(err),
+        }
+    }
+
+    /// Returns `allocation` to the device's block allocator.
+    ///
+    /// Resources bound to the allocation must already be destroyed.
+    pub(crate) unsafe fn free_memory(
+        &self,
+        allocation: &DeviceAllocation<DeviceMemory>,
+        allocator: AllocationCallbacksRef,
+    ) {
+        let source = self.memory_source(allocator);
+
+        unsafe { self.memory_allocator.deallocate(&source, allocation) }
+    }
+
+    /// Frees every memory object of the block allocator, ahead of destroying the device.
+    pub(super) unsafe fn release_memory(&self, allocator: AllocationCallbacksRef) {
+        let source = self.memory_source(allocator);
+
+        unsafe { self.memory_allocator.release(&source) }
+    }
+
+    /// Device memory use of every memory heap, in heap order.
+    #[inline]
+    pub fn memory_statistics(&self) -> Vec<HeapStatistics> {
+        self.memory_allocator.statistics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use allocator::MemoryUsage;
+    use mayon_core::BaseError;
+
+    use super::*;
+    use crate::{
+        Texture, TextureDesc, TextureDimension, VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+    };
+
+    #[test]
+    fn test_images_share_a_block() {
+        let device = mock::device();
+
+        let desc = TextureDesc::new(TextureDimension::D2, mayon_core::Format::Rgba8Unorm, 64, 64);
+        let textures = [
+            Texture::new(&device, &desc).unwrap(),
+            Texture::new(&device, &desc).unwrap(),
+        ];
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::AllocateMemory),
+            1,
+            "The textures are sub-allocated from one block"
+        );
+
+        let statistics = device.memory_statistics();
+
+        assert_eq!(statistics.len(), 2);
+        assert_eq!(statistics[0].memory_objects, 1);
+        assert_eq!(statistics[0].allocations, 2);
+        assert_eq!(statistics[0].used_bytes, 2 << 16);
+        assert_eq!(statistics[1].memory_objects, 0);
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+            let buffer = device
+                .allocate_memory(&AllocationRequest::new(256, 256, 0b01), allocator)
+                .unwrap();
+
+            assert_eq!(
+                MockDriver::call_count(VulkanFunctionName::AllocateMemory),
+                2,
+                "Buffers do not share a block with optimal images"
+            );
+            assert_eq!(device.memory_statistics()[0].memory_objects, 2);
+
+            device.free_memory(&buffer, allocator);
+        }
+
+        drop(textures);
+
+        assert_eq!(device.memory_statistics()[0].allocations, 0);
+
+        drop(device);
+
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::FreeMemory), 2);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let device = mock::device();
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            let upload = device
+                .allocate_memory(
+                    &AllocationRequest::new(256, 16, 0b11).with_usage(MemoryUsage::Upload),
+                    allocator,
+                )
+                .unwrap();
+
+            assert_eq!(
+                upload.memory_type_index(),
+                1,
+                "Upload memory is host visible"
+            );
+
+            let Err(err) = device.allocate_memory(
+                &AllocationRequest::new(256, 16, 0b01).with_usage(MemoryUsage::Upload),
+                allocator,
+            ) else {
+                panic!("Device-local memory cannot serve uploads");
+            };
+
+            assert!(matches!(err.kind(), VulkanErrorKind::NoSuitableMemoryType));
+
+            device.free_memory(&upload, allocator);
+        }
+
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
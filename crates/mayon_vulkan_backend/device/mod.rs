@@ -0,0 +1,156 @@
+mod memory;
+mod queue_families;
+mod render_passes;
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use allocator::{Allocator, BlockAllocator};
+use mayon_core::{DestroyDevice, DeviceHandler, InstanceRef, QueueKind, logger::Logger};
+
+pub(crate) use queue_families::QueueFamilies;
+pub(crate) use render_passes::{AttachmentKey, RenderPassCache, RenderPassKey};
+
+use crate::{
+    VulkanBackend, VulkanVersion,
+    fn_table::DeviceFnTable,
+    types::{Device, DeviceMemory, PhysicalDevice, Queue},
+};
+
+/// First Vulkan version with timeline semaphores in core.
+pub(crate) const TIMELINE_SEMAPHORE_VERSION: VulkanVersion = VulkanVersion::new(1, 2, 0);
+
+/// First Vulkan version with descriptor indexing in core, where its features are optional.
+pub(crate) const DESCRIPTOR_INDEXING_VERSION: VulkanVersion = VulkanVersion::new(1, 2, 0);
+
+/// First Vulkan version with dynamic rendering in core.
+pub(crate) const DYNAMIC_RENDERING_VERSION: VulkanVersion = VulkanVersion::new(1, 3, 0);
+
+pub struct VulkanDevice {
+    pub(crate) handle: Device,
+    pub(crate) physical_device: PhysicalDevice,
+    /// Vulkan version usable on this device: the lower of the adapter's and the instance's.
+    pub(crate) api_version: VulkanVersion,
+    /// Sub-allocates the memory of images and buffers from larger memory objects.
+    pub(crate) memory_allocator: BlockAllocator<DeviceMemory>,
+    pub(crate) graphics_queue: Option<VulkanQueue>,
+    pub(crate) compute_queue: VulkanQueue,
+    pub(crate) transfer_queue: VulkanQueue,
+    pub(crate) fns: DeviceFnTable,
+    /// Whether rendering uses `vkCmdBeginRendering` rather than render pass objects.
+    pub(crate) dynamic_rendering: bool,
+    pub(crate) render_passes: RenderPassCache,
+    /// Whether the descriptor indexing features of bindless tables are enabled.
+    pub(crate) descriptor_indexing: bool,
+    /// Queues are externally synchronized; submissions and presentation from any thread
+    /// hold this lock.
+    pub(crate) queue_lock: Mutex<()>,
+}
+
+/// A queue retrieved from the device together with the family it belongs to.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct VulkanQueue {
+    pub(crate) handle: Queue,
+    pub(crate) family_index: u32,
+}
+
+impl VulkanDevice {
+    /// Highest Vulkan version whose device-level functionality can be used.
+    ///
+    /// This is the lower of [`VulkanAdapter::api_version`](crate::VulkanAdapter::api_version)
+    /// and the instance's negotiated version.
+    #[inline]
+    pub fn api_version(&self) -> VulkanVersion {
+        self.api_version
+    }
+
+    /// Whether rendering uses dynamic rendering, from Vulkan 1.3 or `VK_KHR_dynamic_rendering`.
+    ///
+    /// Otherwise, render passes and framebuffers are created and cached behind the scenes.
+    #[inline]
+    pub fn dynamic_rendering(&self) -> bool {
+        self.dynamic_rendering
+    }
+
+    /// Whether [`BindlessTable`](crate::BindlessTable)s can be created, which takes Vulkan
+    /// 1.2 and an adapter supporting the descriptor indexing features they rely on.
+    #[inline]
+    pub fn descriptor_indexing(&self) -> bool {
+        self.descriptor_indexing
+    }
+
+    /// Queue family used for graphics work, if the adapter supports graphics.
+    #[inline]
+    pub fn graphics_queue_family(&self) -> Option<u32> {
+        self.graphics_queue.map(|queue| queue.family_index)
+    }
+
+    /// Queue family used for compute work.
+    ///
+    /// This is a dedicated compute family when the adapter exposes one.
+    #[inline]
+    pub fn compute_queue_family(&self) -> u32 {
+        self.compute_queue.family_index
+    }
+
+    /// Queue family used for transfer work.
+    ///
+    /// This is a dedicated transfer family when the adapter exposes one.
+    #[inline]
+    pub fn transfer_queue_family(&self) -> u32 {
+        self.transfer_queue.family_index
+    }
+
+    /// The distinct queue families of the device's queues, in ascending order.
+    pub(crate) fn queue_families(&self) -> Vec<u32> {
+        let mut families = Vec::with_capacity(3);
+
+        families.extend(self.graphics_queue_family());
+        families.push(self.compute_queue.family_index);
+        families.push(self.transfer_queue.family_index);
+
+        families.sort_unstable();
+        families.dedup();
+
+        families
+    }
+
+    /// The queue used for `kind` work, if the device has one.
+    #[inline]
+    pub(crate) fn queue(&self, kind: QueueKind) -> Option<VulkanQueue> {
+        match kind {
+            QueueKind::Graphics => self.graphics_queue,
+            QueueKind::Compute => Some(self.compute_queue),
+            QueueKind::Transfer => Some(self.transfer_queue),
+        }
+    }
+
+    /// Locks the device's queues for a submission or presentation.
+    #[inline]
+    pub(crate) fn lock_queues(&self) -> MutexGuard<'_, ()> {
+        // The lock guards no data, so a panic while holding it leaves nothing inconsistent.
+        self.queue_lock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl DeviceHandler for VulkanDevice {}
+
+impl<'a, L, A> DestroyDevice<VulkanBackend<'a, L, A>, L, A> for VulkanDevice
+where
+    L: Logger,
+    A: Allocator,
+{
+    fn destroy(&self, instance: &InstanceRef<VulkanBackend<'a, L, A>, L, A>) {
+        unsafe {
+            // Nothing useful can be done if the device is lost at this point; destroy it anyway.
+            let _ = self.fns.device_wait_idle(self.handle);
+
+            self.release_render_passes(instance.backend().allocator());
+            self.release_memory(instance.backend().allocator());
+
+            self.fns
+                .destroy_device(self.handle, instance.backend().allocator());
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::types::{QueueFamilyProperties, QueueFlags};
+
+/// Queue family indices chosen for a logical device.
+///
+/// Graphics is optional so that compute-only adapters can still be opened. Compute and
+/// transfer prefer dedicated families, which lets the driver run them asynchronously to
+/// graphics work, and fall back to a more general family otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct QueueFamilies {
+    pub graphics: Option<u32>,
+    pub compute: u32,
+    pub transfer: u32,
+}
+
+impl QueueFamilies {
+    /// Picks queue families from `properties`, indexed by queue family index.
+    ///
+    /// Returns `None` if no family supports compute.
+    pub(crate) fn select(properties: &[QueueFamilyProperties]) -> Option<Self> {
+        let find = |required: QueueFlags, excluded: QueueFlags| {
+            properties
+                .iter()
+                .position(|family| {
+                    family.queue_count > 0
+                        && family.queue_flags.contains(required)
+                        && !family.queue_flags.intersects(excluded)
+                })
+                .map(|index| index as u32)
+        };
+
+        let none = QueueFlags(0);
+
+        let graphics = find(QueueFlags::GRAPHICS, none);
+
+        let compute = find(QueueFlags::COMPUTE, QueueFlags::GRAPHICS)
+            .or_else(|| find(QueueFlags::GRAPHICS.union(QueueFlags::COMPUTE), none))
+            .or_else(|| find(QueueFlags::COMPUTE, none))?;
+
+        // Graphics and compute families implicitly support transfer operations.
+        let transfer = find(
+            QueueFlags::TRANSFER,
+            QueueFlags::GRAPHICS.union(QueueFlags::COMPUTE),
+        )
+        .unwrap_or(compute);
+
+        Some(Self {
+            graphics,
+            compute,
+            transfer,
+        })
+    }
+
+    /// Returns the distinct family indices, each listed once, in ascending order.
+    pub(crate) fn unique(&self) -> Vec<u32> {
+        let mut families = Vec::with_capacity(3);
+
+        families.extend(self.graphics);
+        families.push(self.compute);
+        families.push(self.transfer);
+
+        families.sort_unstable();
+        families.dedup();
+
+        families
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn family(flags: u32, queue_count: u32) -> QueueFamilyProperties {
+        QueueFamilyProperties {
+            queue_flags: QueueFlags(flags),
+            queue_count,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select_single_family() {
+        let families = QueueFamilies::select(&[family(0x7, 16)]).unwrap();
+
+        assert_eq!(
+            families,
+            QueueFamilies {
+                graphics: Some(0),
+                compute: 0,
+                transfer: 0,
+            }
+        );
+        assert_eq!(families.unique(), [0]);
+    }
+
+    #[test]
+    fn test_select_dedicated_families() {
+        let families =
+            QueueFamilies::select(&[family(0x7, 16), family(0x6, 8), family(0x4, 2)]).unwrap();
+
+        assert_eq!(
+            families,
+            QueueFamilies {
+                graphics: Some(0),
+                compute: 1,
+                transfer: 2,
+            }
+        );
+        assert_eq!(families.unique(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_select_skips_empty_families() {
+        let families = QueueFamilies::select(&[family(0x6, 0), family(0x7, 1)]).unwrap();
+
+        assert_eq!(families.graphics, Some(1));
+        assert_eq!(families.compute, 1);
+        assert_eq!(families.transfer, 1);
+    }
+
+    #[test]
+    fn test_select_compute_only() {
+        let families = QueueFamilies::select(&[family(0x4, 1), family(0x6, 4)]).unwrap();
+
+        assert_eq!(families.graphics, None);
+        assert_eq!(families.compute, 1);
+        assert_eq!(families.transfer, 0);
+    }
+
+    #[test]
+    fn test_select_without_compute() {
+        assert_eq!(QueueFamilies::select(&[family(0x4, 1)]), None);
+        assert_eq!(QueueFamilies::select(&[]), None);
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{
+    VulkanDevice,
+    types::{
+        AllocationCallbacksRef, AttachmentDescription, AttachmentLoadOp, AttachmentReference,
+        AttachmentStoreOp, Extent2D, Format, Framebuffer, FramebufferCreateInfo, ImageLayout,
+        ImageView, RenderPass, RenderPassCreateInfo, SubpassDescription,
+    },
+};
+
+/// Render passes and framebuffers used to render on devices without dynamic rendering.
+///
+/// Render passes are kept for the lifetime of the device. Framebuffers are destroyed along
+/// with the first of their image views.
+pub(crate) struct RenderPassCache {
+    render_passes: Mutex<HashMap<RenderPassKey, RenderPass>>,
+    framebuffers: Mutex<HashMap<FramebufferKey, Framebuffer>>,
+}
+
+/// Format and load and store operations of a render pass attachment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct AttachmentKey {
+    pub(crate) format: Format,
+    pub(crate) load_op: AttachmentLoadOp,
+    pub(crate) store_op: AttachmentStoreOp,
+}
+
+/// The attachments of a single-subpass render pass. Stencil aspects, if any, are loaded and
+/// stored like the depth aspect.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct RenderPassKey {
+    pub(crate) colors: Vec<AttachmentKey>,
+    pub(crate) depth: Option<AttachmentKey>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct FramebufferKey {
+    render_pass: RenderPass,
+    views: Vec<ImageView>,
+    extent: Extent2D,
+}
+
+impl RenderPassCache {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            render_passes: Mutex::new(HashMap::new()),
+            framebuffers: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Locks `mutex`. The maps are only changed after the objects they hold are created or
+/// destroyed, so a panic while holding the lock leaves them consistent.
+#[inline]
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[inline]
+const fn has_stencil(format: Format) -> bool {
+    matches!(
+        format,
+        Format::D24_UNORM_S8_UINT | Format::D32_SFLOAT_S8_UINT
+    )
+}
+
+impl VulkanDevice {
+    /// Returns the render pass with the attachments of `key`, creating it on first use.
+    ///
+    /// Attachments stay in their attachment layout for the whole render pass. `allocator`
+    /// must be the instance's allocation callbacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkCreateRenderPass` fails.
+    pub(crate) unsafe fn render_pass(
+        &self,
+        key: &RenderPassKey,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<RenderPass> {
+        let mut render_passes = lock(&self.render_passes.render_passes);
+
+        if let Some(&render_pass) = render_passes.get(key) {
+            return Ok(render_pass);
+        }
+
+        let no_stencil = (AttachmentLoadOp::DONT_CARE, AttachmentStoreOp::DONT_CARE);
+
+        let mut attachments = key
+            .colors
+            .iter()
+            .map(|color| {
+                AttachmentDescription::new(
+                    color.format,
+                    ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                    (color.load_op, color.store_op),
+                    no_stencil,
+                )
+            })
+            .collect::<Vec<_>>();
+        let color_references = (0..key.colors.len() as u32)
+            .map(|attachment| AttachmentReference {
+                attachment,
+                layout: ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            })
+            .collect::<Vec<_>>();
+        let depth_reference = key.depth.map(|depth| {
+            let ops = (depth.load_op, depth.store_op);
+
+            attachments.push(AttachmentDescription::new(
+                depth.format,
+                ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                ops,
+                if has_stencil(depth.format) {
+                    ops
+                } else {
+                    no_stencil
+                },
+            ));
+
+            AttachmentReference {
+                attachment: key.colors.len() as u32,
+                layout: ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+            }
+        });
+
+        let subpasses = [SubpassDescription::graphics(
+            &color_references,
+            depth_reference.as_ref(),
+        )];
+
+        let render_pass = unsafe {
+            self.fns.create_render_pass(
+                self.handle,
+                &RenderPassCreateInfo::new(&attachments, &subpasses),
+                allocator,
+            )
+        }?;
+
+        render_passes.insert(key.clone(), render_pass);
+
+        Ok(render_pass)
+    }
+
+    /// Returns the framebuffer of `extent` binding `views` to the attachments of
+    /// `render_pass`, creating it on first use.
+    ///
+    /// `allocator` must be the instance's allocation callbacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkCreateFramebuffer` fails.
+    pub(crate) unsafe fn framebuffer(
+        &self,
+        render_pass: RenderPass,
+        views: &[ImageView],
+        extent: Extent2D,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Framebuffer> {
+        let key = FramebufferKey {
+            render_pass,
+            views: views.to_vec(),
+            extent,
+        };
+
+        let mut framebuffers = lock(&self.render_passes.framebuffers);
+
+        if let Some(&framebuffer) = framebuffers.get(&key) {
+            return Ok(framebuffer);
+        }
+
+        let framebuffer = unsafe {
+            self.fns.create_framebuffer(
+                self.handle,
+                &FramebufferCreateInfo::new(render_pass, views, extent),
+                allocator,
+            )
+        }?;
+
+        framebuffers.insert(key, framebuffer);
+
+        Ok(framebuffer)
+    }
+
+    /// Destroys the framebuffers using `view`, ahead of destroying the view itself.
+    ///
+    /// The view's handle may be reused by the driver afterwards, so framebuffers must not
+    /// outlive it.
+    pub(crate) unsafe fn forget_image_view(
+        &self,
+        view: ImageView,
+        allocator: AllocationCallbacksRef,
+    ) {
+        lock(&self.render_passes.framebuffers).retain(|key, &mut framebuffer| {
+            if !key.views.contains(&view) {
+                return true;
+            }
+
+            unsafe {
+                self.fns
+                    .destroy_framebuffer(self.handle, framebuffer, allocator)
+            };
+
+            false
+        });
+    }
+
+    /// Destroys every framebuffer and render pass, ahead of destroying the device.
+    pub(super) unsafe fn release_render_passes(&self, allocator: AllocationCallbacksRef) {
+        for (_, framebuffer) in lock(&self.render_passes.framebuffers).drain() {
+            unsafe {
+                self.fns
+                    .destroy_framebuffer(self.handle, framebuffer, allocator)
+            };
+        }
+
+        for (_, render_pass) in lock(&self.render_passes.render_passes).drain() {
+            unsafe {
+                self.fns
+                    .destroy_render_pass(self.handle, render_pass, allocator)
+            };
+        }
+    }
+}
@@ -1,8 +1,9 @@
+#[cfg(feature = "error_location")]
 use core::panic::Location;
 
 use mayon_core::{
     BaseError, CreateBackendError, CreateBackendErrorKind, CreateContextError,
-    CreateContextErrorKind,
+    CreateContextErrorKind, CreateDeviceError, CreateDeviceErrorKind,
 };
 
 #[derive(Copy, Clone, Debug, thiserror::Error)]
@@ -26,6 +27,209 @@ pub enum VulkanErrorKind {
         name: crate::VulkanFunctionName,
         code: super::ReturnCode,
     },
+
+    #[error("The device cannot present to this surface")]
+    PresentationUnsupported,
+
+    #[error("No memory type satisfies the resource requirements")]
+    NoSuitableMemoryType,
+
+    /// A layer in `VulkanBackendParams::required_layers` is not available. `index` is its
+    /// position in that list.
+    #[error("Required Vulkan layer #{index} is not available")]
+    MissingLayer { index: usize },
+
+    /// An instance extension in `VulkanBackendParams::required_extensions` is not available.
+    /// `index` is its position in that list.
+    #[error("Required Vulkan instance extension #{index} is not available")]
+    MissingExtension { index: usize },
+
+    #[error("The device has no {kind} queue")]
+    MissingQueue { kind: mayon_core::QueueKind },
+
+    #[error("Command buffer cannot be used in the {state} state")]
+    UnexpectedCommandBufferState {
+        state: mayon_core::CommandBufferState,
+    },
+
+    #[error("Command buffer was allocated from a different command pool")]
+    ForeignCommandBuffer,
+
+    #[error("Vulkan {required} is required, but only {available} is supported")]
+    UnsupportedApiVersion {
+        required: crate::VulkanVersion,
+        available: crate::VulkanVersion,
+    },
+
+    #[error("Buffers must hold at least one byte")]
+    EmptyBuffer,
+
+    /// An access reaches past the end of a buffer. `end` is the element it would reach and
+    /// `len` the number of elements in the buffer.
+    #[error("Element range ending at {end} is out of bounds for a buffer of {len}")]
+    BufferOutOfBounds { end: usize, len: usize },
+
+    #[error("The buffer's memory is not host visible")]
+    BufferNotHostVisible,
+
+    #[error("The buffer was not created with the usage {usage:?}")]
+    MissingBufferUsage { usage: crate::BufferUsage },
+
+    #[error("Textures must have a non-zero extent and layer count")]
+    EmptyTexture,
+
+    #[error("Cube textures must be square")]
+    NonSquareCubeTexture,
+
+    #[error("{requested} mip levels requested, but the texture allows between 1 and {max}")]
+    TooManyMipLevels { requested: u32, max: u32 },
+
+    #[error("The texture exceeds the device's limits for its format")]
+    TextureExceedsLimits,
+
+    #[error("The texture was not created with the usage {usage:?}")]
+    MissingTextureUsage { usage: crate::TextureUsage },
+
+    #[error("Mipmaps cannot be generated on the {kind} queue")]
+    UnsupportedMipmapQueue { kind: mayon_core::QueueKind },
+
+    #[error("The device cannot blit {format} textures to generate mipmaps")]
+    UnsupportedMipmapFormat { format: mayon_core::Format },
+
+    #[error("Mip level {level} is out of range for a texture of {count} levels")]
+    MipLevelOutOfRange { level: u32, count: u32 },
+
+    /// The texel data of an upload does not cover exactly one mip level. `expected` is the
+    /// size of the level in bytes, and `actual` the size of the data.
+    #[error("Expected {expected} bytes of texel data, got {actual}")]
+    TextureDataSize { expected: usize, actual: usize },
+
+    #[error("The view's mip levels or array layers are empty or out of range")]
+    TextureViewOutOfRange,
+
+    /// A single row of texels does not fit in a staging ring. `required` is the row size and
+    /// `capacity` the ring size, in bytes.
+    #[error("The staging ring holds {capacity} bytes, but a texel row needs {required}")]
+    StagingRingTooSmall { required: usize, capacity: usize },
+
+    #[error("The shader code is not a valid SPIR-V module")]
+    InvalidSpirv,
+
+    #[error("The shader binds a resource without a supported descriptor type")]
+    UnsupportedShaderResource,
+
+    #[error("Vertex input at location {location} is not a 32-bit scalar or vector")]
+    UnsupportedVertexInput { location: u32 },
+
+    #[error("Shaders declare set {set} binding {binding} with distinct types or counts")]
+    ShaderBindingMismatch { set: u32, binding: u32 },
+
+    #[error("Set {set} binding {binding} is a runtime-sized array")]
+    UnsizedDescriptorArray { set: u32, binding: u32 },
+
+    /// A pipeline has no shader for `stage`, or its shader module has no entry point of
+    /// that stage with the given name.
+    #[error("The pipeline has no {stage:?} shader entry point")]
+    MissingEntryPoint { stage: crate::ShaderStage },
+
+    /// The vertex shader input at `location` is not fed by a vertex buffer attribute of the
+    /// same format.
+    #[error("No vertex attribute of the right format feeds the input at location {location}")]
+    VertexInputMismatch { location: u32 },
+
+    #[error("Several vertex attributes are bound to location {location}")]
+    DuplicateVertexAttribute { location: u32 },
+
+    #[error("The pipeline layout does not provide set {set} binding {binding} to its shaders")]
+    PipelineLayoutMismatch { set: u32, binding: u32 },
+
+    #[error("Failed to access the pipeline cache file: {kind}")]
+    PipelineCacheIo { kind: std::io::ErrorKind },
+
+    #[error("Rendering was begun and not ended")]
+    RenderingInProgress,
+
+    #[error("No rendering is in progress")]
+    RenderingNotBegun,
+
+    #[error("Rendering needs the graphics queue, not the {kind} queue")]
+    UnsupportedRenderingQueue { kind: mayon_core::QueueKind },
+
+    #[error("Rendering needs at least one attachment")]
+    EmptyRendering,
+
+    #[error("Rendering attachments must all have the same extent")]
+    AttachmentExtentMismatch,
+
+    #[error("Attachment views must cover a single mip level and array layer")]
+    InvalidAttachmentView,
+
+    #[error("Set {set} is out of range for a pipeline layout of {count} sets")]
+    DescriptorSetOutOfRange { set: u32, count: u32 },
+
+    #[error("The descriptor set has no binding {binding}")]
+    UnknownDescriptorBinding { binding: u32 },
+
+    #[error("Binding {binding} holds {expected:?} descriptors")]
+    DescriptorKindMismatch {
+        binding: u32,
+        expected: crate::DescriptorKind,
+    },
+
+    #[error(
+        "Writing {len} descriptors from element {element} exceeds the {count} of binding {binding}"
+    )]
+    DescriptorArrayOutOfRange {
+        binding: u32,
+        element: u32,
+        len: usize,
+        count: u32,
+    },
+
+    #[error("The device does not support the descriptor indexing features of bindless tables")]
+    DescriptorIndexingUnsupported,
+
+    #[error("The bindless table has no free {kind:?} slot left")]
+    BindlessTableFull { kind: crate::BindlessKind },
+
+    #[error("The bindless index does not refer to a resource of the table")]
+    InvalidBindlessIndex,
+
+    /// Set `set` of a pipeline layout is bound with a
+    /// [`BindlessTable`](crate::BindlessTable), and cannot be allocated.
+    #[error("Set {set} is the bindless set of the pipeline layout")]
+    BindlessDescriptorSet { set: u32 },
+
+    #[error("The resource does not belong to the render graph")]
+    UnknownGraphResource,
+
+    #[error("A render graph pass uses a resource in two different ways")]
+    ConflictingGraphAccess,
+
+    #[error("Compute work needs the graphics or compute queue, not the {kind} queue")]
+    UnsupportedComputeQueue { kind: mayon_core::QueueKind },
+
+    #[error("No compute pipeline is bound")]
+    NoComputePipeline,
+
+    /// The descriptor set was allocated with another layout than set `set` of the pipeline
+    /// layout.
+    #[error("The descriptor set does not match set {set} of the pipeline layout")]
+    IncompatibleDescriptorSet { set: u32 },
+
+    #[error(
+        "Push constants at offset {offset} of {size} bytes are misaligned or exceed the pipeline layout's range"
+    )]
+    PushConstantsOutOfRange { offset: u32, size: u32 },
+
+    #[error("Indirect command {index} is out of range for a buffer of {len} commands")]
+    IndirectCommandOutOfRange { index: usize, len: usize },
+
+    #[error("Compute-only instances cannot create contexts")]
+    ComputeOnlyInstance,
+
+    #[error("The surface supports no format textures can describe")]
+    UnsupportedSurfaceFormat,
 }
 
 pub type Result<T> = core::result::Result<T, VulkanError>;
@@ -139,6 +343,16 @@ impl From<VulkanError> for CreateContextError<VulkanErrorKind> {
     }
 }
 
+impl From<VulkanError> for CreateDeviceError<VulkanErrorKind> {
+    fn from(value: VulkanError) -> Self {
+        CreateDeviceError::new(
+            CreateDeviceErrorKind::BackendInternal(value.kind),
+            #[cfg(feature = "error_location")]
+            value.location,
+        )
+    }
+}
+
 impl From<VulkanErrorKind> for VulkanError {
     #[track_caller]
     fn from(kind: VulkanErrorKind) -> Self {
@@ -149,3 +363,68 @@ impl From<VulkanErrorKind> for VulkanError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::{AdapterType, BaseError, logger::DefaultLogger};
+
+    use super::*;
+    use crate::{
+        Instance, ReturnCode, VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+    };
+
+    #[test]
+    fn test_create_backend_error() {
+        MockDriver::reset();
+        MockDriver::fail_next(
+            VulkanFunctionName::CreateInstance,
+            ReturnCode::IncompatibleDriver,
+        );
+
+        let Err(err) = Instance::new(mock::params(), DefaultLogger) else {
+            panic!("Instance creation should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            CreateBackendErrorKind::BackendInternal(VulkanErrorKind::FunctionReturn {
+                name: VulkanFunctionName::CreateInstance,
+                code: ReturnCode::IncompatibleDriver,
+            })
+        ));
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyInstance),
+            0
+        );
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_create_device_error() {
+        let instance = mock::instance();
+        let adapter = instance
+            .select_adapter(&[AdapterType::DiscreteGpu])
+            .unwrap()
+            .unwrap();
+
+        MockDriver::fail_next(VulkanFunctionName::CreateDevice, ReturnCode::DeviceLost);
+
+        let Err(err) = instance.create_device(&adapter) else {
+            panic!("Device creation should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            CreateDeviceErrorKind::BackendInternal(VulkanErrorKind::FunctionReturn {
+                name: VulkanFunctionName::CreateDevice,
+                code: ReturnCode::DeviceLost,
+            })
+        ));
+
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
+    }
+}
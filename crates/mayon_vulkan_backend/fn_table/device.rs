@@ -0,0 +1,1963 @@
+use core::{
+    ffi::{c_char, c_void},
+    mem::{MaybeUninit, transmute_copy},
+    ptr::{NonNull, null, null_mut},
+};
+
+use VulkanFunctionName::*;
+
+use super::{VulkanFunctionName, enumerate};
+use crate::{
+    ReturnCode, VulkanErrorKind,
+    types::{
+        AllocationCallbacksRef, Buffer, BufferCopy, BufferCreateInfo, BufferImageCopy,
+        CommandBuffer, CommandBufferAllocateInfo, CommandBufferBeginInfo, CommandBufferResetFlags,
+        CommandPool, CommandPoolCreateInfo, CommandPoolResetFlags, ComputePipelineCreateInfo,
+        DependencyFlags, DescriptorPool, DescriptorPoolCreateInfo, DescriptorPoolResetFlags,
+        DescriptorSet, DescriptorSetAllocateInfo, DescriptorSetLayout,
+        DescriptorSetLayoutCreateInfo, Device, DeviceMemory, ExtensionName, Fence, FenceCreateInfo,
+        Filter, Framebuffer, FramebufferCreateInfo, GraphicsPipelineCreateInfo, Image, ImageBlit,
+        ImageCreateInfo, ImageLayout, ImageMemoryBarrier, ImageView, ImageViewCreateInfo,
+        MemoryAllocateInfo, MemoryBarrier, MemoryMapFlags, MemoryRequirements, Pipeline,
+        PipelineBindPoint, PipelineCache, PipelineCacheCreateInfo, PipelineLayout,
+        PipelineLayoutCreateInfo, PipelineStageFlags, PresentInfo, Queue, RenderPass,
+        RenderPassBeginInfo, RenderPassCreateInfo, RenderingInfo, Sampler, SamplerCreateInfo,
+        Semaphore, SemaphoreCreateInfo, SemaphoreSignalInfo, SemaphoreWaitInfo, ShaderModule,
+        ShaderModuleCreateInfo, ShaderStageFlags, SubmitInfo, SubpassContents, Swapchain,
+        SwapchainCreateInfo, VkResult, WriteDescriptorSet,
+    },
+};
+
+pub(crate) type PfnGetDeviceProcAddr =
+    unsafe extern "system" fn(
+        device: Device,
+        name: *const c_char,
+    ) -> Option<unsafe extern "system" fn()>;
+
+/// Device-level functions, resolved through `vkGetDeviceProcAddr` for one device.
+///
+/// Calls through this table go straight to the driver, skipping the loader's dispatch
+/// trampolines.
+pub(crate) struct DeviceFnTable {
+    fn_destroy_device: unsafe extern "system" fn(device: Device, allocator: AllocationCallbacksRef),
+
+    fn_get_device_queue: unsafe extern "system" fn(
+        device: Device,
+        queue_family_index: u32,
+        queue_index: u32,
+        queue: *mut Queue,
+    ),
+
+    fn_device_wait_idle: unsafe extern "system" fn(device: Device) -> VkResult,
+
+    fn_create_swapchain: Option<
+        unsafe extern "system" fn(
+            device: Device,
+            create_info: *const SwapchainCreateInfo,
+            allocator: AllocationCallbacksRef,
+            swapchain: *mut Swapchain,
+        ) -> VkResult,
+    >,
+
+    fn_destroy_swapchain: Option<
+        unsafe extern "system" fn(
+            device: Device,
+            swapchain: Swapchain,
+            allocator: AllocationCallbacksRef,
+        ),
+    >,
+
+    fn_get_swapchain_images: Option<
+        unsafe extern "system" fn(
+            device: Device,
+            swapchain: Swapchain,
+            swapchain_image_count: *mut u32,
+            swapchain_images: *mut Image,
+        ) -> VkResult,
+    >,
+
+    fn_acquire_next_image: Option<
+        unsafe extern "system" fn(
+            device: Device,
+            swapchain: Swapchain,
+            timeout: u64,
+            semaphore: Option<Semaphore>,
+            fence: Option<Fence>,
+            image_index: *mut u32,
+        ) -> VkResult,
+    >,
+
+    fn_queue_present: Option<
+        unsafe extern "system" fn(queue: Queue, present_info: *const PresentInfo) -> VkResult,
+    >,
+
+    fn_create_fence: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const FenceCreateInfo,
+        allocator: AllocationCallbacksRef,
+        fence: *mut Fence,
+    ) -> VkResult,
+
+    fn_destroy_fence:
+        unsafe extern "system" fn(device: Device, fence: Fence, allocator: AllocationCallbacksRef),
+
+    fn_wait_for_fences: unsafe extern "system" fn(
+        device: Device,
+        fence_count: u32,
+        fences: *const Fence,
+        wait_all: u32,
+        timeout: u64,
+    ) -> VkResult,
+
+    fn_reset_fences: unsafe extern "system" fn(
+        device: Device,
+        fence_count: u32,
+        fences: *const Fence,
+    ) -> VkResult,
+
+    fn_get_fence_status: unsafe extern "system" fn(device: Device, fence: Fence) -> VkResult,
+
+    fn_create_semaphore: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const SemaphoreCreateInfo,
+        allocator: AllocationCallbacksRef,
+        semaphore: *mut Semaphore,
+    ) -> VkResult,
+
+    fn_destroy_semaphore: unsafe extern "system" fn(
+        device: Device,
+        semaphore: Semaphore,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_signal_semaphore: Option<
+        unsafe extern "system" fn(
+            device: Device,
+            signal_info: *const SemaphoreSignalInfo,
+        ) -> VkResult,
+    >,
+
+    fn_wait_semaphores: Option<
+        unsafe extern "system" fn(
+            device: Device,
+            wait_info: *const SemaphoreWaitInfo,
+            timeout: u64,
+        ) -> VkResult,
+    >,
+
+    fn_get_semaphore_counter_value: Option<
+        unsafe extern "system" fn(
+            device: Device,
+            semaphore: Semaphore,
+            value: *mut u64,
+        ) -> VkResult,
+    >,
+
+    fn_create_command_pool: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const CommandPoolCreateInfo,
+        allocator: AllocationCallbacksRef,
+        command_pool: *mut CommandPool,
+    ) -> VkResult,
+
+    fn_destroy_command_pool: unsafe extern "system" fn(
+        device: Device,
+        command_pool: CommandPool,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_reset_command_pool: unsafe extern "system" fn(
+        device: Device,
+        command_pool: CommandPool,
+        flags: CommandPoolResetFlags,
+    ) -> VkResult,
+
+    fn_allocate_command_buffers: unsafe extern "system" fn(
+        device: Device,
+        allocate_info: *const CommandBufferAllocateInfo,
+        command_buffers: *mut CommandBuffer,
+    ) -> VkResult,
+
+    fn_begin_command_buffer: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        begin_info: *const CommandBufferBeginInfo,
+    ) -> VkResult,
+
+    fn_end_command_buffer: unsafe extern "system" fn(command_buffer: CommandBuffer) -> VkResult,
+
+    fn_reset_command_buffer: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        flags: CommandBufferResetFlags,
+    ) -> VkResult,
+
+    fn_queue_submit: unsafe extern "system" fn(
+        queue: Queue,
+        submit_count: u32,
+        submits: *const SubmitInfo,
+        fence: Option<Fence>,
+    ) -> VkResult,
+
+    fn_cmd_pipeline_barrier: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        src_stage_mask: PipelineStageFlags,
+        dst_stage_mask: PipelineStageFlags,
+        dependency_flags: DependencyFlags,
+        memory_barrier_count: u32,
+        memory_barriers: *const MemoryBarrier,
+        buffer_memory_barrier_count: u32,
+        buffer_memory_barriers: *const c_void,
+        image_memory_barrier_count: u32,
+        image_memory_barriers: *const ImageMemoryBarrier,
+    ),
+
+    fn_create_image: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const ImageCreateInfo,
+        allocator: AllocationCallbacksRef,
+        image: *mut Image,
+    ) -> VkResult,
+
+    fn_destroy_image:
+        unsafe extern "system" fn(device: Device, image: Image, allocator: AllocationCallbacksRef),
+
+    fn_get_image_memory_requirements: unsafe extern "system" fn(
+        device: Device,
+        image: Image,
+        memory_requirements: *mut MemoryRequirements,
+    ),
+
+    fn_allocate_memory: unsafe extern "system" fn(
+        device: Device,
+        allocate_info: *const MemoryAllocateInfo,
+        allocator: AllocationCallbacksRef,
+        memory: *mut DeviceMemory,
+    ) -> VkResult,
+
+    fn_free_memory: unsafe extern "system" fn(
+        device: Device,
+        memory: DeviceMemory,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_bind_image_memory: unsafe extern "system" fn(
+        device: Device,
+        image: Image,
+        memory: DeviceMemory,
+        memory_offset: u64,
+    ) -> VkResult,
+
+    fn_map_memory: unsafe extern "system" fn(
+        device: Device,
+        memory: DeviceMemory,
+        offset: u64,
+        size: u64,
+        flags: MemoryMapFlags,
+        data: *mut *mut c_void,
+    ) -> VkResult,
+
+    fn_create_buffer: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const BufferCreateInfo,
+        allocator: AllocationCallbacksRef,
+        buffer: *mut Buffer,
+    ) -> VkResult,
+
+    fn_destroy_buffer: unsafe extern "system" fn(
+        device: Device,
+        buffer: Buffer,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_get_buffer_memory_requirements: unsafe extern "system" fn(
+        device: Device,
+        buffer: Buffer,
+        memory_requirements: *mut MemoryRequirements,
+    ),
+
+    fn_bind_buffer_memory: unsafe extern "system" fn(
+        device: Device,
+        buffer: Buffer,
+        memory: DeviceMemory,
+        memory_offset: u64,
+    ) -> VkResult,
+
+    fn_cmd_copy_buffer: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        src_buffer: Buffer,
+        dst_buffer: Buffer,
+        region_count: u32,
+        regions: *const BufferCopy,
+    ),
+
+    fn_create_image_view: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const ImageViewCreateInfo,
+        allocator: AllocationCallbacksRef,
+        view: *mut ImageView,
+    ) -> VkResult,
+
+    fn_destroy_image_view: unsafe extern "system" fn(
+        device: Device,
+        image_view: ImageView,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_create_sampler: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const SamplerCreateInfo,
+        allocator: AllocationCallbacksRef,
+        sampler: *mut Sampler,
+    ) -> VkResult,
+
+    fn_destroy_sampler: unsafe extern "system" fn(
+        device: Device,
+        sampler: Sampler,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_cmd_copy_buffer_to_image: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        src_buffer: Buffer,
+        dst_image: Image,
+        dst_image_layout: ImageLayout,
+        region_count: u32,
+        regions: *const BufferImageCopy,
+    ),
+
+    fn_cmd_blit_image: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        src_image: Image,
+        src_image_layout: ImageLayout,
+        dst_image: Image,
+        dst_image_layout: ImageLayout,
+        region_count: u32,
+        regions: *const ImageBlit,
+        filter: Filter,
+    ),
+
+    fn_create_shader_module: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const ShaderModuleCreateInfo,
+        allocator: AllocationCallbacksRef,
+        shader_module: *mut ShaderModule,
+    ) -> VkResult,
+
+    fn_destroy_shader_module: unsafe extern "system" fn(
+        device: Device,
+        shader_module: ShaderModule,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_create_descriptor_set_layout: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const DescriptorSetLayoutCreateInfo,
+        allocator: AllocationCallbacksRef,
+        set_layout: *mut DescriptorSetLayout,
+    ) -> VkResult,
+
+    fn_destroy_descriptor_set_layout: unsafe extern "system" fn(
+        device: Device,
+        set_layout: DescriptorSetLayout,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_create_descriptor_pool: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const DescriptorPoolCreateInfo,
+        allocator: AllocationCallbacksRef,
+        descriptor_pool: *mut DescriptorPool,
+    ) -> VkResult,
+
+    fn_destroy_descriptor_pool: unsafe extern "system" fn(
+        device: Device,
+        descriptor_pool: DescriptorPool,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_reset_descriptor_pool: unsafe extern "system" fn(
+        device: Device,
+        descriptor_pool: DescriptorPool,
+        flags: DescriptorPoolResetFlags,
+    ) -> VkResult,
+
+    fn_allocate_descriptor_sets: unsafe extern "system" fn(
+        device: Device,
+        allocate_info: *const DescriptorSetAllocateInfo,
+        descriptor_sets: *mut DescriptorSet,
+    ) -> VkResult,
+
+    fn_update_descriptor_sets: unsafe extern "system" fn(
+        device: Device,
+        descriptor_write_count: u32,
+        descriptor_writes: *const WriteDescriptorSet,
+        descriptor_copy_count: u32,
+        descriptor_copies: *const c_void,
+    ),
+
+    fn_create_pipeline_layout: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const PipelineLayoutCreateInfo,
+        allocator: AllocationCallbacksRef,
+        pipeline_layout: *mut PipelineLayout,
+    ) -> VkResult,
+
+    fn_destroy_pipeline_layout: unsafe extern "system" fn(
+        device: Device,
+        pipeline_layout: PipelineLayout,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_create_pipeline_cache: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const PipelineCacheCreateInfo,
+        allocator: AllocationCallbacksRef,
+        pipeline_cache: *mut PipelineCache,
+    ) -> VkResult,
+
+    fn_destroy_pipeline_cache: unsafe extern "system" fn(
+        device: Device,
+        pipeline_cache: PipelineCache,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_get_pipeline_cache_data: unsafe extern "system" fn(
+        device: Device,
+        pipeline_cache: PipelineCache,
+        data_size: *mut usize,
+        data: *mut c_void,
+    ) -> VkResult,
+
+    fn_create_graphics_pipelines: unsafe extern "system" fn(
+        device: Device,
+        pipeline_cache: Option<PipelineCache>,
+        create_info_count: u32,
+        create_infos: *const GraphicsPipelineCreateInfo,
+        allocator: AllocationCallbacksRef,
+        pipelines: *mut Pipeline,
+    ) -> VkResult,
+
+    fn_destroy_pipeline: unsafe extern "system" fn(
+        device: Device,
+        pipeline: Pipeline,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_create_compute_pipelines: unsafe extern "system" fn(
+        device: Device,
+        pipeline_cache: Option<PipelineCache>,
+        create_info_count: u32,
+        create_infos: *const ComputePipelineCreateInfo,
+        allocator: AllocationCallbacksRef,
+        pipelines: *mut Pipeline,
+    ) -> VkResult,
+
+    fn_cmd_bind_pipeline: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        pipeline_bind_point: PipelineBindPoint,
+        pipeline: Pipeline,
+    ),
+
+    fn_cmd_bind_descriptor_sets: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        pipeline_bind_point: PipelineBindPoint,
+        layout: PipelineLayout,
+        first_set: u32,
+        descriptor_set_count: u32,
+        descriptor_sets: *const DescriptorSet,
+        dynamic_offset_count: u32,
+        dynamic_offsets: *const u32,
+    ),
+
+    fn_cmd_push_constants: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        layout: PipelineLayout,
+        stage_flags: ShaderStageFlags,
+        offset: u32,
+        size: u32,
+        values: *const c_void,
+    ),
+
+    fn_cmd_dispatch: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    ),
+
+    fn_cmd_dispatch_indirect:
+        unsafe extern "system" fn(command_buffer: CommandBuffer, buffer: Buffer, offset: u64),
+
+    fn_create_render_pass: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const RenderPassCreateInfo,
+        allocator: AllocationCallbacksRef,
+        render_pass: *mut RenderPass,
+    ) -> VkResult,
+
+    fn_destroy_render_pass: unsafe extern "system" fn(
+        device: Device,
+        render_pass: RenderPass,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_create_framebuffer: unsafe extern "system" fn(
+        device: Device,
+        create_info: *const FramebufferCreateInfo,
+        allocator: AllocationCallbacksRef,
+        framebuffer: *mut Framebuffer,
+    ) -> VkResult,
+
+    fn_destroy_framebuffer: unsafe extern "system" fn(
+        device: Device,
+        framebuffer: Framebuffer,
+        allocator: AllocationCallbacksRef,
+    ),
+
+    fn_cmd_begin_render_pass: unsafe extern "system" fn(
+        command_buffer: CommandBuffer,
+        render_pass_begin: *const RenderPassBeginInfo,
+        contents: SubpassContents,
+    ),
+
+    fn_cmd_end_render_pass: unsafe extern "system" fn(command_buffer: CommandBuffer),
+
+    /// `vkCmdBeginRendering`, or `vkCmdBeginRenderingKHR` on devices older than Vulkan 1.3.
+    fn_cmd_begin_rendering: Option<
+        unsafe extern "system" fn(
+            command_buffer: CommandBuffer,
+            rendering_info: *const RenderingInfo,
+        ),
+    >,
+
+    /// `vkCmdEndRendering`, or `vkCmdEndRenderingKHR` on devices older than Vulkan 1.3.
+    fn_cmd_end_rendering: Option<unsafe extern "system" fn(command_buffer: CommandBuffer)>,
+}
+
+impl DeviceFnTable {
+    /// Resolves the device-level functions for `device` through `get_device_proc_addr`.
+    ///
+    /// If any required function is missing, `device` is destroyed before returning, as
+    /// nothing else could destroy it.
+    ///
+    /// Functions of device extensions are only resolved when the extension is among
+    /// `extensions`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FunctionLoadFailed` naming the first function that cannot be resolved.
+    ///
+    /// # Safety
+    ///
+    /// `get_device_proc_addr` must belong to the instance `device` was created from, and
+    /// `device` must have been created with `allocator` and `extensions`.
+    pub(crate) unsafe fn load(
+        get_device_proc_addr: PfnGetDeviceProcAddr,
+        device: Device,
+        allocator: AllocationCallbacksRef,
+        extensions: &[ExtensionName],
+    ) -> crate::Result<Self> {
+        let loader = DeviceProcAddrLoader {
+            get_device_proc_addr,
+            device,
+        };
+
+        let fn_destroy_device: unsafe extern "system" fn(Device, AllocationCallbacksRef) =
+            unsafe { loader.load(DestroyDevice) }?;
+
+        let result = unsafe { Self::load_with(loader, extensions) };
+
+        if result.is_err() {
+            unsafe { fn_destroy_device(device, allocator) };
+        }
+
+        result
+    }
+
+    unsafe fn load_with(
+        loader: DeviceProcAddrLoader,
+        extensions: &[ExtensionName],
+    ) -> crate::Result<Self> {
+        let swapchain = extensions
+            .iter()
+            .any(|name| name.as_c_str() == ExtensionName::SWAPCHAIN.as_c_str());
+
+        unsafe {
+            Ok(Self {
+                fn_destroy_device: loader.load(DestroyDevice)?,
+                fn_get_device_queue: loader.load(GetDeviceQueue)?,
+                fn_device_wait_idle: loader.load(DeviceWaitIdle)?,
+                fn_create_swapchain: loader.load_extension(swapchain, CreateSwapchain),
+                fn_destroy_swapchain: loader.load_extension(swapchain, DestroySwapchain),
+                fn_get_swapchain_images: loader.load_extension(swapchain, GetSwapchainImages),
+                fn_acquire_next_image: loader.load_extension(swapchain, AcquireNextImage),
+                fn_queue_present: loader.load_extension(swapchain, QueuePresent),
+                fn_create_fence: loader.load(CreateFence)?,
+                fn_destroy_fence: loader.load(DestroyFence)?,
+                fn_wait_for_fences: loader.load(WaitForFences)?,
+                fn_reset_fences: loader.load(ResetFences)?,
+                fn_get_fence_status: loader.load(GetFenceStatus)?,
+                fn_create_semaphore: loader.load(CreateSemaphore)?,
+                fn_destroy_semaphore: loader.load(DestroySemaphore)?,
+                fn_signal_semaphore: loader.load_optional(SignalSemaphore),
+                fn_wait_semaphores: loader.load_optional(WaitSemaphores),
+                fn_get_semaphore_counter_value: loader.load_optional(GetSemaphoreCounterValue),
+                fn_create_command_pool: loader.load(CreateCommandPool)?,
+                fn_destroy_command_pool: loader.load(DestroyCommandPool)?,
+                fn_reset_command_pool: loader.load(ResetCommandPool)?,
+                fn_allocate_command_buffers: loader.load(AllocateCommandBuffers)?,
+                fn_begin_command_buffer: loader.load(BeginCommandBuffer)?,
+                fn_end_command_buffer: loader.load(EndCommandBuffer)?,
+                fn_reset_command_buffer: loader.load(ResetCommandBuffer)?,
+                fn_queue_submit: loader.load(QueueSubmit)?,
+                fn_cmd_pipeline_barrier: loader.load(CmdPipelineBarrier)?,
+                fn_create_image: loader.load(CreateImage)?,
+                fn_destroy_image: loader.load(DestroyImage)?,
+                fn_get_image_memory_requirements: loader.load(GetImageMemoryRequirements)?,
+                fn_allocate_memory: loader.load(AllocateMemory)?,
+                fn_free_memory: loader.load(FreeMemory)?,
+                fn_bind_image_memory: loader.load(BindImageMemory)?,
+                fn_map_memory: loader.load(MapMemory)?,
+                fn_create_buffer: loader.load(CreateBuffer)?,
+                fn_destroy_buffer: loader.load(DestroyBuffer)?,
+                fn_get_buffer_memory_requirements: loader.load(GetBufferMemoryRequirements)?,
+                fn_bind_buffer_memory: loader.load(BindBufferMemory)?,
+                fn_cmd_copy_buffer: loader.load(CmdCopyBuffer)?,
+                fn_create_image_view: loader.load(CreateImageView)?,
+                fn_destroy_image_view: loader.load(DestroyImageView)?,
+                fn_create_sampler: loader.load(CreateSampler)?,
+                fn_destroy_sampler: loader.load(DestroySampler)?,
+                fn_cmd_copy_buffer_to_image: loader.load(CmdCopyBufferToImage)?,
+                fn_cmd_blit_image: loader.load(CmdBlitImage)?,
+                fn_create_shader_module: loader.load(CreateShaderModule)?,
+                fn_destroy_shader_module: loader.load(DestroyShaderModule)?,
+                fn_create_descriptor_set_layout: loader.load(CreateDescriptorSetLayout)?,
+                fn_destroy_descriptor_set_layout: loader.load(DestroyDescriptorSetLayout)?,
+                fn_create_descriptor_pool: loader.load(CreateDescriptorPool)?,
+                fn_destroy_descriptor_pool: loader.load(DestroyDescriptorPool)?,
+                fn_reset_descriptor_pool: loader.load(ResetDescriptorPool)?,
+                fn_allocate_descriptor_sets: loader.load(AllocateDescriptorSets)?,
+                fn_update_descriptor_sets: loader.load(UpdateDescriptorSets)?,
+                fn_create_pipeline_layout: loader.load(CreatePipelineLayout)?,
+                fn_destroy_pipeline_layout: loader.load(DestroyPipelineLayout)?,
+                fn_create_pipeline_cache: loader.load(CreatePipelineCache)?,
+                fn_destroy_pipeline_cache: loader.load(DestroyPipelineCache)?,
+                fn_get_pipeline_cache_data: loader.load(GetPipelineCacheData)?,
+                fn_create_graphics_pipelines: loader.load(CreateGraphicsPipelines)?,
+                fn_destroy_pipeline: loader.load(DestroyPipeline)?,
+                fn_create_compute_pipelines: loader.load(CreateComputePipelines)?,
+                fn_cmd_bind_pipeline: loader.load(CmdBindPipeline)?,
+                fn_cmd_bind_descriptor_sets: loader.load(CmdBindDescriptorSets)?,
+                fn_cmd_push_constants: loader.load(CmdPushConstants)?,
+                fn_cmd_dispatch: loader.load(CmdDispatch)?,
+                fn_cmd_dispatch_indirect: loader.load(CmdDispatchIndirect)?,
+                fn_create_render_pass: loader.load(CreateRenderPass)?,
+                fn_destroy_render_pass: loader.load(DestroyRenderPass)?,
+                fn_create_framebuffer: loader.load(CreateFramebuffer)?,
+                fn_destroy_framebuffer: loader.load(DestroyFramebuffer)?,
+                fn_cmd_begin_render_pass: loader.load(CmdBeginRenderPass)?,
+                fn_cmd_end_render_pass: loader.load(CmdEndRenderPass)?,
+                // Only the core names resolve on Vulkan 1.3 devices, and only the extension
+                // names on older devices with `VK_KHR_dynamic_rendering` enabled.
+                fn_cmd_begin_rendering: loader
+                    .load_optional(CmdBeginRendering)
+                    .or_else(|| loader.load_optional(CmdBeginRenderingKhr)),
+                fn_cmd_end_rendering: loader
+                    .load_optional(CmdEndRendering)
+                    .or_else(|| loader.load_optional(CmdEndRenderingKhr)),
+            })
+        }
+    }
+}
+
+/// Resolves entry points of one device through `vkGetDeviceProcAddr`.
+#[derive(Clone, Copy)]
+struct DeviceProcAddrLoader {
+    get_device_proc_addr: PfnGetDeviceProcAddr,
+    device: Device,
+}
+
+impl DeviceProcAddrLoader {
+    /// Resolves a required entry point.
+    ///
+    /// # Safety
+    ///
+    /// `F` must be the function pointer type matching the Vulkan signature of `name`.
+    #[inline]
+    unsafe fn load<F: Copy>(self, name: VulkanFunctionName) -> crate::Result<F> {
+        match unsafe { self.load_optional(name) } {
+            Some(function) => Ok(function),
+            None => VulkanErrorKind::FunctionLoadFailed { name }.into_result(),
+        }
+    }
+
+    /// Resolves an entry point that is allowed to be missing, such as functions of newer
+    /// Vulkan versions.
+    ///
+    /// # Safety
+    ///
+    /// `F` must be the function pointer type matching the Vulkan signature of `name`.
+    #[inline]
+    unsafe fn load_optional<F: Copy>(self, name: VulkanFunctionName) -> Option<F> {
+        const {
+            assert!(size_of::<F>() == size_of::<unsafe extern "system" fn()>());
+        }
+
+        let function = unsafe { (self.get_device_proc_addr)(self.device, name.c_name().as_ptr()) }?;
+
+        Some(unsafe { transmute_copy::<unsafe extern "system" fn(), F>(&function) })
+    }
+
+    /// Resolves an entry point of a device extension, or nothing when the extension is not
+    /// `enabled`.
+    ///
+    /// # Safety
+    ///
+    /// `F` must be the function pointer type matching the Vulkan signature of `name`.
+    #[inline]
+    unsafe fn load_extension<F: Copy>(self, enabled: bool, name: VulkanFunctionName) -> Option<F> {
+        if enabled {
+            unsafe { self.load_optional(name) }
+        } else {
+            None
+        }
+    }
+}
+
+impl DeviceFnTable {
+    #[inline]
+    pub(crate) unsafe fn destroy_device(&self, device: Device, allocator: AllocationCallbacksRef) {
+        unsafe { (self.fn_destroy_device)(device, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_device_queue(
+        &self,
+        device: Device,
+        queue_family_index: u32,
+        queue_index: u32,
+    ) -> Queue {
+        let mut queue = MaybeUninit::<Queue>::uninit();
+
+        unsafe {
+            (self.fn_get_device_queue)(device, queue_family_index, queue_index, queue.as_mut_ptr());
+
+            queue.assume_init()
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn device_wait_idle(&self, device: Device) -> crate::Result<()> {
+        unsafe { (self.fn_device_wait_idle)(device) }.into_result(DeviceWaitIdle, || ())
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_swapchain(
+        &self,
+        device: Device,
+        create_info: &SwapchainCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Swapchain> {
+        let Some(fn_create_swapchain) = self.fn_create_swapchain else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: CreateSwapchain,
+            }
+            .into_result();
+        };
+
+        let mut swapchain = MaybeUninit::<Swapchain>::uninit();
+
+        unsafe { (fn_create_swapchain)(device, create_info, allocator, swapchain.as_mut_ptr()) }
+            .into_result(CreateSwapchain, || unsafe { swapchain.assume_init() })
+    }
+
+    /// Destroys `swapchain`. Does nothing if `VK_KHR_swapchain` is not enabled, since no
+    /// swapchain can exist then.
+    #[inline]
+    pub(crate) unsafe fn destroy_swapchain(
+        &self,
+        device: Device,
+        swapchain: Swapchain,
+        allocator: AllocationCallbacksRef,
+    ) {
+        if let Some(fn_destroy_swapchain) = self.fn_destroy_swapchain {
+            unsafe { (fn_destroy_swapchain)(device, swapchain, allocator) }
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_swapchain_images(
+        &self,
+        device: Device,
+        swapchain: Swapchain,
+    ) -> crate::Result<Vec<Image>> {
+        let Some(fn_get_swapchain_images) = self.fn_get_swapchain_images else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: GetSwapchainImages,
+            }
+            .into_result();
+        };
+
+        unsafe {
+            enumerate(GetSwapchainImages, |count, images| {
+                (fn_get_swapchain_images)(device, swapchain, count, images)
+            })
+        }
+    }
+
+    /// Acquires the next presentable image of `swapchain`.
+    ///
+    /// Unlike most wrappers this hands back the raw `VkResult`, because `VK_SUBOPTIMAL_KHR`
+    /// still yields a valid image and `VK_ERROR_OUT_OF_DATE_KHR` calls for recreation rather
+    /// than failure. The image index is only meaningful for `Success` and `SuboptimalKhr`.
+    /// Fails only if `VK_KHR_swapchain` is not enabled.
+    #[inline]
+    pub(crate) unsafe fn acquire_next_image(
+        &self,
+        device: Device,
+        swapchain: Swapchain,
+        timeout: u64,
+        semaphore: Option<Semaphore>,
+        fence: Option<Fence>,
+    ) -> crate::Result<(VkResult, u32)> {
+        let Some(fn_acquire_next_image) = self.fn_acquire_next_image else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: AcquireNextImage,
+            }
+            .into_result();
+        };
+
+        let mut image_index = 0;
+
+        let result = unsafe {
+            (fn_acquire_next_image)(
+                device,
+                swapchain,
+                timeout,
+                semaphore,
+                fence,
+                &mut image_index,
+            )
+        };
+
+        Ok((result, image_index))
+    }
+
+    /// Queues an image for presentation.
+    ///
+    /// Returns the raw `VkResult` for the same reasons as
+    /// [`acquire_next_image`](Self::acquire_next_image), and fails only if `VK_KHR_swapchain`
+    /// is not enabled.
+    #[inline]
+    pub(crate) unsafe fn queue_present(
+        &self,
+        queue: Queue,
+        present_info: &PresentInfo,
+    ) -> crate::Result<VkResult> {
+        let Some(fn_queue_present) = self.fn_queue_present else {
+            return VulkanErrorKind::FunctionLoadFailed { name: QueuePresent }.into_result();
+        };
+
+        Ok(unsafe { (fn_queue_present)(queue, present_info) })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_fence(
+        &self,
+        device: Device,
+        create_info: &FenceCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Fence> {
+        let mut fence = MaybeUninit::<Fence>::uninit();
+
+        unsafe { (self.fn_create_fence)(device, create_info, allocator, fence.as_mut_ptr()) }
+            .into_result(CreateFence, || unsafe { fence.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_fence(
+        &self,
+        device: Device,
+        fence: Fence,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_fence)(device, fence, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn wait_for_fences(
+        &self,
+        device: Device,
+        fences: &[Fence],
+        wait_all: bool,
+        timeout: u64,
+    ) -> crate::Result<()> {
+        unsafe {
+            (self.fn_wait_for_fences)(
+                device,
+                fences.len() as u32,
+                fences.as_ptr(),
+                wait_all as u32,
+                timeout,
+            )
+        }
+        .into_result(WaitForFences, || ())
+    }
+
+    #[inline]
+    pub(crate) unsafe fn reset_fences(
+        &self,
+        device: Device,
+        fences: &[Fence],
+    ) -> crate::Result<()> {
+        unsafe { (self.fn_reset_fences)(device, fences.len() as u32, fences.as_ptr()) }
+            .into_result(ResetFences, || ())
+    }
+
+    /// Whether `fence` is signaled.
+    #[inline]
+    pub(crate) unsafe fn get_fence_status(
+        &self,
+        device: Device,
+        fence: Fence,
+    ) -> crate::Result<bool> {
+        match unsafe { (self.fn_get_fence_status)(device, fence) } {
+            VkResult::NotReady => Ok(false),
+            result => result.into_result(GetFenceStatus, || true),
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_semaphore(
+        &self,
+        device: Device,
+        create_info: &SemaphoreCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Semaphore> {
+        let mut semaphore = MaybeUninit::<Semaphore>::uninit();
+
+        unsafe {
+            (self.fn_create_semaphore)(device, create_info, allocator, semaphore.as_mut_ptr())
+        }
+        .into_result(CreateSemaphore, || unsafe { semaphore.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_semaphore(
+        &self,
+        device: Device,
+        semaphore: Semaphore,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_semaphore)(device, semaphore, allocator) }
+    }
+
+    /// Sets the counter of a timeline semaphore from the host.
+    #[inline]
+    pub(crate) unsafe fn signal_semaphore(
+        &self,
+        device: Device,
+        signal_info: &SemaphoreSignalInfo,
+    ) -> crate::Result<()> {
+        let Some(fn_signal_semaphore) = self.fn_signal_semaphore else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: SignalSemaphore,
+            }
+            .into_result();
+        };
+
+        unsafe { (fn_signal_semaphore)(device, signal_info) }.into_result(SignalSemaphore, || ())
+    }
+
+    /// Waits for timeline semaphores to reach their values. Running out of `timeout`
+    /// nanoseconds is reported as a `Timeout` return code.
+    #[inline]
+    pub(crate) unsafe fn wait_semaphores(
+        &self,
+        device: Device,
+        wait_info: &SemaphoreWaitInfo,
+        timeout: u64,
+    ) -> crate::Result<()> {
+        let Some(fn_wait_semaphores) = self.fn_wait_semaphores else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: WaitSemaphores,
+            }
+            .into_result();
+        };
+
+        unsafe { (fn_wait_semaphores)(device, wait_info, timeout) }
+            .into_result(WaitSemaphores, || ())
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_semaphore_counter_value(
+        &self,
+        device: Device,
+        semaphore: Semaphore,
+    ) -> crate::Result<u64> {
+        let Some(fn_get_semaphore_counter_value) = self.fn_get_semaphore_counter_value else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: GetSemaphoreCounterValue,
+            }
+            .into_result();
+        };
+
+        let mut value = 0;
+
+        unsafe { (fn_get_semaphore_counter_value)(device, semaphore, &mut value) }
+            .into_result(GetSemaphoreCounterValue, || value)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_command_pool(
+        &self,
+        device: Device,
+        create_info: &CommandPoolCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<CommandPool> {
+        let mut command_pool = MaybeUninit::<CommandPool>::uninit();
+
+        unsafe {
+            (self.fn_create_command_pool)(device, create_info, allocator, command_pool.as_mut_ptr())
+        }
+        .into_result(CreateCommandPool, || unsafe { command_pool.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_command_pool(
+        &self,
+        device: Device,
+        command_pool: CommandPool,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_command_pool)(device, command_pool, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn reset_command_pool(
+        &self,
+        device: Device,
+        command_pool: CommandPool,
+        flags: CommandPoolResetFlags,
+    ) -> crate::Result<()> {
+        unsafe { (self.fn_reset_command_pool)(device, command_pool, flags) }
+            .into_result(ResetCommandPool, || ())
+    }
+
+    /// Allocates a single command buffer.
+    #[inline]
+    pub(crate) unsafe fn allocate_command_buffer(
+        &self,
+        device: Device,
+        allocate_info: &CommandBufferAllocateInfo,
+    ) -> crate::Result<CommandBuffer> {
+        debug_assert_eq!(allocate_info.command_buffer_count, 1);
+
+        let mut command_buffer = MaybeUninit::<CommandBuffer>::uninit();
+
+        unsafe {
+            (self.fn_allocate_command_buffers)(device, allocate_info, command_buffer.as_mut_ptr())
+        }
+        .into_result(AllocateCommandBuffers, || unsafe {
+            command_buffer.assume_init()
+        })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn begin_command_buffer(
+        &self,
+        command_buffer: CommandBuffer,
+        begin_info: &CommandBufferBeginInfo,
+    ) -> crate::Result<()> {
+        unsafe { (self.fn_begin_command_buffer)(command_buffer, begin_info) }
+            .into_result(BeginCommandBuffer, || ())
+    }
+
+    #[inline]
+    pub(crate) unsafe fn end_command_buffer(
+        &self,
+        command_buffer: CommandBuffer,
+    ) -> crate::Result<()> {
+        unsafe { (self.fn_end_command_buffer)(command_buffer) }.into_result(EndCommandBuffer, || ())
+    }
+
+    #[inline]
+    pub(crate) unsafe fn reset_command_buffer(
+        &self,
+        command_buffer: CommandBuffer,
+        flags: CommandBufferResetFlags,
+    ) -> crate::Result<()> {
+        unsafe { (self.fn_reset_command_buffer)(command_buffer, flags) }
+            .into_result(ResetCommandBuffer, || ())
+    }
+
+    #[inline]
+    pub(crate) unsafe fn queue_submit(
+        &self,
+        queue: Queue,
+        submits: &[SubmitInfo],
+        fence: Option<Fence>,
+    ) -> crate::Result<()> {
+        unsafe { (self.fn_queue_submit)(queue, submits.len() as u32, submits.as_ptr(), fence) }
+            .into_result(QueueSubmit, || ())
+    }
+
+    /// Records a pipeline barrier made of image memory barriers only.
+    #[inline]
+    pub(crate) unsafe fn cmd_pipeline_barrier(
+        &self,
+        command_buffer: CommandBuffer,
+        src_stage_mask: PipelineStageFlags,
+        dst_stage_mask: PipelineStageFlags,
+        memory_barriers: &[MemoryBarrier],
+        image_memory_barriers: &[ImageMemoryBarrier],
+    ) {
+        unsafe {
+            (self.fn_cmd_pipeline_barrier)(
+                command_buffer,
+                src_stage_mask,
+                dst_stage_mask,
+                DependencyFlags::EMPTY,
+                memory_barriers.len() as u32,
+                memory_barriers.as_ptr(),
+                0,
+                null(),
+                image_memory_barriers.len() as u32,
+                image_memory_barriers.as_ptr(),
+            )
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_image(
+        &self,
+        device: Device,
+        create_info: &ImageCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Image> {
+        let mut image = MaybeUninit::<Image>::uninit();
+
+        unsafe { (self.fn_create_image)(device, create_info, allocator, image.as_mut_ptr()) }
+            .into_result(CreateImage, || unsafe { image.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_image(
+        &self,
+        device: Device,
+        image: Image,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_image)(device, image, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_image_memory_requirements(
+        &self,
+        device: Device,
+        image: Image,
+    ) -> MemoryRequirements {
+        let mut requirements = MemoryRequirements::default();
+
+        unsafe { (self.fn_get_image_memory_requirements)(device, image, &mut requirements) };
+
+        requirements
+    }
+
+    #[inline]
+    pub(crate) unsafe fn allocate_memory(
+        &self,
+        device: Device,
+        allocate_info: &MemoryAllocateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<DeviceMemory> {
+        let mut memory = MaybeUninit::<DeviceMemory>::uninit();
+
+        unsafe { (self.fn_allocate_memory)(device, allocate_info, allocator, memory.as_mut_ptr()) }
+            .into_result(AllocateMemory, || unsafe { memory.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn free_memory(
+        &self,
+        device: Device,
+        memory: DeviceMemory,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_free_memory)(device, memory, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn bind_image_memory(
+        &self,
+        device: Device,
+        image: Image,
+        memory: DeviceMemory,
+        memory_offset: u64,
+    ) -> crate::Result<()> {
+        unsafe { (self.fn_bind_image_memory)(device, image, memory, memory_offset) }
+            .into_result(BindImageMemory, || ())
+    }
+
+    /// Maps the first `size` bytes of `memory`.
+    #[inline]
+    pub(crate) unsafe fn map_memory(
+        &self,
+        device: Device,
+        memory: DeviceMemory,
+        size: u64,
+    ) -> crate::Result<NonNull<u8>> {
+        let mut data = null_mut();
+
+        let data = unsafe {
+            (self.fn_map_memory)(device, memory, 0, size, MemoryMapFlags::EMPTY, &mut data)
+        }
+        .into_result(MapMemory, || NonNull::new(data.cast()))?;
+
+        match data {
+            Some(data) => Ok(data),
+            None => VulkanErrorKind::FunctionReturn {
+                name: MapMemory,
+                code: ReturnCode::MemoryMapFailed,
+            }
+            .into_result(),
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_buffer(
+        &self,
+        device: Device,
+        create_info: &BufferCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Buffer> {
+        let mut buffer = MaybeUninit::<Buffer>::uninit();
+
+        unsafe { (self.fn_create_buffer)(device, create_info, allocator, buffer.as_mut_ptr()) }
+            .into_result(CreateBuffer, || unsafe { buffer.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_buffer(
+        &self,
+        device: Device,
+        buffer: Buffer,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_buffer)(device, buffer, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_buffer_memory_requirements(
+        &self,
+        device: Device,
+        buffer: Buffer,
+    ) -> MemoryRequirements {
+        let mut requirements = MemoryRequirements::default();
+
+        unsafe { (self.fn_get_buffer_memory_requirements)(device, buffer, &mut requirements) };
+
+        requirements
+    }
+
+    #[inline]
+    pub(crate) unsafe fn bind_buffer_memory(
+        &self,
+        device: Device,
+        buffer: Buffer,
+        memory: DeviceMemory,
+        memory_offset: u64,
+    ) -> crate::Result<()> {
+        unsafe { (self.fn_bind_buffer_memory)(device, buffer, memory, memory_offset) }
+            .into_result(BindBufferMemory, || ())
+    }
+
+    #[inline]
+    pub(crate) unsafe fn cmd_copy_buffer(
+        &self,
+        command_buffer: CommandBuffer,
+        src_buffer: Buffer,
+        dst_buffer: Buffer,
+        regions: &[BufferCopy],
+    ) {
+        unsafe {
+            (self.fn_cmd_copy_buffer)(
+                command_buffer,
+                src_buffer,
+                dst_buffer,
+                regions.len() as u32,
+                regions.as_ptr(),
+            )
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_image_view(
+        &self,
+        device: Device,
+        create_info: &ImageViewCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<ImageView> {
+        let mut view = MaybeUninit::<ImageView>::uninit();
+
+        unsafe { (self.fn_create_image_view)(device, create_info, allocator, view.as_mut_ptr()) }
+            .into_result(CreateImageView, || unsafe { view.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_image_view(
+        &self,
+        device: Device,
+        image_view: ImageView,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_image_view)(device, image_view, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_sampler(
+        &self,
+        device: Device,
+        create_info: &SamplerCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Sampler> {
+        let mut sampler = MaybeUninit::<Sampler>::uninit();
+
+        unsafe { (self.fn_create_sampler)(device, create_info, allocator, sampler.as_mut_ptr()) }
+            .into_result(CreateSampler, || unsafe { sampler.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_sampler(
+        &self,
+        device: Device,
+        sampler: Sampler,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_sampler)(device, sampler, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn cmd_copy_buffer_to_image(
+        &self,
+        command_buffer: CommandBuffer,
+        src_buffer: Buffer,
+        dst_image: Image,
+        dst_image_layout: ImageLayout,
+        regions: &[BufferImageCopy],
+    ) {
+        unsafe {
+            (self.fn_cmd_copy_buffer_to_image)(
+                command_buffer,
+                src_buffer,
+                dst_image,
+                dst_image_layout,
+                regions.len() as u32,
+                regions.as_ptr(),
+            )
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn cmd_blit_image(
+        &self,
+        command_buffer: CommandBuffer,
+        (src_image, src_image_layout): (Image, ImageLayout),
+        (dst_image, dst_image_layout): (Image, ImageLayout),
+        regions: &[ImageBlit],
+        filter: Filter,
+    ) {
+        unsafe {
+            (self.fn_cmd_blit_image)(
+                command_buffer,
+                src_image,
+                src_image_layout,
+                dst_image,
+                dst_image_layout,
+                regions.len() as u32,
+                regions.as_ptr(),
+                filter,
+            )
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_shader_module(
+        &self,
+        device: Device,
+        create_info: &ShaderModuleCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<ShaderModule> {
+        let mut shader_module = MaybeUninit::<ShaderModule>::uninit();
+
+        unsafe {
+            (self.fn_create_shader_module)(
+                device,
+                create_info,
+                allocator,
+                shader_module.as_mut_ptr(),
+            )
+        }
+        .into_result(CreateShaderModule, || unsafe {
+            shader_module.assume_init()
+        })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_shader_module(
+        &self,
+        device: Device,
+        shader_module: ShaderModule,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_shader_module)(device, shader_module, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_descriptor_set_layout(
+        &self,
+        device: Device,
+        create_info: &DescriptorSetLayoutCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<DescriptorSetLayout> {
+        let mut set_layout = MaybeUninit::<DescriptorSetLayout>::uninit();
+
+        unsafe {
+            (self.fn_create_descriptor_set_layout)(
+                device,
+                create_info,
+                allocator,
+                set_layout.as_mut_ptr(),
+            )
+        }
+        .into_result(CreateDescriptorSetLayout, || unsafe {
+            set_layout.assume_init()
+        })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_descriptor_set_layout(
+        &self,
+        device: Device,
+        set_layout: DescriptorSetLayout,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_descriptor_set_layout)(device, set_layout, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_descriptor_pool(
+        &self,
+        device: Device,
+        create_info: &DescriptorPoolCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<DescriptorPool> {
+        let mut descriptor_pool = MaybeUninit::<DescriptorPool>::uninit();
+
+        unsafe {
+            (self.fn_create_descriptor_pool)(
+                device,
+                create_info,
+                allocator,
+                descriptor_pool.as_mut_ptr(),
+            )
+        }
+        .into_result(CreateDescriptorPool, || unsafe {
+            descriptor_pool.assume_init()
+        })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_descriptor_pool(
+        &self,
+        device: Device,
+        descriptor_pool: DescriptorPool,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_descriptor_pool)(device, descriptor_pool, allocator) }
+    }
+
+    /// Frees every set allocated from `descriptor_pool`.
+    #[inline]
+    pub(crate) unsafe fn reset_descriptor_pool(
+        &self,
+        device: Device,
+        descriptor_pool: DescriptorPool,
+    ) -> crate::Result<()> {
+        unsafe {
+            (self.fn_reset_descriptor_pool)(
+                device,
+                descriptor_pool,
+                DescriptorPoolResetFlags::EMPTY,
+            )
+        }
+        .into_result(ResetDescriptorPool, || ())
+    }
+
+    /// Allocates a single descriptor set.
+    #[inline]
+    pub(crate) unsafe fn allocate_descriptor_set(
+        &self,
+        device: Device,
+        allocate_info: &DescriptorSetAllocateInfo,
+    ) -> crate::Result<DescriptorSet> {
+        debug_assert_eq!(allocate_info.descriptor_set_count, 1);
+
+        let mut descriptor_set = MaybeUninit::<DescriptorSet>::uninit();
+
+        unsafe {
+            (self.fn_allocate_descriptor_sets)(device, allocate_info, descriptor_set.as_mut_ptr())
+        }
+        .into_result(AllocateDescriptorSets, || unsafe {
+            descriptor_set.assume_init()
+        })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn update_descriptor_sets(
+        &self,
+        device: Device,
+        descriptor_writes: &[WriteDescriptorSet],
+    ) {
+        unsafe {
+            (self.fn_update_descriptor_sets)(
+                device,
+                descriptor_writes.len() as u32,
+                descriptor_writes.as_ptr(),
+                0,
+                null(),
+            )
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_pipeline_layout(
+        &self,
+        device: Device,
+        create_info: &PipelineLayoutCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<PipelineLayout> {
+        let mut pipeline_layout = MaybeUninit::<PipelineLayout>::uninit();
+
+        unsafe {
+            (self.fn_create_pipeline_layout)(
+                device,
+                create_info,
+                allocator,
+                pipeline_layout.as_mut_ptr(),
+            )
+        }
+        .into_result(CreatePipelineLayout, || unsafe {
+            pipeline_layout.assume_init()
+        })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_pipeline_layout(
+        &self,
+        device: Device,
+        pipeline_layout: PipelineLayout,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_pipeline_layout)(device, pipeline_layout, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_pipeline_cache(
+        &self,
+        device: Device,
+        create_info: &PipelineCacheCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<PipelineCache> {
+        let mut pipeline_cache = MaybeUninit::<PipelineCache>::uninit();
+
+        unsafe {
+            (self.fn_create_pipeline_cache)(
+                device,
+                create_info,
+                allocator,
+                pipeline_cache.as_mut_ptr(),
+            )
+        }
+        .into_result(CreatePipelineCache, || unsafe {
+            pipeline_cache.assume_init()
+        })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_pipeline_cache(
+        &self,
+        device: Device,
+        pipeline_cache: PipelineCache,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_pipeline_cache)(device, pipeline_cache, allocator) }
+    }
+
+    /// Returns the contents of `pipeline_cache`, starting with its header.
+    pub(crate) unsafe fn get_pipeline_cache_data(
+        &self,
+        device: Device,
+        pipeline_cache: PipelineCache,
+    ) -> crate::Result<Vec<u8>> {
+        loop {
+            let mut size = 0;
+
+            unsafe {
+                (self.fn_get_pipeline_cache_data)(device, pipeline_cache, &mut size, null_mut())
+            }
+            .into_result(GetPipelineCacheData, || ())?;
+
+            let mut data = Vec::<u8>::with_capacity(size);
+
+            let result = unsafe {
+                (self.fn_get_pipeline_cache_data)(
+                    device,
+                    pipeline_cache,
+                    &mut size,
+                    data.as_mut_ptr().cast(),
+                )
+            };
+
+            // The cache grew in between, as pipelines may be created concurrently.
+            if result == VkResult::Incomplete {
+                continue;
+            }
+
+            return result.into_result(GetPipelineCacheData, || {
+                unsafe { data.set_len(size) };
+
+                data
+            });
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_graphics_pipeline(
+        &self,
+        device: Device,
+        pipeline_cache: Option<PipelineCache>,
+        create_info: &GraphicsPipelineCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Pipeline> {
+        let mut pipeline = MaybeUninit::<Pipeline>::uninit();
+
+        unsafe {
+            (self.fn_create_graphics_pipelines)(
+                device,
+                pipeline_cache,
+                1,
+                create_info,
+                allocator,
+                pipeline.as_mut_ptr(),
+            )
+        }
+        .into_result(CreateGraphicsPipelines, || unsafe {
+            pipeline.assume_init()
+        })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_pipeline(
+        &self,
+        device: Device,
+        pipeline: Pipeline,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_pipeline)(device, pipeline, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_compute_pipeline(
+        &self,
+        device: Device,
+        pipeline_cache: Option<PipelineCache>,
+        create_info: &ComputePipelineCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Pipeline> {
+        let mut pipeline = MaybeUninit::<Pipeline>::uninit();
+
+        unsafe {
+            (self.fn_create_compute_pipelines)(
+                device,
+                pipeline_cache,
+                1,
+                create_info,
+                allocator,
+                pipeline.as_mut_ptr(),
+            )
+        }
+        .into_result(CreateComputePipelines, || unsafe { pipeline.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn cmd_bind_pipeline(
+        &self,
+        command_buffer: CommandBuffer,
+        pipeline_bind_point: PipelineBindPoint,
+        pipeline: Pipeline,
+    ) {
+        unsafe { (self.fn_cmd_bind_pipeline)(command_buffer, pipeline_bind_point, pipeline) }
+    }
+
+    /// Binds `descriptor_sets` to consecutive sets of `layout` from `first_set`, without
+    /// dynamic offsets.
+    #[inline]
+    pub(crate) unsafe fn cmd_bind_descriptor_sets(
+        &self,
+        command_buffer: CommandBuffer,
+        pipeline_bind_point: PipelineBindPoint,
+        layout: PipelineLayout,
+        first_set: u32,
+        descriptor_sets: &[DescriptorSet],
+    ) {
+        unsafe {
+            (self.fn_cmd_bind_descriptor_sets)(
+                command_buffer,
+                pipeline_bind_point,
+                layout,
+                first_set,
+                descriptor_sets.len() as u32,
+                descriptor_sets.as_ptr(),
+                0,
+                null(),
+            )
+        }
+    }
+
+    /// Updates the push constants at `offset` with the bytes of `values`.
+    #[inline]
+    pub(crate) unsafe fn cmd_push_constants<T: Copy>(
+        &self,
+        command_buffer: CommandBuffer,
+        layout: PipelineLayout,
+        stage_flags: ShaderStageFlags,
+        offset: u32,
+        values: &T,
+    ) {
+        unsafe {
+            (self.fn_cmd_push_constants)(
+                command_buffer,
+                layout,
+                stage_flags,
+                offset,
+                size_of::<T>() as u32,
+                (values as *const T).cast(),
+            )
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn cmd_dispatch(&self, command_buffer: CommandBuffer, [x, y, z]: [u32; 3]) {
+        unsafe { (self.fn_cmd_dispatch)(command_buffer, x, y, z) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn cmd_dispatch_indirect(
+        &self,
+        command_buffer: CommandBuffer,
+        buffer: Buffer,
+        offset: u64,
+    ) {
+        unsafe { (self.fn_cmd_dispatch_indirect)(command_buffer, buffer, offset) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_render_pass(
+        &self,
+        device: Device,
+        create_info: &RenderPassCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<RenderPass> {
+        let mut render_pass = MaybeUninit::<RenderPass>::uninit();
+
+        unsafe {
+            (self.fn_create_render_pass)(device, create_info, allocator, render_pass.as_mut_ptr())
+        }
+        .into_result(CreateRenderPass, || unsafe { render_pass.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_render_pass(
+        &self,
+        device: Device,
+        render_pass: RenderPass,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_render_pass)(device, render_pass, allocator) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_framebuffer(
+        &self,
+        device: Device,
+        create_info: &FramebufferCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Framebuffer> {
+        let mut framebuffer = MaybeUninit::<Framebuffer>::uninit();
+
+        unsafe {
+            (self.fn_create_framebuffer)(device, create_info, allocator, framebuffer.as_mut_ptr())
+        }
+        .into_result(CreateFramebuffer, || unsafe { framebuffer.assume_init() })
+    }
+
+    #[inline]
+    pub(crate) unsafe fn destroy_framebuffer(
+        &self,
+        device: Device,
+        framebuffer: Framebuffer,
+        allocator: AllocationCallbacksRef,
+    ) {
+        unsafe { (self.fn_destroy_framebuffer)(device, framebuffer, allocator) }
+    }
+
+    /// Begins `render_pass_begin` with the commands of its subpass recorded inline.
+    #[inline]
+    pub(crate) unsafe fn cmd_begin_render_pass(
+        &self,
+        command_buffer: CommandBuffer,
+        render_pass_begin: &RenderPassBeginInfo,
+    ) {
+        unsafe {
+            (self.fn_cmd_begin_render_pass)(
+                command_buffer,
+                render_pass_begin,
+                SubpassContents::INLINE,
+            )
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn cmd_end_render_pass(&self, command_buffer: CommandBuffer) {
+        unsafe { (self.fn_cmd_end_render_pass)(command_buffer) }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn cmd_begin_rendering(
+        &self,
+        command_buffer: CommandBuffer,
+        rendering_info: &RenderingInfo,
+    ) -> crate::Result<()> {
+        let Some(fn_cmd_begin_rendering) = self.fn_cmd_begin_rendering else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: CmdBeginRendering,
+            }
+            .into_result();
+        };
+
+        unsafe { (fn_cmd_begin_rendering)(command_buffer, rendering_info) };
+
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) unsafe fn cmd_end_rendering(
+        &self,
+        command_buffer: CommandBuffer,
+    ) -> crate::Result<()> {
+        let Some(fn_cmd_end_rendering) = self.fn_cmd_end_rendering else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: CmdEndRendering,
+            }
+            .into_result();
+        };
+
+        unsafe { (fn_cmd_end_rendering)(command_buffer) };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ffi::{CStr, c_char, c_void};
+
+    use mayon_core::{BaseError, CreateDeviceErrorKind, logger::DefaultLogger};
+
+    use super::*;
+    use crate::{
+        Instance, VulkanBackendParams, VulkanLoader,
+        fn_table::{MockDriver, mock},
+    };
+
+    /// Resolves like the mock driver, except that the device table lacks `vkCreateFence`.
+    unsafe extern "system" fn get_instance_proc_addr(
+        instance: *mut c_void,
+        name: *const c_char,
+    ) -> Option<unsafe extern "system" fn()> {
+        if unsafe { CStr::from_ptr(name) } == c"vkGetDeviceProcAddr" {
+            let get_device_proc_addr: PfnGetDeviceProcAddr = get_device_proc_addr;
+
+            return Some(unsafe { transmute_copy(&get_device_proc_addr) });
+        }
+
+        unsafe { mock::get_instance_proc_addr(instance, name) }
+    }
+
+    unsafe extern "system" fn get_device_proc_addr(
+        device: Device,
+        name: *const c_char,
+    ) -> Option<unsafe extern "system" fn()> {
+        if unsafe { CStr::from_ptr(name) } == c"vkCreateFence" {
+            return None;
+        }
+
+        unsafe { mock::get_device_proc_addr(device, name) }
+    }
+
+    #[test]
+    fn test_device_functions_come_from_device() {
+        MockDriver::reset();
+
+        let params = VulkanBackendParams::default()
+            .with_loader(VulkanLoader::GetInstanceProcAddr(get_instance_proc_addr));
+
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+
+        let Err(err) = instance.create_device(&adapter) else {
+            panic!("Device creation should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            CreateDeviceErrorKind::BackendInternal(VulkanErrorKind::FunctionLoadFailed {
+                name: CreateFence
+            })
+        ));
+        assert_eq!(MockDriver::call_count(DestroyDevice), 1);
+
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_device_without_swapchain() {
+        // Instances without a target platform do not enable `VK_KHR_swapchain`, and a driver
+        // resolves none of its functions then.
+        let device = mock::device();
+
+        assert!(
+            unsafe { mock::get_device_proc_addr(device.handle, c"vkCreateSwapchainKHR".as_ptr()) }
+                .is_none()
+        );
+
+        let swapchain = unsafe { Swapchain::from_raw_unchecked(1) };
+        let Err(err) = (unsafe { device.fns.get_swapchain_images(device.handle, swapchain) })
+        else {
+            panic!("Swapchain images should not be available");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            VulkanErrorKind::FunctionLoadFailed {
+                name: GetSwapchainImages
+            }
+        ));
+
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
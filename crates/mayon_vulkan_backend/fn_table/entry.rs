@@ -0,0 +1,185 @@
+use core::{
+    ffi::{CStr, c_char},
+    mem::MaybeUninit,
+    ptr::null,
+};
+
+use std::sync::Arc;
+
+use libloading::Library;
+
+use super::{PfnGetInstanceProcAddr, ProcAddrLoader, VulkanFunctionName, VulkanLoader, enumerate};
+use crate::{
+    VulkanErrorKind, VulkanVersion,
+    types::{
+        AllocationCallbacksRef, ExtensionProperties, Instance, InstanceCreateInfo, LayerProperties,
+        VkResult,
+    },
+};
+
+use VulkanFunctionName::*;
+
+/// The `vkGetInstanceProcAddr` entry point and the global functions resolved through it.
+///
+/// Global functions are the ones callable before an instance exists. The library the entry
+/// point came from, if any, stays open for as long as the `Entry` lives.
+pub(crate) struct Entry {
+    library: Option<Arc<Library>>,
+
+    fn_get_instance_proc_addr: PfnGetInstanceProcAddr,
+
+    fn_create_instance: unsafe extern "system" fn(
+        create_info: *const InstanceCreateInfo,
+        allocator: AllocationCallbacksRef,
+        instance: *mut Instance,
+    ) -> VkResult,
+
+    fn_enumerate_instance_layer_properties: unsafe extern "system" fn(
+        property_count: *mut u32,
+        properties: *mut LayerProperties,
+    ) -> VkResult,
+
+    fn_enumerate_instance_extension_properties: unsafe extern "system" fn(
+        layer_name: *const c_char,
+        property_count: *mut u32,
+        properties: *mut ExtensionProperties,
+    ) -> VkResult,
+
+    /// Missing from Vulkan 1.0 loaders.
+    fn_enumerate_instance_version:
+        Option<unsafe extern "system" fn(api_version: *mut u32) -> VkResult>,
+}
+
+impl Entry {
+    /// Finds `vkGetInstanceProcAddr` through `loader` and resolves the global functions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LibraryLoad` if the library cannot be opened, or `FunctionLoadFailed` if
+    /// `vkGetInstanceProcAddr` or a global function is missing.
+    pub(crate) fn load(loader: VulkanLoader) -> crate::Result<Self> {
+        let library = match loader {
+            VulkanLoader::System => unsafe { super::loader::system_library().map(Arc::new) },
+            VulkanLoader::Path(path) => unsafe { Library::new(path).map(Arc::new) },
+            VulkanLoader::Library(library) => Ok(Arc::clone(library)),
+            VulkanLoader::GetInstanceProcAddr(get_instance_proc_addr) => {
+                return Self::new(None, get_instance_proc_addr);
+            }
+            #[cfg(any(test, feature = "mock_driver"))]
+            VulkanLoader::Mock => return Self::new(None, super::mock::get_instance_proc_addr),
+        };
+
+        let Ok(library) = library else {
+            return VulkanErrorKind::LibraryLoad.into_result();
+        };
+
+        let get_instance_proc_addr =
+            match unsafe { library.get::<PfnGetInstanceProcAddr>(GetInstanceProcAddr.as_ref()) } {
+                Ok(symbol) => *symbol,
+                Err(_) => {
+                    return VulkanErrorKind::FunctionLoadFailed {
+                        name: GetInstanceProcAddr,
+                    }
+                    .into_result();
+                }
+            };
+
+        Self::new(Some(library), get_instance_proc_addr)
+    }
+
+    fn new(
+        library: Option<Arc<Library>>,
+        get_instance_proc_addr: PfnGetInstanceProcAddr,
+    ) -> crate::Result<Self> {
+        let loader = ProcAddrLoader {
+            get_instance_proc_addr,
+            instance: None,
+        };
+
+        unsafe {
+            Ok(Self {
+                fn_create_instance: loader.load(CreateInstance)?,
+                fn_enumerate_instance_layer_properties: loader
+                    .load(EnumerateInstanceLayerProperties)?,
+                fn_enumerate_instance_extension_properties: loader
+                    .load(EnumerateInstanceExtensionProperties)?,
+                fn_enumerate_instance_version: loader.load_optional(EnumerateInstanceVersion),
+                fn_get_instance_proc_addr: get_instance_proc_addr,
+                library,
+            })
+        }
+    }
+
+    #[inline(always)]
+    pub(super) fn get_instance_proc_addr(&self) -> PfnGetInstanceProcAddr {
+        self.fn_get_instance_proc_addr
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_instance(
+        &self,
+        create_info: &InstanceCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> crate::Result<Instance> {
+        let mut instance = MaybeUninit::<Instance>::uninit();
+
+        unsafe { (self.fn_create_instance)(create_info, allocator, instance.as_mut_ptr()) }
+            .into_result(CreateInstance, || unsafe { instance.assume_init() })
+    }
+
+    /// The highest instance-level Vulkan version supported by the loader.
+    ///
+    /// Loaders predating Vulkan 1.1 lack `vkEnumerateInstanceVersion` and report 1.0.
+    #[inline]
+    pub(crate) fn enumerate_instance_version(&self) -> crate::Result<VulkanVersion> {
+        let Some(fn_enumerate_instance_version) = self.fn_enumerate_instance_version else {
+            return Ok(VulkanVersion::new(1, 0, 0));
+        };
+
+        let mut api_version = 0;
+
+        unsafe { (fn_enumerate_instance_version)(&mut api_version) }
+            .into_result(EnumerateInstanceVersion, || {
+                VulkanVersion::from_raw(api_version)
+            })
+    }
+
+    /// Lists the instance layers available to this entry point.
+    #[inline]
+    pub(crate) fn enumerate_instance_layer_properties(
+        &self,
+    ) -> crate::Result<Vec<LayerProperties>> {
+        unsafe {
+            enumerate(EnumerateInstanceLayerProperties, |count, properties| {
+                (self.fn_enumerate_instance_layer_properties)(count, properties)
+            })
+        }
+    }
+
+    /// Lists the instance extensions provided by `layer`, or by the implementation and
+    /// implicitly enabled layers when `layer` is `None`.
+    #[inline]
+    pub(crate) fn enumerate_instance_extension_properties(
+        &self,
+        layer: Option<&CStr>,
+    ) -> crate::Result<Vec<ExtensionProperties>> {
+        let layer = layer.map_or(null(), CStr::as_ptr);
+
+        unsafe {
+            enumerate(EnumerateInstanceExtensionProperties, |count, properties| {
+                (self.fn_enumerate_instance_extension_properties)(layer, count, properties)
+            })
+        }
+    }
+}
+
+impl Drop for Entry {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(library) = self.library.take().and_then(Arc::into_inner) {
+            // Nothing can be done about a library failing to unload, and panicking in a
+            // destructor could abort while unwinding.
+            let _ = library.close();
+        }
+    }
+}
@@ -1,7 +1,13 @@
-use strum::{Display, IntoStaticStr};
+use std::ffi::CString;
 
-#[derive(Copy, Clone, Debug, IntoStaticStr, Display, PartialEq, Eq)]
+use strum::{Display, EnumString, IntoStaticStr};
+
+#[derive(Copy, Clone, Debug, IntoStaticStr, Display, EnumString, PartialEq, Eq)]
 pub enum VulkanFunctionName {
+    #[strum(serialize = "vkGetInstanceProcAddr")]
+    GetInstanceProcAddr,
+    #[strum(serialize = "vkGetDeviceProcAddr")]
+    GetDeviceProcAddr,
     #[strum(serialize = "vkCreateInstance")]
     CreateInstance,
     #[strum(serialize = "vkDestroyInstance")]
@@ -16,8 +22,204 @@ pub enum VulkanFunctionName {
     CreateXlibSurface,
     #[strum(serialize = "vkDestroySurfaceKHR")]
     DestroySurface,
+    #[strum(serialize = "vkCreateDebugUtilsMessengerEXT")]
+    CreateDebugUtilsMessenger,
+    #[strum(serialize = "vkDestroyDebugUtilsMessengerEXT")]
+    DestroyDebugUtilsMessenger,
     #[strum(serialize = "vkEnumerateInstanceLayerProperties")]
     EnumerateInstanceLayerProperties,
+    #[strum(serialize = "vkEnumerateInstanceExtensionProperties")]
+    EnumerateInstanceExtensionProperties,
+    #[strum(serialize = "vkEnumerateInstanceVersion")]
+    EnumerateInstanceVersion,
+    #[strum(serialize = "vkEnumeratePhysicalDevices")]
+    EnumeratePhysicalDevices,
+    #[strum(serialize = "vkGetPhysicalDeviceProperties")]
+    GetPhysicalDeviceProperties,
+    #[strum(serialize = "vkGetPhysicalDeviceQueueFamilyProperties")]
+    GetPhysicalDeviceQueueFamilyProperties,
+    #[strum(serialize = "vkEnumerateDeviceExtensionProperties")]
+    EnumerateDeviceExtensionProperties,
+    #[strum(serialize = "vkCreateDevice")]
+    CreateDevice,
+    #[strum(serialize = "vkDestroyDevice")]
+    DestroyDevice,
+    #[strum(serialize = "vkGetDeviceQueue")]
+    GetDeviceQueue,
+    #[strum(serialize = "vkDeviceWaitIdle")]
+    DeviceWaitIdle,
+    #[strum(serialize = "vkGetPhysicalDeviceSurfaceSupportKHR")]
+    GetPhysicalDeviceSurfaceSupport,
+    #[strum(serialize = "vkGetPhysicalDeviceSurfaceCapabilitiesKHR")]
+    GetPhysicalDeviceSurfaceCapabilities,
+    #[strum(serialize = "vkGetPhysicalDeviceSurfaceFormatsKHR")]
+    GetPhysicalDeviceSurfaceFormats,
+    #[strum(serialize = "vkGetPhysicalDeviceSurfacePresentModesKHR")]
+    GetPhysicalDeviceSurfacePresentModes,
+    #[strum(serialize = "vkCreateSwapchainKHR")]
+    CreateSwapchain,
+    #[strum(serialize = "vkDestroySwapchainKHR")]
+    DestroySwapchain,
+    #[strum(serialize = "vkGetSwapchainImagesKHR")]
+    GetSwapchainImages,
+    #[strum(serialize = "vkAcquireNextImageKHR")]
+    AcquireNextImage,
+    #[strum(serialize = "vkQueuePresentKHR")]
+    QueuePresent,
+    #[strum(serialize = "vkCreateFence")]
+    CreateFence,
+    #[strum(serialize = "vkDestroyFence")]
+    DestroyFence,
+    #[strum(serialize = "vkWaitForFences")]
+    WaitForFences,
+    #[strum(serialize = "vkResetFences")]
+    ResetFences,
+    #[strum(serialize = "vkCreateSemaphore")]
+    CreateSemaphore,
+    #[strum(serialize = "vkDestroySemaphore")]
+    DestroySemaphore,
+    #[strum(serialize = "vkGetFenceStatus")]
+    GetFenceStatus,
+    #[strum(serialize = "vkCreateCommandPool")]
+    CreateCommandPool,
+    #[strum(serialize = "vkDestroyCommandPool")]
+    DestroyCommandPool,
+    #[strum(serialize = "vkResetCommandPool")]
+    ResetCommandPool,
+    #[strum(serialize = "vkAllocateCommandBuffers")]
+    AllocateCommandBuffers,
+    #[strum(serialize = "vkBeginCommandBuffer")]
+    BeginCommandBuffer,
+    #[strum(serialize = "vkEndCommandBuffer")]
+    EndCommandBuffer,
+    #[strum(serialize = "vkResetCommandBuffer")]
+    ResetCommandBuffer,
+    #[strum(serialize = "vkQueueSubmit")]
+    QueueSubmit,
+    #[strum(serialize = "vkCmdPipelineBarrier")]
+    CmdPipelineBarrier,
+    #[strum(serialize = "vkSignalSemaphore")]
+    SignalSemaphore,
+    #[strum(serialize = "vkWaitSemaphores")]
+    WaitSemaphores,
+    #[strum(serialize = "vkGetSemaphoreCounterValue")]
+    GetSemaphoreCounterValue,
+    #[strum(serialize = "vkGetPhysicalDeviceImageFormatProperties")]
+    GetPhysicalDeviceImageFormatProperties,
+    #[strum(serialize = "vkGetPhysicalDeviceMemoryProperties")]
+    GetPhysicalDeviceMemoryProperties,
+    #[strum(serialize = "vkGetPhysicalDeviceFormatProperties")]
+    GetPhysicalDeviceFormatProperties,
+    #[strum(serialize = "vkGetPhysicalDeviceFeatures2")]
+    GetPhysicalDeviceFeatures2,
+    #[strum(serialize = "vkCreateImage")]
+    CreateImage,
+    #[strum(serialize = "vkDestroyImage")]
+    DestroyImage,
+    #[strum(serialize = "vkGetImageMemoryRequirements")]
+    GetImageMemoryRequirements,
+    #[strum(serialize = "vkAllocateMemory")]
+    AllocateMemory,
+    #[strum(serialize = "vkFreeMemory")]
+    FreeMemory,
+    #[strum(serialize = "vkBindImageMemory")]
+    BindImageMemory,
+    #[strum(serialize = "vkMapMemory")]
+    MapMemory,
+    #[strum(serialize = "vkCreateBuffer")]
+    CreateBuffer,
+    #[strum(serialize = "vkDestroyBuffer")]
+    DestroyBuffer,
+    #[strum(serialize = "vkGetBufferMemoryRequirements")]
+    GetBufferMemoryRequirements,
+    #[strum(serialize = "vkBindBufferMemory")]
+    BindBufferMemory,
+    #[strum(serialize = "vkCmdCopyBuffer")]
+    CmdCopyBuffer,
+    #[strum(serialize = "vkCreateImageView")]
+    CreateImageView,
+    #[strum(serialize = "vkDestroyImageView")]
+    DestroyImageView,
+    #[strum(serialize = "vkCreateSampler")]
+    CreateSampler,
+    #[strum(serialize = "vkDestroySampler")]
+    DestroySampler,
+    #[strum(serialize = "vkCmdCopyBufferToImage")]
+    CmdCopyBufferToImage,
+    #[strum(serialize = "vkCmdBlitImage")]
+    CmdBlitImage,
+    #[strum(serialize = "vkCreateShaderModule")]
+    CreateShaderModule,
+    #[strum(serialize = "vkDestroyShaderModule")]
+    DestroyShaderModule,
+    #[strum(serialize = "vkCreateDescriptorSetLayout")]
+    CreateDescriptorSetLayout,
+    #[strum(serialize = "vkDestroyDescriptorSetLayout")]
+    DestroyDescriptorSetLayout,
+    #[strum(serialize = "vkCreateDescriptorPool")]
+    CreateDescriptorPool,
+    #[strum(serialize = "vkDestroyDescriptorPool")]
+    DestroyDescriptorPool,
+    #[strum(serialize = "vkResetDescriptorPool")]
+    ResetDescriptorPool,
+    #[strum(serialize = "vkAllocateDescriptorSets")]
+    AllocateDescriptorSets,
+    #[strum(serialize = "vkUpdateDescriptorSets")]
+    UpdateDescriptorSets,
+    #[strum(serialize = "vkCreatePipelineLayout")]
+    CreatePipelineLayout,
+    #[strum(serialize = "vkDestroyPipelineLayout")]
+    DestroyPipelineLayout,
+    #[strum(serialize = "vkCreatePipelineCache")]
+    CreatePipelineCache,
+    #[strum(serialize = "vkDestroyPipelineCache")]
+    DestroyPipelineCache,
+    #[strum(serialize = "vkGetPipelineCacheData")]
+    GetPipelineCacheData,
+    #[strum(serialize = "vkCreateGraphicsPipelines")]
+    CreateGraphicsPipelines,
+    #[strum(serialize = "vkDestroyPipeline")]
+    DestroyPipeline,
+    #[strum(serialize = "vkCreateRenderPass")]
+    CreateRenderPass,
+    #[strum(serialize = "vkDestroyRenderPass")]
+    DestroyRenderPass,
+    #[strum(serialize = "vkCreateFramebuffer")]
+    CreateFramebuffer,
+    #[strum(serialize = "vkDestroyFramebuffer")]
+    DestroyFramebuffer,
+    #[strum(serialize = "vkCmdBeginRenderPass")]
+    CmdBeginRenderPass,
+    #[strum(serialize = "vkCmdEndRenderPass")]
+    CmdEndRenderPass,
+    #[strum(serialize = "vkCmdBeginRendering")]
+    CmdBeginRendering,
+    #[strum(serialize = "vkCmdEndRendering")]
+    CmdEndRendering,
+    #[strum(serialize = "vkCmdBeginRenderingKHR")]
+    CmdBeginRenderingKhr,
+    #[strum(serialize = "vkCmdEndRenderingKHR")]
+    CmdEndRenderingKhr,
+    #[strum(serialize = "vkCreateComputePipelines")]
+    CreateComputePipelines,
+    #[strum(serialize = "vkCmdBindPipeline")]
+    CmdBindPipeline,
+    #[strum(serialize = "vkCmdBindDescriptorSets")]
+    CmdBindDescriptorSets,
+    #[strum(serialize = "vkCmdPushConstants")]
+    CmdPushConstants,
+    #[strum(serialize = "vkCmdDispatch")]
+    CmdDispatch,
+    #[strum(serialize = "vkCmdDispatchIndirect")]
+    CmdDispatchIndirect,
+}
+
+impl VulkanFunctionName {
+    /// The entry point name as a C string, as expected by `vkGetInstanceProcAddr`.
+    #[inline]
+    pub(crate) fn c_name(self) -> CString {
+        CString::new(<&str>::from(self)).expect("Vulkan function names contain no NUL bytes")
+    }
 }
 
 impl AsRef<str> for VulkanFunctionName {
@@ -1,16 +1,84 @@
+use core::{
+    ffi::{c_char, c_void},
+    hash::{Hash, Hasher},
+    mem, ptr,
+};
+use std::{path::Path, sync::Arc};
+
 use libloading::{Error, Library};
 
+/// Signature of `vkGetInstanceProcAddr`.
+///
+/// `instance` is the raw `VkInstance`, or null when resolving global functions.
+pub type PfnGetInstanceProcAddr = unsafe extern "system" fn(
+    instance: *mut c_void,
+    name: *const c_char,
+) -> Option<unsafe extern "system" fn()>;
+
+/// Where the backend finds the Vulkan entry point.
+///
+/// Every other function is resolved through `vkGetInstanceProcAddr` from the chosen source.
+/// Libraries and entry points compare equal by identity.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum VulkanLoader<'s> {
+    /// Searches the platform's usual loader names, e.g. `libvulkan.so.1` or `vulkan-1.dll`.
+    #[default]
+    System,
+
+    /// Opens the loader or ICD library at this path.
+    Path(&'s Path),
+
+    /// Uses a library the caller already opened. The backend holds its own reference,
+    /// keeping the library open until the backend is dropped.
+    Library(&'s Arc<Library>),
+
+    /// Resolves everything through this `vkGetInstanceProcAddr`, without opening a library.
+    GetInstanceProcAddr(PfnGetInstanceProcAddr),
+
+    /// Resolves everything to the in-process mock driver, see [`MockDriver`](crate::MockDriver).
+    #[cfg(any(test, feature = "mock_driver"))]
+    Mock,
+}
+
+impl PartialEq for VulkanLoader<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Path(a), Self::Path(b)) => a == b,
+            (Self::Library(a), Self::Library(b)) => Arc::ptr_eq(a, b),
+            (Self::GetInstanceProcAddr(a), Self::GetInstanceProcAddr(b)) => ptr::fn_addr_eq(*a, *b),
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for VulkanLoader<'_> {}
+
+impl Hash for VulkanLoader<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+
+        match self {
+            Self::Path(path) => path.hash(state),
+            Self::Library(library) => Arc::as_ptr(library).hash(state),
+            Self::GetInstanceProcAddr(get_instance_proc_addr) => {
+                (*get_instance_proc_addr as usize).hash(state);
+            }
+            _ => {}
+        }
+    }
+}
+
 #[cfg(target_os = "windows")]
 #[inline]
 #[allow(unsafe_op_in_unsafe_fn)]
-pub(super) unsafe fn vulkan_lib() -> Result<Library, Error> {
+pub(super) unsafe fn system_library() -> Result<Library, Error> {
     Library::new("vulkan-1.dll")
 }
 
 #[cfg(target_os = "linux")]
 #[inline]
 #[allow(unsafe_op_in_unsafe_fn)]
-pub(super) unsafe fn vulkan_lib() -> Result<Library, Error> {
+pub(super) unsafe fn system_library() -> Result<Library, Error> {
     let result = Library::new("libvulkan.so.1");
 
     if result.is_ok() {
@@ -23,7 +91,7 @@ pub(super) unsafe fn vulkan_lib() -> Result<Library, Error> {
 #[cfg(target_os = "macos")]
 #[inline]
 #[allow(unsafe_op_in_unsafe_fn)]
-pub(super) unsafe fn vulkan_lib() -> Result<Library, Error> {
+pub(super) unsafe fn system_library() -> Result<Library, Error> {
     let mut result = Library::new("libvulkan.1.dylib");
 
     if result.is_ok() {
@@ -42,7 +110,7 @@ pub(super) unsafe fn vulkan_lib() -> Result<Library, Error> {
 #[cfg(target_os = "ios")]
 #[inline]
 #[allow(unsafe_op_in_unsafe_fn)]
-pub(super) unsafe fn vulkan_lib() -> Result<Library, Error> {
+pub(super) unsafe fn system_library() -> Result<Library, Error> {
     let result = Library::new("libMoltenVK.dylib");
 
     if result.is_ok() {
@@ -0,0 +1,2662 @@
+//! In-process stand-in for the Vulkan driver.
+//!
+//! The mock table resolves every entry point to a fake implementation instead of loading
+//! the Vulkan library. The fakes report the Khronos validation layer, providing
+//! `VK_EXT_debug_utils`, the common surface extensions, and a single discrete GPU with one
+//! queue family that supports graphics, compute and transfer. Every handle they create is
+//! tracked until it is destroyed.
+//!
+//! Like a real loader, functions of instance extensions only resolve once their extension is
+//! enabled on the instance, and swapchain functions once `VK_KHR_swapchain` is enabled on the
+//! device.
+//!
+//! Submitted work completes immediately: fences are always signaled, timeline semaphores
+//! take their signaled values at submission, and recorded buffer copies are carried out on
+//! host-side copies of the memory objects. Image layouts are tracked per mip level as
+//! commands are recorded, and commands expecting another layout are reported by
+//! [`MockDriver::assert_valid_layouts`]. Dispatches expect the images of their bound
+//! descriptor sets in the layouts the sets were written with.
+//!
+//! Pipeline caches keep their initial data when it was written by the mock device, and
+//! grow with every pipeline created through them. Descriptor pools hold as many sets as
+//! they were created for, and report `VK_ERROR_OUT_OF_POOL_MEMORY` once full.
+//!
+//! The device supports the descriptor indexing features of bindless tables, as reported by
+//! `vkGetPhysicalDeviceFeatures2` when the instance was created for Vulkan 1.1 or later.
+//!
+//! The device reports `VK_KHR_dynamic_rendering`. Like a real driver, the core dynamic
+//! rendering functions only resolve when the instance was created for Vulkan 1.3, and the
+//! extension's functions only once the extension is enabled on the device.
+//!
+//! Driver state is kept per thread, so tests running in parallel do not observe each
+//! other's calls.
+
+use core::{
+    cell::RefCell,
+    ffi::{CStr, c_char, c_void},
+    mem::transmute,
+    ops::Range,
+    ptr::{copy, copy_nonoverlapping},
+    slice,
+};
+use std::ffi::CString;
+
+use VulkanFunctionName::*;
+
+use super::{PfnGetInstanceProcAddr, VulkanFunctionName};
+#[cfg(feature = "windowing")]
+use crate::types::{
+    WaylandSurfaceCreateInfo, Win32SurfaceCreateInfo, XcbSurfaceCreateInfo, XlibSurfaceCreateInfo,
+};
+use crate::{
+    ReturnCode, VulkanVersion,
+    types::{
+        AllocationCallbacksRef, Buffer, BufferCopy, BufferCreateInfo, BufferImageCopy, ColorSpace,
+        CommandBuffer, CommandBufferAllocateInfo, CommandBufferBeginInfo, CommandBufferResetFlags,
+        CommandPool, CommandPoolCreateInfo, CommandPoolResetFlags, CompositeAlphaFlags,
+        ComputePipelineCreateInfo, DebugUtilsMessenger, DebugUtilsMessengerCreateInfo,
+        DependencyFlags, DescriptorPool, DescriptorPoolCreateInfo, DescriptorPoolResetFlags,
+        DescriptorSet, DescriptorSetAllocateInfo, DescriptorSetLayout,
+        DescriptorSetLayoutCreateInfo, Device, DeviceCreateInfo, DeviceMemory, ExtensionName,
+        ExtensionProperties, Extent2D, Extent3D, Fence, FenceCreateInfo, Filter, Format,
+        FormatFeatureFlags, FormatProperties, Framebuffer, FramebufferCreateInfo,
+        GraphicsPipelineCreateInfo, Image, ImageBlit, ImageCreateFlags, ImageCreateInfo,
+        ImageFormatProperties, ImageLayout, ImageMemoryBarrier, ImageTiling, ImageType,
+        ImageUsageFlags, ImageView, ImageViewCreateInfo, Instance, InstanceCreateInfo, LayerName,
+        LayerProperties, MemoryAllocateInfo, MemoryBarrier, MemoryHeap, MemoryHeapFlags,
+        MemoryMapFlags, MemoryPropertyFlags, MemoryRequirements, MemoryType, PhysicalDevice,
+        PhysicalDeviceDescriptorIndexingFeatures, PhysicalDeviceFeatures2,
+        PhysicalDeviceMemoryProperties, PhysicalDeviceProperties, PhysicalDeviceType, Pipeline,
+        PipelineBindPoint, PipelineCache, PipelineCacheCreateInfo, PipelineCacheHeader,
+        PipelineLayout, PipelineLayoutCreateInfo, PipelineStageFlags, PresentInfo, PresentMode,
+        Queue, QueueFamilyProperties, QueueFlags, RenderPass, RenderPassBeginInfo,
+        RenderPassCreateInfo, RenderingInfo, Sampler, SamplerCreateInfo, Semaphore,
+        SemaphoreCreateInfo, SemaphoreSignalInfo, SemaphoreType, SemaphoreTypeCreateInfo,
+        SemaphoreWaitInfo, ShaderModule, ShaderModuleCreateInfo, ShaderStageFlags, StructureType,
+        SubmitInfo, SubpassContents, Surface, SurfaceCapabilities, SurfaceFormat,
+        SurfaceTransformFlags, Swapchain, SwapchainCreateInfo, TimelineSemaphoreSubmitInfo,
+        VkResult, WriteDescriptorSet,
+    },
+};
+
+const DEVICE_NAME: &[u8] = b"Mayon Mock Device\0";
+const VENDOR_ID: u32 = 0x10005;
+const DEVICE_ID: u32 = 0x1;
+const PIPELINE_CACHE_UUID: [u8; 16] = *b"mayon-mock-cache";
+const BUFFER_IMAGE_GRANULARITY: u64 = 1024;
+const SWAPCHAIN_IMAGE_COUNT: u32 = 3;
+
+const PHYSICAL_DEVICE: PhysicalDevice = unsafe { PhysicalDevice::from_raw_unchecked(0x1) };
+const QUEUE: Queue = unsafe { Queue::from_raw_unchecked(0x2) };
+
+/// Kind of object behind a [`MockHandle`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MockHandleKind {
+    Instance,
+    DebugUtilsMessenger,
+    Surface,
+    Device,
+    Swapchain,
+    Fence,
+    Semaphore,
+    CommandPool,
+    Image,
+    DeviceMemory,
+    Buffer,
+    ImageView,
+    Sampler,
+    ShaderModule,
+    DescriptorSetLayout,
+    PipelineLayout,
+    PipelineCache,
+    Pipeline,
+    RenderPass,
+    Framebuffer,
+    DescriptorPool,
+}
+
+/// A handle created by the mock driver.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MockHandle {
+    pub kind: MockHandleKind,
+    pub raw: u64,
+}
+
+/// Control and inspection of the mock driver for the calling thread.
+///
+/// Available with the `mock_driver` feature. Backends reach the mock through
+/// [`VulkanLoader::Mock`](crate::VulkanLoader::Mock).
+pub struct MockDriver;
+
+impl MockDriver {
+    /// The mock's `vkGetInstanceProcAddr`, for wrapping in a custom
+    /// [`VulkanLoader::GetInstanceProcAddr`](crate::VulkanLoader::GetInstanceProcAddr).
+    pub fn get_instance_proc_addr() -> PfnGetInstanceProcAddr {
+        get_instance_proc_addr
+    }
+
+    /// Forgets every recorded call, scripted failure and tracked handle.
+    pub fn reset() {
+        STATE.with_borrow_mut(|state| *state = State::new());
+    }
+
+    /// Makes the next call to `name` return `code` instead of succeeding.
+    ///
+    /// Failures queue up in order, so scripting the same function twice fails its next two
+    /// calls. Entry points without a return value cannot fail and ignore the script.
+    pub fn fail_next(name: VulkanFunctionName, code: ReturnCode) {
+        STATE.with_borrow_mut(|state| state.failures.push((name, code)));
+    }
+
+    /// Makes the next call to `name` that writes elements return `VK_INCOMPLETE`, as if an
+    /// element was added since the count was queried.
+    ///
+    /// Only the entry points enumerating with the two-call idiom use the script.
+    pub fn incomplete_next(name: VulkanFunctionName) {
+        STATE.with_borrow_mut(|state| state.incomplete.push(name));
+    }
+
+    /// Makes the physical device expose a single transfer queue family, as a device that can
+    /// neither render nor run compute work.
+    pub fn transfer_queue_only() {
+        STATE.with_borrow_mut(|state| state.transfer_queue_only = true);
+    }
+
+    /// Every entry point called so far, in call order.
+    pub fn calls() -> Vec<VulkanFunctionName> {
+        STATE.with_borrow(|state| state.calls.clone())
+    }
+
+    /// Number of times `name` has been called.
+    pub fn call_count(name: VulkanFunctionName) -> usize {
+        STATE.with_borrow(|state| state.calls.iter().filter(|&&call| call == name).count())
+    }
+
+    /// Handles that were created and not destroyed yet, in creation order.
+    pub fn live_handles() -> Vec<MockHandle> {
+        STATE.with_borrow(|state| state.live.clone())
+    }
+
+    /// Panics if a handle is still alive, or if a handle was destroyed that was not alive.
+    #[track_caller]
+    pub fn assert_no_leaks() {
+        STATE.with_borrow(|state| {
+            assert!(
+                state.invalid_destroys.is_empty(),
+                "Destroyed handles that were not alive: {:?}",
+                state.invalid_destroys
+            );
+            assert!(state.live.is_empty(), "Leaked handles: {:?}", state.live);
+        });
+    }
+
+    /// Panics if a recorded command used an image mip level in another layout than the one
+    /// its last transition left it in.
+    #[track_caller]
+    pub fn assert_valid_layouts() {
+        STATE.with_borrow(|state| {
+            assert!(
+                state.layout_mismatches.is_empty(),
+                "Image layout mismatches: {:?}",
+                state.layout_mismatches
+            );
+        });
+    }
+}
+
+struct State {
+    calls: Vec<VulkanFunctionName>,
+    failures: Vec<(VulkanFunctionName, ReturnCode)>,
+    /// Enumerations to answer with `VK_INCOMPLETE` once.
+    incomplete: Vec<VulkanFunctionName>,
+    /// Whether the physical device lacks graphics and compute queue families.
+    transfer_queue_only: bool,
+    live: Vec<MockHandle>,
+    invalid_destroys: Vec<MockHandle>,
+    next_handle: u64,
+    next_image_index: u32,
+    /// Counter values of live timeline semaphores, by raw handle.
+    timelines: Vec<(u64, u64)>,
+    /// Contents of live memory objects, by raw handle.
+    memory: Vec<(u64, Box<[u8]>)>,
+    buffers: Vec<MockBuffer>,
+    /// Buffer copies recorded into each command buffer since it was last begun.
+    copies: Vec<(usize, BufferCopyCommand)>,
+    /// Layout of each transitioned image mip level, by raw image handle and level.
+    image_layouts: Vec<((u64, u32), ImageLayout)>,
+    /// Image and mip levels of each image view, by raw view handle.
+    image_views: Vec<(u64, u64, Range<u32>)>,
+    /// View and layout written to each image descriptor, by raw set handle, binding and
+    /// array element.
+    descriptor_images: Vec<((u64, u32, u32), u64, ImageLayout)>,
+    /// Descriptor sets bound to each command buffer since it was last begun, by raw command
+    /// buffer handle and set index.
+    bound_sets: Vec<((usize, u32), u64)>,
+    /// Commands that found a mip level in another layout than they expected, described.
+    layout_mismatches: Vec<String>,
+    /// Contents of live pipeline caches, by raw handle.
+    pipeline_caches: Vec<(u64, Vec<u8>)>,
+    /// Live descriptor pools, by raw handle, with their capacity and allocated sets.
+    descriptor_pools: Vec<(u64, u32, u32)>,
+    /// API version the instance was created for.
+    api_version: VulkanVersion,
+    /// Extensions enabled on the last instance created.
+    instance_extensions: Vec<CString>,
+    /// Extensions enabled on the last device created.
+    device_extensions: Vec<CString>,
+}
+
+/// A live buffer, with the memory and offset it is bound to.
+struct MockBuffer {
+    raw: u64,
+    size: u64,
+    binding: Option<(u64, u64)>,
+}
+
+/// A `vkCmdCopyBuffer` call, replayed when its command buffer is submitted.
+struct BufferCopyCommand {
+    src: u64,
+    dst: u64,
+    regions: Vec<BufferCopy>,
+}
+
+impl State {
+    const fn new() -> Self {
+        Self {
+            calls: Vec::new(),
+            failures: Vec::new(),
+            incomplete: Vec::new(),
+            transfer_queue_only: false,
+            live: Vec::new(),
+            invalid_destroys: Vec::new(),
+            // Leaves room for the fixed physical device and queue handles.
+            next_handle: 0x100,
+            next_image_index: 0,
+            timelines: Vec::new(),
+            memory: Vec::new(),
+            buffers: Vec::new(),
+            copies: Vec::new(),
+            image_layouts: Vec::new(),
+            image_views: Vec::new(),
+            descriptor_images: Vec::new(),
+            bound_sets: Vec::new(),
+            layout_mismatches: Vec::new(),
+            pipeline_caches: Vec::new(),
+            descriptor_pools: Vec::new(),
+            api_version: VulkanVersion::new(1, 3, 0),
+            instance_extensions: Vec::new(),
+            device_extensions: Vec::new(),
+        }
+    }
+
+    /// Checks that mip level `mip_level` of `image` is in `expected`, then moves it to `new`.
+    ///
+    /// `UNDEFINED` is expected by transitions discarding the contents, and matches any layout.
+    fn use_layout(
+        &mut self,
+        name: VulkanFunctionName,
+        (image, mip_level): (u64, u32),
+        expected: ImageLayout,
+        new: ImageLayout,
+    ) {
+        let entry = match self
+            .image_layouts
+            .iter()
+            .position(|&(key, _)| key == (image, mip_level))
+        {
+            Some(index) => &mut self.image_layouts[index].1,
+            None => {
+                self.image_layouts
+                    .push(((image, mip_level), ImageLayout::UNDEFINED));
+
+                &mut self.image_layouts.last_mut().unwrap().1
+            }
+        };
+
+        let actual = *entry;
+
+        *entry = new;
+
+        if expected != ImageLayout::UNDEFINED && expected != actual {
+            self.layout_mismatches.push(format!(
+                "{name:?} expected image {image:#x} level {mip_level} in {expected:?}, found \
+                 {actual:?}"
+            ));
+        }
+    }
+
+    /// Checks the images of the descriptor sets bound to `command_buffer` are in the layouts
+    /// their descriptors were written with.
+    fn use_descriptor_images(&mut self, name: VulkanFunctionName, command_buffer: usize) {
+        let levels = self
+            .bound_sets
+            .iter()
+            .filter(|&&((raw, _), _)| raw == command_buffer)
+            .flat_map(|&(_, set)| {
+                self.descriptor_images
+                    .iter()
+                    .filter(move |&&((raw, ..), ..)| raw == set)
+            })
+            .filter_map(|&(_, view, layout)| {
+                let (_, image, levels) = self.image_views.iter().find(|(raw, ..)| *raw == view)?;
+
+                Some((*image, levels.clone(), layout))
+            })
+            .flat_map(|(image, levels, layout)| levels.map(move |level| (image, level, layout)))
+            .collect::<Vec<_>>();
+
+        for (image, mip_level, layout) in levels {
+            self.use_layout(name, (image, mip_level), layout, layout);
+        }
+    }
+
+    /// Address of byte `offset` of the memory `buffer` is bound to.
+    fn buffer_address(&mut self, buffer: u64, offset: u64) -> Option<*mut u8> {
+        let buffer = self.buffers.iter().find(|live| live.raw == buffer)?;
+        let (memory, memory_offset) = buffer.binding?;
+        let (_, contents) = self.memory.iter_mut().find(|(raw, _)| *raw == memory)?;
+
+        contents
+            .get_mut((memory_offset + offset) as usize..)
+            .map(<[u8]>::as_mut_ptr)
+    }
+
+    /// Replays the buffer copies recorded into `command_buffer`.
+    fn execute(&mut self, command_buffer: CommandBuffer) {
+        let copies = self
+            .copies
+            .iter()
+            .filter(|(raw, _)| *raw == command_buffer.as_raw())
+            .flat_map(|(_, copy)| {
+                copy.regions
+                    .iter()
+                    .map(|region| (copy.src, copy.dst, *region))
+            })
+            .collect::<Vec<_>>();
+
+        for (src, dst, region) in copies {
+            let src = self.buffer_address(src, region.src_offset);
+            let dst = self.buffer_address(dst, region.dst_offset);
+
+            if let (Some(src), Some(dst)) = (src, dst) {
+                unsafe { copy(src, dst, region.size as usize) };
+            }
+        }
+    }
+
+    fn timeline(&mut self, semaphore: Semaphore) -> Option<&mut u64> {
+        self.timelines
+            .iter_mut()
+            .find(|(raw, _)| *raw == semaphore.as_raw())
+            .map(|(_, value)| value)
+    }
+
+    /// Records a call to `name` and returns its scripted result.
+    fn call(&mut self, name: VulkanFunctionName) -> VkResult {
+        self.calls.push(name);
+
+        match self
+            .failures
+            .iter()
+            .position(|&(failing, _)| failing == name)
+        {
+            Some(index) => self.failures.remove(index).1.into(),
+            None => VkResult::Success,
+        }
+    }
+
+    fn next_raw(&mut self) -> u64 {
+        let raw = self.next_handle;
+
+        self.next_handle += 1;
+
+        raw
+    }
+
+    fn destroy(&mut self, kind: MockHandleKind, raw: u64) {
+        let handle = MockHandle { kind, raw };
+
+        match self.live.iter().position(|&live| live == handle) {
+            Some(index) => {
+                self.live.remove(index);
+            }
+            None => self.invalid_destroys.push(handle),
+        }
+    }
+}
+
+thread_local! {
+    static STATE: RefCell<State> = const { RefCell::new(State::new()) };
+}
+
+#[inline]
+fn call(name: VulkanFunctionName) -> VkResult {
+    STATE.with_borrow_mut(|state| state.call(name))
+}
+
+/// Records a call to `name` and, unless a failure is scripted, writes a new tracked handle.
+unsafe fn create<H>(
+    name: VulkanFunctionName,
+    kind: MockHandleKind,
+    handle: *mut H,
+    from_raw: impl FnOnce(u64) -> H,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(name);
+
+        if result == VkResult::Success {
+            let raw = state.next_raw();
+
+            state.live.push(MockHandle { kind, raw });
+
+            unsafe { handle.write(from_raw(raw)) };
+        }
+
+        result
+    })
+}
+
+#[inline]
+fn destroy(name: VulkanFunctionName, kind: MockHandleKind, raw: u64) {
+    STATE.with_borrow_mut(|state| {
+        state.calls.push(name);
+        state.destroy(kind, raw);
+    });
+}
+
+/// Answers one call of the two-call enumeration idiom of `name` with `items`.
+unsafe fn enumerate<T: Copy>(
+    name: VulkanFunctionName,
+    items: &[T],
+    count: *mut u32,
+    elements: *mut T,
+) -> VkResult {
+    unsafe {
+        if elements.is_null() {
+            count.write(items.len() as u32);
+
+            return VkResult::Success;
+        }
+
+        let written = (count.read() as usize).min(items.len());
+
+        // A scripted enumeration acts as if an element was added since the count was queried.
+        let grown = STATE.with_borrow_mut(|state| {
+            let index = state.incomplete.iter().position(|&growing| growing == name);
+
+            index.map(|index| state.incomplete.remove(index)).is_some()
+        });
+
+        copy_nonoverlapping(items.as_ptr(), elements, written);
+        count.write(written as u32);
+
+        if grown || written < items.len() {
+            VkResult::Incomplete
+        } else {
+            VkResult::Success
+        }
+    }
+}
+
+/// The default parameters, loading the mock driver.
+#[cfg(test)]
+pub(crate) fn params() -> crate::VulkanBackendParams<'static> {
+    crate::VulkanBackendParams::default().with_loader(crate::VulkanLoader::Mock)
+}
+
+/// Resets the driver and creates an instance on it with the default parameters.
+#[cfg(test)]
+pub(crate) fn instance() -> crate::Instance<'static> {
+    MockDriver::reset();
+
+    crate::Instance::new(params(), mayon_core::logger::DefaultLogger).unwrap()
+}
+
+/// Resets the driver and creates a device on its only adapter, the fixture most tests start
+/// from.
+#[cfg(test)]
+pub(crate) fn device() -> crate::Device<'static> {
+    let instance = instance();
+    let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+    instance.create_device(&adapter).unwrap()
+}
+
+/// The mock's `vkGetInstanceProcAddr`, resolving every entry point the backend knows of.
+pub(super) unsafe extern "system" fn get_instance_proc_addr(
+    _instance: *mut c_void,
+    name: *const c_char,
+) -> Option<unsafe extern "system" fn()> {
+    let name = unsafe { parse_name(name) }?;
+
+    if let Some(extension) = instance_extension_of(name)
+        && !STATE.with_borrow(|state| is_enabled(&state.instance_extensions, extension))
+    {
+        return None;
+    }
+
+    let function = match name {
+        GetInstanceProcAddr => get_instance_proc_addr as *const (),
+        CreateInstance => create_instance as *const (),
+        DestroyInstance => destroy_instance as *const (),
+        #[cfg(feature = "windowing")]
+        CreateWin32Surface => create_win32_surface as *const (),
+        #[cfg(feature = "windowing")]
+        CreateWaylandSurface => create_wayland_surface as *const (),
+        #[cfg(feature = "windowing")]
+        CreateXcbSurface => create_xcb_surface as *const (),
+        #[cfg(feature = "windowing")]
+        CreateXlibSurface => create_xlib_surface as *const (),
+        DestroySurface => destroy_surface as *const (),
+        EnumerateInstanceLayerProperties => enumerate_instance_layer_properties as *const (),
+        EnumerateInstanceExtensionProperties => {
+            enumerate_instance_extension_properties as *const ()
+        }
+        EnumerateInstanceVersion => enumerate_instance_version as *const (),
+        CreateDebugUtilsMessenger => create_debug_utils_messenger as *const (),
+        DestroyDebugUtilsMessenger => destroy_debug_utils_messenger as *const (),
+        EnumeratePhysicalDevices => enumerate_physical_devices as *const (),
+        GetPhysicalDeviceProperties => get_physical_device_properties as *const (),
+        GetPhysicalDeviceQueueFamilyProperties => {
+            get_physical_device_queue_family_properties as *const ()
+        }
+        EnumerateDeviceExtensionProperties => enumerate_device_extension_properties as *const (),
+        CreateDevice => create_device as *const (),
+        GetPhysicalDeviceSurfaceSupport => get_physical_device_surface_support as *const (),
+        GetPhysicalDeviceSurfaceCapabilities => {
+            get_physical_device_surface_capabilities as *const ()
+        }
+        GetPhysicalDeviceSurfaceFormats => get_physical_device_surface_formats as *const (),
+        GetPhysicalDeviceSurfacePresentModes => {
+            get_physical_device_surface_present_modes as *const ()
+        }
+        GetPhysicalDeviceImageFormatProperties => {
+            get_physical_device_image_format_properties as *const ()
+        }
+        GetPhysicalDeviceMemoryProperties => get_physical_device_memory_properties as *const (),
+        GetPhysicalDeviceFormatProperties => get_physical_device_format_properties as *const (),
+        GetPhysicalDeviceFeatures2
+            if STATE.with_borrow(|state| state.api_version < VulkanVersion::new(1, 1, 0)) =>
+        {
+            return None;
+        }
+        GetPhysicalDeviceFeatures2 => get_physical_device_features2 as *const (),
+        // Like the real loader, device-level functions are also reachable from the instance.
+        name => device_function(name)?,
+    };
+
+    Some(unsafe { transmute::<*const (), unsafe extern "system" fn()>(function) })
+}
+
+/// The mock's `vkGetDeviceProcAddr`, resolving only device-level entry points.
+pub(crate) unsafe extern "system" fn get_device_proc_addr(
+    _device: Device,
+    name: *const c_char,
+) -> Option<unsafe extern "system" fn()> {
+    let function = device_function(unsafe { parse_name(name) }?)?;
+
+    Some(unsafe { transmute::<*const (), unsafe extern "system" fn()>(function) })
+}
+
+unsafe fn parse_name(name: *const c_char) -> Option<VulkanFunctionName> {
+    unsafe { CStr::from_ptr(name) }.to_str().ok()?.parse().ok()
+}
+
+/// The instance extension providing `name`, for entry points that only resolve once their
+/// extension is enabled.
+fn instance_extension_of(name: VulkanFunctionName) -> Option<ExtensionName> {
+    match name {
+        #[cfg(feature = "windowing")]
+        CreateWin32Surface => Some(ExtensionName::WIN32_SURFACE),
+        #[cfg(feature = "windowing")]
+        CreateWaylandSurface => Some(ExtensionName::WAYLAND_SURFACE),
+        #[cfg(feature = "windowing")]
+        CreateXcbSurface => Some(ExtensionName::XCB_SURFACE),
+        #[cfg(feature = "windowing")]
+        CreateXlibSurface => Some(ExtensionName::XLIB_SURFACE),
+        DestroySurface
+        | GetPhysicalDeviceSurfaceSupport
+        | GetPhysicalDeviceSurfaceCapabilities
+        | GetPhysicalDeviceSurfaceFormats
+        | GetPhysicalDeviceSurfacePresentModes => Some(ExtensionName::SURFACE),
+        CreateDebugUtilsMessenger | DestroyDebugUtilsMessenger => Some(ExtensionName::DEBUG_UTILS),
+        _ => None,
+    }
+}
+
+fn is_enabled(extensions: &[CString], extension: ExtensionName) -> bool {
+    extensions
+        .iter()
+        .any(|name| name.as_c_str() == extension.as_c_str())
+}
+
+/// Copies the extension names of a create info.
+unsafe fn extension_names(names: *const ExtensionName, count: u32) -> Vec<CString> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    unsafe { slice::from_raw_parts(names, count as usize) }
+        .iter()
+        .map(|name| name.as_c_str().to_owned())
+        .collect()
+}
+
+fn device_function(name: VulkanFunctionName) -> Option<*const ()> {
+    let function = match name {
+        GetDeviceProcAddr => get_device_proc_addr as *const (),
+        DestroyDevice => destroy_device as *const (),
+        GetDeviceQueue => get_device_queue as *const (),
+        DeviceWaitIdle => device_wait_idle as *const (),
+        CreateSwapchain | DestroySwapchain | GetSwapchainImages | AcquireNextImage
+        | QueuePresent
+            if !STATE.with_borrow(|state| {
+                is_enabled(&state.device_extensions, ExtensionName::SWAPCHAIN)
+            }) =>
+        {
+            return None;
+        }
+        CreateSwapchain => create_swapchain as *const (),
+        DestroySwapchain => destroy_swapchain as *const (),
+        GetSwapchainImages => get_swapchain_images as *const (),
+        AcquireNextImage => acquire_next_image as *const (),
+        QueuePresent => queue_present as *const (),
+        CreateFence => create_fence as *const (),
+        DestroyFence => destroy_fence as *const (),
+        WaitForFences => wait_for_fences as *const (),
+        ResetFences => reset_fences as *const (),
+        CreateSemaphore => create_semaphore as *const (),
+        DestroySemaphore => destroy_semaphore as *const (),
+        GetFenceStatus => get_fence_status as *const (),
+        CreateCommandPool => create_command_pool as *const (),
+        DestroyCommandPool => destroy_command_pool as *const (),
+        ResetCommandPool => reset_command_pool as *const (),
+        AllocateCommandBuffers => allocate_command_buffers as *const (),
+        BeginCommandBuffer => begin_command_buffer as *const (),
+        EndCommandBuffer => end_command_buffer as *const (),
+        ResetCommandBuffer => reset_command_buffer as *const (),
+        QueueSubmit => queue_submit as *const (),
+        CmdPipelineBarrier => cmd_pipeline_barrier as *const (),
+        SignalSemaphore => signal_semaphore as *const (),
+        WaitSemaphores => wait_semaphores as *const (),
+        GetSemaphoreCounterValue => get_semaphore_counter_value as *const (),
+        CreateImage => create_image as *const (),
+        DestroyImage => destroy_image as *const (),
+        GetImageMemoryRequirements => get_image_memory_requirements as *const (),
+        AllocateMemory => allocate_memory as *const (),
+        FreeMemory => free_memory as *const (),
+        BindImageMemory => bind_image_memory as *const (),
+        MapMemory => map_memory as *const (),
+        CreateBuffer => create_buffer as *const (),
+        DestroyBuffer => destroy_buffer as *const (),
+        GetBufferMemoryRequirements => get_buffer_memory_requirements as *const (),
+        BindBufferMemory => bind_buffer_memory as *const (),
+        CmdCopyBuffer => cmd_copy_buffer as *const (),
+        CreateImageView => create_image_view as *const (),
+        DestroyImageView => destroy_image_view as *const (),
+        CreateSampler => create_sampler as *const (),
+        DestroySampler => destroy_sampler as *const (),
+        CmdCopyBufferToImage => cmd_copy_buffer_to_image as *const (),
+        CmdBlitImage => cmd_blit_image as *const (),
+        CreateShaderModule => create_shader_module as *const (),
+        DestroyShaderModule => destroy_shader_module as *const (),
+        CreateDescriptorSetLayout => create_descriptor_set_layout as *const (),
+        DestroyDescriptorSetLayout => destroy_descriptor_set_layout as *const (),
+        CreateDescriptorPool => create_descriptor_pool as *const (),
+        DestroyDescriptorPool => destroy_descriptor_pool as *const (),
+        ResetDescriptorPool => reset_descriptor_pool as *const (),
+        AllocateDescriptorSets => allocate_descriptor_sets as *const (),
+        UpdateDescriptorSets => update_descriptor_sets as *const (),
+        CreatePipelineLayout => create_pipeline_layout as *const (),
+        DestroyPipelineLayout => destroy_pipeline_layout as *const (),
+        CreatePipelineCache => create_pipeline_cache as *const (),
+        DestroyPipelineCache => destroy_pipeline_cache as *const (),
+        GetPipelineCacheData => get_pipeline_cache_data as *const (),
+        CreateGraphicsPipelines => create_graphics_pipelines as *const (),
+        DestroyPipeline => destroy_pipeline as *const (),
+        CreateRenderPass => create_render_pass as *const (),
+        DestroyRenderPass => destroy_render_pass as *const (),
+        CreateFramebuffer => create_framebuffer as *const (),
+        DestroyFramebuffer => destroy_framebuffer as *const (),
+        CmdBeginRenderPass => cmd_begin_render_pass as *const (),
+        CmdEndRenderPass => cmd_end_render_pass as *const (),
+        CmdBeginRendering | CmdEndRendering
+            if STATE.with_borrow(|state| state.api_version < VulkanVersion::new(1, 3, 0)) =>
+        {
+            return None;
+        }
+        CmdBeginRenderingKhr | CmdEndRenderingKhr
+            if !STATE.with_borrow(|state| {
+                is_enabled(&state.device_extensions, ExtensionName::DYNAMIC_RENDERING)
+            }) =>
+        {
+            return None;
+        }
+        CreateComputePipelines => create_compute_pipelines as *const (),
+        CmdBindPipeline => cmd_bind_pipeline as *const (),
+        CmdBindDescriptorSets => cmd_bind_descriptor_sets as *const (),
+        CmdPushConstants => cmd_push_constants as *const (),
+        CmdDispatch => cmd_dispatch as *const (),
+        CmdDispatchIndirect => cmd_dispatch_indirect as *const (),
+        CmdBeginRendering => cmd_begin_rendering as *const (),
+        CmdEndRendering => cmd_end_rendering as *const (),
+        CmdBeginRenderingKhr => cmd_begin_rendering_khr as *const (),
+        CmdEndRenderingKhr => cmd_end_rendering_khr as *const (),
+        _ => return None,
+    };
+
+    Some(function)
+}
+
+unsafe extern "system" fn create_instance(
+    create_info: *const InstanceCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    instance: *mut Instance,
+) -> VkResult {
+    let create_info = unsafe { &*create_info };
+    let application_info = create_info.application_info;
+
+    if !application_info.is_null() {
+        let api_version = VulkanVersion::from_raw(unsafe { (*application_info).api_version });
+
+        STATE.with_borrow_mut(|state| state.api_version = api_version);
+    }
+
+    let extensions = unsafe {
+        extension_names(
+            create_info.enabled_extension_names,
+            create_info.enabled_extension_count,
+        )
+    };
+
+    STATE.with_borrow_mut(|state| state.instance_extensions = extensions);
+
+    unsafe {
+        create(CreateInstance, MockHandleKind::Instance, instance, |raw| {
+            Instance::from_raw_unchecked(raw as usize)
+        })
+    }
+}
+
+unsafe extern "system" fn destroy_instance(instance: Instance, _allocator: AllocationCallbacksRef) {
+    destroy(
+        DestroyInstance,
+        MockHandleKind::Instance,
+        instance.as_raw() as u64,
+    );
+}
+
+#[cfg(feature = "windowing")]
+unsafe extern "system" fn create_win32_surface(
+    _instance: Instance,
+    _create_info: *const Win32SurfaceCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    surface: *mut Surface,
+) -> VkResult {
+    unsafe {
+        create(
+            CreateWin32Surface,
+            MockHandleKind::Surface,
+            surface,
+            |raw| Surface::from_raw_unchecked(raw),
+        )
+    }
+}
+
+#[cfg(feature = "windowing")]
+unsafe extern "system" fn create_wayland_surface(
+    _instance: Instance,
+    _create_info: *const WaylandSurfaceCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    surface: *mut Surface,
+) -> VkResult {
+    unsafe {
+        create(
+            CreateWaylandSurface,
+            MockHandleKind::Surface,
+            surface,
+            |raw| Surface::from_raw_unchecked(raw),
+        )
+    }
+}
+
+#[cfg(feature = "windowing")]
+unsafe extern "system" fn create_xcb_surface(
+    _instance: Instance,
+    _create_info: *const XcbSurfaceCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    surface: *mut Surface,
+) -> VkResult {
+    unsafe {
+        create(CreateXcbSurface, MockHandleKind::Surface, surface, |raw| {
+            Surface::from_raw_unchecked(raw)
+        })
+    }
+}
+
+#[cfg(feature = "windowing")]
+unsafe extern "system" fn create_xlib_surface(
+    _instance: Instance,
+    _create_info: *const XlibSurfaceCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    surface: *mut Surface,
+) -> VkResult {
+    unsafe {
+        create(CreateXlibSurface, MockHandleKind::Surface, surface, |raw| {
+            Surface::from_raw_unchecked(raw)
+        })
+    }
+}
+
+unsafe extern "system" fn destroy_surface(
+    _instance: Instance,
+    surface: Surface,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(DestroySurface, MockHandleKind::Surface, surface.as_raw());
+}
+
+unsafe extern "system" fn create_debug_utils_messenger(
+    _instance: Instance,
+    _create_info: *const DebugUtilsMessengerCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    messenger: *mut DebugUtilsMessenger,
+) -> VkResult {
+    unsafe {
+        create(
+            CreateDebugUtilsMessenger,
+            MockHandleKind::DebugUtilsMessenger,
+            messenger,
+            |raw| DebugUtilsMessenger::from_raw_unchecked(raw),
+        )
+    }
+}
+
+unsafe extern "system" fn destroy_debug_utils_messenger(
+    _instance: Instance,
+    messenger: DebugUtilsMessenger,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyDebugUtilsMessenger,
+        MockHandleKind::DebugUtilsMessenger,
+        messenger.as_raw(),
+    );
+}
+
+unsafe extern "system" fn enumerate_instance_layer_properties(
+    property_count: *mut u32,
+    properties: *mut LayerProperties,
+) -> VkResult {
+    let validation = LayerProperties {
+        layer_name: fixed_name(LayerName::VALIDATION.as_c_str()),
+        spec_version: VulkanVersion::new(1, 3, 0).raw(),
+        implementation_version: 1,
+        description: fixed_name(c"Mock validation layer"),
+    };
+
+    match call(EnumerateInstanceLayerProperties) {
+        VkResult::Success => unsafe {
+            enumerate(
+                EnumerateInstanceLayerProperties,
+                &[validation],
+                property_count,
+                properties,
+            )
+        },
+        result => result,
+    }
+}
+
+unsafe extern "system" fn enumerate_instance_version(api_version: *mut u32) -> VkResult {
+    let result = call(EnumerateInstanceVersion);
+
+    if result == VkResult::Success {
+        unsafe { api_version.write(VulkanVersion::new(1, 3, 0).raw()) };
+    }
+
+    result
+}
+
+unsafe extern "system" fn enumerate_instance_extension_properties(
+    layer_name: *const c_char,
+    property_count: *mut u32,
+    properties: *mut ExtensionProperties,
+) -> VkResult {
+    let result = call(EnumerateInstanceExtensionProperties);
+
+    if result != VkResult::Success {
+        return result;
+    }
+
+    let names: &[ExtensionName] = if layer_name.is_null() {
+        &[
+            ExtensionName::SURFACE,
+            ExtensionName::WIN32_SURFACE,
+            ExtensionName::XCB_SURFACE,
+            ExtensionName::XLIB_SURFACE,
+            ExtensionName::WAYLAND_SURFACE,
+        ]
+    } else if unsafe { CStr::from_ptr(layer_name) } == LayerName::VALIDATION.as_c_str() {
+        &[ExtensionName::DEBUG_UTILS]
+    } else {
+        return VkResult::LayerNotPresent;
+    };
+
+    let extensions = names
+        .iter()
+        .map(|name| ExtensionProperties {
+            extension_name: fixed_name(name.as_c_str()),
+            spec_version: 1,
+        })
+        .collect::<Vec<_>>();
+
+    unsafe {
+        enumerate(
+            EnumerateInstanceExtensionProperties,
+            &extensions,
+            property_count,
+            properties,
+        )
+    }
+}
+
+/// Copies `name` into a zero-padded fixed-size Vulkan string.
+fn fixed_name<const N: usize>(name: &CStr) -> [c_char; N] {
+    let mut buffer = [0; N];
+
+    for (dst, &src) in buffer.iter_mut().zip(name.to_bytes()) {
+        *dst = src as c_char;
+    }
+
+    buffer
+}
+
+unsafe extern "system" fn enumerate_physical_devices(
+    _instance: Instance,
+    physical_device_count: *mut u32,
+    physical_devices: *mut PhysicalDevice,
+) -> VkResult {
+    match call(EnumeratePhysicalDevices) {
+        VkResult::Success => unsafe {
+            enumerate(
+                EnumeratePhysicalDevices,
+                &[PHYSICAL_DEVICE],
+                physical_device_count,
+                physical_devices,
+            )
+        },
+        result => result,
+    }
+}
+
+unsafe extern "system" fn get_physical_device_properties(
+    _physical_device: PhysicalDevice,
+    properties: *mut PhysicalDeviceProperties,
+) {
+    call(GetPhysicalDeviceProperties);
+
+    let mut mock = PhysicalDeviceProperties::zeroized();
+
+    mock.api_version = VulkanVersion::new(1, 3, 0).raw();
+    mock.vendor_id = VENDOR_ID;
+    mock.device_id = DEVICE_ID;
+    mock.device_type = PhysicalDeviceType::DISCRETE_GPU;
+    mock.pipeline_cache_uuid = PIPELINE_CACHE_UUID;
+    mock.limits.buffer_image_granularity = BUFFER_IMAGE_GRANULARITY;
+
+    for (dst, &src) in mock.device_name.iter_mut().zip(DEVICE_NAME) {
+        *dst = src as c_char;
+    }
+
+    unsafe { properties.write(mock) };
+}
+
+unsafe extern "system" fn get_physical_device_queue_family_properties(
+    _physical_device: PhysicalDevice,
+    queue_family_property_count: *mut u32,
+    queue_family_properties: *mut QueueFamilyProperties,
+) {
+    call(GetPhysicalDeviceQueueFamilyProperties);
+
+    let queue_flags = if STATE.with_borrow(|state| state.transfer_queue_only) {
+        QueueFlags::TRANSFER
+    } else {
+        QueueFlags::GRAPHICS
+            .union(QueueFlags::COMPUTE)
+            .union(QueueFlags::TRANSFER)
+    };
+
+    let family = QueueFamilyProperties {
+        queue_flags,
+        queue_count: 1,
+        timestamp_valid_bits: 64,
+        min_image_transfer_granularity: Extent3D {
+            width: 1,
+            height: 1,
+            depth: 1,
+        },
+    };
+
+    unsafe {
+        enumerate(
+            GetPhysicalDeviceQueueFamilyProperties,
+            &[family],
+            queue_family_property_count,
+            queue_family_properties,
+        )
+    };
+}
+
+unsafe extern "system" fn enumerate_device_extension_properties(
+    _physical_device: PhysicalDevice,
+    _layer_name: *const c_char,
+    property_count: *mut u32,
+    properties: *mut ExtensionProperties,
+) -> VkResult {
+    let extensions = [ExtensionName::SWAPCHAIN, ExtensionName::DYNAMIC_RENDERING].map(|name| {
+        ExtensionProperties {
+            extension_name: fixed_name(name.as_c_str()),
+            spec_version: 1,
+        }
+    });
+
+    match call(EnumerateDeviceExtensionProperties) {
+        VkResult::Success => unsafe {
+            enumerate(
+                EnumerateDeviceExtensionProperties,
+                &extensions,
+                property_count,
+                properties,
+            )
+        },
+        result => result,
+    }
+}
+
+unsafe extern "system" fn create_device(
+    _physical_device: PhysicalDevice,
+    create_info: *const DeviceCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    device: *mut Device,
+) -> VkResult {
+    let create_info = unsafe { &*create_info };
+    let extensions = unsafe {
+        extension_names(
+            create_info.enabled_extension_names,
+            create_info.enabled_extension_count,
+        )
+    };
+
+    STATE.with_borrow_mut(|state| state.device_extensions = extensions);
+
+    unsafe {
+        create(CreateDevice, MockHandleKind::Device, device, |raw| {
+            Device::from_raw_unchecked(raw as usize)
+        })
+    }
+}
+
+unsafe extern "system" fn destroy_device(device: Device, _allocator: AllocationCallbacksRef) {
+    destroy(
+        DestroyDevice,
+        MockHandleKind::Device,
+        device.as_raw() as u64,
+    );
+}
+
+unsafe extern "system" fn get_device_queue(
+    _device: Device,
+    _queue_family_index: u32,
+    _queue_index: u32,
+    queue: *mut Queue,
+) {
+    call(GetDeviceQueue);
+
+    unsafe { queue.write(QUEUE) };
+}
+
+unsafe extern "system" fn device_wait_idle(_device: Device) -> VkResult {
+    call(DeviceWaitIdle)
+}
+
+unsafe extern "system" fn get_physical_device_surface_support(
+    _physical_device: PhysicalDevice,
+    _queue_family_index: u32,
+    _surface: Surface,
+    supported: *mut u32,
+) -> VkResult {
+    let result = call(GetPhysicalDeviceSurfaceSupport);
+
+    unsafe { supported.write(1) };
+
+    result
+}
+
+unsafe extern "system" fn get_physical_device_surface_capabilities(
+    _physical_device: PhysicalDevice,
+    _surface: Surface,
+    surface_capabilities: *mut SurfaceCapabilities,
+) -> VkResult {
+    let result = call(GetPhysicalDeviceSurfaceCapabilities);
+
+    let capabilities = SurfaceCapabilities {
+        min_image_count: 2,
+        max_image_count: 0,
+        current_extent: Extent2D {
+            width: SurfaceCapabilities::UNDEFINED_EXTENT,
+            height: SurfaceCapabilities::UNDEFINED_EXTENT,
+        },
+        min_image_extent: Extent2D {
+            width: 1,
+            height: 1,
+        },
+        max_image_extent: Extent2D {
+            width: 16384,
+            height: 16384,
+        },
+        max_image_array_layers: 1,
+        supported_transforms: SurfaceTransformFlags(0x1),
+        current_transform: SurfaceTransformFlags(0x1),
+        supported_composite_alpha: CompositeAlphaFlags::OPAQUE,
+        supported_usage_flags: ImageUsageFlags(
+            ImageUsageFlags::COLOR_ATTACHMENT.0 | ImageUsageFlags::TRANSFER_DST.0,
+        ),
+    };
+
+    unsafe { surface_capabilities.write(capabilities) };
+
+    result
+}
+
+unsafe extern "system" fn get_physical_device_surface_formats(
+    _physical_device: PhysicalDevice,
+    _surface: Surface,
+    surface_format_count: *mut u32,
+    surface_formats: *mut SurfaceFormat,
+) -> VkResult {
+    let format = SurfaceFormat {
+        format: Format::B8G8R8A8_SRGB,
+        color_space: ColorSpace::SRGB_NONLINEAR,
+    };
+
+    match call(GetPhysicalDeviceSurfaceFormats) {
+        VkResult::Success => unsafe {
+            enumerate(
+                GetPhysicalDeviceSurfaceFormats,
+                &[format],
+                surface_format_count,
+                surface_formats,
+            )
+        },
+        result => result,
+    }
+}
+
+unsafe extern "system" fn get_physical_device_surface_present_modes(
+    _physical_device: PhysicalDevice,
+    _surface: Surface,
+    present_mode_count: *mut u32,
+    present_modes: *mut PresentMode,
+) -> VkResult {
+    match call(GetPhysicalDeviceSurfacePresentModes) {
+        VkResult::Success => unsafe {
+            enumerate(
+                GetPhysicalDeviceSurfacePresentModes,
+                &[PresentMode::FIFO],
+                present_mode_count,
+                present_modes,
+            )
+        },
+        result => result,
+    }
+}
+
+unsafe extern "system" fn create_swapchain(
+    _device: Device,
+    _create_info: *const SwapchainCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    swapchain: *mut Swapchain,
+) -> VkResult {
+    unsafe {
+        create(
+            CreateSwapchain,
+            MockHandleKind::Swapchain,
+            swapchain,
+            |raw| Swapchain::from_raw_unchecked(raw),
+        )
+    }
+}
+
+unsafe extern "system" fn destroy_swapchain(
+    _device: Device,
+    swapchain: Swapchain,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroySwapchain,
+        MockHandleKind::Swapchain,
+        swapchain.as_raw(),
+    );
+}
+
+unsafe extern "system" fn get_swapchain_images(
+    _device: Device,
+    swapchain: Swapchain,
+    swapchain_image_count: *mut u32,
+    swapchain_images: *mut Image,
+) -> VkResult {
+    // Swapchain images are owned by the swapchain, so they are derived from its handle
+    // instead of being tracked.
+    let images = (1..=SWAPCHAIN_IMAGE_COUNT as u64)
+        .map(|index| unsafe { Image::from_raw_unchecked(swapchain.as_raw() << 8 | index) })
+        .collect::<Vec<_>>();
+
+    match call(GetSwapchainImages) {
+        VkResult::Success => unsafe {
+            enumerate(
+                GetSwapchainImages,
+                &images,
+                swapchain_image_count,
+                swapchain_images,
+            )
+        },
+        result => result,
+    }
+}
+
+unsafe extern "system" fn acquire_next_image(
+    _device: Device,
+    _swapchain: Swapchain,
+    _timeout: u64,
+    _semaphore: Option<Semaphore>,
+    _fence: Option<Fence>,
+    image_index: *mut u32,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(AcquireNextImage);
+
+        if result == VkResult::Success {
+            unsafe { image_index.write(state.next_image_index) };
+
+            state.next_image_index = (state.next_image_index + 1) % SWAPCHAIN_IMAGE_COUNT;
+        }
+
+        result
+    })
+}
+
+unsafe extern "system" fn queue_present(
+    _queue: Queue,
+    _present_info: *const PresentInfo,
+) -> VkResult {
+    call(QueuePresent)
+}
+
+unsafe extern "system" fn create_fence(
+    _device: Device,
+    _create_info: *const FenceCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    fence: *mut Fence,
+) -> VkResult {
+    unsafe {
+        create(CreateFence, MockHandleKind::Fence, fence, |raw| {
+            Fence::from_raw_unchecked(raw)
+        })
+    }
+}
+
+unsafe extern "system" fn destroy_fence(
+    _device: Device,
+    fence: Fence,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(DestroyFence, MockHandleKind::Fence, fence.as_raw());
+}
+
+unsafe extern "system" fn wait_for_fences(
+    _device: Device,
+    _fence_count: u32,
+    _fences: *const Fence,
+    _wait_all: u32,
+    _timeout: u64,
+) -> VkResult {
+    call(WaitForFences)
+}
+
+unsafe extern "system" fn reset_fences(
+    _device: Device,
+    _fence_count: u32,
+    _fences: *const Fence,
+) -> VkResult {
+    call(ResetFences)
+}
+
+unsafe extern "system" fn create_semaphore(
+    _device: Device,
+    create_info: *const SemaphoreCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    semaphore: *mut Semaphore,
+) -> VkResult {
+    let result = unsafe {
+        create(
+            CreateSemaphore,
+            MockHandleKind::Semaphore,
+            semaphore,
+            |raw| Semaphore::from_raw_unchecked(raw),
+        )
+    };
+
+    let next = unsafe { (*create_info).next }.cast::<SemaphoreTypeCreateInfo>();
+
+    if result == VkResult::Success
+        && !next.is_null()
+        && unsafe { (*next).struct_type } == StructureType::SemaphoreTypeCreateInfo
+        && unsafe { (*next).semaphore_type } == SemaphoreType::TIMELINE
+    {
+        let raw = unsafe { semaphore.read() }.as_raw();
+        let initial_value = unsafe { (*next).initial_value };
+
+        STATE.with_borrow_mut(|state| state.timelines.push((raw, initial_value)));
+    }
+
+    result
+}
+
+unsafe extern "system" fn destroy_semaphore(
+    _device: Device,
+    semaphore: Semaphore,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroySemaphore,
+        MockHandleKind::Semaphore,
+        semaphore.as_raw(),
+    );
+
+    STATE.with_borrow_mut(|state| {
+        state
+            .timelines
+            .retain(|&(raw, _)| raw != semaphore.as_raw())
+    });
+}
+
+unsafe extern "system" fn signal_semaphore(
+    _device: Device,
+    signal_info: *const SemaphoreSignalInfo,
+) -> VkResult {
+    let signal_info = unsafe { &*signal_info };
+
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(SignalSemaphore);
+
+        if result == VkResult::Success
+            && let Some(value) = state.timeline(signal_info.semaphore)
+        {
+            *value = signal_info.value;
+        }
+
+        result
+    })
+}
+
+/// Nothing signals a timeline later on, so a wait either succeeds right away or times out.
+unsafe extern "system" fn wait_semaphores(
+    _device: Device,
+    wait_info: *const SemaphoreWaitInfo,
+    _timeout: u64,
+) -> VkResult {
+    let wait_info = unsafe { &*wait_info };
+    let count = wait_info.semaphore_count as usize;
+    let semaphores = unsafe { slice::from_raw_parts(wait_info.semaphores, count) };
+    let values = unsafe { slice::from_raw_parts(wait_info.values, count) };
+
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(WaitSemaphores);
+
+        if result != VkResult::Success {
+            return result;
+        }
+
+        let reached = semaphores
+            .iter()
+            .zip(values)
+            .all(|(&semaphore, &value)| state.timeline(semaphore).is_some_and(|v| *v >= value));
+
+        if reached {
+            VkResult::Success
+        } else {
+            VkResult::Timeout
+        }
+    })
+}
+
+unsafe extern "system" fn get_semaphore_counter_value(
+    _device: Device,
+    semaphore: Semaphore,
+    value: *mut u64,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(GetSemaphoreCounterValue);
+
+        if result == VkResult::Success {
+            let current = state.timeline(semaphore).map_or(0, |value| *value);
+
+            unsafe { value.write(current) };
+        }
+
+        result
+    })
+}
+
+/// Work completes as soon as it is submitted, so fences are always signaled.
+unsafe extern "system" fn get_fence_status(_device: Device, _fence: Fence) -> VkResult {
+    call(GetFenceStatus)
+}
+
+unsafe extern "system" fn create_command_pool(
+    _device: Device,
+    _create_info: *const CommandPoolCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    command_pool: *mut CommandPool,
+) -> VkResult {
+    unsafe {
+        create(
+            CreateCommandPool,
+            MockHandleKind::CommandPool,
+            command_pool,
+            |raw| CommandPool::from_raw_unchecked(raw),
+        )
+    }
+}
+
+unsafe extern "system" fn destroy_command_pool(
+    _device: Device,
+    command_pool: CommandPool,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyCommandPool,
+        MockHandleKind::CommandPool,
+        command_pool.as_raw(),
+    );
+}
+
+unsafe extern "system" fn reset_command_pool(
+    _device: Device,
+    _command_pool: CommandPool,
+    _flags: CommandPoolResetFlags,
+) -> VkResult {
+    call(ResetCommandPool)
+}
+
+unsafe extern "system" fn allocate_command_buffers(
+    _device: Device,
+    allocate_info: *const CommandBufferAllocateInfo,
+    command_buffers: *mut CommandBuffer,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(AllocateCommandBuffers);
+
+        if result == VkResult::Success {
+            // Command buffers are freed together with their pool, so they are not tracked.
+            for index in 0..unsafe { (*allocate_info).command_buffer_count } as usize {
+                let raw = state.next_raw();
+
+                unsafe {
+                    command_buffers
+                        .add(index)
+                        .write(CommandBuffer::from_raw_unchecked(raw as usize))
+                };
+            }
+        }
+
+        result
+    })
+}
+
+unsafe extern "system" fn begin_command_buffer(
+    command_buffer: CommandBuffer,
+    _begin_info: *const CommandBufferBeginInfo,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        state
+            .copies
+            .retain(|(raw, _)| *raw != command_buffer.as_raw());
+        state
+            .bound_sets
+            .retain(|((raw, _), _)| *raw != command_buffer.as_raw());
+        state.call(BeginCommandBuffer)
+    })
+}
+
+unsafe extern "system" fn end_command_buffer(_command_buffer: CommandBuffer) -> VkResult {
+    call(EndCommandBuffer)
+}
+
+unsafe extern "system" fn reset_command_buffer(
+    command_buffer: CommandBuffer,
+    _flags: CommandBufferResetFlags,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        state
+            .copies
+            .retain(|(raw, _)| *raw != command_buffer.as_raw());
+        state
+            .bound_sets
+            .retain(|((raw, _), _)| *raw != command_buffer.as_raw());
+        state.call(ResetCommandBuffer)
+    })
+}
+
+unsafe extern "system" fn queue_submit(
+    _queue: Queue,
+    submit_count: u32,
+    submits: *const SubmitInfo,
+    _fence: Option<Fence>,
+) -> VkResult {
+    let result = call(QueueSubmit);
+
+    if result != VkResult::Success || submit_count == 0 {
+        return result;
+    }
+
+    for submit in unsafe { slice::from_raw_parts(submits, submit_count as usize) } {
+        let command_buffers = unsafe {
+            slice::from_raw_parts(submit.command_buffers, submit.command_buffer_count as usize)
+        };
+
+        STATE.with_borrow_mut(|state| {
+            for &command_buffer in command_buffers {
+                state.execute(command_buffer);
+            }
+        });
+
+        let next = submit.next.cast::<TimelineSemaphoreSubmitInfo>();
+
+        if next.is_null()
+            || unsafe { (*next).struct_type } != StructureType::TimelineSemaphoreSubmitInfo
+        {
+            continue;
+        }
+
+        let count = submit.signal_semaphore_count as usize;
+        let semaphores = unsafe { slice::from_raw_parts(submit.signal_semaphores, count) };
+        let values = unsafe { slice::from_raw_parts((*next).signal_semaphore_values, count) };
+
+        STATE.with_borrow_mut(|state| {
+            for (&semaphore, &signaled) in semaphores.iter().zip(values) {
+                if let Some(value) = state.timeline(semaphore) {
+                    *value = signaled;
+                }
+            }
+        });
+    }
+
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "system" fn cmd_pipeline_barrier(
+    _command_buffer: CommandBuffer,
+    _src_stage_mask: PipelineStageFlags,
+    _dst_stage_mask: PipelineStageFlags,
+    _dependency_flags: DependencyFlags,
+    _memory_barrier_count: u32,
+    _memory_barriers: *const MemoryBarrier,
+    _buffer_memory_barrier_count: u32,
+    _buffer_memory_barriers: *const c_void,
+    image_memory_barrier_count: u32,
+    image_memory_barriers: *const ImageMemoryBarrier,
+) {
+    let barriers = unsafe {
+        slice::from_raw_parts(image_memory_barriers, image_memory_barrier_count as usize)
+    };
+
+    STATE.with_borrow_mut(|state| {
+        state.calls.push(CmdPipelineBarrier);
+
+        for barrier in barriers {
+            let range = barrier.subresource_range;
+
+            for mip_level in range.base_mip_level..range.base_mip_level + range.level_count {
+                state.use_layout(
+                    CmdPipelineBarrier,
+                    (barrier.image.as_raw(), mip_level),
+                    barrier.old_layout,
+                    barrier.new_layout,
+                );
+            }
+        }
+    });
+}
+
+unsafe extern "system" fn get_physical_device_image_format_properties(
+    _physical_device: PhysicalDevice,
+    _format: Format,
+    image_type: ImageType,
+    _tiling: ImageTiling,
+    _usage: ImageUsageFlags,
+    _flags: ImageCreateFlags,
+    image_format_properties: *mut ImageFormatProperties,
+) -> VkResult {
+    let result = call(GetPhysicalDeviceImageFormatProperties);
+
+    if result == VkResult::Success {
+        let is_3d = image_type == ImageType::TYPE_3D;
+
+        let properties = ImageFormatProperties {
+            max_extent: Extent3D {
+                width: 16384,
+                height: 16384,
+                depth: if is_3d { 2048 } else { 1 },
+            },
+            max_mip_levels: 15,
+            max_array_layers: if is_3d { 1 } else { 2048 },
+            max_resource_size: u64::MAX,
+            ..Default::default()
+        };
+
+        unsafe { image_format_properties.write(properties) };
+    }
+
+    result
+}
+
+unsafe extern "system" fn get_physical_device_memory_properties(
+    _physical_device: PhysicalDevice,
+    memory_properties: *mut PhysicalDeviceMemoryProperties,
+) {
+    call(GetPhysicalDeviceMemoryProperties);
+
+    let mut properties = PhysicalDeviceMemoryProperties {
+        memory_type_count: 2,
+        memory_heap_count: 2,
+        ..Default::default()
+    };
+
+    properties.memory_types[0].property_flags = MemoryPropertyFlags::DEVICE_LOCAL;
+    properties.memory_types[1] = MemoryType {
+        property_flags: MemoryPropertyFlags(
+            MemoryPropertyFlags::HOST_VISIBLE.0 | MemoryPropertyFlags::HOST_COHERENT.0,
+        ),
+        heap_index: 1,
+    };
+    properties.memory_heaps[0] = MemoryHeap {
+        size: 1 << 30,
+        flags: MemoryHeapFlags::DEVICE_LOCAL,
+    };
+    properties.memory_heaps[1].size = 256 << 20;
+
+    unsafe { memory_properties.write(properties) };
+}
+
+/// The start of every structure of a `next` chain.
+#[repr(C)]
+struct BaseOutStructure {
+    struct_type: StructureType,
+    next: *mut c_void,
+}
+
+unsafe extern "system" fn get_physical_device_features2(
+    _physical_device: PhysicalDevice,
+    features: *mut PhysicalDeviceFeatures2,
+) {
+    call(GetPhysicalDeviceFeatures2);
+
+    let mut next = unsafe { (*features).next };
+
+    while !next.is_null() {
+        let header = next.cast::<BaseOutStructure>();
+
+        if unsafe { (*header).struct_type }
+            == StructureType::PhysicalDeviceDescriptorIndexingFeatures
+        {
+            let descriptor_indexing = next.cast::<PhysicalDeviceDescriptorIndexingFeatures>();
+
+            unsafe {
+                let chained = (*descriptor_indexing).next;
+
+                descriptor_indexing.write(PhysicalDeviceDescriptorIndexingFeatures {
+                    next: chained,
+                    ..PhysicalDeviceDescriptorIndexingFeatures::bindless()
+                });
+            }
+        }
+
+        next = unsafe { (*header).next };
+    }
+}
+
+unsafe extern "system" fn get_physical_device_format_properties(
+    _physical_device: PhysicalDevice,
+    _format: Format,
+    format_properties: *mut FormatProperties,
+) {
+    call(GetPhysicalDeviceFormatProperties);
+
+    let properties = FormatProperties {
+        optimal_tiling_features: FormatFeatureFlags(
+            FormatFeatureFlags::BLIT_SRC.0
+                | FormatFeatureFlags::BLIT_DST.0
+                | FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR.0,
+        ),
+        ..Default::default()
+    };
+
+    unsafe { format_properties.write(properties) };
+}
+
+unsafe extern "system" fn create_image(
+    _device: Device,
+    _create_info: *const ImageCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    image: *mut Image,
+) -> VkResult {
+    unsafe {
+        create(CreateImage, MockHandleKind::Image, image, |raw| {
+            Image::from_raw_unchecked(raw)
+        })
+    }
+}
+
+unsafe extern "system" fn destroy_image(
+    _device: Device,
+    image: Image,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(DestroyImage, MockHandleKind::Image, image.as_raw());
+
+    STATE.with_borrow_mut(|state| {
+        state
+            .image_layouts
+            .retain(|&((raw, _), _)| raw != image.as_raw())
+    });
+}
+
+unsafe extern "system" fn get_image_memory_requirements(
+    _device: Device,
+    _image: Image,
+    memory_requirements: *mut MemoryRequirements,
+) {
+    call(GetImageMemoryRequirements);
+
+    let requirements = MemoryRequirements {
+        size: 1 << 16,
+        alignment: 256,
+        memory_type_bits: 0x1,
+    };
+
+    unsafe { memory_requirements.write(requirements) };
+}
+
+unsafe extern "system" fn allocate_memory(
+    _device: Device,
+    allocate_info: *const MemoryAllocateInfo,
+    _allocator: AllocationCallbacksRef,
+    memory: *mut DeviceMemory,
+) -> VkResult {
+    let result = unsafe {
+        create(
+            AllocateMemory,
+            MockHandleKind::DeviceMemory,
+            memory,
+            |raw| DeviceMemory::from_raw_unchecked(raw),
+        )
+    };
+
+    if result == VkResult::Success {
+        let size = unsafe { (*allocate_info).allocation_size } as usize;
+        let raw = unsafe { memory.read() }.as_raw();
+
+        STATE.with_borrow_mut(|state| {
+            state.memory.push((raw, vec![0; size].into_boxed_slice()));
+        });
+    }
+
+    result
+}
+
+unsafe extern "system" fn free_memory(
+    _device: Device,
+    memory: DeviceMemory,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(FreeMemory, MockHandleKind::DeviceMemory, memory.as_raw());
+
+    STATE.with_borrow_mut(|state| state.memory.retain(|(raw, _)| *raw != memory.as_raw()));
+}
+
+unsafe extern "system" fn map_memory(
+    _device: Device,
+    memory: DeviceMemory,
+    offset: u64,
+    _size: u64,
+    _flags: MemoryMapFlags,
+    data: *mut *mut c_void,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(MapMemory);
+
+        if result != VkResult::Success {
+            return result;
+        }
+
+        let Some((_, contents)) = state
+            .memory
+            .iter_mut()
+            .find(|(raw, _)| *raw == memory.as_raw())
+        else {
+            return VkResult::MemoryMapFailed;
+        };
+
+        unsafe { data.write(contents[offset as usize..].as_mut_ptr().cast()) };
+
+        result
+    })
+}
+
+unsafe extern "system" fn create_buffer(
+    _device: Device,
+    create_info: *const BufferCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    buffer: *mut Buffer,
+) -> VkResult {
+    let result = unsafe {
+        create(CreateBuffer, MockHandleKind::Buffer, buffer, |raw| {
+            Buffer::from_raw_unchecked(raw)
+        })
+    };
+
+    if result == VkResult::Success {
+        let raw = unsafe { buffer.read() }.as_raw();
+
+        let size = unsafe { (*create_info).size };
+
+        STATE.with_borrow_mut(|state| {
+            state.buffers.push(MockBuffer {
+                raw,
+                size,
+                binding: None,
+            })
+        });
+    }
+
+    result
+}
+
+unsafe extern "system" fn destroy_buffer(
+    _device: Device,
+    buffer: Buffer,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(DestroyBuffer, MockHandleKind::Buffer, buffer.as_raw());
+
+    STATE.with_borrow_mut(|state| state.buffers.retain(|live| live.raw != buffer.as_raw()));
+}
+
+unsafe extern "system" fn get_buffer_memory_requirements(
+    _device: Device,
+    buffer: Buffer,
+    memory_requirements: *mut MemoryRequirements,
+) {
+    let size = STATE.with_borrow_mut(|state| {
+        state.calls.push(GetBufferMemoryRequirements);
+        state
+            .buffers
+            .iter()
+            .find(|live| live.raw == buffer.as_raw())
+            .map_or(0, |live| live.size)
+    });
+
+    let requirements = MemoryRequirements {
+        size: size.next_multiple_of(256),
+        alignment: 256,
+        memory_type_bits: 0x3,
+    };
+
+    unsafe { memory_requirements.write(requirements) };
+}
+
+unsafe extern "system" fn bind_buffer_memory(
+    _device: Device,
+    buffer: Buffer,
+    memory: DeviceMemory,
+    memory_offset: u64,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(BindBufferMemory);
+
+        if result == VkResult::Success
+            && let Some(live) = state
+                .buffers
+                .iter_mut()
+                .find(|live| live.raw == buffer.as_raw())
+        {
+            live.binding = Some((memory.as_raw(), memory_offset));
+        }
+
+        result
+    })
+}
+
+unsafe extern "system" fn cmd_copy_buffer(
+    command_buffer: CommandBuffer,
+    src_buffer: Buffer,
+    dst_buffer: Buffer,
+    region_count: u32,
+    regions: *const BufferCopy,
+) {
+    let regions = unsafe { slice::from_raw_parts(regions, region_count as usize) }.to_vec();
+
+    STATE.with_borrow_mut(|state| {
+        state.calls.push(CmdCopyBuffer);
+        state.copies.push((
+            command_buffer.as_raw(),
+            BufferCopyCommand {
+                src: src_buffer.as_raw(),
+                dst: dst_buffer.as_raw(),
+                regions,
+            },
+        ));
+    });
+}
+
+unsafe extern "system" fn bind_image_memory(
+    _device: Device,
+    _image: Image,
+    _memory: DeviceMemory,
+    _memory_offset: u64,
+) -> VkResult {
+    call(BindImageMemory)
+}
+
+unsafe extern "system" fn create_image_view(
+    _device: Device,
+    create_info: *const ImageViewCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    view: *mut ImageView,
+) -> VkResult {
+    let result = unsafe {
+        create(CreateImageView, MockHandleKind::ImageView, view, |raw| {
+            ImageView::from_raw_unchecked(raw)
+        })
+    };
+
+    if result == VkResult::Success {
+        let info = unsafe { &*create_info };
+        let range = info.subresource_range;
+        let levels = range.base_mip_level..range.base_mip_level + range.level_count;
+
+        STATE.with_borrow_mut(|state| {
+            state
+                .image_views
+                .push((unsafe { (*view).as_raw() }, info.image.as_raw(), levels));
+        });
+    }
+
+    result
+}
+
+unsafe extern "system" fn destroy_image_view(
+    _device: Device,
+    image_view: ImageView,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyImageView,
+        MockHandleKind::ImageView,
+        image_view.as_raw(),
+    );
+}
+
+unsafe extern "system" fn create_sampler(
+    _device: Device,
+    _create_info: *const SamplerCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    sampler: *mut Sampler,
+) -> VkResult {
+    unsafe {
+        create(CreateSampler, MockHandleKind::Sampler, sampler, |raw| {
+            Sampler::from_raw_unchecked(raw)
+        })
+    }
+}
+
+unsafe extern "system" fn destroy_sampler(
+    _device: Device,
+    sampler: Sampler,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(DestroySampler, MockHandleKind::Sampler, sampler.as_raw());
+}
+
+unsafe extern "system" fn cmd_copy_buffer_to_image(
+    _command_buffer: CommandBuffer,
+    _src_buffer: Buffer,
+    dst_image: Image,
+    dst_image_layout: ImageLayout,
+    region_count: u32,
+    regions: *const BufferImageCopy,
+) {
+    let regions = unsafe { slice::from_raw_parts(regions, region_count as usize) };
+
+    STATE.with_borrow_mut(|state| {
+        state.calls.push(CmdCopyBufferToImage);
+
+        for region in regions {
+            let mip_level = region.image_subresource.mip_level;
+
+            state.use_layout(
+                CmdCopyBufferToImage,
+                (dst_image.as_raw(), mip_level),
+                dst_image_layout,
+                dst_image_layout,
+            );
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "system" fn cmd_blit_image(
+    _command_buffer: CommandBuffer,
+    src_image: Image,
+    src_image_layout: ImageLayout,
+    dst_image: Image,
+    dst_image_layout: ImageLayout,
+    region_count: u32,
+    regions: *const ImageBlit,
+    _filter: Filter,
+) {
+    let regions = unsafe { slice::from_raw_parts(regions, region_count as usize) };
+
+    STATE.with_borrow_mut(|state| {
+        state.calls.push(CmdBlitImage);
+
+        for region in regions {
+            state.use_layout(
+                CmdBlitImage,
+                (src_image.as_raw(), region.src_subresource.mip_level),
+                src_image_layout,
+                src_image_layout,
+            );
+            state.use_layout(
+                CmdBlitImage,
+                (dst_image.as_raw(), region.dst_subresource.mip_level),
+                dst_image_layout,
+                dst_image_layout,
+            );
+        }
+    });
+}
+
+unsafe extern "system" fn create_shader_module(
+    _device: Device,
+    _create_info: *const ShaderModuleCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    shader_module: *mut ShaderModule,
+) -> VkResult {
+    unsafe {
+        create(
+            CreateShaderModule,
+            MockHandleKind::ShaderModule,
+            shader_module,
+            |raw| ShaderModule::from_raw_unchecked(raw),
+        )
+    }
+}
+
+unsafe extern "system" fn destroy_shader_module(
+    _device: Device,
+    shader_module: ShaderModule,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyShaderModule,
+        MockHandleKind::ShaderModule,
+        shader_module.as_raw(),
+    );
+}
+
+unsafe extern "system" fn create_descriptor_set_layout(
+    _device: Device,
+    _create_info: *const DescriptorSetLayoutCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    set_layout: *mut DescriptorSetLayout,
+) -> VkResult {
+    unsafe {
+        create(
+            CreateDescriptorSetLayout,
+            MockHandleKind::DescriptorSetLayout,
+            set_layout,
+            |raw| DescriptorSetLayout::from_raw_unchecked(raw),
+        )
+    }
+}
+
+unsafe extern "system" fn destroy_descriptor_set_layout(
+    _device: Device,
+    set_layout: DescriptorSetLayout,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyDescriptorSetLayout,
+        MockHandleKind::DescriptorSetLayout,
+        set_layout.as_raw(),
+    );
+}
+
+unsafe extern "system" fn create_descriptor_pool(
+    _device: Device,
+    create_info: *const DescriptorPoolCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    descriptor_pool: *mut DescriptorPool,
+) -> VkResult {
+    let max_sets = unsafe { (*create_info).max_sets };
+
+    let result = unsafe {
+        create(
+            CreateDescriptorPool,
+            MockHandleKind::DescriptorPool,
+            descriptor_pool,
+            |raw| DescriptorPool::from_raw_unchecked(raw),
+        )
+    };
+
+    if result == VkResult::Success {
+        let raw = unsafe { (*descriptor_pool).as_raw() };
+
+        STATE.with_borrow_mut(|state| state.descriptor_pools.push((raw, max_sets, 0)));
+    }
+
+    result
+}
+
+unsafe extern "system" fn destroy_descriptor_pool(
+    _device: Device,
+    descriptor_pool: DescriptorPool,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyDescriptorPool,
+        MockHandleKind::DescriptorPool,
+        descriptor_pool.as_raw(),
+    );
+
+    STATE.with_borrow_mut(|state| {
+        state
+            .descriptor_pools
+            .retain(|&(raw, ..)| raw != descriptor_pool.as_raw())
+    });
+}
+
+unsafe extern "system" fn reset_descriptor_pool(
+    _device: Device,
+    descriptor_pool: DescriptorPool,
+    _flags: DescriptorPoolResetFlags,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(ResetDescriptorPool);
+
+        if result == VkResult::Success
+            && let Some((.., allocated)) = state
+                .descriptor_pools
+                .iter_mut()
+                .find(|(raw, ..)| *raw == descriptor_pool.as_raw())
+        {
+            *allocated = 0;
+        }
+
+        result
+    })
+}
+
+unsafe extern "system" fn allocate_descriptor_sets(
+    _device: Device,
+    allocate_info: *const DescriptorSetAllocateInfo,
+    descriptor_sets: *mut DescriptorSet,
+) -> VkResult {
+    let info = unsafe { &*allocate_info };
+
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(AllocateDescriptorSets);
+
+        if result != VkResult::Success {
+            return result;
+        }
+
+        let Some((.., max_sets, allocated)) = state
+            .descriptor_pools
+            .iter_mut()
+            .find(|(raw, ..)| *raw == info.descriptor_pool.as_raw())
+        else {
+            return VkResult::Unknown;
+        };
+
+        if *allocated + info.descriptor_set_count > *max_sets {
+            return VkResult::OutOfPoolMemory;
+        }
+
+        *allocated += info.descriptor_set_count;
+
+        for index in 0..info.descriptor_set_count as usize {
+            let raw = state.next_raw();
+
+            unsafe {
+                descriptor_sets
+                    .add(index)
+                    .write(DescriptorSet::from_raw_unchecked(raw))
+            };
+        }
+
+        VkResult::Success
+    })
+}
+
+unsafe extern "system" fn update_descriptor_sets(
+    _device: Device,
+    descriptor_write_count: u32,
+    descriptor_writes: *const WriteDescriptorSet,
+    _descriptor_copy_count: u32,
+    _descriptor_copies: *const c_void,
+) {
+    let writes =
+        unsafe { slice::from_raw_parts(descriptor_writes, descriptor_write_count as usize) };
+
+    STATE.with_borrow_mut(|state| {
+        state.calls.push(UpdateDescriptorSets);
+
+        for write in writes.iter().filter(|write| !write.image_info.is_null()) {
+            let infos =
+                unsafe { slice::from_raw_parts(write.image_info, write.descriptor_count as usize) };
+
+            for (element, info) in (write.dst_array_element..).zip(infos) {
+                let key = (write.dst_set.as_raw(), write.dst_binding, element);
+
+                state
+                    .descriptor_images
+                    .retain(|&(written, ..)| written != key);
+
+                if let Some(view) = info.image_view {
+                    state
+                        .descriptor_images
+                        .push((key, view.as_raw(), info.image_layout));
+                }
+            }
+        }
+    });
+}
+
+unsafe extern "system" fn create_pipeline_layout(
+    _device: Device,
+    _create_info: *const PipelineLayoutCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    pipeline_layout: *mut PipelineLayout,
+) -> VkResult {
+    unsafe {
+        create(
+            CreatePipelineLayout,
+            MockHandleKind::PipelineLayout,
+            pipeline_layout,
+            |raw| PipelineLayout::from_raw_unchecked(raw),
+        )
+    }
+}
+
+unsafe extern "system" fn destroy_pipeline_layout(
+    _device: Device,
+    pipeline_layout: PipelineLayout,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyPipelineLayout,
+        MockHandleKind::PipelineLayout,
+        pipeline_layout.as_raw(),
+    );
+}
+
+/// The header of the data of every mock pipeline cache.
+fn pipeline_cache_header() -> PipelineCacheHeader {
+    PipelineCacheHeader {
+        header_size: PipelineCacheHeader::SIZE as u32,
+        header_version: PipelineCacheHeader::VERSION_ONE,
+        vendor_id: VENDOR_ID,
+        device_id: DEVICE_ID,
+        pipeline_cache_uuid: PIPELINE_CACHE_UUID,
+    }
+}
+
+/// Creates a pipeline cache holding its initial data, unless that data was written by
+/// another device, in which case it starts empty as a driver would.
+unsafe extern "system" fn create_pipeline_cache(
+    _device: Device,
+    create_info: *const PipelineCacheCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    pipeline_cache: *mut PipelineCache,
+) -> VkResult {
+    let result = unsafe {
+        create(
+            CreatePipelineCache,
+            MockHandleKind::PipelineCache,
+            pipeline_cache,
+            |raw| PipelineCache::from_raw_unchecked(raw),
+        )
+    };
+
+    if result == VkResult::Success {
+        let create_info = unsafe { &*create_info };
+        let initial_data = match create_info.initial_data_size {
+            0 => &[][..],
+            size => unsafe { slice::from_raw_parts(create_info.initial_data.cast::<u8>(), size) },
+        };
+
+        let data = match PipelineCacheHeader::read(initial_data) {
+            Some(header) if header == pipeline_cache_header() => initial_data.to_vec(),
+            _ => pipeline_cache_header().to_bytes().to_vec(),
+        };
+
+        let raw = unsafe { pipeline_cache.read() }.as_raw();
+
+        STATE.with_borrow_mut(|state| state.pipeline_caches.push((raw, data)));
+    }
+
+    result
+}
+
+unsafe extern "system" fn destroy_pipeline_cache(
+    _device: Device,
+    pipeline_cache: PipelineCache,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyPipelineCache,
+        MockHandleKind::PipelineCache,
+        pipeline_cache.as_raw(),
+    );
+
+    STATE.with_borrow_mut(|state| {
+        state
+            .pipeline_caches
+            .retain(|(raw, _)| *raw != pipeline_cache.as_raw())
+    });
+}
+
+unsafe extern "system" fn get_pipeline_cache_data(
+    _device: Device,
+    pipeline_cache: PipelineCache,
+    data_size: *mut usize,
+    data: *mut c_void,
+) -> VkResult {
+    STATE.with_borrow_mut(|state| {
+        let result = state.call(GetPipelineCacheData);
+
+        if result != VkResult::Success {
+            return result;
+        }
+
+        let Some((_, contents)) = state
+            .pipeline_caches
+            .iter()
+            .find(|(raw, _)| *raw == pipeline_cache.as_raw())
+        else {
+            return VkResult::Unknown;
+        };
+
+        unsafe {
+            if data.is_null() {
+                data_size.write(contents.len());
+
+                return VkResult::Success;
+            }
+
+            let written = data_size.read().min(contents.len());
+
+            copy_nonoverlapping(contents.as_ptr(), data.cast(), written);
+            data_size.write(written);
+
+            if written < contents.len() {
+                VkResult::Incomplete
+            } else {
+                VkResult::Success
+            }
+        }
+    })
+}
+
+unsafe extern "system" fn create_graphics_pipelines(
+    _device: Device,
+    pipeline_cache: Option<PipelineCache>,
+    create_info_count: u32,
+    _create_infos: *const GraphicsPipelineCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    pipelines: *mut Pipeline,
+) -> VkResult {
+    unsafe {
+        create_pipelines(
+            CreateGraphicsPipelines,
+            pipeline_cache,
+            create_info_count,
+            pipelines,
+        )
+    }
+}
+
+unsafe extern "system" fn create_compute_pipelines(
+    _device: Device,
+    pipeline_cache: Option<PipelineCache>,
+    create_info_count: u32,
+    _create_infos: *const ComputePipelineCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    pipelines: *mut Pipeline,
+) -> VkResult {
+    unsafe {
+        create_pipelines(
+            CreateComputePipelines,
+            pipeline_cache,
+            create_info_count,
+            pipelines,
+        )
+    }
+}
+
+/// Creates pipelines, recording each one's handle in the cache they were created with as
+/// its compiled code.
+unsafe fn create_pipelines(
+    name: VulkanFunctionName,
+    pipeline_cache: Option<PipelineCache>,
+    create_info_count: u32,
+    pipelines: *mut Pipeline,
+) -> VkResult {
+    for index in 0..create_info_count as usize {
+        let pipeline = unsafe { pipelines.add(index) };
+        let result = unsafe {
+            create(name, MockHandleKind::Pipeline, pipeline, |raw| {
+                Pipeline::from_raw_unchecked(raw)
+            })
+        };
+
+        if result != VkResult::Success {
+            return result;
+        }
+
+        if let Some(pipeline_cache) = pipeline_cache {
+            let raw = unsafe { pipeline.read() }.as_raw();
+
+            STATE.with_borrow_mut(|state| {
+                if let Some((_, contents)) = state
+                    .pipeline_caches
+                    .iter_mut()
+                    .find(|(cache, _)| *cache == pipeline_cache.as_raw())
+                {
+                    contents.extend_from_slice(&raw.to_ne_bytes());
+                }
+            });
+        }
+    }
+
+    VkResult::Success
+}
+
+unsafe extern "system" fn destroy_pipeline(
+    _device: Device,
+    pipeline: Pipeline,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(DestroyPipeline, MockHandleKind::Pipeline, pipeline.as_raw());
+}
+
+unsafe extern "system" fn create_render_pass(
+    _device: Device,
+    _create_info: *const RenderPassCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    render_pass: *mut RenderPass,
+) -> VkResult {
+    unsafe {
+        create(
+            CreateRenderPass,
+            MockHandleKind::RenderPass,
+            render_pass,
+            |raw| RenderPass::from_raw_unchecked(raw),
+        )
+    }
+}
+
+unsafe extern "system" fn destroy_render_pass(
+    _device: Device,
+    render_pass: RenderPass,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyRenderPass,
+        MockHandleKind::RenderPass,
+        render_pass.as_raw(),
+    );
+}
+
+unsafe extern "system" fn create_framebuffer(
+    _device: Device,
+    _create_info: *const FramebufferCreateInfo,
+    _allocator: AllocationCallbacksRef,
+    framebuffer: *mut Framebuffer,
+) -> VkResult {
+    unsafe {
+        create(
+            CreateFramebuffer,
+            MockHandleKind::Framebuffer,
+            framebuffer,
+            |raw| Framebuffer::from_raw_unchecked(raw),
+        )
+    }
+}
+
+unsafe extern "system" fn destroy_framebuffer(
+    _device: Device,
+    framebuffer: Framebuffer,
+    _allocator: AllocationCallbacksRef,
+) {
+    destroy(
+        DestroyFramebuffer,
+        MockHandleKind::Framebuffer,
+        framebuffer.as_raw(),
+    );
+}
+
+unsafe extern "system" fn cmd_begin_render_pass(
+    _command_buffer: CommandBuffer,
+    _render_pass_begin: *const RenderPassBeginInfo,
+    _contents: SubpassContents,
+) {
+    call(CmdBeginRenderPass);
+}
+
+unsafe extern "system" fn cmd_end_render_pass(_command_buffer: CommandBuffer) {
+    call(CmdEndRenderPass);
+}
+
+unsafe extern "system" fn cmd_begin_rendering(
+    _command_buffer: CommandBuffer,
+    _rendering_info: *const RenderingInfo,
+) {
+    call(CmdBeginRendering);
+}
+
+unsafe extern "system" fn cmd_end_rendering(_command_buffer: CommandBuffer) {
+    call(CmdEndRendering);
+}
+
+unsafe extern "system" fn cmd_begin_rendering_khr(
+    _command_buffer: CommandBuffer,
+    _rendering_info: *const RenderingInfo,
+) {
+    call(CmdBeginRenderingKhr);
+}
+
+unsafe extern "system" fn cmd_end_rendering_khr(_command_buffer: CommandBuffer) {
+    call(CmdEndRenderingKhr);
+}
+
+unsafe extern "system" fn cmd_bind_pipeline(
+    _command_buffer: CommandBuffer,
+    _pipeline_bind_point: PipelineBindPoint,
+    _pipeline: Pipeline,
+) {
+    call(CmdBindPipeline);
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe extern "system" fn cmd_bind_descriptor_sets(
+    command_buffer: CommandBuffer,
+    _pipeline_bind_point: PipelineBindPoint,
+    _layout: PipelineLayout,
+    first_set: u32,
+    descriptor_set_count: u32,
+    descriptor_sets: *const DescriptorSet,
+    _dynamic_offset_count: u32,
+    _dynamic_offsets: *const u32,
+) {
+    let sets = unsafe { slice::from_raw_parts(descriptor_sets, descriptor_set_count as usize) };
+
+    STATE.with_borrow_mut(|state| {
+        state.calls.push(CmdBindDescriptorSets);
+
+        for (index, set) in (first_set..).zip(sets) {
+            let key = (command_buffer.as_raw(), index);
+
+            state.bound_sets.retain(|&(bound, _)| bound != key);
+            state.bound_sets.push((key, set.as_raw()));
+        }
+    });
+}
+
+unsafe extern "system" fn cmd_push_constants(
+    _command_buffer: CommandBuffer,
+    _layout: PipelineLayout,
+    _stage_flags: ShaderStageFlags,
+    _offset: u32,
+    _size: u32,
+    _values: *const c_void,
+) {
+    call(CmdPushConstants);
+}
+
+unsafe extern "system" fn cmd_dispatch(
+    command_buffer: CommandBuffer,
+    _group_count_x: u32,
+    _group_count_y: u32,
+    _group_count_z: u32,
+) {
+    STATE.with_borrow_mut(|state| {
+        state.calls.push(CmdDispatch);
+        state.use_descriptor_images(CmdDispatch, command_buffer.as_raw());
+    });
+}
+
+unsafe extern "system" fn cmd_dispatch_indirect(
+    command_buffer: CommandBuffer,
+    _buffer: Buffer,
+    _offset: u64,
+) {
+    STATE.with_borrow_mut(|state| {
+        state.calls.push(CmdDispatchIndirect);
+        state.use_descriptor_images(CmdDispatchIndirect, command_buffer.as_raw());
+    });
+}
@@ -1,36 +1,50 @@
+mod device;
+mod entry;
 mod function_name;
 mod loader;
+#[cfg(any(test, feature = "mock_driver"))]
+pub(crate) mod mock;
 
+pub(crate) use device::DeviceFnTable;
+pub(crate) use entry::Entry;
 pub use function_name::VulkanFunctionName;
-
-use core::mem::MaybeUninit;
-
-use libloading::Library;
-use once_cell::sync::OnceCell;
+pub use loader::{PfnGetInstanceProcAddr, VulkanLoader};
+#[cfg(feature = "mock_driver")]
+pub use mock::MockHandle;
+#[cfg(any(test, feature = "mock_driver"))]
+pub use mock::{MockDriver, MockHandleKind};
+
+use core::{
+    ffi::{c_char, c_void},
+    mem::{MaybeUninit, transmute_copy},
+    ptr::{null, null_mut},
+};
 
 use VulkanFunctionName::*;
+use device::PfnGetDeviceProcAddr;
 
+#[cfg(feature = "windowing")]
+use crate::types::{
+    WaylandSurfaceCreateInfo, Win32SurfaceCreateInfo, XcbSurfaceCreateInfo, XlibSurfaceCreateInfo,
+};
 use crate::{
     VulkanErrorKind,
     types::{
-        AllocationCallbacksRef, Instance, InstanceCreateInfo, LayerProperties, Surface, VkResult,
-        WaylandSurfaceCreateInfo, Win32SurfaceCreateInfo, XcbSurfaceCreateInfo,
-        XlibSurfaceCreateInfo,
+        AllocationCallbacksRef, DebugUtilsMessenger, DebugUtilsMessengerCreateInfo, Device,
+        DeviceCreateInfo, ExtensionName, ExtensionProperties, Format, FormatProperties,
+        ImageCreateFlags, ImageFormatProperties, ImageTiling, ImageType, ImageUsageFlags, Instance,
+        PhysicalDevice, PhysicalDeviceFeatures2, PhysicalDeviceMemoryProperties,
+        PhysicalDeviceProperties, PresentMode, QueueFamilyProperties, Surface, SurfaceCapabilities,
+        SurfaceFormat, VkResult,
     },
 };
 
+/// Instance-level functions, resolved through `vkGetInstanceProcAddr` for one instance.
 pub struct FnTable {
-    library: Option<Library>,
-
-    fn_create_instance: unsafe extern "system" fn(
-        create_info: *const InstanceCreateInfo,
-        allocator: AllocationCallbacksRef,
-        instance: *mut Instance,
-    ) -> VkResult,
-
     fn_destroy_instance:
         unsafe extern "system" fn(instance: Instance, allocator: AllocationCallbacksRef),
 
+    #[cfg(feature = "windowing")]
     fn_create_win32_surface: Option<
         unsafe extern "system" fn(
             instance: Instance,
@@ -40,6 +54,7 @@ pub struct FnTable {
         ) -> VkResult,
     >,
 
+    #[cfg(feature = "windowing")]
     fn_create_wayland_surface: Option<
         unsafe extern "system" fn(
             instance: Instance,
@@ -48,6 +63,7 @@ pub struct FnTable {
             surface: *mut Surface,
         ) -> VkResult,
     >,
+    #[cfg(feature = "windowing")]
     fn_create_xcb_surface: Option<
         unsafe extern "system" fn(
             instance: Instance,
@@ -56,6 +72,7 @@ pub struct FnTable {
             surface: *mut Surface,
         ) -> VkResult,
     >,
+    #[cfg(feature = "windowing")]
     fn_create_xlib_surface: Option<
         unsafe extern "system" fn(
             instance: Instance,
@@ -65,104 +82,332 @@ pub struct FnTable {
         ) -> VkResult,
     >,
 
-    fn_destroy_surface: unsafe extern "system" fn(
+    fn_destroy_surface: Option<
+        unsafe extern "system" fn(
+            instance: Instance,
+            surface: Surface,
+            allocator: AllocationCallbacksRef,
+        ),
+    >,
+
+    fn_create_debug_utils_messenger: Option<
+        unsafe extern "system" fn(
+            instance: Instance,
+            create_info: *const DebugUtilsMessengerCreateInfo,
+            allocator: AllocationCallbacksRef,
+            messenger: *mut DebugUtilsMessenger,
+        ) -> VkResult,
+    >,
+
+    fn_destroy_debug_utils_messenger: Option<
+        unsafe extern "system" fn(
+            instance: Instance,
+            messenger: DebugUtilsMessenger,
+            allocator: AllocationCallbacksRef,
+        ),
+    >,
+
+    fn_enumerate_physical_devices: unsafe extern "system" fn(
         instance: Instance,
-        surface: Surface,
-        allocator: AllocationCallbacksRef,
+        physical_device_count: *mut u32,
+        physical_devices: *mut PhysicalDevice,
+    ) -> VkResult,
+
+    fn_get_physical_device_properties: unsafe extern "system" fn(
+        physical_device: PhysicalDevice,
+        properties: *mut PhysicalDeviceProperties,
+    ),
+
+    fn_get_physical_device_queue_family_properties: unsafe extern "system" fn(
+        physical_device: PhysicalDevice,
+        queue_family_property_count: *mut u32,
+        queue_family_properties: *mut QueueFamilyProperties,
     ),
-    fn_enumerate_instance_layer_properties: unsafe extern "system" fn(
+
+    fn_enumerate_device_extension_properties: unsafe extern "system" fn(
+        physical_device: PhysicalDevice,
+        layer_name: *const c_char,
         property_count: *mut u32,
-        properties: *mut LayerProperties,
+        properties: *mut ExtensionProperties,
     ) -> VkResult,
-}
 
-static FN_TABLE: OnceCell<FnTable> = OnceCell::new();
+    fn_create_device: unsafe extern "system" fn(
+        physical_device: PhysicalDevice,
+        create_info: *const DeviceCreateInfo,
+        allocator: AllocationCallbacksRef,
+        device: *mut Device,
+    ) -> VkResult,
+
+    fn_get_device_proc_addr: PfnGetDeviceProcAddr,
+
+    fn_get_physical_device_surface_support: Option<
+        unsafe extern "system" fn(
+            physical_device: PhysicalDevice,
+            queue_family_index: u32,
+            surface: Surface,
+            supported: *mut u32,
+        ) -> VkResult,
+    >,
+
+    fn_get_physical_device_surface_capabilities: Option<
+        unsafe extern "system" fn(
+            physical_device: PhysicalDevice,
+            surface: Surface,
+            surface_capabilities: *mut SurfaceCapabilities,
+        ) -> VkResult,
+    >,
+
+    fn_get_physical_device_surface_formats: Option<
+        unsafe extern "system" fn(
+            physical_device: PhysicalDevice,
+            surface: Surface,
+            surface_format_count: *mut u32,
+            surface_formats: *mut SurfaceFormat,
+        ) -> VkResult,
+    >,
+
+    fn_get_physical_device_surface_present_modes: Option<
+        unsafe extern "system" fn(
+            physical_device: PhysicalDevice,
+            surface: Surface,
+            present_mode_count: *mut u32,
+            present_modes: *mut PresentMode,
+        ) -> VkResult,
+    >,
+
+    fn_get_physical_device_image_format_properties: unsafe extern "system" fn(
+        physical_device: PhysicalDevice,
+        format: Format,
+        image_type: ImageType,
+        tiling: ImageTiling,
+        usage: ImageUsageFlags,
+        flags: ImageCreateFlags,
+        image_format_properties: *mut ImageFormatProperties,
+    ) -> VkResult,
+
+    fn_get_physical_device_memory_properties: unsafe extern "system" fn(
+        physical_device: PhysicalDevice,
+        memory_properties: *mut PhysicalDeviceMemoryProperties,
+    ),
+
+    fn_get_physical_device_format_properties: unsafe extern "system" fn(
+        physical_device: PhysicalDevice,
+        format: Format,
+        format_properties: *mut FormatProperties,
+    ),
+
+    fn_get_physical_device_features2: Option<
+        unsafe extern "system" fn(
+            physical_device: PhysicalDevice,
+            features: *mut PhysicalDeviceFeatures2,
+        ),
+    >,
+}
 
 impl FnTable {
-    /// Returns the cached global function table, initializing and caching it on first use.
+    /// Resolves the instance-level functions for `instance` through `entry`.
+    ///
+    /// If any required function is missing, `instance` is destroyed before returning, as
+    /// nothing else could destroy it.
+    ///
+    /// Functions of instance extensions are only resolved when the extension is among
+    /// `extensions`, as a loader does not provide them otherwise.
+    ///
+    /// # Errors
     ///
-    /// On success, yields a `'static` reference to the initialized `FnTable`. Returns an error if the Vulkan library cannot be loaded.
+    /// Returns `FunctionLoadFailed` naming the first function that cannot be resolved.
     ///
-    /// # Examples
+    /// # Safety
     ///
-    /// ```
-    /// // Access the global function table (may initialize the table on first call).
-    /// let table = crate::backends::vulkan::FnTable::global().unwrap();
-    /// // Use `table` to call loaded Vulkan functions, e.g. `table.fn_create_instance`.
-    /// ```
-    pub(crate) fn global() -> super::Result<&'static Self> {
-        FN_TABLE.get_or_try_init(Self::new)
+    /// `instance` must have been created by `entry` with `allocator` and `extensions`.
+    pub(crate) unsafe fn load(
+        entry: &Entry,
+        instance: Instance,
+        allocator: AllocationCallbacksRef,
+        extensions: &[ExtensionName],
+    ) -> super::Result<Self> {
+        let loader = ProcAddrLoader {
+            get_instance_proc_addr: entry.get_instance_proc_addr(),
+            instance: Some(instance),
+        };
+
+        let fn_destroy_instance: unsafe extern "system" fn(Instance, AllocationCallbacksRef) =
+            unsafe { loader.load(DestroyInstance) }?;
+
+        let result = unsafe { Self::load_with(loader, extensions) };
+
+        if result.is_err() {
+            unsafe { fn_destroy_instance(instance, allocator) };
+        }
+
+        result
     }
 
-    fn new() -> super::Result<Self> {
-        match unsafe { loader::vulkan_lib() } {
-            Ok(library) => Ok(Self {
-                fn_create_instance: unsafe {
-                    *library.get(CreateInstance.as_ref()).map_err(|_| {
-                        VulkanErrorKind::FunctionLoadFailed {
-                            name: CreateInstance,
-                        }
-                    })?
-                },
-                fn_destroy_instance: unsafe {
-                    *library.get(DestroyInstance.as_ref()).map_err(|_| {
-                        VulkanErrorKind::FunctionLoadFailed {
-                            name: DestroyInstance,
-                        }
-                    })?
-                },
-                fn_create_win32_surface: unsafe {
-                    library
-                        .get(CreateWin32Surface.as_ref())
-                        .map(|ptr| *ptr)
-                        .ok()
-                },
-                fn_create_wayland_surface: unsafe {
-                    library
-                        .get(CreateWaylandSurface.as_ref())
-                        .map(|ptr| *ptr)
-                        .ok()
-                },
-                fn_create_xcb_surface: unsafe {
-                    library.get(CreateXcbSurface.as_ref()).map(|ptr| *ptr).ok()
-                },
-                fn_create_xlib_surface: unsafe {
-                    library.get(CreateXlibSurface.as_ref()).map(|ptr| *ptr).ok()
-                },
-                fn_destroy_surface: unsafe {
-                    *library.get(DestroySurface.as_ref()).map_err(|_| {
-                        VulkanErrorKind::FunctionLoadFailed {
-                            name: DestroySurface,
-                        }
-                    })?
-                },
-                fn_enumerate_instance_layer_properties: unsafe {
-                    *library
-                        .get(EnumerateInstanceLayerProperties.as_ref())
-                        .map_err(|_| VulkanErrorKind::FunctionLoadFailed {
-                            name: EnumerateInstanceLayerProperties,
-                        })?
-                },
-                library: Some(library),
-            }),
-            Err(_) => VulkanErrorKind::LibraryLoad.into_result(),
+    unsafe fn load_with(
+        loader: ProcAddrLoader,
+        extensions: &[ExtensionName],
+    ) -> super::Result<Self> {
+        let enabled = |extension: ExtensionName| {
+            extensions
+                .iter()
+                .any(|name| name.as_c_str() == extension.as_c_str())
+        };
+
+        let surface = enabled(ExtensionName::SURFACE);
+        let debug_utils = enabled(ExtensionName::DEBUG_UTILS);
+
+        unsafe {
+            Ok(Self {
+                fn_destroy_instance: loader.load(DestroyInstance)?,
+                #[cfg(feature = "windowing")]
+                fn_create_win32_surface: loader
+                    .load_extension(enabled(ExtensionName::WIN32_SURFACE), CreateWin32Surface),
+                #[cfg(feature = "windowing")]
+                fn_create_wayland_surface: loader.load_extension(
+                    enabled(ExtensionName::WAYLAND_SURFACE),
+                    CreateWaylandSurface,
+                ),
+                #[cfg(feature = "windowing")]
+                fn_create_xcb_surface: loader
+                    .load_extension(enabled(ExtensionName::XCB_SURFACE), CreateXcbSurface),
+                #[cfg(feature = "windowing")]
+                fn_create_xlib_surface: loader
+                    .load_extension(enabled(ExtensionName::XLIB_SURFACE), CreateXlibSurface),
+                fn_destroy_surface: loader.load_extension(surface, DestroySurface),
+                fn_create_debug_utils_messenger: loader
+                    .load_extension(debug_utils, CreateDebugUtilsMessenger),
+                fn_destroy_debug_utils_messenger: loader
+                    .load_extension(debug_utils, DestroyDebugUtilsMessenger),
+                fn_enumerate_physical_devices: loader.load(EnumeratePhysicalDevices)?,
+                fn_get_physical_device_properties: loader.load(GetPhysicalDeviceProperties)?,
+                fn_get_physical_device_queue_family_properties: loader
+                    .load(GetPhysicalDeviceQueueFamilyProperties)?,
+                fn_enumerate_device_extension_properties: loader
+                    .load(EnumerateDeviceExtensionProperties)?,
+                fn_create_device: loader.load(CreateDevice)?,
+                fn_get_device_proc_addr: loader.load(GetDeviceProcAddr)?,
+                fn_get_physical_device_surface_support: loader
+                    .load_extension(surface, GetPhysicalDeviceSurfaceSupport),
+                fn_get_physical_device_surface_capabilities: loader
+                    .load_extension(surface, GetPhysicalDeviceSurfaceCapabilities),
+                fn_get_physical_device_surface_formats: loader
+                    .load_extension(surface, GetPhysicalDeviceSurfaceFormats),
+                fn_get_physical_device_surface_present_modes: loader
+                    .load_extension(surface, GetPhysicalDeviceSurfacePresentModes),
+                fn_get_physical_device_image_format_properties: loader
+                    .load(GetPhysicalDeviceImageFormatProperties)?,
+                fn_get_physical_device_memory_properties: loader
+                    .load(GetPhysicalDeviceMemoryProperties)?,
+                fn_get_physical_device_format_properties: loader
+                    .load(GetPhysicalDeviceFormatProperties)?,
+                fn_get_physical_device_features2: loader.load_optional(GetPhysicalDeviceFeatures2),
+            })
         }
     }
 }
 
-impl FnTable {
+/// Resolves entry points through `vkGetInstanceProcAddr`.
+///
+/// With no `instance`, only global functions can be resolved.
+#[derive(Clone, Copy)]
+pub(super) struct ProcAddrLoader {
+    pub get_instance_proc_addr: PfnGetInstanceProcAddr,
+    pub instance: Option<Instance>,
+}
+
+impl ProcAddrLoader {
+    /// Resolves a required entry point.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FunctionLoadFailed` naming the function if it cannot be resolved.
+    ///
+    /// # Safety
+    ///
+    /// `F` must be the function pointer type matching the Vulkan signature of `name`.
     #[inline]
-    pub(crate) unsafe fn create_instance(
-        &self,
-        create_info: &InstanceCreateInfo,
-        allocator: AllocationCallbacksRef,
-    ) -> super::Result<Instance> {
-        let mut instance = MaybeUninit::<Instance>::uninit();
+    pub(super) unsafe fn load<F: Copy>(self, name: VulkanFunctionName) -> super::Result<F> {
+        match unsafe { self.load_optional(name) } {
+            Some(function) => Ok(function),
+            None => VulkanErrorKind::FunctionLoadFailed { name }.into_result(),
+        }
+    }
+
+    /// Resolves an entry point that is allowed to be missing, such as platform surface
+    /// extensions.
+    ///
+    /// # Safety
+    ///
+    /// `F` must be the function pointer type matching the Vulkan signature of `name`.
+    #[inline]
+    pub(super) unsafe fn load_optional<F: Copy>(self, name: VulkanFunctionName) -> Option<F> {
+        const {
+            assert!(size_of::<F>() == size_of::<unsafe extern "system" fn()>());
+        }
+
+        let instance = self
+            .instance
+            .map_or(null_mut(), |instance| instance.as_raw() as *mut c_void);
+
+        let function = unsafe { (self.get_instance_proc_addr)(instance, name.c_name().as_ptr()) }?;
 
-        unsafe { (self.fn_create_instance)(create_info, allocator, instance.as_mut_ptr()) }
-            .into_result(CreateInstance, || unsafe { instance.assume_init() })
+        Some(unsafe { transmute_copy::<unsafe extern "system" fn(), F>(&function) })
     }
 
+    /// Resolves an entry point of an instance extension, or nothing when the extension is not
+    /// `enabled`.
+    ///
+    /// # Safety
+    ///
+    /// `F` must be the function pointer type matching the Vulkan signature of `name`.
+    #[inline]
+    pub(super) unsafe fn load_extension<F: Copy>(
+        self,
+        enabled: bool,
+        name: VulkanFunctionName,
+    ) -> Option<F> {
+        if enabled {
+            unsafe { self.load_optional(name) }
+        } else {
+            None
+        }
+    }
+}
+
+/// Runs the two-call enumeration idiom: query the count, then fill a buffer of that size.
+///
+/// Retries while the driver reports `VK_INCOMPLETE`, which happens when the set grows between
+/// the two calls.
+///
+/// # Safety
+///
+/// `call` must forward its arguments to a Vulkan enumeration function writing elements of `T`.
+unsafe fn enumerate<T>(
+    name: VulkanFunctionName,
+    mut call: impl FnMut(*mut u32, *mut T) -> VkResult,
+) -> super::Result<Vec<T>> {
+    loop {
+        let mut count = 0;
+
+        call(&mut count, null_mut()).into_result(name, || ())?;
+
+        let mut elements = Vec::<T>::with_capacity(count as usize);
+
+        let result = call(&mut count, elements.as_mut_ptr());
+
+        if result == VkResult::Incomplete {
+            continue;
+        }
+
+        return result.into_result(name, || {
+            unsafe { elements.set_len(count as usize) };
+
+            elements
+        });
+    }
+}
+
+impl FnTable {
     #[inline]
     pub(crate) unsafe fn destroy_instance(
         &self,
@@ -172,6 +417,7 @@ impl FnTable {
         unsafe { (self.fn_destroy_instance)(instance, allocator) }
     }
 
+    #[cfg(feature = "windowing")]
     #[inline]
     pub(crate) unsafe fn create_win32_surface(
         &self,
@@ -192,6 +438,7 @@ impl FnTable {
             .into_result(CreateWin32Surface, || unsafe { surface.assume_init() })
     }
 
+    #[cfg(feature = "windowing")]
     #[inline]
     pub(crate) unsafe fn create_wayland_surface(
         &self,
@@ -214,6 +461,7 @@ impl FnTable {
         .into_result(CreateWaylandSurface, || unsafe { surface.assume_init() })
     }
 
+    #[cfg(feature = "windowing")]
     #[inline]
     pub(crate) unsafe fn create_xcb_surface(
         &self,
@@ -234,6 +482,7 @@ impl FnTable {
             .into_result(CreateXcbSurface, || unsafe { surface.assume_init() })
     }
 
+    #[cfg(feature = "windowing")]
     #[inline]
     pub(crate) unsafe fn create_xlib_surface(
         &self,
@@ -254,6 +503,8 @@ impl FnTable {
             .into_result(CreateXlibSurface, || unsafe { surface.assume_init() })
     }
 
+    /// Destroys `surface`. Does nothing if `VK_KHR_surface` is not enabled, since no surface
+    /// can exist then.
     #[inline]
     pub(crate) unsafe fn destroy_surface(
         &self,
@@ -261,25 +512,366 @@ impl FnTable {
         surface: Surface,
         allocator: AllocationCallbacksRef,
     ) {
-        unsafe { (self.fn_destroy_surface)(instance, surface, allocator) }
+        if let Some(fn_destroy_surface) = self.fn_destroy_surface {
+            unsafe { (fn_destroy_surface)(instance, surface, allocator) }
+        }
     }
 
     #[inline]
-    pub(crate) unsafe fn enumerate_instance_layer_properties(
+    pub(crate) unsafe fn create_debug_utils_messenger(
         &self,
-        property_count: &mut u32,
-        properties: *mut LayerProperties,
-    ) -> super::Result<()> {
-        unsafe { (self.fn_enumerate_instance_layer_properties)(property_count, properties) }
-            .into_result(EnumerateInstanceLayerProperties, || ())
+        instance: Instance,
+        create_info: &DebugUtilsMessengerCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> super::Result<DebugUtilsMessenger> {
+        let Some(fn_create_debug_utils_messenger) = self.fn_create_debug_utils_messenger else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: CreateDebugUtilsMessenger,
+            }
+            .into_result();
+        };
+
+        let mut messenger = MaybeUninit::<DebugUtilsMessenger>::uninit();
+
+        unsafe {
+            (fn_create_debug_utils_messenger)(
+                instance,
+                create_info,
+                allocator,
+                messenger.as_mut_ptr(),
+            )
+        }
+        .into_result(CreateDebugUtilsMessenger, || unsafe {
+            messenger.assume_init()
+        })
     }
-}
 
-impl Drop for FnTable {
+    /// Destroys `messenger`. Does nothing if `VK_EXT_debug_utils` is not enabled, since no
+    /// messenger can exist then.
     #[inline]
-    fn drop(&mut self) {
-        if let Some(library) = self.library.take() {
-            library.close().unwrap();
+    pub(crate) unsafe fn destroy_debug_utils_messenger(
+        &self,
+        instance: Instance,
+        messenger: DebugUtilsMessenger,
+        allocator: AllocationCallbacksRef,
+    ) {
+        if let Some(fn_destroy_debug_utils_messenger) = self.fn_destroy_debug_utils_messenger {
+            unsafe { (fn_destroy_debug_utils_messenger)(instance, messenger, allocator) }
         }
     }
+
+    /// Lists every physical device visible to `instance`.
+    #[inline]
+    pub(crate) unsafe fn enumerate_physical_devices(
+        &self,
+        instance: Instance,
+    ) -> super::Result<Vec<PhysicalDevice>> {
+        unsafe {
+            enumerate(EnumeratePhysicalDevices, |count, physical_devices| {
+                (self.fn_enumerate_physical_devices)(instance, count, physical_devices)
+            })
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_physical_device_properties(
+        &self,
+        physical_device: PhysicalDevice,
+    ) -> PhysicalDeviceProperties {
+        let mut properties = PhysicalDeviceProperties::zeroized();
+
+        unsafe { (self.fn_get_physical_device_properties)(physical_device, &mut properties) };
+
+        properties
+    }
+
+    /// Lists the queue families exposed by `physical_device`, in family index order.
+    pub(crate) unsafe fn get_physical_device_queue_family_properties(
+        &self,
+        physical_device: PhysicalDevice,
+    ) -> Vec<QueueFamilyProperties> {
+        let mut count = 0;
+
+        unsafe {
+            (self.fn_get_physical_device_queue_family_properties)(
+                physical_device,
+                &mut count,
+                null_mut(),
+            )
+        };
+
+        let mut properties = vec![QueueFamilyProperties::default(); count as usize];
+
+        unsafe {
+            (self.fn_get_physical_device_queue_family_properties)(
+                physical_device,
+                &mut count,
+                properties.as_mut_ptr(),
+            )
+        };
+
+        properties.truncate(count as usize);
+
+        properties
+    }
+
+    /// Lists the device extensions supported by `physical_device`.
+    #[inline]
+    pub(crate) unsafe fn enumerate_device_extension_properties(
+        &self,
+        physical_device: PhysicalDevice,
+    ) -> super::Result<Vec<ExtensionProperties>> {
+        unsafe {
+            enumerate(EnumerateDeviceExtensionProperties, |count, properties| {
+                (self.fn_enumerate_device_extension_properties)(
+                    physical_device,
+                    null(),
+                    count,
+                    properties,
+                )
+            })
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn create_device(
+        &self,
+        physical_device: PhysicalDevice,
+        create_info: &DeviceCreateInfo,
+        allocator: AllocationCallbacksRef,
+    ) -> super::Result<Device> {
+        let mut device = MaybeUninit::<Device>::uninit();
+
+        unsafe {
+            (self.fn_create_device)(physical_device, create_info, allocator, device.as_mut_ptr())
+        }
+        .into_result(CreateDevice, || unsafe { device.assume_init() })
+    }
+
+    /// The `vkGetDeviceProcAddr` of this instance, for building a [`DeviceFnTable`].
+    #[inline(always)]
+    pub(crate) fn get_device_proc_addr(&self) -> PfnGetDeviceProcAddr {
+        self.fn_get_device_proc_addr
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_physical_device_surface_support(
+        &self,
+        physical_device: PhysicalDevice,
+        queue_family_index: u32,
+        surface: Surface,
+    ) -> super::Result<bool> {
+        let Some(fn_get_physical_device_surface_support) =
+            self.fn_get_physical_device_surface_support
+        else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: GetPhysicalDeviceSurfaceSupport,
+            }
+            .into_result();
+        };
+
+        let mut supported = 0;
+
+        unsafe {
+            (fn_get_physical_device_surface_support)(
+                physical_device,
+                queue_family_index,
+                surface,
+                &mut supported,
+            )
+        }
+        .into_result(GetPhysicalDeviceSurfaceSupport, || supported != 0)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_physical_device_surface_capabilities(
+        &self,
+        physical_device: PhysicalDevice,
+        surface: Surface,
+    ) -> super::Result<SurfaceCapabilities> {
+        let Some(fn_get_physical_device_surface_capabilities) =
+            self.fn_get_physical_device_surface_capabilities
+        else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: GetPhysicalDeviceSurfaceCapabilities,
+            }
+            .into_result();
+        };
+
+        let mut capabilities = SurfaceCapabilities::default();
+
+        unsafe {
+            (fn_get_physical_device_surface_capabilities)(
+                physical_device,
+                surface,
+                &mut capabilities,
+            )
+        }
+        .into_result(GetPhysicalDeviceSurfaceCapabilities, || capabilities)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_physical_device_surface_formats(
+        &self,
+        physical_device: PhysicalDevice,
+        surface: Surface,
+    ) -> super::Result<Vec<SurfaceFormat>> {
+        let Some(fn_get_physical_device_surface_formats) =
+            self.fn_get_physical_device_surface_formats
+        else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: GetPhysicalDeviceSurfaceFormats,
+            }
+            .into_result();
+        };
+
+        unsafe {
+            enumerate(GetPhysicalDeviceSurfaceFormats, |count, formats| {
+                (fn_get_physical_device_surface_formats)(physical_device, surface, count, formats)
+            })
+        }
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_physical_device_surface_present_modes(
+        &self,
+        physical_device: PhysicalDevice,
+        surface: Surface,
+    ) -> super::Result<Vec<PresentMode>> {
+        let Some(fn_get_physical_device_surface_present_modes) =
+            self.fn_get_physical_device_surface_present_modes
+        else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: GetPhysicalDeviceSurfacePresentModes,
+            }
+            .into_result();
+        };
+
+        unsafe {
+            enumerate(
+                GetPhysicalDeviceSurfacePresentModes,
+                |count, present_modes| {
+                    (fn_get_physical_device_surface_present_modes)(
+                        physical_device,
+                        surface,
+                        count,
+                        present_modes,
+                    )
+                },
+            )
+        }
+    }
+
+    /// Checks whether `physical_device` supports images of the given format and usage.
+    ///
+    /// Fails with `VK_ERROR_FORMAT_NOT_SUPPORTED` if the combination is unsupported.
+    #[inline]
+    pub(crate) unsafe fn get_physical_device_image_format_properties(
+        &self,
+        physical_device: PhysicalDevice,
+        format: Format,
+        image_type: ImageType,
+        tiling: ImageTiling,
+        usage: ImageUsageFlags,
+        flags: ImageCreateFlags,
+    ) -> super::Result<ImageFormatProperties> {
+        let mut properties = ImageFormatProperties::default();
+
+        unsafe {
+            (self.fn_get_physical_device_image_format_properties)(
+                physical_device,
+                format,
+                image_type,
+                tiling,
+                usage,
+                flags,
+                &mut properties,
+            )
+        }
+        .into_result(GetPhysicalDeviceImageFormatProperties, || properties)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_physical_device_memory_properties(
+        &self,
+        physical_device: PhysicalDevice,
+    ) -> PhysicalDeviceMemoryProperties {
+        let mut properties = PhysicalDeviceMemoryProperties::default();
+
+        unsafe {
+            (self.fn_get_physical_device_memory_properties)(physical_device, &mut properties)
+        };
+
+        properties
+    }
+
+    #[inline]
+    pub(crate) unsafe fn get_physical_device_format_properties(
+        &self,
+        physical_device: PhysicalDevice,
+        format: Format,
+    ) -> FormatProperties {
+        let mut properties = FormatProperties::default();
+
+        unsafe {
+            (self.fn_get_physical_device_format_properties)(
+                physical_device,
+                format,
+                &mut properties,
+            )
+        };
+
+        properties
+    }
+    /// Fills `features` and the structures chained to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `FunctionLoadFailed` if the instance was created for Vulkan 1.0.
+    #[inline]
+    pub(crate) unsafe fn get_physical_device_features2(
+        &self,
+        physical_device: PhysicalDevice,
+        features: &mut PhysicalDeviceFeatures2,
+    ) -> super::Result<()> {
+        let Some(fn_get_physical_device_features2) = self.fn_get_physical_device_features2 else {
+            return VulkanErrorKind::FunctionLoadFailed {
+                name: GetPhysicalDeviceFeatures2,
+            }
+            .into_result();
+        };
+
+        unsafe { (fn_get_physical_device_features2)(physical_device, features) };
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enumerate_retries_incomplete() {
+        let instance = mock::instance();
+        let backend = instance.backend();
+
+        let calls = MockDriver::call_count(EnumeratePhysicalDevices);
+
+        MockDriver::incomplete_next(EnumeratePhysicalDevices);
+
+        let physical_devices =
+            unsafe { backend.fns().enumerate_physical_devices(backend.instance()) }.unwrap();
+
+        assert_eq!(physical_devices.len(), 1);
+        assert_eq!(
+            MockDriver::call_count(EnumeratePhysicalDevices) - calls,
+            4,
+            "Counts and fills again after VK_INCOMPLETE"
+        );
+
+        drop(instance);
+
+        MockDriver::assert_no_leaks();
+    }
 }
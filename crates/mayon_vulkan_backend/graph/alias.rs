@@ -0,0 +1,194 @@
+use core::ops::RangeInclusive;
+
+use crate::types::MemoryRequirements;
+
+/// Memory a transient resource needs, and the levels of the graph it is used in.
+pub(super) struct Request {
+    pub(super) requirements: MemoryRequirements,
+    pub(super) lifetime: RangeInclusive<usize>,
+}
+
+/// A memory allocation shared by transient resources.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Heap {
+    pub(super) size: u64,
+    pub(super) alignment: u64,
+    pub(super) memory_type_bits: u32,
+}
+
+/// Where a transient resource lives: `offset` bytes into heap `heap`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct Placement {
+    pub(super) heap: usize,
+    pub(super) offset: u64,
+}
+
+/// Places `requests` in as little memory as possible, letting resources whose lifetimes do
+/// not overlap share the same bytes.
+///
+/// Resources go into the first heap with a memory type they can all use, largest first, each
+/// at the lowest offset not overlapping a resource alive at the same time.
+pub(super) fn pack(requests: &[Request]) -> (Vec<Heap>, Vec<Placement>) {
+    let mut order = (0..requests.len()).collect::<Vec<_>>();
+
+    order.sort_by_key(|&index| core::cmp::Reverse(requests[index].requirements.size));
+
+    let mut heaps = Vec::<Heap>::new();
+    let mut placements = vec![Placement { heap: 0, offset: 0 }; requests.len()];
+    let mut placed = Vec::<usize>::with_capacity(requests.len());
+
+    for index in order {
+        let request = &requests[index];
+        let requirements = &request.requirements;
+
+        let heap = match heaps
+            .iter()
+            .position(|heap| heap.memory_type_bits & requirements.memory_type_bits != 0)
+        {
+            Some(heap) => heap,
+            None => {
+                heaps.push(Heap {
+                    size: 0,
+                    alignment: 1,
+                    memory_type_bits: requirements.memory_type_bits,
+                });
+
+                heaps.len() - 1
+            }
+        };
+
+        // Every resource overlapping this one in time, sorted by offset.
+        let mut neighbours = placed
+            .iter()
+            .copied()
+            .filter(|&other| {
+                placements[other].heap == heap
+                    && overlaps(&requests[other].lifetime, &request.lifetime)
+            })
+            .map(|other| {
+                let offset = placements[other].offset;
+
+                (offset, offset + requests[other].requirements.size)
+            })
+            .collect::<Vec<_>>();
+
+        neighbours.sort_unstable();
+
+        let mut offset = 0;
+
+        for (start, end) in neighbours {
+            if offset + requirements.size <= start {
+                break;
+            }
+
+            offset = offset.max(end.next_multiple_of(requirements.alignment));
+        }
+
+        let heap_info = &mut heaps[heap];
+
+        heap_info.size = heap_info.size.max(offset + requirements.size);
+        heap_info.alignment = heap_info.alignment.max(requirements.alignment);
+        heap_info.memory_type_bits &= requirements.memory_type_bits;
+
+        placements[index] = Placement { heap, offset };
+        placed.push(index);
+    }
+
+    (heaps, placements)
+}
+
+/// Resources whose memory `requests[index]` reuses: those placed over the same bytes of the
+/// same heap, and last used before it.
+pub(super) fn predecessors(
+    requests: &[Request],
+    placements: &[Placement],
+    index: usize,
+) -> impl Iterator<Item = usize> {
+    let request = &requests[index];
+    let placement = placements[index];
+    let end = placement.offset + request.requirements.size;
+
+    (0..requests.len()).filter(move |&other| {
+        let other_placement = placements[other];
+        let other_end = other_placement.offset + requests[other].requirements.size;
+
+        other != index
+            && other_placement.heap == placement.heap
+            && other_placement.offset < end
+            && placement.offset < other_end
+            && requests[other].lifetime.end() < request.lifetime.start()
+    })
+}
+
+#[inline]
+fn overlaps(a: &RangeInclusive<usize>, b: &RangeInclusive<usize>) -> bool {
+    a.start() <= b.end() && b.start() <= a.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(size: u64, memory_type_bits: u32, lifetime: RangeInclusive<usize>) -> Request {
+        Request {
+            requirements: MemoryRequirements {
+                size,
+                alignment: 256,
+                memory_type_bits,
+            },
+            lifetime,
+        }
+    }
+
+    #[test]
+    fn test_pack() {
+        let requests = [
+            request(1000, 0x3, 0..=1),
+            request(2000, 0x1, 1..=2),
+            request(500, 0x3, 2..=3),
+            request(100, 0x4, 0..=3),
+        ];
+
+        let (heaps, placements) = pack(&requests);
+
+        assert_eq!(
+            heaps,
+            [
+                Heap {
+                    size: 3048,
+                    alignment: 256,
+                    memory_type_bits: 0x1,
+                },
+                Heap {
+                    size: 100,
+                    alignment: 256,
+                    memory_type_bits: 0x4,
+                },
+            ]
+        );
+
+        // The first and third resources are never alive together, and share memory.
+        assert_eq!(placements[1], Placement { heap: 0, offset: 0 });
+        assert_eq!(
+            placements[0],
+            Placement {
+                heap: 0,
+                offset: 2048
+            }
+        );
+        assert_eq!(
+            placements[2],
+            Placement {
+                heap: 0,
+                offset: 2048
+            }
+        );
+        assert_eq!(placements[3], Placement { heap: 1, offset: 0 });
+
+        assert_eq!(
+            predecessors(&requests, &placements, 2).collect::<Vec<_>>(),
+            [0]
+        );
+        assert_eq!(predecessors(&requests, &placements, 0).count(), 0);
+    }
+}
@@ -0,0 +1,1400 @@
+mod alias;
+mod schedule;
+
+use core::{ops::Range, ptr};
+
+use allocator::{AllocationRequest, Allocator, DeviceAllocation, Tiling};
+use mayon_core::{
+    CommandPool, QueueKind, debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+use crate::{
+    Buffer, BufferUsage, DepthTarget, Device, LoadOp, StoreOp, Texture, TextureDesc,
+    TextureDimension, TextureUsage, VulkanCommandBuffer, VulkanErrorKind,
+    device::AttachmentKey,
+    render::Attachment,
+    texture::{LayoutTransition, layout_access},
+    types::{
+        self, AccessFlags, ClearValue, DeviceMemory, Extent2D, ImageAspectFlags, ImageLayout,
+        ImageSubresourceRange, ImageViewCreateInfo, ImageViewType, MemoryBarrier,
+        PipelineStageFlags,
+    },
+};
+
+use alias::Request;
+use schedule::Schedule;
+
+/// A texture of a [`RenderGraph`], either imported or transient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GraphTexture(usize);
+
+/// A depth target imported into a [`RenderGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GraphDepthTarget(usize);
+
+/// A buffer imported into a [`RenderGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GraphBuffer(usize);
+
+/// How a pass uses a texture, other than as an attachment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextureAccess {
+    /// Read by shaders as a sampled image. Needs [`TextureUsage::SAMPLED`].
+    Sampled,
+    /// Read by shaders as a storage image. Needs [`TextureUsage::STORAGE`].
+    StorageRead,
+    /// Read and written by shaders as a storage image. Needs [`TextureUsage::STORAGE`].
+    StorageWrite,
+    /// Read by transfer commands. Needs [`TextureUsage::TRANSFER_SRC`].
+    TransferSrc,
+    /// Overwritten by transfer commands. Needs [`TextureUsage::TRANSFER_DST`].
+    TransferDst,
+}
+
+/// How a pass uses a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BufferAccess {
+    /// Read as a vertex buffer. Needs [`BufferUsage::VERTEX`].
+    Vertex,
+    /// Read as an index buffer. Needs [`BufferUsage::INDEX`].
+    Index,
+    /// Read by shaders as a uniform buffer. Needs [`BufferUsage::UNIFORM`].
+    Uniform,
+    /// Read by shaders as a storage buffer. Needs [`BufferUsage::STORAGE`].
+    StorageRead,
+    /// Read and written by shaders as a storage buffer. Needs [`BufferUsage::STORAGE`].
+    StorageWrite,
+    /// Read as the parameters of indirect commands. Needs [`BufferUsage::INDIRECT`].
+    Indirect,
+    /// Read by transfer commands. Needs [`BufferUsage::TRANSFER_SRC`].
+    TransferSrc,
+    /// Overwritten by transfer commands. Needs [`BufferUsage::TRANSFER_DST`].
+    TransferDst,
+}
+
+impl TextureAccess {
+    /// The layout the access needs, the usage it needs, and whether it reads and writes.
+    const fn split(self) -> (ImageLayout, TextureUsage, bool, bool) {
+        match self {
+            Self::Sampled => (
+                ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                TextureUsage::SAMPLED,
+                true,
+                false,
+            ),
+            Self::StorageRead => (ImageLayout::GENERAL, TextureUsage::STORAGE, true, false),
+            Self::StorageWrite => (ImageLayout::GENERAL, TextureUsage::STORAGE, true, true),
+            Self::TransferSrc => (
+                ImageLayout::TRANSFER_SRC_OPTIMAL,
+                TextureUsage::TRANSFER_SRC,
+                true,
+                false,
+            ),
+            Self::TransferDst => (
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+                TextureUsage::TRANSFER_DST,
+                false,
+                true,
+            ),
+        }
+    }
+}
+
+impl BufferAccess {
+    /// The stages and accesses made, the usage needed, and whether the access writes.
+    ///
+    /// As for textures, shader accesses use all stages.
+    const fn split(self) -> (PipelineStageFlags, AccessFlags, BufferUsage, bool) {
+        match self {
+            Self::Vertex => (
+                PipelineStageFlags::VERTEX_INPUT,
+                AccessFlags::VERTEX_ATTRIBUTE_READ,
+                BufferUsage::VERTEX,
+                false,
+            ),
+            Self::Index => (
+                PipelineStageFlags::VERTEX_INPUT,
+                AccessFlags::INDEX_READ,
+                BufferUsage::INDEX,
+                false,
+            ),
+            Self::Uniform => (
+                PipelineStageFlags::ALL_COMMANDS,
+                AccessFlags::UNIFORM_READ,
+                BufferUsage::UNIFORM,
+                false,
+            ),
+            Self::StorageRead => (
+                PipelineStageFlags::ALL_COMMANDS,
+                AccessFlags::SHADER_READ,
+                BufferUsage::STORAGE,
+                false,
+            ),
+            Self::StorageWrite => (
+                PipelineStageFlags::ALL_COMMANDS,
+                AccessFlags::SHADER_READ.union(AccessFlags::SHADER_WRITE),
+                BufferUsage::STORAGE,
+                true,
+            ),
+            Self::Indirect => (
+                PipelineStageFlags::DRAW_INDIRECT,
+                AccessFlags::INDIRECT_COMMAND_READ,
+                BufferUsage::INDIRECT,
+                false,
+            ),
+            Self::TransferSrc => (
+                PipelineStageFlags::TRANSFER,
+                AccessFlags::TRANSFER_READ,
+                BufferUsage::TRANSFER_SRC,
+                false,
+            ),
+            Self::TransferDst => (
+                PipelineStageFlags::TRANSFER,
+                AccessFlags::TRANSFER_WRITE,
+                BufferUsage::TRANSFER_DST,
+                true,
+            ),
+        }
+    }
+}
+
+enum Resource<'g, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    Texture(&'g Texture<'a, L, A>),
+    /// A texture created when the graph executes, and only used by its passes.
+    Transient(TextureDesc),
+    DepthTarget(&'g DepthTarget<'a, L, A>),
+    Buffer {
+        handle: types::Buffer,
+        usage: BufferUsage,
+    },
+}
+
+/// A use of a resource by a pass.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Access {
+    resource: usize,
+    read: bool,
+    write: bool,
+    state: AccessState,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum AccessState {
+    /// Mip levels `levels` in `layout`.
+    Image {
+        layout: ImageLayout,
+        levels: Range<u32>,
+    },
+    Buffer {
+        stages: PipelineStageFlags,
+        access: AccessFlags,
+    },
+}
+
+impl Access {
+    #[inline]
+    fn layout(&self) -> Option<ImageLayout> {
+        match self.state {
+            AccessState::Image { layout, .. } => Some(layout),
+            AccessState::Buffer { .. } => None,
+        }
+    }
+}
+
+type RecordFn<'g, 'a, L, A> =
+    dyn for<'r, 'p> FnOnce(&mut PassRecorder<'r, 'p, 'a, L, A>) -> crate::Result<()> + 'g;
+
+struct Pass<'g, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    name: &'static str,
+    accesses: Vec<Access>,
+    colors: Vec<(usize, LoadOp<[f32; 4]>, StoreOp)>,
+    depth: Option<(&'g DepthTarget<'a, L, A>, LoadOp<f32>, StoreOp)>,
+    record: Box<RecordFn<'g, 'a, L, A>>,
+}
+
+/// A frame's worth of passes, recorded into a command buffer in one go.
+///
+/// Passes declare the textures and buffers they read and write. When the graph
+/// [executes](Self::execute), it orders the passes by what they depend on, culls the passes
+/// contributing to no imported resource, and records each pass after a single pipeline
+/// barrier moving every resource it uses to the layout and stage it needs. Passes with
+/// attachments are recorded between [`begin_rendering`](VulkanCommandBuffer::begin_rendering)
+/// and [`end_rendering`](VulkanCommandBuffer::end_rendering).
+///
+/// Imported resources outlive the graph, and keep the layouts the graph leaves them in.
+/// Transient textures are created by the graph, and share memory whenever their uses do not
+/// overlap.
+pub struct RenderGraph<'g, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    resources: Vec<Resource<'g, 'a, L, A>>,
+    passes: Vec<Pass<'g, 'a, L, A>>,
+}
+
+impl<'g, 'a, L, A> RenderGraph<'g, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    #[inline]
+    pub fn new(device: &Device<'a, L, A>) -> Self {
+        Self {
+            device: device.clone(),
+            resources: Vec::new(),
+            passes: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn device(&self) -> &Device<'a, L, A> {
+        &self.device
+    }
+
+    /// Number of passes added so far.
+    #[inline]
+    pub fn pass_count(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Makes `texture` usable by the graph's passes. Importing a texture twice returns the
+    /// same handle.
+    pub fn import_texture(&mut self, texture: &'g Texture<'a, L, A>) -> GraphTexture {
+        let existing = self.resources.iter().position(
+            |resource| matches!(resource, Resource::Texture(other) if ptr::eq(*other, texture)),
+        );
+
+        GraphTexture(existing.unwrap_or_else(|| self.push(Resource::Texture(texture))))
+    }
+
+    /// Declares a texture created when the graph executes, and destroyed with the
+    /// [`TransientResources`] it returns. Its contents are undefined until a pass writes them.
+    #[inline]
+    pub fn create_texture(&mut self, desc: &TextureDesc) -> GraphTexture {
+        GraphTexture(self.push(Resource::Transient(*desc)))
+    }
+
+    /// Makes `target` usable as a depth attachment by the graph's passes. Importing a target
+    /// twice returns the same handle.
+    pub fn import_depth_target(&mut self, target: &'g DepthTarget<'a, L, A>) -> GraphDepthTarget {
+        let existing = self.resources.iter().position(
+            |resource| matches!(resource, Resource::DepthTarget(other) if ptr::eq(*other, target)),
+        );
+
+        GraphDepthTarget(existing.unwrap_or_else(|| self.push(Resource::DepthTarget(target))))
+    }
+
+    /// Makes `buffer` usable by the graph's passes. Importing a buffer twice returns the same
+    /// handle.
+    pub fn import_buffer<T: Copy>(&mut self, buffer: &'g Buffer<'a, T, L, A>) -> GraphBuffer {
+        let handle = buffer.handle();
+        let existing = self.resources.iter().position(
+            |resource| matches!(resource, Resource::Buffer { handle: other, .. } if *other == handle),
+        );
+
+        GraphBuffer(existing.unwrap_or_else(|| {
+            self.push(Resource::Buffer {
+                handle,
+                usage: buffer.usage(),
+            })
+        }))
+    }
+
+    /// Starts declaring a pass named `name`, as shown in logs. The pass is added once
+    /// [`PassBuilder::record`] is called.
+    #[inline]
+    pub fn add_pass(&mut self, name: &'static str) -> PassBuilder<'_, 'g, 'a, L, A> {
+        PassBuilder {
+            graph: self,
+            name,
+            accesses: Vec::new(),
+            colors: Vec::new(),
+            depth: None,
+            error: None,
+        }
+    }
+
+    /// Records the graph's passes into `command_buffer`, and returns the transient textures
+    /// they use.
+    ///
+    /// Passes are grouped in levels: a pass goes one level after the last pass it depends
+    /// on, and passes of a level are recorded in the order they were added. Each level starts
+    /// with a single pipeline barrier, holding the layout transitions of the level's textures,
+    /// and a memory barrier for its buffers and for transient textures reusing the memory of
+    /// others. Passes writing no imported resource, and no texture or buffer read by another
+    /// recorded pass, are not recorded at all.
+    ///
+    /// The returned resources must be kept alive until the submissions of `command_buffer`
+    /// complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording,
+    /// `RenderingInProgress` if rendering was begun and not ended, `UnsupportedRenderingQueue`
+    /// if a pass has attachments and the buffer is not for the graphics queue, the error of a
+    /// pass, or a `VulkanError` if creating the transient textures, their memory or the
+    /// attachment views fails. On error, the command buffer may reference destroyed
+    /// resources and must not be submitted; imported textures and depth targets are tracked
+    /// in the layouts they had before.
+    pub fn execute(
+        self,
+        command_buffer: &mut VulkanCommandBuffer<'_, 'a, L, A>,
+    ) -> crate::Result<TransientResources<'a, L, A>> {
+        command_buffer.ensure_recording()?;
+
+        if command_buffer.is_rendering() {
+            return VulkanErrorKind::RenderingInProgress.into_result();
+        }
+
+        let Self {
+            device,
+            resources,
+            passes,
+        } = self;
+
+        // Recording moves imported resources to the layouts the passes need. If it fails, the
+        // command buffer is not submitted, so they are tracked in their previous layouts again.
+        let layouts = resources
+            .iter()
+            .map(|resource| match resource {
+                Resource::Texture(texture) => Some(texture.layouts()),
+                Resource::DepthTarget(target) => Some(vec![target.layout()]),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let result = record(&device, &resources, passes, command_buffer);
+
+        if result.is_err() {
+            for (resource, layouts) in resources.iter().zip(layouts) {
+                match (resource, layouts) {
+                    (Resource::Texture(texture), Some(layouts)) => {
+                        texture.restore_layouts(layouts);
+                    }
+                    (Resource::DepthTarget(target), Some(layouts)) => {
+                        target.restore_layout(layouts[0]);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        result
+    }
+
+    #[inline]
+    fn push(&mut self, resource: Resource<'g, 'a, L, A>) -> usize {
+        self.resources.push(resource);
+        self.resources.len() - 1
+    }
+
+    /// Description of texture `resource`, if it is one.
+    #[inline]
+    fn texture_desc(&self, resource: usize) -> Option<&TextureDesc> {
+        match self.resources.get(resource)? {
+            Resource::Texture(texture) => Some(texture.desc()),
+            Resource::Transient(desc) => Some(desc),
+            _ => None,
+        }
+    }
+}
+
+/// Records the passes of a graph made of `resources` and `passes`, as described in
+/// [`RenderGraph::execute`].
+fn record<'g, 'a, L, A>(
+    device: &Device<'a, L, A>,
+    resources: &[Resource<'g, 'a, L, A>],
+    passes: Vec<Pass<'g, 'a, L, A>>,
+    command_buffer: &mut VulkanCommandBuffer<'_, 'a, L, A>,
+) -> crate::Result<TransientResources<'a, L, A>>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    let accesses = passes
+        .iter()
+        .map(|pass| pass.accesses.as_slice())
+        .collect::<Vec<_>>();
+    let schedule = schedule::schedule(&accesses, resources.len(), |resource| {
+        !matches!(resources[resource], Resource::Transient(_))
+    });
+
+    for &pass in &schedule.culled {
+        debug!(
+            device.instance().logger(),
+            LogTarget::Backend,
+            "Render graph pass culled: {}",
+            passes[pass].name
+        );
+    }
+
+    let renders = |pass: usize| !passes[pass].colors.is_empty() || passes[pass].depth.is_some();
+    let kind = command_buffer.pool().queue();
+
+    if kind != QueueKind::Graphics && schedule.levels.iter().flatten().any(|&pass| renders(pass)) {
+        return VulkanErrorKind::UnsupportedRenderingQueue { kind }.into_result();
+    }
+
+    let (mut transients, predecessors) = TransientResources::new(device, resources, &schedule)?;
+
+    let textures = resources
+        .iter()
+        .zip(&transients.textures)
+        .map(|(resource, transient)| match resource {
+            Resource::Texture(texture) => Some(*texture),
+            _ => transient.as_ref(),
+        })
+        .collect::<Vec<_>>();
+
+    // One view of the first mip level and layer of every texture used as a color
+    // attachment.
+    let mut views = vec![None; resources.len()];
+
+    for &pass in schedule.levels.iter().flatten() {
+        for &(resource, ..) in &passes[pass].colors {
+            if views[resource].is_none() {
+                let texture = textures[resource].expect("Used textures exist");
+
+                let view = create_view(device, texture)?;
+
+                transients.views.push(view);
+                views[resource] = Some(view);
+            }
+        }
+    }
+
+    let mut buffers = vec![BufferState::default(); resources.len()];
+    let mut layouts = vec![None::<ImageLayout>; resources.len()];
+    let mut passes = passes.into_iter().map(Some).collect::<Vec<_>>();
+
+    for level in &schedule.levels {
+        let mut transitions = Vec::new();
+        let mut dependency = MemoryDependency::default();
+
+        for &pass in level {
+            let pass = passes[pass].as_ref().expect("Passes are recorded once");
+
+            for access in &pass.accesses {
+                match (&access.state, &resources[access.resource]) {
+                    (AccessState::Image { layout, .. }, Resource::DepthTarget(target)) => {
+                        transitions.extend(target.update_layout(*layout));
+                    }
+                    (AccessState::Image { layout, levels }, _) => {
+                        let texture = textures[access.resource].expect("Used textures exist");
+
+                        // A transient texture reusing memory must wait for the last
+                        // accesses of the textures that used it before.
+                        if layouts[access.resource].is_none() {
+                            for &predecessor in &predecessors[access.resource] {
+                                if let Some(last) = layouts[predecessor] {
+                                    dependency.add(layout_access(last), layout_access(*layout));
+                                }
+                            }
+                        }
+
+                        layouts[access.resource] = Some(*layout);
+                        transitions.extend(texture.update_layouts(levels.clone(), *layout));
+                    }
+                    (
+                        AccessState::Buffer {
+                            stages,
+                            access: flags,
+                        },
+                        _,
+                    ) => {
+                        buffers[access.resource].access(
+                            &mut dependency,
+                            (*flags, *stages),
+                            access.write,
+                        );
+                    }
+                }
+            }
+        }
+
+        let (src_stage, dst_stage) = LayoutTransition::stages(&transitions);
+        let image_barriers = transitions
+            .iter()
+            .map(LayoutTransition::barrier)
+            .collect::<Vec<_>>();
+        let memory_barriers = dependency.barrier().into_iter().collect::<Vec<_>>();
+
+        command_buffer.pipeline_barrier(
+            (
+                src_stage.union(dependency.src_stages),
+                dst_stage.union(dependency.dst_stages),
+            ),
+            &memory_barriers,
+            &image_barriers,
+        )?;
+
+        for &pass in level {
+            let pass = passes[pass].take().expect("Passes are recorded once");
+            let rendering = !pass.colors.is_empty() || pass.depth.is_some();
+
+            if rendering {
+                begin_rendering(command_buffer, &pass, &textures, &views)?;
+            }
+
+            (pass.record)(&mut PassRecorder {
+                command_buffer: &mut *command_buffer,
+                textures: &textures,
+            })?;
+
+            if rendering {
+                command_buffer.end_rendering()?;
+            }
+        }
+    }
+
+    debug!(
+        device.instance().logger(),
+        LogTarget::Backend,
+        "Render graph recorded: {} passes in {} levels, {} culled, {} bytes of transient memory",
+        schedule.levels.iter().map(Vec::len).sum::<usize>(),
+        schedule.levels.len(),
+        schedule.culled.len(),
+        transients.memory_size()
+    );
+
+    Ok(transients)
+}
+
+/// Begins rendering into the attachments of `pass`, which the graph already moved to their
+/// attachment layouts.
+fn begin_rendering<'a, L, A>(
+    command_buffer: &mut VulkanCommandBuffer<'_, 'a, L, A>,
+    pass: &Pass<'_, 'a, L, A>,
+    textures: &[Option<&Texture<'a, L, A>>],
+    views: &[Option<types::ImageView>],
+) -> crate::Result<()>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    let colors = pass
+        .colors
+        .iter()
+        .map(|&(resource, load, store)| {
+            let texture = textures[resource].expect("Used textures exist");
+            let (load_op, color) = load.split([0.0; 4]);
+
+            Attachment {
+                key: AttachmentKey {
+                    format: texture.format().into(),
+                    load_op,
+                    store_op: store.into(),
+                },
+                view: views[resource].expect("Color attachments have a view"),
+                clear_value: ClearValue::color(color),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let depth = pass.depth.map(|(target, load, store)| {
+        let (load_op, depth) = load.split(1.0);
+
+        Attachment {
+            key: AttachmentKey {
+                format: target.format().into(),
+                load_op,
+                store_op: store.into(),
+            },
+            view: target.view(),
+            clear_value: ClearValue::depth_stencil(depth, 0),
+        }
+    });
+
+    let extent = match (pass.colors.first(), pass.depth) {
+        (Some(&(resource, ..)), _) => {
+            let desc = textures[resource].expect("Used textures exist").desc();
+
+            Extent2D {
+                width: desc.width,
+                height: desc.height,
+            }
+        }
+        (None, Some((target, ..))) => Extent2D {
+            width: target.width(),
+            height: target.height(),
+        },
+        (None, None) => return VulkanErrorKind::EmptyRendering.into_result(),
+    };
+
+    let stencil = pass
+        .depth
+        .is_some_and(|(target, ..)| target.format().has_stencil());
+
+    command_buffer.begin_attachments(extent, &colors, depth.as_ref(), stencil)
+}
+
+/// Creates a view of the first mip level and layer of `texture`.
+fn create_view<L, A>(
+    device: &Device<'_, L, A>,
+    texture: &Texture<'_, L, A>,
+) -> crate::Result<types::ImageView>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    let info = ImageViewCreateInfo::new(
+        texture.handle(),
+        ImageViewType::TYPE_2D,
+        texture.format().into(),
+        ImageSubresourceRange {
+            aspect_mask: ImageAspectFlags::COLOR,
+            base_mip_level: 0,
+            level_count: 1,
+            base_array_layer: 0,
+            layer_count: 1,
+        },
+    );
+
+    unsafe {
+        device.fns.create_image_view(
+            device.handle,
+            &info,
+            device.instance().backend().allocator(),
+        )
+    }
+}
+
+/// Accesses made to a buffer by the passes recorded so far.
+#[derive(Clone, Copy, Default)]
+struct BufferState {
+    /// Accesses and stages of the last write, if any.
+    write: Option<(AccessFlags, PipelineStageFlags)>,
+    /// Accesses and stages the last write was made visible to.
+    visible: (AccessFlags, PipelineStageFlags),
+    /// Stages having read the buffer since the last write.
+    reads: PipelineStageFlags,
+}
+
+impl BufferState {
+    /// Adds what an access of `access` by `stages` must wait for to `dependency`.
+    fn access(
+        &mut self,
+        dependency: &mut MemoryDependency,
+        (access, stages): (AccessFlags, PipelineStageFlags),
+        write: bool,
+    ) {
+        if write {
+            if let Some(last) = self.write {
+                dependency.add(last, (access, stages));
+            }
+
+            // Reads only need to finish before the write starts.
+            if self.reads != PipelineStageFlags::default() {
+                dependency.add(
+                    (AccessFlags::EMPTY, self.reads),
+                    (AccessFlags::EMPTY, stages),
+                );
+            }
+
+            *self = Self {
+                write: Some((access, stages)),
+                ..Self::default()
+            };
+        } else {
+            if let Some(last) = self.write
+                && !(self.visible.0.contains(access) && self.visible.1.contains(stages))
+            {
+                dependency.add(last, (access, stages));
+                self.visible = (self.visible.0.union(access), self.visible.1.union(stages));
+            }
+
+            self.reads = self.reads.union(stages);
+        }
+    }
+}
+
+/// A global memory barrier, accumulated over the accesses of a level.
+#[derive(Default)]
+struct MemoryDependency {
+    src_stages: PipelineStageFlags,
+    dst_stages: PipelineStageFlags,
+    src_access: AccessFlags,
+    dst_access: AccessFlags,
+}
+
+impl MemoryDependency {
+    #[inline]
+    fn add(
+        &mut self,
+        (src_access, src_stages): (AccessFlags, PipelineStageFlags),
+        (dst_access, dst_stages): (AccessFlags, PipelineStageFlags),
+    ) {
+        self.src_stages = self.src_stages.union(src_stages);
+        self.dst_stages = self.dst_stages.union(dst_stages);
+        self.src_access = self.src_access.union(src_access);
+        self.dst_access = self.dst_access.union(dst_access);
+    }
+
+    /// The memory barrier to record, if anything must be waited for. A barrier without
+    /// accesses still orders its stages.
+    #[inline]
+    fn barrier(&self) -> Option<MemoryBarrier> {
+        (self.src_stages != PipelineStageFlags::default())
+            .then(|| MemoryBarrier::new(self.src_access, self.dst_access))
+    }
+}
+
+/// Declares the resources a pass uses, before adding it to its [`RenderGraph`] with
+/// [`record`](Self::record).
+#[must_use]
+pub struct PassBuilder<'r, 'g, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    graph: &'r mut RenderGraph<'g, 'a, L, A>,
+    name: &'static str,
+    accesses: Vec<Access>,
+    colors: Vec<(usize, LoadOp<[f32; 4]>, StoreOp)>,
+    depth: Option<(&'g DepthTarget<'a, L, A>, LoadOp<f32>, StoreOp)>,
+    /// The first problem found with the declared uses.
+    error: Option<VulkanErrorKind>,
+}
+
+impl<'g, 'a, L, A> PassBuilder<'_, 'g, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Declares that the pass uses every mip level of `texture` as `access`.
+    pub fn texture(self, texture: GraphTexture, access: TextureAccess) -> Self {
+        let (layout, usage, read, write) = access.split();
+
+        let Some(desc) = self.graph.texture_desc(texture.0) else {
+            return self.fail(VulkanErrorKind::UnknownGraphResource);
+        };
+
+        if !desc.usage.contains(usage) {
+            return self.fail(VulkanErrorKind::MissingTextureUsage { usage });
+        }
+
+        let levels = 0..desc.mip_levels;
+
+        self.access(Access {
+            resource: texture.0,
+            read,
+            write,
+            state: AccessState::Image { layout, levels },
+        })
+    }
+
+    /// Declares that the pass uses `buffer` as `access`.
+    pub fn buffer(self, buffer: GraphBuffer, access: BufferAccess) -> Self {
+        let (stages, flags, usage, write) = access.split();
+
+        let Some(&Resource::Buffer {
+            usage: buffer_usage,
+            ..
+        }) = self.graph.resources.get(buffer.0)
+        else {
+            return self.fail(VulkanErrorKind::UnknownGraphResource);
+        };
+
+        if !buffer_usage.contains(usage) {
+            return self.fail(VulkanErrorKind::MissingBufferUsage { usage });
+        }
+
+        self.access(Access {
+            resource: buffer.0,
+            read: !write || access == BufferAccess::StorageWrite,
+            write,
+            state: AccessState::Buffer {
+                stages,
+                access: flags,
+            },
+        })
+    }
+
+    /// Renders into the first mip level and layer of `texture`, which needs
+    /// [`TextureUsage::COLOR_ATTACHMENT`], bound to the next fragment shader output.
+    pub fn color_attachment(
+        mut self,
+        texture: GraphTexture,
+        load: LoadOp<[f32; 4]>,
+        store: StoreOp,
+    ) -> Self {
+        let Some(desc) = self.graph.texture_desc(texture.0) else {
+            return self.fail(VulkanErrorKind::UnknownGraphResource);
+        };
+
+        if !desc.usage.contains(TextureUsage::COLOR_ATTACHMENT) {
+            return self.fail(VulkanErrorKind::MissingTextureUsage {
+                usage: TextureUsage::COLOR_ATTACHMENT,
+            });
+        }
+
+        if matches!(desc.dimension, TextureDimension::D3 { .. }) {
+            return self.fail(VulkanErrorKind::InvalidAttachmentView);
+        }
+
+        self.colors.push((texture.0, load, store));
+
+        self.access(Access {
+            resource: texture.0,
+            read: load == LoadOp::Load,
+            write: true,
+            state: AccessState::Image {
+                layout: ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                levels: 0..1,
+            },
+        })
+    }
+
+    /// Renders into `target` as the depth attachment.
+    pub fn depth_attachment(
+        mut self,
+        target: GraphDepthTarget,
+        load: LoadOp<f32>,
+        store: StoreOp,
+    ) -> Self {
+        let Some(&Resource::DepthTarget(depth_target)) = self.graph.resources.get(target.0) else {
+            return self.fail(VulkanErrorKind::UnknownGraphResource);
+        };
+
+        if self.depth.is_some() {
+            return self.fail(VulkanErrorKind::ConflictingGraphAccess);
+        }
+
+        self.depth = Some((depth_target, load, store));
+
+        self.access(Access {
+            resource: target.0,
+            read: load == LoadOp::Load,
+            write: true,
+            state: AccessState::Image {
+                layout: ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+                levels: 0..1,
+            },
+        })
+    }
+
+    /// Adds the pass to the graph, recording its commands with `record` when the graph
+    /// executes, unless it is culled.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnknownGraphResource` if a resource belongs to another graph,
+    /// `MissingTextureUsage` or `MissingBufferUsage` if a resource was not created with the
+    /// usage its access needs, `ConflictingGraphAccess` if the pass uses a resource in two
+    /// ways needing different layouts or stages, or has two depth attachments,
+    /// `InvalidAttachmentView` if a 3D texture is a color attachment, or
+    /// `AttachmentExtentMismatch` if the attachments differ in extent. The graph is left
+    /// untouched on error.
+    pub fn record<F>(self, record: F) -> crate::Result<()>
+    where
+        F: for<'r, 'p> FnOnce(&mut PassRecorder<'r, 'p, 'a, L, A>) -> crate::Result<()> + 'g,
+    {
+        if let Some(error) = self.error {
+            return error.into_result();
+        }
+
+        let mut extents = self
+            .colors
+            .iter()
+            .filter_map(|&(resource, ..)| self.graph.texture_desc(resource))
+            .map(|desc| (desc.width, desc.height))
+            .chain(
+                self.depth
+                    .map(|(target, ..)| (target.width(), target.height())),
+            );
+
+        if let Some(extent) = extents.next()
+            && extents.any(|other| other != extent)
+        {
+            return VulkanErrorKind::AttachmentExtentMismatch.into_result();
+        }
+
+        self.graph.passes.push(Pass {
+            name: self.name,
+            accesses: self.accesses,
+            colors: self.colors,
+            depth: self.depth,
+            record: Box::new(record),
+        });
+
+        Ok(())
+    }
+
+    fn access(mut self, access: Access) -> Self {
+        match self
+            .accesses
+            .iter_mut()
+            .find(|other| other.resource == access.resource)
+        {
+            Some(other) if other.state == access.state => {
+                other.read |= access.read;
+                other.write |= access.write;
+            }
+            Some(_) => return self.fail(VulkanErrorKind::ConflictingGraphAccess),
+            None => self.accesses.push(access),
+        }
+
+        self
+    }
+
+    #[inline]
+    fn fail(mut self, error: VulkanErrorKind) -> Self {
+        self.error.get_or_insert(error);
+        self
+    }
+}
+
+/// What a pass records its commands with.
+pub struct PassRecorder<'r, 'p, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    command_buffer: &'r mut VulkanCommandBuffer<'p, 'a, L, A>,
+    textures: &'r [Option<&'r Texture<'a, L, A>>],
+}
+
+impl<'r, 'p, 'a, L, A> PassRecorder<'r, 'p, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// The command buffer the graph records into. For passes with attachments, rendering has
+    /// begun, and ends once the pass returns.
+    #[inline]
+    pub fn command_buffer(&mut self) -> &mut VulkanCommandBuffer<'p, 'a, L, A> {
+        self.command_buffer
+    }
+
+    /// The texture behind `texture`, including transient ones. Returns `None` for textures
+    /// of another graph, or transient textures no recorded pass uses.
+    #[inline]
+    pub fn texture(&self, texture: GraphTexture) -> Option<&'r Texture<'a, L, A>> {
+        self.textures.get(texture.0).copied().flatten()
+    }
+}
+
+/// What an executed [`RenderGraph`] created: its transient textures, the memory they share,
+/// and the views its attachments were rendered through.
+///
+/// Everything is destroyed on drop, so the resources must outlive the submissions of the
+/// command buffer the graph was recorded into.
+pub struct TransientResources<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    /// Transient texture of each resource of the graph, if it is one and was used.
+    textures: Vec<Option<Texture<'a, L, A>>>,
+    views: Vec<types::ImageView>,
+    memory: Vec<DeviceAllocation<DeviceMemory>>,
+    memory_size: u64,
+}
+
+impl<'a, L, A> TransientResources<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates the transient textures used by `schedule`, binding them to as little memory as
+    /// their lifetimes allow.
+    ///
+    /// Also returns, for each resource, the transient textures that used its memory before.
+    fn new<'g>(
+        device: &Device<'a, L, A>,
+        resources: &[Resource<'g, 'a, L, A>],
+        schedule: &Schedule,
+    ) -> crate::Result<(Self, Vec<Vec<usize>>)> {
+        let mut transients = Self {
+            device: device.clone(),
+            textures: resources.iter().map(|_| None).collect(),
+            views: Vec::new(),
+            memory: Vec::new(),
+            memory_size: 0,
+        };
+
+        let mut used = Vec::new();
+        let mut requests = Vec::new();
+
+        for (index, (resource, lifetime)) in resources.iter().zip(&schedule.lifetimes).enumerate() {
+            if let (Resource::Transient(desc), Some(lifetime)) = (resource, lifetime) {
+                let texture = Texture::new_unbound(device, desc)?;
+
+                requests.push(Request {
+                    requirements: texture.memory_requirements(),
+                    lifetime: lifetime.clone(),
+                });
+                used.push(index);
+                transients.textures[index] = Some(texture);
+            }
+        }
+
+        let (heaps, placements) = alias::pack(&requests);
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            for heap in &heaps {
+                let request =
+                    AllocationRequest::new(heap.size, heap.alignment, heap.memory_type_bits)
+                        .with_tiling(Tiling::Optimal);
+
+                transients
+                    .memory
+                    .push(device.allocate_memory(&request, allocator)?);
+                transients.memory_size += heap.size;
+            }
+
+            for (&resource, placement) in used.iter().zip(&placements) {
+                let memory = &transients.memory[placement.heap];
+                let texture = transients.textures[resource]
+                    .as_ref()
+                    .expect("Used transient textures exist");
+
+                texture.bind_memory(memory.memory(), memory.offset() + placement.offset)?;
+            }
+        }
+
+        let mut predecessors = vec![Vec::new(); resources.len()];
+
+        for (index, &resource) in used.iter().enumerate() {
+            predecessors[resource] = alias::predecessors(&requests, &placements, index)
+                .map(|predecessor| used[predecessor])
+                .collect();
+        }
+
+        Ok((transients, predecessors))
+    }
+
+    /// Bytes of device memory the transient textures share.
+    #[inline]
+    pub fn memory_size(&self) -> u64 {
+        self.memory_size
+    }
+}
+
+impl<L, A> Drop for TransientResources<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        let device = &self.device;
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            for view in self.views.drain(..) {
+                device.forget_image_view(view, allocator);
+                device
+                    .fns
+                    .destroy_image_view(device.handle, view, allocator);
+            }
+
+            self.textures.clear();
+
+            for memory in &self.memory {
+                device.free_memory(memory, allocator);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::RefCell;
+
+    use allocator::MemoryUsage;
+    use mayon_core::{BaseError, CommandBuffer, CommandBufferUsage, Format};
+
+    use super::*;
+    use crate::{
+        VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+    };
+
+    fn color_desc() -> TextureDesc {
+        TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 64, 32)
+            .with_usage(TextureUsage::COLOR_ATTACHMENT | TextureUsage::SAMPLED)
+    }
+
+    #[test]
+    fn test_execute_graph() {
+        let device = mock::device();
+        let pool = device.create_command_pool(QueueKind::Graphics).unwrap();
+
+        let output = Texture::new(&device, &color_desc()).unwrap();
+        let history = Texture::new(&device, &color_desc()).unwrap();
+        let lights = Buffer::<[f32; 4], _, _>::new(
+            &device,
+            16,
+            BufferUsage::STORAGE | BufferUsage::TRANSFER_DST,
+            MemoryUsage::GpuOnly,
+        )
+        .unwrap();
+        let recorded = RefCell::new(Vec::new());
+
+        let mut graph = RenderGraph::new(&device);
+
+        let output_handle = graph.import_texture(&output);
+        let history_handle = graph.import_texture(&history);
+        let lights_handle = graph.import_buffer(&lights);
+        let gbuffer = graph.create_texture(&color_desc());
+        let unused = graph.create_texture(&color_desc());
+        let lit = graph.create_texture(&color_desc());
+        let bloom = graph.create_texture(&color_desc());
+
+        assert_eq!(graph.import_texture(&output), output_handle);
+
+        let record = |name| {
+            let recorded = &recorded;
+
+            move |_: &mut PassRecorder<'_, '_, '_, _, _>| {
+                recorded.borrow_mut().push(name);
+                Ok(())
+            }
+        };
+
+        graph
+            .add_pass("upload")
+            .buffer(lights_handle, BufferAccess::TransferDst)
+            .record(record("upload"))
+            .unwrap();
+        graph
+            .add_pass("gbuffer")
+            .color_attachment(gbuffer, LoadOp::Clear([0.0; 4]), StoreOp::Store)
+            .record(record("gbuffer"))
+            .unwrap();
+        graph
+            .add_pass("unused")
+            .color_attachment(unused, LoadOp::Clear([0.0; 4]), StoreOp::Store)
+            .record(record("unused"))
+            .unwrap();
+        graph
+            .add_pass("lighting")
+            .texture(gbuffer, TextureAccess::Sampled)
+            .buffer(lights_handle, BufferAccess::StorageRead)
+            .color_attachment(lit, LoadOp::DontCare, StoreOp::Store)
+            .record(record("lighting"))
+            .unwrap();
+        graph
+            .add_pass("bloom")
+            .texture(lit, TextureAccess::Sampled)
+            .color_attachment(bloom, LoadOp::DontCare, StoreOp::Store)
+            .record(record("bloom"))
+            .unwrap();
+        graph
+            .add_pass("composite")
+            .texture(bloom, TextureAccess::Sampled)
+            .color_attachment(output_handle, LoadOp::Load, StoreOp::Store)
+            .record(|pass| {
+                assert!(pass.texture(bloom).is_some());
+                assert!(pass.texture(unused).is_none());
+
+                recorded.borrow_mut().push("composite");
+                Ok(())
+            })
+            .unwrap();
+        // Depends on nothing else, so it is recorded with the first passes.
+        graph
+            .add_pass("history")
+            .color_attachment(history_handle, LoadOp::Load, StoreOp::Store)
+            .record(record("history"))
+            .unwrap();
+
+        assert_eq!(graph.pass_count(), 7);
+
+        let mut command_buffer = pool.allocate().unwrap();
+
+        command_buffer
+            .begin(CommandBufferUsage::OneTimeSubmit)
+            .unwrap();
+
+        let transients = graph.execute(&mut command_buffer).unwrap();
+
+        command_buffer.end().unwrap();
+        pool.submit(&[&command_buffer]).unwrap();
+
+        assert_eq!(
+            *recorded.borrow(),
+            [
+                "upload",
+                "gbuffer",
+                "history",
+                "lighting",
+                "bloom",
+                "composite"
+            ]
+        );
+
+        // One barrier per level, and the G-buffer and bloom textures share memory.
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdPipelineBarrier),
+            4
+        );
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdBeginRendering),
+            5
+        );
+        assert_eq!(transients.memory_size(), 2 << 16);
+        assert_eq!(output.layout(0), ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+
+        MockDriver::assert_valid_layouts();
+
+        drop(command_buffer);
+        drop(transients);
+        drop((output, history, lights, pool, device));
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_invalid_passes() {
+        let device = mock::device();
+
+        let sampled = Texture::new(
+            &device,
+            &TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 16, 16),
+        )
+        .unwrap();
+
+        let output = Texture::new(&device, &color_desc()).unwrap();
+
+        let mut other = RenderGraph::new(&device);
+        let mut graph = RenderGraph::new(&device);
+
+        for _ in 0..4 {
+            other.create_texture(&color_desc());
+        }
+
+        let foreign = other.create_texture(&color_desc());
+        let sampled_handle = graph.import_texture(&sampled);
+        let color = graph.create_texture(&color_desc());
+
+        let errors = [
+            graph
+                .add_pass("foreign")
+                .texture(foreign, TextureAccess::Sampled)
+                .record(|_| Ok(())),
+            graph
+                .add_pass("usage")
+                .color_attachment(sampled_handle, LoadOp::Load, StoreOp::Store)
+                .record(|_| Ok(())),
+            graph
+                .add_pass("feedback")
+                .texture(color, TextureAccess::Sampled)
+                .color_attachment(color, LoadOp::Load, StoreOp::Store)
+                .record(|_| Ok(())),
+        ];
+
+        assert!(matches!(
+            errors[0].as_ref().unwrap_err().kind(),
+            VulkanErrorKind::UnknownGraphResource
+        ));
+        assert!(matches!(
+            errors[1].as_ref().unwrap_err().kind(),
+            VulkanErrorKind::MissingTextureUsage {
+                usage: TextureUsage::COLOR_ATTACHMENT
+            }
+        ));
+        assert!(matches!(
+            errors[2].as_ref().unwrap_err().kind(),
+            VulkanErrorKind::ConflictingGraphAccess
+        ));
+        assert_eq!(graph.pass_count(), 0);
+
+        let small = graph.create_texture(&TextureDesc {
+            width: 8,
+            ..color_desc()
+        });
+
+        let Err(err) = graph
+            .add_pass("mismatch")
+            .color_attachment(color, LoadOp::Load, StoreOp::Store)
+            .color_attachment(small, LoadOp::Load, StoreOp::Store)
+            .record(|_| Ok(()))
+        else {
+            panic!("Attachments of different extents should be rejected");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            VulkanErrorKind::AttachmentExtentMismatch
+        ));
+
+        // Rendering needs the graphics queue.
+        let output_handle = graph.import_texture(&output);
+
+        graph
+            .add_pass("render")
+            .color_attachment(output_handle, LoadOp::Clear([0.0; 4]), StoreOp::Store)
+            .record(|_| Ok(()))
+            .unwrap();
+
+        let transfer = device.create_command_pool(QueueKind::Transfer).unwrap();
+        let mut command_buffer = transfer.allocate().unwrap();
+
+        command_buffer.begin(CommandBufferUsage::default()).unwrap();
+
+        let Err(err) = graph.execute(&mut command_buffer) else {
+            panic!("Rendering on the transfer queue should fail");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            VulkanErrorKind::UnsupportedRenderingQueue {
+                kind: QueueKind::Transfer
+            }
+        ));
+
+        drop(command_buffer);
+
+        // A failing pass leaves imported textures in the layouts they had before.
+        let target = Texture::new(&device, &color_desc()).unwrap();
+        let mut graph = RenderGraph::new(&device);
+        let output_handle = graph.import_texture(&output);
+        let target_handle = graph.import_texture(&target);
+
+        graph
+            .add_pass("render")
+            .color_attachment(output_handle, LoadOp::Clear([0.0; 4]), StoreOp::Store)
+            .record(|_| Ok(()))
+            .unwrap();
+        graph
+            .add_pass("failing")
+            .texture(output_handle, TextureAccess::Sampled)
+            .color_attachment(target_handle, LoadOp::DontCare, StoreOp::Store)
+            .record(|_| VulkanErrorKind::EmptyRendering.into_result())
+            .unwrap();
+
+        let graphics = device.create_command_pool(QueueKind::Graphics).unwrap();
+        let mut command_buffer = graphics.allocate().unwrap();
+
+        command_buffer.begin(CommandBufferUsage::default()).unwrap();
+
+        let Err(err) = graph.execute(&mut command_buffer) else {
+            panic!("The error of a pass should be returned");
+        };
+
+        assert!(matches!(err.kind(), VulkanErrorKind::EmptyRendering));
+        assert_eq!(output.layout(0), ImageLayout::UNDEFINED);
+        assert_eq!(target.layout(0), ImageLayout::UNDEFINED);
+
+        drop(command_buffer);
+        drop((graphics, transfer, other, target, output, sampled, device));
+
+        MockDriver::assert_no_leaks();
+    }
+}
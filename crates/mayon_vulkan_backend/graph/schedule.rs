@@ -0,0 +1,202 @@
+use core::ops::RangeInclusive;
+
+use super::Access;
+use crate::types::ImageLayout;
+
+/// The order a graph's passes execute in.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Schedule {
+    /// Passes to execute, grouped in levels executed one after the other. Passes of a level do
+    /// not depend on each other, so a single barrier before each level synchronizes it with
+    /// the previous ones.
+    pub(super) levels: Vec<Vec<usize>>,
+    /// Passes contributing to no imported resource, which are not executed.
+    pub(super) culled: Vec<usize>,
+    /// First and last level using each resource, or `None` if no executed pass uses it.
+    pub(super) lifetimes: Vec<Option<RangeInclusive<usize>>>,
+}
+
+/// Uses of a resource since it was last written.
+#[derive(Default)]
+struct History {
+    writer: Option<usize>,
+    readers: Vec<usize>,
+    /// Layout the readers use the resource in, for images.
+    layout: Option<ImageLayout>,
+}
+
+/// Orders the passes accessing `resource_count` resources with `accesses`, given in
+/// declaration order. Resources for which `imported` returns `true` outlive the graph.
+///
+/// A pass depends on the passes it reads the output of, and is ordered after the passes it
+/// would otherwise overwrite the input or output of. Reads in different image layouts are
+/// ordered too, as each needs the image in its own layout. Passes whose writes are never
+/// read, and which write no imported resource, are culled.
+pub(super) fn schedule(
+    accesses: &[&[Access]],
+    resource_count: usize,
+    imported: impl Fn(usize) -> bool,
+) -> Schedule {
+    let mut histories = (0..resource_count)
+        .map(|_| History::default())
+        .collect::<Vec<_>>();
+    // Passes each pass must follow, and the subset of them it reads the output of.
+    let mut dependencies = vec![Vec::new(); accesses.len()];
+    let mut inputs = vec![Vec::new(); accesses.len()];
+
+    for (pass, pass_accesses) in accesses.iter().enumerate() {
+        for access in *pass_accesses {
+            let history = &mut histories[access.resource];
+
+            if access.read
+                && let Some(writer) = history.writer
+            {
+                inputs[pass].push(writer);
+            }
+
+            if access.write {
+                dependencies[pass].extend(history.writer);
+                dependencies[pass].append(&mut history.readers);
+                history.writer = Some(pass);
+                history.layout = None;
+            } else {
+                let layout = access.layout();
+
+                if history.layout != layout {
+                    dependencies[pass].append(&mut history.readers);
+                    history.layout = layout;
+                }
+
+                dependencies[pass].extend(history.writer);
+                history.readers.push(pass);
+            }
+        }
+    }
+
+    let mut live = accesses
+        .iter()
+        .map(|pass_accesses| {
+            pass_accesses
+                .iter()
+                .any(|access| access.write && imported(access.resource))
+        })
+        .collect::<Vec<_>>();
+
+    // Inputs always come earlier, so one backward sweep reaches every contributing pass.
+    for pass in (0..accesses.len()).rev() {
+        if live[pass] {
+            for &input in &inputs[pass] {
+                live[input] = true;
+            }
+        }
+    }
+
+    // Culled passes still take part in ordering, so the passes they separated stay ordered.
+    let mut pass_levels = vec![0; accesses.len()];
+
+    for pass in 0..accesses.len() {
+        pass_levels[pass] = dependencies[pass]
+            .iter()
+            .map(|&dependency| pass_levels[dependency] + 1)
+            .max()
+            .unwrap_or(0);
+    }
+
+    let mut levels = Vec::<Vec<usize>>::new();
+    let mut culled = Vec::new();
+
+    for pass in 0..accesses.len() {
+        if !live[pass] {
+            culled.push(pass);
+            continue;
+        }
+
+        let level = pass_levels[pass];
+
+        if levels.len() <= level {
+            levels.resize_with(level + 1, Vec::new);
+        }
+
+        levels[level].push(pass);
+    }
+
+    levels.retain(|level| !level.is_empty());
+
+    let mut lifetimes = vec![None::<RangeInclusive<usize>>; resource_count];
+
+    for (level, passes) in levels.iter().enumerate() {
+        for &pass in passes {
+            for access in accesses[pass] {
+                let lifetime = &mut lifetimes[access.resource];
+
+                *lifetime = Some(match lifetime.take() {
+                    Some(lifetime) => *lifetime.start()..=level,
+                    None => level..=level,
+                });
+            }
+        }
+    }
+
+    Schedule {
+        levels,
+        culled,
+        lifetimes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::AccessState;
+
+    fn image(resource: usize, layout: ImageLayout, read: bool, write: bool) -> Access {
+        Access {
+            resource,
+            read,
+            write,
+            state: AccessState::Image {
+                layout,
+                levels: 0..1,
+            },
+        }
+    }
+
+    #[test]
+    fn test_schedule() {
+        const TRANSIENT: usize = 0;
+        const UNUSED: usize = 1;
+        const OUTPUT: usize = 2;
+        const HISTORY: usize = 3;
+
+        let attachment = ImageLayout::COLOR_ATTACHMENT_OPTIMAL;
+        let sampled = ImageLayout::SHADER_READ_ONLY_OPTIMAL;
+
+        let passes = [
+            // 0: renders the transient texture.
+            vec![image(TRANSIENT, attachment, false, true)],
+            // 1: renders a texture nothing reads.
+            vec![image(UNUSED, attachment, false, true)],
+            // 2: composites the transient texture into the output.
+            vec![
+                image(TRANSIENT, sampled, true, false),
+                image(OUTPUT, attachment, false, true),
+            ],
+            // 3: renders the history texture, independently of the others.
+            vec![image(HISTORY, attachment, true, true)],
+            // 4: samples the output, which only needs ordering after pass 2.
+            vec![image(OUTPUT, sampled, true, false)],
+        ];
+        let accesses = passes.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+        let schedule = schedule(&accesses, 4, |resource| resource >= OUTPUT);
+
+        assert_eq!(
+            schedule,
+            Schedule {
+                levels: vec![vec![0, 3], vec![2]],
+                culled: vec![1, 4],
+                lifetimes: vec![Some(0..=1), None, Some(1..=1), Some(0..=0)],
+            }
+        );
+    }
+}
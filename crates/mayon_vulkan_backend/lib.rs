@@ -1,17 +1,64 @@
+mod adapter;
 mod backend;
+mod buffer;
+mod capabilities;
+mod command;
 mod context;
+mod descriptor;
+mod device;
 mod errors;
 mod fn_table;
+mod graph;
+mod pipeline;
+mod render;
+mod semaphore;
+mod shader;
+mod texture;
 mod types;
 
 pub use errors::{Result, VulkanError, VulkanErrorKind};
-pub use fn_table::VulkanFunctionName;
+#[cfg(feature = "mock_driver")]
+pub use fn_table::{MockDriver, MockHandle, MockHandleKind};
+pub use fn_table::{PfnGetInstanceProcAddr, VulkanFunctionName, VulkanLoader};
 
+pub use adapter::VulkanAdapter;
 pub use backend::{VulkanBackend, VulkanBackendParams, VulkanVersion};
-pub use context::VulkanContext;
+pub use buffer::{Buffer, BufferUsage, StagingRing};
+pub use capabilities::{VulkanCapabilities, VulkanExtension, VulkanLayer};
+pub use command::{VulkanCommandBuffer, VulkanCommandPool};
+pub use context::{VulkanContext, VulkanFrame};
+pub use descriptor::{
+    BindlessDesc, BindlessIndex, BindlessKind, BindlessTable, DescriptorAllocator, DescriptorSet,
+    DescriptorWriter,
+};
+pub use device::VulkanDevice;
+pub use graph::{
+    BufferAccess, GraphBuffer, GraphDepthTarget, GraphTexture, PassBuilder, PassRecorder,
+    RenderGraph, TextureAccess, TransientResources,
+};
+pub use pipeline::{
+    BlendComponent, BlendFactor, BlendOp, BlendState, CompareOp, ComputePipeline, CullMode,
+    DepthFormat, DepthState, DispatchIndirectCommand, DynamicStates, FrontFace, GraphicsPipeline,
+    GraphicsPipelineBuilder, PipelineCache, PolygonMode, PrimitiveTopology, RasterState,
+    VertexAttribute, VertexBufferLayout, VertexStepMode,
+};
+pub use render::{ColorAttachment, DepthAttachment, DepthTarget, LoadOp, RenderingDesc, StoreOp};
+pub use semaphore::TimelineSemaphore;
+pub use shader::{
+    DescriptorBinding, DescriptorKind, EntryPoint, PipelineLayout, PushConstantRange, ShaderModule,
+    ShaderReflection, ShaderStage, ShaderStages, VertexFormat, VertexInput,
+};
+pub use texture::{
+    AddressMode, FilterMode, MipmapMode, Sampler, SamplerCache, SamplerDesc, Texture, TextureDesc,
+    TextureDimension, TextureUsage, TextureView,
+};
 pub use types::ReturnCode;
 
+pub use allocator::{HeapStatistics, MemoryUsage};
+
 pub type Instance<'a, L = mayon_core::logger::DefaultLogger, A = allocator::System> =
     mayon_core::Instance<VulkanBackend<'a, L, A>, L, A>;
 pub type Context<'a, L = mayon_core::logger::DefaultLogger, A = allocator::System> =
     mayon_core::Context<VulkanBackend<'a, L, A>, L, A>;
+pub type Device<'a, L = mayon_core::logger::DefaultLogger, A = allocator::System> =
+    mayon_core::Device<VulkanBackend<'a, L, A>, L, A>;
@@ -0,0 +1,481 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    fs,
+    hash::Hash,
+    io,
+    path::Path,
+    sync::{Mutex, PoisonError},
+};
+
+use allocator::Allocator;
+use mayon_core::{
+    debug, info,
+    logger::{Logger, Target as LogTarget},
+    warn,
+};
+
+use super::{compute::ComputePipelineDesc, graphics::GraphicsPipelineDesc};
+use crate::{
+    Device, VulkanErrorKind,
+    types::{self, PhysicalDeviceProperties, PipelineCacheCreateInfo, PipelineCacheHeader},
+};
+
+/// Why data saved by [`PipelineCache::save`] cannot seed a cache of this device.
+fn header_mismatch(data: &[u8], properties: &PhysicalDeviceProperties) -> Option<&'static str> {
+    let Some(header) = PipelineCacheHeader::read(data) else {
+        return Some("truncated header");
+    };
+
+    if header.header_size as usize != PipelineCacheHeader::SIZE
+        || header.header_version != PipelineCacheHeader::VERSION_ONE
+    {
+        Some("unknown header version")
+    } else if header.vendor_id != properties.vendor_id {
+        Some("vendor mismatch")
+    } else if header.device_id != properties.device_id {
+        Some("device mismatch")
+    } else if header.pipeline_cache_uuid != properties.pipeline_cache_uuid {
+        Some("driver mismatch")
+    } else {
+        None
+    }
+}
+
+/// Compiled pipelines, deduplicated by state and persisted across runs.
+///
+/// Building a pipeline with the same shaders and state as an existing one returns that
+/// pipeline instead of compiling another. Compilation results are also kept in a
+/// `VkPipelineCache`, which [`save`](Self::save) writes to a file that [`load`](Self::load)
+/// seeds the next run's cache with, sparing the driver most of the compilation at startup.
+///
+/// Pipelines live until the cache is dropped.
+pub struct PipelineCache<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    handle: types::PipelineCache,
+    graphics: Mutex<HashMap<GraphicsPipelineDesc, types::Pipeline>>,
+    compute: Mutex<HashMap<ComputePipelineDesc, types::Pipeline>>,
+    loaded: bool,
+}
+
+impl<'a, L, A> PipelineCache<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates an empty cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkCreatePipelineCache` fails.
+    #[inline]
+    pub fn new(device: &Device<'a, L, A>) -> crate::Result<Self> {
+        Self::with_data(device, &[])
+    }
+
+    /// Creates a cache seeded with the file at `path`, written by [`save`](Self::save).
+    ///
+    /// A missing file, or one written for another device or driver version, leaves the
+    /// cache empty: pipelines are then compiled from scratch and the file should be saved
+    /// again.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PipelineCacheIo` if the file exists but cannot be read, or a `VulkanError`
+    /// if `vkCreatePipelineCache` fails.
+    pub fn load(device: &Device<'a, L, A>, path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let logger = device.instance().logger();
+
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                info!(
+                    logger,
+                    LogTarget::Backend,
+                    "No pipeline cache at {}, starting empty",
+                    path.display()
+                );
+
+                return Self::new(device);
+            }
+            Err(err) => {
+                return VulkanErrorKind::PipelineCacheIo { kind: err.kind() }.into_result();
+            }
+        };
+
+        let properties = unsafe {
+            device
+                .instance()
+                .backend()
+                .fns()
+                .get_physical_device_properties(device.physical_device)
+        };
+
+        if let Some(reason) = header_mismatch(&data, &properties) {
+            warn!(
+                logger,
+                LogTarget::Backend,
+                "Discarding pipeline cache {} ({reason})",
+                path.display()
+            );
+
+            return Self::new(device);
+        }
+
+        let mut cache = Self::with_data(device, &data)?;
+
+        cache.loaded = true;
+
+        info!(
+            logger,
+            LogTarget::Backend,
+            "Pipeline cache loaded from {} ({} bytes)",
+            path.display(),
+            data.len()
+        );
+
+        Ok(cache)
+    }
+
+    fn with_data(device: &Device<'a, L, A>, data: &[u8]) -> crate::Result<Self> {
+        let handle = unsafe {
+            device.fns.create_pipeline_cache(
+                device.handle,
+                &PipelineCacheCreateInfo::new(data),
+                device.instance().backend().allocator(),
+            )
+        }?;
+
+        Ok(Self {
+            device: device.clone(),
+            handle,
+            graphics: Mutex::new(HashMap::new()),
+            compute: Mutex::new(HashMap::new()),
+            loaded: false,
+        })
+    }
+
+    /// Whether the cache was seeded from a file by [`load`](Self::load).
+    #[inline]
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// The compilation results gathered so far, in the driver's format.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkGetPipelineCacheData` fails.
+    #[inline]
+    pub fn data(&self) -> crate::Result<Vec<u8>> {
+        unsafe {
+            self.device
+                .fns
+                .get_pipeline_cache_data(self.device.handle, self.handle)
+        }
+    }
+
+    /// Writes the compilation results gathered so far to `path`, for [`load`](Self::load)
+    /// to seed the cache of a later run with.
+    ///
+    /// The file is replaced atomically, so a crash while saving leaves the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkGetPipelineCacheData` fails, or `PipelineCacheIo` if
+    /// the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> crate::Result<()> {
+        let path = path.as_ref();
+        let data = self.data()?;
+
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+
+        fs::write(&temporary, &data)
+            .and_then(|()| fs::rename(&temporary, path))
+            .or_else(|err| {
+                let _ = fs::remove_file(&temporary);
+
+                VulkanErrorKind::PipelineCacheIo { kind: err.kind() }.into_result()
+            })?;
+
+        info!(
+            self.device.instance().logger(),
+            LogTarget::Backend,
+            "Pipeline cache saved to {} ({} bytes)",
+            path.display(),
+            data.len()
+        );
+
+        Ok(())
+    }
+
+    /// Number of distinct pipelines built so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.graphics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+            + self
+                .compute
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the graphics pipeline built from `desc`, calling `create` to build it on
+    /// first use.
+    #[inline]
+    pub(super) fn graphics_pipeline(
+        &self,
+        desc: GraphicsPipelineDesc,
+        create: impl FnOnce(types::PipelineCache) -> crate::Result<types::Pipeline>,
+    ) -> crate::Result<types::Pipeline> {
+        self.pipeline(&self.graphics, "graphics", desc, create)
+    }
+
+    /// Returns the compute pipeline built from `desc`, calling `create` to build it on first
+    /// use.
+    #[inline]
+    pub(super) fn compute_pipeline(
+        &self,
+        desc: ComputePipelineDesc,
+        create: impl FnOnce(types::PipelineCache) -> crate::Result<types::Pipeline>,
+    ) -> crate::Result<types::Pipeline> {
+        self.pipeline(&self.compute, "compute", desc, create)
+    }
+
+    fn pipeline<K: Hash + Eq>(
+        &self,
+        pipelines: &Mutex<HashMap<K, types::Pipeline>>,
+        kind: &str,
+        desc: K,
+        create: impl FnOnce(types::PipelineCache) -> crate::Result<types::Pipeline>,
+    ) -> crate::Result<types::Pipeline> {
+        if let Some(&pipeline) = pipelines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&desc)
+        {
+            return Ok(pipeline);
+        }
+
+        // Compiling outside the lock lets threads build distinct pipelines concurrently.
+        let pipeline = create(self.handle)?;
+        let mut pipelines = pipelines.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match pipelines.entry(desc) {
+            // Another thread built the same pipeline in the meantime.
+            Entry::Occupied(entry) => {
+                unsafe {
+                    self.device.fns.destroy_pipeline(
+                        self.device.handle,
+                        pipeline,
+                        self.device.instance().backend().allocator(),
+                    )
+                };
+
+                Ok(*entry.get())
+            }
+            Entry::Vacant(entry) => {
+                debug!(
+                    self.device.instance().logger(),
+                    LogTarget::Backend,
+                    "Vulkan {kind} pipeline created: {:?}",
+                    pipeline
+                );
+
+                Ok(*entry.insert(pipeline))
+            }
+        }
+    }
+}
+
+impl<L, A> Drop for PipelineCache<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        let device = &self.device;
+        let graphics = self
+            .graphics
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        let compute = self
+            .compute
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            for pipeline in graphics
+                .drain()
+                .map(|(_, pipeline)| pipeline)
+                .chain(compute.drain().map(|(_, pipeline)| pipeline))
+            {
+                device
+                    .fns
+                    .destroy_pipeline(device.handle, pipeline, allocator);
+            }
+
+            device
+                .fns
+                .destroy_pipeline_cache(device.handle, self.handle, allocator);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::PathBuf, process};
+
+    use super::*;
+    use crate::{
+        DepthFormat, DepthState, GraphicsPipelineBuilder, PipelineLayout, ShaderModule,
+        VertexBufferLayout, VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+        shader::test_shaders,
+    };
+
+    /// A path in the temporary directory, unique to the test and the process.
+    fn cache_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("mayon-{name}-{}.bin", process::id()))
+    }
+
+    #[test]
+    fn test_header_mismatch() {
+        let mut properties = PhysicalDeviceProperties::zeroized();
+
+        properties.vendor_id = 0x10de;
+        properties.device_id = 0x2684;
+        properties.pipeline_cache_uuid = [7; 16];
+
+        let header = PipelineCacheHeader {
+            header_size: PipelineCacheHeader::SIZE as u32,
+            header_version: PipelineCacheHeader::VERSION_ONE,
+            vendor_id: 0x10de,
+            device_id: 0x2684,
+            pipeline_cache_uuid: [7; 16],
+        };
+        let data = [&header.to_bytes()[..], &[1, 2, 3]].concat();
+
+        assert_eq!(header_mismatch(&data, &properties), None);
+        assert_eq!(
+            header_mismatch(&data[..31], &properties),
+            Some("truncated header")
+        );
+
+        let mismatches = [
+            (
+                PipelineCacheHeader {
+                    header_version: 2,
+                    ..header
+                },
+                "unknown header version",
+            ),
+            (
+                PipelineCacheHeader {
+                    vendor_id: 0x1002,
+                    ..header
+                },
+                "vendor mismatch",
+            ),
+            (
+                PipelineCacheHeader {
+                    device_id: 0x2685,
+                    ..header
+                },
+                "device mismatch",
+            ),
+            (
+                PipelineCacheHeader {
+                    pipeline_cache_uuid: [8; 16],
+                    ..header
+                },
+                "driver mismatch",
+            ),
+        ];
+
+        for (header, reason) in mismatches {
+            assert_eq!(
+                header_mismatch(&header.to_bytes(), &properties),
+                Some(reason)
+            );
+        }
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let device = mock::device();
+
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&vertex]).unwrap();
+        let builder = GraphicsPipelineBuilder::new(&layout)
+            .vertex_shader(&vertex, "main")
+            .vertex_buffer(VertexBufferLayout::packed(
+                vertex.reflection().vertex_inputs(),
+            ))
+            .depth_target(DepthFormat::D32Float, DepthState::default());
+
+        let path = cache_path("pipeline-cache");
+        let _ = fs::remove_file(&path);
+
+        let cold = PipelineCache::load(&device, &path).unwrap();
+
+        assert!(!cold.is_loaded());
+
+        builder.build(&cold).unwrap();
+        cold.save(&path).unwrap();
+
+        let saved = cold.data().unwrap();
+
+        assert!(saved.len() > PipelineCacheHeader::SIZE);
+        assert_eq!(fs::read(&path).unwrap(), saved);
+
+        drop(cold);
+
+        let warm = PipelineCache::load(&device, &path).unwrap();
+
+        assert!(warm.is_loaded());
+        assert!(warm.is_empty());
+        assert_eq!(warm.data().unwrap(), saved);
+
+        drop(warm);
+
+        // A cache written by another driver is discarded rather than handed to this one.
+        let mut stale = saved.clone();
+        stale[16] ^= 0xff;
+        fs::write(&path, &stale).unwrap();
+
+        let discarded = PipelineCache::load(&device, &path).unwrap();
+
+        assert!(!discarded.is_loaded());
+        assert_eq!(discarded.data().unwrap().len(), PipelineCacheHeader::SIZE);
+
+        drop(discarded);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreatePipelineCache),
+            3
+        );
+
+        drop(layout);
+        drop(vertex);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
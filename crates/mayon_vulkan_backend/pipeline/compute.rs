@@ -0,0 +1,590 @@
+use core::marker::PhantomData;
+use std::{ffi::CString, sync::Arc};
+
+use allocator::Allocator;
+use mayon_core::{CommandPool, QueueKind, logger::Logger};
+
+use super::{PipelineCache, entry_point_name, validate_shader};
+use crate::{
+    Buffer, BufferUsage, DescriptorSet, VulkanCommandBuffer, VulkanErrorKind,
+    shader::{DescriptorBinding, PipelineLayout, PushConstantRange, ShaderModule, ShaderStage},
+    types::{self, ComputePipelineCreateInfo, PipelineBindPoint, PipelineShaderStageCreateInfo},
+};
+
+/// A compute pipeline owned by the [`PipelineCache`] it was built with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComputePipeline<'c> {
+    handle: types::Pipeline,
+    workgroup_size: Option<[u32; 3]>,
+    _marker: PhantomData<&'c ()>,
+}
+
+/// Everything a compute pipeline is created from, with the shader identified by its code and
+/// the layout by its bindings.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct ComputePipelineDesc {
+    code: Arc<[u32]>,
+    entry_point: CString,
+    sets: Vec<Vec<DescriptorBinding>>,
+    push_constants: Option<PushConstantRange>,
+}
+
+/// Workgroup counts read by [`dispatch_indirect`](VulkanCommandBuffer::dispatch_indirect),
+/// laid out as `VkDispatchIndirectCommand`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DispatchIndirectCommand {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+impl<'c> ComputePipeline<'c> {
+    /// Returns the pipeline of `cache` running `entry_point` of `module` with `layout`,
+    /// building it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingEntryPoint` if `module` has no compute entry point `entry_point`,
+    /// `PipelineLayoutMismatch` if the layout lacks a binding of the shader, or a
+    /// `VulkanError` if `vkCreateComputePipelines` fails.
+    pub fn new<'a, L, A>(
+        cache: &'c PipelineCache<'a, L, A>,
+        layout: &PipelineLayout<'a, L, A>,
+        module: &ShaderModule<'a, L, A>,
+        entry_point: &str,
+    ) -> crate::Result<Self>
+    where
+        L: Logger,
+        A: Allocator + 'static,
+    {
+        let entry = validate_shader(layout, ShaderStage::Compute, module, entry_point)?;
+        let name = entry_point_name(entry_point);
+
+        let desc = ComputePipelineDesc {
+            code: Arc::clone(module.code()),
+            entry_point: name.clone(),
+            sets: layout.sets().to_vec(),
+            push_constants: layout.push_constants(),
+        };
+
+        let handle = cache.compute_pipeline(desc, |cache| {
+            let device = layout.device();
+            let stage = PipelineShaderStageCreateInfo::new(
+                types::ShaderStageFlags::COMPUTE,
+                module.handle(),
+                &name,
+            );
+
+            unsafe {
+                device.fns.create_compute_pipeline(
+                    device.handle,
+                    Some(cache),
+                    &ComputePipelineCreateInfo::new(stage, layout.handle()),
+                    device.instance().backend().allocator(),
+                )
+            }
+        })?;
+
+        Ok(Self {
+            handle,
+            workgroup_size: entry.workgroup_size,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Invocations per workgroup, as declared by the shader, or `None` if it is set through
+    /// specialization constants.
+    #[inline]
+    pub fn workgroup_size(&self) -> Option<[u32; 3]> {
+        self.workgroup_size
+    }
+
+    /// Workgroups to dispatch for at least `invocations` invocations along each dimension,
+    /// or `None` if the workgroup size is unknown.
+    #[inline]
+    pub fn workgroup_count(&self, invocations: [u32; 3]) -> Option<[u32; 3]> {
+        let size = self.workgroup_size?;
+
+        Some([0, 1, 2].map(|axis| invocations[axis].div_ceil(size[axis])))
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> types::Pipeline {
+        self.handle
+    }
+}
+
+impl<'a, L, A> VulkanCommandBuffer<'_, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Binds `pipeline` for the following dispatches.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording, or
+    /// `UnsupportedComputeQueue` if the buffer is for the transfer queue.
+    pub fn bind_compute_pipeline(&mut self, pipeline: ComputePipeline<'_>) -> crate::Result<()> {
+        self.ensure_recording()?;
+        self.ensure_compute_queue()?;
+
+        self.bind_pipeline(PipelineBindPoint::COMPUTE, pipeline.handle())
+    }
+
+    /// Binds `descriptor_set` as set `set` of `layout` for the following dispatches.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording,
+    /// `UnsupportedComputeQueue` if the buffer is for the transfer queue,
+    /// `DescriptorSetOutOfRange` if `layout` has no set `set`, or `IncompatibleDescriptorSet`
+    /// if `descriptor_set` was allocated for other bindings.
+    pub fn bind_compute_descriptor_set(
+        &mut self,
+        layout: &PipelineLayout<'a, L, A>,
+        set: u32,
+        descriptor_set: &DescriptorSet<'_, 'a, L, A>,
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+        self.ensure_compute_queue()?;
+
+        if set as usize >= layout.set_count() {
+            return VulkanErrorKind::DescriptorSetOutOfRange {
+                set,
+                count: layout.set_count() as u32,
+            }
+            .into_result();
+        }
+
+        if descriptor_set.bindings() != layout.set(set) {
+            return VulkanErrorKind::IncompatibleDescriptorSet { set }.into_result();
+        }
+
+        self.bind_descriptor_sets(
+            PipelineBindPoint::COMPUTE,
+            (layout.handle(), set),
+            &[descriptor_set.handle()],
+        )
+    }
+
+    /// Writes `values` to the push constants of `layout` at `offset` bytes, for every stage
+    /// accessing them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording, or
+    /// `PushConstantsOutOfRange` if `offset` or the size of `T` is not a multiple of 4, or
+    /// the values do not fit in the push constant range of `layout`.
+    pub fn push_constants<T: Copy>(
+        &mut self,
+        layout: &PipelineLayout<'a, L, A>,
+        offset: u32,
+        values: &T,
+    ) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        let size = size_of::<T>() as u32;
+        let Some(range) = layout.push_constants().filter(|range| {
+            offset.is_multiple_of(4)
+                && size.is_multiple_of(4)
+                && offset >= range.offset
+                && u64::from(offset) + u64::from(size) <= u64::from(range.offset + range.size)
+        }) else {
+            return VulkanErrorKind::PushConstantsOutOfRange { offset, size }.into_result();
+        };
+
+        self.push_constant_values((layout.handle(), range.stages.flags()), offset, values)
+    }
+
+    /// Dispatches `workgroups` workgroups of the bound compute pipeline.
+    ///
+    /// [`ComputePipeline::workgroup_count`] gives the workgroups covering a number of
+    /// invocations.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording,
+    /// `RenderingInProgress` if rendering was begun and not ended, or `NoComputePipeline` if
+    /// no compute pipeline was bound.
+    pub fn dispatch(&mut self, workgroups: [u32; 3]) -> crate::Result<()> {
+        self.ensure_dispatch()?;
+
+        self.dispatch_workgroups(workgroups)
+    }
+
+    /// Dispatches the bound compute pipeline with the workgroup counts of command `index` of
+    /// `buffer`, read when the command buffer executes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording,
+    /// `RenderingInProgress` if rendering was begun and not ended, `NoComputePipeline` if no
+    /// compute pipeline was bound, `IndirectCommandOutOfRange` if `buffer` has no command
+    /// `index`, or `MissingBufferUsage` if it was not created with [`BufferUsage::INDIRECT`].
+    pub fn dispatch_indirect(
+        &mut self,
+        buffer: &Buffer<'a, DispatchIndirectCommand, L, A>,
+        index: usize,
+    ) -> crate::Result<()> {
+        self.ensure_dispatch()?;
+
+        if index >= buffer.len() {
+            return VulkanErrorKind::IndirectCommandOutOfRange {
+                index,
+                len: buffer.len(),
+            }
+            .into_result();
+        }
+
+        if !buffer.usage().contains(BufferUsage::INDIRECT) {
+            return VulkanErrorKind::MissingBufferUsage {
+                usage: BufferUsage::INDIRECT,
+            }
+            .into_result();
+        }
+
+        self.dispatch_workgroups_indirect(
+            buffer.handle(),
+            (index * size_of::<DispatchIndirectCommand>()) as u64,
+        )
+    }
+
+    fn ensure_compute_queue(&self) -> crate::Result<()> {
+        let kind = self.pool().queue();
+
+        if kind == QueueKind::Transfer {
+            return VulkanErrorKind::UnsupportedComputeQueue { kind }.into_result();
+        }
+
+        Ok(())
+    }
+
+    fn ensure_dispatch(&self) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        if self.is_rendering() {
+            return VulkanErrorKind::RenderingInProgress.into_result();
+        }
+
+        if !self.has_compute_pipeline() {
+            return VulkanErrorKind::NoComputePipeline.into_result();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::{BaseError, CommandBuffer, CommandBufferUsage, Format, logger::DefaultLogger};
+
+    use super::*;
+    use crate::{
+        DescriptorAllocator, DescriptorWriter, Instance, MemoryUsage, Texture, TextureDesc,
+        TextureDimension, TextureUsage, TextureView, VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+        shader::test_shaders,
+    };
+
+    #[test]
+    fn test_build_compute_pipeline() {
+        let device = mock::device();
+
+        let compute = ShaderModule::from_words(&device, &test_shaders::compute()).unwrap();
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&compute]).unwrap();
+        let vertex_layout = PipelineLayout::new(&device, &[&vertex]).unwrap();
+        let cache = PipelineCache::new(&device).unwrap();
+
+        let pipeline = ComputePipeline::new(&cache, &layout, &compute, "main").unwrap();
+
+        assert_eq!(pipeline.workgroup_size(), Some([64, 1, 1]));
+        assert_eq!(pipeline.workgroup_count([1000, 3, 1]), Some([16, 3, 1]));
+        assert_eq!(
+            ComputePipeline::new(&cache, &layout, &compute, "main").unwrap(),
+            pipeline
+        );
+        assert_eq!(cache.len(), 1);
+
+        assert!(matches!(
+            ComputePipeline::new(&cache, &layout, &vertex, "main")
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::MissingEntryPoint {
+                stage: ShaderStage::Compute
+            }
+        ));
+        assert!(matches!(
+            ComputePipeline::new(&cache, &vertex_layout, &compute, "main")
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::PipelineLayoutMismatch { set: 0, .. }
+        ));
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateComputePipelines),
+            1
+        );
+
+        drop(cache);
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyPipeline),
+            1
+        );
+
+        drop(vertex_layout);
+        drop(layout);
+        drop(vertex);
+        drop(compute);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let device = mock::device();
+
+        let compute = ShaderModule::from_words(&device, &test_shaders::compute()).unwrap();
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let fragment = ShaderModule::from_words(&device, &test_shaders::fragment()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&compute]).unwrap();
+        let graphics_layout = PipelineLayout::new(&device, &[&vertex, &fragment]).unwrap();
+        let cache = PipelineCache::new(&device).unwrap();
+        let pipeline = ComputePipeline::new(&cache, &layout, &compute, "main").unwrap();
+
+        let particles =
+            Buffer::<[f32; 4], _, _>::new(&device, 256, BufferUsage::STORAGE, MemoryUsage::GpuOnly)
+                .unwrap();
+        let commands = Buffer::<DispatchIndirectCommand, _, _>::from_slice(
+            &device,
+            BufferUsage::INDIRECT,
+            &[DispatchIndirectCommand { x: 4, y: 1, z: 1 }; 2],
+        )
+        .unwrap();
+        let not_indirect = Buffer::<DispatchIndirectCommand, _, _>::from_slice(
+            &device,
+            BufferUsage::STORAGE,
+            &[DispatchIndirectCommand::default()],
+        )
+        .unwrap();
+        let image = Texture::new(
+            &device,
+            &TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 16, 16)
+                .with_usage(TextureUsage::STORAGE),
+        )
+        .unwrap();
+        let image_view = TextureView::new(&image).unwrap();
+
+        let allocator = DescriptorAllocator::new(&device);
+        let set = allocator.allocate(&layout, 0).unwrap();
+        let camera = allocator.allocate(&graphics_layout, 0).unwrap();
+
+        DescriptorWriter::new(&set)
+            .storage_buffer(0, &particles)
+            .storage_texture(1, &image_view)
+            .update()
+            .unwrap();
+
+        let pool = device.create_command_pool(QueueKind::Compute).unwrap();
+        let mut cb = pool.allocate().unwrap();
+
+        cb.begin(CommandBufferUsage::OneTimeSubmit).unwrap();
+        image.prepare_storage(&mut cb).unwrap();
+
+        assert!(matches!(
+            cb.dispatch([1, 1, 1]).unwrap_err().kind(),
+            VulkanErrorKind::NoComputePipeline
+        ));
+
+        cb.bind_compute_pipeline(pipeline).unwrap();
+        cb.bind_compute_descriptor_set(&layout, 0, &set).unwrap();
+
+        assert!(matches!(
+            cb.bind_compute_descriptor_set(&layout, 1, &set)
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::DescriptorSetOutOfRange { set: 1, count: 1 }
+        ));
+        assert!(matches!(
+            cb.bind_compute_descriptor_set(&layout, 0, &camera)
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::IncompatibleDescriptorSet { set: 0 }
+        ));
+
+        cb.push_constants(&layout, 0, &(7u32, 0.5f32)).unwrap();
+        cb.push_constants(&layout, 4, &0.25f32).unwrap();
+
+        assert!(matches!(
+            cb.push_constants(&layout, 4, &(1u32, 2u32))
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::PushConstantsOutOfRange { offset: 4, size: 8 }
+        ));
+        assert!(matches!(
+            cb.push_constants(&layout, 2, &1u16).unwrap_err().kind(),
+            VulkanErrorKind::PushConstantsOutOfRange { offset: 2, size: 2 }
+        ));
+
+        cb.dispatch(pipeline.workgroup_count([256, 1, 1]).unwrap())
+            .unwrap();
+        cb.dispatch_indirect(&commands, 1).unwrap();
+
+        assert!(matches!(
+            cb.dispatch_indirect(&commands, 2).unwrap_err().kind(),
+            VulkanErrorKind::IndirectCommandOutOfRange { index: 2, len: 2 }
+        ));
+        assert!(matches!(
+            cb.dispatch_indirect(&not_indirect, 0).unwrap_err().kind(),
+            VulkanErrorKind::MissingBufferUsage {
+                usage: BufferUsage::INDIRECT
+            }
+        ));
+
+        cb.end().unwrap();
+        pool.submit(&[&cb]).unwrap();
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdBindPipeline),
+            1
+        );
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdBindDescriptorSets),
+            1
+        );
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdPushConstants),
+            2
+        );
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::CmdDispatch), 1);
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdDispatchIndirect),
+            1
+        );
+
+        // Pipelines stay bound only until recording begins again.
+        cb.begin(CommandBufferUsage::OneTimeSubmit).unwrap();
+
+        assert!(matches!(
+            cb.dispatch_indirect(&commands, 0).unwrap_err().kind(),
+            VulkanErrorKind::NoComputePipeline
+        ));
+
+        cb.end().unwrap();
+
+        let transfer = device.create_command_pool(QueueKind::Transfer).unwrap();
+        let mut transfer_cb = transfer.allocate().unwrap();
+
+        transfer_cb
+            .begin(CommandBufferUsage::OneTimeSubmit)
+            .unwrap();
+
+        assert!(matches!(
+            transfer_cb
+                .bind_compute_pipeline(pipeline)
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::UnsupportedComputeQueue {
+                kind: QueueKind::Transfer
+            }
+        ));
+
+        transfer_cb.end().unwrap();
+
+        drop(transfer_cb);
+        drop(transfer);
+        drop(cb);
+        drop(pool);
+        drop(allocator);
+        drop(image_view);
+        drop(image);
+        drop(not_indirect);
+        drop(commands);
+        drop(particles);
+        drop(cache);
+        drop(graphics_layout);
+        drop(layout);
+        drop(fragment);
+        drop(vertex);
+        drop(compute);
+        drop(device);
+
+        MockDriver::assert_valid_layouts();
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_dispatch_compute_only() {
+        MockDriver::reset();
+
+        // The strict mock resolves no surface or swapchain function on this instance.
+        let params = mock::params().with_compute_only(true);
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+
+        assert!(
+            unsafe { mock::get_device_proc_addr(device.handle, c"vkCreateSwapchainKHR".as_ptr()) }
+                .is_none()
+        );
+
+        let compute = ShaderModule::from_words(&device, &test_shaders::compute()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&compute]).unwrap();
+        let cache = PipelineCache::new(&device).unwrap();
+        let pipeline = ComputePipeline::new(&cache, &layout, &compute, "main").unwrap();
+
+        let particles =
+            Buffer::<[f32; 4], _, _>::new(&device, 256, BufferUsage::STORAGE, MemoryUsage::GpuOnly)
+                .unwrap();
+        let image = Texture::new(
+            &device,
+            &TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 16, 16)
+                .with_usage(TextureUsage::STORAGE),
+        )
+        .unwrap();
+        let image_view = TextureView::new(&image).unwrap();
+
+        let allocator = DescriptorAllocator::new(&device);
+        let set = allocator.allocate(&layout, 0).unwrap();
+
+        DescriptorWriter::new(&set)
+            .storage_buffer(0, &particles)
+            .storage_texture(1, &image_view)
+            .update()
+            .unwrap();
+
+        let pool = device.create_command_pool(QueueKind::Compute).unwrap();
+        let mut cb = pool.allocate().unwrap();
+
+        cb.begin(CommandBufferUsage::OneTimeSubmit).unwrap();
+        image.prepare_storage(&mut cb).unwrap();
+        cb.bind_compute_pipeline(pipeline).unwrap();
+        cb.bind_compute_descriptor_set(&layout, 0, &set).unwrap();
+        cb.push_constants(&layout, 0, &(7u32, 0.5f32)).unwrap();
+        cb.dispatch(pipeline.workgroup_count([256, 1, 1]).unwrap())
+            .unwrap();
+        cb.end().unwrap();
+        pool.submit(&[&cb]).unwrap();
+
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::CmdDispatch), 1);
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::QueueSubmit), 1);
+
+        drop(cb);
+        drop(pool);
+        drop(allocator);
+        drop(image_view);
+        drop(image);
+        drop(particles);
+        drop(cache);
+        drop(layout);
+        drop(compute);
+        drop(device);
+        drop(instance);
+
+        MockDriver::assert_valid_layouts();
+        MockDriver::assert_no_leaks();
+    }
+}
@@ -0,0 +1,581 @@
+use core::marker::PhantomData;
+use std::{collections::HashSet, ffi::CString, sync::Arc};
+
+use allocator::Allocator;
+use mayon_core::logger::Logger;
+
+use super::{
+    BlendState, DepthFormat, DepthState, DynamicStates, PipelineCache, PrimitiveTopology,
+    RasterState, VertexBufferLayout, entry_point_name, state::color_blend_attachment,
+    validate_shader,
+};
+use crate::{
+    VulkanErrorKind,
+    device::{AttachmentKey, RenderPassKey},
+    shader::{
+        DescriptorBinding, PipelineLayout, PushConstantRange, ShaderModule, ShaderStage,
+        ShaderStages,
+    },
+    types::{
+        self, AttachmentLoadOp, AttachmentStoreOp, Format, GraphicsPipelineCreateInfo,
+        GraphicsPipelineState, PipelineColorBlendStateCreateInfo,
+        PipelineDepthStencilStateCreateInfo, PipelineDynamicStateCreateInfo,
+        PipelineInputAssemblyStateCreateInfo, PipelineMultisampleStateCreateInfo,
+        PipelineRasterizationStateCreateInfo, PipelineRenderingCreateInfo,
+        PipelineShaderStageCreateInfo, PipelineVertexInputStateCreateInfo,
+        PipelineViewportStateCreateInfo, VertexInputAttributeDescription,
+        VertexInputBindingDescription,
+    },
+};
+
+/// A graphics pipeline owned by the [`PipelineCache`] it was built with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GraphicsPipeline<'c> {
+    handle: types::Pipeline,
+    _marker: PhantomData<&'c ()>,
+}
+
+/// Everything a graphics pipeline is created from.
+///
+/// Shaders are identified by their code and layouts by their bindings, rather than by handles
+/// the driver may reuse for other objects once they are destroyed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct GraphicsPipelineDesc {
+    stages: Vec<(ShaderStage, Arc<[u32]>, CString)>,
+    sets: Vec<Vec<DescriptorBinding>>,
+    push_constants: Option<PushConstantRange>,
+    vertex_buffers: Vec<VertexBufferLayout>,
+    topology: PrimitiveTopology,
+    raster: RasterState,
+    depth: Option<(DepthFormat, DepthState)>,
+    color_targets: Vec<(mayon_core::Format, Option<BlendState>)>,
+    dynamic_states: DynamicStates,
+}
+
+/// Describes a graphics pipeline rendering into attachments of known formats.
+///
+/// Pipelines are built through a [`PipelineCache`], which hands out the existing pipeline
+/// when one was already built with the same shaders and state.
+///
+/// # Examples
+///
+/// ```ignore
+/// let pipeline = GraphicsPipelineBuilder::new(&layout)
+///     .vertex_shader(&vertex, "main")
+///     .fragment_shader(&fragment, "main")
+///     .vertex_buffer(VertexBufferLayout::packed(vertex.reflection().vertex_inputs()))
+///     .color_target(Format::Bgra8Srgb, Some(BlendState::ALPHA))
+///     .depth_target(DepthFormat::D32Float, DepthState::default())
+///     .build(&cache)?;
+/// ```
+pub struct GraphicsPipelineBuilder<'s, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    layout: &'s PipelineLayout<'a, L, A>,
+    shaders: Vec<(ShaderStage, &'s ShaderModule<'a, L, A>, &'s str)>,
+    vertex_buffers: Vec<VertexBufferLayout>,
+    topology: PrimitiveTopology,
+    raster: RasterState,
+    depth: Option<(DepthFormat, DepthState)>,
+    color_targets: Vec<(mayon_core::Format, Option<BlendState>)>,
+    dynamic_states: DynamicStates,
+}
+
+impl<'s, 'a, L, A> GraphicsPipelineBuilder<'s, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Starts a pipeline using `layout`, drawing filled triangle lists without culling, and
+    /// without vertex buffers or attachments.
+    pub fn new(layout: &'s PipelineLayout<'a, L, A>) -> Self {
+        Self {
+            layout,
+            shaders: Vec::new(),
+            vertex_buffers: Vec::new(),
+            topology: PrimitiveTopology::default(),
+            raster: RasterState::default(),
+            depth: None,
+            color_targets: Vec::new(),
+            dynamic_states: DynamicStates::EMPTY,
+        }
+    }
+
+    /// Runs the vertex entry point `entry_point` of `module` for each vertex.
+    #[inline]
+    pub fn vertex_shader(self, module: &'s ShaderModule<'a, L, A>, entry_point: &'s str) -> Self {
+        self.shader(ShaderStage::Vertex, module, entry_point)
+    }
+
+    /// Runs the fragment entry point `entry_point` of `module` for each fragment.
+    #[inline]
+    pub fn fragment_shader(self, module: &'s ShaderModule<'a, L, A>, entry_point: &'s str) -> Self {
+        self.shader(ShaderStage::Fragment, module, entry_point)
+    }
+
+    fn shader(
+        mut self,
+        stage: ShaderStage,
+        module: &'s ShaderModule<'a, L, A>,
+        entry_point: &'s str,
+    ) -> Self {
+        self.shaders.retain(|&(existing, ..)| existing != stage);
+        self.shaders.push((stage, module, entry_point));
+        self
+    }
+
+    /// Adds a vertex buffer binding, numbered after the ones added before it.
+    #[inline]
+    pub fn vertex_buffer(mut self, layout: VertexBufferLayout) -> Self {
+        self.vertex_buffers.push(layout);
+        self
+    }
+
+    #[inline]
+    pub fn topology(mut self, topology: PrimitiveTopology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    #[inline]
+    pub fn raster(mut self, raster: RasterState) -> Self {
+        self.raster = raster;
+        self
+    }
+
+    /// Tests fragments against a depth attachment of `format`.
+    #[inline]
+    pub fn depth_target(mut self, format: DepthFormat, state: DepthState) -> Self {
+        self.depth = Some((format, state));
+        self
+    }
+
+    /// Adds a color attachment of `format`, numbered after the ones added before it, which
+    /// fragments are blended into when `blend` is set.
+    #[inline]
+    pub fn color_target(mut self, format: mayon_core::Format, blend: Option<BlendState>) -> Self {
+        self.color_targets.push((format, blend));
+        self
+    }
+
+    /// States set while recording, in addition to the viewport and scissor.
+    #[inline]
+    pub fn dynamic_states(mut self, dynamic_states: DynamicStates) -> Self {
+        self.dynamic_states = dynamic_states;
+        self
+    }
+
+    /// Returns the pipeline of `cache` built with the same shaders and state, building it on
+    /// first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingEntryPoint` if there is no vertex shader or a shader module lacks
+    /// its entry point, `VertexInputMismatch` or `DuplicateVertexAttribute` if the vertex
+    /// buffers do not feed the vertex shader inputs, `PipelineLayoutMismatch` if the layout
+    /// lacks a binding of a shader, or a `VulkanError` if `vkCreateGraphicsPipelines` fails.
+    pub fn build<'c>(
+        &self,
+        cache: &'c PipelineCache<'a, L, A>,
+    ) -> crate::Result<GraphicsPipeline<'c>> {
+        self.validate()?;
+
+        let mut stages = self
+            .shaders
+            .iter()
+            .map(|&(stage, module, entry_point)| {
+                (
+                    stage,
+                    Arc::clone(module.code()),
+                    entry_point_name(entry_point),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Shaders may be set in any order.
+        stages.sort_by_key(|&(stage, ..)| stage as u8);
+
+        let desc = GraphicsPipelineDesc {
+            stages,
+            sets: self.layout.sets().to_vec(),
+            push_constants: self.layout.push_constants(),
+            vertex_buffers: self.vertex_buffers.clone(),
+            topology: self.topology,
+            raster: self.raster,
+            depth: self.depth,
+            color_targets: self.color_targets.clone(),
+            dynamic_states: self.dynamic_states,
+        };
+
+        let handle = cache.graphics_pipeline(desc, |cache| self.create(cache))?;
+
+        Ok(GraphicsPipeline {
+            handle,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Checks the shaders against each other, the vertex buffers and the layout, as the
+    /// driver does not report mismatches.
+    fn validate(&self) -> crate::Result<()> {
+        let mut vertex = None;
+
+        for &(stage, module, entry_point) in &self.shaders {
+            validate_shader(self.layout, stage, module, entry_point)?;
+
+            if stage == ShaderStage::Vertex {
+                vertex = Some(module);
+            }
+        }
+
+        let Some(vertex) = vertex else {
+            return VulkanErrorKind::MissingEntryPoint {
+                stage: ShaderStage::Vertex,
+            }
+            .into_result();
+        };
+
+        let mut locations = HashSet::new();
+
+        for attribute in self
+            .vertex_buffers
+            .iter()
+            .flat_map(|buffer| &buffer.attributes)
+        {
+            if !locations.insert(attribute.location) {
+                return VulkanErrorKind::DuplicateVertexAttribute {
+                    location: attribute.location,
+                }
+                .into_result();
+            }
+        }
+
+        for input in vertex.reflection().vertex_inputs() {
+            let fed = self
+                .vertex_buffers
+                .iter()
+                .flat_map(|buffer| &buffer.attributes)
+                .any(|attribute| {
+                    attribute.location == input.location && attribute.format == input.format
+                });
+
+            if !fed {
+                return VulkanErrorKind::VertexInputMismatch {
+                    location: input.location,
+                }
+                .into_result();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn create(&self, cache: types::PipelineCache) -> crate::Result<types::Pipeline> {
+        let device = self.layout.device();
+
+        let names = self
+            .shaders
+            .iter()
+            .map(|&(.., entry_point)| entry_point_name(entry_point))
+            .collect::<Vec<_>>();
+        let stages = self
+            .shaders
+            .iter()
+            .zip(&names)
+            .map(|(&(stage, module, _), name)| {
+                PipelineShaderStageCreateInfo::new(
+                    ShaderStages::from(stage).flags(),
+                    module.handle(),
+                    name,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let bindings = self
+            .vertex_buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| VertexInputBindingDescription {
+                binding: binding as u32,
+                stride: buffer.stride,
+                input_rate: buffer.step_mode.into(),
+            })
+            .collect::<Vec<_>>();
+        let attributes = self
+            .vertex_buffers
+            .iter()
+            .enumerate()
+            .flat_map(|(binding, buffer)| {
+                buffer
+                    .attributes
+                    .iter()
+                    .map(move |attribute| VertexInputAttributeDescription {
+                        location: attribute.location,
+                        binding: binding as u32,
+                        format: attribute.format.into(),
+                        offset: attribute.offset,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let blend_attachments = self
+            .color_targets
+            .iter()
+            .map(|&(_, blend)| color_blend_attachment(blend))
+            .collect::<Vec<_>>();
+        let dynamic_states = self.dynamic_states.states();
+
+        let (depth_format, depth_stencil) = match self.depth {
+            Some((format, state)) => (
+                Some(format),
+                PipelineDepthStencilStateCreateInfo::new(true, state.write, state.compare.into()),
+            ),
+            None => (
+                None,
+                PipelineDepthStencilStateCreateInfo::new(false, false, types::CompareOp::ALWAYS),
+            ),
+        };
+
+        let color_formats = self
+            .color_targets
+            .iter()
+            .map(|&(format, _)| Format::from(format))
+            .collect::<Vec<_>>();
+        let rendering = PipelineRenderingCreateInfo::new(
+            &color_formats,
+            depth_format.map_or(Format::UNDEFINED, Format::from),
+            depth_format
+                .filter(|format| format.has_stencil())
+                .map_or(Format::UNDEFINED, Format::from),
+        );
+
+        let vertex_input = PipelineVertexInputStateCreateInfo::new(&bindings, &attributes);
+        let input_assembly = PipelineInputAssemblyStateCreateInfo::new(self.topology.into());
+        let viewport = PipelineViewportStateCreateInfo::dynamic();
+        let rasterization = PipelineRasterizationStateCreateInfo::new(
+            self.raster.polygon_mode.into(),
+            self.raster.cull_mode.into(),
+            self.raster.front_face.into(),
+            self.raster.depth_clamp,
+            self.dynamic_states.contains(DynamicStates::DEPTH_BIAS),
+        );
+        let multisample = PipelineMultisampleStateCreateInfo::single_sampled();
+        let color_blend = PipelineColorBlendStateCreateInfo::new(&blend_attachments);
+        let dynamic = PipelineDynamicStateCreateInfo::new(&dynamic_states);
+
+        let state = GraphicsPipelineState {
+            vertex_input: &vertex_input,
+            input_assembly: &input_assembly,
+            viewport: &viewport,
+            rasterization: &rasterization,
+            multisample: &multisample,
+            depth_stencil: &depth_stencil,
+            color_blend: &color_blend,
+            dynamic: &dynamic,
+        };
+
+        let create_info = if device.dynamic_rendering {
+            GraphicsPipelineCreateInfo::with_rendering(
+                &stages,
+                state,
+                self.layout.handle(),
+                &rendering,
+            )
+        } else {
+            // Load and store operations do not affect render pass compatibility, so the
+            // pipeline works with every render pass of the same attachment formats.
+            let attachment = |format| AttachmentKey {
+                format,
+                load_op: AttachmentLoadOp::DONT_CARE,
+                store_op: AttachmentStoreOp::DONT_CARE,
+            };
+            let key = RenderPassKey {
+                colors: color_formats.iter().copied().map(attachment).collect(),
+                depth: depth_format.map(|format| attachment(format.into())),
+            };
+
+            let render_pass =
+                unsafe { device.render_pass(&key, device.instance().backend().allocator()) }?;
+
+            GraphicsPipelineCreateInfo::with_render_pass(
+                &stages,
+                state,
+                self.layout.handle(),
+                render_pass,
+            )
+        };
+
+        unsafe {
+            device.fns.create_graphics_pipeline(
+                device.handle,
+                Some(cache),
+                &create_info,
+                device.instance().backend().allocator(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::BaseError;
+
+    use super::*;
+    use crate::{
+        BlendState, VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+        shader::test_shaders,
+    };
+
+    #[test]
+    fn test_build_graphics_pipeline() {
+        let device = mock::device();
+
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let fragment = ShaderModule::from_words(&device, &test_shaders::fragment()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&vertex, &fragment]).unwrap();
+        let cache = PipelineCache::new(&device).unwrap();
+
+        let builder = GraphicsPipelineBuilder::new(&layout)
+            .vertex_shader(&vertex, "main")
+            .fragment_shader(&fragment, "main")
+            .vertex_buffer(VertexBufferLayout::packed(
+                vertex.reflection().vertex_inputs(),
+            ))
+            .color_target(mayon_core::Format::Bgra8Srgb, None)
+            .depth_target(DepthFormat::D32Float, DepthState::default());
+
+        let opaque = builder.build(&cache).unwrap();
+
+        assert_eq!(builder.build(&cache).unwrap(), opaque);
+
+        // The same code in another module is the same shader.
+        let vertex_copy = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let translucent = GraphicsPipelineBuilder::new(&layout)
+            .vertex_shader(&vertex_copy, "main")
+            .fragment_shader(&fragment, "main")
+            .vertex_buffer(VertexBufferLayout::packed(
+                vertex.reflection().vertex_inputs(),
+            ))
+            .color_target(mayon_core::Format::Bgra8Srgb, Some(BlendState::ALPHA))
+            .depth_target(DepthFormat::D32Float, DepthState::default());
+
+        let blended = translucent.build(&cache).unwrap();
+
+        assert_ne!(blended, opaque);
+        assert_ne!(
+            translucent
+                .color_target(mayon_core::Format::Rgba16Float, None)
+                .build(&cache)
+                .unwrap(),
+            blended
+        );
+        assert_eq!(cache.len(), 3);
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateGraphicsPipelines),
+            3
+        );
+
+        drop(cache);
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyPipeline),
+            3
+        );
+
+        drop(layout);
+        drop(vertex);
+        drop(vertex_copy);
+        drop(fragment);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    fn build_error<'a, L, A>(
+        builder: GraphicsPipelineBuilder<'_, 'a, L, A>,
+        cache: &PipelineCache<'a, L, A>,
+    ) -> VulkanErrorKind
+    where
+        L: Logger,
+        A: Allocator + 'static,
+    {
+        builder.build(cache).err().unwrap().kind()
+    }
+
+    #[test]
+    fn test_validate_graphics_pipeline() {
+        let device = mock::device();
+
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let fragment = ShaderModule::from_words(&device, &test_shaders::fragment()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&vertex, &fragment]).unwrap();
+        let vertex_layout = PipelineLayout::new(&device, &[&vertex]).unwrap();
+        let cache = PipelineCache::new(&device).unwrap();
+
+        let packed = VertexBufferLayout::packed(vertex.reflection().vertex_inputs());
+        let error = |builder| build_error(builder, &cache);
+
+        assert!(matches!(
+            error(GraphicsPipelineBuilder::new(&layout).fragment_shader(&fragment, "main")),
+            VulkanErrorKind::MissingEntryPoint {
+                stage: ShaderStage::Vertex
+            }
+        ));
+        assert!(matches!(
+            error(GraphicsPipelineBuilder::new(&layout).vertex_shader(&fragment, "main")),
+            VulkanErrorKind::MissingEntryPoint {
+                stage: ShaderStage::Vertex
+            }
+        ));
+        assert!(matches!(
+            error(GraphicsPipelineBuilder::new(&layout).vertex_shader(&vertex, "main")),
+            VulkanErrorKind::VertexInputMismatch { location: 0 }
+        ));
+
+        let mut wrong_format = packed.clone();
+        wrong_format.attributes[1].format = crate::VertexFormat::Float4;
+
+        assert!(matches!(
+            error(
+                GraphicsPipelineBuilder::new(&layout)
+                    .vertex_shader(&vertex, "main")
+                    .vertex_buffer(wrong_format)
+            ),
+            VulkanErrorKind::VertexInputMismatch { location: 1 }
+        ));
+        assert!(matches!(
+            error(
+                GraphicsPipelineBuilder::new(&layout)
+                    .vertex_shader(&vertex, "main")
+                    .vertex_buffer(packed.clone())
+                    .vertex_buffer(packed.clone())
+            ),
+            VulkanErrorKind::DuplicateVertexAttribute { location: 0 }
+        ));
+        assert!(matches!(
+            error(
+                GraphicsPipelineBuilder::new(&vertex_layout)
+                    .vertex_shader(&vertex, "main")
+                    .fragment_shader(&fragment, "main")
+                    .vertex_buffer(packed)
+            ),
+            VulkanErrorKind::PipelineLayoutMismatch { set: 0, binding: 0 }
+        ));
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateGraphicsPipelines),
+            0
+        );
+
+        drop(cache);
+        drop(layout);
+        drop(vertex_layout);
+        drop(vertex);
+        drop(fragment);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
@@ -0,0 +1,73 @@
+mod cache;
+mod compute;
+mod graphics;
+mod state;
+
+use std::ffi::CString;
+
+use allocator::Allocator;
+use mayon_core::logger::Logger;
+
+pub use cache::PipelineCache;
+pub use compute::{ComputePipeline, DispatchIndirectCommand};
+pub use graphics::{GraphicsPipeline, GraphicsPipelineBuilder};
+pub use state::{
+    BlendComponent, BlendFactor, BlendOp, BlendState, CompareOp, CullMode, DepthFormat, DepthState,
+    DynamicStates, FrontFace, PolygonMode, PrimitiveTopology, RasterState, VertexAttribute,
+    VertexBufferLayout, VertexStepMode,
+};
+
+use crate::{EntryPoint, PipelineLayout, ShaderModule, ShaderStage, ShaderStages, VulkanErrorKind};
+
+/// Checks that `module` has an entry point `entry_point` for `stage`, and that `layout`
+/// provides every binding of `module` to `stage`, as the driver does not report mismatches.
+fn validate_shader<'m, L, A>(
+    layout: &PipelineLayout<'_, L, A>,
+    stage: ShaderStage,
+    module: &'m ShaderModule<'_, L, A>,
+    entry_point: &str,
+) -> crate::Result<&'m EntryPoint>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    let Some(entry) = module
+        .reflection()
+        .entry_points()
+        .iter()
+        .find(|entry| entry.name == entry_point && entry.stage == stage)
+    else {
+        return VulkanErrorKind::MissingEntryPoint { stage }.into_result();
+    };
+
+    for binding in module.reflection().bindings() {
+        // Shaders may declare a bindless table's arrays runtime-sized or smaller than the
+        // table's.
+        let bindless = layout.bindless_set() == Some(binding.set);
+
+        let provided = layout.set(binding.set).iter().any(|provided| {
+            provided.binding == binding.binding
+                && provided.kind == binding.kind
+                && (provided.count == binding.count
+                    || bindless && (binding.count == 0 || binding.count <= provided.count))
+                && provided.stages.contains(ShaderStages::from(stage))
+        });
+
+        if !provided {
+            return VulkanErrorKind::PipelineLayoutMismatch {
+                set: binding.set,
+                binding: binding.binding,
+            }
+            .into_result();
+        }
+    }
+
+    Ok(entry)
+}
+
+/// `entry_point` as a C string, which it converts to as it matched a name read from a
+/// SPIR-V module.
+#[inline]
+fn entry_point_name(entry_point: &str) -> CString {
+    CString::new(entry_point).expect("SPIR-V entry point names contain no NUL bytes")
+}
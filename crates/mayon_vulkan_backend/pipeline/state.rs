@@ -0,0 +1,443 @@
+use core::ops::BitOr;
+
+use crate::{
+    shader::{VertexFormat, VertexInput},
+    types::{self, Format},
+};
+
+impl From<VertexFormat> for Format {
+    #[inline]
+    fn from(format: VertexFormat) -> Self {
+        match format {
+            VertexFormat::Float => Self::R32_SFLOAT,
+            VertexFormat::Float2 => Self::R32G32_SFLOAT,
+            VertexFormat::Float3 => Self::R32G32B32_SFLOAT,
+            VertexFormat::Float4 => Self::R32G32B32A32_SFLOAT,
+            VertexFormat::Int => Self::R32_SINT,
+            VertexFormat::Int2 => Self::R32G32_SINT,
+            VertexFormat::Int3 => Self::R32G32B32_SINT,
+            VertexFormat::Int4 => Self::R32G32B32A32_SINT,
+            VertexFormat::UInt => Self::R32_UINT,
+            VertexFormat::UInt2 => Self::R32G32_UINT,
+            VertexFormat::UInt3 => Self::R32G32B32_UINT,
+            VertexFormat::UInt4 => Self::R32G32B32A32_UINT,
+        }
+    }
+}
+
+/// Whether a vertex buffer advances per vertex or per instance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VertexStepMode {
+    #[default]
+    Vertex,
+    Instance,
+}
+
+impl From<VertexStepMode> for types::VertexInputRate {
+    #[inline]
+    fn from(mode: VertexStepMode) -> Self {
+        match mode {
+            VertexStepMode::Vertex => Self::VERTEX,
+            VertexStepMode::Instance => Self::INSTANCE,
+        }
+    }
+}
+
+/// A vertex shader input read from a vertex buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VertexAttribute {
+    pub location: u32,
+    pub format: VertexFormat,
+    /// Offset of the attribute from the start of each element, in bytes.
+    pub offset: u32,
+}
+
+/// The elements of one vertex buffer binding.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct VertexBufferLayout {
+    /// Distance between consecutive elements, in bytes.
+    pub stride: u32,
+    pub step_mode: VertexStepMode,
+    pub attributes: Vec<VertexAttribute>,
+}
+
+impl VertexBufferLayout {
+    /// A per-vertex buffer holding `inputs` tightly packed in the order of their locations,
+    /// as reported by [`ShaderReflection::vertex_inputs`](crate::ShaderReflection::vertex_inputs).
+    pub fn packed(inputs: &[VertexInput]) -> Self {
+        let mut inputs = inputs.to_vec();
+
+        inputs.sort_by_key(|input| input.location);
+
+        let mut stride = 0;
+        let attributes = inputs
+            .iter()
+            .map(|input| {
+                let offset = stride;
+
+                stride += input.format.size();
+
+                VertexAttribute {
+                    location: input.location,
+                    format: input.format,
+                    offset,
+                }
+            })
+            .collect();
+
+        Self {
+            stride,
+            step_mode: VertexStepMode::Vertex,
+            attributes,
+        }
+    }
+}
+
+/// How vertices are assembled into primitives.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PrimitiveTopology {
+    PointList,
+    LineList,
+    LineStrip,
+    #[default]
+    TriangleList,
+    TriangleStrip,
+}
+
+impl From<PrimitiveTopology> for types::PrimitiveTopology {
+    #[inline]
+    fn from(topology: PrimitiveTopology) -> Self {
+        match topology {
+            PrimitiveTopology::PointList => Self::POINT_LIST,
+            PrimitiveTopology::LineList => Self::LINE_LIST,
+            PrimitiveTopology::LineStrip => Self::LINE_STRIP,
+            PrimitiveTopology::TriangleList => Self::TRIANGLE_LIST,
+            PrimitiveTopology::TriangleStrip => Self::TRIANGLE_STRIP,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PolygonMode {
+    #[default]
+    Fill,
+    Line,
+    Point,
+}
+
+impl From<PolygonMode> for types::PolygonMode {
+    #[inline]
+    fn from(mode: PolygonMode) -> Self {
+        match mode {
+            PolygonMode::Fill => Self::FILL,
+            PolygonMode::Line => Self::LINE,
+            PolygonMode::Point => Self::POINT,
+        }
+    }
+}
+
+/// Which faces of triangles are discarded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CullMode {
+    #[default]
+    None,
+    Front,
+    Back,
+}
+
+impl From<CullMode> for types::CullModeFlags {
+    #[inline]
+    fn from(mode: CullMode) -> Self {
+        match mode {
+            CullMode::None => Self::NONE,
+            CullMode::Front => Self::FRONT,
+            CullMode::Back => Self::BACK,
+        }
+    }
+}
+
+/// Winding order of the vertices of front-facing triangles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FrontFace {
+    #[default]
+    CounterClockwise,
+    Clockwise,
+}
+
+impl From<FrontFace> for types::FrontFace {
+    #[inline]
+    fn from(face: FrontFace) -> Self {
+        match face {
+            FrontFace::CounterClockwise => Self::COUNTER_CLOCKWISE,
+            FrontFace::Clockwise => Self::CLOCKWISE,
+        }
+    }
+}
+
+/// Rasterization of primitives into fragments.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RasterState {
+    pub polygon_mode: PolygonMode,
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+    /// Clamps fragment depths to the depth range instead of clipping primitives.
+    pub depth_clamp: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    Never,
+    #[default]
+    Less,
+    Equal,
+    LessOrEqual,
+    Greater,
+    NotEqual,
+    GreaterOrEqual,
+    Always,
+}
+
+impl From<CompareOp> for types::CompareOp {
+    #[inline]
+    fn from(op: CompareOp) -> Self {
+        match op {
+            CompareOp::Never => Self::NEVER,
+            CompareOp::Less => Self::LESS,
+            CompareOp::Equal => Self::EQUAL,
+            CompareOp::LessOrEqual => Self::LESS_OR_EQUAL,
+            CompareOp::Greater => Self::GREATER,
+            CompareOp::NotEqual => Self::NOT_EQUAL,
+            CompareOp::GreaterOrEqual => Self::GREATER_OR_EQUAL,
+            CompareOp::Always => Self::ALWAYS,
+        }
+    }
+}
+
+/// Format of a depth attachment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DepthFormat {
+    D16Unorm,
+    D32Float,
+    D24UnormS8Uint,
+    D32FloatS8Uint,
+}
+
+impl DepthFormat {
+    #[inline]
+    pub const fn has_stencil(self) -> bool {
+        matches!(self, Self::D24UnormS8Uint | Self::D32FloatS8Uint)
+    }
+}
+
+impl From<DepthFormat> for Format {
+    #[inline]
+    fn from(format: DepthFormat) -> Self {
+        match format {
+            DepthFormat::D16Unorm => Self::D16_UNORM,
+            DepthFormat::D32Float => Self::D32_SFLOAT,
+            DepthFormat::D24UnormS8Uint => Self::D24_UNORM_S8_UINT,
+            DepthFormat::D32FloatS8Uint => Self::D32_SFLOAT_S8_UINT,
+        }
+    }
+}
+
+/// Depth testing against the depth attachment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DepthState {
+    /// Test passing fragments, comparing their depth to the stored one.
+    pub compare: CompareOp,
+    /// Whether passing fragments store their depth.
+    pub write: bool,
+}
+
+impl Default for DepthState {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            compare: CompareOp::Less,
+            write: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+    ConstantColor,
+    OneMinusConstantColor,
+}
+
+impl From<BlendFactor> for types::BlendFactor {
+    #[inline]
+    fn from(factor: BlendFactor) -> Self {
+        match factor {
+            BlendFactor::Zero => Self::ZERO,
+            BlendFactor::One => Self::ONE,
+            BlendFactor::SrcColor => Self::SRC_COLOR,
+            BlendFactor::OneMinusSrcColor => Self::ONE_MINUS_SRC_COLOR,
+            BlendFactor::DstColor => Self::DST_COLOR,
+            BlendFactor::OneMinusDstColor => Self::ONE_MINUS_DST_COLOR,
+            BlendFactor::SrcAlpha => Self::SRC_ALPHA,
+            BlendFactor::OneMinusSrcAlpha => Self::ONE_MINUS_SRC_ALPHA,
+            BlendFactor::DstAlpha => Self::DST_ALPHA,
+            BlendFactor::OneMinusDstAlpha => Self::ONE_MINUS_DST_ALPHA,
+            BlendFactor::ConstantColor => Self::CONSTANT_COLOR,
+            BlendFactor::OneMinusConstantColor => Self::ONE_MINUS_CONSTANT_COLOR,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlendOp {
+    Add,
+    Subtract,
+    ReverseSubtract,
+    Min,
+    Max,
+}
+
+impl From<BlendOp> for types::BlendOp {
+    #[inline]
+    fn from(op: BlendOp) -> Self {
+        match op {
+            BlendOp::Add => Self::ADD,
+            BlendOp::Subtract => Self::SUBTRACT,
+            BlendOp::ReverseSubtract => Self::REVERSE_SUBTRACT,
+            BlendOp::Min => Self::MIN,
+            BlendOp::Max => Self::MAX,
+        }
+    }
+}
+
+/// Combination of the color or alpha of a fragment with the stored one, as
+/// `src * src_factor <op> dst * dst_factor`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BlendComponent {
+    pub src_factor: BlendFactor,
+    pub dst_factor: BlendFactor,
+    pub op: BlendOp,
+}
+
+/// Blending of the fragments written to a color attachment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BlendState {
+    pub color: BlendComponent,
+    pub alpha: BlendComponent,
+}
+
+impl BlendState {
+    /// Blending by the alpha of the fragment, for straight alpha.
+    pub const ALPHA: Self = Self {
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            op: BlendOp::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            op: BlendOp::Add,
+        },
+    };
+
+    /// Blending of colors already multiplied by their alpha.
+    pub const PREMULTIPLIED_ALPHA: Self = Self {
+        color: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            op: BlendOp::Add,
+        },
+        alpha: Self::ALPHA.alpha,
+    };
+
+    /// Sum of the fragment and the stored color.
+    pub const ADDITIVE: Self = Self {
+        color: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::One,
+            op: BlendOp::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::One,
+            op: BlendOp::Add,
+        },
+    };
+}
+
+/// Writes to a color attachment, replacing the stored color unless `blend` is set.
+pub(crate) fn color_blend_attachment(
+    blend: Option<BlendState>,
+) -> types::PipelineColorBlendAttachmentState {
+    let Some(blend) = blend else {
+        return types::PipelineColorBlendAttachmentState {
+            color_write_mask: types::ColorComponentFlags::RGBA,
+            ..Default::default()
+        };
+    };
+
+    types::PipelineColorBlendAttachmentState {
+        blend_enable: 1,
+        src_color_blend_factor: blend.color.src_factor.into(),
+        dst_color_blend_factor: blend.color.dst_factor.into(),
+        color_blend_op: blend.color.op.into(),
+        src_alpha_blend_factor: blend.alpha.src_factor.into(),
+        dst_alpha_blend_factor: blend.alpha.dst_factor.into(),
+        alpha_blend_op: blend.alpha.op.into(),
+        color_write_mask: types::ColorComponentFlags::RGBA,
+    }
+}
+
+/// A set of pipeline states set while recording instead of when creating the pipeline.
+/// Sets combine with `|`.
+///
+/// The viewport and scissor are always dynamic, so pipelines do not depend on the size of
+/// what they render to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DynamicStates(u32);
+
+impl DynamicStates {
+    pub const EMPTY: Self = Self(0);
+    pub const LINE_WIDTH: Self = Self(1 << 0);
+    /// Enables depth bias, with factors set while recording.
+    pub const DEPTH_BIAS: Self = Self(1 << 1);
+    pub const BLEND_CONSTANTS: Self = Self(1 << 2);
+
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The Vulkan dynamic states of pipelines with these states, including the viewport and
+    /// scissor.
+    pub(crate) fn states(self) -> Vec<types::DynamicState> {
+        [
+            (Self::LINE_WIDTH, types::DynamicState::LINE_WIDTH),
+            (Self::DEPTH_BIAS, types::DynamicState::DEPTH_BIAS),
+            (Self::BLEND_CONSTANTS, types::DynamicState::BLEND_CONSTANTS),
+        ]
+        .into_iter()
+        .filter(|&(state, _)| self.contains(state))
+        .map(|(_, state)| state)
+        .chain([types::DynamicState::VIEWPORT, types::DynamicState::SCISSOR])
+        .collect()
+    }
+}
+
+impl BitOr for DynamicStates {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
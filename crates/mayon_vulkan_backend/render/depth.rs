@@ -0,0 +1,247 @@
+use std::sync::{Mutex, PoisonError};
+
+use allocator::{AllocationRequest, Allocator, DeviceAllocation, Tiling};
+use mayon_core::{
+    debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+use crate::{
+    DepthFormat, Device, VulkanErrorKind,
+    texture::LayoutTransition,
+    types::{
+        self, DeviceMemory, Format, ImageAspectFlags, ImageCreateFlags, ImageCreateInfo,
+        ImageLayout, ImageSubresourceRange, ImageTiling, ImageType, ImageUsageFlags,
+        ImageViewCreateInfo, ImageViewType,
+    },
+};
+
+/// A device-local 2D image rendered into as the depth attachment, with a view of it.
+///
+/// Like [`Texture`](crate::Texture), the target tracks its layout as commands are recorded,
+/// and must not be dropped while a pending submission still uses it.
+pub struct DepthTarget<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    handle: types::Image,
+    view: types::ImageView,
+    memory: DeviceAllocation<DeviceMemory>,
+    format: DepthFormat,
+    width: u32,
+    height: u32,
+    /// Layout as left by the last recorded command.
+    layout: Mutex<ImageLayout>,
+}
+
+impl<'a, L, A> DepthTarget<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates a depth target of `width` by `height` texels on `device`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EmptyTexture` if either extent is zero, `TextureExceedsLimits` if the device
+    /// does not support the target's size, or a `VulkanError` if the format is unsupported
+    /// or creating the image, its view, allocating or binding its memory fails.
+    pub fn new(
+        device: &Device<'a, L, A>,
+        format: DepthFormat,
+        width: u32,
+        height: u32,
+    ) -> crate::Result<Self> {
+        if width == 0 || height == 0 {
+            return VulkanErrorKind::EmptyTexture.into_result();
+        }
+
+        let vk_format = Format::from(format);
+
+        let limits = unsafe {
+            device
+                .instance()
+                .backend()
+                .fns()
+                .get_physical_device_image_format_properties(
+                    device.physical_device,
+                    vk_format,
+                    ImageType::TYPE_2D,
+                    ImageTiling::OPTIMAL,
+                    ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+                    ImageCreateFlags::EMPTY,
+                )
+        }?;
+
+        if width > limits.max_extent.width || height > limits.max_extent.height {
+            return VulkanErrorKind::TextureExceedsLimits.into_result();
+        }
+
+        let fns = &device.fns;
+        let info = ImageCreateInfo::new_2d(
+            vk_format,
+            width,
+            height,
+            ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+        );
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            let handle = fns.create_image(device.handle, &info, allocator)?;
+
+            let requirements = fns.get_image_memory_requirements(device.handle, handle);
+
+            let request = AllocationRequest::from(requirements).with_tiling(Tiling::Optimal);
+
+            let memory = match device.allocate_memory(&request, allocator) {
+                Ok(memory) => memory,
+                Err(err) => {
+                    fns.destroy_image(device.handle, handle, allocator);
+
+                    return Err(err);
+                }
+            };
+
+            let view = fns
+                .bind_image_memory(device.handle, handle, memory.memory(), memory.offset())
+                .and_then(|()| {
+                    let info = ImageViewCreateInfo::new(
+                        handle,
+                        ImageViewType::TYPE_2D,
+                        vk_format,
+                        ImageSubresourceRange {
+                            aspect_mask: aspect(format),
+                            base_mip_level: 0,
+                            level_count: 1,
+                            base_array_layer: 0,
+                            layer_count: 1,
+                        },
+                    );
+
+                    fns.create_image_view(device.handle, &info, allocator)
+                });
+
+            let view = match view {
+                Ok(view) => view,
+                Err(err) => {
+                    fns.destroy_image(device.handle, handle, allocator);
+                    device.free_memory(&memory, allocator);
+
+                    return Err(err);
+                }
+            };
+
+            debug!(
+                device.instance().logger(),
+                LogTarget::Backend,
+                "Vulkan depth target created: {:?}, {}x{} {:?}",
+                handle,
+                width,
+                height,
+                format
+            );
+
+            Ok(Self {
+                device: device.clone(),
+                handle,
+                view,
+                memory,
+                format,
+                width,
+                height,
+                layout: Mutex::new(ImageLayout::UNDEFINED),
+            })
+        }
+    }
+
+    #[inline]
+    pub fn device(&self) -> &Device<'a, L, A> {
+        &self.device
+    }
+
+    #[inline]
+    pub(crate) fn view(&self) -> types::ImageView {
+        self.view
+    }
+
+    #[inline]
+    pub fn format(&self) -> DepthFormat {
+        self.format
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    #[inline]
+    pub(crate) fn layout(&self) -> ImageLayout {
+        *self.layout.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Tracks the target in `layout` again, as returned by [`Self::layout`], once the commands
+    /// recorded since are discarded.
+    #[inline]
+    pub(crate) fn restore_layout(&self, layout: ImageLayout) {
+        *self.layout.lock().unwrap_or_else(PoisonError::into_inner) = layout;
+    }
+
+    /// Moves the target to `new` in the tracked state, and returns the transition commands
+    /// must record first, if any.
+    pub(crate) fn update_layout(&self, new: ImageLayout) -> Option<LayoutTransition> {
+        // The layout is only replaced wholesale, so a panic while holding the lock leaves it
+        // consistent.
+        let old = std::mem::replace(
+            &mut *self.layout.lock().unwrap_or_else(PoisonError::into_inner),
+            new,
+        );
+
+        LayoutTransition::is_needed(old, new).then(|| LayoutTransition {
+            image: self.handle,
+            aspect: aspect(self.format),
+            levels: 0..1,
+            layer_count: 1,
+            old,
+            new,
+        })
+    }
+}
+
+/// Aspects of images of `format`, all of which attachments use.
+#[inline]
+const fn aspect(format: DepthFormat) -> ImageAspectFlags {
+    if format.has_stencil() {
+        ImageAspectFlags::DEPTH.union(ImageAspectFlags::STENCIL)
+    } else {
+        ImageAspectFlags::DEPTH
+    }
+}
+
+impl<L, A> Drop for DepthTarget<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        unsafe {
+            let allocator = self.device.instance().backend().allocator();
+
+            self.device.forget_image_view(self.view, allocator);
+            self.device
+                .fns
+                .destroy_image_view(self.device.handle, self.view, allocator);
+            self.device
+                .fns
+                .destroy_image(self.device.handle, self.handle, allocator);
+            self.device.free_memory(&self.memory, allocator);
+        }
+    }
+}
@@ -0,0 +1,689 @@
+mod depth;
+
+use allocator::Allocator;
+use mayon_core::{CommandPool, QueueKind, logger::Logger};
+
+pub use depth::DepthTarget;
+
+use crate::{
+    TextureDimension, TextureUsage, TextureView, VulkanCommandBuffer, VulkanErrorKind,
+    device::{AttachmentKey, RenderPassKey},
+    texture::LayoutTransition,
+    types::{
+        AttachmentLoadOp, AttachmentStoreOp, ClearValue, Extent2D, ImageLayout, ImageView, Rect2D,
+        RenderPassBeginInfo, RenderingAttachmentInfo, RenderingInfo,
+    },
+};
+
+/// What an attachment holds when rendering begins.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LoadOp<T> {
+    /// Keeps the attachment's contents.
+    Load,
+    /// Fills the attachment with a value.
+    Clear(T),
+    /// Leaves the attachment's contents undefined, for attachments fully overwritten.
+    DontCare,
+}
+
+/// What is kept of an attachment once rendering ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StoreOp {
+    Store,
+    /// Discards what was rendered, for attachments only needed while rendering.
+    DontCare,
+}
+
+/// A color attachment, rendered into through a view of a single mip level and layer of a
+/// texture created with [`TextureUsage::COLOR_ATTACHMENT`].
+pub struct ColorAttachment<'r, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    pub view: &'r TextureView<'r, 'a, L, A>,
+    /// Clear values are RGBA colors.
+    pub load: LoadOp<[f32; 4]>,
+    pub store: StoreOp,
+}
+
+/// A depth attachment. Depth targets with a stencil aspect load and store it like the depth
+/// aspect, clearing it to zero.
+pub struct DepthAttachment<'r, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    pub target: &'r DepthTarget<'a, L, A>,
+    pub load: LoadOp<f32>,
+    pub store: StoreOp,
+}
+
+/// The attachments rendered into between [`VulkanCommandBuffer::begin_rendering`] and
+/// [`VulkanCommandBuffer::end_rendering`]. Attachments must all have the same extent, which
+/// is the area rendered to.
+pub struct RenderingDesc<'r, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Color attachments, bound to the fragment shader outputs of the same index.
+    pub color: &'r [ColorAttachment<'r, 'a, L, A>],
+    pub depth: Option<DepthAttachment<'r, 'a, L, A>>,
+}
+
+impl<T: Copy> LoadOp<T> {
+    /// The load operation, and the value attachments are cleared to.
+    #[inline]
+    pub(crate) fn split(self, default: T) -> (AttachmentLoadOp, T) {
+        match self {
+            Self::Load => (AttachmentLoadOp::LOAD, default),
+            Self::Clear(value) => (AttachmentLoadOp::CLEAR, value),
+            Self::DontCare => (AttachmentLoadOp::DONT_CARE, default),
+        }
+    }
+}
+
+impl From<StoreOp> for AttachmentStoreOp {
+    #[inline]
+    fn from(op: StoreOp) -> Self {
+        match op {
+            StoreOp::Store => Self::STORE,
+            StoreOp::DontCare => Self::DONT_CARE,
+        }
+    }
+}
+
+/// An attachment as passed to the driver.
+pub(crate) struct Attachment {
+    pub(crate) key: AttachmentKey,
+    pub(crate) view: ImageView,
+    pub(crate) clear_value: ClearValue,
+}
+
+impl<'a, L, A> VulkanCommandBuffer<'_, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Begins rendering into the attachments of `desc`, transitioning them to attachment
+    /// layouts first.
+    ///
+    /// Devices with dynamic rendering record `vkCmdBeginRendering`. Other devices begin a
+    /// render pass created for the attachments' formats and operations, with a framebuffer
+    /// for their views; both are cached on the device. Graphics pipelines are built for
+    /// whichever the device uses, so the same code renders on either.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording,
+    /// `RenderingInProgress` if rendering was already begun, `UnsupportedRenderingQueue` if
+    /// the buffer is not for the graphics queue, `EmptyRendering` if `desc` has no
+    /// attachment, `MissingTextureUsage` if a color texture was not created with
+    /// [`TextureUsage::COLOR_ATTACHMENT`], `InvalidAttachmentView` if a color view covers
+    /// several mip levels or layers, or a 3D texture, `AttachmentExtentMismatch` if the
+    /// attachments differ in extent, or a `VulkanError` if creating a render pass or
+    /// framebuffer fails.
+    pub fn begin_rendering(&mut self, desc: &RenderingDesc<'_, 'a, L, A>) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        if self.is_rendering() {
+            return VulkanErrorKind::RenderingInProgress.into_result();
+        }
+
+        let kind = self.pool().queue();
+
+        if kind != QueueKind::Graphics {
+            return VulkanErrorKind::UnsupportedRenderingQueue { kind }.into_result();
+        }
+
+        let extent = validate(desc)?;
+
+        let mut transitions = Vec::new();
+        let mut colors = Vec::with_capacity(desc.color.len());
+
+        for attachment in desc.color {
+            let texture = attachment.view.texture();
+            let levels = attachment.view.levels();
+            let (load_op, color) = attachment.load.split([0.0; 4]);
+
+            transitions
+                .extend(texture.update_layouts(levels, ImageLayout::COLOR_ATTACHMENT_OPTIMAL));
+            colors.push(Attachment {
+                key: AttachmentKey {
+                    format: texture.format().into(),
+                    load_op,
+                    store_op: attachment.store.into(),
+                },
+                view: attachment.view.handle(),
+                clear_value: ClearValue::color(color),
+            });
+        }
+
+        let depth = desc.depth.as_ref().map(|attachment| {
+            let (load_op, depth) = attachment.load.split(1.0);
+
+            transitions.extend(
+                attachment
+                    .target
+                    .update_layout(ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL),
+            );
+
+            Attachment {
+                key: AttachmentKey {
+                    format: attachment.target.format().into(),
+                    load_op,
+                    store_op: attachment.store.into(),
+                },
+                view: attachment.target.view(),
+                clear_value: ClearValue::depth_stencil(depth, 0),
+            }
+        });
+
+        LayoutTransition::record(self, &transitions)?;
+
+        let stencil = desc
+            .depth
+            .as_ref()
+            .is_some_and(|attachment| attachment.target.format().has_stencil());
+
+        self.begin_attachments(extent, &colors, depth.as_ref(), stencil)
+    }
+
+    /// Begins rendering into attachments of `extent` already in their attachment layouts,
+    /// loading and storing the stencil aspect of `depth` if `stencil` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording, or a
+    /// `VulkanError` if creating a render pass or framebuffer fails.
+    pub(crate) fn begin_attachments(
+        &mut self,
+        extent: Extent2D,
+        colors: &[Attachment],
+        depth: Option<&Attachment>,
+        stencil: bool,
+    ) -> crate::Result<()> {
+        let device = self.pool().device();
+
+        if device.dynamic_rendering {
+            let info = |attachment: &Attachment, layout| {
+                RenderingAttachmentInfo::new(
+                    attachment.view,
+                    layout,
+                    (attachment.key.load_op, attachment.key.store_op),
+                    attachment.clear_value,
+                )
+            };
+
+            let color_infos = colors
+                .iter()
+                .map(|color| info(color, ImageLayout::COLOR_ATTACHMENT_OPTIMAL))
+                .collect::<Vec<_>>();
+            let depth_info =
+                depth.map(|depth| info(depth, ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL));
+            let stencil_info = depth_info.as_ref().filter(|_| stencil);
+
+            self.begin_dynamic_rendering(&RenderingInfo::new(
+                Rect2D::from_extent(extent),
+                &color_infos,
+                depth_info.as_ref(),
+                stencil_info,
+            ))
+        } else {
+            let allocator = unsafe { device.instance().backend().allocator() };
+            let attachments = colors.iter().chain(depth).collect::<Vec<_>>();
+
+            let key = RenderPassKey {
+                colors: colors.iter().map(|color| color.key).collect(),
+                depth: depth.map(|depth| depth.key),
+            };
+            let views = attachments
+                .iter()
+                .map(|attachment| attachment.view)
+                .collect::<Vec<_>>();
+            let clear_values = attachments
+                .iter()
+                .map(|attachment| attachment.clear_value)
+                .collect::<Vec<_>>();
+
+            let (render_pass, framebuffer) = unsafe {
+                let render_pass = device.render_pass(&key, allocator)?;
+
+                (
+                    render_pass,
+                    device.framebuffer(render_pass, &views, extent, allocator)?,
+                )
+            };
+
+            self.begin_render_pass(&RenderPassBeginInfo::new(
+                render_pass,
+                framebuffer,
+                Rect2D::from_extent(extent),
+                &clear_values,
+            ))
+        }
+    }
+
+    /// Ends the rendering begun by [`Self::begin_rendering`]. Attachments are left in their
+    /// attachment layouts, and transitioned again by the next command using them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if the buffer is not recording, or
+    /// `RenderingNotBegun` if no rendering is in progress.
+    pub fn end_rendering(&mut self) -> crate::Result<()> {
+        self.ensure_recording()?;
+
+        if !self.is_rendering() {
+            return VulkanErrorKind::RenderingNotBegun.into_result();
+        }
+
+        if self.pool().device().dynamic_rendering {
+            self.end_dynamic_rendering()
+        } else {
+            self.end_render_pass()
+        }
+    }
+}
+
+/// Checks the attachments of `desc`, and returns their common extent.
+fn validate<L, A>(desc: &RenderingDesc<'_, '_, L, A>) -> crate::Result<Extent2D>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    let mut extents = Vec::with_capacity(desc.color.len() + 1);
+
+    for attachment in desc.color {
+        let texture = attachment.view.texture();
+
+        if !texture.usage().contains(TextureUsage::COLOR_ATTACHMENT) {
+            return VulkanErrorKind::MissingTextureUsage {
+                usage: TextureUsage::COLOR_ATTACHMENT,
+            }
+            .into_result();
+        }
+
+        if attachment.view.levels().len() != 1
+            || attachment.view.layers().len() != 1
+            || matches!(texture.dimension(), TextureDimension::D3 { .. })
+        {
+            return VulkanErrorKind::InvalidAttachmentView.into_result();
+        }
+
+        let extent = texture.level_extent(attachment.view.levels().start);
+
+        extents.push(Extent2D {
+            width: extent.width,
+            height: extent.height,
+        });
+    }
+
+    if let Some(attachment) = &desc.depth {
+        extents.push(Extent2D {
+            width: attachment.target.width(),
+            height: attachment.target.height(),
+        });
+    }
+
+    let Some(&extent) = extents.first() else {
+        return VulkanErrorKind::EmptyRendering.into_result();
+    };
+
+    if extents.iter().any(|&other| other != extent) {
+        return VulkanErrorKind::AttachmentExtentMismatch.into_result();
+    }
+
+    Ok(extent)
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::{BaseError, CommandBuffer, CommandBufferUsage, Format, logger::DefaultLogger};
+
+    use super::*;
+    use crate::{
+        DepthFormat, DepthState, GraphicsPipelineBuilder, Instance, PipelineCache, PipelineLayout,
+        ShaderModule, Texture, TextureDesc, VertexBufferLayout, VulkanCommandPool,
+        VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+        shader::test_shaders,
+    };
+
+    /// Records a frame clearing `view` and `depth`, then drawing over them.
+    fn render<'a, L, A>(
+        pool: &VulkanCommandPool<'a, L, A>,
+        view: &TextureView<'_, 'a, L, A>,
+        depth: &DepthTarget<'a, L, A>,
+    ) where
+        L: Logger,
+        A: Allocator + 'static,
+    {
+        let mut command_buffer = pool.allocate().unwrap();
+
+        command_buffer
+            .begin(CommandBufferUsage::OneTimeSubmit)
+            .unwrap();
+
+        let desc = RenderingDesc {
+            color: &[ColorAttachment {
+                view,
+                load: LoadOp::Clear([0.0, 0.0, 0.0, 1.0]),
+                store: StoreOp::Store,
+            }],
+            depth: Some(DepthAttachment {
+                target: depth,
+                load: LoadOp::Clear(1.0),
+                store: StoreOp::DontCare,
+            }),
+        };
+
+        command_buffer.begin_rendering(&desc).unwrap();
+
+        assert!(matches!(
+            command_buffer.begin_rendering(&desc).unwrap_err().kind(),
+            VulkanErrorKind::RenderingInProgress
+        ));
+        assert!(matches!(
+            command_buffer.end().unwrap_err().kind(),
+            VulkanErrorKind::RenderingInProgress
+        ));
+
+        command_buffer.end_rendering().unwrap();
+
+        assert!(matches!(
+            command_buffer.end_rendering().unwrap_err().kind(),
+            VulkanErrorKind::RenderingNotBegun
+        ));
+
+        command_buffer.end().unwrap();
+    }
+
+    fn color_target<'a, L, A>(device: &crate::Device<'a, L, A>) -> Texture<'a, L, A>
+    where
+        L: Logger,
+        A: Allocator + 'static,
+    {
+        Texture::new(
+            device,
+            &TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 64, 32)
+                .with_usage(TextureUsage::COLOR_ATTACHMENT | TextureUsage::SAMPLED),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_dynamic_rendering() {
+        for (version, function, unused) in [
+            (
+                (1, 3),
+                VulkanFunctionName::CmdBeginRendering,
+                VulkanFunctionName::CmdBeginRenderingKhr,
+            ),
+            (
+                (1, 2),
+                VulkanFunctionName::CmdBeginRenderingKhr,
+                VulkanFunctionName::CmdBeginRendering,
+            ),
+        ] {
+            MockDriver::reset();
+
+            let params = mock::params().with_api_version((1, 0), version);
+            let instance = Instance::new(params, DefaultLogger).unwrap();
+            let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+            let device = instance.create_device(&adapter).unwrap();
+
+            assert!(device.dynamic_rendering());
+
+            let texture = color_target(&device);
+            let view = TextureView::new(&texture).unwrap();
+            let depth = DepthTarget::new(&device, DepthFormat::D24UnormS8Uint, 64, 32).unwrap();
+            let pool = device.create_command_pool(QueueKind::Graphics).unwrap();
+
+            render(&pool, &view, &depth);
+
+            assert_eq!(texture.layout(0), ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+            assert_eq!(
+                depth.layout(),
+                ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL
+            );
+            assert_eq!(MockDriver::call_count(function), 1);
+            assert_eq!(MockDriver::call_count(unused), 0);
+            assert_eq!(
+                MockDriver::call_count(VulkanFunctionName::CreateRenderPass),
+                0
+            );
+
+            MockDriver::assert_valid_layouts();
+
+            drop(pool);
+            drop(depth);
+            drop(view);
+            drop(texture);
+            drop(device);
+            drop(instance);
+
+            MockDriver::assert_no_leaks();
+        }
+    }
+
+    #[test]
+    fn test_render_pass_fallback() {
+        MockDriver::reset();
+
+        let params = mock::params().with_api_version((1, 0), (1, 1));
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+
+        assert!(!device.dynamic_rendering());
+
+        let texture = color_target(&device);
+        let view = TextureView::new(&texture).unwrap();
+        let depth = DepthTarget::new(&device, DepthFormat::D32Float, 64, 32).unwrap();
+        let pool = device.create_command_pool(QueueKind::Graphics).unwrap();
+
+        render(&pool, &view, &depth);
+        render(&pool, &view, &depth);
+
+        assert_eq!(texture.layout(0), ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdBeginRenderPass),
+            2
+        );
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateRenderPass),
+            1
+        );
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateFramebuffer),
+            1
+        );
+
+        MockDriver::assert_valid_layouts();
+
+        // Pipelines are created with a render pass of the same formats.
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let fragment = ShaderModule::from_words(&device, &test_shaders::fragment()).unwrap();
+        let layout = PipelineLayout::new(&device, &[&vertex, &fragment]).unwrap();
+        let cache = PipelineCache::new(&device).unwrap();
+
+        GraphicsPipelineBuilder::new(&layout)
+            .vertex_shader(&vertex, "main")
+            .fragment_shader(&fragment, "main")
+            .vertex_buffer(VertexBufferLayout::packed(
+                vertex.reflection().vertex_inputs(),
+            ))
+            .color_target(Format::Rgba8Unorm, None)
+            .depth_target(DepthFormat::D32Float, DepthState::default())
+            .build(&cache)
+            .unwrap();
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateRenderPass),
+            2
+        );
+
+        // Framebuffers go away with the views they use.
+        drop(view);
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyFramebuffer),
+            1
+        );
+
+        drop(cache);
+        drop(layout);
+        drop(vertex);
+        drop(fragment);
+        drop(pool);
+        drop(depth);
+        drop(texture);
+        drop(device);
+        drop(instance);
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyRenderPass),
+            2
+        );
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_validate_rendering() {
+        let device = mock::device();
+
+        let texture = Texture::new(
+            &device,
+            &TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 64, 32)
+                .with_usage(TextureUsage::COLOR_ATTACHMENT)
+                .with_mip_levels(2),
+        )
+        .unwrap();
+        let sampled = Texture::new(
+            &device,
+            &TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 64, 32),
+        )
+        .unwrap();
+        let depth = DepthTarget::new(&device, DepthFormat::D16Unorm, 64, 32).unwrap();
+
+        let full = TextureView::new(&texture).unwrap();
+        let small = TextureView::with_range(&texture, 1..2, 0..1).unwrap();
+        let sampled_view = TextureView::new(&sampled).unwrap();
+
+        let graphics = device.create_command_pool(QueueKind::Graphics).unwrap();
+        let compute = device.create_command_pool(QueueKind::Compute).unwrap();
+
+        let color = |view| ColorAttachment {
+            view,
+            load: LoadOp::Load,
+            store: StoreOp::Store,
+        };
+        let depth_attachment = || DepthAttachment {
+            target: &depth,
+            load: LoadOp::DontCare,
+            store: StoreOp::Store,
+        };
+
+        let mut command_buffer = graphics.allocate().unwrap();
+
+        assert!(matches!(
+            command_buffer
+                .begin_rendering(&RenderingDesc {
+                    color: &[color(&small)],
+                    depth: None,
+                })
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::UnexpectedCommandBufferState { .. }
+        ));
+
+        command_buffer
+            .begin(CommandBufferUsage::OneTimeSubmit)
+            .unwrap();
+
+        for (desc, expected) in [
+            (
+                RenderingDesc {
+                    color: &[],
+                    depth: None,
+                },
+                VulkanErrorKind::EmptyRendering,
+            ),
+            (
+                RenderingDesc {
+                    color: &[color(&sampled_view)],
+                    depth: None,
+                },
+                VulkanErrorKind::MissingTextureUsage {
+                    usage: TextureUsage::COLOR_ATTACHMENT,
+                },
+            ),
+            (
+                RenderingDesc {
+                    color: &[color(&full)],
+                    depth: None,
+                },
+                VulkanErrorKind::InvalidAttachmentView,
+            ),
+            (
+                RenderingDesc {
+                    color: &[color(&small)],
+                    depth: Some(depth_attachment()),
+                },
+                VulkanErrorKind::AttachmentExtentMismatch,
+            ),
+        ] {
+            let kind = command_buffer.begin_rendering(&desc).unwrap_err().kind();
+
+            assert_eq!(kind.to_string(), expected.to_string());
+        }
+
+        // Failed attempts leave the attachments untouched.
+        assert_eq!(texture.layout(1), ImageLayout::UNDEFINED);
+
+        command_buffer
+            .begin_rendering(&RenderingDesc {
+                color: &[color(&small)],
+                depth: None,
+            })
+            .unwrap();
+        command_buffer.end_rendering().unwrap();
+
+        drop(command_buffer);
+
+        let mut command_buffer = compute.allocate().unwrap();
+
+        command_buffer
+            .begin(CommandBufferUsage::OneTimeSubmit)
+            .unwrap();
+
+        assert!(matches!(
+            command_buffer
+                .begin_rendering(&RenderingDesc {
+                    color: &[],
+                    depth: Some(depth_attachment()),
+                })
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::UnsupportedRenderingQueue {
+                kind: QueueKind::Compute
+            }
+        ));
+
+        drop(command_buffer);
+        drop(compute);
+        drop(graphics);
+        drop(full);
+        drop(small);
+        drop(sampled_view);
+        drop(depth);
+        drop(sampled);
+        drop(texture);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
@@ -0,0 +1,252 @@
+use core::time::Duration;
+
+use allocator::Allocator;
+use mayon_core::{
+    debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+use crate::{
+    Device, VulkanErrorKind,
+    device::TIMELINE_SEMAPHORE_VERSION,
+    types::{
+        Semaphore, SemaphoreCreateInfo, SemaphoreSignalInfo, SemaphoreTypeCreateInfo,
+        SemaphoreWaitInfo,
+    },
+};
+
+/// A semaphore with a 64-bit counter that only ever increases.
+///
+/// Unlike binary semaphores and fences, a timeline semaphore can be waited on and signaled by
+/// both the host and any of the device's queues, which makes it suitable for dependencies
+/// between queues. Submissions take `(semaphore, value)` pairs; see
+/// [`VulkanCommandPool::submit_with`](crate::VulkanCommandPool::submit_with).
+///
+/// Requires a device supporting Vulkan 1.2. The semaphore must not be dropped while a
+/// pending submission still waits on or signals it.
+pub struct TimelineSemaphore<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    handle: Semaphore,
+}
+
+impl<'a, L, A> TimelineSemaphore<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates a timeline semaphore on `device` whose counter starts at `initial_value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnsupportedApiVersion` if the device does not support Vulkan 1.2, or a
+    /// `VulkanError` if `vkCreateSemaphore` fails.
+    pub fn new(device: &Device<'a, L, A>, initial_value: u64) -> crate::Result<Self> {
+        if device.api_version < TIMELINE_SEMAPHORE_VERSION {
+            return VulkanErrorKind::UnsupportedApiVersion {
+                required: TIMELINE_SEMAPHORE_VERSION,
+                available: device.api_version,
+            }
+            .into_result();
+        }
+
+        let type_info = SemaphoreTypeCreateInfo::timeline(initial_value);
+
+        let handle = unsafe {
+            device.fns.create_semaphore(
+                device.handle,
+                &SemaphoreCreateInfo::with_type(&type_info),
+                device.instance().backend().allocator(),
+            )
+        }?;
+
+        debug!(
+            device.instance().logger(),
+            LogTarget::Backend,
+            "Vulkan timeline semaphore created: {:?} at {}",
+            handle,
+            initial_value
+        );
+
+        Ok(Self {
+            device: device.clone(),
+            handle,
+        })
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> Semaphore {
+        self.handle
+    }
+
+    /// Current value of the counter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkGetSemaphoreCounterValue` fails.
+    #[inline]
+    pub fn value(&self) -> crate::Result<u64> {
+        unsafe {
+            self.device
+                .fns
+                .get_semaphore_counter_value(self.device.handle, self.handle)
+        }
+    }
+
+    /// Sets the counter to `value` from the host, releasing waits for values up to it.
+    ///
+    /// `value` must be greater than the current value and than any value a pending
+    /// submission signals.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkSignalSemaphore` fails.
+    #[inline]
+    pub fn signal(&self, value: u64) -> crate::Result<()> {
+        unsafe {
+            self.device.fns.signal_semaphore(
+                self.device.handle,
+                &SemaphoreSignalInfo::new(self.handle, value),
+            )
+        }
+    }
+
+    /// Blocks until the counter reaches at least `value`, or until `timeout` has passed.
+    ///
+    /// A zero `timeout` only checks the current value.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `FunctionReturn` error with the `Timeout` return code if the counter did not
+    /// reach `value` in time, or another `VulkanError` if `vkWaitSemaphores` fails.
+    pub fn wait(&self, value: u64, timeout: Duration) -> crate::Result<()> {
+        let semaphores = [self.handle];
+        let values = [value];
+
+        unsafe {
+            self.device.fns.wait_semaphores(
+                self.device.handle,
+                &SemaphoreWaitInfo::new(&semaphores, &values),
+                u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX),
+            )
+        }
+    }
+}
+
+impl<L, A> Drop for TimelineSemaphore<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        unsafe {
+            self.device.fns.destroy_semaphore(
+                self.device.handle,
+                self.handle,
+                self.device.instance().backend().allocator(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::logger::DefaultLogger;
+    use mayon_core::{BaseError, CommandBuffer, CommandBufferUsage, CommandPool, QueueKind};
+
+    use super::*;
+    use crate::{
+        Instance, ReturnCode, VulkanFunctionName, VulkanVersion,
+        fn_table::{MockDriver, mock},
+    };
+
+    #[test]
+    fn test_host_signal_and_wait() {
+        let device = mock::device();
+
+        let semaphore = TimelineSemaphore::new(&device, 1).unwrap();
+
+        assert_eq!(semaphore.value().unwrap(), 1);
+
+        semaphore.wait(1, Duration::ZERO).unwrap();
+
+        let Err(err) = semaphore.wait(2, Duration::from_millis(1)) else {
+            panic!("Waiting for an unsignaled value should time out");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            VulkanErrorKind::FunctionReturn {
+                name: VulkanFunctionName::WaitSemaphores,
+                code: ReturnCode::Timeout,
+            }
+        ));
+
+        semaphore.signal(3).unwrap();
+        semaphore.wait(2, Duration::MAX).unwrap();
+
+        assert_eq!(semaphore.value().unwrap(), 3);
+
+        drop(semaphore);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_submit_with_timeline_points() {
+        let device = mock::device();
+
+        let uploaded = TimelineSemaphore::new(&device, 0).unwrap();
+        let rendered = TimelineSemaphore::new(&device, 0).unwrap();
+
+        let pool = device.create_command_pool(QueueKind::Graphics).unwrap();
+
+        {
+            let mut command_buffer = pool.allocate().unwrap();
+
+            command_buffer.begin(CommandBufferUsage::default()).unwrap();
+            command_buffer.end().unwrap();
+
+            uploaded.signal(1).unwrap();
+            pool.submit_with(&[&command_buffer], &[(&uploaded, 1)], &[(&rendered, 7)])
+                .unwrap();
+        }
+
+        rendered.wait(7, Duration::ZERO).unwrap();
+
+        assert_eq!(rendered.value().unwrap(), 7);
+        assert_eq!(uploaded.value().unwrap(), 1);
+
+        drop(pool);
+        drop(uploaded);
+        drop(rendered);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_requires_vulkan_1_2() {
+        MockDriver::reset();
+
+        let params = mock::params().with_api_version((1, 0), (1, 1));
+        let instance = Instance::new(params, DefaultLogger).unwrap();
+        let adapter = instance.select_adapter(&[]).unwrap().unwrap();
+        let device = instance.create_device(&adapter).unwrap();
+
+        assert_eq!(device.api_version(), VulkanVersion::new(1, 1, 0));
+
+        let Err(err) = TimelineSemaphore::new(&device, 0) else {
+            panic!("Timeline semaphores should need Vulkan 1.2");
+        };
+
+        assert!(matches!(
+            err.kind(),
+            VulkanErrorKind::UnsupportedApiVersion { .. }
+        ));
+    }
+}
@@ -0,0 +1,419 @@
+use std::collections::BTreeMap;
+
+use allocator::Allocator;
+use mayon_core::logger::Logger;
+
+use super::{DescriptorBinding, PushConstantRange, ShaderModule, ShaderReflection};
+use crate::{
+    BindlessTable, Device, VulkanErrorKind,
+    descriptor::BindlessDesc,
+    types::{
+        self, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo, PipelineLayoutCreateInfo,
+    },
+};
+
+/// Merges the interfaces of the shaders of a pipeline into the bindings of each descriptor
+/// set, and the push constants of every stage.
+///
+/// Sets not used by any shader are left empty. The set of `bindless`, if any, gets the
+/// bindings of its table, which the shaders' bindings of that set must be part of.
+fn merge_interfaces(
+    reflections: &[&ShaderReflection],
+    bindless: Option<(u32, &BindlessDesc)>,
+) -> crate::Result<(Vec<Vec<DescriptorBinding>>, Option<PushConstantRange>)> {
+    let mut bindings = BTreeMap::<(u32, u32), DescriptorBinding>::new();
+    let mut push_constants = None::<PushConstantRange>;
+
+    for reflection in reflections {
+        for binding in reflection.bindings() {
+            let key = (binding.set, binding.binding);
+
+            match bindings.get_mut(&key) {
+                Some(merged) if merged.kind == binding.kind && merged.count == binding.count => {
+                    merged.stages = merged.stages | binding.stages;
+                }
+                Some(_) => {
+                    return VulkanErrorKind::ShaderBindingMismatch {
+                        set: binding.set,
+                        binding: binding.binding,
+                    }
+                    .into_result();
+                }
+                None => {
+                    bindings.insert(key, *binding);
+                }
+            }
+        }
+
+        if let Some(range) = reflection.push_constants() {
+            push_constants = Some(match push_constants {
+                Some(merged) => merged.union(range),
+                None => range,
+            });
+        }
+    }
+
+    let set_count = bindings
+        .keys()
+        .next_back()
+        .map(|&(set, _)| set)
+        .into_iter()
+        .chain(bindless.map(|(set, _)| set))
+        .max()
+        .map_or(0, |set| set as usize + 1);
+    let mut sets = vec![Vec::new(); set_count];
+
+    for ((set, _), binding) in bindings {
+        if let Some((bindless_set, desc)) = bindless
+            && set == bindless_set
+        {
+            // Runtime-sized arrays take the size of the table's.
+            let in_table = desc.bindings(set).iter().any(|table| {
+                table.binding == binding.binding
+                    && table.kind == binding.kind
+                    && binding.count <= table.count
+            });
+
+            if !in_table {
+                return VulkanErrorKind::ShaderBindingMismatch {
+                    set,
+                    binding: binding.binding,
+                }
+                .into_result();
+            }
+
+            continue;
+        }
+
+        if binding.count == 0 {
+            return VulkanErrorKind::UnsizedDescriptorArray {
+                set,
+                binding: binding.binding,
+            }
+            .into_result();
+        }
+
+        sets[set as usize].push(binding);
+    }
+
+    if let Some((set, desc)) = bindless {
+        sets[set as usize] = desc.bindings(set);
+    }
+
+    Ok((sets, push_constants))
+}
+
+/// The descriptor set layouts and push constants of a pipeline, built from the reflection of
+/// its shaders so they always match.
+///
+/// Bindings declared by several shaders are shared by their stages, and push constants are
+/// covered by a single range visible to every stage using them.
+pub struct PipelineLayout<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    handle: types::PipelineLayout,
+    set_layouts: Vec<types::DescriptorSetLayout>,
+    sets: Vec<Vec<DescriptorBinding>>,
+    push_constants: Option<PushConstantRange>,
+    /// Set bound with a bindless table, if any.
+    bindless_set: Option<u32>,
+}
+
+impl<'a, L, A> PipelineLayout<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates the layout of a pipeline made of `shaders`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ShaderBindingMismatch` if two shaders declare the same binding with distinct
+    /// kinds or counts, `UnsizedDescriptorArray` if a binding is a runtime-sized array, or a
+    /// `VulkanError` if creating the descriptor set layouts or the pipeline layout fails.
+    #[inline]
+    pub fn new(
+        device: &Device<'a, L, A>,
+        shaders: &[&ShaderModule<'a, L, A>],
+    ) -> crate::Result<Self> {
+        Self::create(device, shaders, None)
+    }
+
+    /// Creates the layout of a pipeline made of `shaders`, binding `table` at set `set`.
+    ///
+    /// The shaders' bindings of that set must be arrays of the table, runtime-sized or no
+    /// larger than it. Sets of this layout other than `set` are allocated as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ShaderBindingMismatch` if two shaders declare the same binding with distinct
+    /// kinds or counts, or a binding of set `set` is not part of the table,
+    /// `UnsizedDescriptorArray` if a binding of another set is a runtime-sized array, or a
+    /// `VulkanError` if creating the descriptor set layouts or the pipeline layout fails.
+    #[inline]
+    pub fn with_bindless(
+        device: &Device<'a, L, A>,
+        shaders: &[&ShaderModule<'a, L, A>],
+        set: u32,
+        table: &BindlessTable<'a, L, A>,
+    ) -> crate::Result<Self> {
+        Self::create(device, shaders, Some((set, table.desc())))
+    }
+
+    fn create(
+        device: &Device<'a, L, A>,
+        shaders: &[&ShaderModule<'a, L, A>],
+        bindless: Option<(u32, &BindlessDesc)>,
+    ) -> crate::Result<Self> {
+        let reflections = shaders
+            .iter()
+            .map(|shader| shader.reflection())
+            .collect::<Vec<_>>();
+        let (sets, push_constants) = merge_interfaces(&reflections, bindless)?;
+
+        let fns = &device.fns;
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+            let mut set_layouts = Vec::with_capacity(sets.len());
+            let destroy_set_layouts = |set_layouts: &[types::DescriptorSetLayout]| {
+                for &set_layout in set_layouts {
+                    fns.destroy_descriptor_set_layout(device.handle, set_layout, allocator);
+                }
+            };
+
+            for (index, set) in sets.iter().enumerate() {
+                let set_layout = match bindless {
+                    Some((bindless_set, desc)) if bindless_set as usize == index => {
+                        desc.create_set_layout(device)
+                    }
+                    _ => {
+                        let bindings = set
+                            .iter()
+                            .map(|binding| {
+                                DescriptorSetLayoutBinding::new(
+                                    binding.binding,
+                                    binding.kind.into(),
+                                    binding.count,
+                                    binding.stages.flags(),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+
+                        fns.create_descriptor_set_layout(
+                            device.handle,
+                            &DescriptorSetLayoutCreateInfo::new(&bindings),
+                            allocator,
+                        )
+                    }
+                };
+
+                match set_layout {
+                    Ok(set_layout) => set_layouts.push(set_layout),
+                    Err(err) => {
+                        destroy_set_layouts(&set_layouts);
+
+                        return Err(err);
+                    }
+                }
+            }
+
+            let ranges = push_constants
+                .iter()
+                .map(|range| types::PushConstantRange {
+                    stage_flags: range.stages.flags(),
+                    offset: range.offset,
+                    size: range.size,
+                })
+                .collect::<Vec<_>>();
+
+            let handle = match fns.create_pipeline_layout(
+                device.handle,
+                &PipelineLayoutCreateInfo::new(&set_layouts, &ranges),
+                allocator,
+            ) {
+                Ok(handle) => handle,
+                Err(err) => {
+                    destroy_set_layouts(&set_layouts);
+
+                    return Err(err);
+                }
+            };
+
+            Ok(Self {
+                device: device.clone(),
+                handle,
+                set_layouts,
+                sets,
+                push_constants,
+                bindless_set: bindless.map(|(set, _)| set),
+            })
+        }
+    }
+
+    #[inline]
+    pub fn device(&self) -> &Device<'a, L, A> {
+        &self.device
+    }
+
+    /// Number of descriptor sets, including the empty ones before the last used set.
+    #[inline]
+    pub fn set_count(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// Bindings of descriptor set `set`, ordered by binding.
+    #[inline]
+    pub fn set(&self, set: u32) -> &[DescriptorBinding] {
+        self.sets.get(set as usize).map_or(&[], Vec::as_slice)
+    }
+
+    #[inline]
+    pub fn push_constants(&self) -> Option<PushConstantRange> {
+        self.push_constants
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> types::PipelineLayout {
+        self.handle
+    }
+
+    /// Layout of descriptor set `set`, if the pipeline has that many sets.
+    #[inline]
+    pub(crate) fn set_layout(&self, set: u32) -> Option<types::DescriptorSetLayout> {
+        self.set_layouts.get(set as usize).copied()
+    }
+
+    /// Set bound with a [`BindlessTable`], for layouts made by
+    /// [`with_bindless`](Self::with_bindless).
+    #[inline]
+    pub fn bindless_set(&self) -> Option<u32> {
+        self.bindless_set
+    }
+
+    /// Bindings of every set, ordered by set.
+    #[inline]
+    pub(crate) fn sets(&self) -> &[Vec<DescriptorBinding>] {
+        &self.sets
+    }
+}
+
+impl<L, A> Drop for PipelineLayout<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        let device = &self.device;
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            device
+                .fns
+                .destroy_pipeline_layout(device.handle, self.handle, allocator);
+
+            for &set_layout in &self.set_layouts {
+                device
+                    .fns
+                    .destroy_descriptor_set_layout(device.handle, set_layout, allocator);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::BaseError;
+
+    use super::*;
+    use crate::{
+        VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+        shader::{DescriptorKind, ShaderStages, test_shaders},
+    };
+
+    #[test]
+    fn test_merge_interfaces() {
+        let vertex = ShaderReflection::new(&test_shaders::vertex()).unwrap();
+        let fragment = ShaderReflection::new(&test_shaders::fragment()).unwrap();
+
+        let (sets, push_constants) = merge_interfaces(&[&vertex, &fragment], None).unwrap();
+
+        assert_eq!(sets.len(), 2);
+        assert_eq!(
+            sets[0],
+            [DescriptorBinding {
+                set: 0,
+                binding: 0,
+                kind: DescriptorKind::UniformBuffer,
+                count: 1,
+                stages: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+            }]
+        );
+        assert_eq!(sets[1].len(), 2);
+        assert_eq!(sets[1][1].stages, ShaderStages::FRAGMENT);
+        assert_eq!(
+            push_constants,
+            Some(PushConstantRange {
+                offset: 0,
+                size: 80,
+                stages: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+            })
+        );
+
+        let mut conflicting = vertex.clone();
+        conflicting.bindings[0].kind = DescriptorKind::StorageBuffer;
+
+        assert!(matches!(
+            merge_interfaces(&[&conflicting, &fragment], None)
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::ShaderBindingMismatch { set: 0, binding: 0 }
+        ));
+
+        let mut unsized_array = fragment.clone();
+        unsized_array.bindings[1].count = 0;
+
+        assert!(matches!(
+            merge_interfaces(&[&unsized_array], None)
+                .unwrap_err()
+                .kind(),
+            VulkanErrorKind::UnsizedDescriptorArray { set: 1, binding: 0 }
+        ));
+    }
+
+    #[test]
+    fn test_create_pipeline_layout() {
+        let device = mock::device();
+
+        let vertex = ShaderModule::from_words(&device, &test_shaders::vertex()).unwrap();
+        let fragment = ShaderModule::from_words(&device, &test_shaders::fragment()).unwrap();
+
+        let layout = PipelineLayout::new(&device, &[&vertex, &fragment]).unwrap();
+
+        assert_eq!(layout.set_count(), 2);
+        assert_eq!(layout.set(1).len(), 2);
+        assert!(layout.set(2).is_empty());
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateDescriptorSetLayout),
+            2
+        );
+
+        drop(layout);
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroyDescriptorSetLayout),
+            2
+        );
+
+        drop(vertex);
+        drop(fragment);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
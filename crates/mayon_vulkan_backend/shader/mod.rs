@@ -0,0 +1,171 @@
+mod layout;
+mod reflect;
+#[cfg(test)]
+pub(crate) mod test_shaders;
+
+use std::sync::Arc;
+
+use allocator::Allocator;
+use mayon_core::{
+    debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+pub use layout::PipelineLayout;
+pub use reflect::{
+    DescriptorBinding, DescriptorKind, EntryPoint, PushConstantRange, ShaderReflection,
+    ShaderStage, ShaderStages, VertexFormat, VertexInput,
+};
+
+use crate::{
+    Device, VulkanErrorKind,
+    types::{self, ShaderModuleCreateInfo},
+};
+
+/// A SPIR-V module on the device, with the reflection of its interface.
+pub struct ShaderModule<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    handle: types::ShaderModule,
+    reflection: ShaderReflection,
+    code: Arc<[u32]>,
+}
+
+impl<'a, L, A> ShaderModule<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates a shader module from the bytes of a SPIR-V file, as output by shader
+    /// compilers.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidSpirv` if `spirv` is not a whole number of words, one of the errors of
+    /// [`ShaderReflection::new`], or a `VulkanError` if `vkCreateShaderModule` fails.
+    pub fn new(device: &Device<'a, L, A>, spirv: &[u8]) -> crate::Result<Self> {
+        if !spirv.len().is_multiple_of(4) {
+            return VulkanErrorKind::InvalidSpirv.into_result();
+        }
+
+        // Copied into words, as `spirv` may not be aligned for them.
+        let code = spirv
+            .chunks_exact(4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect::<Vec<_>>();
+
+        Self::from_words(device, &code)
+    }
+
+    /// Creates a shader module from SPIR-V words.
+    ///
+    /// # Errors
+    ///
+    /// See [`new`](Self::new).
+    pub fn from_words(device: &Device<'a, L, A>, code: &[u32]) -> crate::Result<Self> {
+        let reflection = ShaderReflection::new(code)?;
+
+        let handle = unsafe {
+            device.fns.create_shader_module(
+                device.handle,
+                &ShaderModuleCreateInfo::new(code),
+                device.instance().backend().allocator(),
+            )
+        }?;
+
+        debug!(
+            device.instance().logger(),
+            LogTarget::Backend,
+            "Vulkan shader module created: {:?}, {:?}",
+            handle,
+            reflection.stages()
+        );
+
+        Ok(Self {
+            device: device.clone(),
+            handle,
+            reflection,
+            code: code.into(),
+        })
+    }
+
+    #[inline]
+    pub fn device(&self) -> &Device<'a, L, A> {
+        &self.device
+    }
+
+    #[inline]
+    pub fn reflection(&self) -> &ShaderReflection {
+        &self.reflection
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> types::ShaderModule {
+        self.handle
+    }
+
+    /// The SPIR-V code, identifying the module by content rather than by a handle the driver
+    /// may reuse once the module is destroyed. Shared with the pipeline keys built from it.
+    #[inline]
+    pub(crate) fn code(&self) -> &Arc<[u32]> {
+        &self.code
+    }
+}
+
+impl<L, A> Drop for ShaderModule<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        unsafe {
+            self.device.fns.destroy_shader_module(
+                self.device.handle,
+                self.handle,
+                self.device.instance().backend().allocator(),
+            )
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::BaseError;
+
+    use super::*;
+    use crate::{
+        VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+    };
+
+    #[test]
+    fn test_create_shader_module() {
+        let device = mock::device();
+
+        let spirv = test_shaders::bytes(&test_shaders::vertex());
+        let module = ShaderModule::new(&device, &spirv).unwrap();
+
+        assert_eq!(module.reflection().stages(), ShaderStages::VERTEX);
+
+        assert!(matches!(
+            ShaderModule::new(&device, &spirv[1..])
+                .err()
+                .unwrap()
+                .kind(),
+            VulkanErrorKind::InvalidSpirv
+        ));
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CreateShaderModule),
+            1,
+            "Invalid modules are rejected before reaching the driver"
+        );
+
+        drop(module);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
@@ -0,0 +1,1037 @@
+use core::ops::BitOr;
+use std::collections::HashMap;
+
+use crate::{
+    VulkanErrorKind,
+    types::{DescriptorType, ShaderStageFlags},
+};
+
+/// First word of every SPIR-V module.
+pub(crate) const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+/// Words of the module header, before the first instruction.
+const HEADER_LEN: usize = 5;
+
+// Opcodes of the instructions read by the reflector.
+const OP_ENTRY_POINT: u32 = 15;
+const OP_EXECUTION_MODE: u32 = 16;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+const OP_EXECUTION_MODE_ID: u32 = 331;
+
+// Execution modes.
+const EXECUTION_MODE_LOCAL_SIZE: u32 = 17;
+const EXECUTION_MODE_LOCAL_SIZE_ID: u32 = 38;
+
+// Decorations.
+const DECORATION_BLOCK: u32 = 2;
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_BUILT_IN: u32 = 11;
+const DECORATION_LOCATION: u32 = 30;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+const DECORATION_OFFSET: u32 = 35;
+
+/// Deepest nesting of types whose size is computed, well past what shaders declare.
+const MAX_TYPE_DEPTH: u32 = 64;
+
+// Storage classes.
+const STORAGE_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_INPUT: u32 = 1;
+const STORAGE_UNIFORM: u32 = 2;
+const STORAGE_PUSH_CONSTANT: u32 = 9;
+const STORAGE_STORAGE_BUFFER: u32 = 12;
+
+// Image dimensions needing descriptor types of their own.
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+/// A programmable stage of a pipeline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderStage {
+    Vertex,
+    TessellationControl,
+    TessellationEvaluation,
+    Geometry,
+    Fragment,
+    Compute,
+}
+
+impl ShaderStage {
+    /// The stage of a SPIR-V execution model, if it is one of the supported stages.
+    #[inline]
+    const fn from_execution_model(model: u32) -> Option<Self> {
+        Some(match model {
+            0 => Self::Vertex,
+            1 => Self::TessellationControl,
+            2 => Self::TessellationEvaluation,
+            3 => Self::Geometry,
+            4 => Self::Fragment,
+            5 => Self::Compute,
+            _ => return None,
+        })
+    }
+}
+
+/// A set of [`ShaderStage`]s. Sets combine with `|`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ShaderStages(u32);
+
+impl ShaderStages {
+    pub const EMPTY: Self = Self(0);
+    pub const VERTEX: Self = Self(ShaderStageFlags::VERTEX.0);
+    pub const TESSELLATION_CONTROL: Self = Self(ShaderStageFlags::TESSELLATION_CONTROL.0);
+    pub const TESSELLATION_EVALUATION: Self = Self(ShaderStageFlags::TESSELLATION_EVALUATION.0);
+    pub const GEOMETRY: Self = Self(ShaderStageFlags::GEOMETRY.0);
+    pub const FRAGMENT: Self = Self(ShaderStageFlags::FRAGMENT.0);
+    pub const COMPUTE: Self = Self(ShaderStageFlags::COMPUTE.0);
+    /// Every stage, including those of extensions.
+    pub const ALL: Self = Self(ShaderStageFlags::ALL.0);
+
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub(crate) const fn flags(self) -> ShaderStageFlags {
+        ShaderStageFlags(self.0)
+    }
+}
+
+impl From<ShaderStage> for ShaderStages {
+    #[inline]
+    fn from(stage: ShaderStage) -> Self {
+        match stage {
+            ShaderStage::Vertex => Self::VERTEX,
+            ShaderStage::TessellationControl => Self::TESSELLATION_CONTROL,
+            ShaderStage::TessellationEvaluation => Self::TESSELLATION_EVALUATION,
+            ShaderStage::Geometry => Self::GEOMETRY,
+            ShaderStage::Fragment => Self::FRAGMENT,
+            ShaderStage::Compute => Self::COMPUTE,
+        }
+    }
+}
+
+impl BitOr for ShaderStages {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// A function of a shader module usable as the entry of a pipeline stage.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EntryPoint {
+    pub name: String,
+    pub stage: ShaderStage,
+    /// Invocations per workgroup along each dimension, for compute entry points declaring
+    /// it in the module rather than through specialization constants.
+    pub workgroup_size: Option<[u32; 3]>,
+}
+
+/// Kind of resource bound to a descriptor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DescriptorKind {
+    Sampler,
+    CombinedImageSampler,
+    SampledImage,
+    StorageImage,
+    UniformTexelBuffer,
+    StorageTexelBuffer,
+    UniformBuffer,
+    StorageBuffer,
+    InputAttachment,
+}
+
+impl From<DescriptorKind> for DescriptorType {
+    #[inline]
+    fn from(kind: DescriptorKind) -> Self {
+        match kind {
+            DescriptorKind::Sampler => Self::SAMPLER,
+            DescriptorKind::CombinedImageSampler => Self::COMBINED_IMAGE_SAMPLER,
+            DescriptorKind::SampledImage => Self::SAMPLED_IMAGE,
+            DescriptorKind::StorageImage => Self::STORAGE_IMAGE,
+            DescriptorKind::UniformTexelBuffer => Self::UNIFORM_TEXEL_BUFFER,
+            DescriptorKind::StorageTexelBuffer => Self::STORAGE_TEXEL_BUFFER,
+            DescriptorKind::UniformBuffer => Self::UNIFORM_BUFFER,
+            DescriptorKind::StorageBuffer => Self::STORAGE_BUFFER,
+            DescriptorKind::InputAttachment => Self::INPUT_ATTACHMENT,
+        }
+    }
+}
+
+/// A descriptor binding declared by a shader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DescriptorBinding {
+    pub set: u32,
+    pub binding: u32,
+    pub kind: DescriptorKind,
+    /// Number of descriptors of arrays, `1` for single resources and `0` for runtime-sized
+    /// arrays.
+    pub count: u32,
+    /// Stages accessing the binding.
+    pub stages: ShaderStages,
+}
+
+/// Bytes of push constants accessed by shader stages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PushConstantRange {
+    pub offset: u32,
+    pub size: u32,
+    pub stages: ShaderStages,
+}
+
+impl PushConstantRange {
+    /// The smallest range covering both `self` and `other`, accessed by the stages of both.
+    #[inline]
+    pub(crate) fn union(self, other: Self) -> Self {
+        let offset = self.offset.min(other.offset);
+        let end = (self.offset + self.size).max(other.offset + other.size);
+
+        Self {
+            offset,
+            size: end - offset,
+            stages: self.stages | other.stages,
+        }
+    }
+}
+
+/// Type of a vertex shader input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VertexFormat {
+    Float,
+    Float2,
+    Float3,
+    Float4,
+    Int,
+    Int2,
+    Int3,
+    Int4,
+    UInt,
+    UInt2,
+    UInt3,
+    UInt4,
+}
+
+impl VertexFormat {
+    /// Format of a 32-bit scalar or vector input with `components` components.
+    const fn new(scalar: Scalar, components: u32) -> Option<Self> {
+        Some(match (scalar, components) {
+            (Scalar::Float, 1) => Self::Float,
+            (Scalar::Float, 2) => Self::Float2,
+            (Scalar::Float, 3) => Self::Float3,
+            (Scalar::Float, 4) => Self::Float4,
+            (Scalar::Int { signed: true }, 1) => Self::Int,
+            (Scalar::Int { signed: true }, 2) => Self::Int2,
+            (Scalar::Int { signed: true }, 3) => Self::Int3,
+            (Scalar::Int { signed: true }, 4) => Self::Int4,
+            (Scalar::Int { signed: false }, 1) => Self::UInt,
+            (Scalar::Int { signed: false }, 2) => Self::UInt2,
+            (Scalar::Int { signed: false }, 3) => Self::UInt3,
+            (Scalar::Int { signed: false }, 4) => Self::UInt4,
+            _ => return None,
+        })
+    }
+
+    /// Size of a value of this format in bytes.
+    #[inline]
+    pub const fn size(self) -> u32 {
+        match self {
+            Self::Float | Self::Int | Self::UInt => 4,
+            Self::Float2 | Self::Int2 | Self::UInt2 => 8,
+            Self::Float3 | Self::Int3 | Self::UInt3 => 12,
+            Self::Float4 | Self::Int4 | Self::UInt4 => 16,
+        }
+    }
+}
+
+/// An input of a vertex shader, fed from vertex buffers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct VertexInput {
+    pub location: u32,
+    pub format: VertexFormat,
+}
+
+/// The interface of a SPIR-V module: its entry points, the descriptors and push constants
+/// it uses, and the inputs of its vertex shaders.
+///
+/// Descriptors and push constants are attributed to every stage of the module, as SPIR-V
+/// before 1.4 does not list the resources each entry point uses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderReflection {
+    entry_points: Vec<EntryPoint>,
+    pub(super) bindings: Vec<DescriptorBinding>,
+    push_constants: Option<PushConstantRange>,
+    vertex_inputs: Vec<VertexInput>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scalar {
+    Bool,
+    Int { signed: bool },
+    Float,
+}
+
+#[derive(Clone, Debug)]
+enum Type {
+    Scalar { scalar: Scalar, width: u32 },
+    Vector { component: u32, count: u32 },
+    Matrix { column: u32, count: u32 },
+    Image { dim: u32, sampled: u32 },
+    Sampler,
+    SampledImage,
+    Array { element: u32, length: u32 },
+    RuntimeArray { element: u32 },
+    Struct { members: Vec<u32> },
+    Pointer { pointee: u32 },
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Decorations {
+    set: Option<u32>,
+    binding: Option<u32>,
+    location: Option<u32>,
+    built_in: bool,
+    block: bool,
+    buffer_block: bool,
+    array_stride: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct MemberDecorations {
+    offset: Option<u32>,
+    matrix_stride: Option<u32>,
+}
+
+/// Declarations of a module gathered in a first pass, as decorations precede the
+/// declarations they apply to.
+#[derive(Default)]
+struct Module {
+    /// Entry points with their function and interface variables.
+    entry_points: Vec<(ShaderStage, u32, String, Vec<u32>)>,
+    /// Workgroup sizes declared for functions, as literals or as ids of constants.
+    workgroup_sizes: HashMap<u32, ([u32; 3], bool)>,
+    decorations: HashMap<u32, Decorations>,
+    members: HashMap<(u32, u32), MemberDecorations>,
+    types: HashMap<u32, Type>,
+    constants: HashMap<u32, u32>,
+    /// Variables with their pointer type and storage class, in declaration order.
+    variables: Vec<(u32, u32, u32)>,
+}
+
+/// Decodes a nul-terminated string of a SPIR-V instruction, packed four bytes per word.
+fn decode_string(words: &[u32]) -> crate::Result<(String, usize)> {
+    let mut bytes = Vec::new();
+
+    for (index, word) in words.iter().enumerate() {
+        for byte in word.to_le_bytes() {
+            if byte == 0 {
+                return match String::from_utf8(bytes) {
+                    Ok(string) => Ok((string, index + 1)),
+                    Err(_) => VulkanErrorKind::InvalidSpirv.into_result(),
+                };
+            }
+
+            bytes.push(byte);
+        }
+    }
+
+    VulkanErrorKind::InvalidSpirv.into_result()
+}
+
+impl Module {
+    fn parse(code: &[u32]) -> crate::Result<Self> {
+        if code.len() < HEADER_LEN || code[0] != SPIRV_MAGIC {
+            return VulkanErrorKind::InvalidSpirv.into_result();
+        }
+
+        let mut module = Self::default();
+        let mut rest = &code[HEADER_LEN..];
+
+        while let [first, ..] = rest {
+            let len = (first >> 16) as usize;
+
+            if len == 0 || len > rest.len() {
+                return VulkanErrorKind::InvalidSpirv.into_result();
+            }
+
+            module.read(first & 0xffff, &rest[1..len])?;
+            rest = &rest[len..];
+        }
+
+        Ok(module)
+    }
+
+    fn read(&mut self, opcode: u32, operands: &[u32]) -> crate::Result<()> {
+        match (opcode, operands) {
+            (OP_ENTRY_POINT, [model, function, name @ ..]) => {
+                let (name, len) = decode_string(name)?;
+
+                if let Some(stage) = ShaderStage::from_execution_model(*model) {
+                    self.entry_points
+                        .push((stage, *function, name, operands[2 + len..].to_vec()));
+                }
+            }
+            (OP_EXECUTION_MODE | OP_EXECUTION_MODE_ID, [function, mode, literals @ ..]) => {
+                let by_id = match *mode {
+                    EXECUTION_MODE_LOCAL_SIZE => false,
+                    EXECUTION_MODE_LOCAL_SIZE_ID => true,
+                    _ => return Ok(()),
+                };
+
+                let &[x, y, z] = literals else {
+                    return VulkanErrorKind::InvalidSpirv.into_result();
+                };
+
+                self.workgroup_sizes.insert(*function, ([x, y, z], by_id));
+            }
+            (OP_DECORATE, [target, decoration, literals @ ..]) => {
+                let decorations = self.decorations.entry(*target).or_default();
+                let literal = literals.first().copied();
+
+                match *decoration {
+                    DECORATION_BLOCK => decorations.block = true,
+                    DECORATION_BUFFER_BLOCK => decorations.buffer_block = true,
+                    DECORATION_ARRAY_STRIDE => decorations.array_stride = literal,
+                    DECORATION_BUILT_IN => decorations.built_in = true,
+                    DECORATION_LOCATION => decorations.location = literal,
+                    DECORATION_BINDING => decorations.binding = literal,
+                    DECORATION_DESCRIPTOR_SET => decorations.set = literal,
+                    _ => {}
+                }
+            }
+            (OP_MEMBER_DECORATE, [target, member, decoration, literals @ ..]) => {
+                let decorations = self.members.entry((*target, *member)).or_default();
+                let literal = literals.first().copied();
+
+                match *decoration {
+                    DECORATION_OFFSET => decorations.offset = literal,
+                    DECORATION_MATRIX_STRIDE => decorations.matrix_stride = literal,
+                    _ => {}
+                }
+            }
+            (OP_TYPE_BOOL, [id]) => {
+                self.types.insert(
+                    *id,
+                    Type::Scalar {
+                        scalar: Scalar::Bool,
+                        width: 32,
+                    },
+                );
+            }
+            (OP_TYPE_INT, [id, width, signedness]) => {
+                self.types.insert(
+                    *id,
+                    Type::Scalar {
+                        scalar: Scalar::Int {
+                            signed: *signedness != 0,
+                        },
+                        width: *width,
+                    },
+                );
+            }
+            (OP_TYPE_FLOAT, [id, width, ..]) => {
+                self.types.insert(
+                    *id,
+                    Type::Scalar {
+                        scalar: Scalar::Float,
+                        width: *width,
+                    },
+                );
+            }
+            (OP_TYPE_VECTOR, [id, component, count]) => {
+                self.types.insert(
+                    *id,
+                    Type::Vector {
+                        component: *component,
+                        count: *count,
+                    },
+                );
+            }
+            (OP_TYPE_MATRIX, [id, column, count]) => {
+                self.types.insert(
+                    *id,
+                    Type::Matrix {
+                        column: *column,
+                        count: *count,
+                    },
+                );
+            }
+            (OP_TYPE_IMAGE, [id, _, dim, _, _, _, sampled, ..]) => {
+                self.types.insert(
+                    *id,
+                    Type::Image {
+                        dim: *dim,
+                        sampled: *sampled,
+                    },
+                );
+            }
+            (OP_TYPE_SAMPLER, [id]) => {
+                self.types.insert(*id, Type::Sampler);
+            }
+            (OP_TYPE_SAMPLED_IMAGE, [id, _]) => {
+                self.types.insert(*id, Type::SampledImage);
+            }
+            (OP_TYPE_ARRAY, [id, element, length]) => {
+                let Some(&length) = self.constants.get(length) else {
+                    return VulkanErrorKind::InvalidSpirv.into_result();
+                };
+
+                self.types.insert(
+                    *id,
+                    Type::Array {
+                        element: *element,
+                        length,
+                    },
+                );
+            }
+            (OP_TYPE_RUNTIME_ARRAY, [id, element]) => {
+                self.types
+                    .insert(*id, Type::RuntimeArray { element: *element });
+            }
+            (OP_TYPE_STRUCT, [id, members @ ..]) => {
+                self.types.insert(
+                    *id,
+                    Type::Struct {
+                        members: members.to_vec(),
+                    },
+                );
+            }
+            (OP_TYPE_POINTER, [id, _, pointee]) => {
+                self.types.insert(*id, Type::Pointer { pointee: *pointee });
+            }
+            (OP_CONSTANT, [_, id, value, ..]) => {
+                self.constants.insert(*id, *value);
+            }
+            (OP_VARIABLE, [pointer, id, storage_class, ..]) => {
+                self.variables.push((*id, *pointer, *storage_class));
+            }
+            (
+                OP_ENTRY_POINT
+                | OP_EXECUTION_MODE
+                | OP_EXECUTION_MODE_ID
+                | OP_DECORATE
+                | OP_MEMBER_DECORATE
+                | OP_TYPE_BOOL
+                | OP_TYPE_INT
+                | OP_TYPE_FLOAT
+                | OP_TYPE_VECTOR
+                | OP_TYPE_MATRIX
+                | OP_TYPE_IMAGE
+                | OP_TYPE_SAMPLER
+                | OP_TYPE_SAMPLED_IMAGE
+                | OP_TYPE_ARRAY
+                | OP_TYPE_RUNTIME_ARRAY
+                | OP_TYPE_STRUCT
+                | OP_TYPE_POINTER
+                | OP_CONSTANT
+                | OP_VARIABLE,
+                _,
+            ) => return VulkanErrorKind::InvalidSpirv.into_result(),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn ty(&self, id: u32) -> crate::Result<&Type> {
+        match self.types.get(&id) {
+            Some(ty) => Ok(ty),
+            None => VulkanErrorKind::InvalidSpirv.into_result(),
+        }
+    }
+
+    /// Workgroup size declared for `function`, resolving the constants of `LocalSizeId`.
+    fn workgroup_size(&self, function: u32) -> crate::Result<Option<[u32; 3]>> {
+        let Some(&(size, by_id)) = self.workgroup_sizes.get(&function) else {
+            return Ok(None);
+        };
+
+        if !by_id {
+            return Ok(Some(size));
+        }
+
+        let mut resolved = [0; 3];
+
+        for (resolved, id) in resolved.iter_mut().zip(size) {
+            let Some(&value) = self.constants.get(&id) else {
+                return VulkanErrorKind::InvalidSpirv.into_result();
+            };
+
+            *resolved = value;
+        }
+
+        Ok(Some(resolved))
+    }
+
+    #[inline]
+    fn decorations(&self, id: u32) -> Decorations {
+        self.decorations.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Type a variable of pointer type `pointer` points to.
+    fn pointee(&self, pointer: u32) -> crate::Result<u32> {
+        match self.ty(pointer)? {
+            Type::Pointer { pointee } => Ok(*pointee),
+            _ => VulkanErrorKind::InvalidSpirv.into_result(),
+        }
+    }
+
+    /// Size in bytes of a value of type `id` in a block, `matrix_stride` being the stride of
+    /// the member's columns if it is a matrix.
+    ///
+    /// Fails with `InvalidSpirv` if the size does not fit in a `u32`, or if types are nested
+    /// more than [`MAX_TYPE_DEPTH`] deep, as a type containing itself would be.
+    fn size_of(&self, id: u32, matrix_stride: Option<u32>) -> crate::Result<u32> {
+        self.nested_size_of(id, matrix_stride, 0)
+    }
+
+    fn nested_size_of(
+        &self,
+        id: u32,
+        matrix_stride: Option<u32>,
+        depth: u32,
+    ) -> crate::Result<u32> {
+        if depth > MAX_TYPE_DEPTH {
+            return VulkanErrorKind::InvalidSpirv.into_result();
+        }
+
+        let size_of = |id, matrix_stride| self.nested_size_of(id, matrix_stride, depth + 1);
+
+        let size = match self.ty(id)? {
+            Type::Scalar { width, .. } => Some(width / 8),
+            Type::Vector { component, count } => count.checked_mul(size_of(*component, None)?),
+            Type::Matrix { column, count } => match matrix_stride {
+                Some(stride) => count.checked_mul(stride),
+                None => count.checked_mul(size_of(*column, None)?),
+            },
+            Type::Array { length, .. } => match self.decorations(id).array_stride {
+                Some(stride) => length.checked_mul(stride),
+                None => None,
+            },
+            Type::Struct { members } => {
+                let mut size = Some(0u32);
+
+                for (index, &member) in members.iter().enumerate() {
+                    let decorations = self
+                        .members
+                        .get(&(id, index as u32))
+                        .copied()
+                        .unwrap_or_default();
+                    let Some(offset) = decorations.offset else {
+                        return VulkanErrorKind::InvalidSpirv.into_result();
+                    };
+                    let end = offset.checked_add(size_of(member, decorations.matrix_stride)?);
+
+                    size = size.zip(end).map(|(size, end)| size.max(end));
+                }
+
+                size
+            }
+            _ => None,
+        };
+
+        match size {
+            Some(size) => Ok(size),
+            None => VulkanErrorKind::InvalidSpirv.into_result(),
+        }
+    }
+
+    /// Kind and count of the descriptor bound to a variable of type `pointee` in
+    /// `storage_class`.
+    fn descriptor(&self, storage_class: u32, pointee: u32) -> crate::Result<(DescriptorKind, u32)> {
+        let (element, count) = match self.ty(pointee)? {
+            Type::Array { element, length } => (*element, *length),
+            Type::RuntimeArray { element } => (*element, 0),
+            _ => (pointee, 1),
+        };
+
+        let kind = match (storage_class, self.ty(element)?) {
+            (STORAGE_UNIFORM, Type::Struct { .. }) => {
+                let decorations = self.decorations(element);
+
+                if decorations.buffer_block {
+                    DescriptorKind::StorageBuffer
+                } else if decorations.block {
+                    DescriptorKind::UniformBuffer
+                } else {
+                    return VulkanErrorKind::InvalidSpirv.into_result();
+                }
+            }
+            (STORAGE_STORAGE_BUFFER, Type::Struct { .. }) => DescriptorKind::StorageBuffer,
+            (STORAGE_UNIFORM_CONSTANT, Type::Sampler) => DescriptorKind::Sampler,
+            (STORAGE_UNIFORM_CONSTANT, Type::SampledImage) => DescriptorKind::CombinedImageSampler,
+            (STORAGE_UNIFORM_CONSTANT, Type::Image { dim, sampled }) => match (*dim, *sampled) {
+                (DIM_SUBPASS_DATA, _) => DescriptorKind::InputAttachment,
+                (DIM_BUFFER, 2) => DescriptorKind::StorageTexelBuffer,
+                (DIM_BUFFER, _) => DescriptorKind::UniformTexelBuffer,
+                (_, 2) => DescriptorKind::StorageImage,
+                _ => DescriptorKind::SampledImage,
+            },
+            _ => return VulkanErrorKind::UnsupportedShaderResource.into_result(),
+        };
+
+        Ok((kind, count))
+    }
+
+    /// Format of a vertex input of type `id`.
+    fn vertex_format(&self, id: u32, location: u32) -> crate::Result<VertexFormat> {
+        let (component, count) = match self.ty(id)? {
+            Type::Vector { component, count } => (*component, *count),
+            _ => (id, 1),
+        };
+
+        let format = match self.ty(component)? {
+            Type::Scalar { scalar, width: 32 } => VertexFormat::new(*scalar, count),
+            _ => None,
+        };
+
+        match format {
+            Some(format) => Ok(format),
+            None => VulkanErrorKind::UnsupportedVertexInput { location }.into_result(),
+        }
+    }
+}
+
+impl ShaderReflection {
+    /// Reflects the SPIR-V module `code`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidSpirv` if `code` is not a well-formed SPIR-V module,
+    /// `UnsupportedShaderResource` if it binds a kind of resource without a
+    /// [`DescriptorKind`], or `UnsupportedVertexInput` if a vertex input is not a 32-bit
+    /// scalar or vector.
+    pub fn new(code: &[u32]) -> crate::Result<Self> {
+        let module = Module::parse(code)?;
+
+        let stages = module
+            .entry_points
+            .iter()
+            .fold(ShaderStages::EMPTY, |stages, (stage, ..)| {
+                stages | ShaderStages::from(*stage)
+            });
+
+        let mut bindings = Vec::new();
+        let mut push_constants = None::<PushConstantRange>;
+
+        for &(id, pointer, storage_class) in &module.variables {
+            let pointee = module.pointee(pointer)?;
+
+            match storage_class {
+                STORAGE_UNIFORM_CONSTANT | STORAGE_UNIFORM | STORAGE_STORAGE_BUFFER => {
+                    let decorations = module.decorations(id);
+                    let (Some(set), Some(binding)) = (decorations.set, decorations.binding) else {
+                        return VulkanErrorKind::InvalidSpirv.into_result();
+                    };
+                    let (kind, count) = module.descriptor(storage_class, pointee)?;
+
+                    bindings.push(DescriptorBinding {
+                        set,
+                        binding,
+                        kind,
+                        count,
+                        stages,
+                    });
+                }
+                STORAGE_PUSH_CONSTANT => {
+                    let Type::Struct { members } = module.ty(pointee)? else {
+                        return VulkanErrorKind::InvalidSpirv.into_result();
+                    };
+
+                    // Blocks may start past offset 0 when stages share the push constants.
+                    let offset = (0..members.len() as u32)
+                        .filter_map(|index| module.members.get(&(pointee, index))?.offset)
+                        .min()
+                        .unwrap_or_default();
+                    let range = PushConstantRange {
+                        offset,
+                        size: module.size_of(pointee, None)? - offset,
+                        stages,
+                    };
+
+                    push_constants = Some(match push_constants {
+                        Some(existing) => existing.union(range),
+                        None => range,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        bindings.sort_by_key(|binding| (binding.set, binding.binding));
+
+        let mut vertex_inputs = Vec::new();
+
+        for (.., interface) in module
+            .entry_points
+            .iter()
+            .filter(|(stage, ..)| *stage == ShaderStage::Vertex)
+        {
+            for &(id, pointer, storage_class) in &module.variables {
+                let decorations = module.decorations(id);
+
+                if storage_class != STORAGE_INPUT
+                    || decorations.built_in
+                    || !interface.contains(&id)
+                {
+                    continue;
+                }
+
+                let Some(location) = decorations.location else {
+                    return VulkanErrorKind::InvalidSpirv.into_result();
+                };
+
+                let input = VertexInput {
+                    location,
+                    format: module.vertex_format(module.pointee(pointer)?, location)?,
+                };
+
+                if !vertex_inputs.contains(&input) {
+                    vertex_inputs.push(input);
+                }
+            }
+        }
+
+        vertex_inputs.sort_by_key(|input| input.location);
+
+        let mut entry_points = Vec::with_capacity(module.entry_points.len());
+
+        for (stage, function, name, _) in &module.entry_points {
+            entry_points.push(EntryPoint {
+                name: name.clone(),
+                stage: *stage,
+                workgroup_size: match stage {
+                    ShaderStage::Compute => module.workgroup_size(*function)?,
+                    _ => None,
+                },
+            });
+        }
+
+        Ok(Self {
+            entry_points,
+            bindings,
+            push_constants,
+            vertex_inputs,
+        })
+    }
+
+    #[inline]
+    pub fn entry_points(&self) -> &[EntryPoint] {
+        &self.entry_points
+    }
+
+    /// The entry point named `name`.
+    #[inline]
+    pub fn entry_point(&self, name: &str) -> Option<&EntryPoint> {
+        self.entry_points
+            .iter()
+            .find(|entry_point| entry_point.name == name)
+    }
+
+    /// Stages of every entry point of the module.
+    #[inline]
+    pub fn stages(&self) -> ShaderStages {
+        self.entry_points
+            .iter()
+            .fold(ShaderStages::EMPTY, |stages, entry_point| {
+                stages | ShaderStages::from(entry_point.stage)
+            })
+    }
+
+    /// Descriptor bindings, ordered by set then binding.
+    #[inline]
+    pub fn bindings(&self) -> &[DescriptorBinding] {
+        &self.bindings
+    }
+
+    #[inline]
+    pub fn push_constants(&self) -> Option<PushConstantRange> {
+        self.push_constants
+    }
+
+    /// Inputs of the vertex entry points, ordered by location.
+    #[inline]
+    pub fn vertex_inputs(&self) -> &[VertexInput] {
+        &self.vertex_inputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::BaseError;
+
+    use super::*;
+    use crate::shader::test_shaders;
+
+    #[test]
+    fn test_reflect_vertex_shader() {
+        let reflection = ShaderReflection::new(&test_shaders::vertex()).unwrap();
+
+        assert_eq!(
+            reflection.entry_points(),
+            [EntryPoint {
+                name: "main".to_owned(),
+                stage: ShaderStage::Vertex,
+                workgroup_size: None,
+            }]
+        );
+        assert_eq!(
+            reflection.bindings(),
+            [DescriptorBinding {
+                set: 0,
+                binding: 0,
+                kind: DescriptorKind::UniformBuffer,
+                count: 1,
+                stages: ShaderStages::VERTEX,
+            }]
+        );
+        assert_eq!(
+            reflection.push_constants(),
+            Some(PushConstantRange {
+                offset: 0,
+                size: 64,
+                stages: ShaderStages::VERTEX
+            })
+        );
+        assert_eq!(
+            reflection.vertex_inputs(),
+            [
+                VertexInput {
+                    location: 0,
+                    format: VertexFormat::Float3
+                },
+                VertexInput {
+                    location: 1,
+                    format: VertexFormat::Float2
+                },
+            ],
+            "Built-in inputs are skipped"
+        );
+    }
+
+    #[test]
+    fn test_reflect_fragment_shader() {
+        let reflection = ShaderReflection::new(&test_shaders::fragment()).unwrap();
+
+        assert_eq!(reflection.stages(), ShaderStages::FRAGMENT);
+        assert!(reflection.entry_point("main").is_some());
+        assert_eq!(
+            reflection
+                .bindings()
+                .iter()
+                .map(|binding| (binding.set, binding.binding, binding.kind, binding.count))
+                .collect::<Vec<_>>(),
+            [
+                (0, 0, DescriptorKind::UniformBuffer, 1),
+                (1, 0, DescriptorKind::CombinedImageSampler, 4),
+                (1, 1, DescriptorKind::StorageBuffer, 1),
+            ]
+        );
+        assert_eq!(
+            reflection.push_constants(),
+            Some(PushConstantRange {
+                offset: 64,
+                size: 16,
+                stages: ShaderStages::FRAGMENT
+            })
+        );
+        assert!(reflection.vertex_inputs().is_empty());
+    }
+
+    #[test]
+    fn test_reflect_compute_shader() {
+        let reflection = ShaderReflection::new(&test_shaders::compute()).unwrap();
+
+        assert_eq!(
+            reflection.entry_point("main").unwrap().workgroup_size,
+            Some([64, 1, 1])
+        );
+        assert_eq!(
+            reflection
+                .bindings()
+                .iter()
+                .map(|binding| (binding.set, binding.binding, binding.kind, binding.stages))
+                .collect::<Vec<_>>(),
+            [
+                (0, 0, DescriptorKind::StorageBuffer, ShaderStages::COMPUTE),
+                (0, 1, DescriptorKind::StorageImage, ShaderStages::COMPUTE),
+            ]
+        );
+        assert_eq!(
+            reflection.push_constants(),
+            Some(PushConstantRange {
+                offset: 0,
+                size: 8,
+                stages: ShaderStages::COMPUTE
+            })
+        );
+    }
+
+    #[test]
+    fn test_reflect_invalid_spirv() {
+        let mut code = test_shaders::vertex();
+
+        assert!(matches!(
+            ShaderReflection::new(&code[..3]).unwrap_err().kind(),
+            VulkanErrorKind::InvalidSpirv
+        ));
+
+        // Truncates the last instruction.
+        code.pop();
+
+        assert!(matches!(
+            ShaderReflection::new(&code).unwrap_err().kind(),
+            VulkanErrorKind::InvalidSpirv
+        ));
+
+        code[0] = 0;
+
+        assert!(matches!(
+            ShaderReflection::new(&code).unwrap_err().kind(),
+            VulkanErrorKind::InvalidSpirv
+        ));
+    }
+
+    #[test]
+    fn test_reflect_push_constant_size() {
+        let reflection = ShaderReflection::new(&test_shaders::push_constant_array(4, 16)).unwrap();
+
+        assert_eq!(reflection.push_constants().unwrap().size, 64);
+
+        for code in [
+            test_shaders::push_constant_array(0x1000_0000, 16),
+            test_shaders::recursive_push_constants(),
+        ] {
+            assert!(matches!(
+                ShaderReflection::new(&code).unwrap_err().kind(),
+                VulkanErrorKind::InvalidSpirv
+            ));
+        }
+    }
+}
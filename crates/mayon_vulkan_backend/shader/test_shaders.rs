@@ -0,0 +1,388 @@
+//! Hand-assembled SPIR-V modules for tests, declaring interfaces without function bodies.
+
+use super::reflect::SPIRV_MAGIC;
+
+const OP_CAPABILITY: u32 = 17;
+const OP_MEMORY_MODEL: u32 = 14;
+const OP_ENTRY_POINT: u32 = 15;
+const OP_EXECUTION_MODE: u32 = 16;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+
+const BLOCK: u32 = 2;
+const ARRAY_STRIDE: u32 = 6;
+const MATRIX_STRIDE: u32 = 7;
+const BUILT_IN: u32 = 11;
+const LOCATION: u32 = 30;
+const BINDING: u32 = 33;
+const DESCRIPTOR_SET: u32 = 34;
+const OFFSET: u32 = 35;
+
+const INPUT: u32 = 1;
+const UNIFORM: u32 = 2;
+const UNIFORM_CONSTANT: u32 = 0;
+const PUSH_CONSTANT: u32 = 9;
+const STORAGE_BUFFER: u32 = 12;
+
+struct Assembler {
+    words: Vec<u32>,
+    bound: u32,
+}
+
+impl Assembler {
+    fn new() -> Self {
+        let mut assembler = Self {
+            words: vec![SPIRV_MAGIC, 0x0001_0000, 0, 0, 0],
+            bound: 1,
+        };
+
+        // Shader capability, logical addressing and the GLSL memory model.
+        assembler.op(OP_CAPABILITY, &[1]);
+        assembler.op(OP_MEMORY_MODEL, &[0, 1]);
+
+        assembler
+    }
+
+    fn id(&mut self) -> u32 {
+        self.bound += 1;
+        self.bound - 1
+    }
+
+    fn op(&mut self, opcode: u32, operands: &[u32]) {
+        self.words
+            .push(((operands.len() as u32 + 1) << 16) | opcode);
+        self.words.extend_from_slice(operands);
+    }
+
+    /// Declares a new result id with `opcode`, the id being the first operand.
+    fn declare(&mut self, opcode: u32, operands: &[u32]) -> u32 {
+        let id = self.id();
+
+        self.op(opcode, &[&[id], operands].concat());
+
+        id
+    }
+
+    fn entry_point(&mut self, model: u32, function: u32, name: &str, interface: &[u32]) {
+        let mut name = name.as_bytes().to_vec();
+
+        name.resize(name.len() / 4 * 4 + 4, 0);
+
+        let name = name
+            .chunks(4)
+            .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+
+        self.op(
+            OP_ENTRY_POINT,
+            &[model, function]
+                .into_iter()
+                .chain(name)
+                .chain(interface.iter().copied())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    fn variable(&mut self, pointer: u32, storage_class: u32) -> u32 {
+        let id = self.id();
+
+        self.op(OP_VARIABLE, &[pointer, id, storage_class]);
+
+        id
+    }
+
+    fn finish(mut self) -> Vec<u32> {
+        self.words[3] = self.bound;
+        self.words
+    }
+}
+
+/// A vertex shader reading a position and texture coordinates, a camera uniform buffer at
+/// set 0 binding 0, a 64-byte matrix of push constants, and the built-in vertex index.
+pub(crate) fn vertex() -> Vec<u32> {
+    let mut asm = Assembler::new();
+
+    let main = asm.id();
+    let position = asm.id();
+    let uv = asm.id();
+    let vertex_index = asm.id();
+    let camera = asm.id();
+    let push = asm.id();
+    let camera_block = asm.id();
+    let push_block = asm.id();
+
+    asm.entry_point(0, main, "main", &[position, uv, vertex_index]);
+
+    asm.op(OP_DECORATE, &[position, LOCATION, 0]);
+    asm.op(OP_DECORATE, &[uv, LOCATION, 1]);
+    asm.op(OP_DECORATE, &[vertex_index, BUILT_IN, 42]);
+    asm.op(OP_DECORATE, &[camera, DESCRIPTOR_SET, 0]);
+    asm.op(OP_DECORATE, &[camera, BINDING, 0]);
+
+    for block in [camera_block, push_block] {
+        asm.op(OP_DECORATE, &[block, BLOCK]);
+        asm.op(OP_MEMBER_DECORATE, &[block, 0, OFFSET, 0]);
+        asm.op(OP_MEMBER_DECORATE, &[block, 0, MATRIX_STRIDE, 16]);
+    }
+
+    let float = asm.declare(OP_TYPE_FLOAT, &[32]);
+    let int = asm.declare(OP_TYPE_INT, &[32, 1]);
+    let vec2 = asm.declare(OP_TYPE_VECTOR, &[float, 2]);
+    let vec3 = asm.declare(OP_TYPE_VECTOR, &[float, 3]);
+    let vec4 = asm.declare(OP_TYPE_VECTOR, &[float, 4]);
+    let mat4 = asm.declare(OP_TYPE_MATRIX, &[vec4, 4]);
+
+    asm.op(OP_TYPE_STRUCT, &[camera_block, mat4]);
+    asm.op(OP_TYPE_STRUCT, &[push_block, mat4]);
+
+    let input_vec3 = asm.declare(OP_TYPE_POINTER, &[INPUT, vec3]);
+    let input_vec2 = asm.declare(OP_TYPE_POINTER, &[INPUT, vec2]);
+    let input_int = asm.declare(OP_TYPE_POINTER, &[INPUT, int]);
+    let uniform_camera = asm.declare(OP_TYPE_POINTER, &[UNIFORM, camera_block]);
+    let push_constant = asm.declare(OP_TYPE_POINTER, &[PUSH_CONSTANT, push_block]);
+
+    asm.op(OP_VARIABLE, &[input_vec3, position, INPUT]);
+    asm.op(OP_VARIABLE, &[input_vec2, uv, INPUT]);
+    asm.op(OP_VARIABLE, &[input_int, vertex_index, INPUT]);
+    asm.op(OP_VARIABLE, &[uniform_camera, camera, UNIFORM]);
+    asm.op(OP_VARIABLE, &[push_constant, push, PUSH_CONSTANT]);
+
+    asm.finish()
+}
+
+/// A fragment shader reading texture coordinates, the vertex shader's camera buffer, four
+/// combined image samplers at set 1 binding 0, a runtime-sized storage buffer at set 1
+/// binding 1, and a 16-byte color of push constants at offset 64.
+pub(crate) fn fragment() -> Vec<u32> {
+    let mut asm = Assembler::new();
+
+    let main = asm.id();
+    let uv = asm.id();
+    let camera = asm.id();
+    let textures = asm.id();
+    let lights = asm.id();
+    let camera_block = asm.id();
+    let lights_block = asm.id();
+    let lights_array = asm.id();
+    let tint_block = asm.id();
+
+    asm.entry_point(4, main, "main", &[uv]);
+    // OriginUpperLeft.
+    asm.op(OP_EXECUTION_MODE, &[main, 7]);
+
+    asm.op(OP_DECORATE, &[uv, LOCATION, 0]);
+
+    for (variable, set, binding) in [(camera, 0, 0), (textures, 1, 0), (lights, 1, 1)] {
+        asm.op(OP_DECORATE, &[variable, DESCRIPTOR_SET, set]);
+        asm.op(OP_DECORATE, &[variable, BINDING, binding]);
+    }
+
+    asm.op(OP_DECORATE, &[camera_block, BLOCK]);
+    asm.op(OP_MEMBER_DECORATE, &[camera_block, 0, OFFSET, 0]);
+    asm.op(OP_MEMBER_DECORATE, &[camera_block, 0, MATRIX_STRIDE, 16]);
+    asm.op(OP_DECORATE, &[lights_array, ARRAY_STRIDE, 16]);
+    asm.op(OP_DECORATE, &[lights_block, BLOCK]);
+    asm.op(OP_MEMBER_DECORATE, &[lights_block, 0, OFFSET, 0]);
+    asm.op(OP_DECORATE, &[tint_block, BLOCK]);
+    asm.op(OP_MEMBER_DECORATE, &[tint_block, 0, OFFSET, 64]);
+
+    let float = asm.declare(OP_TYPE_FLOAT, &[32]);
+    let uint = asm.declare(OP_TYPE_INT, &[32, 0]);
+    let vec2 = asm.declare(OP_TYPE_VECTOR, &[float, 2]);
+    let vec4 = asm.declare(OP_TYPE_VECTOR, &[float, 4]);
+    let mat4 = asm.declare(OP_TYPE_MATRIX, &[vec4, 4]);
+    // A sampled 2D image of floats, with an unknown format.
+    let image = asm.declare(OP_TYPE_IMAGE, &[float, 1, 0, 0, 0, 1, 0]);
+    let sampled_image = asm.declare(OP_TYPE_SAMPLED_IMAGE, &[image]);
+    let four = asm.id();
+
+    asm.op(OP_CONSTANT, &[uint, four, 4]);
+
+    let texture_array = asm.declare(OP_TYPE_ARRAY, &[sampled_image, four]);
+
+    asm.op(OP_TYPE_RUNTIME_ARRAY, &[lights_array, vec4]);
+    asm.op(OP_TYPE_STRUCT, &[camera_block, mat4]);
+    asm.op(OP_TYPE_STRUCT, &[lights_block, lights_array]);
+    asm.op(OP_TYPE_STRUCT, &[tint_block, vec4]);
+
+    let input_vec2 = asm.declare(OP_TYPE_POINTER, &[INPUT, vec2]);
+    let uniform_camera = asm.declare(OP_TYPE_POINTER, &[UNIFORM, camera_block]);
+    let uniform_textures = asm.declare(OP_TYPE_POINTER, &[UNIFORM_CONSTANT, texture_array]);
+    let storage_lights = asm.declare(OP_TYPE_POINTER, &[STORAGE_BUFFER, lights_block]);
+    let push_constant = asm.declare(OP_TYPE_POINTER, &[PUSH_CONSTANT, tint_block]);
+
+    asm.op(OP_VARIABLE, &[input_vec2, uv, INPUT]);
+    asm.op(OP_VARIABLE, &[uniform_camera, camera, UNIFORM]);
+    asm.op(OP_VARIABLE, &[uniform_textures, textures, UNIFORM_CONSTANT]);
+    asm.op(OP_VARIABLE, &[storage_lights, lights, STORAGE_BUFFER]);
+    asm.variable(push_constant, PUSH_CONSTANT);
+
+    asm.finish()
+}
+
+/// A compute shader of 64 invocations per workgroup, reading and writing a runtime-sized
+/// storage buffer at set 0 binding 0 and a storage image at set 0 binding 1, with 8 bytes
+/// of push constants.
+pub(crate) fn compute() -> Vec<u32> {
+    let mut asm = Assembler::new();
+
+    let main = asm.id();
+    let particles = asm.id();
+    let target = asm.id();
+    let particles_block = asm.id();
+    let particles_array = asm.id();
+    let push_block = asm.id();
+
+    asm.entry_point(5, main, "main", &[]);
+    // LocalSize.
+    asm.op(OP_EXECUTION_MODE, &[main, 17, 64, 1, 1]);
+
+    for (variable, binding) in [(particles, 0), (target, 1)] {
+        asm.op(OP_DECORATE, &[variable, DESCRIPTOR_SET, 0]);
+        asm.op(OP_DECORATE, &[variable, BINDING, binding]);
+    }
+
+    asm.op(OP_DECORATE, &[particles_array, ARRAY_STRIDE, 16]);
+    asm.op(OP_DECORATE, &[particles_block, BLOCK]);
+    asm.op(OP_MEMBER_DECORATE, &[particles_block, 0, OFFSET, 0]);
+    asm.op(OP_DECORATE, &[push_block, BLOCK]);
+    asm.op(OP_MEMBER_DECORATE, &[push_block, 0, OFFSET, 0]);
+    asm.op(OP_MEMBER_DECORATE, &[push_block, 1, OFFSET, 4]);
+
+    let float = asm.declare(OP_TYPE_FLOAT, &[32]);
+    let uint = asm.declare(OP_TYPE_INT, &[32, 0]);
+    let vec4 = asm.declare(OP_TYPE_VECTOR, &[float, 4]);
+    // A storage 2D image of rgba8 texels.
+    let image = asm.declare(OP_TYPE_IMAGE, &[float, 1, 0, 0, 0, 2, 4]);
+
+    asm.op(OP_TYPE_RUNTIME_ARRAY, &[particles_array, vec4]);
+    asm.op(OP_TYPE_STRUCT, &[particles_block, particles_array]);
+    asm.op(OP_TYPE_STRUCT, &[push_block, uint, float]);
+
+    let storage_particles = asm.declare(OP_TYPE_POINTER, &[STORAGE_BUFFER, particles_block]);
+    let uniform_image = asm.declare(OP_TYPE_POINTER, &[UNIFORM_CONSTANT, image]);
+    let push_constant = asm.declare(OP_TYPE_POINTER, &[PUSH_CONSTANT, push_block]);
+
+    asm.op(OP_VARIABLE, &[storage_particles, particles, STORAGE_BUFFER]);
+    asm.op(OP_VARIABLE, &[uniform_image, target, UNIFORM_CONSTANT]);
+    asm.variable(push_constant, PUSH_CONSTANT);
+
+    asm.finish()
+}
+
+/// A compute shader indexing a bindless table at set 1: a runtime-sized array of sampled
+/// images at binding 0, four samplers at binding 1 and a runtime-sized array of storage
+/// buffers at binding 2.
+pub(crate) fn bindless() -> Vec<u32> {
+    let mut asm = Assembler::new();
+
+    let main = asm.id();
+    let textures = asm.id();
+    let samplers = asm.id();
+    let buffers = asm.id();
+    let buffer_block = asm.id();
+
+    asm.entry_point(5, main, "main", &[]);
+    asm.op(OP_EXECUTION_MODE, &[main, 17, 1, 1, 1]);
+
+    for (variable, binding) in [(textures, 0), (samplers, 1), (buffers, 2)] {
+        asm.op(OP_DECORATE, &[variable, DESCRIPTOR_SET, 1]);
+        asm.op(OP_DECORATE, &[variable, BINDING, binding]);
+    }
+
+    asm.op(OP_DECORATE, &[buffer_block, BLOCK]);
+    asm.op(OP_MEMBER_DECORATE, &[buffer_block, 0, OFFSET, 0]);
+
+    let float = asm.declare(OP_TYPE_FLOAT, &[32]);
+    let uint = asm.declare(OP_TYPE_INT, &[32, 0]);
+    let vec4 = asm.declare(OP_TYPE_VECTOR, &[float, 4]);
+    let image = asm.declare(OP_TYPE_IMAGE, &[float, 1, 0, 0, 0, 1, 0]);
+    let sampler = asm.declare(OP_TYPE_SAMPLER, &[]);
+    let four = asm.id();
+
+    asm.op(OP_CONSTANT, &[uint, four, 4]);
+    asm.op(OP_TYPE_STRUCT, &[buffer_block, vec4]);
+
+    let image_array = asm.declare(OP_TYPE_RUNTIME_ARRAY, &[image]);
+    let sampler_array = asm.declare(OP_TYPE_ARRAY, &[sampler, four]);
+    let buffer_array = asm.declare(OP_TYPE_RUNTIME_ARRAY, &[buffer_block]);
+
+    let uniform_images = asm.declare(OP_TYPE_POINTER, &[UNIFORM_CONSTANT, image_array]);
+    let uniform_samplers = asm.declare(OP_TYPE_POINTER, &[UNIFORM_CONSTANT, sampler_array]);
+    let storage_buffers = asm.declare(OP_TYPE_POINTER, &[STORAGE_BUFFER, buffer_array]);
+
+    asm.op(OP_VARIABLE, &[uniform_images, textures, UNIFORM_CONSTANT]);
+    asm.op(OP_VARIABLE, &[uniform_samplers, samplers, UNIFORM_CONSTANT]);
+    asm.op(OP_VARIABLE, &[storage_buffers, buffers, STORAGE_BUFFER]);
+
+    asm.finish()
+}
+
+/// A compute shader whose push constants are an array of `length` elements `stride` bytes
+/// apart.
+pub(crate) fn push_constant_array(length: u32, stride: u32) -> Vec<u32> {
+    compute_with_push_block(|asm| {
+        let float = asm.declare(OP_TYPE_FLOAT, &[32]);
+        let uint = asm.declare(OP_TYPE_INT, &[32, 0]);
+        let length_id = asm.id();
+        let array = asm.id();
+        let block = asm.id();
+
+        asm.op(OP_DECORATE, &[array, ARRAY_STRIDE, stride]);
+        asm.op(OP_DECORATE, &[block, BLOCK]);
+        asm.op(OP_MEMBER_DECORATE, &[block, 0, OFFSET, 0]);
+        asm.op(OP_CONSTANT, &[uint, length_id, length]);
+        asm.op(OP_TYPE_ARRAY, &[array, float, length_id]);
+        asm.op(OP_TYPE_STRUCT, &[block, array]);
+
+        block
+    })
+}
+
+/// A compute shader whose push constant block has itself as a member.
+pub(crate) fn recursive_push_constants() -> Vec<u32> {
+    compute_with_push_block(|asm| {
+        let block = asm.id();
+
+        asm.op(OP_DECORATE, &[block, BLOCK]);
+        asm.op(OP_MEMBER_DECORATE, &[block, 0, OFFSET, 0]);
+        asm.op(OP_TYPE_STRUCT, &[block, block]);
+
+        block
+    })
+}
+
+/// A compute shader with the push constant block returned by `push_block`, and no other
+/// resources.
+fn compute_with_push_block(push_block: impl FnOnce(&mut Assembler) -> u32) -> Vec<u32> {
+    let mut asm = Assembler::new();
+
+    let main = asm.id();
+
+    asm.entry_point(5, main, "main", &[]);
+    asm.op(OP_EXECUTION_MODE, &[main, 17, 1, 1, 1]);
+
+    let push_block = push_block(&mut asm);
+    let push_constant = asm.declare(OP_TYPE_POINTER, &[PUSH_CONSTANT, push_block]);
+
+    asm.variable(push_constant, PUSH_CONSTANT);
+
+    asm.finish()
+}
+
+/// `code` as the bytes of a SPIR-V file.
+pub(crate) fn bytes(code: &[u32]) -> Vec<u8> {
+    code.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
@@ -0,0 +1,120 @@
+use core::ops::Range;
+
+use allocator::Allocator;
+use mayon_core::logger::Logger;
+
+use crate::{
+    VulkanCommandBuffer,
+    types::{
+        AccessFlags, Image, ImageAspectFlags, ImageLayout, ImageMemoryBarrier,
+        ImageSubresourceRange, PipelineStageFlags,
+    },
+};
+
+/// A layout transition of mip levels `levels` of an image, across all its layers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct LayoutTransition {
+    pub(crate) image: Image,
+    pub(crate) aspect: ImageAspectFlags,
+    pub(crate) levels: Range<u32>,
+    pub(crate) layer_count: u32,
+    pub(crate) old: ImageLayout,
+    pub(crate) new: ImageLayout,
+}
+
+/// Accesses made to images in `layout`, and the stages making them.
+///
+/// Shader accesses use all stages, so the transitions are valid on queues without graphics
+/// or compute support.
+pub(crate) const fn layout_access(layout: ImageLayout) -> (AccessFlags, PipelineStageFlags) {
+    match layout {
+        ImageLayout::TRANSFER_DST_OPTIMAL => {
+            (AccessFlags::TRANSFER_WRITE, PipelineStageFlags::TRANSFER)
+        }
+        ImageLayout::TRANSFER_SRC_OPTIMAL => {
+            (AccessFlags::TRANSFER_READ, PipelineStageFlags::TRANSFER)
+        }
+        ImageLayout::SHADER_READ_ONLY_OPTIMAL => {
+            (AccessFlags::SHADER_READ, PipelineStageFlags::ALL_COMMANDS)
+        }
+        ImageLayout::GENERAL => (
+            AccessFlags::SHADER_READ.union(AccessFlags::SHADER_WRITE),
+            PipelineStageFlags::ALL_COMMANDS,
+        ),
+        ImageLayout::COLOR_ATTACHMENT_OPTIMAL => (
+            AccessFlags::COLOR_ATTACHMENT_READ.union(AccessFlags::COLOR_ATTACHMENT_WRITE),
+            PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+        ),
+        ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL => (
+            AccessFlags::DEPTH_STENCIL_ATTACHMENT_READ
+                .union(AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE),
+            PipelineStageFlags::EARLY_FRAGMENT_TESTS.union(PipelineStageFlags::LATE_FRAGMENT_TESTS),
+        ),
+        ImageLayout::PRESENT_SRC => (AccessFlags::EMPTY, PipelineStageFlags::BOTTOM_OF_PIPE),
+        _ => (AccessFlags::EMPTY, PipelineStageFlags::TOP_OF_PIPE),
+    }
+}
+
+impl LayoutTransition {
+    /// Whether moving from `old` to `new` needs a barrier. Staying in a read-only layout
+    /// does not; staying in a writable one still orders the writes.
+    #[inline]
+    pub(crate) fn is_needed(old: ImageLayout, new: ImageLayout) -> bool {
+        old != new
+            || !matches!(
+                new,
+                ImageLayout::SHADER_READ_ONLY_OPTIMAL | ImageLayout::TRANSFER_SRC_OPTIMAL
+            )
+    }
+
+    pub(crate) fn barrier(&self) -> ImageMemoryBarrier {
+        ImageMemoryBarrier::new(
+            self.image,
+            ImageSubresourceRange {
+                aspect_mask: self.aspect,
+                base_mip_level: self.levels.start,
+                level_count: self.levels.end - self.levels.start,
+                base_array_layer: 0,
+                layer_count: self.layer_count,
+            },
+            (layout_access(self.old).0, self.old),
+            (layout_access(self.new).0, self.new),
+        )
+    }
+
+    /// Source and destination stages of a barrier made of `transitions`.
+    pub(crate) fn stages(
+        transitions: &[LayoutTransition],
+    ) -> (PipelineStageFlags, PipelineStageFlags) {
+        transitions.iter().fold(
+            (PipelineStageFlags::default(), PipelineStageFlags::default()),
+            |(src, dst), transition| {
+                (
+                    src.union(layout_access(transition.old).1),
+                    dst.union(layout_access(transition.new).1),
+                )
+            },
+        )
+    }
+
+    /// Records `transitions` as a single pipeline barrier.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if `command_buffer` is not recording.
+    pub(crate) fn record<L, A>(
+        command_buffer: &mut VulkanCommandBuffer<'_, '_, L, A>,
+        transitions: &[LayoutTransition],
+    ) -> crate::Result<()>
+    where
+        L: Logger,
+        A: Allocator + 'static,
+    {
+        let barriers = transitions
+            .iter()
+            .map(LayoutTransition::barrier)
+            .collect::<Vec<_>>();
+
+        command_buffer.pipeline_barrier(Self::stages(transitions), &[], &barriers)
+    }
+}
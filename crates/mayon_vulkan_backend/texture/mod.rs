@@ -0,0 +1,882 @@
+mod layout;
+mod sampler;
+mod view;
+
+use core::ops::{BitOr, Range};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use allocator::{AllocationRequest, Allocator, DeviceAllocation, Tiling};
+use mayon_core::{
+    CommandPool, QueueKind, debug,
+    logger::{Logger, Target as LogTarget},
+};
+
+pub(crate) use layout::{LayoutTransition, layout_access};
+pub use sampler::{AddressMode, FilterMode, MipmapMode, Sampler, SamplerCache, SamplerDesc};
+pub use view::TextureView;
+
+use crate::{
+    Device, VulkanCommandBuffer, VulkanErrorKind,
+    types::{
+        self, DeviceMemory, Extent3D, Filter, FormatFeatureFlags, ImageAspectFlags, ImageBlit,
+        ImageCreateFlags, ImageCreateInfo, ImageLayout, ImageSubresourceLayers, ImageTiling,
+        ImageType, ImageUsageFlags, MemoryRequirements, Offset3D,
+    },
+};
+
+/// Shape of a [`Texture`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextureDimension {
+    D2,
+    /// An array of `layers` 2D images.
+    D2Array {
+        layers: u32,
+    },
+    /// Six square 2D images, one per cube face in the order +X, -X, +Y, -Y, +Z, -Z.
+    Cube,
+    /// A volume `depth` texels deep.
+    D3 {
+        depth: u32,
+    },
+}
+
+impl TextureDimension {
+    /// Number of array layers of textures with this dimension.
+    #[inline]
+    pub const fn layers(self) -> u32 {
+        match self {
+            Self::D2 | Self::D3 { .. } => 1,
+            Self::D2Array { layers } => layers,
+            Self::Cube => 6,
+        }
+    }
+
+    /// Depth in texels of textures with this dimension.
+    #[inline]
+    pub const fn depth(self) -> u32 {
+        match self {
+            Self::D3 { depth } => depth,
+            _ => 1,
+        }
+    }
+}
+
+/// What a [`Texture`] can be used for. Usages combine with `|`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextureUsage(u32);
+
+impl TextureUsage {
+    /// Read through a [`Sampler`] in shaders.
+    pub const SAMPLED: Self = Self(ImageUsageFlags::SAMPLED.0);
+    /// Read and written as a storage image in shaders.
+    pub const STORAGE: Self = Self(ImageUsageFlags::STORAGE.0);
+    pub const COLOR_ATTACHMENT: Self = Self(ImageUsageFlags::COLOR_ATTACHMENT.0);
+    /// Source of transfer commands, such as mip generation.
+    pub const TRANSFER_SRC: Self = Self(ImageUsageFlags::TRANSFER_SRC.0);
+    /// Destination of transfer commands, such as uploads and mip generation.
+    pub const TRANSFER_DST: Self = Self(ImageUsageFlags::TRANSFER_DST.0);
+
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Both usages, like `|` in constant expressions.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[inline]
+    pub(crate) const fn flags(self) -> ImageUsageFlags {
+        ImageUsageFlags(self.0)
+    }
+}
+
+impl BitOr for TextureUsage {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Parameters of a [`Texture`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TextureDesc {
+    pub dimension: TextureDimension,
+    pub format: mayon_core::Format,
+    pub width: u32,
+    pub height: u32,
+    /// Number of mip levels, the first one being full size.
+    pub mip_levels: u32,
+    pub usage: TextureUsage,
+}
+
+impl TextureDesc {
+    /// Describes a sampled texture with a single mip level.
+    #[inline]
+    pub const fn new(
+        dimension: TextureDimension,
+        format: mayon_core::Format,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        Self {
+            dimension,
+            format,
+            width,
+            height,
+            mip_levels: 1,
+            usage: TextureUsage::SAMPLED,
+        }
+    }
+
+    #[inline]
+    pub const fn with_mip_levels(mut self, mip_levels: u32) -> Self {
+        self.mip_levels = mip_levels;
+        self
+    }
+
+    /// Uses every mip level down to a single texel.
+    #[inline]
+    pub const fn with_full_mip_chain(mut self) -> Self {
+        self.mip_levels = self.full_mip_chain_len();
+        self
+    }
+
+    #[inline]
+    pub const fn with_usage(mut self, usage: TextureUsage) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Number of mip levels down to a single texel.
+    #[inline]
+    pub const fn full_mip_chain_len(&self) -> u32 {
+        let mut largest = self.width;
+
+        if self.height > largest {
+            largest = self.height;
+        }
+
+        if self.dimension.depth() > largest {
+            largest = self.dimension.depth();
+        }
+
+        u32::BITS - largest.leading_zeros()
+    }
+}
+
+/// A device-local image with its memory, and the layout of each of its mip levels.
+///
+/// Layouts are tracked as commands are recorded: operations on the texture transition the
+/// mip levels they use from whatever layout they were left in. Command buffers using the
+/// texture must therefore execute in the order they were recorded in, which holds within a
+/// queue and across queues synchronized with semaphores.
+///
+/// Once uploaded or mip-mapped, sampled textures rest in the shader read-only layout and
+/// storage textures in the general layout. The texture must not be dropped while a pending
+/// submission still uses it.
+pub struct Texture<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    handle: types::Image,
+    /// `None` for textures whose memory is owned by someone else.
+    memory: Option<DeviceAllocation<DeviceMemory>>,
+    /// Whether the image belongs to a swapchain, which destroys it.
+    swapchain_image: bool,
+    desc: TextureDesc,
+    /// Layout of every mip level, as left by the last recorded command.
+    layouts: Mutex<Vec<ImageLayout>>,
+}
+
+impl<'a, L, A> Texture<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates a texture on `device`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EmptyTexture` if any extent is zero, `NonSquareCubeTexture` for cube
+    /// textures with distinct width and height, `TooManyMipLevels` if the mip chain is longer
+    /// than the texture allows, `TextureExceedsLimits` if the device does not support the
+    /// texture's size, or a `VulkanError` if the format is unsupported or creating the image,
+    /// allocating or binding its memory fails.
+    pub fn new(device: &Device<'a, L, A>, desc: &TextureDesc) -> crate::Result<Self> {
+        let mut texture = Self::new_unbound(device, desc)?;
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            let memory = device.allocate_memory(
+                &AllocationRequest::from(texture.memory_requirements())
+                    .with_tiling(Tiling::Optimal),
+                allocator,
+            )?;
+
+            if let Err(err) = texture.bind_memory(memory.memory(), memory.offset()) {
+                device.free_memory(&memory, allocator);
+
+                return Err(err);
+            }
+
+            texture.memory = Some(memory);
+        }
+
+        Ok(texture)
+    }
+
+    /// Creates a texture without memory, which must be bound with
+    /// [`bind_memory`](Self::bind_memory) before use. The caller owns the memory, and frees
+    /// it once the texture is dropped.
+    ///
+    /// # Errors
+    ///
+    /// See [`new`](Self::new), minus the memory allocation.
+    pub(crate) fn new_unbound(
+        device: &Device<'a, L, A>,
+        desc: &TextureDesc,
+    ) -> crate::Result<Self> {
+        let desc = *desc;
+        let layers = desc.dimension.layers();
+        let extent = Extent3D {
+            width: desc.width,
+            height: desc.height,
+            depth: desc.dimension.depth(),
+        };
+
+        if extent.width == 0 || extent.height == 0 || extent.depth == 0 || layers == 0 {
+            return VulkanErrorKind::EmptyTexture.into_result();
+        }
+
+        if desc.dimension == TextureDimension::Cube && desc.width != desc.height {
+            return VulkanErrorKind::NonSquareCubeTexture.into_result();
+        }
+
+        let max_mip_levels = desc.full_mip_chain_len();
+
+        if desc.mip_levels == 0 || desc.mip_levels > max_mip_levels {
+            return VulkanErrorKind::TooManyMipLevels {
+                requested: desc.mip_levels,
+                max: max_mip_levels,
+            }
+            .into_result();
+        }
+
+        let (flags, image_type) = match desc.dimension {
+            TextureDimension::D2 | TextureDimension::D2Array { .. } => {
+                (ImageCreateFlags::EMPTY, ImageType::TYPE_2D)
+            }
+            TextureDimension::Cube => (ImageCreateFlags::CUBE_COMPATIBLE, ImageType::TYPE_2D),
+            TextureDimension::D3 { .. } => (ImageCreateFlags::EMPTY, ImageType::TYPE_3D),
+        };
+        let format = desc.format.into();
+
+        let limits = unsafe {
+            device
+                .instance()
+                .backend()
+                .fns()
+                .get_physical_device_image_format_properties(
+                    device.physical_device,
+                    format,
+                    image_type,
+                    ImageTiling::OPTIMAL,
+                    desc.usage.flags(),
+                    flags,
+                )
+        }?;
+
+        if extent.width > limits.max_extent.width
+            || extent.height > limits.max_extent.height
+            || extent.depth > limits.max_extent.depth
+            || layers > limits.max_array_layers
+            || desc.mip_levels > limits.max_mip_levels
+        {
+            return VulkanErrorKind::TextureExceedsLimits.into_result();
+        }
+
+        let fns = &device.fns;
+        let queue_families = device.queue_families();
+        let info = ImageCreateInfo::new(
+            flags,
+            image_type,
+            format,
+            extent,
+            desc.mip_levels,
+            layers,
+            desc.usage.flags(),
+            &queue_families,
+        );
+
+        let handle = unsafe {
+            fns.create_image(
+                device.handle,
+                &info,
+                device.instance().backend().allocator(),
+            )
+        }?;
+
+        debug!(
+            device.instance().logger(),
+            LogTarget::Backend,
+            "Vulkan texture created: {:?}, {:?} {}x{} {:?}, {} mip levels",
+            handle,
+            desc.dimension,
+            desc.width,
+            desc.height,
+            desc.format,
+            desc.mip_levels
+        );
+
+        Ok(Self {
+            device: device.clone(),
+            handle,
+            memory: None,
+            swapchain_image: false,
+            desc,
+            layouts: Mutex::new(vec![ImageLayout::UNDEFINED; desc.mip_levels as usize]),
+        })
+    }
+
+    /// Wraps `image`, an image of a swapchain described by `desc`. The swapchain keeps
+    /// ownership: dropping the texture leaves the image alive.
+    pub(crate) fn from_swapchain_image(
+        device: &Device<'a, L, A>,
+        image: types::Image,
+        desc: TextureDesc,
+    ) -> Self {
+        Self {
+            device: device.clone(),
+            handle: image,
+            memory: None,
+            swapchain_image: true,
+            desc,
+            layouts: Mutex::new(vec![ImageLayout::UNDEFINED; desc.mip_levels as usize]),
+        }
+    }
+
+    /// Size, alignment and memory types of the memory the texture needs.
+    #[inline]
+    pub(crate) fn memory_requirements(&self) -> MemoryRequirements {
+        unsafe {
+            self.device
+                .fns
+                .get_image_memory_requirements(self.device.handle, self.handle)
+        }
+    }
+
+    /// Binds the texture created by [`new_unbound`](Self::new_unbound) to `offset` bytes into
+    /// `memory`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkBindImageMemory` fails.
+    pub(crate) unsafe fn bind_memory(
+        &self,
+        memory: DeviceMemory,
+        offset: u64,
+    ) -> crate::Result<()> {
+        unsafe {
+            self.device
+                .fns
+                .bind_image_memory(self.device.handle, self.handle, memory, offset)
+        }
+    }
+
+    #[inline]
+    pub fn device(&self) -> &Device<'a, L, A> {
+        &self.device
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> types::Image {
+        self.handle
+    }
+
+    #[inline]
+    pub fn desc(&self) -> &TextureDesc {
+        &self.desc
+    }
+
+    #[inline]
+    pub fn dimension(&self) -> TextureDimension {
+        self.desc.dimension
+    }
+
+    #[inline]
+    pub fn format(&self) -> mayon_core::Format {
+        self.desc.format
+    }
+
+    #[inline]
+    pub fn mip_levels(&self) -> u32 {
+        self.desc.mip_levels
+    }
+
+    #[inline]
+    pub fn usage(&self) -> TextureUsage {
+        self.desc.usage
+    }
+
+    /// Extent of mip level `level` in texels.
+    #[inline]
+    pub(crate) fn level_extent(&self, level: u32) -> Extent3D {
+        Extent3D {
+            width: (self.desc.width >> level).max(1),
+            height: (self.desc.height >> level).max(1),
+            depth: (self.desc.dimension.depth() >> level).max(1),
+        }
+    }
+
+    /// Size in bytes of mip level `level`, with all its array layers.
+    pub fn level_size(&self, level: u32) -> usize {
+        let extent = self.level_extent(level);
+
+        extent.width as usize
+            * extent.height as usize
+            * extent.depth as usize
+            * self.desc.dimension.layers() as usize
+            * self.desc.format.texel_size()
+    }
+
+    /// Layout the texture is left in once written: shader read-only for sampled textures and
+    /// general for storage textures.
+    #[inline]
+    pub(crate) fn resting_layout(&self) -> Option<ImageLayout> {
+        if self.desc.usage.contains(TextureUsage::SAMPLED) {
+            Some(ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+        } else if self.desc.usage.contains(TextureUsage::STORAGE) {
+            Some(ImageLayout::GENERAL)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn lock_layouts(&self) -> MutexGuard<'_, Vec<ImageLayout>> {
+        // Layouts are only replaced wholesale, so a panic while holding the lock leaves none
+        // half-updated.
+        self.layouts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn layout(&self, level: u32) -> ImageLayout {
+        self.lock_layouts()[level as usize]
+    }
+
+    /// Tracked layout of every mip level, to [restore](Self::restore_layouts) if the commands
+    /// recorded after are discarded.
+    #[inline]
+    pub(crate) fn layouts(&self) -> Vec<ImageLayout> {
+        self.lock_layouts().clone()
+    }
+
+    /// Tracks the texture in `layouts` again, as returned by [`Self::layouts`].
+    #[inline]
+    pub(crate) fn restore_layouts(&self, layouts: Vec<ImageLayout>) {
+        *self.lock_layouts() = layouts;
+    }
+
+    /// Moves mip levels `levels` to `new` in the tracked state, and returns the transitions
+    /// commands must record first.
+    pub(crate) fn update_layouts(
+        &self,
+        levels: Range<u32>,
+        new: ImageLayout,
+    ) -> Vec<LayoutTransition> {
+        let mut layouts = self.lock_layouts();
+        let mut transitions = Vec::<LayoutTransition>::new();
+
+        for level in levels {
+            let old = std::mem::replace(&mut layouts[level as usize], new);
+
+            if !LayoutTransition::is_needed(old, new) {
+                continue;
+            }
+
+            match transitions.last_mut() {
+                Some(last) if last.old == old && last.levels.end == level => last.levels.end += 1,
+                _ => transitions.push(LayoutTransition {
+                    image: self.handle,
+                    aspect: ImageAspectFlags::COLOR,
+                    levels: level..level + 1,
+                    layer_count: self.desc.dimension.layers(),
+                    old,
+                    new,
+                }),
+            }
+        }
+
+        transitions
+    }
+
+    /// Records the transitions moving mip levels `levels` to `new`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedCommandBufferState` if `command_buffer` is not recording.
+    pub(crate) fn transition(
+        &self,
+        command_buffer: &mut VulkanCommandBuffer<'_, 'a, L, A>,
+        levels: Range<u32>,
+        new: ImageLayout,
+    ) -> crate::Result<()> {
+        command_buffer.ensure_recording()?;
+
+        LayoutTransition::record(command_buffer, &self.update_layouts(levels, new))
+    }
+
+    /// Records the transition of every mip level to the general layout, which shaders need
+    /// to access the texture through a storage image binding.
+    ///
+    /// Render graphs transition the textures they use themselves; this is for commands
+    /// recorded outside of one, such as compute dispatches.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingTextureUsage` if the texture was not created with
+    /// [`STORAGE`](TextureUsage::STORAGE), or `UnexpectedCommandBufferState` if
+    /// `command_buffer` is not recording.
+    pub fn prepare_storage(
+        &self,
+        command_buffer: &mut VulkanCommandBuffer<'_, 'a, L, A>,
+    ) -> crate::Result<()> {
+        if !self.usage().contains(TextureUsage::STORAGE) {
+            return VulkanErrorKind::MissingTextureUsage {
+                usage: TextureUsage::STORAGE,
+            }
+            .into_result();
+        }
+
+        self.transition(command_buffer, 0..self.mip_levels(), ImageLayout::GENERAL)
+    }
+
+    /// Records commands filling every mip level after the first by repeatedly halving the one
+    /// before, and leaves the texture in its resting layout.
+    ///
+    /// The first mip level must have been written, for example by a [`StagingRing`] upload
+    /// whose flush is waited for. Blits need a graphics queue, so `command_buffer` must come
+    /// from a graphics command pool. Does nothing for textures with a single mip level.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingTextureUsage` if the texture was not created with both
+    /// [`TRANSFER_SRC`](TextureUsage::TRANSFER_SRC) and
+    /// [`TRANSFER_DST`](TextureUsage::TRANSFER_DST), `UnsupportedMipmapQueue` if
+    /// `command_buffer` is not for the graphics queue, `UnsupportedMipmapFormat` if the
+    /// device cannot blit the texture's format, or `UnexpectedCommandBufferState` if
+    /// `command_buffer` is not recording.
+    ///
+    /// [`StagingRing`]: crate::StagingRing
+    pub fn generate_mips(
+        &self,
+        command_buffer: &mut VulkanCommandBuffer<'_, 'a, L, A>,
+    ) -> crate::Result<()> {
+        if self.desc.mip_levels == 1 {
+            return Ok(());
+        }
+
+        for usage in [TextureUsage::TRANSFER_SRC, TextureUsage::TRANSFER_DST] {
+            if !self.desc.usage.contains(usage) {
+                return VulkanErrorKind::MissingTextureUsage { usage }.into_result();
+            }
+        }
+
+        let kind = command_buffer.pool().queue();
+
+        if kind != QueueKind::Graphics {
+            return VulkanErrorKind::UnsupportedMipmapQueue { kind }.into_result();
+        }
+
+        let features = unsafe {
+            self.device
+                .instance()
+                .backend()
+                .fns()
+                .get_physical_device_format_properties(
+                    self.device.physical_device,
+                    self.desc.format.into(),
+                )
+        }
+        .optimal_tiling_features;
+
+        if !features.contains(FormatFeatureFlags(
+            FormatFeatureFlags::BLIT_SRC.0 | FormatFeatureFlags::BLIT_DST.0,
+        )) {
+            return VulkanErrorKind::UnsupportedMipmapFormat {
+                format: self.desc.format,
+            }
+            .into_result();
+        }
+
+        let filter = if features.contains(FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR) {
+            Filter::LINEAR
+        } else {
+            Filter::NEAREST
+        };
+
+        command_buffer.ensure_recording()?;
+
+        let layers = self.desc.dimension.layers();
+
+        for level in 1..self.desc.mip_levels {
+            self.transition(
+                command_buffer,
+                level - 1..level,
+                ImageLayout::TRANSFER_SRC_OPTIMAL,
+            )?;
+            self.transition(
+                command_buffer,
+                level..level + 1,
+                ImageLayout::TRANSFER_DST_OPTIMAL,
+            )?;
+
+            let corner = |extent: Extent3D| Offset3D {
+                x: extent.width as i32,
+                y: extent.height as i32,
+                z: extent.depth as i32,
+            };
+
+            let blit = ImageBlit {
+                src_subresource: ImageSubresourceLayers::color(level - 1, layers),
+                src_offsets: [Offset3D::default(), corner(self.level_extent(level - 1))],
+                dst_subresource: ImageSubresourceLayers::color(level, layers),
+                dst_offsets: [Offset3D::default(), corner(self.level_extent(level))],
+            };
+
+            command_buffer.blit_image(
+                (self.handle, ImageLayout::TRANSFER_SRC_OPTIMAL),
+                (self.handle, ImageLayout::TRANSFER_DST_OPTIMAL),
+                &[blit],
+                filter,
+            )?;
+        }
+
+        if let Some(layout) = self.resting_layout() {
+            self.transition(command_buffer, 0..self.desc.mip_levels, layout)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<L, A> Drop for Texture<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        unsafe {
+            let allocator = self.device.instance().backend().allocator();
+
+            if !self.swapchain_image {
+                self.device
+                    .fns
+                    .destroy_image(self.device.handle, self.handle, allocator);
+            }
+
+            if let Some(memory) = &self.memory {
+                self.device.free_memory(memory, allocator);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::{BaseError, CommandBuffer, CommandBufferUsage, Format};
+
+    use super::*;
+    use crate::{
+        VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+    };
+
+    #[test]
+    fn test_texture_desc() {
+        let desc = TextureDesc::new(TextureDimension::D2, Format::Rgba8Unorm, 256, 100);
+
+        assert_eq!(desc.full_mip_chain_len(), 9);
+        assert_eq!(desc.with_full_mip_chain().mip_levels, 9);
+
+        let volume = TextureDesc::new(TextureDimension::D3 { depth: 1024 }, Format::R8Unorm, 4, 4);
+
+        assert_eq!(volume.full_mip_chain_len(), 11);
+        assert_eq!(TextureDimension::Cube.layers(), 6);
+    }
+
+    #[test]
+    fn test_create_texture() {
+        let device = mock::device();
+
+        let desc = TextureDesc::new(TextureDimension::Cube, Format::Rgba8Srgb, 64, 64);
+        let cube = Texture::new(&device, &desc).unwrap();
+
+        assert_eq!(cube.level_size(0), 64 * 64 * 6 * 4);
+        assert_eq!(cube.layout(0), ImageLayout::UNDEFINED);
+
+        let volume = Texture::new(
+            &device,
+            &TextureDesc::new(TextureDimension::D3 { depth: 16 }, Format::R8Unorm, 16, 8)
+                .with_full_mip_chain(),
+        )
+        .unwrap();
+
+        assert_eq!(volume.mip_levels(), 5);
+        assert_eq!(
+            volume.level_extent(4),
+            Extent3D {
+                width: 1,
+                height: 1,
+                depth: 1
+            }
+        );
+
+        let errors = [
+            (
+                TextureDesc::new(
+                    TextureDimension::D2Array { layers: 0 },
+                    Format::R8Unorm,
+                    4,
+                    4,
+                ),
+                VulkanErrorKind::EmptyTexture,
+            ),
+            (
+                TextureDesc::new(TextureDimension::Cube, Format::R8Unorm, 4, 8),
+                VulkanErrorKind::NonSquareCubeTexture,
+            ),
+            (
+                TextureDesc::new(TextureDimension::D2, Format::R8Unorm, 4, 4).with_mip_levels(4),
+                VulkanErrorKind::TooManyMipLevels {
+                    requested: 4,
+                    max: 3,
+                },
+            ),
+            (
+                TextureDesc::new(TextureDimension::D2, Format::R8Unorm, 32768, 4),
+                VulkanErrorKind::TextureExceedsLimits,
+            ),
+        ];
+
+        for (desc, expected) in errors {
+            let Err(err) = Texture::new(&device, &desc) else {
+                panic!("Creating {desc:?} should fail");
+            };
+
+            assert_eq!(
+                format!("{:?}", err.kind()),
+                format!("{expected:?}"),
+                "{desc:?}"
+            );
+        }
+
+        drop(cube);
+        drop(volume);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+
+    #[test]
+    fn test_generate_mips() {
+        let device = mock::device();
+
+        let desc = TextureDesc::new(
+            TextureDimension::D2Array { layers: 3 },
+            Format::Rgba8Unorm,
+            16,
+            8,
+        )
+        .with_full_mip_chain();
+
+        let plain = Texture::new(&device, &desc).unwrap();
+        let texture = Texture::new(
+            &device,
+            &desc.with_usage(
+                TextureUsage::SAMPLED | TextureUsage::TRANSFER_SRC | TextureUsage::TRANSFER_DST,
+            ),
+        )
+        .unwrap();
+
+        let graphics = device.create_command_pool(QueueKind::Graphics).unwrap();
+        let transfer = device.create_command_pool(QueueKind::Transfer).unwrap();
+
+        {
+            let mut command_buffer = transfer.allocate().unwrap();
+
+            command_buffer
+                .begin(CommandBufferUsage::OneTimeSubmit)
+                .unwrap();
+
+            assert!(matches!(
+                plain.generate_mips(&mut command_buffer).unwrap_err().kind(),
+                VulkanErrorKind::MissingTextureUsage {
+                    usage: TextureUsage::TRANSFER_SRC
+                }
+            ));
+            assert!(matches!(
+                texture
+                    .generate_mips(&mut command_buffer)
+                    .unwrap_err()
+                    .kind(),
+                VulkanErrorKind::UnsupportedMipmapQueue {
+                    kind: QueueKind::Transfer
+                }
+            ));
+        }
+
+        let mut command_buffer = graphics.allocate().unwrap();
+
+        command_buffer
+            .begin(CommandBufferUsage::OneTimeSubmit)
+            .unwrap();
+        texture.generate_mips(&mut command_buffer).unwrap();
+        command_buffer.end().unwrap();
+
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::CmdBlitImage), 4);
+
+        for level in 0..5 {
+            assert_eq!(texture.layout(level), ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+        }
+
+        // Sampled levels are read again without a transition.
+        command_buffer
+            .begin(CommandBufferUsage::OneTimeSubmit)
+            .unwrap();
+
+        let barriers = MockDriver::call_count(VulkanFunctionName::CmdPipelineBarrier);
+
+        texture
+            .transition(
+                &mut command_buffer,
+                0..5,
+                ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            )
+            .unwrap();
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::CmdPipelineBarrier),
+            barriers
+        );
+
+        MockDriver::assert_valid_layouts();
+
+        drop(command_buffer);
+        drop(graphics);
+        drop(transfer);
+        drop(plain);
+        drop(texture);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
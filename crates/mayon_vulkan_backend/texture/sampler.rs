@@ -0,0 +1,247 @@
+use core::marker::PhantomData;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+use allocator::Allocator;
+use mayon_core::logger::Logger;
+
+use crate::{
+    Device,
+    types::{self, Filter, SamplerAddressMode, SamplerCreateInfo, SamplerMipmapMode},
+};
+
+/// How texels are filtered when a texture is magnified or minified.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FilterMode {
+    Nearest,
+    Linear,
+}
+
+impl From<FilterMode> for Filter {
+    #[inline]
+    fn from(mode: FilterMode) -> Self {
+        match mode {
+            FilterMode::Nearest => Self::NEAREST,
+            FilterMode::Linear => Self::LINEAR,
+        }
+    }
+}
+
+/// How samples of neighbouring mip levels are combined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MipmapMode {
+    Nearest,
+    Linear,
+}
+
+impl From<MipmapMode> for SamplerMipmapMode {
+    #[inline]
+    fn from(mode: MipmapMode) -> Self {
+        match mode {
+            MipmapMode::Nearest => Self::NEAREST,
+            MipmapMode::Linear => Self::LINEAR,
+        }
+    }
+}
+
+/// How coordinates outside of the texture are resolved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AddressMode {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+impl From<AddressMode> for SamplerAddressMode {
+    #[inline]
+    fn from(mode: AddressMode) -> Self {
+        match mode {
+            AddressMode::Repeat => Self::REPEAT,
+            AddressMode::MirroredRepeat => Self::MIRRORED_REPEAT,
+            AddressMode::ClampToEdge => Self::CLAMP_TO_EDGE,
+        }
+    }
+}
+
+/// Parameters of a [`Sampler`]. The default filters linearly and repeats the texture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SamplerDesc {
+    pub mag_filter: FilterMode,
+    pub min_filter: FilterMode,
+    pub mipmap_mode: MipmapMode,
+    /// Addressing along every axis.
+    pub address_mode: AddressMode,
+}
+
+impl Default for SamplerDesc {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_mode: MipmapMode::Linear,
+            address_mode: AddressMode::Repeat,
+        }
+    }
+}
+
+/// A sampler owned by the [`SamplerCache`] it was obtained from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sampler<'c> {
+    handle: types::Sampler,
+    _marker: PhantomData<&'c ()>,
+}
+
+impl Sampler<'_> {
+    #[inline]
+    pub(crate) fn handle(&self) -> types::Sampler {
+        self.handle
+    }
+}
+
+/// Creates samplers on demand and hands out the same one for equal [`SamplerDesc`]s.
+///
+/// Devices support a limited number of samplers, so the few distinct ones an application
+/// needs are created once and shared. Samplers live until the cache is dropped.
+pub struct SamplerCache<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    device: Device<'a, L, A>,
+    samplers: Mutex<HashMap<SamplerDesc, types::Sampler>>,
+}
+
+impl<'a, L, A> SamplerCache<'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    #[inline]
+    pub fn new(device: &Device<'a, L, A>) -> Self {
+        Self {
+            device: device.clone(),
+            samplers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the sampler for `desc`, creating it on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkCreateSampler` fails.
+    pub fn get(&self, desc: &SamplerDesc) -> crate::Result<Sampler<'_>> {
+        // Creating a sampler under the lock keeps two threads from creating the same one.
+        let mut samplers = self.samplers.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let handle = match samplers.get(desc) {
+            Some(&handle) => handle,
+            None => {
+                let info = SamplerCreateInfo::new(
+                    desc.mag_filter.into(),
+                    desc.min_filter.into(),
+                    desc.mipmap_mode.into(),
+                    desc.address_mode.into(),
+                );
+
+                let handle = unsafe {
+                    self.device.fns.create_sampler(
+                        self.device.handle,
+                        &info,
+                        self.device.instance().backend().allocator(),
+                    )
+                }?;
+
+                samplers.insert(*desc, handle);
+
+                handle
+            }
+        };
+
+        Ok(Sampler {
+            handle,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Number of distinct samplers created so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.samplers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<L, A> Drop for SamplerCache<'_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        let device = &self.device;
+        let samplers = self
+            .samplers
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            for (_, sampler) in samplers.drain() {
+                device
+                    .fns
+                    .destroy_sampler(device.handle, sampler, allocator);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{
+        VulkanFunctionName,
+        fn_table::{MockDriver, mock},
+    };
+
+    #[test]
+    fn test_sampler_cache() {
+        let device = mock::device();
+
+        let cache = SamplerCache::new(&device);
+
+        let linear = cache.get(&SamplerDesc::default()).unwrap();
+        let pixelated = SamplerDesc {
+            mag_filter: FilterMode::Nearest,
+            mipmap_mode: MipmapMode::Nearest,
+            address_mode: AddressMode::ClampToEdge,
+            ..SamplerDesc::default()
+        };
+
+        assert_ne!(cache.get(&pixelated).unwrap(), linear);
+        assert_eq!(cache.get(&SamplerDesc::default()).unwrap(), linear);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(MockDriver::call_count(VulkanFunctionName::CreateSampler), 2);
+
+        drop(cache);
+
+        assert_eq!(
+            MockDriver::call_count(VulkanFunctionName::DestroySampler),
+            2
+        );
+
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
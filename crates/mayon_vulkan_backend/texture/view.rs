@@ -0,0 +1,228 @@
+use core::ops::Range;
+
+use allocator::Allocator;
+use mayon_core::logger::Logger;
+
+use super::{Texture, TextureDimension};
+use crate::{
+    Device, VulkanErrorKind,
+    types::{self, ImageAspectFlags, ImageSubresourceRange, ImageViewCreateInfo, ImageViewType},
+};
+
+/// A view of a range of mip levels and array layers of a [`Texture`], as bound to shaders.
+///
+/// Views of a whole cube texture are cube views, views of a 3D texture are 3D views, and
+/// other views are 2D views, or 2D array views when they span several layers.
+pub struct TextureView<'t, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    texture: &'t Texture<'a, L, A>,
+    handle: types::ImageView,
+    levels: Range<u32>,
+    layers: Range<u32>,
+    /// Whether dropping the view destroys its handle.
+    owned: bool,
+}
+
+impl<'t, 'a, L, A> TextureView<'t, 'a, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    /// Creates a view of every mip level and layer of `texture`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `VulkanError` if `vkCreateImageView` fails.
+    #[inline]
+    pub fn new(texture: &'t Texture<'a, L, A>) -> crate::Result<Self> {
+        Self::with_range(
+            texture,
+            0..texture.mip_levels(),
+            0..texture.dimension().layers(),
+        )
+    }
+
+    /// Creates a view of mip levels `levels` and array layers `layers` of `texture`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `TextureViewOutOfRange` if either range is empty or reaches past the
+    /// texture's, or a `VulkanError` if `vkCreateImageView` fails.
+    pub fn with_range(
+        texture: &'t Texture<'a, L, A>,
+        levels: Range<u32>,
+        layers: Range<u32>,
+    ) -> crate::Result<Self> {
+        let layer_count = texture.dimension().layers();
+
+        if levels.is_empty()
+            || levels.end > texture.mip_levels()
+            || layers.is_empty()
+            || layers.end > layer_count
+        {
+            return VulkanErrorKind::TextureViewOutOfRange.into_result();
+        }
+
+        let view_type = match texture.dimension() {
+            TextureDimension::D3 { .. } => ImageViewType::TYPE_3D,
+            TextureDimension::Cube if layers == (0..layer_count) => ImageViewType::CUBE,
+            _ if layers.len() == 1 => ImageViewType::TYPE_2D,
+            _ => ImageViewType::TYPE_2D_ARRAY,
+        };
+
+        let info = ImageViewCreateInfo::new(
+            texture.handle(),
+            view_type,
+            texture.format().into(),
+            ImageSubresourceRange {
+                aspect_mask: ImageAspectFlags::COLOR,
+                base_mip_level: levels.start,
+                level_count: levels.end - levels.start,
+                base_array_layer: layers.start,
+                layer_count: layers.end - layers.start,
+            },
+        );
+
+        let device = texture.device();
+
+        let handle = unsafe {
+            device.fns.create_image_view(
+                device.handle,
+                &info,
+                device.instance().backend().allocator(),
+            )
+        }?;
+
+        Ok(Self {
+            texture,
+            handle,
+            levels,
+            layers,
+            owned: true,
+        })
+    }
+
+    /// Wraps `handle`, a view of every mip level and layer of `texture` owned by someone
+    /// else: dropping the returned view leaves `handle` alive.
+    #[inline]
+    pub(crate) fn borrowed(texture: &'t Texture<'a, L, A>, handle: types::ImageView) -> Self {
+        Self {
+            texture,
+            handle,
+            levels: 0..texture.mip_levels(),
+            layers: 0..texture.dimension().layers(),
+            owned: false,
+        }
+    }
+
+    /// Gives up ownership of the view's handle, which the caller must destroy with
+    /// [`destroy_handle`](Self::destroy_handle).
+    #[inline]
+    pub(crate) fn into_handle(mut self) -> types::ImageView {
+        self.owned = false;
+        self.handle
+    }
+
+    /// Destroys `handle`, a view of an image of `device`, and forgets the framebuffers
+    /// using it.
+    pub(crate) unsafe fn destroy_handle(device: &Device<'a, L, A>, handle: types::ImageView) {
+        unsafe {
+            let allocator = device.instance().backend().allocator();
+
+            device.forget_image_view(handle, allocator);
+            device
+                .fns
+                .destroy_image_view(device.handle, handle, allocator);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn handle(&self) -> types::ImageView {
+        self.handle
+    }
+
+    #[inline]
+    pub fn texture(&self) -> &'t Texture<'a, L, A> {
+        self.texture
+    }
+
+    /// Mip levels of the texture covered by the view.
+    #[inline]
+    pub fn levels(&self) -> Range<u32> {
+        self.levels.clone()
+    }
+
+    /// Array layers of the texture covered by the view.
+    #[inline]
+    pub fn layers(&self) -> Range<u32> {
+        self.layers.clone()
+    }
+}
+
+impl<L, A> Drop for TextureView<'_, '_, L, A>
+where
+    L: Logger,
+    A: Allocator + 'static,
+{
+    fn drop(&mut self) {
+        if self.owned {
+            unsafe { Self::destroy_handle(self.texture.device(), self.handle) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mayon_core::{BaseError, Format};
+
+    use super::*;
+    use crate::{
+        TextureDesc,
+        fn_table::{MockDriver, MockHandleKind, mock},
+    };
+
+    #[test]
+    fn test_texture_views() {
+        let device = mock::device();
+
+        let cube = Texture::new(
+            &device,
+            &TextureDesc::new(TextureDimension::Cube, Format::Rgba8Unorm, 32, 32)
+                .with_full_mip_chain(),
+        )
+        .unwrap();
+
+        let full = TextureView::new(&cube).unwrap();
+        let face = TextureView::with_range(&cube, 2..3, 4..5).unwrap();
+
+        assert_eq!(full.levels(), 0..6);
+        assert_eq!(face.layers(), 4..5);
+        assert_eq!(
+            MockDriver::live_handles()
+                .iter()
+                .filter(|handle| handle.kind == MockHandleKind::ImageView)
+                .count(),
+            2
+        );
+
+        for (levels, layers) in [(0..7, 0..6), (0..1, 6..7), (1..1, 0..6)] {
+            assert!(matches!(
+                TextureView::with_range(&cube, levels, layers)
+                    .err()
+                    .unwrap()
+                    .kind(),
+                VulkanErrorKind::TextureViewOutOfRange
+            ));
+        }
+
+        drop(full);
+        drop(face);
+        drop(cube);
+        drop(device);
+
+        MockDriver::assert_no_leaks();
+    }
+}
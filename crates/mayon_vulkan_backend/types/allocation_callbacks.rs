@@ -15,6 +15,11 @@ pub(crate) struct AllocationCallbacks<'a, A> {
 
 pub(crate) type AllocationCallbacksRef<'a> = NonNull<AllocationCallbacks<'a, ()>>;
 
+// SAFETY: The callbacks only ever reach `allocator` through a shared reference, from whichever
+// thread the driver calls them on, which is sound as long as `A` may be shared between threads.
+unsafe impl<A: Sync> Send for AllocationCallbacks<'_, A> {}
+unsafe impl<A: Sync> Sync for AllocationCallbacks<'_, A> {}
+
 impl<'a, A> AllocationCallbacks<'a, A>
 where
     A: Allocator,
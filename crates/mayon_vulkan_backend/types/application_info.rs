@@ -20,27 +20,26 @@ pub(crate) struct ApplicationInfo<'a> {
     pub _marker: PhantomData<&'a ()>,
 }
 
-const VULKAN_API_VERSION: u32 = VulkanVersion::new(1, 2, 0).raw();
-
 impl ApplicationInfo<'_> {
     /// Builds an FFI-compatible ApplicationInfo from the provided VulkanBackendParams.
     ///
     /// The returned struct has its `struct_type` set to `StructureType::ApplicationInfo`,
     /// `next` set to null, optional `application_name` and `engine_name` converted to raw
     /// C string pointers (or null when absent), version fields populated from the
-    /// provided version wrappers, and `api_version` set to the crate's `VULKAN_API_VERSION`.
+    /// provided version wrappers, and `api_version` set to the negotiated `api_version`.
     ///
     /// # Returns
     ///
     /// An `ApplicationInfo` populated from the provided parameters and ready for use in Vulkan FFI.
     pub(crate) fn new(
-        VulkanBackendParams {
+        &VulkanBackendParams {
             application_name,
             application_version,
             engine_name,
             engine_version,
-            target_platform: _,
-        }: VulkanBackendParams,
+            ..
+        }: &VulkanBackendParams,
+        api_version: VulkanVersion,
     ) -> Self {
         Self {
             struct_type: StructureType::ApplicationInfo,
@@ -49,7 +48,7 @@ impl ApplicationInfo<'_> {
             application_version: application_version.raw(),
             engine_name: engine_name.map(CStr::as_ptr).unwrap_or_else(null),
             engine_version: engine_version.raw(),
-            api_version: VULKAN_API_VERSION,
+            api_version: api_version.raw(),
             _marker: PhantomData,
         }
     }
@@ -0,0 +1,165 @@
+use core::{ffi::c_void, ptr::null};
+
+use super::{Image, ImageLayout, StructureType};
+
+/// `VK_QUEUE_FAMILY_IGNORED`, for barriers that do not transfer queue family ownership.
+pub(crate) const QUEUE_FAMILY_IGNORED: u32 = !0;
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PipelineStageFlags(pub(crate) u32);
+
+impl PipelineStageFlags {
+    pub(crate) const TOP_OF_PIPE: Self = Self(0x1);
+    pub(crate) const DRAW_INDIRECT: Self = Self(0x2);
+    pub(crate) const VERTEX_INPUT: Self = Self(0x4);
+    pub(crate) const EARLY_FRAGMENT_TESTS: Self = Self(0x100);
+    pub(crate) const LATE_FRAGMENT_TESTS: Self = Self(0x200);
+    pub(crate) const COLOR_ATTACHMENT_OUTPUT: Self = Self(0x400);
+    pub(crate) const TRANSFER: Self = Self(0x1000);
+    pub(crate) const BOTTOM_OF_PIPE: Self = Self(0x2000);
+    pub(crate) const ALL_COMMANDS: Self = Self(0x10000);
+
+    #[inline]
+    pub(crate) const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[inline]
+    pub(crate) const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct AccessFlags(pub(crate) u32);
+
+impl AccessFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+    pub(crate) const INDIRECT_COMMAND_READ: Self = Self(0x1);
+    pub(crate) const INDEX_READ: Self = Self(0x2);
+    pub(crate) const VERTEX_ATTRIBUTE_READ: Self = Self(0x4);
+    pub(crate) const UNIFORM_READ: Self = Self(0x8);
+    pub(crate) const SHADER_READ: Self = Self(0x20);
+    pub(crate) const SHADER_WRITE: Self = Self(0x40);
+    pub(crate) const COLOR_ATTACHMENT_READ: Self = Self(0x80);
+    pub(crate) const COLOR_ATTACHMENT_WRITE: Self = Self(0x100);
+    pub(crate) const DEPTH_STENCIL_ATTACHMENT_READ: Self = Self(0x200);
+    pub(crate) const DEPTH_STENCIL_ATTACHMENT_WRITE: Self = Self(0x400);
+    pub(crate) const TRANSFER_READ: Self = Self(0x800);
+    pub(crate) const TRANSFER_WRITE: Self = Self(0x1000);
+
+    #[inline]
+    pub(crate) const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[inline]
+    pub(crate) const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct DependencyFlags(pub(crate) u32);
+
+impl DependencyFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ImageAspectFlags(pub(crate) u32);
+
+impl ImageAspectFlags {
+    pub(crate) const COLOR: Self = Self(0x1);
+    pub(crate) const DEPTH: Self = Self(0x2);
+    pub(crate) const STENCIL: Self = Self(0x4);
+
+    #[inline]
+    pub(crate) const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ImageSubresourceRange {
+    pub aspect_mask: ImageAspectFlags,
+    pub base_mip_level: u32,
+    pub level_count: u32,
+    pub base_array_layer: u32,
+    pub layer_count: u32,
+}
+
+impl ImageSubresourceRange {
+    /// The first mip level and array layer of a color image.
+    pub(crate) const COLOR: Self = Self {
+        aspect_mask: ImageAspectFlags::COLOR,
+        base_mip_level: 0,
+        level_count: 1,
+        base_array_layer: 0,
+        layer_count: 1,
+    };
+}
+
+/// Makes memory accesses of the source stages available to the destination stages, for
+/// every resource at once.
+#[repr(C)]
+pub(crate) struct MemoryBarrier {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub src_access_mask: AccessFlags,
+    pub dst_access_mask: AccessFlags,
+}
+
+impl MemoryBarrier {
+    #[inline]
+    pub fn new(src_access_mask: AccessFlags, dst_access_mask: AccessFlags) -> Self {
+        Self {
+            struct_type: StructureType::MemoryBarrier,
+            next: null(),
+            src_access_mask,
+            dst_access_mask,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct ImageMemoryBarrier {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub src_access_mask: AccessFlags,
+    pub dst_access_mask: AccessFlags,
+    pub old_layout: ImageLayout,
+    pub new_layout: ImageLayout,
+    pub src_queue_family_index: u32,
+    pub dst_queue_family_index: u32,
+    pub image: Image,
+    pub subresource_range: ImageSubresourceRange,
+}
+
+impl ImageMemoryBarrier {
+    /// Transitions `subresource_range` of `image` between layouts on the same queue family.
+    pub fn new(
+        image: Image,
+        subresource_range: ImageSubresourceRange,
+        (src_access_mask, old_layout): (AccessFlags, ImageLayout),
+        (dst_access_mask, new_layout): (AccessFlags, ImageLayout),
+    ) -> Self {
+        Self {
+            struct_type: StructureType::ImageMemoryBarrier,
+            next: null(),
+            src_access_mask,
+            dst_access_mask,
+            old_layout,
+            new_layout,
+            src_queue_family_index: QUEUE_FAMILY_IGNORED,
+            dst_queue_family_index: QUEUE_FAMILY_IGNORED,
+            image,
+            subresource_range,
+        }
+    }
+}
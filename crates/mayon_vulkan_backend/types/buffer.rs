@@ -0,0 +1,79 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::{SharingMode, StructureType};
+
+#[repr(C)]
+pub(crate) struct BufferCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: BufferCreateFlags,
+    pub size: u64,
+    pub usage: BufferUsageFlags,
+    pub sharing_mode: SharingMode,
+    pub queue_family_index_count: u32,
+    pub queue_family_indices: *const u32,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> BufferCreateInfo<'a> {
+    /// Creates a buffer of `size` bytes usable by the queue families in `queue_families`.
+    ///
+    /// The buffer is shared concurrently when more than one family is given, so no ownership
+    /// transfers are needed between them.
+    pub fn new(size: u64, usage: BufferUsageFlags, queue_families: &'a [u32]) -> Self {
+        let (sharing_mode, queue_families) = match queue_families {
+            [_, _, ..] => (SharingMode::CONCURRENT, queue_families),
+            _ => (SharingMode::EXCLUSIVE, &[][..]),
+        };
+
+        Self {
+            struct_type: StructureType::BufferCreateInfo,
+            next: null(),
+            flags: BufferCreateFlags::EMPTY,
+            size,
+            usage,
+            sharing_mode,
+            queue_family_index_count: queue_families.len() as u32,
+            queue_family_indices: queue_families.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct BufferCreateFlags(pub(crate) u32);
+
+impl BufferCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct BufferUsageFlags(pub(crate) u32);
+
+impl BufferUsageFlags {
+    pub(crate) const TRANSFER_SRC: Self = Self(0x1);
+    pub(crate) const TRANSFER_DST: Self = Self(0x2);
+    pub(crate) const UNIFORM_BUFFER: Self = Self(0x10);
+    pub(crate) const STORAGE_BUFFER: Self = Self(0x20);
+    pub(crate) const INDEX_BUFFER: Self = Self(0x40);
+    pub(crate) const VERTEX_BUFFER: Self = Self(0x80);
+    pub(crate) const INDIRECT_BUFFER: Self = Self(0x100);
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct BufferCopy {
+    pub src_offset: u64,
+    pub dst_offset: u64,
+    pub size: u64,
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct MemoryMapFlags(pub(crate) u32);
+
+impl MemoryMapFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
@@ -0,0 +1,112 @@
+use core::{ffi::c_void, ptr::null};
+
+use super::{CommandPool, StructureType};
+
+#[repr(C)]
+pub(crate) struct CommandPoolCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: CommandPoolCreateFlags,
+    pub queue_family_index: u32,
+}
+
+impl CommandPoolCreateInfo {
+    /// Creates a pool for queues of `queue_family_index`.
+    pub fn new(flags: CommandPoolCreateFlags, queue_family_index: u32) -> Self {
+        Self {
+            struct_type: StructureType::CommandPoolCreateInfo,
+            next: null(),
+            flags,
+            queue_family_index,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CommandPoolCreateFlags(pub(crate) u32);
+
+impl CommandPoolCreateFlags {
+    pub const RESET_COMMAND_BUFFER: Self = Self(0x2);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CommandPoolResetFlags(pub(crate) u32);
+
+impl CommandPoolResetFlags {
+    pub const EMPTY: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CommandBufferResetFlags(pub(crate) u32);
+
+impl CommandBufferResetFlags {
+    pub const EMPTY: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CommandBufferLevel(pub(crate) u32);
+
+impl CommandBufferLevel {
+    pub const PRIMARY: Self = Self(0);
+    #[allow(unused)]
+    pub const SECONDARY: Self = Self(1);
+}
+
+#[repr(C)]
+pub(crate) struct CommandBufferAllocateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub command_pool: CommandPool,
+    pub level: CommandBufferLevel,
+    pub command_buffer_count: u32,
+}
+
+impl CommandBufferAllocateInfo {
+    /// Allocates `command_buffer_count` buffers of `level` from `command_pool`.
+    pub fn new(
+        command_pool: CommandPool,
+        level: CommandBufferLevel,
+        command_buffer_count: u32,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::CommandBufferAllocateInfo,
+            next: null(),
+            command_pool,
+            level,
+            command_buffer_count,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CommandBufferUsageFlags(pub(crate) u32);
+
+impl CommandBufferUsageFlags {
+    pub const EMPTY: Self = Self(0);
+    pub const ONE_TIME_SUBMIT: Self = Self(0x1);
+}
+
+#[repr(C)]
+pub(crate) struct CommandBufferBeginInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: CommandBufferUsageFlags,
+    pub inheritance_info: *const c_void,
+}
+
+impl CommandBufferBeginInfo {
+    /// Begins a primary command buffer, so no inheritance info is given.
+    pub fn new(flags: CommandBufferUsageFlags) -> Self {
+        Self {
+            struct_type: StructureType::CommandBufferBeginInfo,
+            next: null(),
+            flags,
+            inheritance_info: null(),
+        }
+    }
+}
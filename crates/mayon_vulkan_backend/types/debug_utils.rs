@@ -0,0 +1,87 @@
+use core::ffi::{c_char, c_void};
+
+use super::StructureType;
+
+pub(crate) type PfnDebugUtilsMessengerCallback = unsafe extern "system" fn(
+    message_severity: DebugUtilsMessageSeverityFlags,
+    message_types: DebugUtilsMessageTypeFlags,
+    callback_data: *const DebugUtilsMessengerCallbackData,
+    user_data: *mut c_void,
+) -> u32;
+
+#[repr(C)]
+pub(crate) struct DebugUtilsMessengerCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub message_severity: DebugUtilsMessageSeverityFlags,
+    pub message_type: DebugUtilsMessageTypeFlags,
+    pub user_callback: PfnDebugUtilsMessengerCallback,
+    pub user_data: *mut c_void,
+}
+
+impl DebugUtilsMessengerCreateInfo {
+    /// Subscribes `user_callback` to every severity and message type.
+    pub fn new(user_callback: PfnDebugUtilsMessengerCallback, user_data: *mut c_void) -> Self {
+        Self {
+            struct_type: StructureType::DebugUtilsMessengerCreateInfoExt,
+            next: core::ptr::null(),
+            flags: 0,
+            message_severity: DebugUtilsMessageSeverityFlags::ALL,
+            message_type: DebugUtilsMessageTypeFlags::ALL,
+            user_callback,
+            user_data,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct DebugUtilsMessengerCallbackData {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub message_id_name: *const c_char,
+    pub message_id_number: i32,
+    pub message: *const c_char,
+    pub queue_label_count: u32,
+    pub queue_labels: *const c_void,
+    pub cmd_buf_label_count: u32,
+    pub cmd_buf_labels: *const c_void,
+    pub object_count: u32,
+    pub objects: *const c_void,
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct DebugUtilsMessageSeverityFlags(pub(crate) u32);
+
+impl DebugUtilsMessageSeverityFlags {
+    #[allow(unused)]
+    pub(crate) const VERBOSE: Self = Self(0x1);
+    pub(crate) const INFO: Self = Self(0x10);
+    pub(crate) const WARNING: Self = Self(0x100);
+    pub(crate) const ERROR: Self = Self(0x1000);
+    pub(crate) const ALL: Self = Self(0x1111);
+
+    #[inline]
+    pub(crate) const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct DebugUtilsMessageTypeFlags(pub(crate) u32);
+
+impl DebugUtilsMessageTypeFlags {
+    #[allow(unused)]
+    pub(crate) const GENERAL: Self = Self(0x1);
+    pub(crate) const VALIDATION: Self = Self(0x2);
+    pub(crate) const PERFORMANCE: Self = Self(0x4);
+    pub(crate) const ALL: Self = Self(0x7);
+
+    #[inline]
+    pub(crate) const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
@@ -0,0 +1,265 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::{
+    Buffer, DescriptorPool, DescriptorSet, DescriptorSetLayout,
+    DescriptorSetLayoutBindingFlagsCreateInfo, ImageLayout, ImageView, Sampler, ShaderStageFlags,
+    StructureType,
+};
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct DescriptorType(pub(crate) i32);
+
+impl DescriptorType {
+    pub(crate) const SAMPLER: Self = Self(0);
+    pub(crate) const COMBINED_IMAGE_SAMPLER: Self = Self(1);
+    pub(crate) const SAMPLED_IMAGE: Self = Self(2);
+    pub(crate) const STORAGE_IMAGE: Self = Self(3);
+    pub(crate) const UNIFORM_TEXEL_BUFFER: Self = Self(4);
+    pub(crate) const STORAGE_TEXEL_BUFFER: Self = Self(5);
+    pub(crate) const UNIFORM_BUFFER: Self = Self(6);
+    pub(crate) const STORAGE_BUFFER: Self = Self(7);
+    pub(crate) const INPUT_ATTACHMENT: Self = Self(10);
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct DescriptorSetLayoutBinding {
+    pub binding: u32,
+    pub descriptor_type: DescriptorType,
+    pub descriptor_count: u32,
+    pub stage_flags: ShaderStageFlags,
+    pub immutable_samplers: *const Sampler,
+}
+
+impl DescriptorSetLayoutBinding {
+    /// Binds `descriptor_count` descriptors without immutable samplers.
+    pub fn new(
+        binding: u32,
+        descriptor_type: DescriptorType,
+        descriptor_count: u32,
+        stage_flags: ShaderStageFlags,
+    ) -> Self {
+        Self {
+            binding,
+            descriptor_type,
+            descriptor_count,
+            stage_flags,
+            immutable_samplers: null(),
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct DescriptorSetLayoutCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: DescriptorSetLayoutCreateFlags,
+    pub binding_count: u32,
+    pub bindings: *const DescriptorSetLayoutBinding,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> DescriptorSetLayoutCreateInfo<'a> {
+    pub fn new(bindings: &'a [DescriptorSetLayoutBinding]) -> Self {
+        Self {
+            struct_type: StructureType::DescriptorSetLayoutCreateInfo,
+            next: null(),
+            flags: DescriptorSetLayoutCreateFlags::EMPTY,
+            binding_count: bindings.len() as u32,
+            bindings: bindings.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gives each binding the flags of `binding_flags`, in a layout for sets allocated from
+    /// update-after-bind pools.
+    pub fn update_after_bind(
+        mut self,
+        binding_flags: &'a DescriptorSetLayoutBindingFlagsCreateInfo,
+    ) -> Self {
+        self.next = core::ptr::from_ref(binding_flags).cast();
+        self.flags = DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL;
+        self
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct DescriptorSetLayoutCreateFlags(pub(crate) u32);
+
+impl DescriptorSetLayoutCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+    pub(crate) const UPDATE_AFTER_BIND_POOL: Self = Self(0x2);
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct DescriptorPoolSize {
+    pub descriptor_type: DescriptorType,
+    pub descriptor_count: u32,
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct DescriptorPoolCreateFlags(pub(crate) u32);
+
+impl DescriptorPoolCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+    pub(crate) const UPDATE_AFTER_BIND: Self = Self(0x2);
+}
+
+#[repr(C)]
+pub(crate) struct DescriptorPoolCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: DescriptorPoolCreateFlags,
+    pub max_sets: u32,
+    pub pool_size_count: u32,
+    pub pool_sizes: *const DescriptorPoolSize,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> DescriptorPoolCreateInfo<'a> {
+    /// A pool of up to `max_sets` sets, sharing the descriptors of `pool_sizes`. Sets are
+    /// only freed by resetting the pool.
+    pub fn new(max_sets: u32, pool_sizes: &'a [DescriptorPoolSize]) -> Self {
+        Self {
+            struct_type: StructureType::DescriptorPoolCreateInfo,
+            next: null(),
+            flags: DescriptorPoolCreateFlags::EMPTY,
+            max_sets,
+            pool_size_count: pool_sizes.len() as u32,
+            pool_sizes: pool_sizes.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Allows sets of update-after-bind layouts to be allocated from the pool.
+    pub fn update_after_bind(mut self) -> Self {
+        self.flags = DescriptorPoolCreateFlags::UPDATE_AFTER_BIND;
+        self
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct DescriptorPoolResetFlags(pub(crate) u32);
+
+impl DescriptorPoolResetFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
+
+#[repr(C)]
+pub(crate) struct DescriptorSetAllocateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub descriptor_pool: DescriptorPool,
+    pub descriptor_set_count: u32,
+    pub set_layouts: *const DescriptorSetLayout,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> DescriptorSetAllocateInfo<'a> {
+    /// Allocates one set from `descriptor_pool` per layout of `set_layouts`.
+    pub fn new(descriptor_pool: DescriptorPool, set_layouts: &'a [DescriptorSetLayout]) -> Self {
+        Self {
+            struct_type: StructureType::DescriptorSetAllocateInfo,
+            next: null(),
+            descriptor_pool,
+            descriptor_set_count: set_layouts.len() as u32,
+            set_layouts: set_layouts.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct DescriptorBufferInfo {
+    pub buffer: Buffer,
+    pub offset: u64,
+    pub range: u64,
+}
+
+impl DescriptorBufferInfo {
+    /// `VK_WHOLE_SIZE`.
+    pub(crate) const WHOLE_SIZE: u64 = !0;
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct DescriptorImageInfo {
+    pub sampler: Option<Sampler>,
+    pub image_view: Option<ImageView>,
+    pub image_layout: ImageLayout,
+}
+
+#[repr(C)]
+pub(crate) struct WriteDescriptorSet<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub dst_set: DescriptorSet,
+    pub dst_binding: u32,
+    pub dst_array_element: u32,
+    pub descriptor_count: u32,
+    pub descriptor_type: DescriptorType,
+    pub image_info: *const DescriptorImageInfo,
+    pub buffer_info: *const DescriptorBufferInfo,
+    pub texel_buffer_view: *const c_void,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> WriteDescriptorSet<'a> {
+    /// Writes `buffers` to consecutive elements of `dst_binding`, from `dst_array_element`.
+    pub fn buffers(
+        (dst_set, dst_binding, dst_array_element): (DescriptorSet, u32, u32),
+        descriptor_type: DescriptorType,
+        buffers: &'a [DescriptorBufferInfo],
+    ) -> Self {
+        Self {
+            buffer_info: buffers.as_ptr(),
+            ..Self::new(
+                (dst_set, dst_binding, dst_array_element),
+                descriptor_type,
+                buffers.len(),
+            )
+        }
+    }
+
+    /// Writes `images` to consecutive elements of `dst_binding`, from `dst_array_element`.
+    pub fn images(
+        (dst_set, dst_binding, dst_array_element): (DescriptorSet, u32, u32),
+        descriptor_type: DescriptorType,
+        images: &'a [DescriptorImageInfo],
+    ) -> Self {
+        Self {
+            image_info: images.as_ptr(),
+            ..Self::new(
+                (dst_set, dst_binding, dst_array_element),
+                descriptor_type,
+                images.len(),
+            )
+        }
+    }
+
+    fn new(
+        (dst_set, dst_binding, dst_array_element): (DescriptorSet, u32, u32),
+        descriptor_type: DescriptorType,
+        descriptor_count: usize,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::WriteDescriptorSet,
+            next: null(),
+            dst_set,
+            dst_binding,
+            dst_array_element,
+            descriptor_count: descriptor_count as u32,
+            descriptor_type,
+            image_info: null(),
+            buffer_info: null(),
+            texel_buffer_view: null(),
+            _marker: PhantomData,
+        }
+    }
+}
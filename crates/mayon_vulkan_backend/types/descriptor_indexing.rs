@@ -0,0 +1,156 @@
+use core::{
+    ffi::c_void,
+    marker::PhantomData,
+    ptr::{null, null_mut},
+};
+
+use super::StructureType;
+
+/// Number of `VkBool32` members of `VkPhysicalDeviceFeatures`.
+const FEATURE_COUNT: usize = 55;
+
+/// Filled by `vkGetPhysicalDeviceFeatures2`, along with the structures chained to it.
+#[repr(C)]
+pub(crate) struct PhysicalDeviceFeatures2<'a> {
+    pub struct_type: StructureType,
+    pub next: *mut c_void,
+    /// The Vulkan 1.0 features, which the backend does not look at.
+    pub features: [u32; FEATURE_COUNT],
+    pub _marker: PhantomData<&'a mut ()>,
+}
+
+impl<'a> PhysicalDeviceFeatures2<'a> {
+    /// Queries the descriptor indexing features along with the core features.
+    pub fn new(descriptor_indexing: &'a mut PhysicalDeviceDescriptorIndexingFeatures) -> Self {
+        Self {
+            struct_type: StructureType::PhysicalDeviceFeatures2,
+            next: core::ptr::from_mut(descriptor_indexing).cast(),
+            features: [0; FEATURE_COUNT],
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Queried through a `PhysicalDeviceFeatures2`, or chained into a `DeviceCreateInfo` to
+/// enable descriptor indexing.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PhysicalDeviceDescriptorIndexingFeatures {
+    pub struct_type: StructureType,
+    pub next: *mut c_void,
+    pub shader_input_attachment_array_dynamic_indexing: u32,
+    pub shader_uniform_texel_buffer_array_dynamic_indexing: u32,
+    pub shader_storage_texel_buffer_array_dynamic_indexing: u32,
+    pub shader_uniform_buffer_array_non_uniform_indexing: u32,
+    pub shader_sampled_image_array_non_uniform_indexing: u32,
+    pub shader_storage_buffer_array_non_uniform_indexing: u32,
+    pub shader_storage_image_array_non_uniform_indexing: u32,
+    pub shader_input_attachment_array_non_uniform_indexing: u32,
+    pub shader_uniform_texel_buffer_array_non_uniform_indexing: u32,
+    pub shader_storage_texel_buffer_array_non_uniform_indexing: u32,
+    pub descriptor_binding_uniform_buffer_update_after_bind: u32,
+    pub descriptor_binding_sampled_image_update_after_bind: u32,
+    pub descriptor_binding_storage_image_update_after_bind: u32,
+    pub descriptor_binding_storage_buffer_update_after_bind: u32,
+    pub descriptor_binding_uniform_texel_buffer_update_after_bind: u32,
+    pub descriptor_binding_storage_texel_buffer_update_after_bind: u32,
+    pub descriptor_binding_update_unused_while_pending: u32,
+    pub descriptor_binding_partially_bound: u32,
+    pub descriptor_binding_variable_descriptor_count: u32,
+    pub runtime_descriptor_array: u32,
+}
+
+impl PhysicalDeviceDescriptorIndexingFeatures {
+    /// Every feature unset, ready to be filled by a query.
+    pub fn none() -> Self {
+        Self {
+            struct_type: StructureType::PhysicalDeviceDescriptorIndexingFeatures,
+            next: null_mut(),
+            shader_input_attachment_array_dynamic_indexing: 0,
+            shader_uniform_texel_buffer_array_dynamic_indexing: 0,
+            shader_storage_texel_buffer_array_dynamic_indexing: 0,
+            shader_uniform_buffer_array_non_uniform_indexing: 0,
+            shader_sampled_image_array_non_uniform_indexing: 0,
+            shader_storage_buffer_array_non_uniform_indexing: 0,
+            shader_storage_image_array_non_uniform_indexing: 0,
+            shader_input_attachment_array_non_uniform_indexing: 0,
+            shader_uniform_texel_buffer_array_non_uniform_indexing: 0,
+            shader_storage_texel_buffer_array_non_uniform_indexing: 0,
+            descriptor_binding_uniform_buffer_update_after_bind: 0,
+            descriptor_binding_sampled_image_update_after_bind: 0,
+            descriptor_binding_storage_image_update_after_bind: 0,
+            descriptor_binding_storage_buffer_update_after_bind: 0,
+            descriptor_binding_uniform_texel_buffer_update_after_bind: 0,
+            descriptor_binding_storage_texel_buffer_update_after_bind: 0,
+            descriptor_binding_update_unused_while_pending: 0,
+            descriptor_binding_partially_bound: 0,
+            descriptor_binding_variable_descriptor_count: 0,
+            runtime_descriptor_array: 0,
+        }
+    }
+
+    /// The features bindless tables rely on: runtime-sized arrays of sampled images,
+    /// samplers and storage buffers, indexed non-uniformly, partially bound, and updated
+    /// after being bound.
+    pub fn bindless() -> Self {
+        Self {
+            shader_sampled_image_array_non_uniform_indexing: 1,
+            shader_storage_buffer_array_non_uniform_indexing: 1,
+            descriptor_binding_sampled_image_update_after_bind: 1,
+            descriptor_binding_storage_buffer_update_after_bind: 1,
+            descriptor_binding_update_unused_while_pending: 1,
+            descriptor_binding_partially_bound: 1,
+            runtime_descriptor_array: 1,
+            ..Self::none()
+        }
+    }
+
+    /// Whether every feature of [`bindless`](Self::bindless) is supported.
+    pub fn supports_bindless(&self) -> bool {
+        self.shader_sampled_image_array_non_uniform_indexing != 0
+            && self.shader_storage_buffer_array_non_uniform_indexing != 0
+            && self.descriptor_binding_sampled_image_update_after_bind != 0
+            && self.descriptor_binding_storage_buffer_update_after_bind != 0
+            && self.descriptor_binding_update_unused_while_pending != 0
+            && self.descriptor_binding_partially_bound != 0
+            && self.runtime_descriptor_array != 0
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct DescriptorBindingFlags(pub(crate) u32);
+
+impl DescriptorBindingFlags {
+    pub(crate) const UPDATE_AFTER_BIND: Self = Self(0x1);
+    pub(crate) const UPDATE_UNUSED_WHILE_PENDING: Self = Self(0x2);
+    pub(crate) const PARTIALLY_BOUND: Self = Self(0x4);
+
+    #[inline]
+    pub(crate) const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// Chained into a `DescriptorSetLayoutCreateInfo` to give the flags of each binding.
+#[repr(C)]
+pub(crate) struct DescriptorSetLayoutBindingFlagsCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub binding_count: u32,
+    pub binding_flags: *const DescriptorBindingFlags,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> DescriptorSetLayoutBindingFlagsCreateInfo<'a> {
+    /// Flags of each binding, in the order of the layout's bindings.
+    pub fn new(binding_flags: &'a [DescriptorBindingFlags]) -> Self {
+        Self {
+            struct_type: StructureType::DescriptorSetLayoutBindingFlagsCreateInfo,
+            next: null(),
+            binding_count: binding_flags.len() as u32,
+            binding_flags: binding_flags.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::{ExtensionName, PhysicalDeviceTimelineSemaphoreFeatures, StructureType};
+
+#[repr(C)]
+pub(crate) struct DeviceQueueCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: DeviceQueueCreateFlags,
+    pub queue_family_index: u32,
+    pub queue_count: u32,
+    pub queue_priorities: *const f32,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> DeviceQueueCreateInfo<'a> {
+    /// Requests one queue per entry of `priorities` from the family at `queue_family_index`.
+    pub fn new(queue_family_index: u32, priorities: &'a [f32]) -> Self {
+        Self {
+            struct_type: StructureType::DeviceQueueCreateInfo,
+            next: null(),
+            flags: DeviceQueueCreateFlags::EMPTY,
+            queue_family_index,
+            queue_count: priorities.len() as u32,
+            queue_priorities: priorities.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct DeviceCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: DeviceCreateFlags,
+    pub queue_create_info_count: u32,
+    pub queue_create_infos: *const DeviceQueueCreateInfo<'a>,
+    pub enabled_layer_count: u32,
+    pub enabled_layer_names: *const c_void,
+    pub enabled_extension_count: u32,
+    pub enabled_extension_names: *const ExtensionName,
+    pub enabled_features: *const c_void,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> DeviceCreateInfo<'a> {
+    /// Creates a `DeviceCreateInfo` for the given queues and device extensions.
+    ///
+    /// Device layers are deprecated and always left empty.
+    pub fn new(
+        queue_create_infos: &'a [DeviceQueueCreateInfo<'a>],
+        extension_names: &'a [ExtensionName],
+    ) -> Self {
+        Self {
+            struct_type: StructureType::DeviceCreateInfo,
+            next: null(),
+            flags: DeviceCreateFlags::EMPTY,
+            queue_create_info_count: queue_create_infos.len() as u32,
+            queue_create_infos: queue_create_infos.as_ptr(),
+            enabled_layer_count: 0,
+            enabled_layer_names: null(),
+            enabled_extension_count: extension_names.len() as u32,
+            enabled_extension_names: extension_names.as_ptr(),
+            enabled_features: null(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Enables the features in `features` and the structures chained after it.
+    pub fn with_features(mut self, features: &'a PhysicalDeviceTimelineSemaphoreFeatures) -> Self {
+        self.next = (features as *const PhysicalDeviceTimelineSemaphoreFeatures).cast();
+        self
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DeviceQueueCreateFlags(pub(crate) u32);
+
+impl DeviceQueueCreateFlags {
+    pub const EMPTY: DeviceQueueCreateFlags = DeviceQueueCreateFlags(0);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DeviceCreateFlags(pub(crate) u32);
+
+impl DeviceCreateFlags {
+    pub const EMPTY: DeviceCreateFlags = DeviceCreateFlags(0);
+}
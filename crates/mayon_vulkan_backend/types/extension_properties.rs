@@ -0,0 +1,17 @@
+use core::ffi::{CStr, c_char};
+
+const MAX_EXTENSION_NAME_SIZE: usize = 256;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ExtensionProperties {
+    pub(crate) extension_name: [c_char; MAX_EXTENSION_NAME_SIZE],
+    pub(crate) spec_version: u32,
+}
+
+impl ExtensionProperties {
+    #[inline]
+    pub(crate) fn name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.extension_name.as_ptr()) }
+    }
+}
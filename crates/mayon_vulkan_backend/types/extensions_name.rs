@@ -17,6 +17,9 @@ impl ExtensionName {
     #[allow(unused)]
     pub const IOS_SURFACE: Self = Self::new(c"VK_MVK_ios_surface");
     pub const MACOS_SURFACE: Self = Self::new(c"VK_MVK_macos_surface");
+    pub const SWAPCHAIN: Self = Self::new(c"VK_KHR_swapchain");
+    pub const DYNAMIC_RENDERING: Self = Self::new(c"VK_KHR_dynamic_rendering");
+    pub const DEBUG_UTILS: Self = Self::new(c"VK_EXT_debug_utils");
 
     /// Creates an `ExtensionName` from a static C string by storing its raw pointer.
     ///
@@ -34,6 +37,17 @@ impl ExtensionName {
     pub(crate) const fn new(value: &'static CStr) -> Self {
         Self(value.as_ptr())
     }
+
+    /// Borrows an extension name from a C string that outlives every use of the result.
+    #[inline]
+    pub(crate) const fn from_c_str(value: &CStr) -> Self {
+        Self(value.as_ptr())
+    }
+
+    #[inline]
+    pub(crate) fn as_c_str(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.0) }
+    }
 }
 
 impl fmt::Debug for ExtensionName {
@@ -0,0 +1,39 @@
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Extent2D {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Extent3D {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Offset2D {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Rect2D {
+    pub offset: Offset2D,
+    pub extent: Extent2D,
+}
+
+impl Rect2D {
+    /// The area from the origin to `extent`.
+    #[inline]
+    pub(crate) const fn from_extent(extent: Extent2D) -> Self {
+        Self {
+            offset: Offset2D { x: 0, y: 0 },
+            extent,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use core::{ffi::c_void, ptr::null};
+
+use super::StructureType;
+
+#[repr(C)]
+pub(crate) struct FenceCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: FenceCreateFlags,
+}
+
+impl FenceCreateInfo {
+    pub fn new(flags: FenceCreateFlags) -> Self {
+        Self {
+            struct_type: StructureType::FenceCreateInfo,
+            next: null(),
+            flags,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FenceCreateFlags(pub(crate) u32);
+
+impl FenceCreateFlags {
+    pub const EMPTY: FenceCreateFlags = FenceCreateFlags(0);
+}
@@ -0,0 +1,103 @@
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct Format(pub(crate) i32);
+
+impl Format {
+    pub(crate) const UNDEFINED: Self = Self(0);
+    pub(crate) const R8_UNORM: Self = Self(9);
+    pub(crate) const R8G8_UNORM: Self = Self(16);
+    pub(crate) const R8G8B8A8_UNORM: Self = Self(37);
+    pub(crate) const R8G8B8A8_SRGB: Self = Self(43);
+    pub(crate) const B8G8R8A8_UNORM: Self = Self(44);
+    pub(crate) const B8G8R8A8_SRGB: Self = Self(50);
+    pub(crate) const R16_SFLOAT: Self = Self(76);
+    pub(crate) const R16G16B16A16_SFLOAT: Self = Self(97);
+    pub(crate) const R32_UINT: Self = Self(98);
+    pub(crate) const R32_SINT: Self = Self(99);
+    pub(crate) const R32_SFLOAT: Self = Self(100);
+    pub(crate) const R32G32_UINT: Self = Self(101);
+    pub(crate) const R32G32_SINT: Self = Self(102);
+    pub(crate) const R32G32_SFLOAT: Self = Self(103);
+    pub(crate) const R32G32B32_UINT: Self = Self(104);
+    pub(crate) const R32G32B32_SINT: Self = Self(105);
+    pub(crate) const R32G32B32_SFLOAT: Self = Self(106);
+    pub(crate) const R32G32B32A32_UINT: Self = Self(107);
+    pub(crate) const R32G32B32A32_SINT: Self = Self(108);
+    pub(crate) const R32G32B32A32_SFLOAT: Self = Self(109);
+    pub(crate) const D16_UNORM: Self = Self(124);
+    pub(crate) const D32_SFLOAT: Self = Self(126);
+    pub(crate) const D24_UNORM_S8_UINT: Self = Self(129);
+    pub(crate) const D32_SFLOAT_S8_UINT: Self = Self(130);
+}
+
+impl From<mayon_core::Format> for Format {
+    #[inline]
+    fn from(format: mayon_core::Format) -> Self {
+        use mayon_core::Format::*;
+
+        match format {
+            R8Unorm => Self::R8_UNORM,
+            Rg8Unorm => Self::R8G8_UNORM,
+            Rgba8Unorm => Self::R8G8B8A8_UNORM,
+            Rgba8Srgb => Self::R8G8B8A8_SRGB,
+            Bgra8Unorm => Self::B8G8R8A8_UNORM,
+            Bgra8Srgb => Self::B8G8R8A8_SRGB,
+            R16Float => Self::R16_SFLOAT,
+            Rgba16Float => Self::R16G16B16A16_SFLOAT,
+            R32Float => Self::R32_SFLOAT,
+            Rgba32Float => Self::R32G32B32A32_SFLOAT,
+        }
+    }
+}
+
+impl Format {
+    /// The Mayon format with the same layout, if there is one.
+    pub(crate) fn core_format(self) -> Option<mayon_core::Format> {
+        use mayon_core::Format::*;
+
+        Some(match self {
+            Self::R8_UNORM => R8Unorm,
+            Self::R8G8_UNORM => Rg8Unorm,
+            Self::R8G8B8A8_UNORM => Rgba8Unorm,
+            Self::R8G8B8A8_SRGB => Rgba8Srgb,
+            Self::B8G8R8A8_UNORM => Bgra8Unorm,
+            Self::B8G8R8A8_SRGB => Bgra8Srgb,
+            Self::R16_SFLOAT => R16Float,
+            Self::R16G16B16A16_SFLOAT => Rgba16Float,
+            Self::R32_SFLOAT => R32Float,
+            Self::R32G32B32A32_SFLOAT => Rgba32Float,
+            _ => return None,
+        })
+    }
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct ColorSpace(pub(crate) i32);
+
+impl ColorSpace {
+    pub(crate) const SRGB_NONLINEAR: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct FormatFeatureFlags(pub(crate) u32);
+
+impl FormatFeatureFlags {
+    pub(crate) const BLIT_SRC: Self = Self(0x400);
+    pub(crate) const BLIT_DST: Self = Self(0x800);
+    pub(crate) const SAMPLED_IMAGE_FILTER_LINEAR: Self = Self(0x1000);
+
+    #[inline]
+    pub(crate) const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct FormatProperties {
+    pub linear_tiling_features: FormatFeatureFlags,
+    pub optimal_tiling_features: FormatFeatureFlags,
+    pub buffer_features: FormatFeatureFlags,
+}
@@ -144,6 +144,15 @@ pub struct Pipeline;
 #[vk_handle(u64)]
 pub struct PipelineLayout;
 
+/// Opaque handle to a Vulkan pipeline cache.
+///
+/// Represents a `VkPipelineCache`, which holds the results of pipeline compilation for
+/// reuse across pipelines and application runs. Non-dispatchable handle (64-bit).
+///
+/// Vulkan Documentation Reference: https://docs.vulkan.org/refpages/latest/refpages/source/VkPipelineCache.html
+#[vk_handle(u64)]
+pub struct PipelineCache;
+
 /// Opaque handle to a Vulkan sampler.
 ///
 /// Represents a `VkSampler`, which controls how texture sampling operations
@@ -0,0 +1,143 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::{Extent3D, Format, ImageUsageFlags, SharingMode, StructureType};
+
+#[repr(C)]
+pub(crate) struct ImageCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: ImageCreateFlags,
+    pub image_type: ImageType,
+    pub format: Format,
+    pub extent: Extent3D,
+    pub mip_levels: u32,
+    pub array_layers: u32,
+    pub samples: SampleCountFlags,
+    pub tiling: ImageTiling,
+    pub usage: ImageUsageFlags,
+    pub sharing_mode: SharingMode,
+    pub queue_family_index_count: u32,
+    pub queue_family_indices: *const u32,
+    pub initial_layout: ImageLayout,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl ImageCreateInfo<'static> {
+    /// Creates a single-sampled, optimally tiled 2D image with one mip level and layer.
+    pub fn new_2d(format: Format, width: u32, height: u32, usage: ImageUsageFlags) -> Self {
+        Self {
+            struct_type: StructureType::ImageCreateInfo,
+            next: null(),
+            flags: ImageCreateFlags::EMPTY,
+            image_type: ImageType::TYPE_2D,
+            format,
+            extent: Extent3D {
+                width,
+                height,
+                depth: 1,
+            },
+            mip_levels: 1,
+            array_layers: 1,
+            samples: SampleCountFlags::TYPE_1,
+            tiling: ImageTiling::OPTIMAL,
+            usage,
+            sharing_mode: SharingMode::EXCLUSIVE,
+            queue_family_index_count: 0,
+            queue_family_indices: null(),
+            initial_layout: ImageLayout::UNDEFINED,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> ImageCreateInfo<'a> {
+    /// Creates a single-sampled, optimally tiled image usable by the queue families in
+    /// `queue_families`, shared concurrently when more than one family is given.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        flags: ImageCreateFlags,
+        image_type: ImageType,
+        format: Format,
+        extent: Extent3D,
+        mip_levels: u32,
+        array_layers: u32,
+        usage: ImageUsageFlags,
+        queue_families: &'a [u32],
+    ) -> Self {
+        let (sharing_mode, queue_families) = match queue_families {
+            [_, _, ..] => (SharingMode::CONCURRENT, queue_families),
+            _ => (SharingMode::EXCLUSIVE, &[][..]),
+        };
+
+        Self {
+            flags,
+            image_type,
+            extent,
+            mip_levels,
+            array_layers,
+            sharing_mode,
+            queue_family_index_count: queue_families.len() as u32,
+            queue_family_indices: queue_families.as_ptr(),
+            ..ImageCreateInfo::new_2d(format, 0, 0, usage)
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ImageCreateFlags(pub(crate) u32);
+
+impl ImageCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+    pub(crate) const CUBE_COMPATIBLE: Self = Self(0x10);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ImageType(pub(crate) i32);
+
+impl ImageType {
+    pub(crate) const TYPE_2D: Self = Self(1);
+    pub(crate) const TYPE_3D: Self = Self(2);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ImageTiling(pub(crate) i32);
+
+impl ImageTiling {
+    pub(crate) const OPTIMAL: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct SampleCountFlags(pub(crate) u32);
+
+impl SampleCountFlags {
+    pub(crate) const TYPE_1: Self = Self(0x1);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ImageLayout(pub(crate) i32);
+
+impl ImageLayout {
+    pub(crate) const UNDEFINED: Self = Self(0);
+    pub(crate) const GENERAL: Self = Self(1);
+    pub(crate) const COLOR_ATTACHMENT_OPTIMAL: Self = Self(2);
+    pub(crate) const DEPTH_STENCIL_ATTACHMENT_OPTIMAL: Self = Self(3);
+    pub(crate) const SHADER_READ_ONLY_OPTIMAL: Self = Self(5);
+    pub(crate) const TRANSFER_SRC_OPTIMAL: Self = Self(6);
+    pub(crate) const TRANSFER_DST_OPTIMAL: Self = Self(7);
+    pub(crate) const PRESENT_SRC: Self = Self(1000001002);
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ImageFormatProperties {
+    pub max_extent: Extent3D,
+    pub max_mip_levels: u32,
+    pub max_array_layers: u32,
+    pub sample_counts: SampleCountFlags,
+    pub max_resource_size: u64,
+}
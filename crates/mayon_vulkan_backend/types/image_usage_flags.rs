@@ -0,0 +1,26 @@
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct ImageUsageFlags(pub(crate) u32);
+
+impl ImageUsageFlags {
+    pub(crate) const TRANSFER_SRC: Self = Self(0x1);
+    pub(crate) const TRANSFER_DST: Self = Self(0x2);
+    pub(crate) const SAMPLED: Self = Self(0x4);
+    pub(crate) const STORAGE: Self = Self(0x8);
+    pub(crate) const COLOR_ATTACHMENT: Self = Self(0x10);
+    pub(crate) const DEPTH_STENCIL_ATTACHMENT: Self = Self(0x20);
+
+    #[inline]
+    pub(crate) const fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct SharingMode(pub(crate) i32);
+
+impl SharingMode {
+    pub(crate) const EXCLUSIVE: Self = Self(0);
+    pub(crate) const CONCURRENT: Self = Self(1);
+}
@@ -0,0 +1,65 @@
+use core::{ffi::c_void, ptr::null};
+
+use super::{Format, Image, ImageSubresourceRange, StructureType};
+
+#[repr(C)]
+pub(crate) struct ImageViewCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: ImageViewCreateFlags,
+    pub image: Image,
+    pub view_type: ImageViewType,
+    pub format: Format,
+    pub components: ComponentMapping,
+    pub subresource_range: ImageSubresourceRange,
+}
+
+impl ImageViewCreateInfo {
+    /// Views `subresource_range` of `image` with the identity swizzle.
+    pub fn new(
+        image: Image,
+        view_type: ImageViewType,
+        format: Format,
+        subresource_range: ImageSubresourceRange,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::ImageViewCreateInfo,
+            next: null(),
+            flags: ImageViewCreateFlags::EMPTY,
+            image,
+            view_type,
+            format,
+            components: ComponentMapping::default(),
+            subresource_range,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ImageViewCreateFlags(pub(crate) u32);
+
+impl ImageViewCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ImageViewType(pub(crate) i32);
+
+impl ImageViewType {
+    pub(crate) const TYPE_2D: Self = Self(1);
+    pub(crate) const TYPE_3D: Self = Self(2);
+    pub(crate) const CUBE: Self = Self(3);
+    pub(crate) const TYPE_2D_ARRAY: Self = Self(5);
+}
+
+/// Per-channel swizzle of an image view. The default maps every channel to itself.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ComponentMapping {
+    pub r: i32,
+    pub g: i32,
+    pub b: i32,
+    pub a: i32,
+}
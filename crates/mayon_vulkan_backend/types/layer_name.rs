@@ -15,6 +15,17 @@ impl LayerName {
     pub(crate) const fn new(value: &'static CStr) -> Self {
         Self(unsafe { NonNull::new_unchecked(value.as_ptr().cast_mut() as *mut c_char) })
     }
+
+    /// Borrows a layer name from a C string that outlives every use of the result.
+    #[inline]
+    pub(crate) const fn from_c_str(value: &CStr) -> Self {
+        Self(unsafe { NonNull::new_unchecked(value.as_ptr().cast_mut()) })
+    }
+
+    #[inline]
+    pub(crate) fn as_c_str(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.0.as_ptr()) }
+    }
 }
 
 impl fmt::Debug for LayerName {
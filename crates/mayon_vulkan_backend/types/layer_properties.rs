@@ -1,4 +1,4 @@
-use core::ffi::c_char;
+use core::ffi::{CStr, c_char};
 
 const MAX_EXTENSION_NAME_SIZE: usize = 256;
 const MAX_DESCRIPTION_SIZE: usize = 256;
@@ -14,12 +14,7 @@ pub(crate) struct LayerProperties {
 
 impl LayerProperties {
     #[inline]
-    pub const fn zeroized<const N: usize>() -> [Self; N] {
-        [Self {
-            layer_name: [0; MAX_EXTENSION_NAME_SIZE],
-            spec_version: 0,
-            implementation_version: 0,
-            description: [0; MAX_DESCRIPTION_SIZE],
-        }; N]
+    pub(crate) fn name(&self) -> &CStr {
+        unsafe { CStr::from_ptr(self.layer_name.as_ptr()) }
     }
 }
@@ -0,0 +1,186 @@
+use core::{ffi::c_void, ptr::null};
+
+use allocator::{AllocationRequest, MemoryFlags, MemoryProperties};
+
+use super::StructureType;
+
+const MAX_MEMORY_TYPES: usize = 32;
+const MAX_MEMORY_HEAPS: usize = 16;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct MemoryRequirements {
+    pub size: u64,
+    pub alignment: u64,
+    pub memory_type_bits: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct MemoryType {
+    pub property_flags: MemoryPropertyFlags,
+    pub heap_index: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct MemoryHeap {
+    pub size: u64,
+    pub flags: MemoryHeapFlags,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct PhysicalDeviceMemoryProperties {
+    pub memory_type_count: u32,
+    pub memory_types: [MemoryType; MAX_MEMORY_TYPES],
+    pub memory_heap_count: u32,
+    pub memory_heaps: [MemoryHeap; MAX_MEMORY_HEAPS],
+}
+
+impl PhysicalDeviceMemoryProperties {
+    /// The memory types reported by the driver.
+    #[inline]
+    pub(crate) fn memory_types(&self) -> &[MemoryType] {
+        &self.memory_types[..(self.memory_type_count as usize).min(MAX_MEMORY_TYPES)]
+    }
+
+    /// The memory heaps reported by the driver.
+    #[inline]
+    pub(crate) fn memory_heaps(&self) -> &[MemoryHeap] {
+        &self.memory_heaps[..(self.memory_heap_count as usize).min(MAX_MEMORY_HEAPS)]
+    }
+}
+
+impl From<&PhysicalDeviceMemoryProperties> for MemoryProperties {
+    fn from(properties: &PhysicalDeviceMemoryProperties) -> Self {
+        Self {
+            types: properties
+                .memory_types()
+                .iter()
+                .map(|memory_type| allocator::MemoryType {
+                    flags: MemoryFlags(memory_type.property_flags.0),
+                    heap_index: memory_type.heap_index,
+                })
+                .collect(),
+            heaps: properties
+                .memory_heaps()
+                .iter()
+                .map(|heap| allocator::MemoryHeap {
+                    size: heap.size,
+                    device_local: heap.flags.contains(MemoryHeapFlags::DEVICE_LOCAL),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<MemoryRequirements> for AllocationRequest {
+    #[inline]
+    fn from(requirements: MemoryRequirements) -> Self {
+        Self::new(
+            requirements.size,
+            requirements.alignment,
+            requirements.memory_type_bits,
+        )
+    }
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct MemoryPropertyFlags(pub(crate) u32);
+
+#[cfg(any(test, feature = "mock_driver"))]
+impl MemoryPropertyFlags {
+    pub(crate) const DEVICE_LOCAL: Self = Self(0x1);
+    pub(crate) const HOST_VISIBLE: Self = Self(0x2);
+    pub(crate) const HOST_COHERENT: Self = Self(0x4);
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct MemoryHeapFlags(pub(crate) u32);
+
+impl MemoryHeapFlags {
+    pub(crate) const DEVICE_LOCAL: Self = Self(0x1);
+
+    #[inline]
+    pub(crate) const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+#[repr(C)]
+pub(crate) struct MemoryAllocateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub allocation_size: u64,
+    pub memory_type_index: u32,
+}
+
+impl MemoryAllocateInfo {
+    pub fn new(allocation_size: u64, memory_type_index: u32) -> Self {
+        Self {
+            struct_type: StructureType::MemoryAllocateInfo,
+            next: null(),
+            allocation_size,
+            memory_type_index,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_properties() {
+        let mut properties = PhysicalDeviceMemoryProperties {
+            memory_type_count: 2,
+            memory_heap_count: 2,
+            ..Default::default()
+        };
+
+        properties.memory_types[0].property_flags = MemoryPropertyFlags::DEVICE_LOCAL;
+        properties.memory_types[1] = MemoryType {
+            property_flags: MemoryPropertyFlags::HOST_VISIBLE,
+            heap_index: 1,
+        };
+        properties.memory_types[2].property_flags = MemoryPropertyFlags::DEVICE_LOCAL;
+        properties.memory_heaps[0] = MemoryHeap {
+            size: 1 << 30,
+            flags: MemoryHeapFlags::DEVICE_LOCAL,
+        };
+        properties.memory_heaps[1].size = 1 << 20;
+
+        let properties = MemoryProperties::from(&properties);
+
+        assert_eq!(
+            properties.types,
+            [
+                allocator::MemoryType {
+                    flags: MemoryFlags::DEVICE_LOCAL,
+                    heap_index: 0,
+                },
+                allocator::MemoryType {
+                    flags: MemoryFlags::HOST_VISIBLE,
+                    heap_index: 1,
+                },
+            ],
+            "Types beyond the reported count are ignored"
+        );
+        assert_eq!(
+            properties.heaps,
+            [
+                allocator::MemoryHeap {
+                    size: 1 << 30,
+                    device_local: true,
+                },
+                allocator::MemoryHeap {
+                    size: 1 << 20,
+                    device_local: false,
+                },
+            ]
+        );
+    }
+}
@@ -1,29 +1,144 @@
 mod allocation_callbacks;
 mod application_info;
+mod barrier;
+mod buffer;
+mod command_buffer;
+mod debug_utils;
+mod descriptor;
+mod descriptor_indexing;
+mod device_create_info;
+mod extension_properties;
 mod extensions_name;
+mod extent;
+mod fence_create_info;
+mod format;
 mod handles;
+mod image_copy;
+mod image_create_info;
+mod image_usage_flags;
+mod image_view;
 mod instance_create_info;
 mod layer_name;
 mod layer_properties;
+mod memory;
+mod physical_device_properties;
+mod pipeline;
+mod pipeline_cache;
+mod pipeline_layout;
+mod present_info;
+mod queue_family_properties;
+mod render_pass;
+mod rendering;
 mod result;
+mod sampler;
+mod semaphore_create_info;
+mod shader;
 mod structure_type;
+mod submit_info;
+mod surface_capabilities;
+mod swapchain_create_info;
+mod timeline_semaphore;
+#[cfg(feature = "windowing")]
 mod wayland_surface_create_info;
+#[cfg(feature = "windowing")]
 mod win32_surface_create_info;
+#[cfg(feature = "windowing")]
 mod xcb_surface_create_info;
+#[cfg(feature = "windowing")]
 mod xlib_surface_create_info;
 
 pub(super) use allocation_callbacks::*;
 pub(super) use application_info::ApplicationInfo;
+pub(super) use barrier::{
+    AccessFlags, DependencyFlags, ImageAspectFlags, ImageMemoryBarrier, ImageSubresourceRange,
+    MemoryBarrier, PipelineStageFlags,
+};
+pub(super) use buffer::{BufferCopy, BufferCreateInfo, BufferUsageFlags, MemoryMapFlags};
+pub(super) use command_buffer::{
+    CommandBufferAllocateInfo, CommandBufferBeginInfo, CommandBufferLevel, CommandBufferResetFlags,
+    CommandBufferUsageFlags, CommandPoolCreateFlags, CommandPoolCreateInfo, CommandPoolResetFlags,
+};
+pub(super) use debug_utils::{
+    DebugUtilsMessageSeverityFlags, DebugUtilsMessageTypeFlags, DebugUtilsMessengerCallbackData,
+    DebugUtilsMessengerCreateInfo,
+};
+pub(super) use descriptor::{
+    DescriptorBufferInfo, DescriptorImageInfo, DescriptorPoolCreateInfo, DescriptorPoolResetFlags,
+    DescriptorPoolSize, DescriptorSetAllocateInfo, DescriptorSetLayoutBinding,
+    DescriptorSetLayoutCreateInfo, DescriptorType, WriteDescriptorSet,
+};
+pub(super) use descriptor_indexing::{
+    DescriptorBindingFlags, DescriptorSetLayoutBindingFlagsCreateInfo,
+    PhysicalDeviceDescriptorIndexingFeatures, PhysicalDeviceFeatures2,
+};
+pub(super) use device_create_info::{DeviceCreateInfo, DeviceQueueCreateInfo};
+pub(super) use extension_properties::ExtensionProperties;
 pub(super) use extensions_name::ExtensionName;
+pub(super) use extent::{Extent2D, Extent3D, Rect2D};
+pub(super) use fence_create_info::{FenceCreateFlags, FenceCreateInfo};
+pub(super) use format::{ColorSpace, Format, FormatFeatureFlags, FormatProperties};
 pub(super) use handles::*;
+pub(super) use image_copy::{BufferImageCopy, ImageBlit, ImageSubresourceLayers, Offset3D};
+pub(super) use image_create_info::{
+    ImageCreateFlags, ImageCreateInfo, ImageFormatProperties, ImageLayout, ImageTiling, ImageType,
+    SampleCountFlags,
+};
+pub(super) use image_usage_flags::{ImageUsageFlags, SharingMode};
+pub(super) use image_view::{ImageViewCreateInfo, ImageViewType};
 pub(super) use instance_create_info::InstanceCreateInfo;
 pub(super) use layer_name::LayerName;
 pub(super) use layer_properties::LayerProperties;
+pub(super) use memory::{MemoryAllocateInfo, MemoryRequirements, PhysicalDeviceMemoryProperties};
+pub(super) use physical_device_properties::{PhysicalDeviceProperties, PhysicalDeviceType};
+pub(super) use pipeline::{
+    BlendFactor, BlendOp, ColorComponentFlags, CompareOp, ComputePipelineCreateInfo, CullModeFlags,
+    DynamicState, FrontFace, GraphicsPipelineCreateInfo, GraphicsPipelineState,
+    PipelineColorBlendAttachmentState, PipelineColorBlendStateCreateInfo,
+    PipelineDepthStencilStateCreateInfo, PipelineDynamicStateCreateInfo,
+    PipelineInputAssemblyStateCreateInfo, PipelineMultisampleStateCreateInfo,
+    PipelineRasterizationStateCreateInfo, PipelineRenderingCreateInfo,
+    PipelineShaderStageCreateInfo, PipelineVertexInputStateCreateInfo,
+    PipelineViewportStateCreateInfo, PolygonMode, PrimitiveTopology,
+    VertexInputAttributeDescription, VertexInputBindingDescription, VertexInputRate,
+};
+pub(super) use pipeline_cache::{PipelineCacheCreateInfo, PipelineCacheHeader};
+pub(super) use pipeline_layout::{PipelineLayoutCreateInfo, PushConstantRange};
+pub(super) use present_info::PresentInfo;
+pub(super) use queue_family_properties::{QueueFamilyProperties, QueueFlags};
+pub(super) use render_pass::{
+    AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp, ClearValue,
+    FramebufferCreateInfo, PipelineBindPoint, RenderPassBeginInfo, RenderPassCreateInfo,
+    SubpassContents, SubpassDescription,
+};
+pub(super) use rendering::{
+    PhysicalDeviceDynamicRenderingFeatures, RenderingAttachmentInfo, RenderingInfo,
+};
 pub(super) use result::VkResult;
+pub(super) use sampler::{Filter, SamplerAddressMode, SamplerCreateInfo, SamplerMipmapMode};
+pub(super) use semaphore_create_info::SemaphoreCreateInfo;
+pub(super) use shader::{ShaderModuleCreateInfo, ShaderStageFlags};
 pub(super) use structure_type::StructureType;
+pub(super) use submit_info::SubmitInfo;
+pub(super) use surface_capabilities::{
+    CompositeAlphaFlags, PresentMode, SurfaceCapabilities, SurfaceFormat, SurfaceTransformFlags,
+};
+pub(super) use swapchain_create_info::SwapchainCreateInfo;
+pub(super) use timeline_semaphore::{
+    PhysicalDeviceTimelineSemaphoreFeatures, SemaphoreSignalInfo, SemaphoreTypeCreateInfo,
+    SemaphoreWaitInfo, TimelineSemaphoreSubmitInfo,
+};
+#[cfg(feature = "windowing")]
 pub(super) use wayland_surface_create_info::{WaylandSurfaceCreateFlags, WaylandSurfaceCreateInfo};
+#[cfg(feature = "windowing")]
 pub(super) use win32_surface_create_info::{Win32SurfaceCreateFlags, Win32SurfaceCreateInfo};
+#[cfg(feature = "windowing")]
 pub(super) use xcb_surface_create_info::{XcbSurfaceCreateFlags, XcbSurfaceCreateInfo};
+#[cfg(feature = "windowing")]
 pub(super) use xlib_surface_create_info::{XlibSurfaceCreateFlags, XlibSurfaceCreateInfo};
 
+#[cfg(any(test, feature = "mock_driver"))]
+pub(super) use memory::{MemoryHeap, MemoryHeapFlags, MemoryPropertyFlags, MemoryType};
+#[cfg(any(test, feature = "mock_driver"))]
+pub(super) use timeline_semaphore::SemaphoreType;
+
 pub use result::ReturnCode;
@@ -0,0 +1,210 @@
+use core::ffi::{CStr, c_char};
+
+const MAX_PHYSICAL_DEVICE_NAME_SIZE: usize = 256;
+const UUID_SIZE: usize = 16;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PhysicalDeviceProperties {
+    pub api_version: u32,
+    pub driver_version: u32,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub device_type: PhysicalDeviceType,
+    pub device_name: [c_char; MAX_PHYSICAL_DEVICE_NAME_SIZE],
+    pub pipeline_cache_uuid: [u8; UUID_SIZE],
+    pub limits: PhysicalDeviceLimits,
+    pub sparse_properties: PhysicalDeviceSparseProperties,
+}
+
+impl PhysicalDeviceProperties {
+    /// Returns a zero-filled properties struct, ready to be written by the driver.
+    #[inline]
+    pub(crate) const fn zeroized() -> Self {
+        unsafe { core::mem::zeroed() }
+    }
+
+    /// Returns the device name as a `CStr`.
+    ///
+    /// The driver is required to null-terminate the name; if it does not, the
+    /// full fixed-size buffer is never read past its end.
+    #[inline]
+    pub(crate) fn device_name(&self) -> &CStr {
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                self.device_name.as_ptr().cast::<u8>(),
+                self.device_name.len(),
+            )
+        };
+
+        CStr::from_bytes_until_nul(bytes).unwrap_or(c"")
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PhysicalDeviceLimits {
+    pub max_image_dimension_1d: u32,
+    pub max_image_dimension_2d: u32,
+    pub max_image_dimension_3d: u32,
+    pub max_image_dimension_cube: u32,
+    pub max_image_array_layers: u32,
+    pub max_texel_buffer_elements: u32,
+    pub max_uniform_buffer_range: u32,
+    pub max_storage_buffer_range: u32,
+    pub max_push_constants_size: u32,
+    pub max_memory_allocation_count: u32,
+    pub max_sampler_allocation_count: u32,
+    pub buffer_image_granularity: u64,
+    pub sparse_address_space_size: u64,
+    pub max_bound_descriptor_sets: u32,
+    pub max_per_stage_descriptor_samplers: u32,
+    pub max_per_stage_descriptor_uniform_buffers: u32,
+    pub max_per_stage_descriptor_storage_buffers: u32,
+    pub max_per_stage_descriptor_sampled_images: u32,
+    pub max_per_stage_descriptor_storage_images: u32,
+    pub max_per_stage_descriptor_input_attachments: u32,
+    pub max_per_stage_resources: u32,
+    pub max_descriptor_set_samplers: u32,
+    pub max_descriptor_set_uniform_buffers: u32,
+    pub max_descriptor_set_uniform_buffers_dynamic: u32,
+    pub max_descriptor_set_storage_buffers: u32,
+    pub max_descriptor_set_storage_buffers_dynamic: u32,
+    pub max_descriptor_set_sampled_images: u32,
+    pub max_descriptor_set_storage_images: u32,
+    pub max_descriptor_set_input_attachments: u32,
+    pub max_vertex_input_attributes: u32,
+    pub max_vertex_input_bindings: u32,
+    pub max_vertex_input_attribute_offset: u32,
+    pub max_vertex_input_binding_stride: u32,
+    pub max_vertex_output_components: u32,
+    pub max_tessellation_generation_level: u32,
+    pub max_tessellation_patch_size: u32,
+    pub max_tessellation_control_per_vertex_input_components: u32,
+    pub max_tessellation_control_per_vertex_output_components: u32,
+    pub max_tessellation_control_per_patch_output_components: u32,
+    pub max_tessellation_control_total_output_components: u32,
+    pub max_tessellation_evaluation_input_components: u32,
+    pub max_tessellation_evaluation_output_components: u32,
+    pub max_geometry_shader_invocations: u32,
+    pub max_geometry_input_components: u32,
+    pub max_geometry_output_components: u32,
+    pub max_geometry_output_vertices: u32,
+    pub max_geometry_total_output_components: u32,
+    pub max_fragment_input_components: u32,
+    pub max_fragment_output_attachments: u32,
+    pub max_fragment_dual_src_attachments: u32,
+    pub max_fragment_combined_output_resources: u32,
+    pub max_compute_shared_memory_size: u32,
+    pub max_compute_work_group_count: [u32; 3],
+    pub max_compute_work_group_invocations: u32,
+    pub max_compute_work_group_size: [u32; 3],
+    pub sub_pixel_precision_bits: u32,
+    pub sub_texel_precision_bits: u32,
+    pub mipmap_precision_bits: u32,
+    pub max_draw_indexed_index_value: u32,
+    pub max_draw_indirect_count: u32,
+    pub max_sampler_lod_bias: f32,
+    pub max_sampler_anisotropy: f32,
+    pub max_viewports: u32,
+    pub max_viewport_dimensions: [u32; 2],
+    pub viewport_bounds_range: [f32; 2],
+    pub viewport_sub_pixel_bits: u32,
+    pub min_memory_map_alignment: usize,
+    pub min_texel_buffer_offset_alignment: u64,
+    pub min_uniform_buffer_offset_alignment: u64,
+    pub min_storage_buffer_offset_alignment: u64,
+    pub min_texel_offset: i32,
+    pub max_texel_offset: u32,
+    pub min_texel_gather_offset: i32,
+    pub max_texel_gather_offset: u32,
+    pub min_interpolation_offset: f32,
+    pub max_interpolation_offset: f32,
+    pub sub_pixel_interpolation_offset_bits: u32,
+    pub max_framebuffer_width: u32,
+    pub max_framebuffer_height: u32,
+    pub max_framebuffer_layers: u32,
+    pub framebuffer_color_sample_counts: u32,
+    pub framebuffer_depth_sample_counts: u32,
+    pub framebuffer_stencil_sample_counts: u32,
+    pub framebuffer_no_attachments_sample_counts: u32,
+    pub max_color_attachments: u32,
+    pub sampled_image_color_sample_counts: u32,
+    pub sampled_image_integer_sample_counts: u32,
+    pub sampled_image_depth_sample_counts: u32,
+    pub sampled_image_stencil_sample_counts: u32,
+    pub storage_image_sample_counts: u32,
+    pub max_sample_mask_words: u32,
+    pub timestamp_compute_and_graphics: u32,
+    pub timestamp_period: f32,
+    pub max_clip_distances: u32,
+    pub max_cull_distances: u32,
+    pub max_combined_clip_and_cull_distances: u32,
+    pub discrete_queue_priorities: u32,
+    pub point_size_range: [f32; 2],
+    pub line_width_range: [f32; 2],
+    pub point_size_granularity: f32,
+    pub line_width_granularity: f32,
+    pub strict_lines: u32,
+    pub standard_sample_locations: u32,
+    pub optimal_buffer_copy_offset_alignment: u64,
+    pub optimal_buffer_copy_row_pitch_alignment: u64,
+    pub non_coherent_atom_size: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PhysicalDeviceSparseProperties {
+    pub residency_standard_2d_block_shape: u32,
+    pub residency_standard_2d_multisample_block_shape: u32,
+    pub residency_standard_3d_block_shape: u32,
+    pub residency_aligned_mip_size: u32,
+    pub residency_non_resident_strict: u32,
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct PhysicalDeviceType(pub(crate) i32);
+
+impl PhysicalDeviceType {
+    #[allow(unused)]
+    pub(crate) const OTHER: Self = Self(0);
+    pub(crate) const INTEGRATED_GPU: Self = Self(1);
+    pub(crate) const DISCRETE_GPU: Self = Self(2);
+    pub(crate) const VIRTUAL_GPU: Self = Self(3);
+    pub(crate) const CPU: Self = Self(4);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_physical_device_properties_layout() {
+        assert_eq!(size_of::<PhysicalDeviceLimits>(), 504);
+        assert_eq!(size_of::<PhysicalDeviceSparseProperties>(), 20);
+        assert_eq!(size_of::<PhysicalDeviceProperties>(), 824);
+    }
+
+    #[test]
+    fn test_device_name() {
+        let mut properties = PhysicalDeviceProperties::zeroized();
+
+        for (i, &b) in b"llvmpipe (LLVM 17.0.6, 256 bits)\0".iter().enumerate() {
+            properties.device_name[i] = b as c_char;
+        }
+
+        assert_eq!(
+            properties.device_name(),
+            c"llvmpipe (LLVM 17.0.6, 256 bits)"
+        );
+
+        properties.device_name = [b'x' as c_char; MAX_PHYSICAL_DEVICE_NAME_SIZE];
+        assert_eq!(
+            properties.device_name(),
+            c"",
+            "Unterminated name is rejected"
+        );
+    }
+}
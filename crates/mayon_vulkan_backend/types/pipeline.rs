@@ -0,0 +1,606 @@
+use core::{
+    ffi::{CStr, c_char, c_void},
+    marker::PhantomData,
+    ptr::null,
+};
+
+use super::{
+    Format, Pipeline, PipelineLayout, RenderPass, ShaderModule, ShaderStageFlags, StructureType,
+};
+
+#[repr(C)]
+pub(crate) struct PipelineShaderStageCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: PipelineShaderStageCreateFlags,
+    pub stage: ShaderStageFlags,
+    pub module: ShaderModule,
+    pub name: *const c_char,
+    pub specialization_info: *const c_void,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineShaderStageCreateInfo<'a> {
+    pub fn new(stage: ShaderStageFlags, module: ShaderModule, name: &'a CStr) -> Self {
+        Self {
+            struct_type: StructureType::PipelineShaderStageCreateInfo,
+            next: null(),
+            flags: PipelineShaderStageCreateFlags::EMPTY,
+            stage,
+            module,
+            name: name.as_ptr(),
+            specialization_info: null(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PipelineShaderStageCreateFlags(pub(crate) u32);
+
+impl PipelineShaderStageCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct VertexInputBindingDescription {
+    pub binding: u32,
+    pub stride: u32,
+    pub input_rate: VertexInputRate,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct VertexInputAttributeDescription {
+    pub location: u32,
+    pub binding: u32,
+    pub format: Format,
+    pub offset: u32,
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct VertexInputRate(pub(crate) i32);
+
+impl VertexInputRate {
+    pub(crate) const VERTEX: Self = Self(0);
+    pub(crate) const INSTANCE: Self = Self(1);
+}
+
+#[repr(C)]
+pub(crate) struct PipelineVertexInputStateCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub vertex_binding_description_count: u32,
+    pub vertex_binding_descriptions: *const VertexInputBindingDescription,
+    pub vertex_attribute_description_count: u32,
+    pub vertex_attribute_descriptions: *const VertexInputAttributeDescription,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineVertexInputStateCreateInfo<'a> {
+    pub fn new(
+        bindings: &'a [VertexInputBindingDescription],
+        attributes: &'a [VertexInputAttributeDescription],
+    ) -> Self {
+        Self {
+            struct_type: StructureType::PipelineVertexInputStateCreateInfo,
+            next: null(),
+            flags: 0,
+            vertex_binding_description_count: bindings.len() as u32,
+            vertex_binding_descriptions: bindings.as_ptr(),
+            vertex_attribute_description_count: attributes.len() as u32,
+            vertex_attribute_descriptions: attributes.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct PipelineInputAssemblyStateCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub topology: PrimitiveTopology,
+    pub primitive_restart_enable: u32,
+}
+
+impl PipelineInputAssemblyStateCreateInfo {
+    pub fn new(topology: PrimitiveTopology) -> Self {
+        Self {
+            struct_type: StructureType::PipelineInputAssemblyStateCreateInfo,
+            next: null(),
+            flags: 0,
+            topology,
+            primitive_restart_enable: 0,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PrimitiveTopology(pub(crate) i32);
+
+impl PrimitiveTopology {
+    pub(crate) const POINT_LIST: Self = Self(0);
+    pub(crate) const LINE_LIST: Self = Self(1);
+    pub(crate) const LINE_STRIP: Self = Self(2);
+    pub(crate) const TRIANGLE_LIST: Self = Self(3);
+    pub(crate) const TRIANGLE_STRIP: Self = Self(4);
+}
+
+/// Viewport state of pipelines with a dynamic viewport and scissor, leaving the arrays
+/// null.
+#[repr(C)]
+pub(crate) struct PipelineViewportStateCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub viewport_count: u32,
+    pub viewports: *const c_void,
+    pub scissor_count: u32,
+    pub scissors: *const c_void,
+}
+
+impl PipelineViewportStateCreateInfo {
+    /// Viewport state for one viewport and scissor, both set while recording.
+    pub fn dynamic() -> Self {
+        Self {
+            struct_type: StructureType::PipelineViewportStateCreateInfo,
+            next: null(),
+            flags: 0,
+            viewport_count: 1,
+            viewports: null(),
+            scissor_count: 1,
+            scissors: null(),
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct PipelineRasterizationStateCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub depth_clamp_enable: u32,
+    pub rasterizer_discard_enable: u32,
+    pub polygon_mode: PolygonMode,
+    pub cull_mode: CullModeFlags,
+    pub front_face: FrontFace,
+    pub depth_bias_enable: u32,
+    pub depth_bias_constant_factor: f32,
+    pub depth_bias_clamp: f32,
+    pub depth_bias_slope_factor: f32,
+    pub line_width: f32,
+}
+
+impl PipelineRasterizationStateCreateInfo {
+    /// Rasterization with a line width of 1, and a depth bias set while recording when
+    /// enabled.
+    pub fn new(
+        polygon_mode: PolygonMode,
+        cull_mode: CullModeFlags,
+        front_face: FrontFace,
+        depth_clamp_enable: bool,
+        depth_bias_enable: bool,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::PipelineRasterizationStateCreateInfo,
+            next: null(),
+            flags: 0,
+            depth_clamp_enable: depth_clamp_enable as u32,
+            rasterizer_discard_enable: 0,
+            polygon_mode,
+            cull_mode,
+            front_face,
+            depth_bias_enable: depth_bias_enable as u32,
+            depth_bias_constant_factor: 0.0,
+            depth_bias_clamp: 0.0,
+            depth_bias_slope_factor: 0.0,
+            line_width: 1.0,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PolygonMode(pub(crate) i32);
+
+impl PolygonMode {
+    pub(crate) const FILL: Self = Self(0);
+    pub(crate) const LINE: Self = Self(1);
+    pub(crate) const POINT: Self = Self(2);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct CullModeFlags(pub(crate) u32);
+
+impl CullModeFlags {
+    pub(crate) const NONE: Self = Self(0);
+    pub(crate) const FRONT: Self = Self(0x1);
+    pub(crate) const BACK: Self = Self(0x2);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct FrontFace(pub(crate) i32);
+
+impl FrontFace {
+    pub(crate) const COUNTER_CLOCKWISE: Self = Self(0);
+    pub(crate) const CLOCKWISE: Self = Self(1);
+}
+
+/// Multisample state of single-sampled pipelines.
+#[repr(C)]
+pub(crate) struct PipelineMultisampleStateCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub rasterization_samples: u32,
+    pub sample_shading_enable: u32,
+    pub min_sample_shading: f32,
+    pub sample_mask: *const u32,
+    pub alpha_to_coverage_enable: u32,
+    pub alpha_to_one_enable: u32,
+}
+
+impl PipelineMultisampleStateCreateInfo {
+    pub fn single_sampled() -> Self {
+        Self {
+            struct_type: StructureType::PipelineMultisampleStateCreateInfo,
+            next: null(),
+            flags: 0,
+            // VK_SAMPLE_COUNT_1_BIT.
+            rasterization_samples: 0x1,
+            sample_shading_enable: 0,
+            min_sample_shading: 0.0,
+            sample_mask: null(),
+            alpha_to_coverage_enable: 0,
+            alpha_to_one_enable: 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct StencilOpState {
+    pub fail_op: i32,
+    pub pass_op: i32,
+    pub depth_fail_op: i32,
+    pub compare_op: CompareOp,
+    pub compare_mask: u32,
+    pub write_mask: u32,
+    pub reference: u32,
+}
+
+#[repr(C)]
+pub(crate) struct PipelineDepthStencilStateCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub depth_test_enable: u32,
+    pub depth_write_enable: u32,
+    pub depth_compare_op: CompareOp,
+    pub depth_bounds_test_enable: u32,
+    pub stencil_test_enable: u32,
+    pub front: StencilOpState,
+    pub back: StencilOpState,
+    pub min_depth_bounds: f32,
+    pub max_depth_bounds: f32,
+}
+
+impl PipelineDepthStencilStateCreateInfo {
+    /// Depth testing with `depth_compare_op`, without depth bounds or stencil tests.
+    pub fn new(
+        depth_test_enable: bool,
+        depth_write_enable: bool,
+        depth_compare_op: CompareOp,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::PipelineDepthStencilStateCreateInfo,
+            next: null(),
+            flags: 0,
+            depth_test_enable: depth_test_enable as u32,
+            depth_write_enable: depth_write_enable as u32,
+            depth_compare_op,
+            depth_bounds_test_enable: 0,
+            stencil_test_enable: 0,
+            front: StencilOpState::default(),
+            back: StencilOpState::default(),
+            min_depth_bounds: 0.0,
+            max_depth_bounds: 1.0,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct CompareOp(pub(crate) i32);
+
+impl CompareOp {
+    pub(crate) const NEVER: Self = Self(0);
+    pub(crate) const LESS: Self = Self(1);
+    pub(crate) const EQUAL: Self = Self(2);
+    pub(crate) const LESS_OR_EQUAL: Self = Self(3);
+    pub(crate) const GREATER: Self = Self(4);
+    pub(crate) const NOT_EQUAL: Self = Self(5);
+    pub(crate) const GREATER_OR_EQUAL: Self = Self(6);
+    pub(crate) const ALWAYS: Self = Self(7);
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PipelineColorBlendAttachmentState {
+    pub blend_enable: u32,
+    pub src_color_blend_factor: BlendFactor,
+    pub dst_color_blend_factor: BlendFactor,
+    pub color_blend_op: BlendOp,
+    pub src_alpha_blend_factor: BlendFactor,
+    pub dst_alpha_blend_factor: BlendFactor,
+    pub alpha_blend_op: BlendOp,
+    pub color_write_mask: ColorComponentFlags,
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct BlendFactor(pub(crate) i32);
+
+impl BlendFactor {
+    pub(crate) const ZERO: Self = Self(0);
+    pub(crate) const ONE: Self = Self(1);
+    pub(crate) const SRC_COLOR: Self = Self(2);
+    pub(crate) const ONE_MINUS_SRC_COLOR: Self = Self(3);
+    pub(crate) const DST_COLOR: Self = Self(4);
+    pub(crate) const ONE_MINUS_DST_COLOR: Self = Self(5);
+    pub(crate) const SRC_ALPHA: Self = Self(6);
+    pub(crate) const ONE_MINUS_SRC_ALPHA: Self = Self(7);
+    pub(crate) const DST_ALPHA: Self = Self(8);
+    pub(crate) const ONE_MINUS_DST_ALPHA: Self = Self(9);
+    pub(crate) const CONSTANT_COLOR: Self = Self(10);
+    pub(crate) const ONE_MINUS_CONSTANT_COLOR: Self = Self(11);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct BlendOp(pub(crate) i32);
+
+impl BlendOp {
+    pub(crate) const ADD: Self = Self(0);
+    pub(crate) const SUBTRACT: Self = Self(1);
+    pub(crate) const REVERSE_SUBTRACT: Self = Self(2);
+    pub(crate) const MIN: Self = Self(3);
+    pub(crate) const MAX: Self = Self(4);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ColorComponentFlags(pub(crate) u32);
+
+impl ColorComponentFlags {
+    pub(crate) const RGBA: Self = Self(0xF);
+}
+
+#[repr(C)]
+pub(crate) struct PipelineColorBlendStateCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub logic_op_enable: u32,
+    pub logic_op: i32,
+    pub attachment_count: u32,
+    pub attachments: *const PipelineColorBlendAttachmentState,
+    pub blend_constants: [f32; 4],
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineColorBlendStateCreateInfo<'a> {
+    pub fn new(attachments: &'a [PipelineColorBlendAttachmentState]) -> Self {
+        Self {
+            struct_type: StructureType::PipelineColorBlendStateCreateInfo,
+            next: null(),
+            flags: 0,
+            logic_op_enable: 0,
+            logic_op: 0,
+            attachment_count: attachments.len() as u32,
+            attachments: attachments.as_ptr(),
+            blend_constants: [0.0; 4],
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct PipelineDynamicStateCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub dynamic_state_count: u32,
+    pub dynamic_states: *const DynamicState,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineDynamicStateCreateInfo<'a> {
+    pub fn new(dynamic_states: &'a [DynamicState]) -> Self {
+        Self {
+            struct_type: StructureType::PipelineDynamicStateCreateInfo,
+            next: null(),
+            flags: 0,
+            dynamic_state_count: dynamic_states.len() as u32,
+            dynamic_states: dynamic_states.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct DynamicState(pub(crate) i32);
+
+impl DynamicState {
+    pub(crate) const VIEWPORT: Self = Self(0);
+    pub(crate) const SCISSOR: Self = Self(1);
+    pub(crate) const LINE_WIDTH: Self = Self(2);
+    pub(crate) const DEPTH_BIAS: Self = Self(3);
+    pub(crate) const BLEND_CONSTANTS: Self = Self(4);
+}
+
+/// Attachment formats of a pipeline used with dynamic rendering.
+#[repr(C)]
+pub(crate) struct PipelineRenderingCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub view_mask: u32,
+    pub color_attachment_count: u32,
+    pub color_attachment_formats: *const Format,
+    pub depth_attachment_format: Format,
+    pub stencil_attachment_format: Format,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineRenderingCreateInfo<'a> {
+    pub fn new(
+        color_attachment_formats: &'a [Format],
+        depth_attachment_format: Format,
+        stencil_attachment_format: Format,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::PipelineRenderingCreateInfo,
+            next: null(),
+            view_mask: 0,
+            color_attachment_count: color_attachment_formats.len() as u32,
+            color_attachment_formats: color_attachment_formats.as_ptr(),
+            depth_attachment_format,
+            stencil_attachment_format,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Creation parameters of a graphics pipeline, borrowing the state structures it points to.
+#[repr(C)]
+pub(crate) struct GraphicsPipelineCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: PipelineCreateFlags,
+    pub stage_count: u32,
+    pub stages: *const PipelineShaderStageCreateInfo<'a>,
+    pub vertex_input_state: *const PipelineVertexInputStateCreateInfo<'a>,
+    pub input_assembly_state: *const PipelineInputAssemblyStateCreateInfo,
+    pub tessellation_state: *const c_void,
+    pub viewport_state: *const PipelineViewportStateCreateInfo,
+    pub rasterization_state: *const PipelineRasterizationStateCreateInfo,
+    pub multisample_state: *const PipelineMultisampleStateCreateInfo,
+    pub depth_stencil_state: *const PipelineDepthStencilStateCreateInfo,
+    pub color_blend_state: *const PipelineColorBlendStateCreateInfo<'a>,
+    pub dynamic_state: *const PipelineDynamicStateCreateInfo<'a>,
+    pub layout: PipelineLayout,
+    pub render_pass: Option<RenderPass>,
+    pub subpass: u32,
+    pub base_pipeline_handle: Option<Pipeline>,
+    pub base_pipeline_index: i32,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+/// The fixed-function state of a graphics pipeline.
+pub(crate) struct GraphicsPipelineState<'a> {
+    pub vertex_input: &'a PipelineVertexInputStateCreateInfo<'a>,
+    pub input_assembly: &'a PipelineInputAssemblyStateCreateInfo,
+    pub viewport: &'a PipelineViewportStateCreateInfo,
+    pub rasterization: &'a PipelineRasterizationStateCreateInfo,
+    pub multisample: &'a PipelineMultisampleStateCreateInfo,
+    pub depth_stencil: &'a PipelineDepthStencilStateCreateInfo,
+    pub color_blend: &'a PipelineColorBlendStateCreateInfo<'a>,
+    pub dynamic: &'a PipelineDynamicStateCreateInfo<'a>,
+}
+
+impl<'a> GraphicsPipelineCreateInfo<'a> {
+    /// A pipeline used with dynamic rendering into the attachments described by `rendering`.
+    pub fn with_rendering(
+        stages: &'a [PipelineShaderStageCreateInfo<'a>],
+        state: GraphicsPipelineState<'a>,
+        layout: PipelineLayout,
+        rendering: &'a PipelineRenderingCreateInfo<'a>,
+    ) -> Self {
+        let mut info = Self::new(stages, state, layout, None);
+
+        info.next = (rendering as *const PipelineRenderingCreateInfo).cast();
+        info
+    }
+
+    /// A pipeline used in the first subpass of render passes compatible with `render_pass`.
+    pub fn with_render_pass(
+        stages: &'a [PipelineShaderStageCreateInfo<'a>],
+        state: GraphicsPipelineState<'a>,
+        layout: PipelineLayout,
+        render_pass: RenderPass,
+    ) -> Self {
+        Self::new(stages, state, layout, Some(render_pass))
+    }
+
+    fn new(
+        stages: &'a [PipelineShaderStageCreateInfo<'a>],
+        state: GraphicsPipelineState<'a>,
+        layout: PipelineLayout,
+        render_pass: Option<RenderPass>,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::GraphicsPipelineCreateInfo,
+            next: null(),
+            flags: PipelineCreateFlags::EMPTY,
+            stage_count: stages.len() as u32,
+            stages: stages.as_ptr(),
+            vertex_input_state: state.vertex_input,
+            input_assembly_state: state.input_assembly,
+            tessellation_state: null(),
+            viewport_state: state.viewport,
+            rasterization_state: state.rasterization,
+            multisample_state: state.multisample,
+            depth_stencil_state: state.depth_stencil,
+            color_blend_state: state.color_blend,
+            dynamic_state: state.dynamic,
+            layout,
+            render_pass,
+            subpass: 0,
+            base_pipeline_handle: None,
+            base_pipeline_index: -1,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct ComputePipelineCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: PipelineCreateFlags,
+    pub stage: PipelineShaderStageCreateInfo<'a>,
+    pub layout: PipelineLayout,
+    pub base_pipeline_handle: Option<Pipeline>,
+    pub base_pipeline_index: i32,
+}
+
+impl<'a> ComputePipelineCreateInfo<'a> {
+    pub fn new(stage: PipelineShaderStageCreateInfo<'a>, layout: PipelineLayout) -> Self {
+        Self {
+            struct_type: StructureType::ComputePipelineCreateInfo,
+            next: null(),
+            flags: PipelineCreateFlags::EMPTY,
+            stage,
+            layout,
+            base_pipeline_handle: None,
+            base_pipeline_index: -1,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PipelineCreateFlags(pub(crate) u32);
+
+impl PipelineCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
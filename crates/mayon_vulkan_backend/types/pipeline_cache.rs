@@ -0,0 +1,88 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::StructureType;
+
+#[repr(C)]
+pub(crate) struct PipelineCacheCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: PipelineCacheCreateFlags,
+    pub initial_data_size: usize,
+    pub initial_data: *const c_void,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineCacheCreateInfo<'a> {
+    /// A cache starting with `initial_data`, as returned by `vkGetPipelineCacheData`.
+    pub fn new(initial_data: &'a [u8]) -> Self {
+        Self {
+            struct_type: StructureType::PipelineCacheCreateInfo,
+            next: null(),
+            flags: PipelineCacheCreateFlags::EMPTY,
+            initial_data_size: initial_data.len(),
+            initial_data: initial_data.as_ptr().cast(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PipelineCacheCreateFlags(pub(crate) u32);
+
+impl PipelineCacheCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
+
+/// The `VkPipelineCacheHeaderVersionOne` starting the data of every pipeline cache,
+/// identifying the device and driver it was created by.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct PipelineCacheHeader {
+    pub header_size: u32,
+    pub header_version: u32,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub pipeline_cache_uuid: [u8; 16],
+}
+
+impl PipelineCacheHeader {
+    pub(crate) const SIZE: usize = 32;
+    /// `VK_PIPELINE_CACHE_HEADER_VERSION_ONE`.
+    pub(crate) const VERSION_ONE: u32 = 1;
+
+    /// Reads the header at the start of `data`, if it is long enough to hold one.
+    pub(crate) fn read(data: &[u8]) -> Option<Self> {
+        let header = data.get(..Self::SIZE)?;
+        let word =
+            |index: usize| u32::from_ne_bytes(header[index * 4..index * 4 + 4].try_into().unwrap());
+
+        Some(Self {
+            header_size: word(0),
+            header_version: word(1),
+            vendor_id: word(2),
+            device_id: word(3),
+            pipeline_cache_uuid: header[16..].try_into().unwrap(),
+        })
+    }
+
+    #[cfg(any(test, feature = "mock_driver"))]
+    pub(crate) fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+
+        for (index, word) in [
+            self.header_size,
+            self.header_version,
+            self.vendor_id,
+            self.device_id,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            bytes[index * 4..index * 4 + 4].copy_from_slice(&word.to_ne_bytes());
+        }
+
+        bytes[16..].copy_from_slice(&self.pipeline_cache_uuid);
+
+        bytes
+    }
+}
@@ -0,0 +1,49 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::{DescriptorSetLayout, ShaderStageFlags, StructureType};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PushConstantRange {
+    pub stage_flags: ShaderStageFlags,
+    pub offset: u32,
+    pub size: u32,
+}
+
+#[repr(C)]
+pub(crate) struct PipelineLayoutCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: PipelineLayoutCreateFlags,
+    pub set_layout_count: u32,
+    pub set_layouts: *const DescriptorSetLayout,
+    pub push_constant_range_count: u32,
+    pub push_constant_ranges: *const PushConstantRange,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PipelineLayoutCreateInfo<'a> {
+    pub fn new(
+        set_layouts: &'a [DescriptorSetLayout],
+        push_constant_ranges: &'a [PushConstantRange],
+    ) -> Self {
+        Self {
+            struct_type: StructureType::PipelineLayoutCreateInfo,
+            next: null(),
+            flags: PipelineLayoutCreateFlags::EMPTY,
+            set_layout_count: set_layouts.len() as u32,
+            set_layouts: set_layouts.as_ptr(),
+            push_constant_range_count: push_constant_ranges.len() as u32,
+            push_constant_ranges: push_constant_ranges.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PipelineLayoutCreateFlags(pub(crate) u32);
+
+impl PipelineLayoutCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
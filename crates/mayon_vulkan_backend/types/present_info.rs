@@ -0,0 +1,37 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::{Semaphore, StructureType, Swapchain, VkResult};
+
+#[repr(C)]
+pub(crate) struct PresentInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub wait_semaphore_count: u32,
+    pub wait_semaphores: *const Semaphore,
+    pub swapchain_count: u32,
+    pub swapchains: *const Swapchain,
+    pub image_indices: *const u32,
+    pub results: *mut VkResult,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> PresentInfo<'a> {
+    /// Presents `image_index` of `swapchain` once every semaphore in `wait_semaphores` is signaled.
+    pub fn new(
+        wait_semaphores: &'a [Semaphore],
+        swapchain: &'a Swapchain,
+        image_index: &'a u32,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::PresentInfoKhr,
+            next: null(),
+            wait_semaphore_count: wait_semaphores.len() as u32,
+            wait_semaphores: wait_semaphores.as_ptr(),
+            swapchain_count: 1,
+            swapchains: swapchain,
+            image_indices: image_index,
+            results: core::ptr::null_mut(),
+            _marker: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,261 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::{
+    Extent2D, Format, Framebuffer, ImageLayout, ImageView, Rect2D, RenderPass, SampleCountFlags,
+    StructureType,
+};
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct AttachmentLoadOp(pub(crate) i32);
+
+impl AttachmentLoadOp {
+    pub(crate) const LOAD: Self = Self(0);
+    pub(crate) const CLEAR: Self = Self(1);
+    pub(crate) const DONT_CARE: Self = Self(2);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct AttachmentStoreOp(pub(crate) i32);
+
+impl AttachmentStoreOp {
+    pub(crate) const STORE: Self = Self(0);
+    pub(crate) const DONT_CARE: Self = Self(1);
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct AttachmentDescription {
+    pub flags: u32,
+    pub format: Format,
+    pub samples: SampleCountFlags,
+    pub load_op: AttachmentLoadOp,
+    pub store_op: AttachmentStoreOp,
+    pub stencil_load_op: AttachmentLoadOp,
+    pub stencil_store_op: AttachmentStoreOp,
+    pub initial_layout: ImageLayout,
+    pub final_layout: ImageLayout,
+}
+
+impl AttachmentDescription {
+    /// A single-sampled attachment that stays in `layout` for the whole render pass.
+    pub fn new(
+        format: Format,
+        layout: ImageLayout,
+        (load_op, store_op): (AttachmentLoadOp, AttachmentStoreOp),
+        (stencil_load_op, stencil_store_op): (AttachmentLoadOp, AttachmentStoreOp),
+    ) -> Self {
+        Self {
+            flags: 0,
+            format,
+            samples: SampleCountFlags::TYPE_1,
+            load_op,
+            store_op,
+            stencil_load_op,
+            stencil_store_op,
+            initial_layout: layout,
+            final_layout: layout,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct AttachmentReference {
+    pub attachment: u32,
+    pub layout: ImageLayout,
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct PipelineBindPoint(pub(crate) i32);
+
+impl PipelineBindPoint {
+    pub(crate) const GRAPHICS: Self = Self(0);
+    pub(crate) const COMPUTE: Self = Self(1);
+}
+
+#[repr(C)]
+pub(crate) struct SubpassDescription<'a> {
+    pub flags: u32,
+    pub pipeline_bind_point: PipelineBindPoint,
+    pub input_attachment_count: u32,
+    pub input_attachments: *const AttachmentReference,
+    pub color_attachment_count: u32,
+    pub color_attachments: *const AttachmentReference,
+    pub resolve_attachments: *const AttachmentReference,
+    pub depth_stencil_attachment: *const AttachmentReference,
+    pub preserve_attachment_count: u32,
+    pub preserve_attachments: *const u32,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> SubpassDescription<'a> {
+    /// A graphics subpass writing `color_attachments`, and testing against
+    /// `depth_stencil_attachment` if there is one.
+    pub fn graphics(
+        color_attachments: &'a [AttachmentReference],
+        depth_stencil_attachment: Option<&'a AttachmentReference>,
+    ) -> Self {
+        Self {
+            flags: 0,
+            pipeline_bind_point: PipelineBindPoint::GRAPHICS,
+            input_attachment_count: 0,
+            input_attachments: null(),
+            color_attachment_count: color_attachments.len() as u32,
+            color_attachments: color_attachments.as_ptr(),
+            resolve_attachments: null(),
+            depth_stencil_attachment: depth_stencil_attachment.map_or(null(), |depth| depth),
+            preserve_attachment_count: 0,
+            preserve_attachments: null(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct RenderPassCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub attachment_count: u32,
+    pub attachments: *const AttachmentDescription,
+    pub subpass_count: u32,
+    pub subpasses: *const SubpassDescription<'a>,
+    pub dependency_count: u32,
+    pub dependencies: *const c_void,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> RenderPassCreateInfo<'a> {
+    /// A render pass without explicit subpass dependencies.
+    pub fn new(
+        attachments: &'a [AttachmentDescription],
+        subpasses: &'a [SubpassDescription<'a>],
+    ) -> Self {
+        Self {
+            struct_type: StructureType::RenderPassCreateInfo,
+            next: null(),
+            flags: 0,
+            attachment_count: attachments.len() as u32,
+            attachments: attachments.as_ptr(),
+            subpass_count: subpasses.len() as u32,
+            subpasses: subpasses.as_ptr(),
+            dependency_count: 0,
+            dependencies: null(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct FramebufferCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub render_pass: RenderPass,
+    pub attachment_count: u32,
+    pub attachments: *const ImageView,
+    pub width: u32,
+    pub height: u32,
+    pub layers: u32,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> FramebufferCreateInfo<'a> {
+    /// A single-layer framebuffer of `extent` binding `attachments` to the attachments of
+    /// `render_pass`.
+    pub fn new(render_pass: RenderPass, attachments: &'a [ImageView], extent: Extent2D) -> Self {
+        Self {
+            struct_type: StructureType::FramebufferCreateInfo,
+            next: null(),
+            flags: 0,
+            render_pass,
+            attachment_count: attachments.len() as u32,
+            attachments: attachments.as_ptr(),
+            width: extent.width,
+            height: extent.height,
+            layers: 1,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) struct ClearDepthStencilValue {
+    pub depth: f32,
+    pub stencil: u32,
+}
+
+/// `VkClearValue`, read as a color or as depth and stencil depending on the attachment.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub(crate) union ClearValue {
+    pub color: [f32; 4],
+    pub depth_stencil: ClearDepthStencilValue,
+}
+
+impl ClearValue {
+    #[inline]
+    pub(crate) const fn color(color: [f32; 4]) -> Self {
+        Self { color }
+    }
+
+    #[inline]
+    pub(crate) const fn depth_stencil(depth: f32, stencil: u32) -> Self {
+        Self {
+            depth_stencil: ClearDepthStencilValue { depth, stencil },
+        }
+    }
+}
+
+impl Default for ClearValue {
+    #[inline]
+    fn default() -> Self {
+        Self::color([0.0; 4])
+    }
+}
+
+#[repr(C)]
+pub(crate) struct RenderPassBeginInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub render_pass: RenderPass,
+    pub framebuffer: Framebuffer,
+    pub render_area: Rect2D,
+    pub clear_value_count: u32,
+    pub clear_values: *const ClearValue,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> RenderPassBeginInfo<'a> {
+    /// Begins `render_pass` on `framebuffer`, clearing attachments to the value at their
+    /// index in `clear_values`.
+    pub fn new(
+        render_pass: RenderPass,
+        framebuffer: Framebuffer,
+        render_area: Rect2D,
+        clear_values: &'a [ClearValue],
+    ) -> Self {
+        Self {
+            struct_type: StructureType::RenderPassBeginInfo,
+            next: null(),
+            render_pass,
+            framebuffer,
+            render_area,
+            clear_value_count: clear_values.len() as u32,
+            clear_values: clear_values.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct SubpassContents(pub(crate) i32);
+
+impl SubpassContents {
+    pub(crate) const INLINE: Self = Self(0);
+}
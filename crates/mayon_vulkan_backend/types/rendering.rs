@@ -0,0 +1,106 @@
+use core::{
+    ffi::c_void,
+    marker::PhantomData,
+    ptr::{null, null_mut},
+};
+
+use super::{
+    AttachmentLoadOp, AttachmentStoreOp, ClearValue, ImageLayout, ImageView, Rect2D, StructureType,
+};
+
+/// Describes the attachments of a dynamic rendering instance, as begun by
+/// `vkCmdBeginRendering`.
+#[repr(C)]
+pub(crate) struct RenderingInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: u32,
+    pub render_area: Rect2D,
+    pub layer_count: u32,
+    pub view_mask: u32,
+    pub color_attachment_count: u32,
+    pub color_attachments: *const RenderingAttachmentInfo,
+    pub depth_attachment: *const RenderingAttachmentInfo,
+    pub stencil_attachment: *const RenderingAttachmentInfo,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> RenderingInfo<'a> {
+    /// Renders a single layer of `render_area` into the given attachments.
+    pub fn new(
+        render_area: Rect2D,
+        color_attachments: &'a [RenderingAttachmentInfo],
+        depth_attachment: Option<&'a RenderingAttachmentInfo>,
+        stencil_attachment: Option<&'a RenderingAttachmentInfo>,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::RenderingInfo,
+            next: null(),
+            flags: 0,
+            render_area,
+            layer_count: 1,
+            view_mask: 0,
+            color_attachment_count: color_attachments.len() as u32,
+            color_attachments: color_attachments.as_ptr(),
+            depth_attachment: depth_attachment.map_or(null(), |depth| depth),
+            stencil_attachment: stencil_attachment.map_or(null(), |stencil| stencil),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub(crate) struct RenderingAttachmentInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub image_view: ImageView,
+    pub image_layout: ImageLayout,
+    pub resolve_mode: u32,
+    pub resolve_image_view: Option<ImageView>,
+    pub resolve_image_layout: ImageLayout,
+    pub load_op: AttachmentLoadOp,
+    pub store_op: AttachmentStoreOp,
+    pub clear_value: ClearValue,
+}
+
+impl RenderingAttachmentInfo {
+    /// Renders into `image_view`, which is in `image_layout`, without resolving it.
+    pub fn new(
+        image_view: ImageView,
+        image_layout: ImageLayout,
+        (load_op, store_op): (AttachmentLoadOp, AttachmentStoreOp),
+        clear_value: ClearValue,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::RenderingAttachmentInfo,
+            next: null(),
+            image_view,
+            image_layout,
+            resolve_mode: 0,
+            resolve_image_view: None,
+            resolve_image_layout: ImageLayout::UNDEFINED,
+            load_op,
+            store_op,
+            clear_value,
+        }
+    }
+}
+
+/// Chained into a `DeviceCreateInfo` to enable dynamic rendering.
+#[repr(C)]
+pub(crate) struct PhysicalDeviceDynamicRenderingFeatures {
+    pub struct_type: StructureType,
+    pub next: *mut c_void,
+    pub dynamic_rendering: u32,
+}
+
+impl PhysicalDeviceDynamicRenderingFeatures {
+    pub fn enabled() -> Self {
+        Self {
+            struct_type: StructureType::PhysicalDeviceDynamicRenderingFeatures,
+            next: null_mut(),
+            dynamic_rendering: 1,
+        }
+    }
+}
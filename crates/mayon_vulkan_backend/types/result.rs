@@ -101,6 +101,14 @@ impl VkResult {
     }
 }
 
+impl From<ReturnCode> for VkResult {
+    #[inline]
+    fn from(code: ReturnCode) -> Self {
+        // Every `ReturnCode` discriminant is also a `VkResult` discriminant.
+        unsafe { transmute::<ReturnCode, Self>(code) }
+    }
+}
+
 #[repr(i32)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, thiserror::Error)]
 pub enum ReturnCode {
@@ -0,0 +1,95 @@
+use core::{ffi::c_void, ptr::null};
+
+use super::StructureType;
+
+/// `VK_LOD_CLAMP_NONE`, for samplers that may use every mip level.
+const LOD_CLAMP_NONE: f32 = 1000.0;
+
+#[repr(C)]
+pub(crate) struct SamplerCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: SamplerCreateFlags,
+    pub mag_filter: Filter,
+    pub min_filter: Filter,
+    pub mipmap_mode: SamplerMipmapMode,
+    pub address_mode_u: SamplerAddressMode,
+    pub address_mode_v: SamplerAddressMode,
+    pub address_mode_w: SamplerAddressMode,
+    pub mip_lod_bias: f32,
+    pub anisotropy_enable: u32,
+    pub max_anisotropy: f32,
+    pub compare_enable: u32,
+    pub compare_op: i32,
+    pub min_lod: f32,
+    pub max_lod: f32,
+    pub border_color: i32,
+    pub unnormalized_coordinates: u32,
+}
+
+impl SamplerCreateInfo {
+    /// Creates a sampler without anisotropy or depth comparison, using every mip level.
+    pub fn new(
+        mag_filter: Filter,
+        min_filter: Filter,
+        mipmap_mode: SamplerMipmapMode,
+        address_mode: SamplerAddressMode,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::SamplerCreateInfo,
+            next: null(),
+            flags: SamplerCreateFlags::EMPTY,
+            mag_filter,
+            min_filter,
+            mipmap_mode,
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+            mip_lod_bias: 0.0,
+            anisotropy_enable: 0,
+            max_anisotropy: 1.0,
+            compare_enable: 0,
+            compare_op: 0,
+            min_lod: 0.0,
+            max_lod: LOD_CLAMP_NONE,
+            border_color: 0,
+            unnormalized_coordinates: 0,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct SamplerCreateFlags(pub(crate) u32);
+
+impl SamplerCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct Filter(pub(crate) i32);
+
+impl Filter {
+    pub(crate) const NEAREST: Self = Self(0);
+    pub(crate) const LINEAR: Self = Self(1);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct SamplerMipmapMode(pub(crate) i32);
+
+impl SamplerMipmapMode {
+    pub(crate) const NEAREST: Self = Self(0);
+    pub(crate) const LINEAR: Self = Self(1);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct SamplerAddressMode(pub(crate) i32);
+
+impl SamplerAddressMode {
+    pub(crate) const REPEAT: Self = Self(0);
+    pub(crate) const MIRRORED_REPEAT: Self = Self(1);
+    pub(crate) const CLAMP_TO_EDGE: Self = Self(2);
+}
@@ -0,0 +1,39 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::{SemaphoreTypeCreateInfo, StructureType};
+
+#[repr(C)]
+pub(crate) struct SemaphoreCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: SemaphoreCreateFlags,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> SemaphoreCreateInfo<'a> {
+    /// Creates a binary semaphore.
+    pub fn new() -> Self {
+        Self {
+            struct_type: StructureType::SemaphoreCreateInfo,
+            next: null(),
+            flags: SemaphoreCreateFlags::EMPTY,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a semaphore of the type described by `type_info`.
+    pub fn with_type(type_info: &'a SemaphoreTypeCreateInfo) -> Self {
+        Self {
+            next: (type_info as *const SemaphoreTypeCreateInfo).cast(),
+            ..Self::new()
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SemaphoreCreateFlags(pub(crate) u32);
+
+impl SemaphoreCreateFlags {
+    pub const EMPTY: Self = Self(0);
+}
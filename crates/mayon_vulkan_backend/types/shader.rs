@@ -0,0 +1,49 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::StructureType;
+
+#[repr(C)]
+pub(crate) struct ShaderModuleCreateInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: ShaderModuleCreateFlags,
+    /// Size of the code in bytes.
+    pub code_size: usize,
+    pub code: *const u32,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> ShaderModuleCreateInfo<'a> {
+    pub fn new(code: &'a [u32]) -> Self {
+        Self {
+            struct_type: StructureType::ShaderModuleCreateInfo,
+            next: null(),
+            flags: ShaderModuleCreateFlags::EMPTY,
+            code_size: size_of_val(code),
+            code: code.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ShaderModuleCreateFlags(pub(crate) u32);
+
+impl ShaderModuleCreateFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub(crate) struct ShaderStageFlags(pub(crate) u32);
+
+impl ShaderStageFlags {
+    pub(crate) const VERTEX: Self = Self(0x1);
+    pub(crate) const TESSELLATION_CONTROL: Self = Self(0x2);
+    pub(crate) const TESSELLATION_EVALUATION: Self = Self(0x4);
+    pub(crate) const GEOMETRY: Self = Self(0x8);
+    pub(crate) const FRAGMENT: Self = Self(0x10);
+    pub(crate) const COMPUTE: Self = Self(0x20);
+    pub(crate) const ALL: Self = Self(0x7FFF_FFFF);
+}
@@ -0,0 +1,65 @@
+use core::{ffi::c_void, marker::PhantomData, ptr::null};
+
+use super::{
+    CommandBuffer, PipelineStageFlags, Semaphore, StructureType, TimelineSemaphoreSubmitInfo,
+};
+
+#[repr(C)]
+pub(crate) struct SubmitInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub wait_semaphore_count: u32,
+    pub wait_semaphores: *const Semaphore,
+    pub wait_dst_stage_mask: *const PipelineStageFlags,
+    pub command_buffer_count: u32,
+    pub command_buffers: *const CommandBuffer,
+    pub signal_semaphore_count: u32,
+    pub signal_semaphores: *const Semaphore,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> SubmitInfo<'a> {
+    /// Executes `command_buffers` in order, without waiting on or signaling semaphores.
+    pub fn new(command_buffers: &'a [CommandBuffer]) -> Self {
+        Self {
+            struct_type: StructureType::SubmitInfo,
+            next: null(),
+            wait_semaphore_count: 0,
+            wait_semaphores: null(),
+            wait_dst_stage_mask: null(),
+            command_buffer_count: command_buffers.len() as u32,
+            command_buffers: command_buffers.as_ptr(),
+            signal_semaphore_count: 0,
+            signal_semaphores: null(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Delays the stages in `wait_stages` until the matching semaphore in `wait_semaphores`
+    /// is signaled.
+    pub fn with_wait_semaphores(
+        mut self,
+        wait_semaphores: &'a [Semaphore],
+        wait_stages: &'a [PipelineStageFlags],
+    ) -> Self {
+        debug_assert_eq!(wait_semaphores.len(), wait_stages.len());
+
+        self.wait_semaphore_count = wait_semaphores.len() as u32;
+        self.wait_semaphores = wait_semaphores.as_ptr();
+        self.wait_dst_stage_mask = wait_stages.as_ptr();
+        self
+    }
+
+    /// Signals `signal_semaphores` once the command buffers completed.
+    pub fn with_signal_semaphores(mut self, signal_semaphores: &'a [Semaphore]) -> Self {
+        self.signal_semaphore_count = signal_semaphores.len() as u32;
+        self.signal_semaphores = signal_semaphores.as_ptr();
+        self
+    }
+
+    /// Gives the counter values to wait for and signal on timeline semaphores.
+    pub fn with_timeline_values(mut self, values: &'a TimelineSemaphoreSubmitInfo<'a>) -> Self {
+        self.next = (values as *const TimelineSemaphoreSubmitInfo).cast();
+        self
+    }
+}
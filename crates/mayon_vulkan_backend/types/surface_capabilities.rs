@@ -0,0 +1,59 @@
+use super::{ColorSpace, Extent2D, Format, ImageUsageFlags};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SurfaceCapabilities {
+    pub min_image_count: u32,
+    pub max_image_count: u32,
+    pub current_extent: Extent2D,
+    pub min_image_extent: Extent2D,
+    pub max_image_extent: Extent2D,
+    pub max_image_array_layers: u32,
+    pub supported_transforms: SurfaceTransformFlags,
+    pub current_transform: SurfaceTransformFlags,
+    pub supported_composite_alpha: CompositeAlphaFlags,
+    pub supported_usage_flags: ImageUsageFlags,
+}
+
+impl SurfaceCapabilities {
+    /// Sentinel used in `current_extent` when the surface size is determined by the swapchain.
+    pub(crate) const UNDEFINED_EXTENT: u32 = u32::MAX;
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct SurfaceFormat {
+    pub format: Format,
+    pub color_space: ColorSpace,
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct PresentMode(pub(crate) i32);
+
+impl PresentMode {
+    #[allow(unused)]
+    pub(crate) const IMMEDIATE: Self = Self(0);
+    pub(crate) const MAILBOX: Self = Self(1);
+    pub(crate) const FIFO: Self = Self(2);
+}
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct SurfaceTransformFlags(pub(crate) u32);
+
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub(crate) struct CompositeAlphaFlags(pub(crate) u32);
+
+impl CompositeAlphaFlags {
+    pub(crate) const OPAQUE: Self = Self(0x1);
+    pub(crate) const PRE_MULTIPLIED: Self = Self(0x2);
+    pub(crate) const POST_MULTIPLIED: Self = Self(0x4);
+    pub(crate) const INHERIT: Self = Self(0x8);
+
+    #[inline]
+    pub(crate) const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
@@ -0,0 +1,75 @@
+use core::{ffi::c_void, ptr::null};
+
+use super::{
+    ColorSpace, CompositeAlphaFlags, Extent2D, Format, ImageUsageFlags, PresentMode, SharingMode,
+    StructureType, Surface, SurfaceTransformFlags, Swapchain,
+};
+
+#[repr(C)]
+pub(crate) struct SwapchainCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: SwapchainCreateFlags,
+    pub surface: Surface,
+    pub min_image_count: u32,
+    pub image_format: Format,
+    pub image_color_space: ColorSpace,
+    pub image_extent: Extent2D,
+    pub image_array_layers: u32,
+    pub image_usage: ImageUsageFlags,
+    pub image_sharing_mode: SharingMode,
+    pub queue_family_index_count: u32,
+    pub queue_family_indices: *const u32,
+    pub pre_transform: SurfaceTransformFlags,
+    pub composite_alpha: CompositeAlphaFlags,
+    pub present_mode: PresentMode,
+    pub clipped: u32,
+    pub old_swapchain: Option<Swapchain>,
+}
+
+impl SwapchainCreateInfo {
+    /// Creates a `SwapchainCreateInfo` for a single-layer swapchain owned by one queue family.
+    ///
+    /// `old_swapchain` is handed to the driver so resources can be reused during recreation.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        surface: Surface,
+        min_image_count: u32,
+        surface_format: super::SurfaceFormat,
+        image_extent: Extent2D,
+        image_usage: ImageUsageFlags,
+        pre_transform: SurfaceTransformFlags,
+        composite_alpha: CompositeAlphaFlags,
+        present_mode: PresentMode,
+        old_swapchain: Option<Swapchain>,
+    ) -> Self {
+        Self {
+            struct_type: StructureType::SwapchainCreateInfoKhr,
+            next: null(),
+            flags: SwapchainCreateFlags::EMPTY,
+            surface,
+            min_image_count,
+            image_format: surface_format.format,
+            image_color_space: surface_format.color_space,
+            image_extent,
+            image_array_layers: 1,
+            image_usage,
+            image_sharing_mode: SharingMode::EXCLUSIVE,
+            queue_family_index_count: 0,
+            queue_family_indices: null(),
+            pre_transform,
+            composite_alpha,
+            present_mode,
+            clipped: 1,
+            old_swapchain,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SwapchainCreateFlags(pub(crate) u32);
+
+impl SwapchainCreateFlags {
+    pub const EMPTY: SwapchainCreateFlags = SwapchainCreateFlags(0);
+}
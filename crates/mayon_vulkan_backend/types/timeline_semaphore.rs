@@ -0,0 +1,142 @@
+use core::{
+    ffi::c_void,
+    marker::PhantomData,
+    ptr::{null, null_mut},
+};
+
+use super::{Semaphore, StructureType};
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct SemaphoreType(pub(crate) i32);
+
+impl SemaphoreType {
+    #[allow(unused)]
+    pub(crate) const BINARY: Self = Self(0);
+    pub(crate) const TIMELINE: Self = Self(1);
+}
+
+/// Chained into a `SemaphoreCreateInfo` to pick the semaphore type.
+#[repr(C)]
+pub(crate) struct SemaphoreTypeCreateInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub semaphore_type: SemaphoreType,
+    pub initial_value: u64,
+}
+
+impl SemaphoreTypeCreateInfo {
+    /// Creates a timeline semaphore whose counter starts at `initial_value`.
+    pub fn timeline(initial_value: u64) -> Self {
+        Self {
+            struct_type: StructureType::SemaphoreTypeCreateInfo,
+            next: null(),
+            semaphore_type: SemaphoreType::TIMELINE,
+            initial_value,
+        }
+    }
+}
+
+/// Chained into a `SubmitInfo` to give the counter values of its timeline semaphores.
+///
+/// Values line up with the submit's wait and signal semaphores; entries of binary
+/// semaphores are ignored.
+#[repr(C)]
+pub(crate) struct TimelineSemaphoreSubmitInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub wait_semaphore_value_count: u32,
+    pub wait_semaphore_values: *const u64,
+    pub signal_semaphore_value_count: u32,
+    pub signal_semaphore_values: *const u64,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> TimelineSemaphoreSubmitInfo<'a> {
+    pub fn new(wait_values: &'a [u64], signal_values: &'a [u64]) -> Self {
+        Self {
+            struct_type: StructureType::TimelineSemaphoreSubmitInfo,
+            next: null(),
+            wait_semaphore_value_count: wait_values.len() as u32,
+            wait_semaphore_values: wait_values.as_ptr(),
+            signal_semaphore_value_count: signal_values.len() as u32,
+            signal_semaphore_values: signal_values.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct SemaphoreWaitFlags(pub(crate) u32);
+
+impl SemaphoreWaitFlags {
+    pub(crate) const EMPTY: Self = Self(0);
+    #[allow(unused)]
+    pub(crate) const ANY: Self = Self(0x1);
+}
+
+#[repr(C)]
+pub(crate) struct SemaphoreWaitInfo<'a> {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub flags: SemaphoreWaitFlags,
+    pub semaphore_count: u32,
+    pub semaphores: *const Semaphore,
+    pub values: *const u64,
+    pub _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> SemaphoreWaitInfo<'a> {
+    /// Waits until every semaphore in `semaphores` reaches the matching value in `values`.
+    pub fn new(semaphores: &'a [Semaphore], values: &'a [u64]) -> Self {
+        debug_assert_eq!(semaphores.len(), values.len());
+
+        Self {
+            struct_type: StructureType::SemaphoreWaitInfo,
+            next: null(),
+            flags: SemaphoreWaitFlags::EMPTY,
+            semaphore_count: semaphores.len() as u32,
+            semaphores: semaphores.as_ptr(),
+            values: values.as_ptr(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct SemaphoreSignalInfo {
+    pub struct_type: StructureType,
+    pub next: *const c_void,
+    pub semaphore: Semaphore,
+    pub value: u64,
+}
+
+impl SemaphoreSignalInfo {
+    pub fn new(semaphore: Semaphore, value: u64) -> Self {
+        Self {
+            struct_type: StructureType::SemaphoreSignalInfo,
+            next: null(),
+            semaphore,
+            value,
+        }
+    }
+}
+
+/// Chained into a `DeviceCreateInfo` to enable timeline semaphores.
+#[repr(C)]
+pub(crate) struct PhysicalDeviceTimelineSemaphoreFeatures {
+    pub struct_type: StructureType,
+    pub next: *mut c_void,
+    pub timeline_semaphore: u32,
+}
+
+impl PhysicalDeviceTimelineSemaphoreFeatures {
+    pub fn enabled() -> Self {
+        Self {
+            struct_type: StructureType::PhysicalDeviceTimelineSemaphoreFeatures,
+            next: null_mut(),
+            timeline_semaphore: 1,
+        }
+    }
+}
@@ -6,28 +6,35 @@ use winit::{
 };
 
 use mayon::{
+    AdapterType,
     allocator::{Allocator, System},
-    backends::vulkan::{Context, Instance, VulkanBackendParams},
+    backends::vulkan::{
+        ColorAttachment, Context, Device, Instance, LoadOp, RenderingDesc, StoreOp,
+        VulkanBackendParams,
+    },
     logger::Logger,
 };
 
+/// Color the window is cleared to every frame.
+const CLEAR_COLOR: [f32; 4] = [0.1, 0.1, 0.12, 1.0];
+
 pub struct Handler<L, A = System>
 where
     L: Logger,
     A: Allocator + 'static,
 {
     instance: Instance<'static, L, A>,
+    device: Device<'static, L, A>,
     window_state: Option<WindowState<L, A>>,
 }
 
-#[allow(unused)]
 struct WindowState<L, A = System>
 where
     L: Logger,
     A: Allocator + 'static,
 {
-    window: Window,
     context: Context<'static, L, A>,
+    window: Window,
 }
 
 impl<L, A> Handler<L, A>
@@ -48,8 +55,17 @@ where
         )
         .unwrap();
 
+        let adapter = instance
+            .select_adapter(&[AdapterType::DiscreteGpu, AdapterType::IntegratedGpu])
+            .unwrap()
+            .or_else(|| instance.select_adapter(&[]).unwrap())
+            .expect("No Vulkan adapter found");
+
+        let device = instance.create_device(&adapter).unwrap();
+
         Self {
             instance,
+            device,
             window_state: None,
         }
     }
@@ -73,7 +89,13 @@ where
             )
             .unwrap();
 
-        let context = self.instance.create_context_from_rwh(&window).unwrap();
+        let mut context = self.instance.create_context_from_rwh(&window).unwrap();
+
+        let size = window.inner_size();
+
+        context
+            .configure(&self.device, size.width, size.height)
+            .unwrap();
 
         self.window_state = Some(WindowState { window, context });
     }
@@ -84,16 +106,39 @@ where
         _: winit::window::WindowId,
         event: winit::event::WindowEvent,
     ) {
-        let Some(_s) = self.window_state.as_mut() else {
+        let Some(state) = self.window_state.as_mut() else {
             return;
         };
 
-        #[allow(clippy::single_match)]
         match event {
             winit::event::WindowEvent::CloseRequested => {
                 event_loop.exit();
             }
-            winit::event::WindowEvent::RedrawRequested => {}
+            winit::event::WindowEvent::Resized(size) => {
+                state.context.resize(size.width, size.height);
+                state.window.request_redraw();
+            }
+            winit::event::WindowEvent::RedrawRequested => {
+                if let Some(mut frame) = state.context.begin_frame().unwrap() {
+                    let view = frame.view();
+                    let command_buffer = frame.command_buffer();
+
+                    command_buffer
+                        .begin_rendering(&RenderingDesc {
+                            color: &[ColorAttachment {
+                                view: &view,
+                                load: LoadOp::Clear(CLEAR_COLOR),
+                                store: StoreOp::Store,
+                            }],
+                            depth: None,
+                        })
+                        .unwrap();
+                    command_buffer.end_rendering().unwrap();
+
+                    state.window.pre_present_notify();
+                    frame.end().unwrap();
+                }
+            }
             _ => {}
         }
     }